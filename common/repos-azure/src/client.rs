@@ -0,0 +1,113 @@
+//! Azure DevOps client implementation
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+
+/// Environment variable holding the Azure DevOps personal access token
+pub const TOKEN_ENV: &str = "AZURE_DEVOPS_TOKEN";
+
+/// REST API version sent with every request
+const API_VERSION: &str = "7.1";
+
+/// Azure DevOps API client for one organization
+pub struct AzureClient {
+    pub(crate) client: reqwest::Client,
+    /// Organization URL without a trailing slash, e.g. `https://dev.azure.com/org`
+    pub(crate) organization_url: String,
+    pub(crate) token: Option<String>,
+}
+
+impl AzureClient {
+    /// Create a client for the organization at `organization_url` with an optional token
+    /// If no token is provided, will try to read from AZURE_DEVOPS_TOKEN environment variable
+    pub fn new(organization_url: &str, token: Option<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            organization_url: organization_url.trim_end_matches('/').to_string(),
+            token: token.or_else(|| std::env::var(TOKEN_ENV).ok()),
+        }
+    }
+
+    /// URL of a Git API endpoint of a project, e.g. `repositories/api`
+    pub(crate) fn api_url(&self, project: &str, path: &str) -> String {
+        format!(
+            "{}/{}/_apis/git/{}?api-version={}",
+            self.organization_url, project, path, API_VERSION
+        )
+    }
+
+    /// Fail with a helpful message when no token is configured
+    pub(crate) fn require_token(&self, action: &str) -> Result<()> {
+        if self.token.is_none() {
+            anyhow::bail!(
+                "Azure DevOps token is required to {}. Set {} environment variable.",
+                action,
+                TOKEN_ENV
+            );
+        }
+        Ok(())
+    }
+
+    /// Send an API request and parse the JSON response
+    ///
+    /// `action` describes the request in error messages, e.g. "create pull request".
+    pub(crate) async fn send_json<T: DeserializeOwned>(
+        &self,
+        request: reqwest::RequestBuilder,
+        action: &str,
+    ) -> Result<T> {
+        let mut request = request.header("User-Agent", "repos-cli");
+        if let Some(token) = &self.token {
+            // Personal access tokens go in the password of basic auth
+            request = request.basic_auth("", Some(token));
+        }
+
+        let request = request.build()?;
+        log::debug!("{} {} ({})", request.method(), request.url(), action);
+        let response = self.client.execute(request).await?;
+        log::trace!("{} responded with {}", action, response.status());
+
+        // Missing or rejected tokens get a sign-in page instead of a 401
+        if response.status() == reqwest::StatusCode::NON_AUTHORITATIVE_INFORMATION {
+            anyhow::bail!(
+                "Failed to {}: Azure DevOps did not accept the token",
+                action
+            );
+        }
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(anyhow::anyhow!(
+                "Failed to {} ({} {}): {}",
+                action,
+                status.as_u16(),
+                status.canonical_reason().unwrap_or("Unknown"),
+                error_text
+            ));
+        }
+
+        response
+            .json()
+            .await
+            .with_context(|| format!("Failed to parse {} response", action))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_url() {
+        let client = AzureClient::new("https://dev.azure.com/org/", Some("token".to_string()));
+        assert_eq!(
+            client.api_url("Platform", "repositories/api"),
+            "https://dev.azure.com/org/Platform/_apis/git/repositories/api?api-version=7.1"
+        );
+        assert!(client.require_token("create pull request").is_ok());
+    }
+}
@@ -0,0 +1,23 @@
+//! Azure DevOps API client library
+//!
+//! This library provides the subset of the Azure DevOps REST API that `repos`
+//! needs to work with Azure Repos: repository information and pull request
+//! creation.
+//!
+//! ## Modules
+//!
+//! - [`client`]: Core Azure DevOps client implementation
+//! - [`pull_requests`]: Pull request creation
+//! - [`repositories`]: Repository information retrieval
+//! - [`util`]: Utility functions for Azure DevOps operations
+
+mod client;
+mod pull_requests;
+mod repositories;
+mod util;
+
+// Re-export public API
+pub use client::{AzureClient, TOKEN_ENV};
+pub use pull_requests::{PullRequest, PullRequestParams};
+pub use repositories::AzureRepo;
+pub use util::{AzureRepoRef, parse_azure_url};
@@ -0,0 +1,168 @@
+//! Pull request operations
+
+use crate::client::AzureClient;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct CreatePullRequestPayload<'a> {
+    source_ref_name: String,
+    target_ref_name: String,
+    title: &'a str,
+    description: &'a str,
+    is_draft: bool,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct PullRequestResponse {
+    pull_request_id: u64,
+    title: String,
+    status: String,
+    repository: PullRequestRepository,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct PullRequestRepository {
+    web_url: String,
+}
+
+#[derive(Debug)]
+pub struct PullRequest {
+    pub html_url: String,
+    pub number: u64,
+    pub title: String,
+    pub state: String,
+}
+
+impl From<PullRequestResponse> for PullRequest {
+    fn from(response: PullRequestResponse) -> Self {
+        Self {
+            // The response only carries the API URL of the pull request
+            html_url: format!(
+                "{}/pullrequest/{}",
+                response.repository.web_url, response.pull_request_id
+            ),
+            number: response.pull_request_id,
+            title: response.title,
+            state: response.status,
+        }
+    }
+}
+
+/// Parameters for creating a pull request
+#[derive(Debug, Clone)]
+pub struct PullRequestParams<'a> {
+    pub project: &'a str,
+    pub repo: &'a str,
+    pub title: &'a str,
+    pub head: &'a str,
+    pub base: &'a str,
+    pub body: &'a str,
+    pub draft: bool,
+}
+
+impl<'a> PullRequestParams<'a> {
+    pub fn new(
+        project: &'a str,
+        repo: &'a str,
+        title: &'a str,
+        head: &'a str,
+        base: &'a str,
+        body: &'a str,
+        draft: bool,
+    ) -> Self {
+        Self {
+            project,
+            repo,
+            title,
+            head,
+            base,
+            body,
+            draft,
+        }
+    }
+
+    fn payload(&self) -> CreatePullRequestPayload<'a> {
+        CreatePullRequestPayload {
+            source_ref_name: format!("refs/heads/{}", self.head),
+            target_ref_name: format!("refs/heads/{}", self.base),
+            title: self.title,
+            description: self.body,
+            is_draft: self.draft,
+        }
+    }
+}
+
+impl AzureClient {
+    /// Create a pull request
+    pub async fn create_pull_request(&self, params: PullRequestParams<'_>) -> Result<PullRequest> {
+        self.require_token("create pull requests")?;
+
+        let url = self.api_url(
+            params.project,
+            &format!("repositories/{}/pullrequests", params.repo),
+        );
+        let response: PullRequestResponse = self
+            .send_json(
+                self.client.post(&url).json(&params.payload()),
+                "create pull request",
+            )
+            .await?;
+        Ok(response.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload() {
+        let params = PullRequestParams::new(
+            "Platform",
+            "api",
+            "Bump deps",
+            "automated-changes",
+            "main",
+            "Body",
+            true,
+        );
+        assert_eq!(
+            params.payload(),
+            CreatePullRequestPayload {
+                source_ref_name: "refs/heads/automated-changes".to_string(),
+                target_ref_name: "refs/heads/main".to_string(),
+                title: "Bump deps",
+                description: "Body",
+                is_draft: true,
+            }
+        );
+        assert_eq!(
+            serde_json::to_value(params.payload()).unwrap()["sourceRefName"],
+            "refs/heads/automated-changes"
+        );
+    }
+
+    #[test]
+    fn test_pull_request_web_url() {
+        let response: PullRequestResponse = serde_json::from_str(
+            r#"{
+                "pullRequestId": 42,
+                "title": "Bump deps",
+                "status": "active",
+                "url": "https://dev.azure.com/org/_apis/git/repositories/1/pullRequests/42",
+                "repository": {"webUrl": "https://dev.azure.com/org/Platform/_git/api"}
+            }"#,
+        )
+        .unwrap();
+        let pull_request = PullRequest::from(response);
+        assert_eq!(pull_request.number, 42);
+        assert_eq!(
+            pull_request.html_url,
+            "https://dev.azure.com/org/Platform/_git/api/pullrequest/42"
+        );
+    }
+}
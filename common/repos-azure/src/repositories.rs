@@ -0,0 +1,52 @@
+//! Repository-related operations
+
+use crate::client::AzureClient;
+use anyhow::Result;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AzureRepo {
+    pub name: String,
+    /// Full ref of the default branch, e.g. `refs/heads/main`
+    #[serde(default)]
+    pub default_branch: Option<String>,
+    #[serde(default)]
+    pub web_url: Option<String>,
+}
+
+impl AzureRepo {
+    /// Default branch without its `refs/heads/` prefix
+    pub fn default_branch_name(&self) -> Option<&str> {
+        self.default_branch
+            .as_deref()
+            .map(|branch| branch.strip_prefix("refs/heads/").unwrap_or(branch))
+    }
+}
+
+impl AzureClient {
+    /// Get repository details, including its default branch
+    pub async fn get_repository_details(&self, project: &str, repo: &str) -> Result<AzureRepo> {
+        let url = self.api_url(project, &format!("repositories/{}", repo));
+        self.send_json(self.client.get(&url), "get repository")
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_branch_name() {
+        let repo: AzureRepo = serde_json::from_str(
+            r#"{"name": "api", "defaultBranch": "refs/heads/main", "webUrl": "https://dev.azure.com/org/Platform/_git/api"}"#,
+        )
+        .unwrap();
+        assert_eq!(repo.default_branch_name(), Some("main"));
+
+        // Empty repositories have no default branch yet
+        let repo: AzureRepo = serde_json::from_str(r#"{"name": "api"}"#).unwrap();
+        assert_eq!(repo.default_branch_name(), None);
+    }
+}
@@ -0,0 +1,167 @@
+//! Utility functions for Azure DevOps operations
+
+use anyhow::{Result, anyhow};
+
+/// Where a repository lives in Azure DevOps
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AzureRepoRef {
+    /// Base URL of the organization's API, e.g. `https://dev.azure.com/org`
+    pub organization_url: String,
+    pub organization: String,
+    pub project: String,
+    pub repo: String,
+}
+
+/// Parse an Azure Repos clone URL into the organization, project, and repository name
+///
+/// Supports these formats:
+/// - HTTPS: `https://dev.azure.com/org/project/_git/repo`, optionally with a
+///   user name such as `https://org@dev.azure.com/...`
+/// - SSH: `git@ssh.dev.azure.com:v3/org/project/repo`
+/// - Legacy HTTPS: `https://org.visualstudio.com/project/_git/repo`, optionally
+///   with `DefaultCollection/` before the project
+/// - Legacy SSH: `org@vs-ssh.visualstudio.com:v3/org/project/repo`
+///
+/// Repositories on a legacy `visualstudio.com` host keep that host in their
+/// organization URL, which the API still serves.
+pub fn parse_azure_url(url: &str) -> Result<AzureRepoRef> {
+    let trimmed = url.trim_end_matches('/').trim_end_matches(".git");
+    let invalid = || anyhow!("Invalid Azure DevOps URL format: {}", url);
+
+    // SSH URLs: v3/org/project/repo
+    if let Some((authority, path)) = trimmed.split_once(":v3/") {
+        let host = authority.rsplit('@').next().unwrap_or(authority);
+        return match path.split('/').collect::<Vec<_>>().as_slice() {
+            [organization, project, repo]
+                if !organization.is_empty() && !project.is_empty() && !repo.is_empty() =>
+            {
+                let organization_url = if host.ends_with("visualstudio.com") {
+                    format!("https://{}.visualstudio.com", organization)
+                } else {
+                    format!("https://dev.azure.com/{}", organization)
+                };
+                Ok(repo_ref(organization_url, organization, project, repo))
+            }
+            _ => Err(invalid()),
+        };
+    }
+
+    let rest = trimmed
+        .strip_prefix("https://")
+        .or_else(|| trimmed.strip_prefix("http://"))
+        .ok_or_else(invalid)?;
+    let (authority, path) = rest.split_once('/').ok_or_else(invalid)?;
+    let host = authority.rsplit('@').next().unwrap_or(authority);
+    let segments: Vec<&str> = path.split('/').collect();
+
+    let (organization_url, organization, segments) = if host == "dev.azure.com" {
+        let (organization, segments) = segments.split_first().ok_or_else(invalid)?;
+        (
+            format!("https://dev.azure.com/{}", organization),
+            organization.to_string(),
+            segments,
+        )
+    } else if let Some(organization) = host.strip_suffix(".visualstudio.com") {
+        let segments = match segments.split_first() {
+            Some((&"DefaultCollection", segments)) => segments,
+            _ => &segments[..],
+        };
+        (
+            format!("https://{}", host),
+            organization.to_string(),
+            segments,
+        )
+    } else {
+        return Err(invalid());
+    };
+
+    match segments {
+        [project, "_git", repo] if !organization.is_empty() && !project.is_empty() => {
+            Ok(repo_ref(organization_url, &organization, project, repo))
+        }
+        // Repositories named after their project may leave the project out
+        ["_git", repo] if !organization.is_empty() => {
+            Ok(repo_ref(organization_url, &organization, repo, repo))
+        }
+        _ => Err(invalid()),
+    }
+}
+
+fn repo_ref(
+    organization_url: String,
+    organization: &str,
+    project: &str,
+    repo: &str,
+) -> AzureRepoRef {
+    AzureRepoRef {
+        organization_url,
+        organization: organization.to_string(),
+        project: project.to_string(),
+        repo: repo.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed(organization_url: &str, project: &str, repo: &str) -> AzureRepoRef {
+        AzureRepoRef {
+            organization_url: organization_url.to_string(),
+            organization: "org".to_string(),
+            project: project.to_string(),
+            repo: repo.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_https_url() {
+        let expected = parsed("https://dev.azure.com/org", "Platform", "api");
+        assert_eq!(
+            parse_azure_url("https://dev.azure.com/org/Platform/_git/api").unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse_azure_url("https://org@dev.azure.com/org/Platform/_git/api").unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse_azure_url("https://dev.azure.com/org/_git/api").unwrap(),
+            parsed("https://dev.azure.com/org", "api", "api")
+        );
+    }
+
+    #[test]
+    fn test_parse_ssh_url() {
+        assert_eq!(
+            parse_azure_url("git@ssh.dev.azure.com:v3/org/Platform/api").unwrap(),
+            parsed("https://dev.azure.com/org", "Platform", "api")
+        );
+        assert_eq!(
+            parse_azure_url("org@vs-ssh.visualstudio.com:v3/org/Platform/api").unwrap(),
+            parsed("https://org.visualstudio.com", "Platform", "api")
+        );
+    }
+
+    #[test]
+    fn test_parse_visualstudio_url() {
+        let expected = parsed("https://org.visualstudio.com", "Platform", "api");
+        assert_eq!(
+            parse_azure_url("https://org.visualstudio.com/Platform/_git/api").unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse_azure_url("https://org.visualstudio.com/DefaultCollection/Platform/_git/api")
+                .unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_url() {
+        assert!(parse_azure_url("invalid-url").is_err());
+        assert!(parse_azure_url("https://github.com/org/api").is_err());
+        assert!(parse_azure_url("https://dev.azure.com/org/Platform/api").is_err());
+        assert!(parse_azure_url("git@ssh.dev.azure.com:v3/org/api").is_err());
+    }
+}
@@ -0,0 +1,98 @@
+//! Gitea client implementation
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+
+/// Environment variable holding the Gitea API token
+pub const TOKEN_ENV: &str = "GITEA_TOKEN";
+
+/// Gitea API client for one instance
+pub struct GiteaClient {
+    pub(crate) client: reqwest::Client,
+    /// Instance URL without a trailing slash, e.g. `https://git.example.com`
+    pub(crate) base_url: String,
+    pub(crate) token: Option<String>,
+}
+
+impl GiteaClient {
+    /// Create a client for the instance at `base_url` with an optional token
+    /// If no token is provided, will try to read from GITEA_TOKEN environment variable
+    pub fn new(base_url: &str, token: Option<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token: token.or_else(|| std::env::var(TOKEN_ENV).ok()),
+        }
+    }
+
+    /// URL of an API endpoint, e.g. `repos/owner/repo`
+    pub(crate) fn api_url(&self, path: &str) -> String {
+        format!("{}/api/v1/{}", self.base_url, path)
+    }
+
+    /// Fail with a helpful message when no token is configured
+    pub(crate) fn require_token(&self, action: &str) -> Result<()> {
+        if self.token.is_none() {
+            anyhow::bail!(
+                "Gitea token is required to {}. Set {} environment variable.",
+                action,
+                TOKEN_ENV
+            );
+        }
+        Ok(())
+    }
+
+    /// Send an API request and parse the JSON response
+    ///
+    /// `action` describes the request in error messages, e.g. "create pull request".
+    pub(crate) async fn send_json<T: DeserializeOwned>(
+        &self,
+        request: reqwest::RequestBuilder,
+        action: &str,
+    ) -> Result<T> {
+        let mut request = request.header("User-Agent", "repos-cli");
+        if let Some(token) = &self.token {
+            request = request.header("Authorization", format!("token {}", token));
+        }
+
+        let request = request.build()?;
+        log::debug!("{} {} ({})", request.method(), request.url(), action);
+        let response = self.client.execute(request).await?;
+        log::trace!("{} responded with {}", action, response.status());
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(anyhow::anyhow!(
+                "Failed to {} ({} {}): {}",
+                action,
+                status.as_u16(),
+                status.canonical_reason().unwrap_or("Unknown"),
+                error_text
+            ));
+        }
+
+        response
+            .json()
+            .await
+            .with_context(|| format!("Failed to parse {} response", action))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_url() {
+        let client = GiteaClient::new("https://git.example.com/", Some("token".to_string()));
+        assert_eq!(
+            client.api_url("repos/org/api"),
+            "https://git.example.com/api/v1/repos/org/api"
+        );
+        assert!(client.require_token("create pull request").is_ok());
+    }
+}
@@ -0,0 +1,23 @@
+//! Gitea API client library
+//!
+//! This library provides the subset of the Gitea API that `repos` needs to work
+//! with self-hosted Gitea and Forgejo instances: repository information and pull
+//! request creation.
+//!
+//! ## Modules
+//!
+//! - [`client`]: Core Gitea client implementation
+//! - [`pull_requests`]: Pull request creation
+//! - [`repositories`]: Repository information retrieval
+//! - [`util`]: Utility functions for Gitea operations
+
+mod client;
+mod pull_requests;
+mod repositories;
+mod util;
+
+// Re-export public API
+pub use client::{GiteaClient, TOKEN_ENV};
+pub use pull_requests::{PullRequest, PullRequestParams};
+pub use repositories::GiteaRepo;
+pub use util::{GiteaRepoRef, parse_gitea_url};
@@ -0,0 +1,119 @@
+//! Pull request operations
+
+use crate::client::GiteaClient;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// Title prefix Gitea uses to mark a pull request as work in progress
+const DRAFT_PREFIX: &str = "WIP: ";
+
+#[derive(Serialize, Debug, PartialEq)]
+struct CreatePullRequestPayload<'a> {
+    title: String,
+    head: &'a str,
+    base: &'a str,
+    body: &'a str,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct PullRequest {
+    pub html_url: String,
+    pub number: u64,
+    pub title: String,
+    pub state: String,
+}
+
+/// Parameters for creating a pull request
+#[derive(Debug, Clone)]
+pub struct PullRequestParams<'a> {
+    pub owner: &'a str,
+    pub repo: &'a str,
+    pub title: &'a str,
+    pub head: &'a str,
+    pub base: &'a str,
+    pub body: &'a str,
+    /// Gitea has no draft state; drafts get the work-in-progress title prefix
+    pub draft: bool,
+}
+
+impl<'a> PullRequestParams<'a> {
+    pub fn new(
+        owner: &'a str,
+        repo: &'a str,
+        title: &'a str,
+        head: &'a str,
+        base: &'a str,
+        body: &'a str,
+        draft: bool,
+    ) -> Self {
+        Self {
+            owner,
+            repo,
+            title,
+            head,
+            base,
+            body,
+            draft,
+        }
+    }
+
+    fn payload(&self) -> CreatePullRequestPayload<'a> {
+        CreatePullRequestPayload {
+            title: if self.draft {
+                format!("{}{}", DRAFT_PREFIX, self.title)
+            } else {
+                self.title.to_string()
+            },
+            head: self.head,
+            base: self.base,
+            body: self.body,
+        }
+    }
+}
+
+impl GiteaClient {
+    /// Create a pull request
+    pub async fn create_pull_request(&self, params: PullRequestParams<'_>) -> Result<PullRequest> {
+        self.require_token("create pull requests")?;
+
+        let url = self.api_url(&format!("repos/{}/{}/pulls", params.owner, params.repo));
+        self.send_json(
+            self.client.post(&url).json(&params.payload()),
+            "create pull request",
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_draft_payload() {
+        let params = PullRequestParams::new(
+            "org",
+            "api",
+            "Bump deps",
+            "automated-changes",
+            "main",
+            "Body",
+            true,
+        );
+        assert_eq!(params.payload().title, "WIP: Bump deps");
+
+        let params = PullRequestParams {
+            draft: false,
+            ..params
+        };
+        assert_eq!(
+            params.payload(),
+            CreatePullRequestPayload {
+                title: "Bump deps".to_string(),
+                head: "automated-changes",
+                base: "main",
+                body: "Body",
+            }
+        );
+    }
+}
@@ -0,0 +1,22 @@
+//! Repository-related operations
+
+use crate::client::GiteaClient;
+use anyhow::Result;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct GiteaRepo {
+    #[serde(default)]
+    pub topics: Vec<String>,
+    #[serde(default)]
+    pub default_branch: Option<String>,
+}
+
+impl GiteaClient {
+    /// Get repository details, including its topics and default branch
+    pub async fn get_repository_details(&self, owner: &str, repo: &str) -> Result<GiteaRepo> {
+        let url = self.api_url(&format!("repos/{}/{}", owner, repo));
+        self.send_json(self.client.get(&url), "get repository")
+            .await
+    }
+}
@@ -0,0 +1,111 @@
+//! Utility functions for Gitea operations
+
+use anyhow::{Result, anyhow};
+
+/// Where a repository lives on a Gitea instance
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GiteaRepoRef {
+    /// Web and API base URL of the instance, e.g. `https://git.example.com`
+    pub instance_url: String,
+    pub owner: String,
+    pub repo: String,
+}
+
+/// Parse a Gitea clone URL into the instance URL, owner, and repository name
+///
+/// Supports these formats:
+/// - SSH: `git@git.example.com:owner/repo.git`
+/// - SSH with a port: `ssh://git@git.example.com:2222/owner/repo.git`
+/// - HTTPS, including instances served from a sub-path:
+///   `https://example.com/gitea/owner/repo.git`
+///
+/// SSH URLs map to an HTTPS instance URL on the same host.
+pub fn parse_gitea_url(url: &str) -> Result<GiteaRepoRef> {
+    let trimmed = url.trim_end_matches('/').trim_end_matches(".git");
+
+    let invalid = || anyhow!("Invalid Gitea URL format: {}", url);
+    let (instance_url, path) = match trimmed.split_once("://") {
+        Some((scheme @ ("https" | "http"), rest)) => {
+            // Everything before owner/repo belongs to the instance URL
+            let segments: Vec<&str> = rest.split('/').collect();
+            if segments.len() < 3 {
+                return Err(invalid());
+            }
+            let split = segments.len() - 2;
+            (
+                format!("{}://{}", scheme, segments[..split].join("/")),
+                segments[split..].join("/"),
+            )
+        }
+        Some(("ssh", rest)) => {
+            let (authority, path) = rest.split_once('/').ok_or_else(invalid)?;
+            (format!("https://{}", host_of(authority)), path.to_string())
+        }
+        Some(_) => return Err(invalid()),
+        None => match trimmed.split_once(':') {
+            Some((authority, path)) if authority.contains('@') => {
+                (format!("https://{}", host_of(authority)), path.to_string())
+            }
+            _ => return Err(invalid()),
+        },
+    };
+
+    match path.split('/').collect::<Vec<_>>().as_slice() {
+        [owner, repo] if !owner.is_empty() && !repo.is_empty() => Ok(GiteaRepoRef {
+            instance_url,
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+        }),
+        _ => Err(invalid()),
+    }
+}
+
+/// Host of an SSH authority such as `git@host:2222`
+fn host_of(authority: &str) -> &str {
+    let host = authority.rsplit('@').next().unwrap_or(authority);
+    host.split(':').next().unwrap_or(host)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed(instance_url: &str, owner: &str, repo: &str) -> GiteaRepoRef {
+        GiteaRepoRef {
+            instance_url: instance_url.to_string(),
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_ssh_url() {
+        assert_eq!(
+            parse_gitea_url("git@git.example.com:org/api.git").unwrap(),
+            parsed("https://git.example.com", "org", "api")
+        );
+        assert_eq!(
+            parse_gitea_url("ssh://git@git.example.com:2222/org/api.git").unwrap(),
+            parsed("https://git.example.com", "org", "api")
+        );
+    }
+
+    #[test]
+    fn test_parse_https_url() {
+        assert_eq!(
+            parse_gitea_url("https://codeberg.org/org/api.git").unwrap(),
+            parsed("https://codeberg.org", "org", "api")
+        );
+        assert_eq!(
+            parse_gitea_url("http://example.com/gitea/org/api/").unwrap(),
+            parsed("http://example.com/gitea", "org", "api")
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_url() {
+        assert!(parse_gitea_url("invalid-url").is_err());
+        assert!(parse_gitea_url("https://git.example.com/api").is_err());
+        assert!(parse_gitea_url("git@git.example.com:org/group/api.git").is_err());
+    }
+}
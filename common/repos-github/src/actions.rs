@@ -0,0 +1,60 @@
+//! GitHub Actions workflow runs
+
+use crate::client::GitHubClient;
+use anyhow::Result;
+use serde::Deserialize;
+
+/// A run of a GitHub Actions workflow
+#[derive(Deserialize, Debug, Clone)]
+pub struct WorkflowRun {
+    pub id: u64,
+    pub workflow_id: u64,
+    /// Name of the workflow
+    #[serde(default)]
+    pub name: Option<String>,
+    pub head_branch: Option<String>,
+    pub head_sha: String,
+    /// Event that triggered the run, e.g. `push` or `schedule`
+    pub event: String,
+    /// `queued`, `in_progress` or `completed`, among others
+    #[serde(default)]
+    pub status: Option<String>,
+    /// Set once the run is completed, e.g. `success` or `failure`
+    pub conclusion: Option<String>,
+    pub html_url: String,
+    /// RFC 3339 timestamp of the last update
+    pub updated_at: String,
+}
+
+#[derive(Deserialize)]
+struct WorkflowRunList {
+    workflow_runs: Vec<WorkflowRun>,
+}
+
+impl GitHubClient {
+    /// List the most recent workflow runs on a branch, newest first
+    ///
+    /// Runs triggered by pull requests are left out.
+    pub async fn list_workflow_runs(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+    ) -> Result<Vec<WorkflowRun>> {
+        let url = reqwest::Url::parse_with_params(
+            &format!(
+                "https://api.github.com/repos/{}/{}/actions/runs",
+                owner, repo
+            ),
+            [
+                ("branch", branch),
+                ("exclude_pull_requests", "true"),
+                ("per_page", "100"),
+            ],
+        )?;
+        let list: WorkflowRunList = self
+            .send_json(self.client.get(url), "list workflow runs")
+            .await?;
+        Ok(list.workflow_runs)
+    }
+}
@@ -0,0 +1,253 @@
+//! Branch protection rules
+
+use crate::client::GitHubClient;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+/// Protection rules of a branch
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct BranchProtection {
+    /// Approving reviews a pull request needs, `None` when reviews are not required
+    pub required_approving_reviews: Option<u32>,
+    pub dismiss_stale_reviews: bool,
+    pub require_code_owner_reviews: bool,
+    /// Status checks that must pass, `None` when no checks are required
+    pub required_status_checks: Option<Vec<String>>,
+    /// Whether the branch must be up to date before merging
+    pub strict_status_checks: bool,
+    pub enforce_admins: bool,
+    pub required_linear_history: bool,
+    pub allow_force_pushes: bool,
+    pub allow_deletions: bool,
+    pub required_conversation_resolution: bool,
+    /// Who may push, kept as is when the protection is updated
+    pub restrictions: Option<PushRestrictions>,
+}
+
+/// Users, teams and apps allowed to push to a branch
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PushRestrictions {
+    pub users: Vec<String>,
+    pub teams: Vec<String>,
+    pub apps: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct ProtectionResponse {
+    required_status_checks: Option<StatusChecksResponse>,
+    required_pull_request_reviews: Option<ReviewsResponse>,
+    enforce_admins: Option<Enabled>,
+    required_linear_history: Option<Enabled>,
+    allow_force_pushes: Option<Enabled>,
+    allow_deletions: Option<Enabled>,
+    required_conversation_resolution: Option<Enabled>,
+    restrictions: Option<RestrictionsResponse>,
+}
+
+#[derive(Deserialize)]
+struct StatusChecksResponse {
+    #[serde(default)]
+    strict: bool,
+    #[serde(default)]
+    contexts: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct ReviewsResponse {
+    #[serde(default)]
+    dismiss_stale_reviews: bool,
+    #[serde(default)]
+    require_code_owner_reviews: bool,
+    #[serde(default)]
+    required_approving_review_count: u32,
+}
+
+#[derive(Deserialize)]
+struct Enabled {
+    enabled: bool,
+}
+
+#[derive(Deserialize)]
+struct RestrictionsResponse {
+    #[serde(default)]
+    users: Vec<Login>,
+    #[serde(default)]
+    teams: Vec<Slug>,
+    #[serde(default)]
+    apps: Vec<Slug>,
+}
+
+#[derive(Deserialize)]
+struct Login {
+    login: String,
+}
+
+#[derive(Deserialize)]
+struct Slug {
+    slug: String,
+}
+
+impl From<ProtectionResponse> for BranchProtection {
+    fn from(response: ProtectionResponse) -> Self {
+        let enabled = |setting: Option<Enabled>| setting.is_some_and(|s| s.enabled);
+        let reviews = response.required_pull_request_reviews;
+        BranchProtection {
+            required_approving_reviews: reviews.as_ref().map(|r| r.required_approving_review_count),
+            dismiss_stale_reviews: reviews.as_ref().is_some_and(|r| r.dismiss_stale_reviews),
+            require_code_owner_reviews: reviews
+                .as_ref()
+                .is_some_and(|r| r.require_code_owner_reviews),
+            strict_status_checks: response
+                .required_status_checks
+                .as_ref()
+                .is_some_and(|c| c.strict),
+            required_status_checks: response.required_status_checks.map(|c| c.contexts),
+            enforce_admins: enabled(response.enforce_admins),
+            required_linear_history: enabled(response.required_linear_history),
+            allow_force_pushes: enabled(response.allow_force_pushes),
+            allow_deletions: enabled(response.allow_deletions),
+            required_conversation_resolution: enabled(response.required_conversation_resolution),
+            restrictions: response.restrictions.map(|r| PushRestrictions {
+                users: r.users.into_iter().map(|u| u.login).collect(),
+                teams: r.teams.into_iter().map(|t| t.slug).collect(),
+                apps: r.apps.into_iter().map(|a| a.slug).collect(),
+            }),
+        }
+    }
+}
+
+impl BranchProtection {
+    /// Body of the request replacing a branch's protection with this one
+    fn request_body(&self) -> serde_json::Value {
+        json!({
+            "required_status_checks": self.required_status_checks.as_ref().map(|contexts| json!({
+                "strict": self.strict_status_checks,
+                "contexts": contexts,
+            })),
+            "enforce_admins": self.enforce_admins,
+            "required_pull_request_reviews": self.required_approving_reviews.map(|count| json!({
+                "dismiss_stale_reviews": self.dismiss_stale_reviews,
+                "require_code_owner_reviews": self.require_code_owner_reviews,
+                "required_approving_review_count": count,
+            })),
+            "restrictions": self.restrictions,
+            "required_linear_history": self.required_linear_history,
+            "allow_force_pushes": self.allow_force_pushes,
+            "allow_deletions": self.allow_deletions,
+            "required_conversation_resolution": self.required_conversation_resolution,
+        })
+    }
+}
+
+impl GitHubClient {
+    /// Protection rules of a branch, or `None` when the branch is not protected
+    pub async fn get_branch_protection(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+    ) -> Result<Option<BranchProtection>> {
+        self.require_token("get branch protection")?;
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/branches/{}/protection",
+            owner, repo, branch
+        );
+        let mut request = self.client.get(&url).header("User-Agent", "repos-cli");
+        if let Some(token) = &self.token {
+            request = request.header("Authorization", format!("token {}", token));
+        }
+
+        log::debug!("GET {}", url);
+        let response = request.send().await?;
+
+        if response.status().as_u16() == 404 {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(Self::status_error(response, "get branch protection").await);
+        }
+
+        let protection: ProtectionResponse = response
+            .json()
+            .await
+            .context("Failed to parse branch protection response")?;
+        Ok(Some(protection.into()))
+    }
+
+    /// Replace the protection rules of a branch
+    pub async fn update_branch_protection(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+        protection: &BranchProtection,
+    ) -> Result<()> {
+        self.require_token("update branch protection")?;
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/branches/{}/protection",
+            owner, repo, branch
+        );
+        self.send(
+            self.client.put(&url).json(&protection.request_body()),
+            "update branch protection",
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_protection_response() {
+        let response: ProtectionResponse = serde_json::from_value(json!({
+            "required_status_checks": {"strict": true, "contexts": ["ci/build"]},
+            "required_pull_request_reviews": {
+                "dismiss_stale_reviews": true,
+                "required_approving_review_count": 2
+            },
+            "enforce_admins": {"enabled": false},
+            "allow_force_pushes": {"enabled": false},
+            "required_linear_history": {"enabled": true},
+            "restrictions": {"users": [{"login": "alice"}], "teams": [{"slug": "core"}], "apps": []}
+        }))
+        .unwrap();
+        let protection = BranchProtection::from(response);
+
+        assert_eq!(protection.required_approving_reviews, Some(2));
+        assert!(protection.dismiss_stale_reviews);
+        assert!(!protection.require_code_owner_reviews);
+        assert_eq!(
+            protection.required_status_checks,
+            Some(vec!["ci/build".to_string()])
+        );
+        assert!(protection.strict_status_checks);
+        assert!(protection.required_linear_history);
+        assert!(!protection.allow_deletions);
+        let restrictions = protection.restrictions.unwrap();
+        assert_eq!(restrictions.users, vec!["alice"]);
+        assert_eq!(restrictions.teams, vec!["core"]);
+    }
+
+    #[test]
+    fn test_request_body() {
+        let protection = BranchProtection {
+            required_approving_reviews: Some(1),
+            enforce_admins: true,
+            ..Default::default()
+        };
+        let body = protection.request_body();
+
+        assert_eq!(
+            body["required_pull_request_reviews"]["required_approving_review_count"],
+            1
+        );
+        assert!(body["required_status_checks"].is_null());
+        assert!(body["restrictions"].is_null());
+        assert_eq!(body["enforce_admins"], true);
+        assert_eq!(body["allow_force_pushes"], false);
+    }
+}
@@ -0,0 +1,194 @@
+//! On-disk cache of GET responses
+//!
+//! Cached responses are served without a request while they are younger than
+//! the TTL. Older ones are revalidated with `If-None-Match`, so an unchanged
+//! resource costs a `304 Not Modified`, which GitHub does not count against
+//! the rate limit. Entries are kept per repository and per token, and a
+//! successful write to a repository drops its entries.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+static DEFAULT: OnceLock<Option<ResponseCache>> = OnceLock::new();
+
+/// Cache every client created afterwards uses, or `None` to disable caching
+///
+/// Only the first call has an effect.
+pub fn set_default_cache(cache: Option<ResponseCache>) {
+    let _ = DEFAULT.set(cache);
+}
+
+/// Cache new clients use; caching is off until [`set_default_cache`] is called
+pub(crate) fn default_cache() -> Option<ResponseCache> {
+    DEFAULT.get().cloned().flatten()
+}
+
+/// Directory of cached responses and how long they are served without asking GitHub
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResponseCache {
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+/// A cached response body
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct CacheEntry {
+    pub etag: Option<String>,
+    /// Seconds since the Unix epoch when the body was last confirmed
+    pub fetched_at: u64,
+    pub body: String,
+}
+
+impl CacheEntry {
+    pub fn new(etag: Option<String>, body: String) -> Self {
+        Self {
+            etag,
+            fetched_at: now(),
+            body,
+        }
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+impl ResponseCache {
+    pub fn new(dir: impl Into<PathBuf>, ttl: Duration) -> Self {
+        Self {
+            dir: dir.into(),
+            ttl,
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Entry for `url` requested with `token`, fresh or not
+    pub(crate) fn get(&self, url: &str, token: Option<&str>) -> Option<CacheEntry> {
+        let path = self.entry_path(url, token)?;
+        let content = std::fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Whether an entry may be used without revalidating it
+    pub(crate) fn is_fresh(&self, entry: &CacheEntry) -> bool {
+        now().saturating_sub(entry.fetched_at) < self.ttl.as_secs()
+    }
+
+    pub(crate) fn put(&self, url: &str, token: Option<&str>, entry: &CacheEntry) -> Result<()> {
+        let Some(path) = self.entry_path(url, token) else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        std::fs::write(&path, serde_json::to_string(entry)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Drop the cached responses of the repository `url` belongs to
+    pub(crate) fn invalidate(&self, url: &str) {
+        if let Some(dir) = self.repository_dir(url) {
+            let _ = std::fs::remove_dir_all(dir);
+        }
+    }
+
+    /// Remove every cached response
+    pub fn clear(&self) -> Result<()> {
+        if self.dir.exists() {
+            std::fs::remove_dir_all(&self.dir)
+                .with_context(|| format!("Failed to remove {}", self.dir.display()))?;
+        }
+        Ok(())
+    }
+
+    /// `<dir>/<owner>/<repo>` for URLs under `/repos/<owner>/<repo>`; other URLs are not cached
+    fn repository_dir(&self, url: &str) -> Option<PathBuf> {
+        let url = reqwest::Url::parse(url).ok()?;
+        let mut segments = url.path_segments()?;
+        if segments.next()? != "repos" {
+            return None;
+        }
+        let owner = segments.next().filter(|s| is_safe_segment(s))?;
+        let repo = segments.next().filter(|s| is_safe_segment(s))?;
+        Some(
+            self.dir
+                .join(owner.to_lowercase())
+                .join(repo.to_lowercase()),
+        )
+    }
+
+    fn entry_path(&self, url: &str, token: Option<&str>) -> Option<PathBuf> {
+        let mut hasher = Sha256::new();
+        hasher.update(token.unwrap_or_default());
+        hasher.update([0]);
+        hasher.update(url);
+        let key: String = hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        Some(self.repository_dir(url)?.join(format!("{}.json", key)))
+    }
+}
+
+fn is_safe_segment(segment: &str) -> bool {
+    !segment.is_empty() && segment != "." && segment != ".."
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entries_per_repository_and_token() {
+        let temp_dir = std::env::temp_dir().join(format!("repos-github-cache-{}", now()));
+        let cache = ResponseCache::new(&temp_dir, Duration::from_secs(60));
+        let url = "https://api.github.com/repos/Acme/api/pulls?state=open";
+
+        assert!(cache.get(url, Some("a")).is_none());
+        cache
+            .put(
+                url,
+                Some("a"),
+                &CacheEntry::new(Some("\"v1\"".into()), "[]".into()),
+            )
+            .unwrap();
+        let entry = cache.get(url, Some("a")).unwrap();
+        assert_eq!(entry.etag.as_deref(), Some("\"v1\""));
+        assert!(cache.is_fresh(&entry));
+        assert!(temp_dir.join("acme").join("api").is_dir());
+
+        // Another token does not see the entry
+        assert!(cache.get(url, Some("b")).is_none());
+
+        // Only repository URLs are cached
+        let user = "https://api.github.com/user";
+        cache
+            .put(user, None, &CacheEntry::new(None, "{}".into()))
+            .unwrap();
+        assert!(cache.get(user, None).is_none());
+
+        cache.invalidate("https://api.github.com/repos/acme/api/pulls/1/merge");
+        assert!(cache.get(url, Some("a")).is_none());
+
+        let stale = CacheEntry {
+            fetched_at: now() - 120,
+            ..entry
+        };
+        assert!(!cache.is_fresh(&stale));
+
+        cache.clear().unwrap();
+        assert!(!temp_dir.exists());
+    }
+}
@@ -1,9 +1,21 @@
 //! GitHub client implementation
 
+use crate::cache::{CacheEntry, ResponseCache, default_cache};
+use anyhow::{Context, Result};
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+
 /// GitHub API client for making authenticated requests
 pub struct GitHubClient {
     pub(crate) client: reqwest::Client,
     pub(crate) token: Option<String>,
+    pub(crate) cache: Option<ResponseCache>,
+}
+
+/// Outcome of a GET request that may have been answered from the cache
+pub(crate) enum CachedResponse {
+    Body(String),
+    Failed(reqwest::Response),
 }
 
 impl GitHubClient {
@@ -13,8 +25,226 @@ impl GitHubClient {
         Self {
             client: reqwest::Client::new(),
             token: token.or_else(|| std::env::var("GITHUB_TOKEN").ok()),
+            cache: default_cache(),
+        }
+    }
+
+    /// Cache repository details and pull request lists in `cache` instead of the default
+    pub fn with_cache(mut self, cache: Option<ResponseCache>) -> Self {
+        self.cache = cache;
+        self
+    }
+}
+
+impl GitHubClient {
+    /// Fail with a helpful message when no token is configured
+    pub(crate) fn require_token(&self, action: &str) -> Result<()> {
+        if self.token.is_none() {
+            anyhow::bail!(
+                "GitHub token is required to {}. Set GITHUB_TOKEN environment variable.",
+                action
+            );
+        }
+        Ok(())
+    }
+
+    /// Send an API request and parse the JSON response
+    ///
+    /// `action` describes the request in error messages, e.g. "create tree".
+    pub(crate) async fn send_json<T: DeserializeOwned>(
+        &self,
+        request: reqwest::RequestBuilder,
+        action: &str,
+    ) -> Result<T> {
+        self.send(request, action)
+            .await?
+            .json()
+            .await
+            .with_context(|| format!("Failed to parse {} response", action))
+    }
+
+    /// Send an API request, failing on any non-success status
+    pub(crate) async fn send(
+        &self,
+        request: reqwest::RequestBuilder,
+        action: &str,
+    ) -> Result<reqwest::Response> {
+        let mut request = request.header("User-Agent", "repos-cli");
+        if let Some(token) = &self.token {
+            request = request.header("Authorization", format!("token {}", token));
+        }
+
+        let request = request.build()?;
+        let is_write = request.method() != reqwest::Method::GET;
+        let url = request.url().to_string();
+        log::debug!("{} {} ({})", request.method(), url, action);
+        let response = self.client.execute(request).await?;
+        log::trace!("{} responded with {}", action, response.status());
+
+        if !response.status().is_success() {
+            return Err(Self::status_error(response, action).await);
+        }
+
+        // Cached lists and details of the repository may no longer be accurate
+        if is_write && let Some(cache) = &self.cache {
+            cache.invalidate(&url);
+        }
+
+        Ok(response)
+    }
+
+    /// Error describing a response with a non-success status
+    pub(crate) async fn status_error(response: reqwest::Response, action: &str) -> anyhow::Error {
+        let status = response.status();
+        let error_text = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "Unknown error".to_string());
+        anyhow::anyhow!(
+            "Failed to {} ({} {}): {}",
+            action,
+            status.as_u16(),
+            status.canonical_reason().unwrap_or("Unknown"),
+            error_text
+        )
+    }
+
+    /// GET `url` and parse the JSON response, going through the cache
+    pub(crate) async fn get_json_cached<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        action: &str,
+    ) -> Result<T> {
+        match self.get_cached(url, action).await? {
+            CachedResponse::Body(body) => serde_json::from_str(&body)
+                .with_context(|| format!("Failed to parse {} response", action)),
+            CachedResponse::Failed(response) => Err(Self::status_error(response, action).await),
         }
     }
+
+    /// GET `url`, answering from the cache while the entry is fresh and
+    /// revalidating it with its ETag once it is not
+    pub(crate) async fn get_cached(&self, url: &str, action: &str) -> Result<CachedResponse> {
+        let token = self.token.as_deref();
+        let entry = self.cache.as_ref().and_then(|cache| cache.get(url, token));
+        if let (Some(cache), Some(entry)) = (&self.cache, &entry)
+            && cache.is_fresh(entry)
+        {
+            log::debug!("GET {} ({}, cached)", url, action);
+            return Ok(CachedResponse::Body(entry.body.clone()));
+        }
+
+        let mut request = self.client.get(url).header("User-Agent", "repos-cli");
+        if let Some(token) = token {
+            request = request.header("Authorization", format!("token {}", token));
+        }
+        if let Some(etag) = entry.as_ref().and_then(|entry| entry.etag.as_ref()) {
+            request = request.header("If-None-Match", etag);
+        }
+        log::debug!("GET {} ({})", url, action);
+        let response = request.send().await?;
+        log::trace!("{} responded with {}", action, response.status());
+
+        if response.status() == StatusCode::NOT_MODIFIED
+            && let Some(entry) = entry
+        {
+            let entry = CacheEntry::new(entry.etag, entry.body);
+            self.store(url, &entry);
+            return Ok(CachedResponse::Body(entry.body));
+        }
+        if !response.status().is_success() {
+            return Ok(CachedResponse::Failed(response));
+        }
+
+        let etag = response
+            .headers()
+            .get(reqwest::header::ETAG)
+            .and_then(|etag| etag.to_str().ok())
+            .map(str::to_string);
+        let body = response
+            .text()
+            .await
+            .with_context(|| format!("Failed to read {} response", action))?;
+        self.store(url, &CacheEntry::new(etag, body.clone()));
+        Ok(CachedResponse::Body(body))
+    }
+
+    /// Save a response; a cache that cannot be written only costs the next request
+    fn store(&self, url: &str, entry: &CacheEntry) {
+        if let Some(cache) = &self.cache
+            && let Err(e) = cache.put(url, self.token.as_deref(), entry)
+        {
+            log::debug!("Not caching {}: {:#}", url, e);
+        }
+    }
+}
+
+/// An error GraphQL reported next to the data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct GraphqlError {
+    pub message: String,
+    /// Alias or field of the query the error belongs to, if any
+    pub field: Option<String>,
+}
+
+impl GitHubClient {
+    /// Run a GraphQL query or mutation and return its `data`
+    pub(crate) async fn graphql(
+        &self,
+        query: &str,
+        variables: serde_json::Value,
+        action: &str,
+    ) -> Result<serde_json::Value> {
+        let (data, errors) = self.graphql_partial(query, variables, action).await?;
+        if !errors.is_empty() {
+            let messages: Vec<&str> = errors.iter().map(|e| e.message.as_str()).collect();
+            anyhow::bail!("Failed to {}: {}", action, messages.join("; "));
+        }
+        Ok(data)
+    }
+
+    /// Run a GraphQL query and return its `data` along with the errors of the
+    /// fields that failed, failing only when there is no data at all
+    pub(crate) async fn graphql_partial(
+        &self,
+        query: &str,
+        variables: serde_json::Value,
+        action: &str,
+    ) -> Result<(serde_json::Value, Vec<GraphqlError>)> {
+        self.require_token(action)?;
+
+        let payload = serde_json::json!({ "query": query, "variables": variables });
+        let mut response: serde_json::Value = self
+            .send_json(
+                self.client
+                    .post("https://api.github.com/graphql")
+                    .json(&payload),
+                action,
+            )
+            .await?;
+
+        // GraphQL reports failures in the body with a 200 status
+        let errors: Vec<GraphqlError> = response
+            .get("errors")
+            .and_then(|e| e.as_array())
+            .map(|errors| {
+                errors
+                    .iter()
+                    .map(|e| GraphqlError {
+                        message: e["message"].as_str().unwrap_or("Unknown error").to_string(),
+                        field: e["path"][0].as_str().map(str::to_string),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let data = response["data"].take();
+        if data.is_null() && !errors.is_empty() {
+            let messages: Vec<&str> = errors.iter().map(|e| e.message.as_str()).collect();
+            anyhow::bail!("Failed to {}: {}", action, messages.join("; "));
+        }
+
+        Ok((data, errors))
+    }
 }
 
 impl Default for GitHubClient {
@@ -0,0 +1,62 @@
+//! Commit status and check run operations
+
+use crate::client::GitHubClient;
+use anyhow::Result;
+use serde::Deserialize;
+
+/// Combined result of the commit statuses reported for a commit
+#[derive(Deserialize, Debug, Clone)]
+pub struct CombinedStatus {
+    /// `success`, `pending` or `failure`
+    pub state: String,
+    pub total_count: u64,
+}
+
+/// A check run reported for a commit, e.g. by GitHub Actions
+#[derive(Deserialize, Debug, Clone)]
+pub struct CheckRun {
+    pub name: String,
+    /// `queued`, `in_progress` or `completed`
+    pub status: String,
+    /// Set once the run is completed, e.g. `success` or `failure`
+    pub conclusion: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CheckRunList {
+    check_runs: Vec<CheckRun>,
+}
+
+impl GitHubClient {
+    /// Get the combined commit status for a ref or SHA
+    pub async fn get_combined_status(
+        &self,
+        owner: &str,
+        repo: &str,
+        reference: &str,
+    ) -> Result<CombinedStatus> {
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/commits/{}/status",
+            owner, repo, reference
+        );
+        self.send_json(self.client.get(&url), "get commit status")
+            .await
+    }
+
+    /// List the check runs for a ref or SHA
+    pub async fn list_check_runs(
+        &self,
+        owner: &str,
+        repo: &str,
+        reference: &str,
+    ) -> Result<Vec<CheckRun>> {
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/commits/{}/check-runs?per_page=100",
+            owner, repo, reference
+        );
+        let list: CheckRunList = self
+            .send_json(self.client.get(&url), "list check runs")
+            .await?;
+        Ok(list.check_runs)
+    }
+}
@@ -0,0 +1,221 @@
+//! Git data operations for committing without a local clone
+
+use crate::client::GitHubClient;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// A file to write into a tree, with its full contents
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeFile {
+    /// Path relative to the repository root, using `/` separators
+    pub path: String,
+    pub content: String,
+}
+
+#[derive(Serialize)]
+struct TreeEntryPayload<'a> {
+    path: &'a str,
+    mode: &'static str,
+    #[serde(rename = "type")]
+    kind: &'static str,
+    content: &'a str,
+}
+
+#[derive(Serialize)]
+struct CreateTreePayload<'a> {
+    base_tree: &'a str,
+    tree: Vec<TreeEntryPayload<'a>>,
+}
+
+impl<'a> CreateTreePayload<'a> {
+    fn new(base_tree: &'a str, files: &'a [TreeFile]) -> Self {
+        Self {
+            base_tree,
+            tree: files
+                .iter()
+                .map(|file| TreeEntryPayload {
+                    path: &file.path,
+                    mode: "100644",
+                    kind: "blob",
+                    content: &file.content,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct CreateCommitPayload<'a> {
+    message: &'a str,
+    tree: &'a str,
+    parents: [&'a str; 1],
+}
+
+#[derive(Serialize)]
+struct CreateRefPayload<'a> {
+    #[serde(rename = "ref")]
+    reference: String,
+    sha: &'a str,
+}
+
+#[derive(Deserialize)]
+struct GitObject {
+    sha: String,
+}
+
+#[derive(Deserialize)]
+struct GitRef {
+    object: GitObject,
+}
+
+#[derive(Deserialize)]
+struct GitCommit {
+    tree: GitObject,
+}
+
+impl GitHubClient {
+    /// Get the commit SHA a branch points to
+    pub async fn get_branch_sha(&self, owner: &str, repo: &str, branch: &str) -> Result<String> {
+        self.require_token("get branch")?;
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/git/ref/heads/{}",
+            owner, repo, branch
+        );
+        let git_ref: GitRef = self.send_json(self.client.get(&url), "get branch").await?;
+        Ok(git_ref.object.sha)
+    }
+
+    /// Get the tree SHA of a commit
+    pub async fn get_commit_tree_sha(
+        &self,
+        owner: &str,
+        repo: &str,
+        commit: &str,
+    ) -> Result<String> {
+        self.require_token("get commit")?;
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/git/commits/{}",
+            owner, repo, commit
+        );
+        let commit: GitCommit = self.send_json(self.client.get(&url), "get commit").await?;
+        Ok(commit.tree.sha)
+    }
+
+    /// Create a tree that replaces or adds `files` on top of `base_tree`
+    ///
+    /// Returns the new tree SHA, which equals `base_tree` when nothing changed.
+    pub async fn create_tree(
+        &self,
+        owner: &str,
+        repo: &str,
+        base_tree: &str,
+        files: &[TreeFile],
+    ) -> Result<String> {
+        self.require_token("create tree")?;
+        let url = format!("https://api.github.com/repos/{}/{}/git/trees", owner, repo);
+        let payload = CreateTreePayload::new(base_tree, files);
+        let tree: GitObject = self
+            .send_json(self.client.post(&url).json(&payload), "create tree")
+            .await?;
+        Ok(tree.sha)
+    }
+
+    /// Create a commit with a single parent and return its SHA
+    pub async fn create_commit(
+        &self,
+        owner: &str,
+        repo: &str,
+        message: &str,
+        tree: &str,
+        parent: &str,
+    ) -> Result<String> {
+        self.require_token("create commit")?;
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/git/commits",
+            owner, repo
+        );
+        let payload = CreateCommitPayload {
+            message,
+            tree,
+            parents: [parent],
+        };
+        let commit: GitObject = self
+            .send_json(self.client.post(&url).json(&payload), "create commit")
+            .await?;
+        Ok(commit.sha)
+    }
+
+    /// Create a branch pointing at a commit
+    pub async fn create_branch(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+        sha: &str,
+    ) -> Result<()> {
+        self.require_token("create branch")?;
+        let url = format!("https://api.github.com/repos/{}/{}/git/refs", owner, repo);
+        let payload = CreateRefPayload {
+            reference: format!("refs/heads/{}", branch),
+            sha,
+        };
+        let _: GitRef = self
+            .send_json(self.client.post(&url).json(&payload), "create branch")
+            .await?;
+        Ok(())
+    }
+
+    /// Point an existing branch at a commit, even if that discards commits
+    pub async fn force_update_branch(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+        sha: &str,
+    ) -> Result<()> {
+        self.require_token("update branch")?;
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/git/refs/heads/{}",
+            owner, repo, branch
+        );
+        let payload = serde_json::json!({ "sha": sha, "force": true });
+        let _: GitRef = self
+            .send_json(self.client.patch(&url).json(&payload), "update branch")
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tree_payload_serialization() {
+        let files = [TreeFile {
+            path: ".github/CODEOWNERS".to_string(),
+            content: "* @org/team\n".to_string(),
+        }];
+        let payload = CreateTreePayload::new("abc123", &files);
+
+        let json = serde_json::to_value(&payload).unwrap();
+        assert_eq!(json["base_tree"], "abc123");
+        assert_eq!(json["tree"][0]["type"], "blob");
+        assert_eq!(json["tree"][0]["path"], ".github/CODEOWNERS");
+    }
+
+    #[tokio::test]
+    async fn test_requires_token() {
+        let client = GitHubClient {
+            client: reqwest::Client::new(),
+            token: None,
+            cache: None,
+        };
+
+        let err = client
+            .get_branch_sha("owner", "repo", "main")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("token is required"));
+    }
+}
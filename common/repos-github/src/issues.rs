@@ -0,0 +1,208 @@
+//! Issues and the operations they share with pull requests, such as comments
+
+use crate::client::GitHubClient;
+use crate::pull_requests::{Label, ReviewUser};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize)]
+struct CreateCommentPayload<'a> {
+    body: &'a str,
+}
+
+/// A comment posted on an issue or pull request
+#[derive(Deserialize, Debug, Clone)]
+pub struct IssueComment {
+    pub id: u64,
+    pub html_url: String,
+}
+
+/// An issue as returned when listing or creating issues
+#[derive(Deserialize, Debug, Clone)]
+pub struct Issue {
+    pub number: u64,
+    pub title: String,
+    pub html_url: String,
+    /// `open` or `closed`
+    pub state: String,
+    pub user: Option<ReviewUser>,
+    #[serde(default)]
+    pub labels: Vec<Label>,
+    /// RFC 3339 timestamp of the last activity
+    #[serde(default)]
+    pub updated_at: String,
+    /// Present when the issue is a pull request
+    #[serde(default)]
+    pull_request: Option<serde_json::Value>,
+}
+
+impl Issue {
+    pub fn has_label(&self, name: &str) -> bool {
+        self.labels
+            .iter()
+            .any(|label| label.name.eq_ignore_ascii_case(name))
+    }
+}
+
+/// Parameters for creating an issue
+#[derive(Serialize, Debug, Clone)]
+pub struct NewIssue<'a> {
+    pub title: &'a str,
+    pub body: &'a str,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    pub labels: &'a [String],
+}
+
+#[derive(Serialize)]
+struct UpdateIssuePayload<'a> {
+    state: &'a str,
+}
+
+#[derive(Serialize)]
+struct AddLabelsPayload<'a> {
+    labels: &'a [String],
+}
+
+impl GitHubClient {
+    /// Post a comment on an issue or pull request
+    ///
+    /// Pull requests are issues in the GitHub API, so `number` may be either.
+    pub async fn create_issue_comment(
+        &self,
+        owner: &str,
+        repo: &str,
+        number: u64,
+        body: &str,
+    ) -> Result<IssueComment> {
+        self.require_token("create comment")?;
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/issues/{}/comments",
+            owner, repo, number
+        );
+        self.send_json(
+            self.client.post(&url).json(&CreateCommentPayload { body }),
+            "create comment",
+        )
+        .await
+    }
+
+    /// List up to 100 open issues carrying all of `labels`, most recently created first
+    ///
+    /// Pull requests are left out even though the API returns them as issues.
+    pub async fn list_open_issues(
+        &self,
+        owner: &str,
+        repo: &str,
+        labels: &[String],
+    ) -> Result<Vec<Issue>> {
+        let mut params = vec![
+            ("state", "open".to_string()),
+            ("per_page", "100".to_string()),
+        ];
+        if !labels.is_empty() {
+            params.push(("labels", labels.join(",")));
+        }
+        let url = reqwest::Url::parse_with_params(
+            &format!("https://api.github.com/repos/{}/{}/issues", owner, repo),
+            params,
+        )?;
+        let issues: Vec<Issue> = self.send_json(self.client.get(url), "list issues").await?;
+        Ok(issues
+            .into_iter()
+            .filter(|issue| issue.pull_request.is_none())
+            .collect())
+    }
+
+    /// Open an issue
+    pub async fn create_issue(
+        &self,
+        owner: &str,
+        repo: &str,
+        issue: &NewIssue<'_>,
+    ) -> Result<Issue> {
+        self.require_token("create issue")?;
+        let url = format!("https://api.github.com/repos/{}/{}/issues", owner, repo);
+        self.send_json(self.client.post(&url).json(issue), "create issue")
+            .await
+    }
+
+    /// Close an issue
+    pub async fn close_issue(&self, owner: &str, repo: &str, number: u64) -> Result<Issue> {
+        self.require_token("close issue")?;
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/issues/{}",
+            owner, repo, number
+        );
+        self.send_json(
+            self.client
+                .patch(&url)
+                .json(&UpdateIssuePayload { state: "closed" }),
+            "close issue",
+        )
+        .await
+    }
+
+    /// Add labels to an issue or pull request, keeping the ones it already has
+    pub async fn add_issue_labels(
+        &self,
+        owner: &str,
+        repo: &str,
+        number: u64,
+        labels: &[String],
+    ) -> Result<()> {
+        self.require_token("add labels")?;
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/issues/{}/labels",
+            owner, repo, number
+        );
+        self.send(
+            self.client.post(&url).json(&AddLabelsPayload { labels }),
+            "add labels",
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_issue_deserialization() {
+        let json = serde_json::json!([
+            {
+                "number": 7,
+                "title": "Rotate deploy keys",
+                "html_url": "https://github.com/acme/api/issues/7",
+                "state": "open",
+                "user": { "login": "alice" },
+                "labels": [{ "name": "Security" }]
+            },
+            {
+                "number": 8,
+                "title": "Bump serde",
+                "html_url": "https://github.com/acme/api/pull/8",
+                "state": "open",
+                "user": null,
+                "pull_request": { "url": "https://api.github.com/repos/acme/api/pulls/8" }
+            }
+        ]);
+
+        let issues: Vec<Issue> = serde_json::from_value(json).unwrap();
+        assert!(issues[0].has_label("security"));
+        assert!(issues[0].pull_request.is_none());
+        assert!(issues[1].pull_request.is_some());
+    }
+
+    #[test]
+    fn test_new_issue_omits_empty_labels() {
+        let issue = NewIssue {
+            title: "Title",
+            body: "Body",
+            labels: &[],
+        };
+        let json = serde_json::to_value(&issue).unwrap();
+        assert!(json.get("labels").is_none());
+    }
+}
@@ -5,18 +5,50 @@
 //!
 //! ## Modules
 //!
+//! - [`actions`]: GitHub Actions workflow runs
+//! - [`branch_protection`]: Branch protection rules
+//! - [`cache`]: On-disk cache of repository details and pull request lists
 //! - [`client`]: Core GitHub client implementation
+//! - [`commits`]: Commit statuses and check runs
+//! - [`git_data`]: Branches, trees and commits for changes without a local clone
+//! - [`issues`]: Issues, and comments on issues and pull requests
 //! - [`pull_requests`]: Pull request creation and management
-//! - [`repositories`]: Repository information retrieval
+//! - [`pull_requests_bulk`]: Pull requests of many repositories in batched GraphQL queries
+//! - [`releases`]: Releases and downloading their assets
+//! - [`repositories`]: Repository information, settings, labels and webhooks
+//! - [`teams`]: Team membership and the authenticated user
 //! - [`util`]: Utility functions for GitHub operations
 
+mod actions;
+mod branch_protection;
+pub mod cache;
 mod client;
+mod commits;
+mod git_data;
+mod issues;
 mod pull_requests;
+mod pull_requests_bulk;
+mod releases;
 mod repositories;
+mod teams;
 mod util;
 
 // Re-export public API
+pub use actions::WorkflowRun;
+pub use branch_protection::{BranchProtection, PushRestrictions};
+pub use cache::{ResponseCache, set_default_cache};
 pub use client::GitHubClient;
-pub use pull_requests::{PullRequest, PullRequestParams};
-pub use repositories::GitHubRepo;
+pub use commits::{CheckRun, CombinedStatus};
+pub use git_data::TreeFile;
+pub use issues::{Issue, IssueComment, NewIssue};
+pub use pull_requests::{
+    Label, MergeMethod, MergeQueueInfo, PullRequest, PullRequestDetails, PullRequestFile,
+    PullRequestHead, PullRequestParams, PullRequestReview, PullRequestSummary, ReviewUser,
+};
+pub use pull_requests_bulk::{PullRequestOverview, PullRequestRef};
+pub use releases::{NewRelease, Release, ReleaseAsset};
+pub use repositories::{
+    BranchActivity, CreatedRepository, GitHubBranch, GitHubRepo, NewRepository, NewWebhook,
+    RepositoryLabel, RepositorySettings, Webhook, WebhookConfig,
+};
 pub use util::parse_github_url;
@@ -3,6 +3,8 @@
 use crate::client::GitHubClient;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
 
 #[derive(Serialize)]
 pub(crate) struct CreatePullRequestPayload<'a> {
@@ -18,11 +20,195 @@ pub(crate) struct CreatePullRequestPayload<'a> {
 pub struct PullRequest {
     pub html_url: String,
     pub number: u64,
+    /// GraphQL node ID, needed for mutations such as enabling auto-merge
+    #[serde(default)]
+    pub node_id: String,
     pub id: u64,
     pub title: String,
     pub state: String,
 }
 
+/// How a pull request is merged
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MergeMethod {
+    #[default]
+    Merge,
+    Squash,
+    Rebase,
+}
+
+impl MergeMethod {
+    /// Name used by the REST API
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MergeMethod::Merge => "merge",
+            MergeMethod::Squash => "squash",
+            MergeMethod::Rebase => "rebase",
+        }
+    }
+
+    /// Name used by the GraphQL API
+    fn graphql_name(&self) -> &'static str {
+        match self {
+            MergeMethod::Merge => "MERGE",
+            MergeMethod::Squash => "SQUASH",
+            MergeMethod::Rebase => "REBASE",
+        }
+    }
+}
+
+impl fmt::Display for MergeMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for MergeMethod {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "merge" => Ok(MergeMethod::Merge),
+            "squash" => Ok(MergeMethod::Squash),
+            "rebase" => Ok(MergeMethod::Rebase),
+            _ => anyhow::bail!(
+                "Unknown merge method '{}' (expected merge, squash or rebase)",
+                s
+            ),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct RequestReviewersPayload<'a> {
+    #[serde(skip_serializing_if = "<[String]>::is_empty")]
+    reviewers: &'a [String],
+    #[serde(skip_serializing_if = "<[String]>::is_empty")]
+    team_reviewers: &'a [String],
+}
+
+#[derive(Serialize)]
+struct UpdateStatePayload {
+    state: &'static str,
+}
+
+#[derive(Serialize)]
+struct UpdatePullRequestPayload<'a> {
+    title: &'a str,
+    body: &'a str,
+}
+
+#[derive(Serialize)]
+struct MergePayload {
+    merge_method: &'static str,
+}
+
+#[derive(Deserialize)]
+struct MergeResponse {
+    sha: String,
+}
+
+/// Current state of an existing pull request
+#[derive(Deserialize, Debug, Clone)]
+pub struct PullRequestDetails {
+    pub number: u64,
+    pub html_url: String,
+    /// `open` or `closed`
+    pub state: String,
+    #[serde(default)]
+    pub merged: bool,
+    /// `None` while GitHub is still computing mergeability
+    pub mergeable: Option<bool>,
+    pub mergeable_state: Option<String>,
+    pub head: PullRequestHead,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct PullRequestHead {
+    #[serde(rename = "ref")]
+    pub branch: String,
+    pub sha: String,
+}
+
+/// An open pull request as returned when listing a repository's pull requests
+#[derive(Deserialize, Debug, Clone)]
+pub struct PullRequestSummary {
+    pub number: u64,
+    pub html_url: String,
+    pub title: String,
+    pub user: Option<ReviewUser>,
+    pub head: PullRequestHead,
+    #[serde(default)]
+    pub base: Option<PullRequestHead>,
+    #[serde(default)]
+    pub labels: Vec<Label>,
+    /// RFC 3339 timestamp of the last activity
+    #[serde(default)]
+    pub updated_at: String,
+}
+
+impl PullRequestSummary {
+    pub fn has_label(&self, name: &str) -> bool {
+        self.labels
+            .iter()
+            .any(|label| label.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Whether the pull request was opened by a GitHub App such as Dependabot
+    pub fn is_from_bot(&self) -> bool {
+        self.user
+            .as_ref()
+            .is_some_and(|user| user.login.ends_with("[bot]"))
+    }
+}
+
+/// A file changed by a pull request
+#[derive(Deserialize, Debug, Clone)]
+pub struct PullRequestFile {
+    pub filename: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Label {
+    pub name: String,
+}
+
+/// A submitted review on a pull request
+#[derive(Deserialize, Debug, Clone)]
+pub struct PullRequestReview {
+    pub user: Option<ReviewUser>,
+    /// `APPROVED`, `CHANGES_REQUESTED`, `COMMENTED`, `DISMISSED` or `PENDING`
+    pub state: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ReviewUser {
+    pub login: String,
+}
+
+/// Merge queue state of a pull request and its base branch
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MergeQueueInfo {
+    /// GraphQL node ID of the pull request
+    pub pull_request_id: String,
+    /// Whether the base branch requires a merge queue
+    pub enabled: bool,
+    /// Position in the queue (1-based), when the pull request is queued
+    pub position: Option<u64>,
+}
+
+impl MergeQueueInfo {
+    pub(crate) fn from_graphql(data: &serde_json::Value) -> Self {
+        let repository = &data["repository"];
+        let pull_request = &repository["pullRequest"];
+        Self {
+            pull_request_id: pull_request["id"].as_str().unwrap_or_default().to_string(),
+            enabled: !repository["mergeQueue"].is_null(),
+            position: pull_request["mergeQueueEntry"]["position"].as_u64(),
+        }
+    }
+}
+
 /// Parameters for creating a pull request
 #[derive(Debug, Clone)]
 pub struct PullRequestParams<'a> {
@@ -97,6 +283,7 @@ impl GitHubClient {
             request = request.header("Authorization", format!("token {}", token));
         }
 
+        log::debug!("POST {}", url);
         let response = request.json(&payload).send().await?;
 
         if !response.status().is_success() {
@@ -119,4 +306,317 @@ impl GitHubClient {
             .context("Failed to parse PR creation response")?;
         Ok(pr)
     }
+
+    /// Get the current state of a pull request
+    pub async fn get_pull_request(
+        &self,
+        owner: &str,
+        repo: &str,
+        number: u64,
+    ) -> Result<PullRequestDetails> {
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/pulls/{}",
+            owner, repo, number
+        );
+        self.send_json(self.client.get(&url), "get pull request")
+            .await
+    }
+
+    /// List open pull requests in a repository, most recently created first
+    pub async fn list_open_pull_requests(
+        &self,
+        owner: &str,
+        repo: &str,
+    ) -> Result<Vec<PullRequestSummary>> {
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/pulls?state=open&per_page=100",
+            owner, repo
+        );
+        self.get_json_cached(&url, "list pull requests").await
+    }
+
+    /// Find the open pull request from `head`, a branch of the repository itself, into `base`
+    pub async fn find_open_pull_request(
+        &self,
+        owner: &str,
+        repo: &str,
+        head: &str,
+        base: &str,
+    ) -> Result<Option<PullRequestSummary>> {
+        let url = reqwest::Url::parse_with_params(
+            &format!("https://api.github.com/repos/{}/{}/pulls", owner, repo),
+            [
+                ("state", "open".to_string()),
+                ("head", format!("{}:{}", owner, head)),
+                ("base", base.to_string()),
+            ],
+        )?;
+        let pull_requests: Vec<PullRequestSummary> = self
+            .send_json(self.client.get(url), "find pull request")
+            .await?;
+        Ok(pull_requests.into_iter().next())
+    }
+
+    /// Replace the title and body of a pull request
+    pub async fn update_pull_request(
+        &self,
+        owner: &str,
+        repo: &str,
+        number: u64,
+        title: &str,
+        body: &str,
+    ) -> Result<()> {
+        self.require_token("update pull request")?;
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/pulls/{}",
+            owner, repo, number
+        );
+        self.send(
+            self.client
+                .patch(&url)
+                .json(&UpdatePullRequestPayload { title, body }),
+            "update pull request",
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// List the files changed by a pull request
+    pub async fn list_pull_request_files(
+        &self,
+        owner: &str,
+        repo: &str,
+        number: u64,
+    ) -> Result<Vec<PullRequestFile>> {
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/pulls/{}/files?per_page=100",
+            owner, repo, number
+        );
+        self.send_json(self.client.get(&url), "list pull request files")
+            .await
+    }
+
+    /// Get the changes of a pull request as a unified diff
+    pub async fn get_pull_request_diff(
+        &self,
+        owner: &str,
+        repo: &str,
+        number: u64,
+    ) -> Result<String> {
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/pulls/{}",
+            owner, repo, number
+        );
+        let request = self
+            .client
+            .get(&url)
+            .header("Accept", "application/vnd.github.diff");
+        self.send(request, "get pull request diff")
+            .await?
+            .text()
+            .await
+            .context("Failed to read pull request diff")
+    }
+
+    /// List the reviews submitted on a pull request, oldest first
+    pub async fn list_pull_request_reviews(
+        &self,
+        owner: &str,
+        repo: &str,
+        number: u64,
+    ) -> Result<Vec<PullRequestReview>> {
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/pulls/{}/reviews?per_page=100",
+            owner, repo, number
+        );
+        self.send_json(self.client.get(&url), "list reviews").await
+    }
+
+    /// Merge a pull request and return the resulting commit SHA
+    pub async fn merge_pull_request(
+        &self,
+        owner: &str,
+        repo: &str,
+        number: u64,
+        method: MergeMethod,
+    ) -> Result<String> {
+        self.require_token("merge pull request")?;
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/pulls/{}/merge",
+            owner, repo, number
+        );
+        let payload = MergePayload {
+            merge_method: method.as_str(),
+        };
+        let response: MergeResponse = self
+            .send_json(self.client.put(&url).json(&payload), "merge pull request")
+            .await?;
+        Ok(response.sha)
+    }
+
+    /// Close a pull request without merging it
+    pub async fn close_pull_request(&self, owner: &str, repo: &str, number: u64) -> Result<()> {
+        self.require_token("close pull request")?;
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/pulls/{}",
+            owner, repo, number
+        );
+        self.send(
+            self.client
+                .patch(&url)
+                .json(&UpdateStatePayload { state: "closed" }),
+            "close pull request",
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Enable auto-merge so GitHub merges the pull request once requirements pass
+    ///
+    /// Requires auto-merge to be allowed in the repository settings.
+    pub async fn enable_auto_merge(&self, node_id: &str, method: MergeMethod) -> Result<()> {
+        let query = "mutation($id: ID!, $method: PullRequestMergeMethod!) { \
+            enablePullRequestAutoMerge(input: { pullRequestId: $id, mergeMethod: $method }) { \
+            clientMutationId } }";
+        self.graphql(
+            query,
+            serde_json::json!({ "id": node_id, "method": method.graphql_name() }),
+            "enable auto-merge",
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Request reviews from users and/or teams (team slugs without the org)
+    pub async fn request_reviewers(
+        &self,
+        owner: &str,
+        repo: &str,
+        number: u64,
+        reviewers: &[String],
+        team_reviewers: &[String],
+    ) -> Result<()> {
+        self.require_token("request reviewers")?;
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/pulls/{}/requested_reviewers",
+            owner, repo, number
+        );
+        let payload = RequestReviewersPayload {
+            reviewers,
+            team_reviewers,
+        };
+        let _: serde_json::Value = self
+            .send_json(self.client.post(&url).json(&payload), "request reviewers")
+            .await?;
+        Ok(())
+    }
+
+    /// Count open pull requests currently awaiting a review from `login`
+    pub async fn count_review_requests(&self, login: &str) -> Result<u64> {
+        let url = format!(
+            "https://api.github.com/search/issues?q=is:pr+is:open+review-requested:{}&per_page=1",
+            login
+        );
+        let result: serde_json::Value = self
+            .send_json(self.client.get(&url), "count review requests")
+            .await?;
+        Ok(result["total_count"].as_u64().unwrap_or(0))
+    }
+
+    /// Look up whether `base` uses a merge queue and where the pull request sits in it
+    pub async fn get_merge_queue_info(
+        &self,
+        owner: &str,
+        repo: &str,
+        number: u64,
+        base: &str,
+    ) -> Result<MergeQueueInfo> {
+        let query = "query($owner: String!, $repo: String!, $number: Int!, $base: String!) { \
+            repository(owner: $owner, name: $repo) { \
+            mergeQueue(branch: $base) { id } \
+            pullRequest(number: $number) { id mergeQueueEntry { position } } } }";
+        let data = self
+            .graphql(
+                query,
+                serde_json::json!({ "owner": owner, "repo": repo, "number": number, "base": base }),
+                "get merge queue",
+            )
+            .await?;
+
+        Ok(MergeQueueInfo::from_graphql(&data))
+    }
+
+    /// Add a pull request to its base branch's merge queue and return its position
+    pub async fn enqueue_pull_request(&self, node_id: &str) -> Result<Option<u64>> {
+        let query = "mutation($id: ID!) { \
+            enqueuePullRequest(input: { pullRequestId: $id }) { mergeQueueEntry { position } } }";
+        let data = self
+            .graphql(
+                query,
+                serde_json::json!({ "id": node_id }),
+                "add to merge queue",
+            )
+            .await?;
+        Ok(data["enqueuePullRequest"]["mergeQueueEntry"]["position"].as_u64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_method_parsing() {
+        for method in [MergeMethod::Merge, MergeMethod::Squash, MergeMethod::Rebase] {
+            assert_eq!(method.as_str().parse::<MergeMethod>().unwrap(), method);
+        }
+        assert_eq!(MergeMethod::Squash.graphql_name(), "SQUASH");
+        assert!("fast-forward".parse::<MergeMethod>().is_err());
+    }
+
+    #[test]
+    fn test_pull_request_summary_labels() {
+        let pr: PullRequestSummary = serde_json::from_value(serde_json::json!({
+            "number": 7,
+            "html_url": "https://github.com/org/api/pull/7",
+            "title": "Bump dependencies",
+            "user": { "login": "alice" },
+            "head": { "ref": "deps/bump", "sha": "abc123" },
+            "labels": [{ "name": "Dependencies" }]
+        }))
+        .unwrap();
+
+        assert_eq!(pr.head.branch, "deps/bump");
+        assert!(pr.has_label("dependencies"));
+        assert!(!pr.has_label("release"));
+        assert!(!pr.is_from_bot());
+    }
+
+    #[test]
+    fn test_merge_queue_info_from_graphql() {
+        let queued = serde_json::json!({
+            "repository": {
+                "mergeQueue": { "id": "MQ_1" },
+                "pullRequest": { "id": "PR_1", "mergeQueueEntry": { "position": 3 } }
+            }
+        });
+        assert_eq!(
+            MergeQueueInfo::from_graphql(&queued),
+            MergeQueueInfo {
+                pull_request_id: "PR_1".to_string(),
+                enabled: true,
+                position: Some(3),
+            }
+        );
+
+        let no_queue = serde_json::json!({
+            "repository": {
+                "mergeQueue": null,
+                "pullRequest": { "id": "PR_2", "mergeQueueEntry": null }
+            }
+        });
+        let info = MergeQueueInfo::from_graphql(&no_queue);
+        assert!(!info.enabled);
+        assert_eq!(info.position, None);
+    }
 }
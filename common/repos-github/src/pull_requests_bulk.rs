@@ -0,0 +1,321 @@
+//! Pull requests of many repositories in batched GraphQL queries
+//!
+//! Each query covers up to [`BATCH_SIZE`] repositories or pull requests, so
+//! reports over hundreds of repositories take a handful of requests instead of
+//! several REST calls per repository. A repository that cannot be read fails
+//! on its own without failing the rest of its batch.
+
+use crate::client::{GitHubClient, GraphqlError};
+use crate::pull_requests::{MergeQueueInfo, PullRequestReview, ReviewUser};
+use anyhow::{Result, anyhow};
+use serde_json::Value;
+
+/// Repositories or pull requests per query
+pub const BATCH_SIZE: usize = 20;
+
+/// Fields read of every pull request
+const PULL_REQUEST_FIELDS: &str = "fragment PullRequestOverview on PullRequest { \
+    id number title url isDraft state createdAt updatedAt headRefName baseRefName mergeable \
+    author { login } \
+    reviewRequests { totalCount } \
+    latestOpinionatedReviews(first: 50) { nodes { state author { login } } } \
+    commits(last: 1) { nodes { commit { statusCheckRollup { state } } } } }";
+
+/// A pull request with its reviews and the combined state of its checks
+#[derive(Debug, Clone)]
+pub struct PullRequestOverview {
+    /// GraphQL node ID
+    pub id: String,
+    pub number: u64,
+    pub title: String,
+    pub html_url: String,
+    pub author: Option<String>,
+    pub draft: bool,
+    /// `open`, `closed` or `merged`
+    pub state: String,
+    pub head_branch: String,
+    pub base_branch: String,
+    /// `None` while GitHub is still computing mergeability
+    pub mergeable: Option<bool>,
+    /// Reviews requested from users or teams that have not reviewed yet
+    pub review_requests: u64,
+    /// Latest approving or change-requesting review of each reviewer
+    pub reviews: Vec<PullRequestReview>,
+    /// Combined state of the checks and statuses of the head commit, e.g.
+    /// `SUCCESS`, `PENDING` or `FAILURE`; `None` without any
+    pub checks: Option<String>,
+    /// RFC 3339 timestamp of when the pull request was opened
+    pub created_at: String,
+    /// RFC 3339 timestamp of the last activity
+    pub updated_at: String,
+    /// Merge queue of the base branch; only looked up by [`GitHubClient::get_pull_requests`]
+    pub merge_queue: Option<MergeQueueInfo>,
+}
+
+impl PullRequestOverview {
+    fn from_graphql(node: &Value) -> Self {
+        let text = |value: &Value| value.as_str().unwrap_or_default().to_string();
+        Self {
+            id: text(&node["id"]),
+            number: node["number"].as_u64().unwrap_or_default(),
+            title: text(&node["title"]),
+            html_url: text(&node["url"]),
+            author: node["author"]["login"].as_str().map(str::to_string),
+            draft: node["isDraft"].as_bool().unwrap_or(false),
+            state: text(&node["state"]).to_lowercase(),
+            head_branch: text(&node["headRefName"]),
+            base_branch: text(&node["baseRefName"]),
+            mergeable: match node["mergeable"].as_str() {
+                Some("MERGEABLE") => Some(true),
+                Some("CONFLICTING") => Some(false),
+                _ => None,
+            },
+            review_requests: node["reviewRequests"]["totalCount"]
+                .as_u64()
+                .unwrap_or_default(),
+            reviews: node["latestOpinionatedReviews"]["nodes"]
+                .as_array()
+                .map(|reviews| {
+                    reviews
+                        .iter()
+                        .map(|review| PullRequestReview {
+                            user: review["author"]["login"].as_str().map(|login| ReviewUser {
+                                login: login.to_string(),
+                            }),
+                            state: text(&review["state"]),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+            checks: node["commits"]["nodes"][0]["commit"]["statusCheckRollup"]["state"]
+                .as_str()
+                .map(str::to_string),
+            created_at: text(&node["createdAt"]),
+            updated_at: text(&node["updatedAt"]),
+            merge_queue: None,
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.state == "open"
+    }
+}
+
+/// A pull request to look up, with the branch it merges into
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PullRequestRef {
+    pub owner: String,
+    pub repo: String,
+    pub number: u64,
+    pub base: String,
+}
+
+/// Error of the field aliased `alias`, if GraphQL reported one
+fn alias_error(errors: &[GraphqlError], alias: &str) -> Option<anyhow::Error> {
+    errors
+        .iter()
+        .find(|e| e.field.as_deref() == Some(alias))
+        .map(|e| anyhow!("{}", e.message))
+}
+
+impl GitHubClient {
+    /// Open pull requests of each repository, newest first, in the order given
+    ///
+    /// Up to 100 pull requests are returned per repository.
+    pub async fn list_open_pull_requests_bulk(
+        &self,
+        repositories: &[(String, String)],
+    ) -> Result<Vec<Result<Vec<PullRequestOverview>>>> {
+        let mut results = Vec::with_capacity(repositories.len());
+        for batch in repositories.chunks(BATCH_SIZE) {
+            let mut parameters = Vec::new();
+            let mut fields = Vec::new();
+            let mut variables = serde_json::Map::new();
+            for (i, (owner, repo)) in batch.iter().enumerate() {
+                parameters.push(format!("$o{i}: String!, $n{i}: String!"));
+                fields.push(format!(
+                    "r{i}: repository(owner: $o{i}, name: $n{i}) {{ \
+                     pullRequests(first: 100, states: OPEN, orderBy: {{ field: CREATED_AT, direction: DESC }}) {{ \
+                     nodes {{ ...PullRequestOverview }} }} }}"
+                ));
+                variables.insert(format!("o{i}"), owner.as_str().into());
+                variables.insert(format!("n{i}"), repo.as_str().into());
+            }
+            let query = format!(
+                "query({}) {{ {} }} {}",
+                parameters.join(", "),
+                fields.join(" "),
+                PULL_REQUEST_FIELDS
+            );
+
+            let (data, errors) = self
+                .graphql_partial(&query, variables.into(), "list pull requests")
+                .await?;
+            for (i, (owner, repo)) in batch.iter().enumerate() {
+                let alias = format!("r{i}");
+                let repository = &data[&alias];
+                results.push(if let Some(error) = alias_error(&errors, &alias) {
+                    Err(error)
+                } else if repository.is_null() {
+                    Err(anyhow!("Repository {}/{} not found", owner, repo))
+                } else {
+                    Ok(repository["pullRequests"]["nodes"]
+                        .as_array()
+                        .map(|nodes| {
+                            nodes
+                                .iter()
+                                .map(PullRequestOverview::from_graphql)
+                                .collect()
+                        })
+                        .unwrap_or_default())
+                });
+            }
+        }
+        Ok(results)
+    }
+
+    /// Current state of each pull request, with the merge queue of its base branch
+    ///
+    /// Merge queues do not exist on older GitHub Enterprise servers; there the
+    /// pull requests are looked up without them.
+    pub async fn get_pull_requests(
+        &self,
+        pull_requests: &[PullRequestRef],
+    ) -> Result<Vec<Result<PullRequestOverview>>> {
+        let mut results = Vec::with_capacity(pull_requests.len());
+        for batch in pull_requests.chunks(BATCH_SIZE) {
+            let (merge_queue, (data, errors)) = match self.query_pull_requests(batch, true).await {
+                Ok(response) => (true, response),
+                Err(e) => {
+                    log::debug!("Looking up pull requests without merge queues: {:#}", e);
+                    (false, self.query_pull_requests(batch, false).await?)
+                }
+            };
+            for (i, pr) in batch.iter().enumerate() {
+                let alias = format!("p{i}");
+                let repository = &data[&alias];
+                let node = &repository["pullRequest"];
+                results.push(if let Some(error) = alias_error(&errors, &alias) {
+                    Err(error)
+                } else if node.is_null() {
+                    Err(anyhow!(
+                        "Pull request {}/{}#{} not found",
+                        pr.owner,
+                        pr.repo,
+                        pr.number
+                    ))
+                } else {
+                    let mut overview = PullRequestOverview::from_graphql(node);
+                    if merge_queue {
+                        overview.merge_queue = Some(MergeQueueInfo::from_graphql(
+                            &serde_json::json!({ "repository": repository }),
+                        ));
+                    }
+                    Ok(overview)
+                });
+            }
+        }
+        Ok(results)
+    }
+
+    async fn query_pull_requests(
+        &self,
+        batch: &[PullRequestRef],
+        merge_queue: bool,
+    ) -> Result<(Value, Vec<GraphqlError>)> {
+        let mut parameters = Vec::new();
+        let mut fields = Vec::new();
+        let mut variables = serde_json::Map::new();
+        for (i, pr) in batch.iter().enumerate() {
+            parameters.push(format!("$o{i}: String!, $n{i}: String!, $p{i}: Int!"));
+            let (entry, queue) = if merge_queue {
+                parameters.push(format!("$b{i}: String!"));
+                variables.insert(format!("b{i}"), pr.base.as_str().into());
+                (
+                    "mergeQueueEntry { position }".to_string(),
+                    format!("mergeQueue(branch: $b{i}) {{ id }}"),
+                )
+            } else {
+                (String::new(), String::new())
+            };
+            fields.push(format!(
+                "p{i}: repository(owner: $o{i}, name: $n{i}) {{ \
+                 pullRequest(number: $p{i}) {{ ...PullRequestOverview {entry} }} {queue} }}"
+            ));
+            variables.insert(format!("o{i}"), pr.owner.as_str().into());
+            variables.insert(format!("n{i}"), pr.repo.as_str().into());
+            variables.insert(format!("p{i}"), pr.number.into());
+        }
+        let query = format!(
+            "query({}) {{ {} }} {}",
+            parameters.join(", "),
+            fields.join(" "),
+            PULL_REQUEST_FIELDS
+        );
+        self.graphql_partial(&query, variables.into(), "get pull requests")
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pull_request_overview_from_graphql() {
+        let node = serde_json::json!({
+            "id": "PR_1",
+            "number": 42,
+            "title": "Bump serde",
+            "url": "https://github.com/acme/api/pull/42",
+            "isDraft": false,
+            "state": "OPEN",
+            "createdAt": "2024-04-20T08:00:00Z",
+            "updatedAt": "2024-05-01T10:00:00Z",
+            "headRefName": "deps/serde",
+            "baseRefName": "main",
+            "mergeable": "CONFLICTING",
+            "author": { "login": "dependabot" },
+            "reviewRequests": { "totalCount": 2 },
+            "latestOpinionatedReviews": { "nodes": [
+                { "state": "APPROVED", "author": { "login": "alice" } },
+                { "state": "CHANGES_REQUESTED", "author": null }
+            ] },
+            "commits": { "nodes": [ { "commit": { "statusCheckRollup": { "state": "FAILURE" } } } ] }
+        });
+
+        let overview = PullRequestOverview::from_graphql(&node);
+        assert_eq!(overview.number, 42);
+        assert_eq!(overview.author.as_deref(), Some("dependabot"));
+        assert!(overview.is_open());
+        assert_eq!(overview.mergeable, Some(false));
+        assert_eq!(overview.review_requests, 2);
+        assert_eq!(overview.reviews.len(), 2);
+        assert_eq!(overview.reviews[0].user.as_ref().unwrap().login, "alice");
+        assert!(overview.reviews[1].user.is_none());
+        assert_eq!(overview.checks.as_deref(), Some("FAILURE"));
+
+        let without_checks = PullRequestOverview::from_graphql(&serde_json::json!({
+            "state": "MERGED",
+            "mergeable": "UNKNOWN",
+            "commits": { "nodes": [ { "commit": { "statusCheckRollup": null } } ] }
+        }));
+        assert_eq!(without_checks.state, "merged");
+        assert_eq!(without_checks.mergeable, None);
+        assert_eq!(without_checks.checks, None);
+    }
+
+    #[tokio::test]
+    async fn test_bulk_queries_require_token() {
+        let client = GitHubClient {
+            client: reqwest::Client::new(),
+            token: None,
+            cache: None,
+        };
+        let err = client
+            .list_open_pull_requests_bulk(&[("acme".to_string(), "api".to_string())])
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("token is required"));
+    }
+}
@@ -0,0 +1,85 @@
+//! Releases and their downloadable assets
+
+use crate::client::GitHubClient;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// A published release of a repository
+#[derive(Deserialize, Debug, Clone)]
+pub struct Release {
+    pub tag_name: String,
+    #[serde(default)]
+    pub html_url: String,
+    #[serde(default)]
+    pub assets: Vec<ReleaseAsset>,
+}
+
+/// A file attached to a release
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ReleaseAsset {
+    pub name: String,
+    pub browser_download_url: String,
+    #[serde(default)]
+    pub size: u64,
+}
+
+/// A release to create for an existing tag
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct NewRelease {
+    pub tag_name: String,
+    pub name: String,
+    pub body: String,
+    pub draft: bool,
+    pub prerelease: bool,
+}
+
+impl GitHubClient {
+    /// Latest published release, skipping drafts and prereleases
+    pub async fn get_latest_release(&self, owner: &str, repo: &str) -> Result<Release> {
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/releases/latest",
+            owner, repo
+        );
+        self.send_json(self.client.get(&url), "get latest release")
+            .await
+    }
+
+    /// Release published for a tag
+    pub async fn get_release_by_tag(&self, owner: &str, repo: &str, tag: &str) -> Result<Release> {
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/releases/tags/{}",
+            owner, repo, tag
+        );
+        self.send_json(self.client.get(&url), "get release").await
+    }
+
+    /// Publish a release for a tag that has already been pushed
+    pub async fn create_release(
+        &self,
+        owner: &str,
+        repo: &str,
+        release: &NewRelease,
+    ) -> Result<Release> {
+        self.require_token("create release")?;
+        let url = format!("https://api.github.com/repos/{}/{}/releases", owner, repo);
+        self.send_json(self.client.post(&url).json(release), "create release")
+            .await
+    }
+
+    /// Download the contents of a release asset
+    pub async fn download_asset(&self, asset: &ReleaseAsset) -> Result<Vec<u8>> {
+        let response = self
+            .send(
+                self.client
+                    .get(&asset.browser_download_url)
+                    .header("Accept", "application/octet-stream"),
+                "download release asset",
+            )
+            .await?;
+        let bytes = response
+            .bytes()
+            .await
+            .with_context(|| format!("Failed to download {}", asset.name))?;
+        Ok(bytes.to_vec())
+    }
+}
@@ -1,47 +1,459 @@
 //! Repository-related operations
 
-use crate::client::GitHubClient;
+use crate::client::{CachedResponse, GitHubClient};
 use anyhow::{Context, Result, anyhow};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct GitHubRepo {
+    /// `owner/name`, which differs from the requested one after a rename or transfer
+    #[serde(default)]
+    pub full_name: Option<String>,
+    #[serde(default)]
+    pub archived: bool,
+    #[serde(default)]
     pub topics: Vec<String>,
+    #[serde(default)]
+    pub default_branch: Option<String>,
+    /// Merge button settings, only reported to tokens with push access
+    #[serde(default)]
+    pub allow_merge_commit: Option<bool>,
+    #[serde(default)]
+    pub allow_squash_merge: Option<bool>,
+    #[serde(default)]
+    pub allow_rebase_merge: Option<bool>,
+    #[serde(default)]
+    pub allow_auto_merge: Option<bool>,
+    #[serde(default)]
+    pub delete_branch_on_merge: Option<bool>,
+}
+
+/// Settings of a repository to change, leaving out the ones to keep
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepositorySettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_branch: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_merge_commit: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_squash_merge: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_rebase_merge: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_auto_merge: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delete_branch_on_merge: Option<bool>,
+}
+
+impl RepositorySettings {
+    /// Whether no setting would change
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// A label issues and pull requests of a repository can carry
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RepositoryLabel {
+    pub name: String,
+    /// Hex color without the leading `#`
+    pub color: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// A webhook of a repository
+#[derive(Deserialize, Debug, Clone)]
+pub struct Webhook {
+    pub id: u64,
+    #[serde(default)]
+    pub events: Vec<String>,
+    #[serde(default)]
+    pub active: bool,
+    pub config: WebhookConfig,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct WebhookConfig {
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+/// Parameters for creating a webhook delivering JSON payloads
+#[derive(Debug, Clone)]
+pub struct NewWebhook {
+    pub url: String,
+    pub events: Vec<String>,
+    pub secret: Option<String>,
+}
+
+/// Parameters for creating a repository
+#[derive(Serialize, Debug, Clone)]
+pub struct NewRepository<'a> {
+    pub name: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<&'a str>,
+    pub private: bool,
+}
+
+/// A repository created through the API
+#[derive(Deserialize, Debug, Clone)]
+pub struct CreatedRepository {
+    pub html_url: String,
+    pub clone_url: String,
+    pub ssh_url: String,
+    #[serde(default)]
+    pub default_branch: Option<String>,
+}
+
+/// A branch of a repository
+#[derive(Deserialize, Debug, Clone)]
+pub struct GitHubBranch {
+    pub name: String,
+    /// Whether branch protection rules apply to the branch
+    #[serde(default)]
+    pub protected: bool,
+}
+
+/// A branch with what is needed to decide whether it is still in use
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchActivity {
+    pub name: String,
+    /// RFC 3339 timestamp of the head commit
+    pub last_commit_at: String,
+    pub is_default: bool,
+    /// Whether a branch protection rule applies to the branch
+    pub protected: bool,
+    /// Number of open pull requests from the branch
+    pub open_pull_requests: u64,
+}
+
+impl BranchActivity {
+    fn list_from_graphql(data: &serde_json::Value) -> Vec<Self> {
+        let repository = &data["repository"];
+        let default_branch = repository["defaultBranchRef"]["name"].as_str();
+
+        repository["refs"]["nodes"]
+            .as_array()
+            .map(|nodes| {
+                nodes
+                    .iter()
+                    .filter_map(|node| {
+                        let name = node["name"].as_str()?;
+                        Some(Self {
+                            name: name.to_string(),
+                            last_commit_at: node["target"]["committedDate"]
+                                .as_str()
+                                .unwrap_or_default()
+                                .to_string(),
+                            is_default: default_branch == Some(name),
+                            protected: !node["branchProtectionRule"].is_null(),
+                            open_pull_requests: node["associatedPullRequests"]["totalCount"]
+                                .as_u64()
+                                .unwrap_or(0),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
 }
 
 impl GitHubClient {
+    /// Details and topics of a repository, cached like pull request lists
     pub async fn get_repository_details(&self, owner: &str, repo: &str) -> Result<GitHubRepo> {
         let url = format!("https://api.github.com/repos/{}/{}", owner, repo);
+        let response = match self.get_cached(&url, "get repository").await? {
+            CachedResponse::Body(body) => body,
+            CachedResponse::Failed(response) => {
+                let status = response.status();
+                let error_msg = if status.as_u16() == 403 {
+                    if self.token.is_none() {
+                        "Access forbidden. This may be a private repository. Set GITHUB_TOKEN environment variable."
+                    } else {
+                        "Access forbidden. Check your GITHUB_TOKEN permissions or repository access."
+                    }
+                } else {
+                    status.canonical_reason().unwrap_or("Unknown error")
+                };
+                return Err(anyhow!(
+                    "Failed to connect ({} {})",
+                    status.as_u16(),
+                    error_msg
+                ));
+            }
+        };
+
+        let repo_data: GitHubRepo =
+            serde_json::from_str(&response).context("Failed to parse GitHub API response")?;
+        Ok(repo_data)
+    }
+
+    /// Details of a repository, or `None` when it does not exist or the token cannot see it
+    ///
+    /// Renamed and transferred repositories are followed to where they are now,
+    /// so their [`GitHubRepo::full_name`] differs from `owner/repo`.
+    pub async fn find_repository(&self, owner: &str, repo: &str) -> Result<Option<GitHubRepo>> {
+        let url = format!("https://api.github.com/repos/{}/{}", owner, repo);
+        match self.get_cached(&url, "get repository").await? {
+            CachedResponse::Body(body) => Ok(Some(
+                serde_json::from_str(&body).context("Failed to parse GitHub API response")?,
+            )),
+            CachedResponse::Failed(response) if response.status().as_u16() == 404 => Ok(None),
+            CachedResponse::Failed(response) => {
+                Err(Self::status_error(response, "get repository").await)
+            }
+        }
+    }
+
+    /// Get a branch, or `None` when it does not exist
+    pub async fn get_branch(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+    ) -> Result<Option<GitHubBranch>> {
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/branches/{}",
+            owner, repo, branch
+        );
         let mut request = self.client.get(&url).header("User-Agent", "repos-cli");
 
         if let Some(token) = &self.token {
             request = request.header("Authorization", format!("token {}", token));
         }
 
+        log::debug!("GET {}", url);
         let response = request.send().await?;
 
+        if response.status().as_u16() == 404 {
+            return Ok(None);
+        }
         if !response.status().is_success() {
             let status = response.status();
-            let error_msg = if status.as_u16() == 403 {
-                if self.token.is_none() {
-                    "Access forbidden. This may be a private repository. Set GITHUB_TOKEN environment variable."
-                } else {
-                    "Access forbidden. Check your GITHUB_TOKEN permissions or repository access."
-                }
-            } else {
-                status.canonical_reason().unwrap_or("Unknown error")
-            };
             return Err(anyhow!(
-                "Failed to connect ({} {})",
+                "Failed to get branch '{}' ({} {})",
+                branch,
                 status.as_u16(),
-                error_msg
+                status.canonical_reason().unwrap_or("Unknown error")
             ));
         }
 
-        let repo_data: GitHubRepo = response
+        let branch: GitHubBranch = response
             .json()
             .await
             .context("Failed to parse GitHub API response")?;
-        Ok(repo_data)
+        Ok(Some(branch))
+    }
+
+    /// List up to 100 branches with their last commit date, protection and open pull requests
+    pub async fn list_branch_activity(
+        &self,
+        owner: &str,
+        repo: &str,
+    ) -> Result<Vec<BranchActivity>> {
+        let query = "query($owner: String!, $repo: String!) { \
+            repository(owner: $owner, name: $repo) { \
+            defaultBranchRef { name } \
+            refs(refPrefix: \"refs/heads/\", first: 100) { nodes { \
+            name \
+            branchProtectionRule { id } \
+            associatedPullRequests(states: OPEN) { totalCount } \
+            target { ... on Commit { committedDate } } } } } }";
+        let data = self
+            .graphql(
+                query,
+                serde_json::json!({ "owner": owner, "repo": repo }),
+                "list branches",
+            )
+            .await?;
+
+        Ok(BranchActivity::list_from_graphql(&data))
+    }
+
+    /// Create an empty repository owned by `owner`
+    ///
+    /// `owner` may be an organization or the user the token belongs to.
+    pub async fn create_repository(
+        &self,
+        owner: &str,
+        repository: &NewRepository<'_>,
+    ) -> Result<CreatedRepository> {
+        self.require_token("create repository")?;
+        let url = if self
+            .get_authenticated_user()
+            .await?
+            .eq_ignore_ascii_case(owner)
+        {
+            "https://api.github.com/user/repos".to_string()
+        } else {
+            format!("https://api.github.com/orgs/{}/repos", owner)
+        };
+        self.send_json(self.client.post(&url).json(repository), "create repository")
+            .await
+    }
+
+    /// Set some of the settings of a repository
+    pub async fn update_repository(
+        &self,
+        owner: &str,
+        repo: &str,
+        settings: &RepositorySettings,
+    ) -> Result<()> {
+        self.require_token("update repository")?;
+        let url = format!("https://api.github.com/repos/{}/{}", owner, repo);
+        self.send(self.client.patch(&url).json(settings), "update repository")
+            .await?;
+        Ok(())
+    }
+
+    /// Replace all topics of a repository
+    pub async fn replace_topics(&self, owner: &str, repo: &str, topics: &[String]) -> Result<()> {
+        self.require_token("replace topics")?;
+        let url = format!("https://api.github.com/repos/{}/{}/topics", owner, repo);
+        self.send(
+            self.client
+                .put(&url)
+                .json(&serde_json::json!({ "names": topics })),
+            "replace topics",
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// List up to 100 labels of a repository
+    pub async fn list_labels(&self, owner: &str, repo: &str) -> Result<Vec<RepositoryLabel>> {
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/labels?per_page=100",
+            owner, repo
+        );
+        self.send_json(self.client.get(&url), "list labels").await
+    }
+
+    /// Create a label
+    pub async fn create_label(
+        &self,
+        owner: &str,
+        repo: &str,
+        label: &RepositoryLabel,
+    ) -> Result<()> {
+        self.require_token("create label")?;
+        let url = format!("https://api.github.com/repos/{}/{}/labels", owner, repo);
+        self.send(self.client.post(&url).json(label), "create label")
+            .await?;
+        Ok(())
+    }
+
+    /// Change the color and description of the label called `label.name`
+    pub async fn update_label(
+        &self,
+        owner: &str,
+        repo: &str,
+        label: &RepositoryLabel,
+    ) -> Result<()> {
+        self.require_token("update label")?;
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/labels/{}",
+            owner, repo, label.name
+        );
+        self.send(self.client.patch(&url).json(label), "update label")
+            .await?;
+        Ok(())
+    }
+
+    /// List the webhooks of a repository, which needs admin access
+    pub async fn list_webhooks(&self, owner: &str, repo: &str) -> Result<Vec<Webhook>> {
+        self.require_token("list webhooks")?;
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/hooks?per_page=100",
+            owner, repo
+        );
+        self.send_json(self.client.get(&url), "list webhooks").await
+    }
+
+    /// Create an active webhook
+    pub async fn create_webhook(
+        &self,
+        owner: &str,
+        repo: &str,
+        webhook: &NewWebhook,
+    ) -> Result<()> {
+        self.require_token("create webhook")?;
+        let url = format!("https://api.github.com/repos/{}/{}/hooks", owner, repo);
+        let mut config = serde_json::json!({
+            "url": webhook.url,
+            "content_type": "json",
+        });
+        if let Some(secret) = &webhook.secret {
+            config["secret"] = serde_json::json!(secret);
+        }
+        let body = serde_json::json!({
+            "name": "web",
+            "active": true,
+            "events": webhook.events,
+            "config": config,
+        });
+        self.send(self.client.post(&url).json(&body), "create webhook")
+            .await?;
+        Ok(())
+    }
+
+    /// Delete a branch
+    pub async fn delete_branch(&self, owner: &str, repo: &str, branch: &str) -> Result<()> {
+        self.require_token("delete branch")?;
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/git/refs/heads/{}",
+            owner, repo, branch
+        );
+        self.send(self.client.delete(&url), "delete branch").await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_branch_activity_from_graphql() {
+        let data = serde_json::json!({
+            "repository": {
+                "defaultBranchRef": { "name": "main" },
+                "refs": { "nodes": [
+                    {
+                        "name": "main",
+                        "branchProtectionRule": { "id": "BPR_1" },
+                        "associatedPullRequests": { "totalCount": 0 },
+                        "target": { "committedDate": "2024-05-01T10:00:00Z" }
+                    },
+                    {
+                        "name": "feature/old",
+                        "branchProtectionRule": null,
+                        "associatedPullRequests": { "totalCount": 1 },
+                        "target": { "committedDate": "2023-01-01T10:00:00Z" }
+                    }
+                ] }
+            }
+        });
+
+        assert_eq!(
+            BranchActivity::list_from_graphql(&data),
+            vec![
+                BranchActivity {
+                    name: "main".to_string(),
+                    last_commit_at: "2024-05-01T10:00:00Z".to_string(),
+                    is_default: true,
+                    protected: true,
+                    open_pull_requests: 0,
+                },
+                BranchActivity {
+                    name: "feature/old".to_string(),
+                    last_commit_at: "2023-01-01T10:00:00Z".to_string(),
+                    is_default: false,
+                    protected: false,
+                    open_pull_requests: 1,
+                },
+            ]
+        );
     }
 }
@@ -0,0 +1,42 @@
+//! Team and user operations
+
+use crate::client::GitHubClient;
+use anyhow::Result;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct User {
+    login: String,
+}
+
+#[derive(Deserialize)]
+struct TeamMember {
+    login: String,
+}
+
+impl GitHubClient {
+    /// Login of the user the token belongs to
+    pub async fn get_authenticated_user(&self) -> Result<String> {
+        self.require_token("get authenticated user")?;
+        let user: User = self
+            .send_json(
+                self.client.get("https://api.github.com/user"),
+                "get authenticated user",
+            )
+            .await?;
+        Ok(user.login)
+    }
+
+    /// Logins of the members of an organization team
+    pub async fn list_team_members(&self, org: &str, team_slug: &str) -> Result<Vec<String>> {
+        self.require_token("list team members")?;
+        let url = format!(
+            "https://api.github.com/orgs/{}/teams/{}/members?per_page=100",
+            org, team_slug
+        );
+        let members: Vec<TeamMember> = self
+            .send_json(self.client.get(&url), "list team members")
+            .await?;
+        Ok(members.into_iter().map(|m| m.login).collect())
+    }
+}
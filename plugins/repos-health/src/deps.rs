@@ -0,0 +1,719 @@
+//! Outdated dependency detection and updates for each supported ecosystem
+//!
+//! Ecosystems are picked by the manifest files found at the repository root.
+//! Each one lists its upgrades with the ecosystem's own tooling, and applies the
+//! upgrades the update policy allows.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Package ecosystem of a repository, detected from its manifest files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Ecosystem {
+    Npm,
+    Cargo,
+    Pip,
+    Poetry,
+    Maven,
+    Gradle,
+    Go,
+}
+
+impl fmt::Display for Ecosystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Ecosystem::Npm => "npm",
+            Ecosystem::Cargo => "cargo",
+            Ecosystem::Pip => "pip",
+            Ecosystem::Poetry => "poetry",
+            Ecosystem::Maven => "maven",
+            Ecosystem::Gradle => "gradle",
+            Ecosystem::Go => "go",
+        })
+    }
+}
+
+/// How far an upgrade moves a dependency
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateKind {
+    Patch,
+    Minor,
+    Major,
+}
+
+impl fmt::Display for UpdateKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            UpdateKind::Patch => "patch",
+            UpdateKind::Minor => "minor",
+            UpdateKind::Major => "major",
+        })
+    }
+}
+
+/// An available upgrade of one dependency
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Upgrade {
+    pub name: String,
+    pub current: String,
+    pub latest: String,
+    pub kind: UpdateKind,
+}
+
+impl Upgrade {
+    /// An upgrade, or `None` when `latest` is not newer than `current`
+    fn new(name: &str, current: &str, latest: &str) -> Option<Self> {
+        let kind = update_kind(current, latest)?;
+        Some(Self {
+            name: name.to_string(),
+            current: current.to_string(),
+            latest: latest.to_string(),
+            kind,
+        })
+    }
+}
+
+/// Upgrades found and applied for one ecosystem of a repository
+#[derive(Debug, Clone, Serialize)]
+pub struct EcosystemReport {
+    pub ecosystem: Ecosystem,
+    pub upgrades: Vec<Upgrade>,
+    /// Names of the dependencies that were updated
+    pub updated: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl Ecosystem {
+    /// Ecosystems whose manifests are present in a repository
+    pub fn detect(repo_path: &Path) -> Vec<Ecosystem> {
+        let has = |file: &str| repo_path.join(file).exists();
+        let mut ecosystems = Vec::new();
+        if has("package.json") {
+            ecosystems.push(Ecosystem::Npm);
+        }
+        if has("Cargo.toml") {
+            ecosystems.push(Ecosystem::Cargo);
+        }
+        let poetry = std::fs::read_to_string(repo_path.join("pyproject.toml"))
+            .is_ok_and(|content| content.contains("[tool.poetry"));
+        if poetry {
+            ecosystems.push(Ecosystem::Poetry);
+        } else if has("requirements.txt") {
+            ecosystems.push(Ecosystem::Pip);
+        }
+        if has("pom.xml") {
+            ecosystems.push(Ecosystem::Maven);
+        }
+        if has("build.gradle") || has("build.gradle.kts") {
+            ecosystems.push(Ecosystem::Gradle);
+        }
+        if has("go.mod") {
+            ecosystems.push(Ecosystem::Go);
+        }
+        ecosystems
+    }
+
+    /// Files an update of this ecosystem may change
+    pub fn files(&self) -> &'static [&'static str] {
+        match self {
+            Ecosystem::Npm => &[
+                "package.json",
+                "package-lock.json",
+                "yarn.lock",
+                "pnpm-lock.yaml",
+            ],
+            Ecosystem::Cargo => &["Cargo.toml", "Cargo.lock"],
+            Ecosystem::Pip => &["requirements.txt"],
+            Ecosystem::Poetry => &["pyproject.toml", "poetry.lock"],
+            Ecosystem::Maven => &["pom.xml"],
+            Ecosystem::Gradle => &["build.gradle", "build.gradle.kts"],
+            Ecosystem::Go => &["go.mod", "go.sum"],
+        }
+    }
+
+    /// List the available upgrades of the repository's direct dependencies
+    pub fn outdated(&self, repo_path: &Path) -> Result<Vec<Upgrade>> {
+        match self {
+            Ecosystem::Npm => {
+                // npm outdated exits with 1 when there are outdated packages
+                let output = run(repo_path, "npm", &["outdated", "--json"], &[0, 1])?;
+                parse_npm_outdated(&output)
+            }
+            Ecosystem::Cargo => {
+                let output = run(
+                    repo_path,
+                    "cargo",
+                    &["outdated", "--root-deps-only", "--format", "json"],
+                    &[0],
+                )?;
+                parse_cargo_outdated(&output)
+            }
+            Ecosystem::Pip => {
+                let output = run(
+                    repo_path,
+                    "pip",
+                    &["list", "--outdated", "--format", "json"],
+                    &[0],
+                )?;
+                let requirements = std::fs::read_to_string(repo_path.join("requirements.txt"))?;
+                parse_pip_outdated(&output, &requirements)
+            }
+            Ecosystem::Poetry => {
+                let output = run(
+                    repo_path,
+                    "poetry",
+                    &["show", "--outdated", "--top-level"],
+                    &[0],
+                )?;
+                Ok(parse_poetry_outdated(&output))
+            }
+            Ecosystem::Maven => {
+                let output = run(
+                    repo_path,
+                    "mvn",
+                    &["-B", "versions:display-dependency-updates"],
+                    &[0],
+                )?;
+                Ok(parse_maven_updates(&output))
+            }
+            Ecosystem::Gradle => {
+                let gradle = if repo_path.join("gradlew").exists() {
+                    "./gradlew"
+                } else {
+                    "gradle"
+                };
+                let output = run(repo_path, gradle, &["-q", "dependencyUpdates"], &[0])?;
+                Ok(parse_gradle_updates(&output))
+            }
+            Ecosystem::Go => {
+                let output = run(repo_path, "go", &["list", "-m", "-u", "-json", "all"], &[0])?;
+                parse_go_updates(&output)
+            }
+        }
+    }
+
+    /// Apply upgrades, returning the names of the dependencies updated
+    pub fn update(&self, repo_path: &Path, upgrades: &[&Upgrade]) -> Result<Vec<String>> {
+        if upgrades.is_empty() {
+            return Ok(Vec::new());
+        }
+        match self {
+            Ecosystem::Npm => {
+                let mut args = vec!["install".to_string()];
+                args.extend(upgrades.iter().map(|u| format!("{}@{}", u.name, u.latest)));
+                run_owned(repo_path, "npm", &args)?;
+            }
+            Ecosystem::Cargo => {
+                for upgrade in upgrades {
+                    // Compatible versions only need the lockfile, others the manifest
+                    if upgrade.kind == UpdateKind::Major {
+                        let package = format!("{}@{}", upgrade.name, upgrade.latest);
+                        run(
+                            repo_path,
+                            "cargo",
+                            &["upgrade", "--incompatible", "-p", &package],
+                            &[0],
+                        )?;
+                    } else {
+                        run(
+                            repo_path,
+                            "cargo",
+                            &["update", "-p", &upgrade.name, "--precise", &upgrade.latest],
+                            &[0],
+                        )?;
+                    }
+                }
+            }
+            Ecosystem::Pip => {
+                let path = repo_path.join("requirements.txt");
+                let content = std::fs::read_to_string(&path)?;
+                let (rewritten, updated) = rewrite_requirements(&content, upgrades);
+                std::fs::write(&path, rewritten)?;
+                return Ok(updated);
+            }
+            Ecosystem::Poetry => {
+                let mut args = vec!["add".to_string()];
+                args.extend(upgrades.iter().map(|u| format!("{}@^{}", u.name, u.latest)));
+                run_owned(repo_path, "poetry", &args)?;
+            }
+            Ecosystem::Maven => {
+                for upgrade in upgrades {
+                    run_owned(
+                        repo_path,
+                        "mvn",
+                        &[
+                            "-B".to_string(),
+                            "versions:use-dep-version".to_string(),
+                            format!("-Dincludes={}", upgrade.name),
+                            format!("-DdepVersion={}", upgrade.latest),
+                            "-DforceVersion=true".to_string(),
+                            "-DgenerateBackupPoms=false".to_string(),
+                        ],
+                    )?;
+                }
+            }
+            Ecosystem::Gradle => {
+                anyhow::bail!("Gradle builds are reported only; update the build files by hand")
+            }
+            Ecosystem::Go => {
+                let mut args = vec!["get".to_string()];
+                args.extend(upgrades.iter().map(|u| format!("{}@{}", u.name, u.latest)));
+                run_owned(repo_path, "go", &args)?;
+                run(repo_path, "go", &["mod", "tidy"], &[0])?;
+            }
+        }
+        Ok(upgrades.iter().map(|u| u.name.clone()).collect())
+    }
+
+    /// Check a repository for upgrades and apply those the policy allows
+    pub fn check(&self, repo_path: &Path, policy: UpdateKind) -> EcosystemReport {
+        let mut report = EcosystemReport {
+            ecosystem: *self,
+            upgrades: Vec::new(),
+            updated: Vec::new(),
+            error: None,
+        };
+        match self.outdated(repo_path) {
+            Ok(upgrades) => report.upgrades = upgrades,
+            Err(e) => {
+                report.error = Some(e.to_string());
+                return report;
+            }
+        }
+
+        let allowed: Vec<&Upgrade> = report
+            .upgrades
+            .iter()
+            .filter(|upgrade| upgrade.kind <= policy)
+            .collect();
+        match self.update(repo_path, &allowed) {
+            Ok(updated) => report.updated = updated,
+            Err(e) => report.error = Some(e.to_string()),
+        }
+        report
+    }
+}
+
+fn run(repo_path: &Path, program: &str, args: &[&str], ok_codes: &[i32]) -> Result<String> {
+    let output = Command::new(program)
+        .args(args)
+        .current_dir(repo_path)
+        .stdin(Stdio::null())
+        .output()
+        .with_context(|| format!("{} is not installed", program))?;
+    if !output
+        .status
+        .code()
+        .is_some_and(|code| ok_codes.contains(&code))
+    {
+        anyhow::bail!(
+            "{} {} failed: {}",
+            program,
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn run_owned(repo_path: &Path, program: &str, args: &[String]) -> Result<String> {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    run(repo_path, program, &args, &[0])
+}
+
+/// Numeric components of a version such as `v1.2.3`, `^1.2` or `30.0-jre`
+fn version_parts(version: &str) -> Option<Vec<u64>> {
+    let version = version.trim_start_matches(['v', '^', '~', '=']);
+    let parts: Vec<u64> = version
+        .split('.')
+        .take(3)
+        .map_while(|part| {
+            let digits: String = part.chars().take_while(char::is_ascii_digit).collect();
+            digits.parse().ok()
+        })
+        .collect();
+    if parts.is_empty() { None } else { Some(parts) }
+}
+
+/// How far moving from `current` to `latest` goes, or `None` when it is not newer
+///
+/// As in Cargo, a change to the leftmost non-zero component is breaking, so
+/// `0.3` to `0.4` is a major upgrade. Versions that cannot be compared count as
+/// major.
+pub fn update_kind(current: &str, latest: &str) -> Option<UpdateKind> {
+    if current == latest {
+        return None;
+    }
+    let (Some(mut current), Some(mut latest)) = (version_parts(current), version_parts(latest))
+    else {
+        return Some(UpdateKind::Major);
+    };
+    current.resize(3, 0);
+    latest.resize(3, 0);
+
+    let changed = (0..3).find(|&i| current[i] != latest[i])?;
+    if latest[changed] < current[changed] {
+        return None;
+    }
+    let breaking = current.iter().position(|&part| part != 0).unwrap_or(2);
+    Some(if changed <= breaking {
+        UpdateKind::Major
+    } else if changed == 1 {
+        UpdateKind::Minor
+    } else {
+        UpdateKind::Patch
+    })
+}
+
+fn parse_npm_outdated(output: &str) -> Result<Vec<Upgrade>> {
+    #[derive(Deserialize)]
+    struct Entry {
+        current: Option<String>,
+        latest: Option<String>,
+    }
+
+    if output.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let entries: std::collections::BTreeMap<String, Entry> =
+        serde_json::from_str(output).context("Failed to parse npm outdated output")?;
+    Ok(entries
+        .iter()
+        .filter_map(|(name, entry)| {
+            Upgrade::new(name, entry.current.as_deref()?, entry.latest.as_deref()?)
+        })
+        .collect())
+}
+
+fn parse_cargo_outdated(output: &str) -> Result<Vec<Upgrade>> {
+    #[derive(Deserialize)]
+    struct Report {
+        dependencies: Vec<Entry>,
+    }
+    #[derive(Deserialize)]
+    struct Entry {
+        name: String,
+        project: String,
+        latest: String,
+    }
+
+    let report: Report =
+        serde_json::from_str(output).context("Failed to parse cargo outdated output")?;
+    Ok(report
+        .dependencies
+        .iter()
+        // `---` and `Removed` mark dependencies without a newer release
+        .filter(|entry| version_parts(&entry.latest).is_some())
+        .filter_map(|entry| Upgrade::new(&entry.name, &entry.project, &entry.latest))
+        .collect())
+}
+
+/// Name of a Python package as pip compares them
+fn normalize_python_name(name: &str) -> String {
+    name.to_lowercase().replace(['_', '.'], "-")
+}
+
+/// Package name of a requirements line such as `requests[socks]==2.25.0`
+fn requirement_name(line: &str) -> Option<&str> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') || line.starts_with('-') {
+        return None;
+    }
+    let end = line
+        .find(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_' || c == '.'))
+        .unwrap_or(line.len());
+    Some(&line[..end]).filter(|name| !name.is_empty())
+}
+
+fn parse_pip_outdated(output: &str, requirements: &str) -> Result<Vec<Upgrade>> {
+    #[derive(Deserialize)]
+    struct Entry {
+        name: String,
+        version: String,
+        latest_version: String,
+    }
+
+    let entries: Vec<Entry> =
+        serde_json::from_str(output).context("Failed to parse pip list output")?;
+    // pip reports the whole environment, so only keep the repository's requirements
+    let required: Vec<String> = requirements
+        .lines()
+        .filter_map(requirement_name)
+        .map(normalize_python_name)
+        .collect();
+    Ok(entries
+        .iter()
+        .filter(|entry| required.contains(&normalize_python_name(&entry.name)))
+        .filter_map(|entry| Upgrade::new(&entry.name, &entry.version, &entry.latest_version))
+        .collect())
+}
+
+/// Update the `==` pins of a requirements file, returning it with the names of the packages changed
+fn rewrite_requirements(content: &str, upgrades: &[&Upgrade]) -> (String, Vec<String>) {
+    let mut updated = Vec::new();
+    let lines: Vec<String> = content
+        .lines()
+        .map(|line| {
+            let upgrade = requirement_name(line).and_then(|name| {
+                upgrades
+                    .iter()
+                    .find(|u| normalize_python_name(&u.name) == normalize_python_name(name))
+            });
+            match (upgrade, line.split_once("==")) {
+                (Some(upgrade), Some((requirement, pin))) => {
+                    // Keep environment markers and comments after the version
+                    let rest = pin
+                        .find(|c: char| c.is_whitespace() || c == ';')
+                        .map(|end| &pin[end..])
+                        .unwrap_or("");
+                    updated.push(upgrade.name.clone());
+                    format!("{}=={}{}", requirement, upgrade.latest, rest)
+                }
+                _ => line.to_string(),
+            }
+        })
+        .collect();
+
+    let mut rewritten = lines.join("\n");
+    if content.ends_with('\n') {
+        rewritten.push('\n');
+    }
+    (rewritten, updated)
+}
+
+fn parse_poetry_outdated(output: &str) -> Vec<Upgrade> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let mut name = fields.next()?;
+            // Packages that are not installed are marked with `(!)`
+            if name == "(!)" {
+                name = fields.next()?;
+            }
+            let current = fields.next()?;
+            let current = if current == "(!)" {
+                fields.next()?
+            } else {
+                current
+            };
+            let latest = fields.next()?;
+            Upgrade::new(name, current, latest)
+        })
+        .collect()
+}
+
+/// Parse `mvn versions:display-dependency-updates`, whose long names wrap onto a second line
+fn parse_maven_updates(output: &str) -> Vec<Upgrade> {
+    let mut upgrades = Vec::new();
+    let mut pending: Option<&str> = None;
+    for line in output.lines() {
+        let tokens: Vec<&str> = line
+            .trim_start_matches("[INFO]")
+            .split_whitespace()
+            .collect();
+        let name = tokens.first().filter(|token| token.contains(':')).copied();
+        match tokens.iter().position(|token| *token == "->") {
+            Some(arrow) if arrow >= 1 && arrow + 1 < tokens.len() => {
+                if let Some(name) = name.or(pending.take())
+                    && let Some(upgrade) = Upgrade::new(name, tokens[arrow - 1], tokens[arrow + 1])
+                {
+                    upgrades.push(upgrade);
+                }
+            }
+            _ => pending = name,
+        }
+    }
+    upgrades
+}
+
+/// Parse the text report of the Gradle Versions Plugin's `dependencyUpdates` task
+fn parse_gradle_updates(output: &str) -> Vec<Upgrade> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim().strip_prefix("- ")?;
+            let (name, versions) = line.split_once(" [")?;
+            let (current, latest) = versions.trim_end_matches(']').split_once(" -> ")?;
+            Upgrade::new(name.trim(), current.trim(), latest.trim())
+        })
+        .collect()
+}
+
+fn parse_go_updates(output: &str) -> Result<Vec<Upgrade>> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Module {
+        path: String,
+        #[serde(default)]
+        version: Option<String>,
+        #[serde(default)]
+        main: bool,
+        #[serde(default)]
+        indirect: bool,
+        #[serde(default)]
+        update: Option<ModuleUpdate>,
+    }
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct ModuleUpdate {
+        version: String,
+    }
+
+    let mut upgrades = Vec::new();
+    for module in serde_json::Deserializer::from_str(output).into_iter::<Module>() {
+        let module = module.context("Failed to parse go list output")?;
+        if module.main || module.indirect {
+            continue;
+        }
+        if let (Some(version), Some(update)) = (&module.version, &module.update)
+            && let Some(upgrade) = Upgrade::new(&module.path, version, &update.version)
+        {
+            upgrades.push(upgrade);
+        }
+    }
+    Ok(upgrades)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn upgrade(name: &str, current: &str, latest: &str) -> Upgrade {
+        Upgrade::new(name, current, latest).unwrap()
+    }
+
+    #[test]
+    fn test_update_kind() {
+        assert_eq!(update_kind("1.2.3", "1.2.4"), Some(UpdateKind::Patch));
+        assert_eq!(update_kind("1.2.3", "1.3.0"), Some(UpdateKind::Minor));
+        assert_eq!(update_kind("1.2.3", "2.0.0"), Some(UpdateKind::Major));
+        assert_eq!(update_kind("v0.3.1", "v0.4.0"), Some(UpdateKind::Major));
+        assert_eq!(update_kind("0.3.1", "0.3.2"), Some(UpdateKind::Patch));
+        assert_eq!(
+            update_kind("30.0-jre", "32.1.2-jre"),
+            Some(UpdateKind::Major)
+        );
+        assert_eq!(update_kind("1.2.3", "1.2.3"), None);
+        assert_eq!(update_kind("1.3.0", "1.2.9"), None);
+    }
+
+    #[test]
+    fn test_detect() {
+        let temp_dir = TempDir::new().unwrap();
+        for file in ["package.json", "Cargo.toml", "requirements.txt", "go.mod"] {
+            std::fs::write(temp_dir.path().join(file), "").unwrap();
+        }
+        assert_eq!(
+            Ecosystem::detect(temp_dir.path()),
+            vec![
+                Ecosystem::Npm,
+                Ecosystem::Cargo,
+                Ecosystem::Pip,
+                Ecosystem::Go
+            ]
+        );
+
+        // Poetry projects are not also handled by pip
+        std::fs::write(
+            temp_dir.path().join("pyproject.toml"),
+            "[tool.poetry]\nname = \"api\"\n",
+        )
+        .unwrap();
+        assert!(Ecosystem::detect(temp_dir.path()).contains(&Ecosystem::Poetry));
+        assert!(!Ecosystem::detect(temp_dir.path()).contains(&Ecosystem::Pip));
+    }
+
+    #[test]
+    fn test_parse_npm_and_cargo() {
+        let npm = r#"{"lodash": {"current": "4.17.20", "wanted": "4.17.21", "latest": "4.17.21"},
+                      "react": {"wanted": "18.2.0", "latest": "18.2.0"}}"#;
+        assert_eq!(
+            parse_npm_outdated(npm).unwrap(),
+            vec![upgrade("lodash", "4.17.20", "4.17.21")]
+        );
+        assert!(parse_npm_outdated("").unwrap().is_empty());
+
+        let cargo = r#"{"crate_name": "api", "dependencies": [
+            {"name": "clap", "project": "3.2.0", "compat": "3.2.25", "latest": "4.5.0", "kind": "Normal"},
+            {"name": "old", "project": "1.0.0", "compat": "---", "latest": "Removed", "kind": "Normal"}]}"#;
+        let upgrades = parse_cargo_outdated(cargo).unwrap();
+        assert_eq!(upgrades, vec![upgrade("clap", "3.2.0", "4.5.0")]);
+        assert_eq!(upgrades[0].kind, UpdateKind::Major);
+    }
+
+    #[test]
+    fn test_parse_pip_and_rewrite_requirements() {
+        let requirements =
+            "# web\nrequests[socks]==2.25.0 ; python_version > \"3.8\"\nDjango>=4.0\n";
+        let pip = r#"[{"name": "requests", "version": "2.25.0", "latest_version": "2.31.0"},
+                      {"name": "django", "version": "4.1.0", "latest_version": "4.2.0"},
+                      {"name": "pip", "version": "23.0", "latest_version": "24.0"}]"#;
+        let upgrades = parse_pip_outdated(pip, requirements).unwrap();
+        assert_eq!(upgrades.len(), 2);
+
+        let selected: Vec<&Upgrade> = upgrades.iter().collect();
+        let (rewritten, updated) = rewrite_requirements(requirements, &selected);
+        assert_eq!(
+            rewritten,
+            "# web\nrequests[socks]==2.31.0 ; python_version > \"3.8\"\nDjango>=4.0\n"
+        );
+        // Ranges already allow the new version
+        assert_eq!(updated, vec!["requests"]);
+    }
+
+    #[test]
+    fn test_parse_text_reports() {
+        let poetry = "requests   2.25.0 2.31.0 Python HTTP for Humans.\n(!) black 22.1.0 24.1.0 The uncompromising code formatter.\n";
+        assert_eq!(
+            parse_poetry_outdated(poetry),
+            vec![
+                upgrade("requests", "2.25.0", "2.31.0"),
+                upgrade("black", "22.1.0", "24.1.0")
+            ]
+        );
+
+        let maven = "\
+[INFO] The following dependencies in Dependencies have newer versions:
+[INFO]   org.slf4j:slf4j-api ............................ 1.7.30 -> 2.0.9
+[INFO]   com.fasterxml.jackson.core:jackson-databind ...
+[INFO]                                                  2.12.0 -> 2.12.7
+";
+        assert_eq!(
+            parse_maven_updates(maven),
+            vec![
+                upgrade("org.slf4j:slf4j-api", "1.7.30", "2.0.9"),
+                upgrade(
+                    "com.fasterxml.jackson.core:jackson-databind",
+                    "2.12.0",
+                    "2.12.7"
+                )
+            ]
+        );
+
+        let gradle = "The following dependencies have later milestone versions:\n - com.google.guava:guava [30.0-jre -> 32.1.2-jre]\n     https://github.com/google/guava\n";
+        assert_eq!(
+            parse_gradle_updates(gradle),
+            vec![upgrade("com.google.guava:guava", "30.0-jre", "32.1.2-jre")]
+        );
+    }
+
+    #[test]
+    fn test_parse_go_updates() {
+        let output = r#"{"Path": "example.com/api", "Main": true}
+{"Path": "github.com/spf13/cobra", "Version": "v1.7.0", "Update": {"Path": "github.com/spf13/cobra", "Version": "v1.8.0"}}
+{"Path": "golang.org/x/sys", "Version": "v0.1.0", "Indirect": true, "Update": {"Version": "v0.15.0"}}
+{"Path": "github.com/google/uuid", "Version": "v1.4.0"}"#;
+        assert_eq!(
+            parse_go_updates(output).unwrap(),
+            vec![upgrade("github.com/spf13/cobra", "v1.7.0", "v1.8.0")]
+        );
+    }
+}
@@ -1,34 +1,28 @@
+mod deps;
+mod report;
+mod score;
+
 use anyhow::{Context, Result};
+use deps::{Ecosystem, EcosystemReport, UpdateKind};
+use report::{DepsDocument, OutputFormat, PrsDocument, RepoError};
 use repos::Repository;
-use serde::{Deserialize, Serialize};
+use repos::credentials::Credentials;
+use repos_github::GitHubClient;
+use score::{ScoreDocument, ScoreSettings};
+use serde::Serialize;
 use std::env;
 use std::path::Path;
-use std::process::{Command, Stdio};
-
-#[derive(Debug, Serialize, Deserialize)]
-struct PrUser {
-    login: String,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-struct PullRequest {
-    number: u64,
-    title: String,
-    html_url: String,
-    user: PrUser,
-    draft: bool,
-    #[serde(default)]
-    requested_reviewers: Vec<PrUser>,
-}
+use std::process::Command;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 struct PrReport {
+    #[serde(rename = "repository")]
     repo_name: String,
     total_prs: usize,
     awaiting_approval: Vec<PrSummary>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 struct PrSummary {
     number: u64,
     title: String,
@@ -36,6 +30,15 @@ struct PrSummary {
     url: String,
 }
 
+/// Dependency findings for one repository
+#[derive(Debug, Serialize)]
+struct RepoDeps {
+    repository: String,
+    ecosystems: Vec<EcosystemReport>,
+    /// Whether updates left manifest or lockfile changes to commit
+    changed: bool,
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     let args: Vec<String> = env::args().collect();
@@ -45,23 +48,64 @@ async fn main() -> Result<()> {
         .context("Failed to load plugin context")?
         .ok_or_else(|| anyhow::anyhow!("Plugin must be invoked via repos CLI"))?;
 
-    // Parse mode from arguments
+    // Parse mode and options from arguments
     let mut mode = "deps"; // default mode
-    for arg in &args[1..] {
-        if arg == "deps" || arg == "prs" {
-            mode = arg;
-            break;
-        } else if arg == "--help" || arg == "-h" {
-            print_help();
-            return Ok(());
+    let mut policy = UpdateKind::Minor;
+    let mut format = OutputFormat::Text;
+    let mut settings = ScoreSettings::default();
+    let mut args_iter = args[1..].iter();
+    while let Some(arg) = args_iter.next() {
+        let mut value = || {
+            args_iter
+                .next()
+                .ok_or_else(|| anyhow::anyhow!("{} requires a value", arg))
+        };
+        match arg.as_str() {
+            "deps" | "prs" | "score" => mode = arg,
+            "--weight" => settings.set_weight(value()?)?,
+            "--threshold" => {
+                settings.threshold = value()?
+                    .parse()
+                    .context("--threshold must be a number from 0 to 100")?
+            }
+            "--max-commit-age" => {
+                settings.max_commit_age_days = value()?
+                    .parse()
+                    .context("--max-commit-age must be a number of days")?
+            }
+            "--max-pr-age" => {
+                settings.max_pr_age_days = value()?
+                    .parse()
+                    .context("--max-pr-age must be a number of days")?
+            }
+            "--major" => policy = UpdateKind::Major,
+            "--minor" => policy = UpdateKind::Minor,
+            "--patch" => policy = UpdateKind::Patch,
+            "--json" | "--markdown" => {
+                let requested = if arg == "--json" {
+                    OutputFormat::Json
+                } else {
+                    OutputFormat::Markdown
+                };
+                if format != OutputFormat::Text && format != requested {
+                    anyhow::bail!("--json and --markdown cannot be used together");
+                }
+                format = requested;
+            }
+            "--help" | "-h" => {
+                print_help();
+                return Ok(());
+            }
+            _ => {}
         }
     }
 
     match mode {
-        "deps" => run_deps_check(repos).await,
-        "prs" => run_pr_report(repos).await,
+        "deps" => run_deps_check(repos, policy, format).await,
+        "prs" => run_pr_report(repos, format).await,
+        "score" => run_score(repos, &settings, format).await,
         _ => {
-            eprintln!("Unknown mode: {}. Use 'deps' or 'prs'", mode);
+            eprintln!("Unknown mode: {}. Use 'deps', 'prs' or 'score'", mode);
             print_help();
             std::process::exit(1);
         }
@@ -72,19 +116,27 @@ fn print_help() {
     println!("repos-health - Repository health checks and reports");
     println!();
     println!("USAGE:");
-    println!("    repos health [MODE]");
+    println!("    repos health [MODE] [OPTIONS]");
     println!();
     println!("MODES:");
-    println!("    deps    Check and update npm dependencies (default)");
+    println!("    deps    Check and update dependencies (default)");
     println!("    prs     Generate PR report showing PRs awaiting approval");
+    println!("    score   Rank repositories by a composite health score");
     println!();
     println!("DEPS MODE:");
-    println!("    Scans repositories for outdated npm packages and automatically");
-    println!("    updates them locally.");
+    println!("    Scans repositories for outdated dependencies and automatically");
+    println!("    updates them locally. Ecosystems are picked by manifest file:");
+    println!("    - package.json: npm (npm outdated / npm install)");
+    println!("    - Cargo.toml: Cargo (cargo outdated / cargo update, cargo upgrade)");
+    println!("    - requirements.txt: pip (pip list --outdated, pins rewritten)");
+    println!("    - pyproject.toml with [tool.poetry]: Poetry (poetry show / poetry add)");
+    println!("    - pom.xml: Maven (versions-maven-plugin)");
+    println!("    - build.gradle(.kts): Gradle (gradle-versions-plugin, report only)");
+    println!("    - go.mod: Go modules (go list -m -u / go get)");
     println!();
-    println!("    For each repository with a package.json file:");
-    println!("    1. Checks for outdated npm packages");
-    println!("    2. Updates packages if found");
+    println!("    For each repository:");
+    println!("    1. Lists available upgrades of direct dependencies");
+    println!("    2. Applies the upgrades the policy allows");
     println!("    3. Reports changes for manual commit");
     println!();
     println!("PRS MODE:");
@@ -95,110 +147,254 @@ fn print_help() {
     println!("    - PR number, title, author, and URL");
     println!();
     println!("    Requires:");
-    println!("    - GITHUB_TOKEN environment variable for API access");
+    println!("    - GITHUB_TOKEN environment variable or `repos auth login` for API access");
     println!("    - Repositories must be GitHub repositories");
     println!();
+    println!("SCORE MODE:");
+    println!("    Scores each repository from 0 to 100 by the weighted share of");
+    println!("    passed checks, ranks them and fails when any scores below the");
+    println!("    threshold. Checks (default weight):");
+    println!("    - ci (2): CI configuration such as .github/workflows/*.yml");
+    println!("    - commits (2): a commit within --max-commit-age days");
+    println!("    - readme (1), license (1): README and LICENSE files");
+    println!("    - deps (2): no major dependency upgrades outstanding");
+    println!("    - prs (1): no open PR older than --max-pr-age days (GitHub)");
+    println!("    - protection (2): default branch is protected (GitHub)");
+    println!("    Checks that cannot be evaluated do not count towards the score.");
+    println!();
     println!("OPTIONS:");
+    println!("    --patch       Only apply patch upgrades (deps mode)");
+    println!("    --minor       Apply minor and patch upgrades (deps mode, default)");
+    println!("    --major       Apply all upgrades, including breaking ones (deps mode)");
+    println!("    --weight CHECK=N      Weight of a check; 0 skips it (score mode)");
+    println!("    --threshold N         Fail below this score, default 70 (score mode)");
+    println!("    --max-commit-age DAYS Age of the last commit allowed, default 90");
+    println!("    --max-pr-age DAYS     Age of the oldest open PR allowed, default 30");
+    println!("    --json        Print the findings as one JSON document");
+    println!("    --markdown    Print the findings as Markdown, e.g. for a GitHub issue");
     println!("    -h, --help    Print this help message");
     println!();
     println!("EXAMPLES:");
     println!("    repos health          # Run dependency check (default)");
     println!("    repos health deps     # Explicitly run dependency check");
+    println!("    repos health deps --major  # Also apply breaking upgrades");
     println!("    repos health prs      # Generate PR report");
+    println!("    repos health prs --markdown > report.md");
+    println!("    repos health score --weight deps=0 --threshold 80");
 }
 
-async fn run_deps_check(repos: Vec<Repository>) -> Result<()> {
-    let mut processed = 0;
+async fn run_deps_check(
+    repos: Vec<Repository>,
+    policy: UpdateKind,
+    format: OutputFormat,
+) -> Result<()> {
+    let mut results = Vec::new();
+    let mut skipped = Vec::new();
     for repo in repos {
-        if let Err(e) = process_repo(&repo) {
-            eprintln!("health: {} skipped: {}", repo.name, e);
-        } else {
-            processed += 1;
+        match process_repo(&repo, policy) {
+            Ok(result) => {
+                if format == OutputFormat::Text {
+                    print_repo_deps(&result, policy);
+                }
+                results.push(result);
+            }
+            Err(e) => {
+                eprintln!("health: {} skipped: {}", repo.name, e);
+                skipped.push(RepoError {
+                    repository: repo.name.clone(),
+                    error: e.to_string(),
+                });
+            }
         }
     }
-    println!("health: processed {} repositories", processed);
+
+    let document = DepsDocument {
+        generated_at: report::generated_at(),
+        policy,
+        repositories: &results,
+        skipped: &skipped,
+    };
+    match format {
+        OutputFormat::Text => println!("health: processed {} repositories", results.len()),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&document)?),
+        OutputFormat::Markdown => print!("{}", report::render_deps_markdown(&document)),
+    }
     Ok(())
 }
 
-async fn run_pr_report(repos: Vec<Repository>) -> Result<()> {
-    let github_token = std::env::var("GITHUB_TOKEN").context("GITHUB_TOKEN not set")?;
-    let mut reports = Vec::new();
+async fn run_pr_report(repos: Vec<Repository>, format: OutputFormat) -> Result<()> {
+    let credentials = Credentials::load(None);
+    if credentials.is_empty() {
+        anyhow::bail!("No GitHub token: set GITHUB_TOKEN or save one with `repos auth login`");
+    }
+    let mut errors = Vec::new();
 
+    // Repositories sharing a token are queried together in batches
+    let mut by_token: Vec<(String, Vec<&Repository>)> = Vec::new();
     for repo in &repos {
-        match fetch_pr_report(repo, &github_token).await {
-            Ok(report) => reports.push(report),
-            Err(e) => eprintln!("Error fetching PRs for {}: {}", repo.name, e),
+        let Some(github_token) = credentials.token_for_repository(repo) else {
+            eprintln!("No GitHub token for {}; skipping", repo.name);
+            errors.push(RepoError {
+                repository: repo.name.clone(),
+                error: "no GitHub token".to_string(),
+            });
+            continue;
+        };
+        match by_token
+            .iter_mut()
+            .find(|(token, _)| *token == github_token)
+        {
+            Some((_, group)) => group.push(repo),
+            None => by_token.push((github_token, vec![repo])),
         }
     }
 
-    println!("\n=== Pull Request Report ===\n");
-    for report in &reports {
-        print_repo_report(report);
+    let mut fetched = Vec::new();
+    for (token, group) in &by_token {
+        let results = fetch_pr_reports(group, token).await;
+        fetched.extend(group.iter().map(|repo| &repo.name).zip(results));
+    }
+
+    // Report in configuration order whatever the grouping
+    let mut reports = Vec::new();
+    for repo in &repos {
+        let Some(position) = fetched.iter().position(|(name, _)| **name == repo.name) else {
+            continue;
+        };
+        match fetched.swap_remove(position).1 {
+            Ok(report) => reports.push(report),
+            Err(e) => {
+                eprintln!("Error fetching PRs for {}: {}", repo.name, e);
+                errors.push(RepoError {
+                    repository: repo.name.clone(),
+                    error: e.to_string(),
+                });
+            }
+        }
     }
 
     let total_prs: usize = reports.iter().map(|r| r.total_prs).sum();
     let total_awaiting: usize = reports.iter().map(|r| r.awaiting_approval.len()).sum();
-    println!(
-        "Total: {} open PRs, {} awaiting review assignment",
-        total_prs, total_awaiting
-    );
+    let document = PrsDocument {
+        generated_at: report::generated_at(),
+        total_prs,
+        awaiting_review: total_awaiting,
+        repositories: &reports,
+        errors: &errors,
+    };
+
+    match format {
+        OutputFormat::Text => {
+            println!("\n=== Pull Request Report ===\n");
+            for report in &reports {
+                print_repo_report(report);
+            }
+            println!(
+                "Total: {} open PRs, {} awaiting review assignment",
+                total_prs, total_awaiting
+            );
+        }
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&document)?),
+        OutputFormat::Markdown => print!("{}", report::render_prs_markdown(&document)),
+    }
 
     Ok(())
 }
 
-async fn fetch_pr_report(repo: &Repository, token: &str) -> Result<PrReport> {
-    // Parse owner/repo from URL
-    let (owner, repo_name) = parse_github_repo(&repo.url)
-        .with_context(|| format!("Failed to parse GitHub URL: {}", repo.url))?;
-
-    // Fetch open PRs from GitHub API
-    let client = reqwest::Client::new();
-    let url = format!(
-        "https://api.github.com/repos/{}/{}/pulls?state=open",
-        owner, repo_name
-    );
-
-    let response = client
-        .get(&url)
-        .header("Authorization", format!("Bearer {}", token))
-        .header("User-Agent", "repos-health")
-        .header("Accept", "application/vnd.github.v3+json")
-        .send()
-        .await
-        .context("Failed to fetch PRs from GitHub")?;
+async fn run_score(
+    repos: Vec<Repository>,
+    settings: &ScoreSettings,
+    format: OutputFormat,
+) -> Result<()> {
+    let scores = score::score_repositories(&repos, settings).await;
+    let document = ScoreDocument {
+        generated_at: report::generated_at(),
+        settings,
+        repositories: &scores,
+        below_threshold: scores
+            .iter()
+            .filter(|score| score.score < settings.threshold)
+            .map(|score| score.repository.as_str())
+            .collect(),
+    };
+
+    match format {
+        OutputFormat::Text => score::print_table(&scores, settings),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&document)?),
+        OutputFormat::Markdown => print!("{}", score::render_markdown(&document)),
+    }
 
-    if !response.status().is_success() {
+    if !document.below_threshold.is_empty() {
         anyhow::bail!(
-            "GitHub API error: {} - {} (URL parsed as: {}/{})",
-            response.status(),
-            response.text().await.unwrap_or_default(),
-            owner,
-            repo_name
+            "{} of {} repositories scored below {}: {}",
+            document.below_threshold.len(),
+            scores.len(),
+            settings.threshold,
+            document.below_threshold.join(", ")
         );
     }
+    Ok(())
+}
 
-    let prs: Vec<PullRequest> = response
-        .json()
-        .await
-        .context("Failed to parse PR response")?;
-
-    let total_prs = prs.len();
-    let awaiting_approval: Vec<PrSummary> = prs
-        .into_iter()
-        .filter(|pr| !pr.draft && pr.requested_reviewers.is_empty())
-        .map(|pr| PrSummary {
-            number: pr.number,
-            title: pr.title,
-            author: pr.user.login,
-            url: pr.html_url,
+/// Open pull requests of each repository, in order, fetched in batched GraphQL queries
+async fn fetch_pr_reports(repos: &[&Repository], token: &str) -> Vec<Result<PrReport>> {
+    let parsed: Vec<Result<(String, String)>> = repos
+        .iter()
+        .map(|repo| {
+            parse_github_repo(&repo.url)
+                .with_context(|| format!("Failed to parse GitHub URL: {}", repo.url))
         })
         .collect();
+    let coordinates: Vec<(String, String)> = parsed
+        .iter()
+        .filter_map(|result| result.as_ref().ok().cloned())
+        .collect();
 
-    Ok(PrReport {
-        repo_name: repo.name.clone(),
-        total_prs,
-        awaiting_approval,
-    })
+    let mut fetched = if coordinates.is_empty() {
+        Vec::new()
+    } else {
+        match GitHubClient::new(Some(token.to_string()))
+            .list_open_pull_requests_bulk(&coordinates)
+            .await
+        {
+            Ok(results) => results,
+            Err(e) => {
+                let message = format!("{:#}", e);
+                coordinates
+                    .iter()
+                    .map(|_| Err(anyhow::anyhow!("{}", message)))
+                    .collect()
+            }
+        }
+    }
+    .into_iter();
+
+    repos
+        .iter()
+        .zip(parsed)
+        .map(|(repo, coordinates)| {
+            coordinates?;
+            let prs = fetched
+                .next()
+                .context("Missing pull requests in GitHub response")??;
+            let total_prs = prs.len();
+            let awaiting_approval = prs
+                .into_iter()
+                .filter(|pr| !pr.draft && pr.review_requests == 0)
+                .map(|pr| PrSummary {
+                    number: pr.number,
+                    title: pr.title,
+                    author: pr.author.unwrap_or_else(|| "ghost".to_string()),
+                    url: pr.html_url,
+                })
+                .collect();
+            Ok(PrReport {
+                repo_name: repo.name.clone(),
+                total_prs,
+                awaiting_approval,
+            })
+        })
+        .collect()
 }
 
 fn parse_github_repo(url: &str) -> Result<(String, String)> {
@@ -266,87 +462,85 @@ fn print_repo_report(report: &PrReport) {
     println!();
 }
 
-fn process_repo(repo: &Repository) -> Result<()> {
+fn process_repo(repo: &Repository, policy: UpdateKind) -> Result<RepoDeps> {
     let repo_path = repo.get_target_dir();
     let path = Path::new(&repo_path);
-    let pkg = path.join("package.json");
-    if !pkg.exists() {
-        anyhow::bail!("no package.json");
+    let ecosystems = Ecosystem::detect(path);
+    if ecosystems.is_empty() {
+        anyhow::bail!("no supported dependency manifest");
     }
 
-    let outdated = check_outdated(path)?;
-    if outdated.is_empty() {
-        println!("health: {} up-to-date", repo.name);
-        return Ok(());
-    }
+    let reports: Vec<EcosystemReport> = ecosystems
+        .iter()
+        .map(|ecosystem| ecosystem.check(path, policy))
+        .collect();
+    let changed = reports.iter().any(|report| !report.updated.is_empty())
+        && has_dependency_changes(path, &ecosystems)?;
 
-    println!(
-        "health: {} outdated packages: {}",
-        repo.name,
-        outdated.join(", ")
-    );
-    update_dependencies(path)?;
-    let changed = has_lockfile_changes(path)?;
-    if !changed {
-        println!("health: {} no lockfile changes after update", repo.name);
-        return Ok(());
-    }
+    Ok(RepoDeps {
+        repository: repo.name.clone(),
+        ecosystems: reports,
+        changed,
+    })
+}
 
-    println!(
-        "health: {} dependencies updated - review changes and commit manually",
-        repo.name
-    );
-    Ok(())
+fn print_repo_deps(result: &RepoDeps, policy: UpdateKind) {
+    print_deps_report(&result.repository, &result.ecosystems, policy);
+
+    if !result
+        .ecosystems
+        .iter()
+        .any(|report| !report.updated.is_empty())
+    {
+        return;
+    }
+    if result.changed {
+        println!(
+            "health: {} dependencies updated - review changes and commit manually",
+            result.repository
+        );
+    } else {
+        println!(
+            "health: {} no dependency file changes after update",
+            result.repository
+        );
+    }
 }
 
-fn check_outdated(repo_path: &Path) -> Result<Vec<String>> {
-    // Try npm outdated --json; if npm missing or error, return mock info
-    let output = Command::new("npm")
-        .arg("outdated")
-        .arg("--json")
-        .current_dir(repo_path)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::null())
-        .output();
-
-    match output {
-        Ok(o) if o.status.success() || o.status.code() == Some(1) => {
-            // npm outdated exits 1 if there are outdated deps
-            if o.stdout.is_empty() {
-                return Ok(vec![]);
-            }
-            let v: serde_json::Value =
-                serde_json::from_slice(&o.stdout).context("parse npm outdated json")?;
-            let mut deps = Vec::new();
-            if let serde_json::Value::Object(map) = v {
-                for (name, info) in map {
-                    if info.get("latest").is_some() {
-                        deps.push(name);
-                    }
-                }
+fn print_deps_report(repo_name: &str, reports: &[EcosystemReport], policy: UpdateKind) {
+    println!("health: {}", repo_name);
+    for report in reports {
+        if let Some(error) = &report.error {
+            println!("  {}: error: {}", report.ecosystem, error);
+        }
+        if report.upgrades.is_empty() {
+            if report.error.is_none() {
+                println!("  {}: up-to-date", report.ecosystem);
             }
-            Ok(deps)
+            continue;
         }
-        Ok(_) => Ok(vec![]),
-        Err(_) => {
-            // Mock fallback when npm not present
-            Ok(vec![]) // keep empty for minimal intrusive behavior
+
+        println!(
+            "  {}: {} upgrade{} available",
+            report.ecosystem,
+            report.upgrades.len(),
+            if report.upgrades.len() == 1 { "" } else { "s" }
+        );
+        for upgrade in &report.upgrades {
+            println!(
+                "    {} {} -> {} ({}, {})",
+                upgrade.name,
+                upgrade.current,
+                upgrade.latest,
+                upgrade.kind,
+                report::upgrade_status(report, upgrade, policy)
+            );
         }
     }
 }
 
-fn update_dependencies(repo_path: &Path) -> Result<()> {
-    // Best effort upgrade; ignore failures to keep minimal
-    let _ = Command::new("npm")
-        .arg("update")
-        .current_dir(repo_path)
-        .status();
-    Ok(())
-}
-
-fn has_lockfile_changes(repo_path: &Path) -> Result<bool> {
-    // Check git diff for package-lock.json / yarn.lock / pnpm-lock.yaml
-    let patterns = ["package-lock.json", "yarn.lock", "pnpm-lock.yaml"];
+fn has_dependency_changes(repo_path: &Path, ecosystems: &[Ecosystem]) -> Result<bool> {
+    // Check git status for the manifests and lockfiles of the updated ecosystems
     let output = Command::new("git")
         .arg("status")
         .arg("--porcelain")
@@ -354,7 +548,10 @@ fn has_lockfile_changes(repo_path: &Path) -> Result<bool> {
         .output()
         .context("git status")?;
     let text = String::from_utf8_lossy(&output.stdout);
-    Ok(patterns.iter().any(|p| text.contains(p)))
+    Ok(ecosystems
+        .iter()
+        .flat_map(|ecosystem| ecosystem.files())
+        .any(|file| text.contains(file)))
 }
 
 #[cfg(test)]
@@ -445,31 +642,37 @@ mod tests {
     }
 
     #[test]
-    fn test_check_outdated_execution() {
-        // Test execution path for check_outdated function
+    fn test_check_execution() {
+        // Test execution path for checking an ecosystem
         let temp_dir = TempDir::new().unwrap();
         let repo_path = temp_dir.path();
 
-        // This will hit the npm command execution path
-        // Expected to return empty vec since npm likely not available in test environment
-        let result = check_outdated(repo_path);
-        assert!(result.is_ok());
+        // npm is likely not available in the test environment, and without a
+        // package.json there is nothing to update either way
+        let report = Ecosystem::Npm.check(repo_path, UpdateKind::Minor);
+        assert!(report.updated.is_empty());
     }
 
     #[test]
-    fn test_update_dependencies_execution() {
-        // Test execution path for update_dependencies function
-        let temp_dir = TempDir::new().unwrap();
-        let repo_path = temp_dir.path();
-
-        // This will execute the npm update command path
-        let result = update_dependencies(repo_path);
-        assert!(result.is_ok()); // Should always succeed (ignores npm failures)
+    fn test_print_deps_report() {
+        let report = EcosystemReport {
+            ecosystem: Ecosystem::Cargo,
+            upgrades: vec![deps::Upgrade {
+                name: "clap".to_string(),
+                current: "3.2.0".to_string(),
+                latest: "4.5.0".to_string(),
+                kind: UpdateKind::Major,
+            }],
+            updated: vec![],
+            error: None,
+        };
+        print_deps_report("test-repo", &[report], UpdateKind::Minor);
+        // Should complete without panic
     }
 
     #[test]
-    fn test_has_lockfile_changes_execution() {
-        // Test execution path for has_lockfile_changes function
+    fn test_has_dependency_changes_execution() {
+        // Test execution path for has_dependency_changes function
         let temp_dir = TempDir::new().unwrap();
         let repo_path = temp_dir.path();
 
@@ -480,14 +683,14 @@ mod tests {
             .output();
 
         // This will hit the git status execution path
-        let result = has_lockfile_changes(repo_path);
+        let result = has_dependency_changes(repo_path, &[Ecosystem::Npm]);
         // May succeed or fail depending on git setup, but tests execution path
         let _ = result; // Don't assert result since git may not be available
     }
 
     #[test]
-    fn test_process_repo_no_package_json() {
-        // Test process_repo execution path when no package.json exists
+    fn test_process_repo_no_manifest() {
+        // Test process_repo execution path when no dependency manifest exists
         let temp_dir = TempDir::new().unwrap();
 
         let repo = Repository {
@@ -497,16 +700,32 @@ mod tests {
             branch: None,
             tags: vec![],
             config_dir: None,
+            path_strategy: Default::default(),
+            default_branch: None,
+            sparse: Vec::new(),
+            provider: None,
+            provider_url: None,
+            provider_defaults: Default::default(),
+            git: Default::default(),
+            depends_on: Vec::new(),
+            post_clone: None,
+            archived: false,
+            remotes: Default::default(),
         };
 
-        // This should hit the "no package.json" error path
-        let result = process_repo(&repo);
+        // This should hit the "no supported dependency manifest" error path
+        let result = process_repo(&repo, UpdateKind::Minor);
         assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("no package.json"));
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("no supported dependency manifest")
+        );
     }
 
     #[tokio::test]
-    async fn test_fetch_pr_report_invalid_url() {
+    async fn test_fetch_pr_reports_invalid_url() {
         let repo = Repository {
             name: "test".to_string(),
             url: "invalid".to_string(),
@@ -514,9 +733,21 @@ mod tests {
             branch: None,
             tags: vec![],
             config_dir: None,
+            path_strategy: Default::default(),
+            default_branch: None,
+            sparse: Vec::new(),
+            provider: None,
+            provider_url: None,
+            provider_defaults: Default::default(),
+            git: Default::default(),
+            depends_on: Vec::new(),
+            post_clone: None,
+            archived: false,
+            remotes: Default::default(),
         };
 
-        let result = fetch_pr_report(&repo, "fake-token").await;
-        assert!(result.is_err());
+        let results = fetch_pr_reports(&[&repo], "fake-token").await;
+        assert_eq!(results.len(), 1);
+        assert!(results[0].is_err());
     }
 }
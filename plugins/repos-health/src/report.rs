@@ -0,0 +1,301 @@
+//! Structured report output (`--json` and `--markdown`)
+//!
+//! Both modes can print their findings as one JSON document for dashboards, or
+//! as Markdown to paste into an issue.
+
+use crate::deps::{EcosystemReport, UpdateKind, Upgrade};
+use crate::{PrReport, RepoDeps};
+use serde::Serialize;
+
+/// How reports are printed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Progress lines for the terminal
+    #[default]
+    Text,
+    Json,
+    Markdown,
+}
+
+/// A repository that could not be checked
+#[derive(Debug, Clone, Serialize)]
+pub struct RepoError {
+    pub repository: String,
+    pub error: String,
+}
+
+/// Findings of `deps` mode
+#[derive(Debug, Serialize)]
+pub struct DepsDocument<'a> {
+    pub generated_at: String,
+    /// Largest upgrade that was applied
+    pub policy: UpdateKind,
+    pub repositories: &'a [RepoDeps],
+    pub skipped: &'a [RepoError],
+}
+
+/// Findings of `prs` mode
+#[derive(Debug, Serialize)]
+pub struct PrsDocument<'a> {
+    pub generated_at: String,
+    pub total_prs: usize,
+    pub awaiting_review: usize,
+    pub repositories: &'a [PrReport],
+    pub errors: &'a [RepoError],
+}
+
+pub fn generated_at() -> String {
+    chrono::Local::now().to_rfc3339()
+}
+
+/// How an upgrade was handled, e.g. `updated` or `skipped, allow with --major`
+pub fn upgrade_status(report: &EcosystemReport, upgrade: &Upgrade, policy: UpdateKind) -> String {
+    if report.updated.contains(&upgrade.name) {
+        "updated".to_string()
+    } else if upgrade.kind > policy {
+        format!("skipped, allow with --{}", upgrade.kind)
+    } else {
+        "not updated".to_string()
+    }
+}
+
+/// Make text safe for a Markdown table cell or link text
+fn escape(text: &str) -> String {
+    text.replace('|', "\\|")
+        .replace('[', "\\[")
+        .replace(']', "\\]")
+}
+
+fn plural(count: usize) -> &'static str {
+    if count == 1 { "" } else { "s" }
+}
+
+pub fn render_deps_markdown(document: &DepsDocument) -> String {
+    let mut out = String::from("# Dependency report\n\n");
+    out.push_str(&format!(
+        "{} repositor{} checked; {} and smaller upgrades were applied.\n",
+        document.repositories.len(),
+        if document.repositories.len() == 1 {
+            "y"
+        } else {
+            "ies"
+        },
+        document.policy
+    ));
+
+    for repo in document.repositories {
+        out.push_str(&format!("\n## {}\n\n", escape(&repo.repository)));
+
+        let mut notes = Vec::new();
+        let mut rows = Vec::new();
+        for report in &repo.ecosystems {
+            if let Some(error) = &report.error {
+                notes.push(format!("- **{}**: {}", report.ecosystem, escape(error)));
+            } else if report.upgrades.is_empty() {
+                notes.push(format!("- **{}**: up-to-date", report.ecosystem));
+            }
+            for upgrade in &report.upgrades {
+                rows.push(format!(
+                    "| {} | {} | {} | {} | {} | {} |",
+                    report.ecosystem,
+                    escape(&upgrade.name),
+                    escape(&upgrade.current),
+                    escape(&upgrade.latest),
+                    upgrade.kind,
+                    upgrade_status(report, upgrade, document.policy)
+                ));
+            }
+        }
+
+        if !rows.is_empty() {
+            out.push_str("| Ecosystem | Dependency | Current | Latest | Upgrade | Status |\n");
+            out.push_str("|---|---|---|---|---|---|\n");
+            for row in rows {
+                out.push_str(&row);
+                out.push('\n');
+            }
+            if !notes.is_empty() {
+                out.push('\n');
+            }
+        }
+        for note in notes {
+            out.push_str(&note);
+            out.push('\n');
+        }
+    }
+
+    if !document.skipped.is_empty() {
+        out.push_str("\n## Skipped\n\n");
+        for skipped in document.skipped {
+            out.push_str(&format!(
+                "- **{}**: {}\n",
+                escape(&skipped.repository),
+                escape(&skipped.error)
+            ));
+        }
+    }
+    out
+}
+
+pub fn render_prs_markdown(document: &PrsDocument) -> String {
+    let mut out = String::from("# Pull request report\n\n");
+    out.push_str(&format!(
+        "**{}** open pull request{}, **{}** awaiting review assignment.\n",
+        document.total_prs,
+        plural(document.total_prs),
+        document.awaiting_review
+    ));
+
+    for report in document.repositories {
+        out.push_str(&format!("\n## {}\n\n", escape(&report.repo_name)));
+        if report.total_prs == 0 {
+            out.push_str("No open pull requests.\n");
+            continue;
+        }
+        out.push_str(&format!(
+            "{} open pull request{}",
+            report.total_prs,
+            plural(report.total_prs)
+        ));
+        if report.awaiting_approval.is_empty() {
+            out.push_str(", all with reviewers assigned.\n");
+            continue;
+        }
+        out.push_str(&format!(
+            ", {} awaiting reviewer assignment:\n\n",
+            report.awaiting_approval.len()
+        ));
+        for pr in &report.awaiting_approval {
+            out.push_str(&format!(
+                "- [#{} {}]({}) by @{}\n",
+                pr.number,
+                escape(&pr.title),
+                pr.url,
+                pr.author
+            ));
+        }
+    }
+
+    if !document.errors.is_empty() {
+        out.push_str("\n## Errors\n\n");
+        for error in document.errors {
+            out.push_str(&format!(
+                "- **{}**: {}\n",
+                escape(&error.repository),
+                escape(&error.error)
+            ));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PrSummary;
+    use crate::deps::Ecosystem;
+
+    fn deps() -> Vec<RepoDeps> {
+        vec![RepoDeps {
+            repository: "api".to_string(),
+            ecosystems: vec![
+                EcosystemReport {
+                    ecosystem: Ecosystem::Cargo,
+                    upgrades: vec![
+                        Upgrade {
+                            name: "clap".to_string(),
+                            current: "3.2.0".to_string(),
+                            latest: "4.5.0".to_string(),
+                            kind: UpdateKind::Major,
+                        },
+                        Upgrade {
+                            name: "serde".to_string(),
+                            current: "1.0.190".to_string(),
+                            latest: "1.0.197".to_string(),
+                            kind: UpdateKind::Patch,
+                        },
+                    ],
+                    updated: vec!["serde".to_string()],
+                    error: None,
+                },
+                EcosystemReport {
+                    ecosystem: Ecosystem::Go,
+                    upgrades: vec![],
+                    updated: vec![],
+                    error: Some("go is not installed".to_string()),
+                },
+            ],
+            changed: true,
+        }]
+    }
+
+    #[test]
+    fn test_deps_markdown() {
+        let repositories = deps();
+        let skipped = vec![RepoError {
+            repository: "docs".to_string(),
+            error: "no supported dependency manifest".to_string(),
+        }];
+        let markdown = render_deps_markdown(&DepsDocument {
+            generated_at: generated_at(),
+            policy: UpdateKind::Minor,
+            repositories: &repositories,
+            skipped: &skipped,
+        });
+        assert!(
+            markdown.contains(
+                "| cargo | clap | 3.2.0 | 4.5.0 | major | skipped, allow with --major |\n"
+            )
+        );
+        assert!(markdown.contains("| cargo | serde | 1.0.190 | 1.0.197 | patch | updated |\n"));
+        assert!(markdown.contains("- **go**: go is not installed\n"));
+        assert!(markdown.contains("## Skipped\n\n- **docs**: no supported dependency manifest\n"));
+    }
+
+    #[test]
+    fn test_deps_json() {
+        let repositories = deps();
+        let document = DepsDocument {
+            generated_at: generated_at(),
+            policy: UpdateKind::Minor,
+            repositories: &repositories,
+            skipped: &[],
+        };
+        let json = serde_json::to_value(&document).unwrap();
+        assert_eq!(json["policy"], "minor");
+        let cargo = &json["repositories"][0]["ecosystems"][0];
+        assert_eq!(cargo["ecosystem"], "cargo");
+        assert_eq!(cargo["upgrades"][0]["kind"], "major");
+        assert_eq!(cargo["updated"][0], "serde");
+    }
+
+    #[test]
+    fn test_prs_markdown_and_json() {
+        let reports = vec![PrReport {
+            repo_name: "api".to_string(),
+            total_prs: 2,
+            awaiting_approval: vec![PrSummary {
+                number: 7,
+                title: "Fix [bug] | crash".to_string(),
+                author: "octocat".to_string(),
+                url: "https://github.com/org/api/pull/7".to_string(),
+            }],
+        }];
+        let document = PrsDocument {
+            generated_at: generated_at(),
+            total_prs: 2,
+            awaiting_review: 1,
+            repositories: &reports,
+            errors: &[],
+        };
+        let markdown = render_prs_markdown(&document);
+        assert!(markdown.contains("**2** open pull requests, **1** awaiting"));
+        assert!(markdown.contains(
+            "- [#7 Fix \\[bug\\] \\| crash](https://github.com/org/api/pull/7) by @octocat\n"
+        ));
+
+        let json = serde_json::to_value(&document).unwrap();
+        assert_eq!(json["repositories"][0]["repository"], "api");
+        assert_eq!(json["repositories"][0]["awaiting_approval"][0]["number"], 7);
+    }
+}
@@ -0,0 +1,683 @@
+//! Composite health score per repository (`score` mode)
+//!
+//! Every repository is rated by a handful of checks, each passing or failing.
+//! The score is the weighted share of passed checks, from 0 to 100. Checks that
+//! could not be evaluated, such as GitHub checks without a token, are left out
+//! of the score instead of counting as failures.
+
+use crate::deps::{Ecosystem, UpdateKind};
+use crate::parse_github_repo;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use repos::Repository;
+use repos::credentials::Credentials;
+use repos_github::GitHubClient;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use std::process::Command;
+use std::str::FromStr;
+
+/// Files any of which means the repository is built by CI
+const CI_FILES: &[&str] = &[
+    ".gitlab-ci.yml",
+    ".circleci/config.yml",
+    ".travis.yml",
+    "Jenkinsfile",
+    "azure-pipelines.yml",
+    "bitbucket-pipelines.yml",
+    ".drone.yml",
+];
+
+/// Something a healthy repository has
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Check {
+    /// A CI configuration, e.g. a GitHub Actions workflow
+    Ci,
+    /// A commit on the checked out branch within `--max-commit-age` days
+    Commits,
+    Readme,
+    License,
+    /// No major upgrades of direct dependencies outstanding
+    Deps,
+    /// No open pull request older than `--max-pr-age` days
+    Prs,
+    /// Branch protection on the default branch
+    Protection,
+}
+
+impl Check {
+    pub const ALL: [Check; 7] = [
+        Check::Ci,
+        Check::Commits,
+        Check::Readme,
+        Check::License,
+        Check::Deps,
+        Check::Prs,
+        Check::Protection,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Check::Ci => "ci",
+            Check::Commits => "commits",
+            Check::Readme => "readme",
+            Check::License => "license",
+            Check::Deps => "deps",
+            Check::Prs => "prs",
+            Check::Protection => "protection",
+        }
+    }
+
+    fn default_weight(&self) -> u32 {
+        match self {
+            Check::Ci | Check::Commits | Check::Deps | Check::Protection => 2,
+            Check::Readme | Check::License | Check::Prs => 1,
+        }
+    }
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Check {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Check::ALL
+            .into_iter()
+            .find(|check| check.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Check::ALL.iter().map(Check::name).collect();
+                anyhow::anyhow!("Unknown check '{}' (expected {})", s, names.join(", "))
+            })
+    }
+}
+
+/// Weights and thresholds of `score` mode
+#[derive(Debug, Clone, Serialize)]
+pub struct ScoreSettings {
+    /// Weight of each check; checks weighing 0 are not run
+    pub weights: BTreeMap<Check, u32>,
+    /// Repositories scoring below this fail the command
+    pub threshold: u32,
+    pub max_commit_age_days: i64,
+    pub max_pr_age_days: i64,
+}
+
+impl Default for ScoreSettings {
+    fn default() -> Self {
+        Self {
+            weights: Check::ALL
+                .into_iter()
+                .map(|check| (check, check.default_weight()))
+                .collect(),
+            threshold: 70,
+            max_commit_age_days: 90,
+            max_pr_age_days: 30,
+        }
+    }
+}
+
+impl ScoreSettings {
+    /// Apply a `--weight` value such as `deps=0`
+    pub fn set_weight(&mut self, value: &str) -> Result<()> {
+        let (check, weight) = value
+            .split_once('=')
+            .with_context(|| format!("Invalid weight '{}' (expected CHECK=WEIGHT)", value))?;
+        let weight = weight
+            .trim()
+            .parse()
+            .with_context(|| format!("Invalid weight '{}' for {}", weight, check))?;
+        self.weights.insert(check.trim().parse()?, weight);
+        Ok(())
+    }
+
+    fn weight(&self, check: Check) -> u32 {
+        self.weights.get(&check).copied().unwrap_or(0)
+    }
+
+    fn enabled(&self, check: Check) -> bool {
+        self.weight(check) > 0
+    }
+}
+
+/// Outcome of one check in one repository
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckResult {
+    pub check: Check,
+    /// `None` when the check could not be evaluated
+    pub passed: Option<bool>,
+    pub detail: String,
+}
+
+impl CheckResult {
+    fn new(check: Check, passed: bool, detail: impl Into<String>) -> Self {
+        Self {
+            check,
+            passed: Some(passed),
+            detail: detail.into(),
+        }
+    }
+
+    fn unknown(check: Check, detail: impl Into<String>) -> Self {
+        Self {
+            check,
+            passed: None,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Score and check results of one repository
+#[derive(Debug, Clone, Serialize)]
+pub struct RepoScore {
+    pub repository: String,
+    /// Weighted share of passed checks, from 0 to 100
+    pub score: u32,
+    pub checks: Vec<CheckResult>,
+}
+
+impl RepoScore {
+    pub fn new(repository: &str, checks: Vec<CheckResult>, settings: &ScoreSettings) -> Self {
+        let (passed, evaluated) = checks.iter().fold((0, 0), |(passed, evaluated), result| {
+            let weight = settings.weight(result.check);
+            match result.passed {
+                Some(true) => (passed + weight, evaluated + weight),
+                Some(false) => (passed, evaluated + weight),
+                None => (passed, evaluated),
+            }
+        });
+        let score = if evaluated == 0 {
+            0
+        } else {
+            (f64::from(passed) * 100.0 / f64::from(evaluated)).round() as u32
+        };
+        Self {
+            repository: repository.to_string(),
+            score,
+            checks,
+        }
+    }
+
+    /// Checks that failed, with what was found
+    pub fn failures(&self) -> impl Iterator<Item = &CheckResult> {
+        self.checks
+            .iter()
+            .filter(|result| result.passed == Some(false))
+    }
+}
+
+/// Scores of all repositories, best first
+#[derive(Debug, Serialize)]
+pub struct ScoreDocument<'a> {
+    pub generated_at: String,
+    pub settings: &'a ScoreSettings,
+    pub repositories: &'a [RepoScore],
+    /// Repositories scoring below the threshold
+    pub below_threshold: Vec<&'a str>,
+}
+
+/// Score every repository and rank them, best first
+pub async fn score_repositories(repos: &[Repository], settings: &ScoreSettings) -> Vec<RepoScore> {
+    let now = Utc::now();
+    let mut remote = remote_checks(repos, settings, now).await;
+    let mut scores: Vec<RepoScore> = repos
+        .iter()
+        .map(|repo| {
+            let mut checks = local_checks(Path::new(&repo.get_target_dir()), settings, now);
+            checks.append(remote.entry(repo.name.clone()).or_default());
+            checks.sort_by_key(|result| result.check);
+            RepoScore::new(&repo.name, checks, settings)
+        })
+        .collect();
+    scores.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| a.repository.cmp(&b.repository))
+    });
+    scores
+}
+
+/// Checks answered by the working copy
+fn local_checks(path: &Path, settings: &ScoreSettings, now: DateTime<Utc>) -> Vec<CheckResult> {
+    let mut results = Vec::new();
+    if !path.exists() {
+        for check in [
+            Check::Ci,
+            Check::Commits,
+            Check::Readme,
+            Check::License,
+            Check::Deps,
+        ] {
+            if settings.enabled(check) {
+                results.push(CheckResult::unknown(check, "repository is not cloned"));
+            }
+        }
+        return results;
+    }
+
+    if settings.enabled(Check::Ci) {
+        results.push(match ci_config(path) {
+            Some(file) => CheckResult::new(Check::Ci, true, file),
+            None => CheckResult::new(Check::Ci, false, "no CI configuration"),
+        });
+    }
+    if settings.enabled(Check::Commits) {
+        results.push(match last_commit(path) {
+            Ok(committed) => {
+                let days = (now - committed).num_days();
+                CheckResult::new(
+                    Check::Commits,
+                    days <= settings.max_commit_age_days,
+                    format!("last commit {} days ago", days),
+                )
+            }
+            Err(e) => CheckResult::unknown(Check::Commits, e.to_string()),
+        });
+    }
+    if settings.enabled(Check::Readme) {
+        results.push(match root_file(path, &["README"]) {
+            Some(file) => CheckResult::new(Check::Readme, true, file),
+            None => CheckResult::new(Check::Readme, false, "no README"),
+        });
+    }
+    if settings.enabled(Check::License) {
+        results.push(match root_file(path, &["LICENSE", "LICENCE", "COPYING"]) {
+            Some(file) => CheckResult::new(Check::License, true, file),
+            None => CheckResult::new(Check::License, false, "no LICENSE"),
+        });
+    }
+    if settings.enabled(Check::Deps) {
+        results.push(dependency_freshness(path));
+    }
+    results
+}
+
+/// First CI configuration file found, relative to the repository
+fn ci_config(path: &Path) -> Option<String> {
+    let workflows = path.join(".github").join("workflows");
+    if let Ok(entries) = std::fs::read_dir(&workflows) {
+        let mut names: Vec<String> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .filter(|name| name.ends_with(".yml") || name.ends_with(".yaml"))
+            .collect();
+        names.sort();
+        if let Some(name) = names.first() {
+            return Some(format!(".github/workflows/{}", name));
+        }
+    }
+    CI_FILES
+        .iter()
+        .find(|file| path.join(file).is_file())
+        .map(|file| file.to_string())
+}
+
+/// A file at the repository root whose name starts with one of `prefixes`, ignoring case
+fn root_file(path: &Path, prefixes: &[&str]) -> Option<String> {
+    let mut names: Vec<String> = std::fs::read_dir(path)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_file())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| {
+            let upper = name.to_uppercase();
+            prefixes.iter().any(|prefix| upper.starts_with(prefix))
+        })
+        .collect();
+    names.sort();
+    names.into_iter().next()
+}
+
+fn last_commit(path: &Path) -> Result<DateTime<Utc>> {
+    let output = Command::new("git")
+        .args(["log", "-1", "--format=%cI"])
+        .current_dir(path)
+        .output()
+        .context("git log")?;
+    let date = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if !output.status.success() || date.is_empty() {
+        anyhow::bail!("no commits");
+    }
+    Ok(DateTime::parse_from_rfc3339(&date)
+        .with_context(|| format!("Invalid commit date '{}'", date))?
+        .with_timezone(&Utc))
+}
+
+/// Whether direct dependencies are at most a minor version behind
+fn dependency_freshness(path: &Path) -> CheckResult {
+    let ecosystems = Ecosystem::detect(path);
+    if ecosystems.is_empty() {
+        return CheckResult::unknown(Check::Deps, "no supported dependency manifest");
+    }
+
+    let mut major = Vec::new();
+    let mut errors = Vec::new();
+    for ecosystem in &ecosystems {
+        match ecosystem.outdated(path) {
+            Ok(upgrades) => major.extend(
+                upgrades
+                    .into_iter()
+                    .filter(|upgrade| upgrade.kind == UpdateKind::Major)
+                    .map(|upgrade| upgrade.name),
+            ),
+            Err(e) => errors.push(format!("{}: {}", ecosystem, e)),
+        }
+    }
+
+    if !major.is_empty() {
+        CheckResult::new(
+            Check::Deps,
+            false,
+            format!("{} major upgrade(s): {}", major.len(), major.join(", ")),
+        )
+    } else if errors.len() == ecosystems.len() {
+        CheckResult::unknown(Check::Deps, errors.join("; "))
+    } else {
+        CheckResult::new(Check::Deps, true, "no major upgrades outstanding")
+    }
+}
+
+/// A repository with its GitHub owner and name
+type GitHubTarget<'a> = (&'a Repository, (String, String));
+
+/// Checks answered by the GitHub API, by repository name
+async fn remote_checks(
+    repos: &[Repository],
+    settings: &ScoreSettings,
+    now: DateTime<Utc>,
+) -> BTreeMap<String, Vec<CheckResult>> {
+    let checks: Vec<Check> = [Check::Prs, Check::Protection]
+        .into_iter()
+        .filter(|check| settings.enabled(*check))
+        .collect();
+    let mut results: BTreeMap<String, Vec<CheckResult>> = BTreeMap::new();
+    if checks.is_empty() {
+        return results;
+    }
+
+    let credentials = Credentials::load(None);
+    let mut unknown = |name: &str, detail: &str| {
+        results.insert(
+            name.to_string(),
+            checks
+                .iter()
+                .map(|check| CheckResult::unknown(*check, detail))
+                .collect(),
+        );
+    };
+
+    // Repositories sharing a token are queried together
+    let mut by_token: Vec<(String, Vec<GitHubTarget>)> = Vec::new();
+    for repo in repos {
+        let Ok(coordinates) = parse_github_repo(&repo.url) else {
+            unknown(&repo.name, "not a GitHub repository");
+            continue;
+        };
+        let Some(token) = credentials.token_for_repository(repo) else {
+            unknown(&repo.name, "no GitHub token");
+            continue;
+        };
+        match by_token.iter_mut().find(|(known, _)| *known == token) {
+            Some((_, group)) => group.push((repo, coordinates)),
+            None => by_token.push((token, vec![(repo, coordinates)])),
+        }
+    }
+
+    for (token, group) in by_token {
+        let client = GitHubClient::new(Some(token));
+        let pull_requests = if checks.contains(&Check::Prs) {
+            let coordinates: Vec<(String, String)> =
+                group.iter().map(|(_, coords)| coords.clone()).collect();
+            match client.list_open_pull_requests_bulk(&coordinates).await {
+                Ok(lists) => lists.into_iter().map(Some).collect(),
+                Err(e) => {
+                    eprintln!("health: failed to list pull requests: {:#}", e);
+                    group.iter().map(|_| None).collect()
+                }
+            }
+        } else {
+            Vec::new()
+        };
+
+        for (index, (repo, (owner, name))) in group.iter().enumerate() {
+            let mut repo_results = Vec::new();
+            if checks.contains(&Check::Prs) {
+                repo_results.push(match pull_requests.get(index) {
+                    Some(Some(Ok(prs))) => {
+                        let oldest = prs
+                            .iter()
+                            .filter_map(|pr| DateTime::parse_from_rfc3339(&pr.created_at).ok())
+                            .map(|created| (now - created.with_timezone(&Utc)).num_days())
+                            .max();
+                        match oldest {
+                            Some(days) => CheckResult::new(
+                                Check::Prs,
+                                days <= settings.max_pr_age_days,
+                                format!("oldest open PR is {} days old", days),
+                            ),
+                            None => CheckResult::new(Check::Prs, true, "no open PRs"),
+                        }
+                    }
+                    Some(Some(Err(e))) => CheckResult::unknown(Check::Prs, format!("{:#}", e)),
+                    _ => CheckResult::unknown(Check::Prs, "pull requests unavailable"),
+                });
+            }
+            if checks.contains(&Check::Protection) {
+                repo_results.push(match branch_protection(&client, repo, owner, name).await {
+                    Ok((branch, protected)) => CheckResult::new(
+                        Check::Protection,
+                        protected,
+                        if protected {
+                            format!("{} is protected", branch)
+                        } else {
+                            format!("{} is not protected", branch)
+                        },
+                    ),
+                    Err(e) => CheckResult::unknown(Check::Protection, format!("{:#}", e)),
+                });
+            }
+            results.insert(repo.name.clone(), repo_results);
+        }
+    }
+    results
+}
+
+/// Default branch of a repository and whether it is protected
+async fn branch_protection(
+    client: &GitHubClient,
+    repo: &Repository,
+    owner: &str,
+    name: &str,
+) -> Result<(String, bool)> {
+    let branch = match &repo.default_branch {
+        Some(branch) => branch.clone(),
+        None => client
+            .get_repository_details(owner, name)
+            .await?
+            .default_branch
+            .unwrap_or_else(|| "main".to_string()),
+    };
+    let protected = client
+        .get_branch(owner, name, &branch)
+        .await?
+        .is_some_and(|branch| branch.protected);
+    Ok((branch, protected))
+}
+
+/// Print the ranked table
+pub fn print_table(scores: &[RepoScore], settings: &ScoreSettings) {
+    let width = scores
+        .iter()
+        .map(|score| score.repository.len())
+        .max()
+        .unwrap_or(0)
+        .max("Repository".len());
+    println!("\n=== Health Scores ===\n");
+    println!(
+        "{:>4}  {:>5}  {:<width$}  Failing checks",
+        "Rank", "Score", "Repository"
+    );
+    for (rank, score) in scores.iter().enumerate() {
+        let failing: Vec<String> = score
+            .failures()
+            .map(|result| format!("{} ({})", result.check, result.detail))
+            .collect();
+        println!(
+            "{:>4}  {:>5}  {:<width$}  {}{}",
+            rank + 1,
+            score.score,
+            score.repository,
+            if failing.is_empty() {
+                "-".to_string()
+            } else {
+                failing.join(", ")
+            },
+            if score.score < settings.threshold {
+                "  [below threshold]"
+            } else {
+                ""
+            }
+        );
+    }
+    println!();
+}
+
+/// Render the ranked table as Markdown
+pub fn render_markdown(document: &ScoreDocument) -> String {
+    let mut out = String::from("# Health scores\n\n");
+    out.push_str(&format!(
+        "{} repositor{} scored; threshold {}.\n\n",
+        document.repositories.len(),
+        if document.repositories.len() == 1 {
+            "y"
+        } else {
+            "ies"
+        },
+        document.settings.threshold
+    ));
+    out.push_str("| Rank | Repository | Score | Failing checks |\n");
+    out.push_str("|---|---|---|---|\n");
+    for (rank, score) in document.repositories.iter().enumerate() {
+        let failing: Vec<String> = score
+            .failures()
+            .map(|result| format!("{} ({})", result.check, result.detail.replace('|', "\\|")))
+            .collect();
+        let marker = if score.score < document.settings.threshold {
+            " ⚠️"
+        } else {
+            ""
+        };
+        out.push_str(&format!(
+            "| {} | {} | {}{} | {} |\n",
+            rank + 1,
+            score.repository.replace('|', "\\|"),
+            score.score,
+            marker,
+            if failing.is_empty() {
+                "-".to_string()
+            } else {
+                failing.join(", ")
+            }
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_set_weight() {
+        let mut settings = ScoreSettings::default();
+        settings.set_weight("deps=0").unwrap();
+        settings.set_weight("license = 5").unwrap();
+        assert_eq!(settings.weight(Check::Deps), 0);
+        assert_eq!(settings.weight(Check::License), 5);
+        assert!(!settings.enabled(Check::Deps));
+
+        assert!(settings.set_weight("deps").is_err());
+        assert!(settings.set_weight("docs=1").is_err());
+        assert!(settings.set_weight("ci=high").is_err());
+    }
+
+    #[test]
+    fn test_score_skips_unknown_checks() {
+        let settings = ScoreSettings::default();
+        let score = RepoScore::new(
+            "api",
+            vec![
+                CheckResult::new(Check::Ci, true, ".github/workflows/ci.yml"),
+                CheckResult::new(Check::License, false, "no LICENSE"),
+                CheckResult::unknown(Check::Protection, "no GitHub token"),
+            ],
+            &settings,
+        );
+        // ci (2) passed out of ci (2) and license (1)
+        assert_eq!(score.score, 67);
+        assert_eq!(score.failures().count(), 1);
+
+        let empty = RepoScore::new(
+            "docs",
+            vec![CheckResult::unknown(Check::Prs, "no GitHub token")],
+            &settings,
+        );
+        assert_eq!(empty.score, 0);
+    }
+
+    #[test]
+    fn test_local_checks() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path();
+        std::fs::create_dir_all(path.join(".github/workflows")).unwrap();
+        std::fs::write(path.join(".github/workflows/test.yaml"), "on: push\n").unwrap();
+        std::fs::write(path.join("readme.md"), "# api\n").unwrap();
+
+        let mut settings = ScoreSettings::default();
+        settings.set_weight("deps=0").unwrap();
+        let results = local_checks(path, &settings, Utc::now());
+        let result = |check: Check| results.iter().find(|r| r.check == check).unwrap();
+
+        assert_eq!(result(Check::Ci).passed, Some(true));
+        assert_eq!(result(Check::Ci).detail, ".github/workflows/test.yaml");
+        assert_eq!(result(Check::Readme).passed, Some(true));
+        assert_eq!(result(Check::License).passed, Some(false));
+        // Not a git repository
+        assert_eq!(result(Check::Commits).passed, None);
+        assert!(results.iter().all(|r| r.check != Check::Deps));
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let settings = ScoreSettings::default();
+        let scores = vec![
+            RepoScore::new(
+                "api",
+                vec![CheckResult::new(Check::Ci, true, "Jenkinsfile")],
+                &settings,
+            ),
+            RepoScore::new(
+                "web",
+                vec![CheckResult::new(Check::Ci, false, "no CI configuration")],
+                &settings,
+            ),
+        ];
+        let document = ScoreDocument {
+            generated_at: "2024-05-01T10:00:00+00:00".to_string(),
+            settings: &settings,
+            repositories: &scores,
+            below_threshold: vec!["web"],
+        };
+        let markdown = render_markdown(&document);
+        assert!(markdown.contains("| 1 | api | 100 | - |"));
+        assert!(markdown.contains("| 2 | web | 0 ⚠️ | ci (no CI configuration) |"));
+    }
+}
@@ -1,7 +1,11 @@
 use anyhow::{Context, Result};
 use clap::Parser;
 use colored::Colorize;
-use repos::{Repository, is_debug_mode, load_plugin_context, save_config};
+use repos::commands::tags::topic_tag_changes;
+use repos::config::Provider;
+use repos::config::edit::set_repository_tags;
+use repos::credentials::Credentials;
+use repos::{Repository, is_debug_mode, load_plugin_context};
 use repos_github::GitHubClient;
 use std::collections::{HashMap, HashSet};
 use std::fs;
@@ -62,61 +66,36 @@ async fn main() -> Result<()> {
 
     println!("Validating repository connectivity...");
 
-    let gh_client = GitHubClient::new(None);
+    // Each repository uses the token saved for its owner, if any
+    let credentials = Credentials::load(None);
     let mut errors = 0;
-    let mut sync_map: HashMap<String, TopicSync> = HashMap::new();
+    // Tags each repository should end up with
+    let mut sync_map: HashMap<String, Vec<String>> = HashMap::new();
 
     for repo in repos {
+        let gh_client = GitHubClient::new(credentials.token_for_repository(&repo));
         match validate_repository(&gh_client, &repo, args.sync_topics).await {
             Ok(topics) => {
                 println!("{} {}: Accessible.", "✅".green(), repo.name);
-                if args.sync_topics && !topics.is_empty() {
-                    let existing_tags: HashSet<_> = repo.tags.iter().cloned().collect();
-
-                    // GitHub topics with gh: prefix
-                    let gh_topics: HashSet<String> =
-                        topics.iter().map(|t| format!("gh:{}", t)).collect();
-
-                    // Find existing gh: tags in config
-                    let existing_gh_tags: HashSet<String> = existing_tags
-                        .iter()
-                        .filter(|t| t.starts_with("gh:"))
-                        .cloned()
-                        .collect();
-
-                    // Topics to add (in GitHub but not in tags)
-                    let to_add: Vec<String> =
-                        gh_topics.difference(&existing_tags).cloned().collect();
-
-                    // Topics to remove (gh: tags in config but not in GitHub topics)
-                    let to_remove: Vec<String> =
-                        existing_gh_tags.difference(&gh_topics).cloned().collect();
-
-                    if !to_add.is_empty() || !to_remove.is_empty() {
-                        if args.apply {
-                            sync_map.insert(
-                                repo.name.clone(),
-                                TopicSync {
-                                    add: to_add.clone(),
-                                    remove: to_remove.clone(),
-                                },
-                            );
-                            if !to_add.is_empty() {
-                                println!("    - Topics to add: {:?}", to_add);
-                            }
-                            if !to_remove.is_empty() {
-                                println!("    - Topics to remove: {:?}", to_remove);
-                            }
+                if args.sync_topics {
+                    let change = topic_tag_changes(&repo.tags, &topics);
+                    if change.is_empty() {
+                        println!("    - Topics already synchronized");
+                    } else {
+                        let (add_label, remove_label) = if args.apply {
+                            ("Topics to add", "Topics to remove")
                         } else {
-                            if !to_add.is_empty() {
-                                println!("    - Would add: {:?}", to_add);
-                            }
-                            if !to_remove.is_empty() {
-                                println!("    - Would remove: {:?}", to_remove);
-                            }
+                            ("Would add", "Would remove")
+                        };
+                        if !change.add.is_empty() {
+                            println!("    - {}: {:?}", add_label, change.add);
+                        }
+                        if !change.remove.is_empty() {
+                            println!("    - {}: {:?}", remove_label, change.remove);
+                        }
+                        if args.apply {
+                            sync_map.insert(repo.name.clone(), change.apply(&repo.tags));
                         }
-                    } else {
-                        println!("    - Topics already synchronized");
                     }
                 }
             }
@@ -148,29 +127,41 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-#[derive(Debug)]
-struct TopicSync {
-    add: Vec<String>,
-    remove: Vec<String>,
-}
-
 async fn validate_repository(
     gh_client: &GitHubClient,
     repo: &Repository,
     fetch_topics: bool,
 ) -> Result<Vec<String>> {
-    // Parse owner/repo from the URL
-    let (owner, repo_name) = parse_github_url(&repo.url)?;
-
-    // Get repository details from GitHub
-    let repo_data = gh_client.get_repository_details(&owner, &repo_name).await?;
+    let topics = match repo.provider() {
+        Provider::GitHub => {
+            // Parse owner/repo from the URL
+            let (owner, repo_name) = parse_github_url(&repo.url)?;
+
+            // Get repository details from GitHub
+            gh_client
+                .get_repository_details(&owner, &repo_name)
+                .await?
+                .topics
+        }
+        Provider::Gitea => {
+            let (client, repo_ref) = repos::gitea::client_for(repo)?;
+            client
+                .get_repository_details(&repo_ref.owner, &repo_ref.repo)
+                .await?
+                .topics
+        }
+        Provider::Azure => {
+            // Azure Repos has no topics, so this only checks access
+            let (client, repo_ref) = repos::azure::client_for(repo)?;
+            client
+                .get_repository_details(&repo_ref.project, &repo_ref.repo)
+                .await?;
+            Vec::new()
+        }
+    };
 
     // Return topics if requested, otherwise empty vector
-    if fetch_topics {
-        Ok(repo_data.topics)
-    } else {
-        Ok(vec![])
-    }
+    if fetch_topics { Ok(topics) } else { Ok(vec![]) }
 }
 
 fn parse_github_url(url: &str) -> Result<(String, String)> {
@@ -246,60 +237,22 @@ fn create_backup(config_path: &PathBuf) -> Result<PathBuf> {
     Ok(backup_path)
 }
 
-fn apply_sync(config_path: &PathBuf, sync_map: &HashMap<String, TopicSync>) -> Result<()> {
+fn apply_sync(config_path: &PathBuf, sync_map: &HashMap<String, Vec<String>>) -> Result<()> {
     println!("Applying topic synchronization to repos.yaml...");
 
     // Create backup first
     create_backup(config_path)?;
 
     // Read the config file
-    let content = fs::read_to_string(config_path)
+    let mut content = fs::read_to_string(config_path)
         .context(format!("Failed to read config file: {:?}", config_path))?;
 
-    // Parse as YAML
-    let mut config: serde_yaml::Value =
-        serde_yaml::from_str(&content).context("Failed to parse repos.yaml")?;
-
-    // Update repositories with synchronized topics
-    if let Some(repos) = config
-        .get_mut("repositories")
-        .and_then(|r| r.as_sequence_mut())
-    {
-        for repo in repos {
-            // Get the name first
-            let name = repo.get("name").and_then(|n| n.as_str()).map(String::from);
-
-            if let Some(name) = name
-                && let Some(sync) = sync_map.get(&name)
-            {
-                // Get existing tags or create new array
-                let tags = repo
-                    .get_mut("tags")
-                    .and_then(|t| t.as_sequence_mut())
-                    .context(format!("Repository '{}' has invalid tags field", name))?;
-
-                // Remove outdated gh: tags
-                for topic in &sync.remove {
-                    let topic_value = serde_yaml::Value::String(topic.clone());
-                    if let Some(pos) = tags.iter().position(|t| t == &topic_value) {
-                        tags.remove(pos);
-                    }
-                }
-
-                // Add new topics
-                for topic in &sync.add {
-                    let topic_value = serde_yaml::Value::String(topic.clone());
-                    if !tags.contains(&topic_value) {
-                        tags.push(topic_value);
-                    }
-                }
-            }
-        }
+    // Edit the tags in place so comments and formatting survive
+    for (name, tags) in sync_map {
+        content = set_repository_tags(&content, name, tags)?;
     }
 
-    // Write back to file using centralized save_config function
-    save_config(&config, config_path.to_str().unwrap())
-        .context("Failed to write updated config")?;
+    fs::write(config_path, content).context("Failed to write updated config")?;
 
     println!("{} Successfully updated repos.yaml", "✅".green());
     println!("   {} repositories were synchronized", sync_map.len());
@@ -0,0 +1,107 @@
+//! Azure DevOps API operations
+//!
+//! Repositories with `provider: azure` use these instead of the GitHub API. The
+//! organization is taken from the clone URL, or from the repository's
+//! `provider_url` when set, and the personal access token is read from
+//! `AZURE_DEVOPS_TOKEN`.
+
+use crate::config::Repository;
+use crate::github::PrOptions;
+use crate::logging;
+use crate::state::{PullRequestRecord, StateStore};
+use anyhow::Result;
+use colored::*;
+use log::Level;
+use repos_azure::{AzureClient, AzureRepoRef, parse_azure_url};
+
+/// Client for the organization hosting a repository, with the repository's project and name
+pub fn client_for(repo: &Repository) -> Result<(AzureClient, AzureRepoRef)> {
+    let mut repo_ref = parse_azure_url(&repo.url)?;
+    if let Some(url) = repo.provider_url() {
+        repo_ref.organization_url = url.trim_end_matches('/').to_string();
+    }
+    Ok((AzureClient::new(&repo_ref.organization_url, None), repo_ref))
+}
+
+/// Open a pull request and record it in the state store, returning its URL
+pub async fn create_pull_request(
+    repo: &Repository,
+    branch_name: &str,
+    base_branch: &str,
+    options: &PrOptions,
+) -> Result<String> {
+    let (client, repo_ref) = client_for(repo)?;
+    let params = repos_azure::PullRequestParams::new(
+        &repo_ref.project,
+        &repo_ref.repo,
+        &options.title,
+        branch_name,
+        base_branch,
+        &options.body,
+        options.draft,
+    );
+    let result = client.create_pull_request(params).await?;
+
+    let record = PullRequestRecord {
+        repository: repo.name.clone(),
+        owner: repo_ref.project,
+        repo: repo_ref.repo,
+        number: result.number,
+        url: result.html_url.clone(),
+        branch: branch_name.to_string(),
+        base: base_branch.to_string(),
+        created_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+    };
+    if let Err(e) = StateStore::open_default().record_pull_request(record) {
+        logging::status(
+            Level::Warn,
+            &repo.name,
+            format!("Warning: Failed to record pull request: {}", e).yellow(),
+        );
+    }
+
+    Ok(result.html_url)
+}
+
+/// Default branch reported by Azure DevOps
+pub async fn fetch_default_branch(repo: &Repository) -> Result<String> {
+    let (client, repo_ref) = client_for(repo)?;
+    client
+        .get_repository_details(&repo_ref.project, &repo_ref.repo)
+        .await?
+        .default_branch_name()
+        .map(str::to_string)
+        .ok_or_else(|| anyhow::anyhow!("No default branch reported for {}", repo.url))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Provider, RepositoryBuilder};
+
+    #[test]
+    fn test_client_for_organization_url() {
+        let repo = RepositoryBuilder::new(
+            "api".to_string(),
+            "git@ssh.dev.azure.com:v3/org/Platform/api".to_string(),
+        )
+        .with_provider(Provider::Azure, None)
+        .build();
+        let (_, repo_ref) = client_for(&repo).unwrap();
+        assert_eq!(repo_ref.organization_url, "https://dev.azure.com/org");
+        assert_eq!(repo_ref.project, "Platform");
+        assert_eq!(repo_ref.repo, "api");
+
+        let repo = RepositoryBuilder::new(
+            "api".to_string(),
+            "https://org.visualstudio.com/Platform/_git/api".to_string(),
+        )
+        .with_provider(
+            Provider::Azure,
+            Some("https://dev.azure.com/org/".to_string()),
+        )
+        .build();
+        let (_, repo_ref) = client_for(&repo).unwrap();
+        assert_eq!(repo_ref.organization_url, "https://dev.azure.com/org");
+    }
+}
@@ -0,0 +1,118 @@
+//! Content-addressed cache of successful run results
+//!
+//! Results are keyed by repository name, HEAD commit, and the command or recipe
+//! that produced them, so a cached entry is only reused when none of those change.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+
+/// Result of a run recorded in the cache
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedResult {
+    pub repository: String,
+    pub exit_code: i32,
+    pub stdout: String,
+    pub stderr: String,
+    pub timestamp: String,
+}
+
+impl CachedResult {
+    pub fn new(repository: &str, exit_code: i32, stdout: String, stderr: String) -> Self {
+        Self {
+            repository: repository.to_string(),
+            exit_code,
+            stdout,
+            stderr,
+            timestamp: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        }
+    }
+}
+
+/// Directory-backed store of cached run results
+#[derive(Debug, Clone)]
+pub struct RunCache {
+    dir: PathBuf,
+}
+
+impl RunCache {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Compute the cache key for a repository at a given commit running the given input
+    pub fn key(repository: &str, head: &str, input: &str) -> String {
+        let mut hasher = Sha256::new();
+        for part in [repository, head, input] {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    /// Look up a cached result; unreadable entries are treated as misses
+    pub fn lookup(&self, key: &str) -> Option<CachedResult> {
+        let content = std::fs::read_to_string(self.entry_path(key)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Record a result under the given key
+    pub fn store(&self, key: &str, result: &CachedResult) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create cache directory {}", self.dir.display()))?;
+        std::fs::write(self.entry_path(key), serde_json::to_string_pretty(result)?)?;
+        Ok(())
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_key_depends_on_all_inputs() {
+        let key = RunCache::key("repo", "abc123", "command:make");
+
+        assert_eq!(key.len(), 64);
+        assert_eq!(key, RunCache::key("repo", "abc123", "command:make"));
+        assert_ne!(key, RunCache::key("other", "abc123", "command:make"));
+        assert_ne!(key, RunCache::key("repo", "def456", "command:make"));
+        assert_ne!(key, RunCache::key("repo", "abc123", "command:make test"));
+    }
+
+    #[test]
+    fn test_store_and_lookup() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = RunCache::new(temp_dir.path().join("cache"));
+        let key = RunCache::key("repo", "abc123", "command:echo hi");
+
+        assert!(cache.lookup(&key).is_none());
+
+        let result = CachedResult::new("repo", 0, "hi\n".to_string(), String::new());
+        cache.store(&key, &result).unwrap();
+
+        let cached = cache.lookup(&key).unwrap();
+        assert_eq!(cached.repository, "repo");
+        assert_eq!(cached.exit_code, 0);
+        assert_eq!(cached.stdout, "hi\n");
+    }
+
+    #[test]
+    fn test_lookup_ignores_corrupt_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = RunCache::new(temp_dir.path().to_path_buf());
+        std::fs::write(temp_dir.path().join("bad.json"), "not json").unwrap();
+
+        assert!(cache.lookup("bad").is_none());
+    }
+}
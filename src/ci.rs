@@ -0,0 +1,169 @@
+//! CI provider integration (`--ci github`)
+//!
+//! In GitHub Actions mode output is adapted for the Actions log viewer:
+//! per-repository output of sequential runs is folded into `::group::`
+//! sections, `<repo> | Error: ...` lines become annotations through a problem
+//! matcher, and run summaries are appended to the job's step summary.
+
+use anyhow::{Context, Result};
+use std::io::Write;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+/// Owner of the problem matcher registered for `repos` output
+const PROBLEM_MATCHER_OWNER: &str = "repos";
+
+/// Matches `<repo> | Error: <message>`, tolerating color codes around both parts
+const ERROR_LINE_PATTERN: &str = r"^(?:\x1b\[[0-9;]*m)*(\S+?)(?:\x1b\[[0-9;]*m)* \| (?:\x1b\[[0-9;]*m)*(Error: .*?)(?:\x1b\[[0-9;]*m)*$";
+
+/// Supported CI providers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CiProvider {
+    GitHub,
+}
+
+impl FromStr for CiProvider {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "github" | "github-actions" => Ok(CiProvider::GitHub),
+            _ => anyhow::bail!("Unknown CI provider '{}' (expected github)", s),
+        }
+    }
+}
+
+static PROVIDER: OnceLock<CiProvider> = OnceLock::new();
+
+/// Enable CI mode for the rest of the process
+pub fn init(provider: CiProvider) -> Result<()> {
+    if PROVIDER.set(provider).is_err() {
+        return Ok(());
+    }
+
+    match provider {
+        CiProvider::GitHub => register_problem_matcher(),
+    }
+}
+
+/// Whether output should be adapted for GitHub Actions
+pub fn github_actions() -> bool {
+    PROVIDER.get() == Some(&CiProvider::GitHub)
+}
+
+/// Start a collapsible log group
+pub fn start_group(title: &str) {
+    if github_actions() {
+        println!("::group::{}", title);
+    }
+}
+
+/// End the current log group
+pub fn end_group() {
+    if github_actions() {
+        println!("::endgroup::");
+    }
+}
+
+/// Append Markdown to the job's step summary, if one is available
+pub fn append_step_summary(markdown: &str) -> Result<()> {
+    if !github_actions() {
+        return Ok(());
+    }
+    let Some(path) = std::env::var_os("GITHUB_STEP_SUMMARY") else {
+        return Ok(());
+    };
+
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open step summary {}", path.to_string_lossy()))?;
+    writeln!(file, "{}", markdown)?;
+    Ok(())
+}
+
+/// GitHub token from the environment
+///
+/// `GITHUB_TOKEN` is always honored. In GitHub Actions mode `GH_TOKEN` and the
+/// `token` / `github-token` action inputs are used as fallbacks.
+pub fn github_token() -> Option<String> {
+    let mut names = vec!["GITHUB_TOKEN"];
+    if github_actions() {
+        names.extend(["GH_TOKEN", "INPUT_GITHUB-TOKEN", "INPUT_TOKEN"]);
+    }
+    names
+        .into_iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|token| !token.is_empty())
+}
+
+/// Problem matcher definition turning error lines into annotations
+fn problem_matcher() -> serde_json::Value {
+    serde_json::json!({
+        "problemMatcher": [{
+            "owner": PROBLEM_MATCHER_OWNER,
+            "severity": "error",
+            "pattern": [{
+                "regexp": ERROR_LINE_PATTERN,
+                "code": 1,
+                "message": 2
+            }]
+        }]
+    })
+}
+
+/// Write the problem matcher into the runner's temp directory and register it
+fn register_problem_matcher() -> Result<()> {
+    let dir = std::env::var_os("RUNNER_TEMP")
+        .map(PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir);
+    let path = dir.join(format!("{}-problem-matcher.json", PROBLEM_MATCHER_OWNER));
+    std::fs::write(&path, serde_json::to_string_pretty(&problem_matcher())?)
+        .with_context(|| format!("Failed to write problem matcher {}", path.display()))?;
+    println!("::add-matcher::{}", path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+
+    #[test]
+    fn test_provider_parsing() {
+        assert_eq!("github".parse::<CiProvider>().unwrap(), CiProvider::GitHub);
+        assert_eq!(
+            "github-actions".parse::<CiProvider>().unwrap(),
+            CiProvider::GitHub
+        );
+        assert!("gitlab".parse::<CiProvider>().is_err());
+    }
+
+    #[test]
+    fn test_error_line_pattern() {
+        let pattern = Regex::new(ERROR_LINE_PATTERN).unwrap();
+
+        let plain = pattern.captures("api | Error: push rejected").unwrap();
+        assert_eq!(&plain[1], "api");
+        assert_eq!(&plain[2], "Error: push rejected");
+
+        let colored = pattern
+            .captures("\x1b[1;36mapi\x1b[0m | \x1b[31mError: push rejected\x1b[0m")
+            .unwrap();
+        assert_eq!(&colored[1], "api");
+        assert_eq!(&colored[2], "Error: push rejected");
+
+        assert!(pattern.captures("api | Successfully cloned").is_none());
+    }
+
+    #[test]
+    fn test_problem_matcher_definition() {
+        let matcher = problem_matcher();
+        let pattern = &matcher["problemMatcher"][0]["pattern"][0];
+        assert_eq!(matcher["problemMatcher"][0]["owner"], "repos");
+        assert_eq!(pattern["regexp"], ERROR_LINE_PATTERN);
+        assert_eq!(pattern["message"], 2);
+    }
+}
@@ -0,0 +1,553 @@
+//! Apply command implementation
+
+use super::{Command, CommandContext, GlobalOptions, validators};
+use crate::config::{Config, Repository};
+use crate::{constants, logging};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use clap::Args;
+use colored::*;
+use log::Level;
+use regex::Regex;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// A codemod: text rewrites applied to matching files of every repository
+///
+/// ```yaml
+/// files: ["**/*.yml"]
+/// exclude: ["vendor/**"]
+/// replace:
+///   - find: actions/checkout@v3
+///     with: actions/checkout@v4
+///   - regex: 'node-version: "?1[68]"?'
+///     with: 'node-version: 20'
+/// run:
+///   - npm install --package-lock-only
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Codemod {
+    /// Shown when the codemod is applied
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Globs of the files to rewrite, relative to the repository root
+    pub files: Vec<String>,
+    /// Globs of files to leave alone
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// Rewrites applied to each file in order
+    pub replace: Vec<Replacement>,
+    /// Shell commands run in each repository the rewrites changed
+    #[serde(default)]
+    pub run: Vec<String>,
+}
+
+/// One rewrite: a literal `find` or a `regex`, replaced `with` a string
+///
+/// Regex replacements may refer to capture groups as `$1` or `${name}`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Replacement {
+    #[serde(default)]
+    pub find: Option<String>,
+    #[serde(default)]
+    pub regex: Option<String>,
+    pub with: String,
+}
+
+/// A replacement ready to apply
+#[derive(Debug)]
+enum Rewrite {
+    Literal(String, String),
+    Pattern(Regex, String),
+}
+
+impl Rewrite {
+    /// Rewrite `text`, returning the new text and the number of replacements
+    fn apply(&self, text: &str) -> (String, usize) {
+        match self {
+            Rewrite::Literal(find, with) => {
+                let count = text.matches(find.as_str()).count();
+                if count == 0 {
+                    return (text.to_string(), 0);
+                }
+                (text.replace(find.as_str(), with), count)
+            }
+            Rewrite::Pattern(regex, with) => {
+                let count = regex.find_iter(text).count();
+                if count == 0 {
+                    return (text.to_string(), 0);
+                }
+                (regex.replace_all(text, with.as_str()).into_owned(), count)
+            }
+        }
+    }
+}
+
+/// A codemod with its globs and regexes compiled
+#[derive(Debug)]
+pub struct CompiledCodemod {
+    pub codemod: Codemod,
+    files: Vec<glob::Pattern>,
+    exclude: Vec<glob::Pattern>,
+    rewrites: Vec<Rewrite>,
+}
+
+/// `*` stays within one directory; `**` crosses directories
+const MATCH_OPTIONS: glob::MatchOptions = glob::MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+fn compile_globs(globs: &[String]) -> Result<Vec<glob::Pattern>> {
+    globs
+        .iter()
+        .map(|g| glob::Pattern::new(g).with_context(|| format!("Invalid glob '{}'", g)))
+        .collect()
+}
+
+impl Codemod {
+    /// Load a codemod spec from a YAML file
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read codemod {}", path.display()))?;
+        serde_yaml::from_str(&content)
+            .with_context(|| format!("Failed to parse codemod {}", path.display()))
+    }
+
+    /// Check the spec and compile its globs and regexes
+    pub fn compile(self) -> Result<CompiledCodemod> {
+        if self.files.is_empty() {
+            anyhow::bail!("Codemod lists no `files` to rewrite");
+        }
+        if self.replace.is_empty() {
+            anyhow::bail!("Codemod has no `replace` entries");
+        }
+
+        let mut rewrites = Vec::new();
+        for (index, replacement) in self.replace.iter().enumerate() {
+            let rewrite = match (&replacement.find, &replacement.regex) {
+                (Some(find), None) if !find.is_empty() => {
+                    Rewrite::Literal(find.clone(), replacement.with.clone())
+                }
+                (None, Some(pattern)) => Rewrite::Pattern(
+                    Regex::new(pattern).with_context(|| {
+                        format!("Invalid regex in replacement {}: {}", index + 1, pattern)
+                    })?,
+                    replacement.with.clone(),
+                ),
+                _ => anyhow::bail!(
+                    "Replacement {} needs exactly one non-empty `find` or `regex`",
+                    index + 1
+                ),
+            };
+            rewrites.push(rewrite);
+        }
+
+        Ok(CompiledCodemod {
+            files: compile_globs(&self.files)?,
+            exclude: compile_globs(&self.exclude)?,
+            rewrites,
+            codemod: self,
+        })
+    }
+}
+
+/// Changes a codemod made to one file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChange {
+    pub path: PathBuf,
+    pub replacements: usize,
+}
+
+impl CompiledCodemod {
+    fn matches(&self, relative: &Path) -> bool {
+        let path = relative.to_string_lossy();
+        self.files
+            .iter()
+            .any(|p| p.matches_with(&path, MATCH_OPTIONS))
+            && !self
+                .exclude
+                .iter()
+                .any(|p| p.matches_with(&path, MATCH_OPTIONS))
+    }
+
+    /// Rewrite the matching files of a repository
+    ///
+    /// Files ignored by git and files that are not UTF-8 text are left alone.
+    /// With `dry_run`, the changes are counted but not written.
+    pub fn rewrite(&self, repo_dir: &Path, dry_run: bool) -> Result<Vec<FileChange>> {
+        let mut changes = Vec::new();
+        let walker = ignore::WalkBuilder::new(repo_dir)
+            .hidden(false)
+            .require_git(false)
+            .filter_entry(|e| e.file_name() != ".git")
+            .sort_by_file_name(|a, b| a.cmp(b))
+            .build();
+        for entry in walker {
+            let entry = entry?;
+            if !entry.file_type().is_some_and(|t| t.is_file()) {
+                continue;
+            }
+            let relative = entry.path().strip_prefix(repo_dir)?;
+            if !self.matches(relative) {
+                continue;
+            }
+            let Ok(original) = std::fs::read_to_string(entry.path()) else {
+                continue;
+            };
+
+            let mut text = original.clone();
+            let mut replacements = 0;
+            for rewrite in &self.rewrites {
+                let (rewritten, count) = rewrite.apply(&text);
+                text = rewritten;
+                replacements += count;
+            }
+            if text == original {
+                continue;
+            }
+
+            if !dry_run {
+                std::fs::write(entry.path(), text)
+                    .with_context(|| format!("Failed to write '{}'", relative.display()))?;
+            }
+            changes.push(FileChange {
+                path: relative.to_path_buf(),
+                replacements,
+            });
+        }
+        Ok(changes)
+    }
+
+    /// Run the post-commands in a repository, stopping at the first failure
+    fn run_commands(&self, repo_dir: &Path) -> Result<()> {
+        for command in &self.codemod.run {
+            log::debug!("Running '{}' in {}", command, repo_dir.display());
+            let output = std::process::Command::new("sh")
+                .arg("-c")
+                .arg(command)
+                .current_dir(repo_dir)
+                .output()
+                .with_context(|| format!("Failed to run '{}'", command))?;
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                anyhow::bail!(
+                    "'{}' failed with exit code {}: {}",
+                    command,
+                    output.status.code().unwrap_or(-1),
+                    stderr.trim()
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Apply command for running a codemod spec across repositories
+pub struct ApplyCommand {
+    /// YAML file describing the codemod
+    pub spec: PathBuf,
+    /// Report what would change without writing files or running commands
+    pub dry_run: bool,
+}
+
+#[async_trait]
+impl Command for ApplyCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let codemod = Codemod::load(&self.spec)?.compile()?;
+
+        let repositories = context.config.filter_repositories(
+            &context.tag,
+            &context.exclude_tag,
+            context.repos.as_deref(),
+        );
+        if repositories.is_empty() {
+            logging::message(Level::Info, "No repositories found".yellow());
+            return Ok(());
+        }
+
+        logging::message(
+            Level::Info,
+            format!(
+                "{} {} to {} repositories...",
+                if self.dry_run { "Checking" } else { "Applying" },
+                codemod
+                    .codemod
+                    .description
+                    .clone()
+                    .unwrap_or_else(|| self.spec.display().to_string()),
+                repositories.len()
+            )
+            .green(),
+        );
+        if !self.dry_run {
+            super::rollback::checkpoint(&format!("apply {}", self.spec.display()), &repositories)?;
+        }
+
+        let mut changed = 0;
+        let mut errors = Vec::new();
+        for repo in &repositories {
+            match self.apply_to(&codemod, repo) {
+                Ok(changes) if changes.is_empty() => {
+                    logging::status(Level::Info, &repo.name, "No changes".dimmed());
+                }
+                Ok(changes) => {
+                    changed += 1;
+                    let replacements: usize = changes.iter().map(|c| c.replacements).sum();
+                    logging::status(
+                        Level::Info,
+                        &repo.name,
+                        format!(
+                            "{} {} replacement(s) in {} file(s)",
+                            if self.dry_run { "Would make" } else { "Made" },
+                            replacements,
+                            changes.len()
+                        )
+                        .green(),
+                    );
+                    for change in &changes {
+                        logging::message(
+                            Level::Info,
+                            format!("  {} ({})", change.path.display(), change.replacements),
+                        );
+                    }
+                }
+                Err(e) => {
+                    logging::status(Level::Error, &repo.name, format!("Error: {e}").red());
+                    errors.push((repo.name.clone(), e));
+                }
+            }
+        }
+
+        logging::message(
+            Level::Info,
+            format!(
+                "{} {} repositories, failed {}",
+                if self.dry_run {
+                    "Would change"
+                } else {
+                    "Changed"
+                },
+                changed,
+                errors.len()
+            )
+            .bold(),
+        );
+        if changed > 0 && !self.dry_run {
+            logging::message(
+                Level::Info,
+                "Review with `repos diff`, then open pull requests with `repos pr`",
+            );
+        }
+
+        if let Some((repo_name, e)) = errors.first() {
+            anyhow::bail!(
+                "Failed to apply the codemod to {} repositories. First error ({}): {}",
+                errors.len(),
+                repo_name,
+                e
+            );
+        }
+        Ok(())
+    }
+}
+
+impl ApplyCommand {
+    fn apply_to(&self, codemod: &CompiledCodemod, repo: &Repository) -> Result<Vec<FileChange>> {
+        let target_dir = repo.get_target_dir();
+        let repo_dir = Path::new(&target_dir);
+        if !repo_dir.is_dir() {
+            anyhow::bail!("Repository directory does not exist: {}", target_dir);
+        }
+
+        let changes = codemod.rewrite(repo_dir, self.dry_run)?;
+        if !changes.is_empty() && !self.dry_run {
+            codemod.run_commands(repo_dir)?;
+        }
+        Ok(changes)
+    }
+}
+
+/// Arguments of `repos apply`
+#[derive(Debug, Args)]
+pub struct ApplyArgs {
+    /// YAML file listing the file globs, replacements and commands to run
+    pub spec: PathBuf,
+
+    /// Specific repository names to change (if not provided, uses tag filter or all repos)
+    pub repos: Vec<String>,
+
+    /// Show what would change without writing files or running commands
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Configuration file path (can be specified multiple times to combine fleets)
+    #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+    pub config: Vec<String>,
+
+    /// Filter repositories by tag (can be specified multiple times)
+    #[arg(short, long)]
+    pub tag: Vec<String>,
+
+    /// Exclude repositories with these tags (can be specified multiple times)
+    #[arg(short = 'e', long)]
+    pub exclude_tag: Vec<String>,
+}
+
+impl ApplyArgs {
+    /// Validate the arguments and run `repos apply`
+    pub async fn run(self, globals: &GlobalOptions) -> Result<()> {
+        let Self {
+            spec,
+            repos,
+            dry_run,
+            config,
+            tag,
+            exclude_tag,
+        } = self;
+        let config = Config::load_all(&config)?;
+
+        validators::validate_tag_filters(&tag)?;
+        validators::validate_tag_filters(&exclude_tag)?;
+        validators::validate_repository_names(&repos)?;
+
+        let mut context = globals.context(config, tag, exclude_tag, repos);
+        context.skip_archived(globals.include_archived);
+
+        ApplyCommand { spec, dry_run }.execute(&context).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn codemod(yaml: &str) -> CompiledCodemod {
+        serde_yaml::from_str::<Codemod>(yaml)
+            .unwrap()
+            .compile()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_compile_rejects_invalid_specs() {
+        for yaml in [
+            "files: ['*']\nreplace: []\n",
+            "files: []\nreplace: [{find: a, with: b}]\n",
+            "files: ['*']\nreplace: [{with: b}]\n",
+            "files: ['*']\nreplace: [{find: a, regex: a, with: b}]\n",
+            "files: ['*']\nreplace: [{regex: '(', with: b}]\n",
+        ] {
+            let spec: Codemod = serde_yaml::from_str(yaml).unwrap();
+            assert!(spec.compile().is_err(), "{yaml}");
+        }
+        assert!(serde_yaml::from_str::<Codemod>("files: ['*']\nreplace: []\nsed: x\n").is_err());
+    }
+
+    #[test]
+    fn test_rewrite_literal_and_regex() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = temp_dir.path();
+        fs::create_dir_all(repo.join(".github/workflows")).unwrap();
+        fs::create_dir_all(repo.join("vendor")).unwrap();
+        fs::write(
+            repo.join(".github/workflows/ci.yml"),
+            "- uses: actions/checkout@v3\n  with:\n    node-version: 16\n",
+        )
+        .unwrap();
+        fs::write(repo.join("vendor/ci.yml"), "actions/checkout@v3\n").unwrap();
+        fs::write(repo.join("top.yml"), "actions/checkout@v3\n").unwrap();
+
+        let codemod = codemod(
+            r#"
+files: [".github/**/*.yml", "vendor/*.yml"]
+exclude: ["vendor/**"]
+replace:
+  - find: actions/checkout@v3
+    with: actions/checkout@v4
+  - regex: '(?m)^(\s+)node-version: 1[68]$'
+    with: '${1}node-version: 20'
+"#,
+        );
+
+        let changes = codemod.rewrite(repo, true).unwrap();
+        assert_eq!(
+            changes,
+            vec![FileChange {
+                path: PathBuf::from(".github/workflows/ci.yml"),
+                replacements: 2,
+            }]
+        );
+        assert!(
+            fs::read_to_string(repo.join(".github/workflows/ci.yml"))
+                .unwrap()
+                .contains("@v3")
+        );
+
+        codemod.rewrite(repo, false).unwrap();
+        assert_eq!(
+            fs::read_to_string(repo.join(".github/workflows/ci.yml")).unwrap(),
+            "- uses: actions/checkout@v4\n  with:\n    node-version: 20\n"
+        );
+        assert_eq!(
+            fs::read_to_string(repo.join("top.yml")).unwrap(),
+            "actions/checkout@v3\n"
+        );
+
+        // Applying again finds nothing to change
+        assert!(codemod.rewrite(repo, false).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_apply_runs_commands_in_changed_repositories() {
+        let temp_dir = TempDir::new().unwrap();
+        let spec = temp_dir.path().join("codemod.yaml");
+        fs::write(
+            &spec,
+            "files: ['*.txt']\nreplace: [{find: old, with: new}]\nrun: ['touch ran']\n",
+        )
+        .unwrap();
+
+        let mut repositories = Vec::new();
+        for (name, content) in [("api", "old name\n"), ("web", "nothing\n")] {
+            let dir = temp_dir.path().join(name);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("README.txt"), content).unwrap();
+            let mut repo =
+                Repository::new(name.to_string(), format!("git@github.com:org/{name}.git"));
+            repo.path = Some(dir.to_string_lossy().to_string());
+            repositories.push(repo);
+        }
+        let mut config = Config::new();
+        config.repositories = repositories;
+        let context = CommandContext {
+            config,
+            tag: vec![],
+            exclude_tag: vec![],
+            parallel: false,
+            repos: None,
+        };
+
+        ApplyCommand {
+            spec,
+            dry_run: false,
+        }
+        .execute(&context)
+        .await
+        .unwrap();
+
+        let api = temp_dir.path().join("api");
+        assert_eq!(
+            fs::read_to_string(api.join("README.txt")).unwrap(),
+            "new name\n"
+        );
+        assert!(api.join("ran").exists());
+        assert!(!temp_dir.path().join("web/ran").exists());
+    }
+}
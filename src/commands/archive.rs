@@ -0,0 +1,324 @@
+//! Archive command implementation
+
+use super::{Command, CommandContext, GlobalOptions, validators};
+use crate::config::{Config, Repository};
+use crate::github::placeholders::Placeholders;
+use crate::logging;
+use crate::utils::archive::{ArchiveFormat, create_archive};
+use crate::utils::sanitize_for_filename;
+use crate::{constants, git};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Local};
+use clap::Args;
+use colored::*;
+use log::Level;
+use std::path::{Path, PathBuf};
+
+/// Default archive file name, without the extension
+pub const DEFAULT_NAME_TEMPLATE: &str = "{{repo.name}}-{{timestamp}}";
+
+/// Archive command for writing a compressed snapshot of each repository
+pub struct ArchiveCommand {
+    pub output_dir: PathBuf,
+    pub format: ArchiveFormat,
+    /// Glob patterns, relative to the repository root, of paths to leave out
+    pub exclude: Vec<String>,
+    /// File name without the extension; supports `{{repo.name}}`, `{{date}}`,
+    /// `{{timestamp}}`, `{{branch}}` and `{{commit}}`
+    pub name_template: String,
+}
+
+impl ArchiveCommand {
+    fn archive_repository(
+        &self,
+        repo: &Repository,
+        exclude: &[glob::Pattern],
+        now: DateTime<Local>,
+    ) -> Result<(PathBuf, usize)> {
+        let target_dir = repo.get_target_dir();
+        let source = Path::new(&target_dir);
+        if !source.is_dir() {
+            anyhow::bail!("Repository directory does not exist: {}", target_dir);
+        }
+
+        let file_name = format!(
+            "{}.{}",
+            render_archive_name(&self.name_template, repo, now),
+            self.format.extension()
+        );
+        let destination = self.output_dir.join(file_name);
+        let files = create_archive(source, &destination, self.format, exclude, &repo.name)?;
+        Ok((destination, files))
+    }
+}
+
+#[async_trait]
+impl Command for ArchiveCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.config.filter_repositories(
+            &context.tag,
+            &context.exclude_tag,
+            context.repos.as_deref(),
+        );
+
+        if repositories.is_empty() {
+            logging::message(Level::Info, "No repositories found".yellow());
+            return Ok(());
+        }
+
+        let exclude = self
+            .exclude
+            .iter()
+            .map(|pattern| {
+                glob::Pattern::new(pattern)
+                    .with_context(|| format!("Invalid --exclude pattern '{}'", pattern))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        std::fs::create_dir_all(&self.output_dir).with_context(|| {
+            format!(
+                "Failed to create output directory {}",
+                self.output_dir.display()
+            )
+        })?;
+
+        logging::message(
+            Level::Info,
+            format!("Archiving {} repositories...", repositories.len()).green(),
+        );
+
+        // One timestamp for the whole run keeps the archives of a snapshot together
+        let now = Local::now();
+        let mut errors = Vec::new();
+        let mut successful = 0;
+
+        for repo in &repositories {
+            match self.archive_repository(repo, &exclude, now) {
+                Ok((path, files)) => {
+                    logging::status(
+                        Level::Info,
+                        &repo.name,
+                        format!(
+                            "{} {}",
+                            format!("Archived {} file(s) to", files).green(),
+                            path.display()
+                        ),
+                    );
+                    successful += 1;
+                }
+                Err(e) => {
+                    logging::status(Level::Error, &repo.name, format!("Error: {e}").red());
+                    errors.push((repo.name.clone(), e));
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            logging::message(Level::Info, "Done archiving repositories".green());
+        } else {
+            logging::message(
+                Level::Info,
+                format!(
+                    "Completed with {} successful, {} failed",
+                    successful,
+                    errors.len()
+                )
+                .yellow(),
+            );
+
+            let (repo_name, e) = &errors[0];
+            anyhow::bail!(
+                "Failed to archive {} repositories. First error ({}): {}",
+                errors.len(),
+                repo_name,
+                e
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Render the archive file name for a repository, without the extension
+fn render_archive_name(template: &str, repo: &Repository, now: DateTime<Local>) -> String {
+    let target_dir = repo.get_target_dir();
+    let mut placeholders = Placeholders::with_date(&now.format("%Y%m%d").to_string(), Vec::new())
+        .with_value("timestamp", &now.format("%Y%m%d-%H%M%S").to_string());
+
+    // Git lookups only run when the template needs them
+    if template.contains("{{branch}}") {
+        let branch = git::get_current_branch(&target_dir).unwrap_or_else(|_| "unknown".to_string());
+        placeholders = placeholders.with_value("branch", &branch);
+    }
+    if template.contains("{{commit}}") {
+        let commit: String = git::get_head_commit(&target_dir)
+            .map(|sha| sha.chars().take(7).collect())
+            .unwrap_or_else(|_| "unknown".to_string());
+        placeholders = placeholders.with_value("commit", &commit);
+    }
+
+    sanitize_for_filename(&placeholders.fill(template, repo))
+}
+
+/// Arguments of `repos archive`
+#[derive(Debug, Args)]
+pub struct ArchiveArgs {
+    /// Specific repository names to archive (if not provided, uses tag filter or all repos)
+    pub repos: Vec<String>,
+
+    /// Archive format
+    #[arg(long, default_value = "tar.gz", value_parser = ["tar.gz", "zip"])]
+    pub format: String,
+
+    /// Leave out paths matching this glob, relative to the repository root, e.g. .git or target (can be specified multiple times)
+    #[arg(long, value_name = "GLOB")]
+    pub exclude: Vec<String>,
+
+    /// Directory to write archives to (default: output/archives)
+    #[arg(long)]
+    pub output_dir: Option<String>,
+
+    /// Archive file name without extension; supports {{repo.name}}, {{date}}, {{timestamp}}, {{branch}} and {{commit}}
+    #[arg(long, default_value = DEFAULT_NAME_TEMPLATE)]
+    pub name: String,
+
+    /// Configuration file path (can be specified multiple times to combine fleets)
+    #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+    pub config: Vec<String>,
+
+    /// Filter repositories by tag (can be specified multiple times)
+    #[arg(short, long)]
+    pub tag: Vec<String>,
+
+    /// Exclude repositories with these tags (can be specified multiple times)
+    #[arg(short = 'e', long)]
+    pub exclude_tag: Vec<String>,
+}
+
+impl ArchiveArgs {
+    /// Validate the arguments and run `repos archive`
+    pub async fn run(self, globals: &GlobalOptions) -> Result<()> {
+        let Self {
+            repos,
+            format,
+            exclude,
+            output_dir,
+            name,
+            config,
+            tag,
+            exclude_tag,
+        } = self;
+        let config = Config::load_all(&config)?;
+
+        validators::validate_tag_filters(&tag)?;
+        validators::validate_tag_filters(&exclude_tag)?;
+        validators::validate_repository_names(&repos)?;
+        validators::validate_output_directory(&output_dir)?;
+
+        let context = globals.context(config, tag, exclude_tag, repos);
+
+        ArchiveCommand {
+            output_dir: output_dir.map(PathBuf::from).unwrap_or_else(|| {
+                PathBuf::from(constants::config::DEFAULT_LOGS_DIR).join("archives")
+            }),
+            format: format.parse()?,
+            exclude,
+            name_template: name,
+        }
+        .execute(&context)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use chrono::TimeZone;
+    use tempfile::TempDir;
+
+    fn repository(dir: &Path) -> Repository {
+        let mut repo = Repository::new("api".to_string(), "git@github.com:org/api.git".to_string());
+        repo.path = Some(dir.to_string_lossy().to_string());
+        repo
+    }
+
+    #[test]
+    fn test_render_archive_name() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = repository(temp_dir.path());
+        let now = Local.with_ymd_and_hms(2024, 3, 9, 14, 5, 0).unwrap();
+
+        assert_eq!(
+            render_archive_name(DEFAULT_NAME_TEMPLATE, &repo, now),
+            "api-20240309-140500"
+        );
+        assert_eq!(
+            render_archive_name("snapshots/{{date}}-{{repo.name}}", &repo, now),
+            "snapshots_20240309-api"
+        );
+        // Outside a git repository the branch cannot be determined
+        assert_eq!(
+            render_archive_name("{{name}}@{{branch}}", &repo, now),
+            "api_unknown"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_archive_command() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_dir = temp_dir.path().join("api");
+        std::fs::create_dir_all(repo_dir.join(".git")).unwrap();
+        std::fs::write(repo_dir.join("README.md"), "# api\n").unwrap();
+        let output_dir = temp_dir.path().join("archives");
+
+        let mut config = Config::new();
+        config.repositories.push(repository(&repo_dir));
+        let context = CommandContext {
+            config,
+            tag: vec![],
+            exclude_tag: vec![],
+            repos: None,
+            parallel: false,
+        };
+
+        ArchiveCommand {
+            output_dir: output_dir.clone(),
+            format: ArchiveFormat::Zip,
+            exclude: vec![".git".to_string()],
+            name_template: "{{name}}".to_string(),
+        }
+        .execute(&context)
+        .await
+        .unwrap();
+
+        assert!(output_dir.join("api.zip").is_file());
+    }
+
+    #[tokio::test]
+    async fn test_archive_missing_repository() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut config = Config::new();
+        config
+            .repositories
+            .push(repository(&temp_dir.path().join("missing")));
+        let context = CommandContext {
+            config,
+            tag: vec![],
+            exclude_tag: vec![],
+            repos: None,
+            parallel: false,
+        };
+
+        let result = ArchiveCommand {
+            output_dir: temp_dir.path().join("archives"),
+            format: ArchiveFormat::TarGz,
+            exclude: vec![],
+            name_template: DEFAULT_NAME_TEMPLATE.to_string(),
+        }
+        .execute(&context)
+        .await;
+        assert!(result.is_err());
+    }
+}
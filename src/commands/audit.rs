@@ -0,0 +1,707 @@
+//! Audit command implementation
+
+use super::protect::{apply_policy, check_protection};
+use super::{Command, CommandContext, GlobalOptions, validators};
+use crate::config::{BranchProtectionPolicy, Config, Repository, SettingsSpec};
+use crate::credentials::Credentials;
+use crate::utils::upload::expand_env;
+use crate::{constants, logging};
+use anyhow::Result;
+use async_trait::async_trait;
+use clap::Args;
+use colored::*;
+use futures::StreamExt;
+use log::Level;
+use repos_github::{
+    BranchProtection, GitHubClient, GitHubRepo, NewWebhook, RepositoryLabel, RepositorySettings,
+    Webhook, parse_github_url,
+};
+use serde::Serialize;
+use std::fmt;
+
+/// Repositories queried at the same time
+const CONCURRENCY: usize = 8;
+
+/// Color of labels created without one
+const DEFAULT_LABEL_COLOR: &str = "ededed";
+
+/// Audit command comparing repository settings against the `repository_settings` specs in the config
+pub struct AuditCommand {
+    pub token: Option<String>,
+    /// Change the settings that drift to match the spec
+    pub fix: bool,
+    /// Print the drift report as JSON
+    pub json: bool,
+}
+
+/// A way in which a repository's settings differ from a spec
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SettingsDrift {
+    MissingLabel {
+        name: String,
+    },
+    LabelDiffers {
+        name: String,
+        field: &'static str,
+        expected: String,
+        actual: String,
+    },
+    MissingTopic {
+        topic: String,
+    },
+    Setting {
+        setting: &'static str,
+        expected: String,
+        actual: String,
+    },
+    MissingWebhook {
+        url: String,
+    },
+}
+
+impl fmt::Display for SettingsDrift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingsDrift::MissingLabel { name } => write!(f, "label '{}' is missing", name),
+            SettingsDrift::LabelDiffers {
+                name,
+                field,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "label '{}' has {} {:?}, expected {:?}",
+                name, field, actual, expected
+            ),
+            SettingsDrift::MissingTopic { topic } => write!(f, "topic '{}' is missing", topic),
+            SettingsDrift::Setting {
+                setting,
+                expected,
+                actual,
+            } => write!(f, "{} is {}, expected {}", setting, actual, expected),
+            SettingsDrift::MissingWebhook { url } => write!(f, "webhook {} is missing", url),
+        }
+    }
+}
+
+/// Drift found in one repository
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingsReport {
+    pub repository: String,
+    pub drift: Vec<SettingsDrift>,
+    /// Whether the drifting settings were changed to match the spec
+    pub fixed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// What a spec is compared against; only the parts the spec checks are fetched
+#[derive(Debug, Clone, Default)]
+pub struct RepositoryState {
+    pub details: Option<GitHubRepo>,
+    pub labels: Vec<RepositoryLabel>,
+    pub webhooks: Vec<Webhook>,
+    /// Protection of the default branch, `None` when it is not protected
+    pub protection: Option<BranchProtection>,
+}
+
+/// Changes bringing a repository in line with a spec
+#[derive(Debug, Clone, Default)]
+pub struct Remediation {
+    pub create_labels: Vec<RepositoryLabel>,
+    pub update_labels: Vec<RepositoryLabel>,
+    /// All topics the repository should have
+    pub topics: Option<Vec<String>>,
+    pub settings: RepositorySettings,
+    /// New protection of the default branch
+    pub protection: Option<(String, BranchProtection)>,
+    pub webhooks: Vec<NewWebhook>,
+}
+
+fn normalize_color(color: &str) -> String {
+    color.trim_start_matches('#').to_lowercase()
+}
+
+fn describe(value: Option<bool>) -> String {
+    value.map_or_else(|| "unknown".to_string(), |value| value.to_string())
+}
+
+/// Compare a repository against a spec, returning the drift and the changes fixing it
+pub fn plan(spec: &SettingsSpec, state: &RepositoryState) -> (Vec<SettingsDrift>, Remediation) {
+    let mut drift = Vec::new();
+    let mut remediation = Remediation::default();
+
+    for wanted in &spec.labels {
+        let color = wanted.color.as_deref().map(normalize_color);
+        let Some(label) = state
+            .labels
+            .iter()
+            .find(|label| label.name.eq_ignore_ascii_case(&wanted.name))
+        else {
+            drift.push(SettingsDrift::MissingLabel {
+                name: wanted.name.clone(),
+            });
+            remediation.create_labels.push(RepositoryLabel {
+                name: wanted.name.clone(),
+                color: color.unwrap_or_else(|| DEFAULT_LABEL_COLOR.to_string()),
+                description: wanted.description.clone(),
+            });
+            continue;
+        };
+
+        let mut updated = label.clone();
+        if let Some(color) = color
+            && normalize_color(&label.color) != color
+        {
+            drift.push(SettingsDrift::LabelDiffers {
+                name: label.name.clone(),
+                field: "color",
+                expected: color.clone(),
+                actual: label.color.clone(),
+            });
+            updated.color = color;
+        }
+        if let Some(description) = &wanted.description
+            && label.description.as_deref().unwrap_or_default() != description
+        {
+            drift.push(SettingsDrift::LabelDiffers {
+                name: label.name.clone(),
+                field: "description",
+                expected: description.clone(),
+                actual: label.description.clone().unwrap_or_default(),
+            });
+            updated.description = Some(description.clone());
+        }
+        if updated != *label {
+            remediation.update_labels.push(updated);
+        }
+    }
+
+    if let Some(details) = &state.details {
+        let mut topics = details.topics.clone();
+        for topic in &spec.topics {
+            if !topics.iter().any(|t| t.eq_ignore_ascii_case(topic)) {
+                drift.push(SettingsDrift::MissingTopic {
+                    topic: topic.clone(),
+                });
+                topics.push(topic.to_lowercase());
+            }
+        }
+        if topics.len() != details.topics.len() {
+            remediation.topics = Some(topics);
+        }
+
+        if let Some(branch) = &spec.default_branch
+            && details.default_branch.as_ref() != Some(branch)
+        {
+            drift.push(SettingsDrift::Setting {
+                setting: "default_branch",
+                expected: branch.clone(),
+                actual: details
+                    .default_branch
+                    .clone()
+                    .unwrap_or_else(|| "unknown".to_string()),
+            });
+            remediation.settings.default_branch = Some(branch.clone());
+        }
+
+        let flags = [
+            (
+                "allow_merge_commit",
+                spec.allow_merge_commit,
+                details.allow_merge_commit,
+                &mut remediation.settings.allow_merge_commit,
+            ),
+            (
+                "allow_squash_merge",
+                spec.allow_squash_merge,
+                details.allow_squash_merge,
+                &mut remediation.settings.allow_squash_merge,
+            ),
+            (
+                "allow_rebase_merge",
+                spec.allow_rebase_merge,
+                details.allow_rebase_merge,
+                &mut remediation.settings.allow_rebase_merge,
+            ),
+            (
+                "allow_auto_merge",
+                spec.allow_auto_merge,
+                details.allow_auto_merge,
+                &mut remediation.settings.allow_auto_merge,
+            ),
+            (
+                "delete_branch_on_merge",
+                spec.delete_branch_on_merge,
+                details.delete_branch_on_merge,
+                &mut remediation.settings.delete_branch_on_merge,
+            ),
+        ];
+        for (setting, expected, actual, change) in flags {
+            if let Some(expected) = expected
+                && actual != Some(expected)
+            {
+                drift.push(SettingsDrift::Setting {
+                    setting,
+                    expected: expected.to_string(),
+                    actual: describe(actual),
+                });
+                *change = Some(expected);
+            }
+        }
+
+        if let Some(reviews) = spec.required_approving_reviews
+            && let Some(branch) = &details.default_branch
+        {
+            let policy = BranchProtectionPolicy {
+                required_approving_reviews: Some(reviews),
+                ..Default::default()
+            };
+            let protection_drift = check_protection(branch, &policy, state.protection.as_ref());
+            if !protection_drift.is_empty() {
+                remediation.protection = Some((
+                    branch.clone(),
+                    apply_policy(&policy, state.protection.as_ref()),
+                ));
+            }
+            drift.extend(
+                protection_drift
+                    .into_iter()
+                    .map(|d| SettingsDrift::Setting {
+                        setting: d.setting,
+                        expected: d.expected,
+                        actual: d.actual,
+                    }),
+            );
+        }
+    }
+
+    for webhook in &spec.webhooks {
+        let configured = state
+            .webhooks
+            .iter()
+            .any(|hook| hook.config.url.as_deref() == Some(webhook.url.as_str()));
+        if !configured {
+            drift.push(SettingsDrift::MissingWebhook {
+                url: webhook.url.clone(),
+            });
+            remediation.webhooks.push(NewWebhook {
+                url: webhook.url.clone(),
+                events: webhook.events.clone(),
+                secret: webhook.secret.as_deref().map(expand_env),
+            });
+        }
+    }
+
+    (drift, remediation)
+}
+
+impl AuditCommand {
+    /// Fetch the parts of a repository a spec checks
+    async fn fetch_state(
+        client: &GitHubClient,
+        owner: &str,
+        name: &str,
+        spec: &SettingsSpec,
+    ) -> Result<RepositoryState> {
+        let mut state = RepositoryState::default();
+        let details = client.get_repository_details(owner, name).await?;
+        if !spec.labels.is_empty() {
+            state.labels = client.list_labels(owner, name).await?;
+        }
+        if !spec.webhooks.is_empty() {
+            state.webhooks = client.list_webhooks(owner, name).await?;
+        }
+        if spec.required_approving_reviews.is_some()
+            && let Some(branch) = &details.default_branch
+        {
+            state.protection = client.get_branch_protection(owner, name, branch).await?;
+        }
+        state.details = Some(details);
+        Ok(state)
+    }
+
+    async fn remediate(
+        client: &GitHubClient,
+        owner: &str,
+        name: &str,
+        remediation: &Remediation,
+    ) -> Result<()> {
+        for label in &remediation.create_labels {
+            client.create_label(owner, name, label).await?;
+        }
+        for label in &remediation.update_labels {
+            client.update_label(owner, name, label).await?;
+        }
+        if let Some(topics) = &remediation.topics {
+            client.replace_topics(owner, name, topics).await?;
+        }
+        // Protect the branch before it stops being the default
+        if let Some((branch, protection)) = &remediation.protection {
+            client
+                .update_branch_protection(owner, name, branch, protection)
+                .await?;
+        }
+        if !remediation.settings.is_empty() {
+            client
+                .update_repository(owner, name, &remediation.settings)
+                .await?;
+        }
+        for webhook in &remediation.webhooks {
+            client.create_webhook(owner, name, webhook).await?;
+        }
+        Ok(())
+    }
+
+    /// Check, and with `--fix` change, the settings of one repository
+    async fn audit_repository(
+        &self,
+        credentials: &Credentials,
+        repo: &Repository,
+        specs: &[&SettingsSpec],
+    ) -> Result<(Vec<SettingsDrift>, bool)> {
+        let (owner, name) = parse_github_url(&repo.url)?;
+        let client = GitHubClient::new(credentials.token_for_repository(repo));
+
+        let mut drift = Vec::new();
+        let mut fixed = false;
+        for spec in specs {
+            let state = Self::fetch_state(&client, &owner, &name, spec).await?;
+            let (spec_drift, remediation) = plan(spec, &state);
+            if self.fix && !spec_drift.is_empty() {
+                Self::remediate(&client, &owner, &name, &remediation).await?;
+                fixed = true;
+            }
+            drift.extend(spec_drift);
+        }
+
+        Ok((drift, fixed))
+    }
+}
+
+#[async_trait]
+impl Command for AuditCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let specs = &context.config.repository_settings;
+        if specs.is_empty() {
+            anyhow::bail!(
+                "No repository settings configured; add a 'repository_settings' section to the config"
+            );
+        }
+
+        let (repositories, other): (Vec<Repository>, Vec<Repository>) = context
+            .config
+            .filter_repositories(&context.tag, &context.exclude_tag, context.repos.as_deref())
+            .into_iter()
+            .filter(|repo| specs.iter().any(|spec| spec.applies_to(repo)))
+            .partition(|repo| repo.provider().is_github());
+
+        if !other.is_empty() {
+            logging::message(
+                Level::Warn,
+                format!("Skipping {} repositories not hosted on GitHub", other.len()).yellow(),
+            );
+        }
+        if repositories.is_empty() {
+            logging::message(Level::Info, "No repositories found".yellow());
+            return Ok(());
+        }
+
+        let credentials = Credentials::load(self.token.clone());
+        let audits: Vec<_> = repositories
+            .iter()
+            .map(|repo| {
+                let credentials = &credentials;
+                async move {
+                    let applicable: Vec<&SettingsSpec> =
+                        specs.iter().filter(|spec| spec.applies_to(repo)).collect();
+                    match self.audit_repository(credentials, repo, &applicable).await {
+                        Ok((drift, fixed)) => SettingsReport {
+                            repository: repo.name.clone(),
+                            drift,
+                            fixed,
+                            error: None,
+                        },
+                        Err(e) => SettingsReport {
+                            repository: repo.name.clone(),
+                            drift: Vec::new(),
+                            fixed: false,
+                            error: Some(format!("{:#}", e)),
+                        },
+                    }
+                }
+            })
+            .collect();
+        let reports: Vec<SettingsReport> = futures::stream::iter(audits)
+            .buffered(CONCURRENCY)
+            .collect()
+            .await;
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&reports)?);
+        } else {
+            for report in &reports {
+                if let Some(error) = &report.error {
+                    logging::status(
+                        Level::Error,
+                        &report.repository,
+                        format!("Error: {}", error).red(),
+                    );
+                } else if report.drift.is_empty() {
+                    logging::status(Level::Info, &report.repository, "Up to date".green());
+                } else {
+                    for drift in &report.drift {
+                        logging::status(Level::Warn, &report.repository, drift.to_string().red());
+                    }
+                    if report.fixed {
+                        logging::status(
+                            Level::Info,
+                            &report.repository,
+                            "Fixed repository settings".green(),
+                        );
+                    }
+                }
+            }
+        }
+
+        let drifted = reports.iter().filter(|r| !r.drift.is_empty()).count();
+        let failed: Vec<&SettingsReport> = reports.iter().filter(|r| r.error.is_some()).collect();
+        if !self.json {
+            logging::message(
+                Level::Info,
+                format!(
+                    "{} of {} repositories drift from the repository settings",
+                    drifted,
+                    reports.len() - failed.len()
+                )
+                .bold(),
+            );
+        }
+
+        if let Some(first) = failed.first() {
+            anyhow::bail!(
+                "Failed to audit {} repositories. First error ({}): {}",
+                failed.len(),
+                first.repository,
+                first.error.as_deref().unwrap_or_default()
+            );
+        }
+        if drifted > 0 && !self.fix {
+            anyhow::bail!(
+                "{} repositories drift from the repository settings",
+                drifted
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Arguments of `repos audit`
+#[derive(Debug, Args)]
+pub struct AuditArgs {
+    /// Specific repository names to check (if not provided, uses tag filter or all repos)
+    pub repos: Vec<String>,
+
+    /// Change drifting settings to match the config
+    #[arg(long)]
+    pub fix: bool,
+
+    /// Output the drift report in JSON format
+    #[arg(long)]
+    pub json: bool,
+
+    /// GitHub token
+    #[arg(long)]
+    pub token: Option<String>,
+
+    /// Configuration file path (can be specified multiple times to combine fleets)
+    #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+    pub config: Vec<String>,
+
+    /// Filter repositories by tag (can be specified multiple times)
+    #[arg(short, long)]
+    pub tag: Vec<String>,
+
+    /// Exclude repositories with these tags (can be specified multiple times)
+    #[arg(short = 'e', long)]
+    pub exclude_tag: Vec<String>,
+}
+
+impl AuditArgs {
+    /// Validate the arguments and run `repos audit`
+    pub async fn run(self, globals: &GlobalOptions) -> Result<()> {
+        let Self {
+            repos,
+            fix,
+            json,
+            token,
+            config,
+            tag,
+            exclude_tag,
+        } = self;
+        let config = Config::load_all(&config)?;
+
+        validators::validate_tag_filters(&tag)?;
+        validators::validate_tag_filters(&exclude_tag)?;
+        validators::validate_repository_names(&repos)?;
+
+        let token = token.or_else(|| config.resolve_profile_token());
+        let mut context = globals.context(config, tag, exclude_tag, repos);
+        context.skip_archived(globals.include_archived);
+        AuditCommand { token, fix, json }.execute(&context).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{LabelSpec, WebhookSpec};
+
+    fn details(json: serde_json::Value) -> GitHubRepo {
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn label(name: &str, color: &str) -> RepositoryLabel {
+        RepositoryLabel {
+            name: name.to_string(),
+            color: color.to_string(),
+            description: None,
+        }
+    }
+
+    #[test]
+    fn test_plan_labels() {
+        let spec = SettingsSpec {
+            labels: vec![
+                LabelSpec {
+                    name: "security".to_string(),
+                    color: Some("#D73A4A".to_string()),
+                    description: None,
+                },
+                LabelSpec {
+                    name: "dependencies".to_string(),
+                    color: Some("0366d6".to_string()),
+                    description: Some("Dependency updates".to_string()),
+                },
+                LabelSpec {
+                    name: "triage".to_string(),
+                    color: None,
+                    description: None,
+                },
+            ],
+            ..Default::default()
+        };
+        let state = RepositoryState {
+            labels: vec![label("Security", "d73a4a"), label("dependencies", "cccccc")],
+            ..Default::default()
+        };
+
+        let (drift, remediation) = plan(&spec, &state);
+        assert_eq!(drift.len(), 3);
+        assert_eq!(
+            drift[0].to_string(),
+            "label 'dependencies' has color \"cccccc\", expected \"0366d6\""
+        );
+        assert_eq!(drift[2].to_string(), "label 'triage' is missing");
+        assert_eq!(remediation.create_labels, vec![label("triage", "ededed")]);
+        assert_eq!(remediation.update_labels.len(), 1);
+        assert_eq!(remediation.update_labels[0].color, "0366d6");
+        assert_eq!(
+            remediation.update_labels[0].description.as_deref(),
+            Some("Dependency updates")
+        );
+    }
+
+    #[test]
+    fn test_plan_repository_settings() {
+        let spec = SettingsSpec {
+            topics: vec!["backend".to_string(), "rust".to_string()],
+            default_branch: Some("main".to_string()),
+            allow_merge_commit: Some(false),
+            allow_squash_merge: Some(true),
+            delete_branch_on_merge: Some(true),
+            required_approving_reviews: Some(1),
+            ..Default::default()
+        };
+        let state = RepositoryState {
+            details: Some(details(serde_json::json!({
+                "topics": ["rust"],
+                "default_branch": "master",
+                "allow_merge_commit": true,
+                "allow_squash_merge": true,
+            }))),
+            ..Default::default()
+        };
+
+        let (drift, remediation) = plan(&spec, &state);
+        let messages: Vec<String> = drift.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "topic 'backend' is missing",
+                "default_branch is master, expected main",
+                "allow_merge_commit is true, expected false",
+                "delete_branch_on_merge is unknown, expected true",
+                "required_approving_reviews is not required, expected 1",
+            ]
+        );
+        assert_eq!(
+            remediation.topics,
+            Some(vec!["rust".to_string(), "backend".to_string()])
+        );
+        assert_eq!(
+            remediation.settings,
+            RepositorySettings {
+                default_branch: Some("main".to_string()),
+                allow_merge_commit: Some(false),
+                delete_branch_on_merge: Some(true),
+                ..Default::default()
+            }
+        );
+        let (branch, protection) = remediation.protection.unwrap();
+        assert_eq!(branch, "master");
+        assert_eq!(protection.required_approving_reviews, Some(1));
+    }
+
+    #[test]
+    fn test_plan_webhooks() {
+        let spec = SettingsSpec {
+            webhooks: vec![
+                WebhookSpec {
+                    url: "https://hooks.example.com/github".to_string(),
+                    events: vec!["push".to_string()],
+                    secret: None,
+                },
+                WebhookSpec {
+                    url: "https://ci.example.com/hook".to_string(),
+                    events: vec!["pull_request".to_string()],
+                    secret: Some("s3cret".to_string()),
+                },
+            ],
+            ..Default::default()
+        };
+        let webhook: Webhook = serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "events": ["push"],
+            "active": true,
+            "config": {"url": "https://hooks.example.com/github", "content_type": "json"}
+        }))
+        .unwrap();
+        let state = RepositoryState {
+            webhooks: vec![webhook],
+            ..Default::default()
+        };
+
+        let (drift, remediation) = plan(&spec, &state);
+        assert_eq!(
+            drift,
+            vec![SettingsDrift::MissingWebhook {
+                url: "https://ci.example.com/hook".to_string()
+            }]
+        );
+        assert_eq!(remediation.webhooks.len(), 1);
+        assert_eq!(remediation.webhooks[0].events, vec!["pull_request"]);
+        assert_eq!(remediation.webhooks[0].secret.as_deref(), Some("s3cret"));
+    }
+}
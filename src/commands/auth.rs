@@ -0,0 +1,209 @@
+//! Auth command implementation, managing named tokens in the OS keychain
+
+use super::{Command, CommandContext};
+use crate::config::Config;
+use crate::constants::credentials::DEFAULT_HOST;
+use crate::credentials::{Identity, IdentityStore};
+use crate::logging;
+use crate::{ci, constants};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use clap::Subcommand;
+use colored::*;
+use log::Level;
+use std::io::{IsTerminal, Read};
+
+/// Auth login command saving a token under a name
+pub struct AuthLoginCommand {
+    pub name: String,
+    /// Host the token is for
+    pub host: String,
+    /// Organization or user the token is limited to
+    pub owner: Option<String>,
+    /// Read the token from standard input instead of prompting for it
+    pub with_token: bool,
+}
+
+/// Auth status command listing saved identities
+pub struct AuthStatusCommand;
+
+/// Auth logout command deleting a saved identity and its token
+pub struct AuthLogoutCommand {
+    pub name: String,
+}
+
+impl AuthLoginCommand {
+    fn read_token(&self) -> Result<String> {
+        let token = if self.with_token || !std::io::stdin().is_terminal() {
+            let mut token = String::new();
+            std::io::stdin()
+                .read_to_string(&mut token)
+                .context("Failed to read token from standard input")?;
+            token
+        } else {
+            dialoguer::Password::new()
+                .with_prompt(format!("Token for {}", self.identity().scope()))
+                .interact()
+                .context("Failed to read token")?
+        };
+
+        let token = token.trim().to_string();
+        if token.is_empty() {
+            anyhow::bail!("No token given");
+        }
+        Ok(token)
+    }
+
+    fn identity(&self) -> Identity {
+        Identity {
+            name: self.name.clone(),
+            host: self.host.to_lowercase(),
+            owner: self.owner.clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl Command for AuthLoginCommand {
+    async fn execute(&self, _context: &CommandContext) -> Result<()> {
+        let identity = self.identity();
+        let token = self.read_token()?;
+
+        // Only github.com can be checked; the client does not know other API URLs
+        if identity.host == DEFAULT_HOST {
+            let login = repos_github::GitHubClient::new(Some(token.clone()))
+                .get_authenticated_user()
+                .await
+                .context("GitHub rejected the token")?;
+            logging::message(
+                Level::Info,
+                format!("Token belongs to {}", login.cyan()).green(),
+            );
+        }
+
+        let store = IdentityStore::open_default()?;
+        store.save(identity.clone(), &token)?;
+        logging::message(
+            Level::Info,
+            format!(
+                "Saved identity '{}' for {}",
+                identity.name,
+                identity.scope()
+            )
+            .green(),
+        );
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Command for AuthStatusCommand {
+    async fn execute(&self, _context: &CommandContext) -> Result<()> {
+        if ci::github_token().is_some() {
+            println!(
+                "{}",
+                "A token from the environment is set and is used instead of saved identities"
+                    .yellow()
+            );
+        }
+
+        let store = IdentityStore::open_default()?;
+        let identities = store.list()?;
+        if identities.is_empty() {
+            println!(
+                "{}",
+                "No saved identities; add one with `repos auth login`".yellow()
+            );
+            return Ok(());
+        }
+
+        for identity in &identities {
+            let token = match store.token(identity) {
+                Ok(Some(_)) => "token in keychain".green(),
+                Ok(None) => "no token in keychain".red(),
+                Err(e) => format!("keychain error: {}", e).red(),
+            };
+            println!(
+                "{} | {} | {}",
+                identity.name.bold(),
+                identity.scope().cyan(),
+                token
+            );
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Command for AuthLogoutCommand {
+    async fn execute(&self, _context: &CommandContext) -> Result<()> {
+        if !IdentityStore::open_default()?.remove(&self.name)? {
+            anyhow::bail!("Identity '{}' not found", self.name);
+        }
+        logging::message(
+            Level::Info,
+            format!("Removed identity '{}'", self.name).green(),
+        );
+        Ok(())
+    }
+}
+
+/// Subcommands of `repos auth`
+#[derive(Debug, Subcommand)]
+pub enum AuthAction {
+    /// Save a token under a name, prompting for it or reading it from stdin
+    Login {
+        /// Name of the identity, e.g. `work` or `oss`
+        name: String,
+
+        /// Host the token is for
+        #[arg(long, default_value = constants::credentials::DEFAULT_HOST)]
+        host: String,
+
+        /// Only use the token for repositories of this organization or user
+        #[arg(long)]
+        owner: Option<String>,
+
+        /// Read the token from standard input
+        #[arg(long)]
+        with_token: bool,
+    },
+
+    /// List saved identities and whether their tokens are in the keychain
+    Status,
+
+    /// Remove a saved identity and delete its token from the keychain
+    Logout {
+        /// Name of the identity
+        name: String,
+    },
+}
+
+impl AuthAction {
+    /// Validate the arguments and run `repos auth <action>`
+    pub async fn run(self) -> Result<()> {
+        // Identities live in the home directory, so no config is needed
+        let context = CommandContext::new(Config::new());
+        match self {
+            AuthAction::Login {
+                name,
+                host,
+                owner,
+                with_token,
+            } => {
+                AuthLoginCommand {
+                    name,
+                    host,
+                    owner,
+                    with_token,
+                }
+                .execute(&context)
+                .await?
+            }
+            AuthAction::Status => AuthStatusCommand.execute(&context).await?,
+            AuthAction::Logout { name } => AuthLogoutCommand { name }.execute(&context).await?,
+        }
+
+        Ok(())
+    }
+}
@@ -1,7 +1,47 @@
 //! Base types and traits for the command pattern
 
 use crate::config::Config;
+use crate::constants::config::ARCHIVED_TAG;
+use crate::logging;
+use crate::state::StateStore;
+use crate::utils::interactive;
+use crate::utils::manifest::{Manifest, ManifestFilters};
 use anyhow::Result;
+use colored::*;
+use log::Level;
+
+/// Options given before the command that apply to every built-in command
+#[derive(Debug, Clone, Default)]
+pub struct GlobalOptions {
+    /// Repository names listed with `--repos-from`
+    pub repos_from: Option<Vec<String>>,
+    /// Also operate on archived repositories, which commands that change remotes skip
+    pub include_archived: bool,
+}
+
+impl GlobalOptions {
+    /// Repository names given as arguments plus those listed with `--repos-from`
+    pub fn repository_selection(&self, repos: Vec<String>) -> Option<Vec<String>> {
+        let mut repos = repos;
+        if let Some(listed) = &self.repos_from {
+            repos.extend(listed.iter().cloned());
+        }
+        if repos.is_empty() { None } else { Some(repos) }
+    }
+
+    /// Context over the repositories selected by tags and names, plus those listed with `--repos-from`
+    pub fn context(
+        &self,
+        config: Config,
+        tag: Vec<String>,
+        exclude_tag: Vec<String>,
+        repos: Vec<String>,
+    ) -> CommandContext {
+        CommandContext::new(config)
+            .with_tags(tag, exclude_tag)
+            .with_repos(self.repository_selection(repos))
+    }
+}
 
 /// Context passed to all commands containing shared configuration and options
 #[derive(Clone)]
@@ -18,9 +58,185 @@ pub struct CommandContext {
     pub repos: Option<Vec<String>>,
 }
 
+impl CommandContext {
+    /// Context over every repository in `config`, processed one after another
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            tag: Vec::new(),
+            exclude_tag: Vec::new(),
+            parallel: false,
+            repos: None,
+        }
+    }
+
+    /// Only repositories with all of `tag` and none of `exclude_tag`
+    pub fn with_tags(mut self, tag: Vec<String>, exclude_tag: Vec<String>) -> Self {
+        self.tag = tag;
+        self.exclude_tag = exclude_tag;
+        self
+    }
+
+    /// Only the repositories named here, when any are
+    pub fn with_repos(mut self, repos: Option<Vec<String>>) -> Self {
+        self.repos = repos;
+        self
+    }
+
+    pub fn with_parallel(mut self, parallel: bool) -> Self {
+        self.parallel = parallel;
+        self
+    }
+
+    /// Narrow the filtered repositories down to those the user picks interactively
+    pub fn select_interactively(&mut self) -> Result<()> {
+        let candidates =
+            self.config
+                .filter_repositories(&self.tag, &self.exclude_tag, self.repos.as_deref());
+        if candidates.is_empty() {
+            return Ok(());
+        }
+
+        let selected = interactive::select_repositories(&candidates)?;
+        if selected.is_empty() {
+            anyhow::bail!("No repositories selected");
+        }
+
+        self.repos = Some(selected.into_iter().map(|repo| repo.name).collect());
+        Ok(())
+    }
+
+    /// Leave out repositories marked as known failing by `repos triage`
+    ///
+    /// Repositories named explicitly are kept, so a marked repository can still be run on its own.
+    pub fn skip_known_failing(&mut self) {
+        let state = match StateStore::open_default().load() {
+            Ok(state) => state,
+            Err(e) => {
+                logging::message(
+                    Level::Warn,
+                    format!("Warning: Failed to read known-failing repositories: {}", e).yellow(),
+                );
+                return;
+            }
+        };
+
+        let named = self.repos.clone().unwrap_or_default();
+        let mut skipped = Vec::new();
+        self.config.repositories.retain(|repo| {
+            let known_failing = state
+                .repositories
+                .get(&repo.name)
+                .is_some_and(|repo_state| repo_state.known_failing);
+            if known_failing && !named.contains(&repo.name) {
+                skipped.push(repo.name.clone());
+                return false;
+            }
+            true
+        });
+        if !skipped.is_empty() {
+            logging::message(
+                Level::Warn,
+                format!(
+                    "Skipping {} known-failing repositories ({}); pass --include-known-failing to run them",
+                    skipped.len(),
+                    skipped.join(", ")
+                )
+                .yellow(),
+            );
+        }
+    }
+
+    /// Leave archived repositories out of a command that changes remotes
+    ///
+    /// Nothing is skipped with `include_archived`, or when `archived` is asked for with `--tag`.
+    /// Repositories named explicitly are kept, and their provider's refusal is reported instead.
+    pub fn skip_archived(&mut self, include_archived: bool) {
+        if include_archived || self.tag.iter().any(|tag| tag == ARCHIVED_TAG) {
+            return;
+        }
+
+        let named = self.repos.clone().unwrap_or_default();
+        let skipped = self
+            .config
+            .filter_repositories(&self.tag, &self.exclude_tag, self.repos.as_deref())
+            .iter()
+            .filter(|repo| repo.archived && !named.contains(&repo.name))
+            .count();
+        self.config
+            .repositories
+            .retain(|repo| !repo.archived || named.contains(&repo.name));
+        if skipped > 0 {
+            logging::message(
+                Level::Warn,
+                format!(
+                    "Skipping {} archived repositories; pass --include-archived to include them",
+                    skipped
+                ),
+            );
+        }
+    }
+
+    /// Start the manifest of `operation` over the repositories this context selects
+    pub fn start_manifest(&self, operation: &str) -> Result<Manifest> {
+        let filters = ManifestFilters {
+            tags: self.tag.clone(),
+            exclude_tags: self.exclude_tag.clone(),
+            repositories: self.repos.clone(),
+        };
+        Manifest::start(operation, &self.config, filters)
+    }
+}
+
 /// Trait that all commands must implement
 #[async_trait::async_trait]
 pub trait Command {
     /// Execute the command with the given context
     async fn execute(&self, context: &CommandContext) -> Result<()>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Repository;
+
+    #[test]
+    fn test_skip_archived() {
+        let mut config = Config::new();
+        for (name, archived) in [("api", false), ("legacy", true), ("old-web", true)] {
+            let mut repo =
+                Repository::new(name.to_string(), format!("git@github.com:org/{}.git", name));
+            repo.archived = archived;
+            config.repositories.push(repo);
+        }
+        let context = CommandContext {
+            config,
+            tag: Vec::new(),
+            exclude_tag: Vec::new(),
+            parallel: false,
+            repos: None,
+        };
+        let names = |context: &CommandContext| -> Vec<String> {
+            context
+                .config
+                .repositories
+                .iter()
+                .map(|repo| repo.name.clone())
+                .collect()
+        };
+
+        let mut skipping = context.clone();
+        skipping.skip_archived(false);
+        assert_eq!(names(&skipping), vec!["api"]);
+
+        let mut including = context.clone();
+        including.skip_archived(true);
+        assert_eq!(names(&including).len(), 3);
+
+        // Naming an archived repository keeps it
+        let mut named = context;
+        named.repos = Some(vec!["legacy".to_string()]);
+        named.skip_archived(false);
+        assert_eq!(names(&named), vec!["api", "legacy"]);
+    }
+}
@@ -0,0 +1,404 @@
+//! Bookmark command implementation
+
+use super::{Command, CommandContext, GlobalOptions, validators};
+use crate::config::{Config, Repository};
+use crate::logging;
+use crate::state::{Bookmark, BookmarkEntry, StateStore};
+use crate::{constants, git};
+use anyhow::Result;
+use async_trait::async_trait;
+use clap::Subcommand;
+use colored::*;
+use log::Level;
+use std::path::Path;
+
+/// Bookmark save command recording the current branch and commit of each repository
+pub struct BookmarkSaveCommand {
+    pub name: String,
+    /// Replace an existing bookmark with the same name
+    pub force: bool,
+}
+
+/// Bookmark switch command returning each repository to its bookmarked branch
+pub struct BookmarkSwitchCommand {
+    pub name: String,
+}
+
+/// Bookmark list command
+pub struct BookmarkListCommand;
+
+/// Bookmark delete command
+pub struct BookmarkDeleteCommand {
+    pub name: String,
+}
+
+/// Current branch (`None` when detached) and commit of a cloned repository
+fn current_position(repo: &Repository) -> Result<BookmarkEntry> {
+    let target_dir = repo.get_target_dir();
+    if !Path::new(&target_dir).exists() {
+        anyhow::bail!("Repository directory does not exist: {}", target_dir);
+    }
+
+    Ok(BookmarkEntry {
+        branch: git::get_current_branch(&target_dir).ok(),
+        commit: git::get_head_commit(&target_dir)?,
+    })
+}
+
+/// Check out the bookmarked position, returning a note about how it was restored
+fn restore_position(repo: &Repository, entry: &BookmarkEntry) -> Result<String> {
+    let target_dir = repo.get_target_dir();
+    if !Path::new(&target_dir).exists() {
+        anyhow::bail!("Repository directory does not exist: {}", target_dir);
+    }
+    if git::has_changes(&target_dir)? {
+        anyhow::bail!("Uncommitted changes; commit or stash them before switching");
+    }
+
+    let Some(branch) = &entry.branch else {
+        git::checkout_commit(&target_dir, &entry.commit)?;
+        return Ok(format!(
+            "Checked out {} (detached)",
+            short_sha(&entry.commit)
+        ));
+    };
+
+    if git::checkout_branch(&target_dir, branch).is_err() {
+        // The branch is gone; the commit is still the best approximation
+        git::checkout_commit(&target_dir, &entry.commit)?;
+        return Ok(format!(
+            "Branch '{}' no longer exists; checked out {} (detached)",
+            branch,
+            short_sha(&entry.commit)
+        ));
+    }
+
+    let head = git::get_head_commit(&target_dir)?;
+    if head == entry.commit {
+        Ok(format!("Switched to '{}'", branch))
+    } else {
+        Ok(format!(
+            "Switched to '{}' (moved from {} to {} since the bookmark was saved)",
+            branch,
+            short_sha(&entry.commit),
+            short_sha(&head)
+        ))
+    }
+}
+
+fn short_sha(commit: &str) -> &str {
+    &commit[..commit.len().min(7)]
+}
+
+fn describe(entry: &BookmarkEntry) -> String {
+    match &entry.branch {
+        Some(branch) => format!("{} @ {}", branch, short_sha(&entry.commit)),
+        None => format!("{} (detached)", short_sha(&entry.commit)),
+    }
+}
+
+fn validate_name(name: &str) -> Result<()> {
+    if name.trim().is_empty() {
+        anyhow::bail!("Bookmark name cannot be empty");
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl Command for BookmarkSaveCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        validate_name(&self.name)?;
+        let store = StateStore::open_default();
+        if !self.force && store.bookmark(&self.name)?.is_some() {
+            anyhow::bail!(
+                "Bookmark '{}' already exists; use --force to replace it",
+                self.name
+            );
+        }
+
+        let repositories = context.config.filter_repositories(
+            &context.tag,
+            &context.exclude_tag,
+            context.repos.as_deref(),
+        );
+        if repositories.is_empty() {
+            logging::message(Level::Info, "No repositories found".yellow());
+            return Ok(());
+        }
+
+        let mut bookmark = Bookmark {
+            created_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            ..Default::default()
+        };
+        for repo in &repositories {
+            match current_position(repo) {
+                Ok(entry) => {
+                    logging::status(Level::Info, &repo.name, describe(&entry));
+                    bookmark.repositories.insert(repo.name.clone(), entry);
+                }
+                Err(e) => {
+                    logging::status(Level::Warn, &repo.name, format!("Skipping: {}", e).yellow())
+                }
+            }
+        }
+
+        if bookmark.repositories.is_empty() {
+            anyhow::bail!("None of the selected repositories are cloned");
+        }
+
+        let saved = bookmark.repositories.len();
+        store.save_bookmark(&self.name, bookmark)?;
+        logging::message(
+            Level::Info,
+            format!("Saved bookmark '{}' with {} repositories", self.name, saved).green(),
+        );
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Command for BookmarkSwitchCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let bookmark = StateStore::open_default()
+            .bookmark(&self.name)?
+            .ok_or_else(|| anyhow::anyhow!("Bookmark '{}' not found", self.name))?;
+
+        let repositories: Vec<Repository> = context
+            .config
+            .filter_repositories(&context.tag, &context.exclude_tag, context.repos.as_deref())
+            .into_iter()
+            .filter(|repo| bookmark.repositories.contains_key(&repo.name))
+            .collect();
+
+        if repositories.is_empty() {
+            logging::message(
+                Level::Info,
+                format!(
+                    "No selected repositories are part of bookmark '{}'",
+                    self.name
+                )
+                .yellow(),
+            );
+            return Ok(());
+        }
+
+        let mut errors = Vec::new();
+        for repo in &repositories {
+            let entry = &bookmark.repositories[&repo.name];
+            match restore_position(repo, entry) {
+                Ok(message) => logging::status(Level::Info, &repo.name, message.green()),
+                Err(e) => {
+                    logging::status(Level::Error, &repo.name, format!("Error: {e}").red());
+                    errors.push((repo.name.clone(), e));
+                }
+            }
+        }
+
+        if let Some((repo_name, e)) = errors.first() {
+            anyhow::bail!(
+                "Failed to switch {} repositories to bookmark '{}'. First error ({}): {}",
+                errors.len(),
+                self.name,
+                repo_name,
+                e
+            );
+        }
+
+        logging::message(
+            Level::Info,
+            format!("Switched to bookmark '{}'", self.name).green(),
+        );
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Command for BookmarkListCommand {
+    async fn execute(&self, _context: &CommandContext) -> Result<()> {
+        let bookmarks = StateStore::open_default().load()?.bookmarks;
+        if bookmarks.is_empty() {
+            println!("{}", "No bookmarks saved".yellow());
+            return Ok(());
+        }
+
+        for (name, bookmark) in &bookmarks {
+            println!(
+                "{} ({} repositories, saved {})",
+                name.bold(),
+                bookmark.repositories.len(),
+                bookmark.created_at
+            );
+            for (repo_name, entry) in &bookmark.repositories {
+                println!("  {} | {}", repo_name.cyan(), describe(entry));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Command for BookmarkDeleteCommand {
+    async fn execute(&self, _context: &CommandContext) -> Result<()> {
+        if !StateStore::open_default().remove_bookmark(&self.name)? {
+            anyhow::bail!("Bookmark '{}' not found", self.name);
+        }
+        logging::message(
+            Level::Info,
+            format!("Deleted bookmark '{}'", self.name).green(),
+        );
+        Ok(())
+    }
+}
+
+/// Subcommands of `repos bookmark`
+#[derive(Debug, Subcommand)]
+pub enum BookmarkAction {
+    /// Record the current branch and commit of each repository under a name
+    Save {
+        /// Bookmark name
+        name: String,
+
+        /// Specific repository names to include (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Replace an existing bookmark with the same name
+        #[arg(long)]
+        force: bool,
+
+        /// Configuration file path (can be specified multiple times to combine fleets)
+        #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+        config: Vec<String>,
+
+        /// Filter repositories by tag (can be specified multiple times)
+        #[arg(short, long)]
+        tag: Vec<String>,
+
+        /// Exclude repositories with these tags (can be specified multiple times)
+        #[arg(short = 'e', long)]
+        exclude_tag: Vec<String>,
+    },
+
+    /// Check out the branches (or commits) recorded in a bookmark
+    Switch {
+        /// Bookmark name
+        name: String,
+
+        /// Specific repository names to switch (if not provided, uses tag filter or all bookmarked repos)
+        repos: Vec<String>,
+
+        /// Configuration file path (can be specified multiple times to combine fleets)
+        #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+        config: Vec<String>,
+
+        /// Filter repositories by tag (can be specified multiple times)
+        #[arg(short, long)]
+        tag: Vec<String>,
+
+        /// Exclude repositories with these tags (can be specified multiple times)
+        #[arg(short = 'e', long)]
+        exclude_tag: Vec<String>,
+    },
+
+    /// List saved bookmarks
+    List,
+
+    /// Delete a bookmark
+    Delete {
+        /// Bookmark name
+        name: String,
+    },
+}
+
+impl BookmarkAction {
+    /// Validate the arguments and run `repos bookmark <action>`
+    pub async fn run(self, globals: &GlobalOptions) -> Result<()> {
+        match self {
+            BookmarkAction::Save {
+                name,
+                repos,
+                force,
+                config,
+                tag,
+                exclude_tag,
+            } => {
+                let config = Config::load_all(&config)?;
+
+                validators::validate_tag_filters(&tag)?;
+                validators::validate_tag_filters(&exclude_tag)?;
+                validators::validate_repository_names(&repos)?;
+
+                let context = globals.context(config, tag, exclude_tag, repos);
+                BookmarkSaveCommand { name, force }
+                    .execute(&context)
+                    .await?;
+            }
+            BookmarkAction::Switch {
+                name,
+                repos,
+                config,
+                tag,
+                exclude_tag,
+            } => {
+                let config = Config::load_all(&config)?;
+
+                validators::validate_tag_filters(&tag)?;
+                validators::validate_tag_filters(&exclude_tag)?;
+                validators::validate_repository_names(&repos)?;
+
+                let context = globals.context(config, tag, exclude_tag, repos);
+                BookmarkSwitchCommand { name }.execute(&context).await?;
+            }
+            BookmarkAction::List | BookmarkAction::Delete { .. } => {
+                // Bookmarks live in the state store, so no config is needed
+                let context = CommandContext::new(Config::new());
+                match self {
+                    BookmarkAction::Delete { name } => {
+                        BookmarkDeleteCommand { name }.execute(&context).await?
+                    }
+                    _ => BookmarkListCommand.execute(&context).await?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe() {
+        let on_branch = BookmarkEntry {
+            branch: Some("feature/login".to_string()),
+            commit: "0123456789abcdef".to_string(),
+        };
+        let detached = BookmarkEntry {
+            branch: None,
+            commit: "fedcba9876543210".to_string(),
+        };
+
+        assert_eq!(describe(&on_branch), "feature/login @ 0123456");
+        assert_eq!(describe(&detached), "fedcba9 (detached)");
+        assert_eq!(short_sha("abc"), "abc");
+    }
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("login-work").is_ok());
+        assert!(validate_name("  ").is_err());
+    }
+
+    #[test]
+    fn test_missing_repository_position() {
+        let mut repo = Repository::new("api".to_string(), "git@github.com:org/api.git".to_string());
+        repo.path = Some("/nonexistent/repos/api".to_string());
+
+        assert!(current_position(&repo).is_err());
+        let entry = BookmarkEntry {
+            branch: None,
+            commit: "abc".to_string(),
+        };
+        assert!(restore_position(&repo, &entry).is_err());
+    }
+}
@@ -0,0 +1,432 @@
+//! Checkout command implementation
+
+use super::{Command, CommandContext, GlobalOptions, validators};
+use crate::config::{Config, Repository};
+use crate::constants::git::FALLBACK_BRANCH;
+use crate::git::{self, BranchLocation, SwitchOutcome};
+use crate::logging;
+use crate::{constants, events};
+use anyhow::Result;
+use async_trait::async_trait;
+use clap::Args;
+use colored::*;
+use log::Level;
+use std::path::Path;
+
+/// Checkout command switching every repository to the same branch
+#[derive(Debug, Clone)]
+pub struct CheckoutCommand {
+    pub branch: String,
+    /// Create the branch from the default branch where it does not exist
+    pub create: bool,
+    /// Fetch `origin` first, so branches pushed by others are found
+    pub fetch: bool,
+}
+
+/// What happened in one repository
+#[derive(Debug, PartialEq, Eq)]
+enum RepoResult {
+    AlreadyOn,
+    Switched,
+    Created {
+        from: String,
+    },
+    /// The branch exists neither locally nor on `origin`, and `--create` was not given
+    Missing,
+    /// Number of tracked files with uncommitted changes
+    Dirty(usize),
+    /// Unresolved merge conflicts, or files the checkout would overwrite
+    Conflicts(Vec<String>),
+}
+
+impl CheckoutCommand {
+    /// Base branch for new branches: the config, then `origin/HEAD`
+    fn base_branch(repo: &Repository, repo_path: &str) -> String {
+        repo.default_branch
+            .clone()
+            .or_else(|| git::get_remote_default_branch(repo_path))
+            .unwrap_or_else(|| FALLBACK_BRANCH.to_string())
+    }
+
+    /// Switch one repository to the branch
+    fn checkout_repository(&self, repo: &Repository) -> Result<RepoResult> {
+        let repo_path = repo.get_target_dir();
+        if !Path::new(&repo_path).exists() {
+            anyhow::bail!("Repository directory does not exist: {}", repo_path);
+        }
+
+        let status = git::working_status(&repo_path)?;
+        if status.branch.as_deref() == Some(self.branch.as_str()) {
+            return Ok(RepoResult::AlreadyOn);
+        }
+        if status.conflicted > 0 {
+            return Ok(RepoResult::Conflicts(vec![format!(
+                "{} files with unresolved conflicts",
+                status.conflicted
+            )]));
+        }
+        if status.changed > 0 {
+            return Ok(RepoResult::Dirty(status.changed));
+        }
+
+        if self.fetch {
+            git::fetch(&repo_path)?;
+        }
+
+        let location = git::branch_location(&repo_path, &self.branch)?;
+        let start_point = match location {
+            BranchLocation::Missing if !self.create => return Ok(RepoResult::Missing),
+            BranchLocation::Missing => Some(git::start_point(
+                &repo_path,
+                &Self::base_branch(repo, &repo_path),
+            )?),
+            _ => None,
+        };
+
+        match git::switch_branch(&repo_path, &self.branch, location, start_point.as_deref())? {
+            SwitchOutcome::Blocked(files) => Ok(RepoResult::Conflicts(files)),
+            SwitchOutcome::Switched => Ok(match start_point {
+                Some(from) => RepoResult::Created { from },
+                None => RepoResult::Switched,
+            }),
+        }
+    }
+}
+
+/// Print the repositories of one group of the summary, if there are any
+fn list_group(title: &str, names: &[String]) {
+    if !names.is_empty() {
+        logging::message(
+            Level::Warn,
+            format!("{} ({}): {}", title, names.len(), names.join(", ")).yellow(),
+        );
+    }
+}
+
+#[async_trait]
+impl Command for CheckoutCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.config.filter_repositories(
+            &context.tag,
+            &context.exclude_tag,
+            context.repos.as_deref(),
+        );
+
+        if repositories.is_empty() {
+            logging::message(Level::Info, "No repositories found".yellow());
+            return Ok(());
+        }
+
+        logging::message(
+            Level::Info,
+            format!(
+                "Switching {} repositories to '{}'...",
+                repositories.len(),
+                self.branch
+            )
+            .green(),
+        );
+
+        let mut results: Vec<(Repository, Result<RepoResult>)> = Vec::new();
+        if context.parallel {
+            let tasks: Vec<_> = repositories
+                .into_iter()
+                .map(|repo| {
+                    let command = self.clone();
+                    tokio::task::spawn_blocking(move || {
+                        let result =
+                            events::track(&repo.name, || command.checkout_repository(&repo));
+                        (repo, result)
+                    })
+                })
+                .collect();
+
+            for task in tasks {
+                results.push(task.await?);
+            }
+        } else {
+            for repo in repositories {
+                let result = events::track(&repo.name, || self.checkout_repository(&repo));
+                results.push((repo, result));
+            }
+        }
+
+        let mut switched = 0;
+        let mut missing = Vec::new();
+        let mut dirty = Vec::new();
+        let mut conflicts = Vec::new();
+        let mut errors = Vec::new();
+        for (repo, result) in results {
+            match result {
+                Ok(RepoResult::AlreadyOn) => {
+                    logging::status(
+                        Level::Info,
+                        &repo.name,
+                        format!("Already on '{}'", self.branch).green(),
+                    );
+                    switched += 1;
+                }
+                Ok(RepoResult::Switched) => {
+                    logging::status(
+                        Level::Info,
+                        &repo.name,
+                        format!("Switched to '{}'", self.branch).green(),
+                    );
+                    switched += 1;
+                }
+                Ok(RepoResult::Created { from }) => {
+                    logging::status(
+                        Level::Info,
+                        &repo.name,
+                        format!("Created '{}' from {}", self.branch, from).green(),
+                    );
+                    switched += 1;
+                }
+                Ok(RepoResult::Missing) => {
+                    logging::status(
+                        Level::Warn,
+                        &repo.name,
+                        format!("No branch '{}'", self.branch).yellow(),
+                    );
+                    missing.push(repo.name);
+                }
+                Ok(RepoResult::Dirty(changes)) => {
+                    logging::status(
+                        Level::Warn,
+                        &repo.name,
+                        format!("{} files with uncommitted changes; not switched", changes)
+                            .yellow(),
+                    );
+                    dirty.push(repo.name);
+                }
+                Ok(RepoResult::Conflicts(files)) => {
+                    logging::status(
+                        Level::Warn,
+                        &repo.name,
+                        format!("Conflicts; not switched: {}", files.join(", ")).yellow(),
+                    );
+                    conflicts.push(repo.name);
+                }
+                Err(e) => {
+                    logging::status(Level::Error, &repo.name, format!("Error: {e}").red());
+                    errors.push((repo.name, e));
+                }
+            }
+        }
+
+        logging::message(
+            Level::Info,
+            format!(
+                "On '{}' {}, without the branch {}, dirty {}, conflicts {}, failed {}",
+                self.branch,
+                switched,
+                missing.len(),
+                dirty.len(),
+                conflicts.len(),
+                errors.len()
+            )
+            .bold(),
+        );
+        list_group(&format!("Without branch '{}'", self.branch), &missing);
+        list_group("Uncommitted changes", &dirty);
+        list_group("Conflicts", &conflicts);
+        if !missing.is_empty() && !self.create {
+            logging::message(
+                Level::Info,
+                "Re-run with --create to create the branch from the default branch".yellow(),
+            );
+        }
+
+        if let Some((repo_name, e)) = errors.first() {
+            anyhow::bail!(
+                "Failed to switch {} repositories. First error ({}): {}",
+                errors.len(),
+                repo_name,
+                e
+            );
+        }
+        let blocked = dirty.len() + conflicts.len();
+        if blocked > 0 {
+            anyhow::bail!(
+                "{} repositories could not be switched to '{}'",
+                blocked,
+                self.branch
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Arguments of `repos checkout`
+#[derive(Debug, Args)]
+pub struct CheckoutArgs {
+    /// Branch to switch to
+    pub branch: String,
+
+    /// Specific repository names to switch (if not provided, uses tag filter or all repos)
+    pub repos: Vec<String>,
+
+    /// Create the branch from the default branch where it does not exist
+    #[arg(long)]
+    pub create: bool,
+
+    /// Fetch origin first so branches pushed by others are found
+    #[arg(long)]
+    pub fetch: bool,
+
+    /// Configuration file path (can be specified multiple times to combine fleets)
+    #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+    pub config: Vec<String>,
+
+    /// Filter repositories by tag (can be specified multiple times)
+    #[arg(short, long)]
+    pub tag: Vec<String>,
+
+    /// Exclude repositories with these tags (can be specified multiple times)
+    #[arg(short = 'e', long)]
+    pub exclude_tag: Vec<String>,
+
+    /// Execute operations in parallel
+    #[arg(short, long)]
+    pub parallel: bool,
+}
+
+impl CheckoutArgs {
+    /// Validate the arguments and run `repos checkout`
+    pub async fn run(self, globals: &GlobalOptions) -> Result<()> {
+        let Self {
+            branch,
+            repos,
+            create,
+            fetch,
+            config,
+            tag,
+            exclude_tag,
+            parallel,
+        } = self;
+        let config = Config::load_all(&config)?;
+
+        validators::validate_tag_filters(&tag)?;
+        validators::validate_tag_filters(&exclude_tag)?;
+        validators::validate_repository_names(&repos)?;
+
+        let context = globals
+            .context(config, tag, exclude_tag, repos)
+            .with_parallel(parallel);
+        CheckoutCommand {
+            branch,
+            create,
+            fetch,
+        }
+        .execute(&context)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command as ProcessCommand;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = ProcessCommand::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    fn repository(temp_dir: &TempDir) -> Repository {
+        let dir = temp_dir.path();
+        git(dir, &["init", "-q", "-b", "main"]);
+        git(dir, &["config", "user.email", "test@example.com"]);
+        git(dir, &["config", "user.name", "Test"]);
+        std::fs::write(dir.join("README.md"), "hello\n").unwrap();
+        git(dir, &["add", "."]);
+        git(dir, &["commit", "-q", "-m", "Initial commit"]);
+
+        let mut repo = Repository::new("api".to_string(), "git@github.com:org/api.git".to_string());
+        repo.path = Some(dir.to_string_lossy().to_string());
+        repo.default_branch = Some("main".to_string());
+        repo
+    }
+
+    fn command(branch: &str, create: bool) -> CheckoutCommand {
+        CheckoutCommand {
+            branch: branch.to_string(),
+            create,
+            fetch: false,
+        }
+    }
+
+    #[test]
+    fn test_checkout_outcomes() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = repository(&temp_dir);
+
+        assert_eq!(
+            command("feature/login", false)
+                .checkout_repository(&repo)
+                .unwrap(),
+            RepoResult::Missing
+        );
+        assert_eq!(
+            command("feature/login", true)
+                .checkout_repository(&repo)
+                .unwrap(),
+            RepoResult::Created {
+                from: "main".to_string()
+            }
+        );
+        assert_eq!(
+            command("feature/login", false)
+                .checkout_repository(&repo)
+                .unwrap(),
+            RepoResult::AlreadyOn
+        );
+        assert_eq!(
+            command("main", false).checkout_repository(&repo).unwrap(),
+            RepoResult::Switched
+        );
+
+        std::fs::write(temp_dir.path().join("README.md"), "changed\n").unwrap();
+        assert!(matches!(
+            command("feature/login", false)
+                .checkout_repository(&repo)
+                .unwrap(),
+            RepoResult::Dirty(_)
+        ));
+    }
+
+    #[test]
+    fn test_untracked_files_in_the_way() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = repository(&temp_dir);
+        let dir = temp_dir.path();
+        git(dir, &["checkout", "-q", "-b", "feature/docs"]);
+        std::fs::write(dir.join("NOTES.md"), "tracked\n").unwrap();
+        git(dir, &["add", "."]);
+        git(dir, &["commit", "-q", "-m", "Add notes"]);
+        git(dir, &["checkout", "-q", "main"]);
+        std::fs::write(dir.join("NOTES.md"), "untracked\n").unwrap();
+
+        assert_eq!(
+            command("feature/docs", false)
+                .checkout_repository(&repo)
+                .unwrap(),
+            RepoResult::Conflicts(vec!["NOTES.md".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_missing_repository() {
+        let mut repo = Repository::new("api".to_string(), "git@github.com:org/api.git".to_string());
+        repo.path = Some("/nonexistent/repos/api".to_string());
+
+        assert!(command("main", false).checkout_repository(&repo).is_err());
+    }
+}
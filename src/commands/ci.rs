@@ -0,0 +1,488 @@
+//! CI command implementation
+
+use super::{Command, CommandContext, GlobalOptions, validators};
+use crate::config::{Config, Repository};
+use crate::credentials::Credentials;
+use crate::{constants, logging};
+use anyhow::Result;
+use async_trait::async_trait;
+use clap::Args;
+use colored::*;
+use futures::StreamExt;
+use log::Level;
+use repos_github::{GitHubClient, WorkflowRun, parse_github_url};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::time::Duration;
+
+/// Repositories queried at the same time
+const CONCURRENCY: usize = 8;
+
+/// Overall CI state of a branch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CiHealth {
+    Success,
+    Pending,
+    Failure,
+    /// No workflow runs on the branch
+    None,
+    /// GitHub could not be queried
+    Error,
+}
+
+impl CiHealth {
+    fn label(&self) -> ColoredString {
+        match self {
+            CiHealth::Success => "success".green(),
+            CiHealth::Pending => "pending".yellow(),
+            CiHealth::Failure => "failure".red(),
+            CiHealth::None => "-".normal(),
+            CiHealth::Error => "error".red(),
+        }
+    }
+}
+
+/// Latest run of one workflow
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkflowResult {
+    pub workflow: String,
+    pub status: Option<String>,
+    pub conclusion: Option<String>,
+    pub url: String,
+    pub updated_at: String,
+}
+
+impl WorkflowResult {
+    fn from_run(run: &WorkflowRun) -> Self {
+        Self {
+            workflow: run
+                .name
+                .clone()
+                .unwrap_or_else(|| format!("workflow {}", run.workflow_id)),
+            status: run.status.clone(),
+            conclusion: run.conclusion.clone(),
+            url: run.html_url.clone(),
+            updated_at: run.updated_at.clone(),
+        }
+    }
+
+    pub fn is_failure(&self) -> bool {
+        matches!(
+            self.conclusion.as_deref(),
+            Some("failure" | "timed_out" | "cancelled" | "action_required" | "startup_failure")
+        )
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.status.as_deref() != Some("completed")
+    }
+}
+
+/// CI state of one repository's branch
+#[derive(Debug, Clone, Serialize)]
+pub struct RepoCiStatus {
+    pub repository: String,
+    pub branch: Option<String>,
+    pub state: CiHealth,
+    /// Latest run of each workflow, newest first
+    pub workflows: Vec<WorkflowResult>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl RepoCiStatus {
+    /// Summarise the runs of a branch, newest first, by the latest run of each workflow
+    pub fn from_runs(repository: &str, branch: &str, runs: &[WorkflowRun]) -> Self {
+        let mut seen = HashSet::new();
+        let workflows: Vec<WorkflowResult> = runs
+            .iter()
+            .filter(|run| seen.insert(run.workflow_id))
+            .map(WorkflowResult::from_run)
+            .collect();
+
+        let state = if workflows.iter().any(WorkflowResult::is_failure) {
+            CiHealth::Failure
+        } else if workflows.iter().any(WorkflowResult::is_pending) {
+            CiHealth::Pending
+        } else if workflows.is_empty() {
+            CiHealth::None
+        } else {
+            CiHealth::Success
+        };
+
+        Self {
+            repository: repository.to_string(),
+            branch: Some(branch.to_string()),
+            state,
+            workflows,
+            error: None,
+        }
+    }
+
+    fn failed(repository: &str, error: anyhow::Error) -> Self {
+        Self {
+            repository: repository.to_string(),
+            branch: None,
+            state: CiHealth::Error,
+            workflows: Vec::new(),
+            error: Some(error.to_string()),
+        }
+    }
+}
+
+/// CI command reporting the latest GitHub Actions runs of each repository
+pub struct CiCommand {
+    pub token: Option<String>,
+    /// Branch to report on instead of each repository's default branch
+    pub branch: Option<String>,
+    pub json: bool,
+    /// Poll again at this interval until interrupted
+    pub watch: Option<Duration>,
+}
+
+impl CiCommand {
+    async fn fetch(&self, credentials: &Credentials, repo: &Repository) -> Result<RepoCiStatus> {
+        let (owner, name) = parse_github_url(&repo.url)?;
+        let client = GitHubClient::new(credentials.token_for_repository(repo));
+        let branch = match &self.branch {
+            Some(branch) => branch.clone(),
+            None => client
+                .get_repository_details(&owner, &name)
+                .await?
+                .default_branch
+                .ok_or_else(|| anyhow::anyhow!("GitHub did not report a default branch"))?,
+        };
+        let runs = client.list_workflow_runs(&owner, &name, &branch).await?;
+        Ok(RepoCiStatus::from_runs(&repo.name, &branch, &runs))
+    }
+
+    async fn fetch_all(
+        &self,
+        credentials: &Credentials,
+        repositories: &[Repository],
+    ) -> Vec<RepoCiStatus> {
+        let queries: Vec<_> = repositories
+            .iter()
+            .map(|repo| async move {
+                match self.fetch(credentials, repo).await {
+                    Ok(status) => status,
+                    Err(e) => RepoCiStatus::failed(&repo.name, e),
+                }
+            })
+            .collect();
+        futures::stream::iter(queries)
+            .buffered(CONCURRENCY)
+            .collect()
+            .await
+    }
+
+    fn print(&self, statuses: &[RepoCiStatus]) -> Result<()> {
+        if self.json {
+            // One document per poll while watching, so the output can be streamed
+            if self.watch.is_some() {
+                println!("{}", serde_json::to_string(statuses)?);
+            } else {
+                println!("{}", serde_json::to_string_pretty(statuses)?);
+            }
+        } else {
+            if self.watch.is_some() {
+                // Clear the screen and move to the top left corner
+                print!("\x1b[2J\x1b[H");
+            }
+            print_table(statuses);
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Command for CiCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let (repositories, other): (Vec<Repository>, Vec<Repository>) = context
+            .config
+            .filter_repositories(&context.tag, &context.exclude_tag, context.repos.as_deref())
+            .into_iter()
+            .partition(|repo| repo.provider().is_github());
+
+        if !other.is_empty() {
+            logging::message(
+                Level::Warn,
+                format!("Skipping {} repositories not hosted on GitHub", other.len()).yellow(),
+            );
+        }
+        if repositories.is_empty() {
+            logging::message(Level::Info, "No GitHub repositories found".yellow());
+            return Ok(());
+        }
+
+        let credentials = Credentials::load(self.token.clone());
+        let Some(interval) = self.watch else {
+            let statuses = self.fetch_all(&credentials, &repositories).await;
+            self.print(&statuses)?;
+            let failing = statuses
+                .iter()
+                .filter(|status| matches!(status.state, CiHealth::Failure | CiHealth::Error))
+                .count();
+            if failing > 0 {
+                anyhow::bail!("CI is failing or unknown for {} repositories", failing);
+            }
+            return Ok(());
+        };
+
+        loop {
+            let statuses = self.fetch_all(&credentials, &repositories).await;
+            self.print(&statuses)?;
+            if !self.json {
+                println!(
+                    "\n{}",
+                    format!("Refreshing every {}s (Ctrl+C to stop)", interval.as_secs()).dimmed()
+                );
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(interval) => {}
+                _ = tokio::signal::ctrl_c() => return Ok(()),
+            }
+        }
+    }
+}
+
+/// Print one row per repository, followed by links to failed runs
+fn print_table(statuses: &[RepoCiStatus]) {
+    let name_width = statuses
+        .iter()
+        .map(|s| s.repository.len())
+        .max()
+        .unwrap_or(0)
+        .max("REPOSITORY".len());
+    let branch_width = statuses
+        .iter()
+        .filter_map(|s| s.branch.as_ref().map(String::len))
+        .max()
+        .unwrap_or(0)
+        .max("BRANCH".len());
+
+    println!();
+    println!(
+        "{:<name_width$}  {:<branch_width$}  {:<7}  {:<9}  {}",
+        "REPOSITORY".bold(),
+        "BRANCH".bold(),
+        "CI".bold(),
+        "WORKFLOWS".bold(),
+        "UPDATED".bold(),
+    );
+    for status in statuses {
+        let passing = status
+            .workflows
+            .iter()
+            .filter(|w| !w.is_failure() && !w.is_pending())
+            .count();
+        let workflows = if status.workflows.is_empty() {
+            "-".to_string()
+        } else {
+            format!("{}/{} ok", passing, status.workflows.len())
+        };
+        let updated = status
+            .workflows
+            .iter()
+            .map(|w| w.updated_at.as_str())
+            .max()
+            .and_then(|updated| chrono::DateTime::parse_from_rfc3339(updated).ok())
+            .map(|updated| {
+                updated
+                    .with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string()
+            })
+            .unwrap_or_else(|| "-".to_string());
+        println!(
+            "{:<name_width$}  {:<branch_width$}  {:<7}  {:<9}  {}",
+            status.repository.cyan(),
+            status.branch.as_deref().unwrap_or("-"),
+            status.state.label(),
+            workflows,
+            updated,
+        );
+    }
+
+    let failures: Vec<String> = statuses
+        .iter()
+        .flat_map(|status| {
+            let errors = status
+                .error
+                .iter()
+                .map(move |e| format!("  {}  {}", status.repository.cyan(), e.red()));
+            let runs = status
+                .workflows
+                .iter()
+                .filter(|w| w.is_failure())
+                .map(move |w| {
+                    format!(
+                        "  {}  {} ({})  {}",
+                        status.repository.cyan(),
+                        w.workflow,
+                        w.conclusion.as_deref().unwrap_or_default().red(),
+                        w.url
+                    )
+                });
+            errors.chain(runs)
+        })
+        .collect();
+    if !failures.is_empty() {
+        println!("\n{}", "Failures:".bold());
+        for failure in failures {
+            println!("{}", failure);
+        }
+    }
+
+    let count = |state: CiHealth| statuses.iter().filter(|s| s.state == state).count();
+    println!(
+        "\n{} passing, {} failing, {} pending, {} without runs{}",
+        count(CiHealth::Success).to_string().green(),
+        count(CiHealth::Failure).to_string().red(),
+        count(CiHealth::Pending).to_string().yellow(),
+        count(CiHealth::None),
+        match count(CiHealth::Error) {
+            0 => String::new(),
+            errors => format!(", {} errors", errors),
+        }
+    );
+}
+
+/// Arguments of `repos ci`
+#[derive(Debug, Args)]
+pub struct CiArgs {
+    /// Specific repository names to check (if not provided, uses tag filter or all repos)
+    pub repos: Vec<String>,
+
+    /// Branch to check instead of each repository's default branch
+    #[arg(short, long)]
+    pub branch: Option<String>,
+
+    /// Output in JSON format for machine consumption
+    #[arg(long)]
+    pub json: bool,
+
+    /// Poll again every SECONDS (default: 30) until interrupted
+    #[arg(long, value_name = "SECONDS", num_args = 0..=1, default_missing_value = "30")]
+    pub watch: Option<u64>,
+
+    /// GitHub token
+    #[arg(long)]
+    pub token: Option<String>,
+
+    /// Configuration file path (can be specified multiple times to combine fleets)
+    #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+    pub config: Vec<String>,
+
+    /// Filter repositories by tag (can be specified multiple times)
+    #[arg(short, long)]
+    pub tag: Vec<String>,
+
+    /// Exclude repositories with these tags (can be specified multiple times)
+    #[arg(short = 'e', long)]
+    pub exclude_tag: Vec<String>,
+}
+
+impl CiArgs {
+    /// Validate the arguments and run `repos ci`
+    pub async fn run(self, globals: &GlobalOptions) -> Result<()> {
+        let Self {
+            repos,
+            branch,
+            json,
+            watch,
+            token,
+            config,
+            tag,
+            exclude_tag,
+        } = self;
+        let config = Config::load_all(&config)?;
+
+        validators::validate_tag_filters(&tag)?;
+        validators::validate_tag_filters(&exclude_tag)?;
+        validators::validate_repository_names(&repos)?;
+
+        if watch == Some(0) {
+            anyhow::bail!("--watch interval must be at least one second");
+        }
+
+        let token = token.or_else(|| config.resolve_profile_token());
+        let context = globals.context(config, tag, exclude_tag, repos);
+        CiCommand {
+            token,
+            branch,
+            json,
+            watch: watch.map(Duration::from_secs),
+        }
+        .execute(&context)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(workflow_id: u64, status: &str, conclusion: Option<&str>) -> WorkflowRun {
+        serde_json::from_value(serde_json::json!({
+            "id": workflow_id * 10,
+            "workflow_id": workflow_id,
+            "name": format!("workflow-{}", workflow_id),
+            "head_branch": "main",
+            "head_sha": "abc",
+            "event": "push",
+            "status": status,
+            "conclusion": conclusion,
+            "html_url": format!("https://github.com/org/api/actions/runs/{}", workflow_id),
+            "updated_at": "2024-05-01T12:00:00Z",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_latest_run_per_workflow_decides() {
+        // Newest first: workflow 1 recovered after an earlier failure
+        let runs = [
+            run(1, "completed", Some("success")),
+            run(2, "completed", Some("skipped")),
+            run(1, "completed", Some("failure")),
+        ];
+        let status = RepoCiStatus::from_runs("api", "main", &runs);
+        assert_eq!(status.state, CiHealth::Success);
+        assert_eq!(status.workflows.len(), 2);
+
+        let runs = [
+            run(1, "in_progress", None),
+            run(2, "completed", Some("timed_out")),
+        ];
+        assert_eq!(
+            RepoCiStatus::from_runs("api", "main", &runs).state,
+            CiHealth::Failure
+        );
+
+        let runs = [run(1, "queued", None), run(2, "completed", Some("success"))];
+        assert_eq!(
+            RepoCiStatus::from_runs("api", "main", &runs).state,
+            CiHealth::Pending
+        );
+        assert_eq!(
+            RepoCiStatus::from_runs("api", "main", &[]).state,
+            CiHealth::None
+        );
+    }
+
+    #[test]
+    fn test_json_output() {
+        let status =
+            RepoCiStatus::from_runs("api", "main", &[run(1, "completed", Some("failure"))]);
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(json["state"], "failure");
+        assert_eq!(json["workflows"][0]["workflow"], "workflow-1");
+        assert_eq!(
+            json["workflows"][0]["url"],
+            "https://github.com/org/api/actions/runs/1"
+        );
+        assert!(json.get("error").is_none());
+    }
+}
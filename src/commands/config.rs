@@ -0,0 +1,646 @@
+//! Config command implementation
+
+use super::{Command, CommandContext, GlobalOptions, validators};
+use crate::config::edit::set_repository_url;
+use crate::config::export::{ExportFormat, export};
+use crate::config::schema::validate_config_source;
+use crate::config::{Config, Repository};
+use crate::credentials::Credentials;
+use crate::git::{self, RemoteAccess, RemoteChange};
+use crate::github::api::cache_archived;
+use crate::state::StateStore;
+use crate::{constants, logging};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use clap::Subcommand;
+use colored::*;
+use log::Level;
+use repos_github::{GitHubClient, GitHubRepo, parse_github_url};
+use std::path::{Path, PathBuf};
+
+/// Config validate command for checking a config file against the strict schema
+pub struct ConfigValidateCommand {
+    pub path: String,
+}
+
+#[async_trait]
+impl Command for ConfigValidateCommand {
+    async fn execute(&self, _context: &CommandContext) -> Result<()> {
+        let source = std::fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read config file '{}'", self.path))?;
+
+        let issues = validate_config_source(&source);
+        if issues.is_empty() {
+            println!("{} {}", self.path.bold(), "is valid".green());
+            return Ok(());
+        }
+
+        for issue in &issues {
+            println!("{}:{}", self.path.bold(), issue.to_string().red());
+        }
+
+        anyhow::bail!("Found {} problem(s) in '{}'", issues.len(), self.path)
+    }
+}
+
+/// Config export command rendering the selected repositories for other systems
+pub struct ConfigExportCommand {
+    pub format: ExportFormat,
+    /// File to write instead of stdout
+    pub output: Option<PathBuf>,
+}
+
+#[async_trait]
+impl Command for ConfigExportCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.config.filter_repositories(
+            &context.tag,
+            &context.exclude_tag,
+            context.repos.as_deref(),
+        );
+        let rendered = export(&repositories, self.format)?;
+
+        match &self.output {
+            Some(path) => {
+                std::fs::write(path, rendered)
+                    .with_context(|| format!("Failed to write '{}'", path.display()))?;
+                logging::message(
+                    Level::Info,
+                    format!(
+                        "Exported {} repositories to '{}'",
+                        repositories.len(),
+                        path.display()
+                    )
+                    .green(),
+                );
+            }
+            None => print!("{}", rendered),
+        }
+        Ok(())
+    }
+}
+
+/// Config reconcile command catching up with repositories renamed, moved, archived or deleted on GitHub
+pub struct ConfigReconcileCommand {
+    /// Config file the new URLs are written to
+    pub config_path: PathBuf,
+    /// Write the new URLs instead of only showing them
+    pub apply: bool,
+    /// Also point the remotes of existing clones at the new URLs
+    pub update_remotes: bool,
+    pub token: Option<String>,
+}
+
+/// How a repository on GitHub differs from its entry in the config
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RepositoryDrift {
+    /// Renamed or transferred; GitHub redirects `url` to `full_name`
+    Moved {
+        full_name: String,
+        url: String,
+    },
+    Archived,
+    /// Deleted, or no longer visible to the token
+    Missing,
+}
+
+impl std::fmt::Display for RepositoryDrift {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RepositoryDrift::Moved { full_name, url } => {
+                write!(f, "moved to {} ({})", full_name, url)
+            }
+            RepositoryDrift::Archived => f.write_str("archived"),
+            RepositoryDrift::Missing => f.write_str("deleted or not accessible"),
+        }
+    }
+}
+
+/// `url` pointing at `full_name` instead, keeping its protocol, host and suffix
+pub fn moved_url(url: &str, full_name: &str) -> Result<String> {
+    let (owner, name) = parse_github_url(url)?;
+    let old_path = format!("{}/{}", owner, name);
+    let position = url
+        .rfind(&old_path)
+        .with_context(|| format!("Cannot find '{}' in URL {}", old_path, url))?;
+    Ok(format!(
+        "{}{}{}",
+        &url[..position],
+        full_name,
+        &url[position + old_path.len()..]
+    ))
+}
+
+/// Compare the URL of a repository with what GitHub reports for it
+///
+/// `found` is `None` when GitHub does not know the repository.
+pub fn repository_drift(url: &str, found: Option<&GitHubRepo>) -> Result<Vec<RepositoryDrift>> {
+    let Some(details) = found else {
+        return Ok(vec![RepositoryDrift::Missing]);
+    };
+
+    let mut drift = Vec::new();
+    let (owner, name) = parse_github_url(url)?;
+    if let Some(full_name) = &details.full_name
+        && !full_name.eq_ignore_ascii_case(&format!("{}/{}", owner, name))
+    {
+        drift.push(RepositoryDrift::Moved {
+            full_name: full_name.clone(),
+            url: moved_url(url, full_name)?,
+        });
+    }
+    if details.archived {
+        drift.push(RepositoryDrift::Archived);
+    }
+    Ok(drift)
+}
+
+/// Look a GitHub repository up and compare it with its entry in the config
+async fn github_drift(
+    repo: &Repository,
+    credentials: &Credentials,
+) -> Result<Vec<RepositoryDrift>> {
+    let (owner, name) = parse_github_url(&repo.url)?;
+    let client = GitHubClient::new(credentials.token_for_repository(repo));
+    let found = client.find_repository(&owner, &name).await?;
+    if let Some(details) = &found {
+        cache_archived(&StateStore::open_default(), repo, details.archived);
+    }
+    repository_drift(&repo.url, found.as_ref())
+}
+
+impl ConfigReconcileCommand {
+    /// Point the remote of a clone that tracks `url` at the repository's new URL
+    fn update_remote(repo: &Repository, url: &str) -> Result<Option<RemoteChange>> {
+        let repo_path = repo.get_target_dir();
+        if !Path::new(&repo_path).exists() {
+            return Ok(None);
+        }
+
+        let mut moved = repo.clone();
+        moved.url = url.to_string();
+        let name = git::url_remote_name(repo);
+        let to = RemoteAccess::for_repository(&moved, None).remote_url(url);
+        let change = match git::list_remotes(&repo_path)?.get(name) {
+            Some(from) if *from == to => return Ok(None),
+            Some(from) => RemoteChange::SetUrl {
+                name: name.to_string(),
+                from: from.clone(),
+                to,
+            },
+            None => RemoteChange::Add {
+                name: name.to_string(),
+                url: to,
+            },
+        };
+        git::apply_remote_change(&repo_path, &change)?;
+        Ok(Some(change))
+    }
+
+    /// Write the new URLs to the config file and, if asked, the remotes of the clones
+    fn apply_moves(
+        &self,
+        moves: &[(&Repository, String)],
+        errors: &mut Vec<(String, anyhow::Error)>,
+    ) -> Result<()> {
+        let path = &self.config_path;
+        let mut source = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+        for (repo, url) in moves {
+            source = set_repository_url(&source, &repo.name, url)
+                .with_context(|| format!("Failed to update {}", path.display()))?;
+        }
+        std::fs::write(path, source)
+            .with_context(|| format!("Failed to write config file {}", path.display()))?;
+        logging::message(
+            Level::Info,
+            format!(
+                "Updated the URLs of {} repositories in {}",
+                moves.len(),
+                path.display()
+            )
+            .bold(),
+        );
+
+        if !self.update_remotes {
+            return Ok(());
+        }
+        for (repo, url) in moves {
+            match Self::update_remote(repo, url) {
+                Ok(Some(change)) => logging::status(
+                    Level::Info,
+                    &repo.name,
+                    format!("Updated remote {}", change).green(),
+                ),
+                Ok(None) => {}
+                Err(e) => {
+                    logging::status(Level::Error, &repo.name, format!("Error: {e}").red());
+                    errors.push((repo.name.clone(), e));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Command for ConfigReconcileCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.config.filter_repositories(
+            &context.tag,
+            &context.exclude_tag,
+            context.repos.as_deref(),
+        );
+        if repositories.is_empty() {
+            logging::message(Level::Info, "No repositories found".yellow());
+            return Ok(());
+        }
+
+        logging::message(
+            Level::Info,
+            format!(
+                "Checking {} repositories against GitHub...",
+                repositories.len()
+            )
+            .green(),
+        );
+
+        let credentials = Credentials::load(self.token.clone());
+        let mut moves = Vec::new();
+        let mut archived = Vec::new();
+        let mut missing = Vec::new();
+        let mut errors = Vec::new();
+        for repo in &repositories {
+            if !repo.provider().is_github() {
+                logging::status(
+                    Level::Info,
+                    &repo.name,
+                    format!(
+                        "Skipping: {} repositories are not reconciled",
+                        repo.provider()
+                    )
+                    .dimmed(),
+                );
+                continue;
+            }
+
+            let drift = match github_drift(repo, &credentials).await {
+                Ok(drift) => drift,
+                Err(e) => {
+                    logging::status(Level::Error, &repo.name, format!("Error: {e}").red());
+                    errors.push((repo.name.clone(), e));
+                    continue;
+                }
+            };
+
+            if drift.is_empty() {
+                logging::status(Level::Info, &repo.name, "In sync".dimmed());
+            }
+            for item in drift {
+                logging::status(Level::Warn, &repo.name, item.to_string().yellow());
+                match item {
+                    RepositoryDrift::Moved { url, .. } => moves.push((repo, url)),
+                    RepositoryDrift::Archived => archived.push(repo.name.clone()),
+                    RepositoryDrift::Missing => missing.push(repo.name.clone()),
+                }
+            }
+        }
+
+        if !archived.is_empty() {
+            logging::message(
+                Level::Warn,
+                format!("Archived ({}): {}", archived.len(), archived.join(", ")).yellow(),
+            );
+        }
+        if !missing.is_empty() {
+            logging::message(
+                Level::Warn,
+                format!(
+                    "Deleted or not accessible ({}): {}",
+                    missing.len(),
+                    missing.join(", ")
+                )
+                .yellow(),
+            );
+        }
+
+        if moves.is_empty() {
+            logging::message(Level::Info, "All URLs are up to date".green());
+        } else if !self.apply {
+            logging::message(
+                Level::Info,
+                format!(
+                    "{} repositories moved; run with --apply to update {}",
+                    moves.len(),
+                    self.config_path.display()
+                )
+                .bold(),
+            );
+        } else {
+            self.apply_moves(&moves, &mut errors)?;
+        }
+
+        if let Some((repo_name, e)) = errors.first() {
+            anyhow::bail!(
+                "Failed to reconcile {} repositories. First error ({}): {}",
+                errors.len(),
+                repo_name,
+                e
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Subcommands of `repos config`
+#[derive(Debug, Subcommand)]
+pub enum ConfigAction {
+    /// Validate the config file against the strict schema, reporting every problem with its location
+    Validate {
+        /// Configuration file path
+        #[arg(short, long, default_value_t = constants::config::DEFAULT_CONFIG_FILE.to_string())]
+        config: String,
+    },
+
+    /// Export the repositories as a Google repo manifest, JSON or a Markdown table
+    Export {
+        /// Specific repository names to export (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Output format: repo-manifest, json or markdown
+        #[arg(short, long, default_value = "repo-manifest")]
+        format: String,
+
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Configuration file path (can be specified multiple times to combine fleets)
+        #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+        config: Vec<String>,
+
+        /// Filter repositories by tag (can be specified multiple times)
+        #[arg(short, long)]
+        tag: Vec<String>,
+
+        /// Exclude repositories with these tags (can be specified multiple times)
+        #[arg(short = 'e', long)]
+        exclude_tag: Vec<String>,
+    },
+
+    /// Find repositories renamed, transferred, archived or deleted on GitHub, showing the new URLs
+    Reconcile {
+        /// Specific repository names to check (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Write the new URLs to the config file, keeping its comments and layout
+        #[arg(long)]
+        apply: bool,
+
+        /// Also point the remotes of existing clones at the new URLs
+        #[arg(long, requires = "apply")]
+        update_remotes: bool,
+
+        /// GitHub token
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Configuration file path
+        #[arg(short, long, default_value_t = constants::config::DEFAULT_CONFIG_FILE.to_string())]
+        config: String,
+
+        /// Filter repositories by tag (can be specified multiple times)
+        #[arg(short, long)]
+        tag: Vec<String>,
+
+        /// Exclude repositories with these tags (can be specified multiple times)
+        #[arg(short = 'e', long)]
+        exclude_tag: Vec<String>,
+    },
+}
+
+impl ConfigAction {
+    /// Validate the arguments and run `repos config <action>`
+    pub async fn run(self, globals: &GlobalOptions) -> Result<()> {
+        match self {
+            ConfigAction::Validate { config } => {
+                // Validation works on the raw file, so the config is not loaded here
+                let context = CommandContext::new(Config::new());
+                ConfigValidateCommand { path: config }
+                    .execute(&context)
+                    .await?;
+            }
+            ConfigAction::Export {
+                repos,
+                format,
+                output,
+                config,
+                tag,
+                exclude_tag,
+            } => {
+                let config = Config::load_all(&config)?;
+
+                validators::validate_tag_filters(&tag)?;
+                validators::validate_tag_filters(&exclude_tag)?;
+                validators::validate_repository_names(&repos)?;
+
+                let context = globals.context(config, tag, exclude_tag, repos);
+                ConfigExportCommand {
+                    format: format.parse()?,
+                    output,
+                }
+                .execute(&context)
+                .await?;
+            }
+            ConfigAction::Reconcile {
+                repos,
+                apply,
+                update_remotes,
+                token,
+                config,
+                tag,
+                exclude_tag,
+            } => {
+                let config_path = crate::config::profile::editable_path(&config);
+                let config = Config::load(&config)?;
+
+                validators::validate_tag_filters(&tag)?;
+                validators::validate_tag_filters(&exclude_tag)?;
+                validators::validate_repository_names(&repos)?;
+
+                let token = token.or_else(|| config.resolve_profile_token());
+                let context = globals.context(config, tag, exclude_tag, repos);
+
+                ConfigReconcileCommand {
+                    config_path,
+                    apply,
+                    update_remotes,
+                    token,
+                }
+                .execute(&context)
+                .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use tempfile::TempDir;
+
+    fn context() -> CommandContext {
+        CommandContext {
+            config: Config::new(),
+            tag: vec![],
+            exclude_tag: vec![],
+            parallel: false,
+            repos: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_validate_valid_config() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("repos.yaml");
+        std::fs::write(
+            &path,
+            "repositories:\n  - name: api\n    url: git@github.com:org/api.git\n    tags: []\n",
+        )
+        .unwrap();
+
+        let command = ConfigValidateCommand {
+            path: path.to_string_lossy().to_string(),
+        };
+        assert!(command.execute(&context()).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_validate_reports_problems() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("repos.yaml");
+        std::fs::write(
+            &path,
+            "repositories:\n  - name: api\n    url: not-a-url\n    tgas: [x]\n",
+        )
+        .unwrap();
+
+        let command = ConfigValidateCommand {
+            path: path.to_string_lossy().to_string(),
+        };
+        let error = command.execute(&context()).await.unwrap_err();
+        assert!(error.to_string().contains("Found 2 problem(s)"));
+    }
+
+    #[tokio::test]
+    async fn test_validate_missing_file() {
+        let command = ConfigValidateCommand {
+            path: "/nonexistent/repos.yaml".to_string(),
+        };
+        assert!(command.execute(&context()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_export_writes_selected_repositories() {
+        let temp_dir = TempDir::new().unwrap();
+        let output = temp_dir.path().join("fleet.md");
+        let mut context = context();
+        context.config.repositories = vec![
+            crate::config::Repository::new(
+                "api".to_string(),
+                "git@github.com:org/api.git".to_string(),
+            ),
+            crate::config::Repository::new(
+                "web".to_string(),
+                "git@github.com:org/web.git".to_string(),
+            ),
+        ];
+        context.repos = Some(vec!["web".to_string()]);
+
+        ConfigExportCommand {
+            format: ExportFormat::Markdown,
+            output: Some(output.clone()),
+        }
+        .execute(&context)
+        .await
+        .unwrap();
+
+        let markdown = std::fs::read_to_string(output).unwrap();
+        assert!(markdown.contains("| web |"));
+        assert!(!markdown.contains("| api |"));
+    }
+
+    fn details(json: serde_json::Value) -> GitHubRepo {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_moved_url_keeps_protocol_and_suffix() {
+        assert_eq!(
+            moved_url("git@github.com:org/api.git", "platform/api-server").unwrap(),
+            "git@github.com:platform/api-server.git"
+        );
+        assert_eq!(
+            moved_url("https://github.com/org/api", "platform/api").unwrap(),
+            "https://github.com/platform/api"
+        );
+    }
+
+    #[test]
+    fn test_repository_drift() {
+        let url = "git@github.com:org/api.git";
+        let current = details(serde_json::json!({"full_name": "Org/API"}));
+        assert!(repository_drift(url, Some(&current)).unwrap().is_empty());
+
+        let moved = details(serde_json::json!({"full_name": "platform/api", "archived": true}));
+        assert_eq!(
+            repository_drift(url, Some(&moved)).unwrap(),
+            vec![
+                RepositoryDrift::Moved {
+                    full_name: "platform/api".to_string(),
+                    url: "git@github.com:platform/api.git".to_string(),
+                },
+                RepositoryDrift::Archived,
+            ]
+        );
+
+        assert_eq!(
+            repository_drift(url, None).unwrap(),
+            vec![RepositoryDrift::Missing]
+        );
+    }
+
+    #[test]
+    fn test_update_remote_of_moved_clone() {
+        let temp_dir = TempDir::new().unwrap();
+        let run = |args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .args(args)
+                .current_dir(temp_dir.path())
+                .output()
+                .unwrap()
+                .status;
+            assert!(status.success());
+        };
+        run(&["init", "-q"]);
+        run(&["remote", "add", "origin", "git@github.com:org/api.git"]);
+
+        let mut repo = Repository::new("api".to_string(), "git@github.com:org/api.git".to_string());
+        repo.path = Some(temp_dir.path().to_string_lossy().to_string());
+
+        let url = "git@github.com:platform/api.git";
+        let change = ConfigReconcileCommand::update_remote(&repo, url).unwrap();
+        assert!(matches!(change, Some(RemoteChange::SetUrl { .. })));
+        let remotes = git::list_remotes(&temp_dir.path().to_string_lossy()).unwrap();
+        assert_eq!(remotes["origin"], url);
+
+        // Nothing left to change the second time
+        repo.url = url.to_string();
+        assert_eq!(
+            ConfigReconcileCommand::update_remote(&repo, url).unwrap(),
+            None
+        );
+    }
+}
@@ -0,0 +1,481 @@
+//! Copy command implementation
+
+use super::{Command, CommandContext, GlobalOptions, validators};
+use crate::config::{Config, Repository};
+use crate::github::placeholders::Placeholders;
+use crate::{constants, logging};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use clap::Args;
+use colored::*;
+use log::Level;
+use std::path::{Component, Path, PathBuf};
+use std::str::FromStr;
+
+/// What to do with files that already exist in a repository with different content
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverwritePolicy {
+    /// Leave the existing file alone
+    #[default]
+    Skip,
+    /// Replace the existing file
+    Overwrite,
+    /// Copy nothing into the repository
+    Fail,
+}
+
+impl FromStr for OverwritePolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "skip" => Ok(OverwritePolicy::Skip),
+            "overwrite" => Ok(OverwritePolicy::Overwrite),
+            "fail" => Ok(OverwritePolicy::Fail),
+            _ => anyhow::bail!(
+                "Unknown overwrite policy '{}' (expected skip, overwrite or fail)",
+                s
+            ),
+        }
+    }
+}
+
+/// Copy command for writing a local file or directory into every repository
+pub struct CopyCommand {
+    /// Local file or directory to copy
+    pub source: PathBuf,
+    /// Path relative to each repository root to copy to
+    pub destination: PathBuf,
+    /// Replace placeholders such as `{{repo.name}}` in text files
+    pub template: bool,
+    pub overwrite: OverwritePolicy,
+    /// Report what would be copied without writing anything
+    pub dry_run: bool,
+}
+
+/// A file to copy, with its path relative to the destination
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceFile {
+    pub path: PathBuf,
+    pub relative: PathBuf,
+}
+
+/// Files copied into one repository, by outcome
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct CopyOutcome {
+    pub created: Vec<PathBuf>,
+    pub overwritten: Vec<PathBuf>,
+    /// Files that already had the same content
+    pub unchanged: Vec<PathBuf>,
+    /// Files that exist with different content and were left alone
+    pub skipped: Vec<PathBuf>,
+}
+
+impl CopyOutcome {
+    fn describe(&self, dry_run: bool) -> String {
+        let (created, overwritten) = if dry_run {
+            ("would create", "would overwrite")
+        } else {
+            ("created", "overwritten")
+        };
+        let mut parts = Vec::new();
+        for (count, label) in [
+            (self.created.len(), created),
+            (self.overwritten.len(), overwritten),
+            (self.unchanged.len(), "unchanged"),
+            (self.skipped.len(), "skipped (exists)"),
+        ] {
+            if count > 0 {
+                parts.push(format!("{} {}", count, label));
+            }
+        }
+        parts.join(", ")
+    }
+}
+
+/// List the files to copy from `source`
+///
+/// A directory is walked recursively, honoring the `.gitignore` and `.ignore`
+/// files inside it; `.git` directories are never copied.
+pub fn collect_source_files(source: &Path) -> Result<Vec<SourceFile>> {
+    if source.is_file() {
+        return Ok(vec![SourceFile {
+            path: source.to_path_buf(),
+            relative: PathBuf::new(),
+        }]);
+    }
+    if !source.is_dir() {
+        anyhow::bail!("Source '{}' does not exist", source.display());
+    }
+
+    let mut files = Vec::new();
+    let walker = ignore::WalkBuilder::new(source)
+        .hidden(false)
+        .git_global(false)
+        // Honor .gitignore files whether or not the source is inside a git repository
+        .require_git(false)
+        .filter_entry(|e| e.file_name() != ".git")
+        .sort_by_file_name(|a, b| a.cmp(b))
+        .build();
+    for entry in walker {
+        let entry = entry?;
+        if entry.file_type().is_some_and(|t| t.is_file()) {
+            files.push(SourceFile {
+                path: entry.path().to_path_buf(),
+                relative: entry.path().strip_prefix(source)?.to_path_buf(),
+            });
+        }
+    }
+
+    if files.is_empty() {
+        anyhow::bail!("Source directory '{}' contains no files", source.display());
+    }
+    Ok(files)
+}
+
+/// Check that a destination stays inside the repository it is copied into
+pub fn validate_destination(destination: &Path) -> Result<()> {
+    let escapes = destination
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir));
+    if escapes || destination.as_os_str().is_empty() {
+        anyhow::bail!(
+            "Destination '{}' must be a path relative to the repository root; use --to to choose one",
+            destination.display()
+        );
+    }
+    Ok(())
+}
+
+impl CopyCommand {
+    fn copy_into(&self, repo: &Repository, files: &[SourceFile]) -> Result<CopyOutcome> {
+        let target_dir = repo.get_target_dir();
+        if !Path::new(&target_dir).is_dir() {
+            anyhow::bail!("Repository directory does not exist: {}", target_dir);
+        }
+
+        // Plan every file first so that the fail policy leaves the repository untouched
+        let mut plan = Vec::new();
+        let mut outcome = CopyOutcome::default();
+        let destination: PathBuf = self
+            .destination
+            .components()
+            .filter(|c| *c != Component::CurDir)
+            .collect();
+        for file in files {
+            let relative = if file.relative.as_os_str().is_empty() {
+                destination.clone()
+            } else {
+                destination.join(&file.relative)
+            };
+            let target = Path::new(&target_dir).join(&relative);
+            let content = self.render(&file.path, repo)?;
+
+            if target.is_dir() {
+                anyhow::bail!(
+                    "'{}' is a directory; use --to to name the file to copy to",
+                    target.display()
+                );
+            }
+            match std::fs::read(&target) {
+                Ok(existing) if existing == content => outcome.unchanged.push(relative),
+                Ok(_) => match self.overwrite {
+                    OverwritePolicy::Skip => outcome.skipped.push(relative),
+                    OverwritePolicy::Fail => anyhow::bail!(
+                        "'{}' already exists with different content; use --overwrite overwrite or skip",
+                        relative.display()
+                    ),
+                    OverwritePolicy::Overwrite => {
+                        outcome.overwritten.push(relative);
+                        plan.push((target, content));
+                    }
+                },
+                Err(_) => {
+                    outcome.created.push(relative);
+                    plan.push((target, content));
+                }
+            }
+        }
+
+        if !self.dry_run {
+            for (target, content) in plan {
+                if let Some(parent) = target.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(&target, content)
+                    .with_context(|| format!("Failed to write '{}'", target.display()))?;
+            }
+        }
+        Ok(outcome)
+    }
+
+    /// Content to write for a source file; binary files are never templated
+    fn render(&self, path: &Path, repo: &Repository) -> Result<Vec<u8>> {
+        let bytes =
+            std::fs::read(path).with_context(|| format!("Failed to read '{}'", path.display()))?;
+        if !self.template {
+            return Ok(bytes);
+        }
+        match String::from_utf8(bytes) {
+            Ok(text) => Ok(Placeholders::default().fill(&text, repo).into_bytes()),
+            Err(e) => Ok(e.into_bytes()),
+        }
+    }
+}
+
+#[async_trait]
+impl Command for CopyCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        validate_destination(&self.destination)?;
+        let files = collect_source_files(&self.source)?;
+
+        let repositories = context.config.filter_repositories(
+            &context.tag,
+            &context.exclude_tag,
+            context.repos.as_deref(),
+        );
+        if repositories.is_empty() {
+            logging::message(Level::Info, "No repositories found".yellow());
+            return Ok(());
+        }
+
+        logging::message(
+            Level::Info,
+            format!(
+                "{} {} file(s) to '{}' in {} repositories...",
+                if self.dry_run {
+                    "Checking copy of"
+                } else {
+                    "Copying"
+                },
+                files.len(),
+                self.destination.display(),
+                repositories.len()
+            )
+            .green(),
+        );
+
+        let mut changed = 0;
+        let mut errors = Vec::new();
+        for repo in &repositories {
+            match self.copy_into(repo, &files) {
+                Ok(outcome) => {
+                    if !outcome.created.is_empty() || !outcome.overwritten.is_empty() {
+                        changed += 1;
+                    }
+                    let message = outcome.describe(self.dry_run);
+                    if outcome.skipped.is_empty() {
+                        logging::status(Level::Info, &repo.name, message.green());
+                    } else {
+                        logging::status(Level::Warn, &repo.name, message.yellow());
+                    }
+                }
+                Err(e) => {
+                    logging::status(Level::Error, &repo.name, format!("Error: {e}").red());
+                    errors.push((repo.name.clone(), e));
+                }
+            }
+        }
+
+        logging::message(
+            Level::Info,
+            format!(
+                "{} {} repositories, failed {}",
+                if self.dry_run {
+                    "Would change"
+                } else {
+                    "Changed"
+                },
+                changed,
+                errors.len()
+            )
+            .bold(),
+        );
+
+        if let Some((repo_name, e)) = errors.first() {
+            anyhow::bail!(
+                "Failed to copy into {} repositories. First error ({}): {}",
+                errors.len(),
+                repo_name,
+                e
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Arguments of `repos cp`
+#[derive(Debug, Args)]
+pub struct CpArgs {
+    /// File or directory to copy; directories honor their .gitignore files
+    pub source: PathBuf,
+
+    /// Specific repository names to copy into (if not provided, uses tag filter or all repos)
+    pub repos: Vec<String>,
+
+    /// Path relative to each repository root to copy to (default: the source path)
+    #[arg(long, value_name = "PATH")]
+    pub to: Option<PathBuf>,
+
+    /// Replace {{repo.name}}, {{repo.url}}, {{repo.owner}}, {{repo.repo}} and {{date}} in copied text files
+    #[arg(long)]
+    pub template: bool,
+
+    /// What to do with existing files that differ
+    #[arg(long, default_value = "skip", value_parser = ["skip", "overwrite", "fail"])]
+    pub overwrite: String,
+
+    /// Show what would be copied without writing anything
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Configuration file path (can be specified multiple times to combine fleets)
+    #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+    pub config: Vec<String>,
+
+    /// Filter repositories by tag (can be specified multiple times)
+    #[arg(short, long)]
+    pub tag: Vec<String>,
+
+    /// Exclude repositories with these tags (can be specified multiple times)
+    #[arg(short = 'e', long)]
+    pub exclude_tag: Vec<String>,
+}
+
+impl CpArgs {
+    /// Validate the arguments and run `repos cp`
+    pub async fn run(self, globals: &GlobalOptions) -> Result<()> {
+        let Self {
+            source,
+            repos,
+            to,
+            template,
+            overwrite,
+            dry_run,
+            config,
+            tag,
+            exclude_tag,
+        } = self;
+        let config = Config::load_all(&config)?;
+
+        validators::validate_tag_filters(&tag)?;
+        validators::validate_tag_filters(&exclude_tag)?;
+        validators::validate_repository_names(&repos)?;
+
+        let mut context = globals.context(config, tag, exclude_tag, repos);
+        context.skip_archived(globals.include_archived);
+
+        CopyCommand {
+            destination: to.unwrap_or_else(|| source.clone()),
+            source,
+            template,
+            overwrite: overwrite.parse()?,
+            dry_run,
+        }
+        .execute(&context)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn repository(temp_dir: &TempDir, name: &str) -> Repository {
+        let repo_dir = temp_dir.path().join(name);
+        fs::create_dir_all(&repo_dir).unwrap();
+        let mut repo = Repository::new(name.to_string(), format!("git@github.com:org/{name}.git"));
+        repo.path = Some(repo_dir.to_string_lossy().to_string());
+        repo
+    }
+
+    fn command(source: &Path, destination: &str, overwrite: OverwritePolicy) -> CopyCommand {
+        CopyCommand {
+            source: source.to_path_buf(),
+            destination: PathBuf::from(destination),
+            template: true,
+            overwrite,
+            dry_run: false,
+        }
+    }
+
+    #[test]
+    fn test_collect_source_files_honors_gitignore() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("workflows");
+        fs::create_dir_all(source.join("node_modules/pkg")).unwrap();
+        fs::write(source.join(".gitignore"), "node_modules/\n*.log\n").unwrap();
+        fs::write(source.join("ci.yml"), "name: ci\n").unwrap();
+        fs::write(source.join("debug.log"), "noise").unwrap();
+        fs::write(source.join("node_modules/pkg/index.js"), "").unwrap();
+
+        let files = collect_source_files(&source).unwrap();
+        let relative: Vec<_> = files.iter().map(|f| f.relative.clone()).collect();
+        assert_eq!(
+            relative,
+            vec![PathBuf::from(".gitignore"), PathBuf::from("ci.yml")]
+        );
+
+        assert!(collect_source_files(&temp_dir.path().join("missing")).is_err());
+    }
+
+    #[test]
+    fn test_validate_destination() {
+        assert!(validate_destination(Path::new(".github/workflows")).is_ok());
+        assert!(validate_destination(Path::new("./CODEOWNERS")).is_ok());
+        assert!(validate_destination(Path::new("../other")).is_err());
+        assert!(validate_destination(Path::new("/etc/passwd")).is_err());
+        assert!(validate_destination(Path::new("")).is_err());
+    }
+
+    #[test]
+    fn test_copy_into_with_templates_and_policies() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("CODEOWNERS");
+        fs::write(&source, "* @org/{{repo}}-owners\n").unwrap();
+        let files = collect_source_files(&source).unwrap();
+        let repo = repository(&temp_dir, "api");
+        let target = Path::new(&repo.get_target_dir()).join(".github/CODEOWNERS");
+
+        let copy = command(&source, ".github/CODEOWNERS", OverwritePolicy::Skip);
+        let outcome = copy.copy_into(&repo, &files).unwrap();
+        assert_eq!(outcome.created, vec![PathBuf::from(".github/CODEOWNERS")]);
+        assert_eq!(fs::read_to_string(&target).unwrap(), "* @org/api-owners\n");
+
+        // Copying again changes nothing
+        let outcome = copy.copy_into(&repo, &files).unwrap();
+        assert_eq!(outcome.unchanged.len(), 1);
+
+        fs::write(&target, "* @someone\n").unwrap();
+        let outcome = copy.copy_into(&repo, &files).unwrap();
+        assert_eq!(outcome.skipped.len(), 1);
+        assert_eq!(fs::read_to_string(&target).unwrap(), "* @someone\n");
+
+        let fail = command(&source, ".github/CODEOWNERS", OverwritePolicy::Fail);
+        assert!(fail.copy_into(&repo, &files).is_err());
+
+        let overwrite = command(&source, ".github/CODEOWNERS", OverwritePolicy::Overwrite);
+        let outcome = overwrite.copy_into(&repo, &files).unwrap();
+        assert_eq!(outcome.overwritten.len(), 1);
+        assert_eq!(fs::read_to_string(&target).unwrap(), "* @org/api-owners\n");
+    }
+
+    #[test]
+    fn test_copy_into_dry_run() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("templates");
+        fs::create_dir_all(&source).unwrap();
+        fs::write(source.join("a.txt"), "a").unwrap();
+        let files = collect_source_files(&source).unwrap();
+        let repo = repository(&temp_dir, "api");
+
+        let mut copy = command(&source, "templates", OverwritePolicy::Skip);
+        copy.dry_run = true;
+        let outcome = copy.copy_into(&repo, &files).unwrap();
+        assert_eq!(outcome.created, vec![PathBuf::from("templates/a.txt")]);
+        assert!(!Path::new(&repo.get_target_dir()).join("templates").exists());
+        assert_eq!(outcome.describe(true), "1 would create");
+    }
+}
@@ -0,0 +1,349 @@
+//! Diff command implementation
+
+use super::{Command, CommandContext, GlobalOptions, validators};
+use crate::config::{Config, Repository};
+use crate::logging;
+use crate::{constants, git};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use clap::Args;
+use colored::*;
+use log::Level;
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+/// Diff command showing the uncommitted changes of every repository, e.g. before `repos pr`
+///
+/// Untracked files are included, since they become part of the commit `repos pr`
+/// creates. File paths are prefixed with the repository name, so the combined
+/// patch reads, and applies, relative to the directory holding the repositories.
+pub struct DiffCommand {
+    /// Show a diffstat per repository instead of the full patch
+    pub stat: bool,
+    /// Write the combined patch to this file instead of printing it
+    pub output: Option<PathBuf>,
+    /// Print straight to stdout even when it is a terminal
+    pub no_pager: bool,
+}
+
+impl DiffCommand {
+    /// Diff of one repository, or `None` when it has no changes
+    fn repository_diff(&self, repo: &Repository, color: bool) -> Result<Option<String>> {
+        let repo_path = repo.get_target_dir();
+        if !git::has_changes(&repo_path)? {
+            return Ok(None);
+        }
+
+        let src_prefix = format!("--src-prefix=a/{}/", repo.name);
+        let dst_prefix = format!("--dst-prefix=b/{}/", repo.name);
+        let mut args = vec![if color {
+            "--color=always"
+        } else {
+            "--no-color"
+        }];
+        if self.stat {
+            args.push("--stat");
+        } else {
+            args.extend([src_prefix.as_str(), dst_prefix.as_str()]);
+        }
+
+        let diff = git::workspace_diff(&repo_path, &args)?;
+        if self.stat {
+            let header = format!("{}:", repo.name);
+            let header = if color {
+                header.bold().to_string()
+            } else {
+                header
+            };
+            return Ok(Some(format!("{}\n{}\n", header, diff.trim_end())));
+        }
+        Ok(Some(diff))
+    }
+}
+
+#[async_trait]
+impl Command for DiffCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.config.filter_repositories(
+            &context.tag,
+            &context.exclude_tag,
+            context.repos.as_deref(),
+        );
+
+        if repositories.is_empty() {
+            logging::message(Level::Info, "No repositories found".yellow());
+            return Ok(());
+        }
+
+        let to_terminal = self.output.is_none() && std::io::stdout().is_terminal();
+        let mut combined = String::new();
+        let mut changed = 0;
+        let mut failed = 0;
+        for repo in &repositories {
+            if !Path::new(&repo.get_target_dir()).exists() {
+                logging::status(
+                    Level::Warn,
+                    &repo.name,
+                    "Skipping: repository is not cloned".yellow(),
+                );
+                continue;
+            }
+            match self.repository_diff(repo, to_terminal) {
+                Ok(Some(diff)) => {
+                    combined.push_str(&diff);
+                    if self.stat {
+                        combined.push('\n');
+                    }
+                    changed += 1;
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    logging::status(Level::Error, &repo.name, format!("Error: {e}").red());
+                    failed += 1;
+                }
+            }
+        }
+
+        if changed == 0 {
+            if failed > 0 {
+                anyhow::bail!("Failed to diff {} repositories", failed);
+            }
+            logging::message(Level::Info, "No changes in any repository".yellow());
+            return Ok(());
+        }
+
+        match &self.output {
+            Some(path) => {
+                std::fs::write(path, &combined)
+                    .with_context(|| format!("Failed to write {}", path.display()))?;
+                logging::message(
+                    Level::Info,
+                    format!(
+                        "Wrote changes of {} repositories to {}",
+                        changed,
+                        path.display()
+                    )
+                    .green(),
+                );
+            }
+            None if to_terminal && !self.no_pager => page(&combined)?,
+            None => print!("{}", combined),
+        }
+
+        logging::message(
+            Level::Info,
+            format!(
+                "{} of {} repositories have changes",
+                changed,
+                repositories.len()
+            )
+            .green(),
+        );
+        if failed > 0 {
+            anyhow::bail!("Failed to diff {} repositories", failed);
+        }
+        Ok(())
+    }
+}
+
+/// Show text through `$PAGER` (default `less`), like git does
+fn page(text: &str) -> Result<()> {
+    let pager = std::env::var("PAGER")
+        .ok()
+        .filter(|pager| !pager.is_empty())
+        .unwrap_or_else(|| "less".to_string());
+
+    let mut child = match std::process::Command::new("sh")
+        .args(["-c", &pager])
+        // Quit when everything fits on one screen and keep colors
+        .env(
+            "LESS",
+            std::env::var("LESS").unwrap_or_else(|_| "FRX".to_string()),
+        )
+        .stdin(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(_) => {
+            print!("{}", text);
+            return Ok(());
+        }
+    };
+
+    // The pager closes its input early when the user quits before the end
+    let _ = child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(text.as_bytes());
+    child.wait().context("Failed to wait for pager")?;
+    Ok(())
+}
+
+/// Arguments of `repos diff`
+#[derive(Debug, Args)]
+pub struct DiffArgs {
+    /// Specific repository names to show (if not provided, uses tag filter or all repos)
+    pub repos: Vec<String>,
+
+    /// Show a diffstat per repository instead of the full patch
+    #[arg(long)]
+    pub stat: bool,
+
+    /// Write the combined patch of all repositories to this file
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Print to stdout instead of a pager
+    #[arg(long)]
+    pub no_pager: bool,
+
+    /// Configuration file path (can be specified multiple times to combine fleets)
+    #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+    pub config: Vec<String>,
+
+    /// Filter repositories by tag (can be specified multiple times)
+    #[arg(short, long)]
+    pub tag: Vec<String>,
+
+    /// Exclude repositories with these tags (can be specified multiple times)
+    #[arg(short = 'e', long)]
+    pub exclude_tag: Vec<String>,
+
+    /// Pick repositories interactively from the filtered list before executing
+    #[arg(short, long)]
+    pub interactive: bool,
+}
+
+impl DiffArgs {
+    /// Validate the arguments and run `repos diff`
+    pub async fn run(self, globals: &GlobalOptions) -> Result<()> {
+        let Self {
+            repos,
+            stat,
+            output,
+            no_pager,
+            config,
+            tag,
+            exclude_tag,
+            interactive,
+        } = self;
+        let config = Config::load_all(&config)?;
+
+        validators::validate_tag_filters(&tag)?;
+        validators::validate_tag_filters(&exclude_tag)?;
+        validators::validate_repository_names(&repos)?;
+
+        let mut context = globals.context(config, tag, exclude_tag, repos);
+        if interactive {
+            context.select_interactively()?;
+        }
+
+        DiffCommand {
+            stat,
+            output,
+            no_pager,
+        }
+        .execute(&context)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use std::fs;
+    use std::process::Command as Process;
+    use tempfile::TempDir;
+
+    fn create_repo(root: &Path, name: &str) -> Repository {
+        let dir = root.join(name);
+        fs::create_dir_all(&dir).unwrap();
+        for args in [
+            vec!["init", "-q"],
+            vec!["config", "user.name", "Test"],
+            vec!["config", "user.email", "test@example.com"],
+        ] {
+            Process::new("git")
+                .args(args)
+                .current_dir(&dir)
+                .output()
+                .unwrap();
+        }
+        fs::write(dir.join("README.md"), "# Test\n").unwrap();
+        Process::new("git")
+            .args(["add", "."])
+            .current_dir(&dir)
+            .output()
+            .unwrap();
+        Process::new("git")
+            .args(["commit", "-q", "-m", "Initial commit"])
+            .current_dir(&dir)
+            .output()
+            .unwrap();
+
+        let mut repo =
+            Repository::new(name.to_string(), format!("git@github.com:org/{}.git", name));
+        repo.path = Some(dir.to_string_lossy().to_string());
+        repo
+    }
+
+    fn context_for(repositories: Vec<Repository>) -> CommandContext {
+        let mut config = Config::new();
+        config.repositories = repositories;
+        CommandContext {
+            config,
+            tag: vec![],
+            exclude_tag: vec![],
+            parallel: false,
+            repos: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_diff_writes_combined_patch() {
+        let temp_dir = TempDir::new().unwrap();
+        let api = create_repo(temp_dir.path(), "api");
+        let web = create_repo(temp_dir.path(), "web");
+        fs::write(temp_dir.path().join("api/README.md"), "# Updated\n").unwrap();
+        fs::write(temp_dir.path().join("api/NEW.md"), "new\n").unwrap();
+
+        let output = temp_dir.path().join("changes.patch");
+        DiffCommand {
+            stat: false,
+            output: Some(output.clone()),
+            no_pager: false,
+        }
+        .execute(&context_for(vec![api, web]))
+        .await
+        .unwrap();
+
+        let patch = fs::read_to_string(&output).unwrap();
+        assert!(patch.contains("--- a/api/README.md"));
+        assert!(patch.contains("+++ b/api/NEW.md"));
+        assert!(!patch.contains("web/"));
+    }
+
+    #[tokio::test]
+    async fn test_diff_stat() {
+        let temp_dir = TempDir::new().unwrap();
+        let api = create_repo(temp_dir.path(), "api");
+        fs::write(temp_dir.path().join("api/README.md"), "# Updated\n").unwrap();
+
+        let output = temp_dir.path().join("changes.txt");
+        DiffCommand {
+            stat: true,
+            output: Some(output.clone()),
+            no_pager: false,
+        }
+        .execute(&context_for(vec![api]))
+        .await
+        .unwrap();
+
+        let stat = fs::read_to_string(&output).unwrap();
+        assert!(stat.starts_with("api:\n"));
+        assert!(stat.contains("README.md | 2 +-"));
+    }
+}
@@ -0,0 +1,436 @@
+//! Fetch command implementation
+
+use super::{Command, CommandContext, GlobalOptions, validators};
+use crate::config::{Config, Repository};
+use crate::constants::git::DEFAULT_FETCH_JOBS;
+use crate::git::{self, RefUpdates, RemoteAccess, UpstreamSummary};
+use crate::logging;
+use crate::{constants, events};
+use anyhow::Result;
+use async_trait::async_trait;
+use clap::Args;
+use colored::*;
+use futures::stream::{self, StreamExt};
+use log::Level;
+use std::path::Path;
+
+/// Fetch command refreshing the remote state of every repository
+///
+/// Fetching leaves working trees alone, so repositories are always fetched in
+/// parallel, `jobs` at a time.
+pub struct FetchCommand {
+    /// Number of repositories fetched at the same time
+    pub jobs: usize,
+    /// Also summarize the upstream commits since this date
+    pub since: Option<String>,
+    /// Also fetch the remotes of checked-out submodules
+    pub recurse_submodules: bool,
+}
+
+impl Default for FetchCommand {
+    fn default() -> Self {
+        Self {
+            jobs: DEFAULT_FETCH_JOBS,
+            since: None,
+            recurse_submodules: false,
+        }
+    }
+}
+
+/// What a fetch found in one repository
+struct FetchReport {
+    updates: RefUpdates,
+    upstream: Option<UpstreamSummary>,
+}
+
+fn count(n: usize, singular: &str, plural: &str) -> String {
+    format!("{} {}", n, if n == 1 { singular } else { plural })
+}
+
+fn describe_updates(updates: &RefUpdates) -> String {
+    let mut parts = Vec::new();
+    if !updates.new_branches.is_empty() {
+        parts.push(format!(
+            "{} ({})",
+            count(updates.new_branches.len(), "new branch", "new branches"),
+            updates.new_branches.join(", ")
+        ));
+    }
+    if !updates.updated_branches.is_empty() {
+        parts.push(format!("{} updated", updates.updated_branches.len()));
+    }
+    if !updates.pruned_branches.is_empty() {
+        parts.push(format!(
+            "{} pruned ({})",
+            updates.pruned_branches.len(),
+            updates.pruned_branches.join(", ")
+        ));
+    }
+    if !updates.new_tags.is_empty() {
+        parts.push(format!(
+            "{} ({})",
+            count(updates.new_tags.len(), "new tag", "new tags"),
+            updates.new_tags.join(", ")
+        ));
+    }
+    parts.join(", ")
+}
+
+fn describe_upstream(summary: &UpstreamSummary, since: &str) -> String {
+    if summary.commits == 0 {
+        return format!("no upstream commits since {}", since);
+    }
+    format!(
+        "{} by {} since {}",
+        count(summary.commits, "commit", "commits"),
+        count(summary.authors.len(), "author", "authors"),
+        since
+    )
+}
+
+/// Fetch one clone, and its submodules too when given the access to their remotes
+fn fetch_repository(
+    repo_path: &str,
+    since: Option<&str>,
+    submodules: Option<&RemoteAccess>,
+) -> Result<FetchReport> {
+    let updates = git::fetch_all(repo_path)?;
+    if let Some(access) = submodules
+        && git::has_submodules(repo_path)
+    {
+        git::fetch_submodules(repo_path, access)?;
+    }
+    let upstream = since
+        .map(|since| git::upstream_summary(repo_path, since))
+        .transpose()?;
+    Ok(FetchReport { updates, upstream })
+}
+
+/// Fetch `origin` in all cloned repositories concurrently
+///
+/// Commands that must work through repositories one at a time call this first,
+/// so that the slow network phase is not sequential as well. Repositories that
+/// are not cloned are left out of the results.
+pub async fn prefetch(repositories: &[Repository]) -> Result<Vec<(Repository, Result<()>)>> {
+    let tasks: Vec<_> = repositories
+        .iter()
+        .filter(|repo| Path::new(&repo.get_target_dir()).exists())
+        .cloned()
+        .map(|repo| {
+            tokio::task::spawn_blocking(move || {
+                let result =
+                    logging::with_repository(&repo.name, || git::fetch(&repo.get_target_dir()));
+                (repo, result)
+            })
+        })
+        .collect();
+
+    let mut results = Vec::new();
+    for task in tasks {
+        results.push(task.await?);
+    }
+    Ok(results)
+}
+
+#[async_trait]
+impl Command for FetchCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.config.filter_repositories(
+            &context.tag,
+            &context.exclude_tag,
+            context.repos.as_deref(),
+        );
+
+        if repositories.is_empty() {
+            logging::message(Level::Info, "No repositories found".yellow());
+            return Ok(());
+        }
+
+        for repo in &repositories {
+            if !Path::new(&repo.get_target_dir()).exists() {
+                logging::status(
+                    Level::Warn,
+                    &repo.name,
+                    "Skipping: repository is not cloned".yellow(),
+                );
+            }
+        }
+
+        let cloned: Vec<Repository> = repositories
+            .into_iter()
+            .filter(|repo| Path::new(&repo.get_target_dir()).exists())
+            .collect();
+
+        logging::message(
+            Level::Info,
+            format!("Fetching {} repositories...", cloned.len()).green(),
+        );
+
+        let since = self.since.clone();
+        let recurse_submodules = self.recurse_submodules;
+        let results: Vec<_> = stream::iter(cloned)
+            .map(|repo| {
+                let since = since.clone();
+                tokio::task::spawn_blocking(move || {
+                    let result = events::track(&repo.name, || {
+                        let access =
+                            recurse_submodules.then(|| RemoteAccess::for_repository(&repo, None));
+                        fetch_repository(&repo.get_target_dir(), since.as_deref(), access.as_ref())
+                    });
+                    (repo, result)
+                })
+            })
+            .buffered(self.jobs.max(1))
+            .collect()
+            .await;
+
+        let mut fetched = 0;
+        let mut new_branches = 0;
+        let mut new_tags = 0;
+        let mut upstream_commits = 0;
+        let mut errors = Vec::new();
+        for result in results {
+            let (repo, result) = result?;
+            match result {
+                Ok(report) => {
+                    let mut message = if report.updates.is_empty() {
+                        "Up to date".to_string()
+                    } else {
+                        describe_updates(&report.updates)
+                    };
+                    if let (Some(summary), Some(since)) = (&report.upstream, &self.since) {
+                        message = format!("{}; {}", message, describe_upstream(summary, since));
+                        upstream_commits += summary.commits;
+                    }
+                    if report.updates.is_empty() {
+                        logging::status(Level::Info, &repo.name, message.dimmed());
+                    } else {
+                        logging::status(Level::Info, &repo.name, message.green());
+                    }
+                    fetched += 1;
+                    new_branches += report.updates.new_branches.len();
+                    new_tags += report.updates.new_tags.len();
+                }
+                Err(e) => {
+                    logging::status(Level::Error, &repo.name, format!("Error: {e}").red());
+                    errors.push((repo.name, e));
+                }
+            }
+        }
+
+        if let Some((repo_name, e)) = errors.first() {
+            logging::message(
+                Level::Info,
+                format!(
+                    "Completed with {} successful, {} failed",
+                    fetched,
+                    errors.len()
+                )
+                .yellow(),
+            );
+            anyhow::bail!(
+                "Failed to fetch {} repositories. First error ({}): {}",
+                errors.len(),
+                repo_name,
+                e
+            );
+        }
+
+        let mut summary = format!(
+            "Done fetching {} repositories: {}, {}",
+            fetched,
+            count(new_branches, "new branch", "new branches"),
+            count(new_tags, "new tag", "new tags")
+        );
+        if let Some(since) = &self.since {
+            summary.push_str(&format!(
+                ", {} upstream since {}",
+                count(upstream_commits, "commit", "commits"),
+                since
+            ));
+        }
+        logging::message(Level::Info, summary.green());
+        Ok(())
+    }
+}
+
+/// Arguments of `repos fetch`
+#[derive(Debug, Args)]
+pub struct FetchArgs {
+    /// Specific repository names to fetch (if not provided, uses tag filter or all repos)
+    pub repos: Vec<String>,
+
+    /// Number of repositories to fetch at the same time
+    #[arg(short, long, default_value_t = constants::git::DEFAULT_FETCH_JOBS)]
+    pub jobs: usize,
+
+    /// Summarize the upstream commits since this date, e.g. 2024-01-01 or "1 week ago"
+    #[arg(long)]
+    pub since: Option<String>,
+
+    /// Also fetch the remotes of checked-out submodules, including nested ones
+    #[arg(long)]
+    pub recurse_submodules: bool,
+
+    /// Configuration file path (can be specified multiple times to combine fleets)
+    #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+    pub config: Vec<String>,
+
+    /// Filter repositories by tag (can be specified multiple times)
+    #[arg(short, long)]
+    pub tag: Vec<String>,
+
+    /// Exclude repositories with these tags (can be specified multiple times)
+    #[arg(short = 'e', long)]
+    pub exclude_tag: Vec<String>,
+}
+
+impl FetchArgs {
+    /// Validate the arguments and run `repos fetch`
+    pub async fn run(self, globals: &GlobalOptions) -> Result<()> {
+        let Self {
+            repos,
+            jobs,
+            since,
+            recurse_submodules,
+            config,
+            tag,
+            exclude_tag,
+        } = self;
+        let config = Config::load_all(&config)?;
+
+        validators::validate_tag_filters(&tag)?;
+        validators::validate_tag_filters(&exclude_tag)?;
+        validators::validate_repository_names(&repos)?;
+
+        let context = globals
+            .context(config, tag, exclude_tag, repos)
+            .with_parallel(true);
+        FetchCommand {
+            jobs,
+            since,
+            recurse_submodules,
+        }
+        .execute(&context)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use std::process::Command as Process;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let output = Process::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "git {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    /// Commit an empty change and push it to `origin`, returning the new commit
+    fn push_commit(work: &Path, message: &str) -> String {
+        git(
+            work,
+            &[
+                "-c",
+                "user.name=Test",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "--allow-empty",
+                "-m",
+                message,
+            ],
+        );
+        git(work, &["push", "-q", "origin", "HEAD"]);
+        git(work, &["rev-parse", "HEAD"])
+    }
+
+    #[tokio::test]
+    async fn test_fetch_updates_remote_tracking_branches() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        git(root, &["init", "-q", "--bare", "origin.git"]);
+        git(root, &["clone", "-q", "origin.git", "work"]);
+        let work = root.join("work");
+        push_commit(&work, "initial");
+        git(root, &["clone", "-q", "origin.git", "api"]);
+
+        let latest = push_commit(&work, "second");
+        let branch = git(&work, &["rev-parse", "--abbrev-ref", "HEAD"]);
+
+        let mut api = Repository::new("api".to_string(), "git@github.com:org/api.git".to_string());
+        api.path = Some(root.join("api").to_string_lossy().to_string());
+        let mut missing = Repository::new(
+            "missing".to_string(),
+            "git@github.com:org/missing.git".to_string(),
+        );
+        missing.path = Some(root.join("missing").to_string_lossy().to_string());
+
+        let mut config = Config::new();
+        config.repositories = vec![api, missing];
+        let context = CommandContext {
+            config,
+            tag: vec![],
+            exclude_tag: vec![],
+            parallel: false,
+            repos: None,
+        };
+
+        FetchCommand::default().execute(&context).await.unwrap();
+
+        assert_eq!(
+            git(
+                &root.join("api"),
+                &["rev-parse", &format!("origin/{}", branch)]
+            ),
+            latest
+        );
+    }
+
+    #[test]
+    fn test_fetch_repository_reports_new_refs() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        git(root, &["init", "-q", "--bare", "origin.git"]);
+        git(root, &["clone", "-q", "origin.git", "work"]);
+        let work = root.join("work");
+        push_commit(&work, "initial");
+        git(&work, &["push", "-q", "origin", "HEAD:stale"]);
+        git(root, &["clone", "-q", "origin.git", "api"]);
+        let api = root.join("api").to_string_lossy().to_string();
+
+        push_commit(&work, "second");
+        git(&work, &["push", "-q", "origin", "HEAD:feature", ":stale"]);
+        git(&work, &["tag", "v1.0.0"]);
+        git(&work, &["push", "-q", "origin", "v1.0.0"]);
+
+        let report = fetch_repository(&api, Some("1 week ago"), None).unwrap();
+        assert_eq!(report.updates.new_branches, vec!["origin/feature"]);
+        assert_eq!(report.updates.updated_branches.len(), 1);
+        assert_eq!(report.updates.pruned_branches, vec!["origin/stale"]);
+        assert_eq!(report.updates.new_tags, vec!["v1.0.0"]);
+        assert_eq!(
+            report.upstream,
+            Some(UpstreamSummary {
+                commits: 2,
+                authors: vec!["Test".to_string()],
+            })
+        );
+
+        let report = fetch_repository(&api, None, None).unwrap();
+        assert!(report.updates.is_empty());
+        assert_eq!(report.upstream, None);
+    }
+}
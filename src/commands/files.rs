@@ -0,0 +1,403 @@
+//! Files command implementation
+
+use super::{Command, CommandContext, GlobalOptions, validators};
+use crate::config::{Config, FileRequirement, Repository};
+use crate::credentials::Credentials;
+use crate::github::PrOptions;
+use crate::github::api::create_pr_from_files;
+use crate::github::placeholders::Placeholders;
+use crate::github::template::render_file;
+use crate::{constants, logging};
+use anyhow::Result;
+use async_trait::async_trait;
+use clap::Subcommand;
+use colored::*;
+use log::Level;
+use repos_github::TreeFile;
+use serde::Serialize;
+use std::fmt;
+use std::path::Path;
+
+/// Files check command comparing each repository against the `files` requirements in the config
+pub struct FilesCheckCommand {
+    /// Print the drift report as JSON
+    pub json: bool,
+    /// Open a pull request writing the templates of unmet requirements
+    pub fix: Option<PrOptions>,
+}
+
+/// A way in which a repository differs from a requirement
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Drift {
+    MissingFile { path: String },
+    MissingContent { path: String, snippet: String },
+}
+
+impl Drift {
+    pub fn path(&self) -> &str {
+        match self {
+            Drift::MissingFile { path } | Drift::MissingContent { path, .. } => path,
+        }
+    }
+}
+
+impl fmt::Display for Drift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Drift::MissingFile { path } => write!(f, "{} is missing", path),
+            Drift::MissingContent { path, snippet } => {
+                write!(f, "{} does not contain {:?}", path, snippet)
+            }
+        }
+    }
+}
+
+/// Drift found in one repository
+#[derive(Debug, Clone, Serialize)]
+pub struct FileReport {
+    pub repository: String,
+    /// False when the repository is not cloned and could not be checked
+    pub cloned: bool,
+    pub drift: Vec<Drift>,
+}
+
+/// Compare a local clone against the requirements that apply to it
+pub fn check_repository(repo: &Repository, requirements: &[FileRequirement]) -> FileReport {
+    let target_dir = repo.get_target_dir();
+    if !Path::new(&target_dir).exists() {
+        return FileReport {
+            repository: repo.name.clone(),
+            cloned: false,
+            drift: Vec::new(),
+        };
+    }
+
+    let mut drift = Vec::new();
+    for requirement in requirements.iter().filter(|r| r.applies_to(repo)) {
+        let file = Path::new(&target_dir).join(&requirement.path);
+        // Unreadable or binary files cannot contain the snippets either
+        let Ok(content) = std::fs::read_to_string(&file) else {
+            drift.push(Drift::MissingFile {
+                path: requirement.path.clone(),
+            });
+            continue;
+        };
+        for snippet in &requirement.contains {
+            if !content.contains(snippet.as_str()) {
+                drift.push(Drift::MissingContent {
+                    path: requirement.path.clone(),
+                    snippet: snippet.clone(),
+                });
+            }
+        }
+    }
+
+    FileReport {
+        repository: repo.name.clone(),
+        cloned: true,
+        drift,
+    }
+}
+
+impl FilesCheckCommand {
+    /// Open a pull request fixing the drift of one repository, returning whether one was attempted
+    ///
+    /// Only requirements with a template can be fixed; the template replaces the whole file.
+    async fn fix_repository(
+        repo: &Repository,
+        requirements: &[FileRequirement],
+        report: &FileReport,
+        options: &PrOptions,
+    ) -> Result<bool> {
+        let mut files = Vec::new();
+        for requirement in requirements.iter().filter(|r| r.applies_to(repo)) {
+            let Some(template) = requirement.template_path() else {
+                continue;
+            };
+            let drifted = report.drift.iter().any(|d| d.path() == requirement.path);
+            if drifted && !files.iter().any(|f: &TreeFile| f.path == requirement.path) {
+                files.push(render_file(
+                    &template,
+                    &requirement.path,
+                    repo,
+                    &Placeholders::default(),
+                )?);
+            }
+        }
+
+        if files.is_empty() {
+            return Ok(false);
+        }
+        create_pr_from_files(repo, options, &files).await?;
+        Ok(true)
+    }
+}
+
+#[async_trait]
+impl Command for FilesCheckCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let requirements = &context.config.files;
+        if requirements.is_empty() {
+            anyhow::bail!("No file requirements configured; add a 'files' section to the config");
+        }
+
+        let repositories = context.config.filter_repositories(
+            &context.tag,
+            &context.exclude_tag,
+            context.repos.as_deref(),
+        );
+        if repositories.is_empty() {
+            logging::message(Level::Info, "No repositories found".yellow());
+            return Ok(());
+        }
+
+        let reports: Vec<FileReport> = repositories
+            .iter()
+            .map(|repo| check_repository(repo, requirements))
+            .collect();
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&reports)?);
+        } else {
+            for report in &reports {
+                if !report.cloned {
+                    logging::status(
+                        Level::Warn,
+                        &report.repository,
+                        "Not cloned, skipping".yellow(),
+                    );
+                } else if report.drift.is_empty() {
+                    logging::status(Level::Info, &report.repository, "Up to date".green());
+                } else {
+                    for drift in &report.drift {
+                        logging::status(Level::Warn, &report.repository, drift.to_string().red());
+                    }
+                }
+            }
+        }
+
+        let drifted: Vec<(&Repository, &FileReport)> = repositories
+            .iter()
+            .zip(&reports)
+            .filter(|(_, report)| !report.drift.is_empty())
+            .collect();
+        if !self.json {
+            logging::message(
+                Level::Info,
+                format!(
+                    "{} of {} repositories drift from the required files",
+                    drifted.len(),
+                    reports.iter().filter(|r| r.cloned).count()
+                )
+                .bold(),
+            );
+        }
+
+        let mut unresolved = drifted.len();
+        if let Some(options) = &self.fix {
+            let mut errors = Vec::new();
+            for (repo, report) in &drifted {
+                match Self::fix_repository(repo, requirements, report, options).await {
+                    Ok(true) => unresolved -= 1,
+                    Ok(false) => logging::status(
+                        Level::Warn,
+                        &repo.name,
+                        "No template configured for the missing files, cannot fix".yellow(),
+                    ),
+                    Err(e) => {
+                        logging::status(Level::Error, &repo.name, format!("Error: {e}").red());
+                        errors.push((repo.name.clone(), e));
+                    }
+                }
+            }
+            if let Some((repo_name, e)) = errors.first() {
+                anyhow::bail!(
+                    "Failed to open fix pull requests for {} repositories. First error ({}): {}",
+                    errors.len(),
+                    repo_name,
+                    e
+                );
+            }
+        }
+
+        if unresolved > 0 {
+            anyhow::bail!("{} repositories drift from the required files", unresolved);
+        }
+        Ok(())
+    }
+}
+
+/// Subcommands of `repos files`
+#[derive(Debug, Subcommand)]
+pub enum FilesAction {
+    /// Report repositories missing required files or content, optionally opening PRs to fix them
+    Check {
+        /// Specific repository names to check (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Output the drift report in JSON format
+        #[arg(long)]
+        json: bool,
+
+        /// Open a pull request writing the configured templates where files drift
+        #[arg(long)]
+        fix: bool,
+
+        /// Title for fix pull requests
+        #[arg(long, default_value = "Add required files", requires = "fix")]
+        title: String,
+
+        /// Body text for fix pull requests
+        #[arg(
+            long,
+            default_value = "This PR was created automatically by repos files check",
+            requires = "fix"
+        )]
+        body: String,
+
+        /// Branch name for fix pull requests
+        #[arg(long, requires = "fix")]
+        branch: Option<String>,
+
+        /// Create fix pull requests as drafts
+        #[arg(long, requires = "fix")]
+        draft: bool,
+
+        /// GitHub token
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Configuration file path (can be specified multiple times to combine fleets)
+        #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+        config: Vec<String>,
+
+        /// Filter repositories by tag (can be specified multiple times)
+        #[arg(short, long)]
+        tag: Vec<String>,
+
+        /// Exclude repositories with these tags (can be specified multiple times)
+        #[arg(short = 'e', long)]
+        exclude_tag: Vec<String>,
+    },
+}
+
+impl FilesAction {
+    /// Validate the arguments and run `repos files <action>`
+    pub async fn run(self, globals: &GlobalOptions) -> Result<()> {
+        match self {
+            FilesAction::Check {
+                repos,
+                json,
+                fix,
+                title,
+                body,
+                branch,
+                draft,
+                token,
+                config,
+                tag,
+                exclude_tag,
+            } => {
+                let config = Config::load_all(&config)?;
+
+                validators::validate_tag_filters(&tag)?;
+                validators::validate_tag_filters(&exclude_tag)?;
+                validators::validate_repository_names(&repos)?;
+                validators::validate_branch_name(&branch)?;
+
+                let fix = if fix {
+                    let token = Credentials::load(token.or_else(|| config.resolve_profile_token()))
+                    .default_token()
+                    .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "GitHub token not provided. Use --token flag, set GITHUB_TOKEN environment variable, or save one with `repos auth login`."
+                    )
+                })?;
+                    let mut options = crate::github::PrOptions::new(title, body, token);
+                    if let Some(branch) = branch {
+                        options = options.with_branch_name(branch);
+                    }
+                    if draft {
+                        options = options.as_draft();
+                    }
+                    Some(options)
+                } else {
+                    None
+                };
+
+                let mut context = globals.context(config, tag, exclude_tag, repos);
+                context.skip_archived(globals.include_archived);
+                FilesCheckCommand { json, fix }.execute(&context).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn requirement(path: &str, contains: &[&str]) -> FileRequirement {
+        FileRequirement {
+            path: path.to_string(),
+            contains: contains.iter().map(|s| s.to_string()).collect(),
+            tags: Vec::new(),
+            template: None,
+            config_dir: None,
+        }
+    }
+
+    #[test]
+    fn test_check_repository() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("SECURITY.md"),
+            "# Security\n\n## Reporting a Vulnerability\n",
+        )
+        .unwrap();
+
+        let mut repo = Repository::new("api".to_string(), "git@github.com:org/api.git".to_string());
+        repo.path = Some(temp_dir.path().to_string_lossy().to_string());
+
+        let mut frontend_only = requirement("package.json", &[]);
+        frontend_only.tags = vec!["frontend".to_string()];
+        let requirements = vec![
+            requirement("SECURITY.md", &["Reporting a Vulnerability", "security@"]),
+            requirement(".github/workflows/ci.yml", &["cargo audit"]),
+            frontend_only,
+        ];
+
+        let report = check_repository(&repo, &requirements);
+        assert!(report.cloned);
+        assert_eq!(
+            report.drift,
+            vec![
+                Drift::MissingContent {
+                    path: "SECURITY.md".to_string(),
+                    snippet: "security@".to_string(),
+                },
+                Drift::MissingFile {
+                    path: ".github/workflows/ci.yml".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            report.drift[1].to_string(),
+            ".github/workflows/ci.yml is missing"
+        );
+    }
+
+    #[test]
+    fn test_check_repository_not_cloned() {
+        let mut repo = Repository::new("api".to_string(), "git@github.com:org/api.git".to_string());
+        repo.path = Some("/nonexistent/repos/api".to_string());
+
+        let report = check_repository(&repo, &[requirement("README.md", &[])]);
+        assert!(!report.cloned);
+        assert!(report.drift.is_empty());
+    }
+}
@@ -1,13 +1,22 @@
 //! Init command implementation
 
+use super::tags::{repository_topics, topic_tag_changes};
 use super::{Command, CommandContext};
-use crate::config::{Config, RepositoryBuilder};
+use crate::config::{Config, PathStrategy, Repository, RepositoryBuilder, import};
+use crate::credentials::Credentials;
+use crate::utils::config_path_for;
+use crate::utils::interactive;
+use crate::utils::repository_discovery::{get_remotes, primary_remote};
+use crate::{constants, logging};
 use anyhow::Result;
 use async_trait::async_trait;
 use colored::*;
-use std::path::Path;
+use log::Level;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
+use clap::Args;
 #[cfg(test)]
 use serial_test::serial;
 
@@ -16,6 +25,176 @@ pub struct InitCommand {
     pub output: String,
     pub overwrite: bool,
     pub supplement: bool,
+    /// Remote whose URL becomes a repository's `url`; `origin`, or the first remote, when missing
+    pub remote: Option<String>,
+    /// Review each discovered repository instead of adding all of them
+    pub interactive: bool,
+    /// Convert another tool's configuration instead of discovering repositories
+    pub import: Option<PathBuf>,
+}
+
+/// A Git repository found below the current directory
+struct Discovered {
+    dir: PathBuf,
+    name: String,
+    url: String,
+    /// Remotes besides the one `url` comes from
+    remotes: BTreeMap<String, String>,
+}
+
+impl Discovered {
+    /// The repository entry, with a `path` only when the path strategy does not
+    /// already resolve it to `dir`
+    fn repository(&self, config_dir: &Path, strategy: &PathStrategy) -> Repository {
+        let mut builder = RepositoryBuilder::new(self.name.clone(), self.url.clone())
+            .with_remotes(self.remotes.clone());
+        if let Some(path) = config_path_for(&self.dir, config_dir, &self.name, &self.url, strategy)
+        {
+            builder = builder.with_path(path);
+        }
+        builder.build()
+    }
+}
+
+/// Split tags typed at a prompt, separated by commas or spaces
+fn parse_tags(input: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in input.split([',', ' ']).map(str::trim) {
+        if !tag.is_empty() && !tags.iter().any(|t| t == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
+}
+
+impl InitCommand {
+    /// Find Git repositories with a remote up to four levels below `current_dir`
+    fn discover(&self, current_dir: &Path) -> Result<Vec<Discovered>> {
+        let mut discovered = Vec::new();
+        for entry in WalkDir::new(current_dir)
+            .max_depth(4)
+            .into_iter()
+            .filter_map(|e| e.ok())
+        {
+            if entry.file_name() == ".git"
+                && entry.file_type().is_dir()
+                && let Some(repo_dir) = entry.path().parent()
+                && let Some(name) = repo_dir.file_name().and_then(|n| n.to_str())
+            {
+                let mut remotes = get_remotes(repo_dir)?;
+                let Some((remote, url)) = primary_remote(&remotes, self.remote.as_deref())
+                    .map(|(remote, url)| (remote.clone(), url.clone()))
+                else {
+                    continue;
+                };
+                if let Some(preferred) = &self.remote
+                    && *preferred != remote
+                {
+                    logging::status(
+                        Level::Warn,
+                        name,
+                        format!("No remote '{}', using '{}'", preferred, remote).yellow(),
+                    );
+                }
+                // The other remotes are recorded alongside the URL
+                remotes.remove(&remote);
+
+                discovered.push(Discovered {
+                    dir: repo_dir.to_path_buf(),
+                    name: name.to_string(),
+                    url,
+                    remotes,
+                });
+            }
+        }
+        Ok(discovered)
+    }
+
+    /// Ask for the workspace root, then accept, skip or edit each repository
+    async fn review(
+        &self,
+        candidates: Vec<Discovered>,
+        config: &mut Config,
+        output_dir: &Path,
+    ) -> Result<Vec<Repository>> {
+        let root = interactive::input(
+            "Workspace root, relative to the config file (empty for its directory)",
+            config.root.as_deref().unwrap_or_default(),
+        )?;
+        config.root = (!root.is_empty()).then_some(root);
+        let config_dir = config.root_dir(output_dir)?;
+
+        let credentials = interactive::confirm("Seed tags from repository topics?")?
+            .then(|| Credentials::load(None));
+
+        let total = candidates.len();
+        let mut accepted: Vec<Repository> = Vec::new();
+        for (index, candidate) in candidates.into_iter().enumerate() {
+            let mut repo = candidate.repository(&config_dir, &config.path_strategy);
+            if let Some(credentials) = &credentials {
+                match repository_topics(&repo, credentials).await {
+                    Ok(Some(topics)) => repo.tags = topic_tag_changes(&[], &topics).add,
+                    Ok(None) => {}
+                    Err(e) => logging::status(
+                        Level::Warn,
+                        &repo.name,
+                        format!("Could not fetch topics: {}", e).yellow(),
+                    ),
+                }
+            }
+
+            let mut prompt = format!(
+                "[{}/{}] {} ({})",
+                index + 1,
+                total,
+                repo.name.bold(),
+                candidate.dir.display()
+            );
+            if !repo.tags.is_empty() {
+                prompt.push_str(&format!(" [{}]", repo.tags.join(", ")));
+            }
+            let repo = match interactive::choose(&prompt, &["Accept", "Skip", "Edit"], 0)? {
+                Some(0) => repo,
+                Some(2) => Self::edit(repo, &config.path_strategy)?,
+                Some(_) => continue,
+                None => anyhow::bail!("Init cancelled; no configuration was written"),
+            };
+
+            if accepted.iter().any(|other| other.name == repo.name) {
+                logging::status(
+                    Level::Warn,
+                    &repo.name,
+                    "Skipping: a repository with this name was already added".yellow(),
+                );
+                continue;
+            }
+            accepted.push(repo);
+        }
+        Ok(accepted)
+    }
+
+    /// Let the user change the name, tags and path of a repository
+    ///
+    /// The directory stays the same when only the name changes.
+    fn edit(mut repo: Repository, strategy: &PathStrategy) -> Result<Repository> {
+        let dir = repo
+            .path
+            .clone()
+            .unwrap_or_else(|| strategy.relative_dir(&repo.name, &repo.url));
+
+        let name = interactive::input("Name", &repo.name)?;
+        if !name.is_empty() {
+            repo.name = name;
+        }
+        repo.tags = parse_tags(&interactive::input(
+            "Tags, separated by commas",
+            &repo.tags.join(", "),
+        )?);
+        let path = interactive::input("Path, relative to the workspace root", &dir)?;
+        let path = if path.is_empty() { dir } else { path };
+        repo.path = (path != strategy.relative_dir(&repo.name, &repo.url)).then_some(path);
+        Ok(repo)
+    }
 }
 
 #[async_trait]
@@ -23,8 +202,9 @@ impl Command for InitCommand {
     async fn execute(&self, _context: &CommandContext) -> Result<()> {
         // Load existing config if supplementing, otherwise check for overwrite
         let mut existing_config = if self.supplement && Path::new(&self.output).exists() {
-            println!("{}", "Loading existing configuration...".green());
-            Config::load(&self.output)?
+            logging::message(Level::Info, "Loading existing configuration...".green());
+            // Without any profile, so saving does not write a profile's view back
+            Config::load_file(&self.output)?
         } else {
             if Path::new(&self.output).exists() && !self.overwrite {
                 return Err(anyhow::anyhow!(
@@ -35,46 +215,63 @@ impl Command for InitCommand {
             Config::new()
         };
 
-        println!("{}", "Discovering Git repositories...".green());
+        let discovered_repositories = if let Some(file) = &self.import {
+            logging::message(
+                Level::Info,
+                format!("Importing repositories from '{}'...", file.display()).green(),
+            );
+            let imported = import::import_repositories(file, &existing_config.path_strategy)?;
+            if imported.is_empty() {
+                logging::message(
+                    Level::Info,
+                    format!("No repositories found in '{}'", file.display()).yellow(),
+                );
+                if !self.supplement {
+                    return Ok(());
+                }
+            }
+            imported
+        } else {
+            logging::message(Level::Info, "Discovering Git repositories...".green());
 
-        let mut discovered_repositories = Vec::new();
-        let current_dir = std::env::current_dir()?;
+            let current_dir = std::env::current_dir()?;
+            let output_dir =
+                current_dir.join(Path::new(&self.output).parent().unwrap_or(Path::new("")));
+            let mut candidates = self.discover(&current_dir)?;
 
-        for entry in WalkDir::new(&current_dir)
-            .max_depth(4)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
-            if entry.file_name() == ".git"
-                && entry.file_type().is_dir()
-                && let Some(repo_dir) = entry.path().parent()
-                && let Some(name) = repo_dir.file_name().and_then(|n| n.to_str())
-            {
-                // Try to get remote URL
-                if let Ok(url) = get_git_remote_url(repo_dir) {
-                    let repo = RepositoryBuilder::new(name.to_string(), url)
-                        .with_path(
-                            repo_dir
-                                .strip_prefix(&current_dir)
-                                .unwrap_or(repo_dir)
-                                .to_string_lossy()
-                                .to_string(),
-                        )
-                        .build();
-                    discovered_repositories.push(repo);
+            if candidates.is_empty() {
+                logging::message(
+                    Level::Info,
+                    "No Git repositories found in current directory".yellow(),
+                );
+                if !self.supplement {
+                    return Ok(());
                 }
             }
-        }
 
-        if discovered_repositories.is_empty() {
-            println!(
-                "{}",
-                "No Git repositories found in current directory".yellow()
-            );
-            if !self.supplement {
-                return Ok(());
+            if self.interactive && !candidates.is_empty() {
+                // Repositories already in the config are not offered again
+                candidates
+                    .retain(|candidate| existing_config.get_repository(&candidate.name).is_none());
+                let selected = self
+                    .review(candidates, &mut existing_config, &output_dir)
+                    .await?;
+                if selected.is_empty() && !self.supplement {
+                    logging::message(Level::Info, "No repositories selected".yellow());
+                    return Ok(());
+                }
+                selected
+            } else {
+                // Paths are recorded relative to the root directory, which is how they are resolved
+                let config_dir = existing_config.root_dir(&output_dir)?;
+                candidates
+                    .iter()
+                    .map(|candidate| {
+                        candidate.repository(&config_dir, &existing_config.path_strategy)
+                    })
+                    .collect()
             }
-        }
+        };
 
         let mut added_count = 0;
         let has_existing_config = Path::new(&self.output).exists();
@@ -86,24 +283,24 @@ impl Command for InitCommand {
                     existing_config.add_repository(repo)?;
                     added_count += 1;
                 } else {
-                    println!(
-                        "{}",
+                    logging::message(
+                        Level::Info,
                         format!(
                             "Repository '{}' already exists in config, skipping",
                             repo.name
                         )
-                        .yellow()
+                        .yellow(),
                     );
                 }
             }
 
             if added_count > 0 {
-                println!(
-                    "{}",
-                    format!("Added {} new repositories to existing config", added_count).green()
+                logging::message(
+                    Level::Info,
+                    format!("Added {} new repositories to existing config", added_count).green(),
                 );
             } else {
-                println!("{}", "No new repositories found to add".yellow());
+                logging::message(Level::Info, "No new repositories found to add".yellow());
             }
 
             // Only save if we have new repositories to add or if config already existed
@@ -111,28 +308,28 @@ impl Command for InitCommand {
                 existing_config.save(&self.output)?;
 
                 if added_count > 0 {
-                    println!(
-                        "{}",
+                    logging::message(
+                        Level::Info,
                         format!(
                             "Configuration updated with {} new repositories in '{}'",
                             added_count, self.output
                         )
-                        .green()
+                        .green(),
                     );
                 }
             }
         } else {
             // Replace mode - use all discovered repositories
             existing_config.repositories = discovered_repositories;
-            println!(
-                "{}",
-                format!("Found {} repositories", existing_config.repositories.len()).green()
+            logging::message(
+                Level::Info,
+                format!("Found {} repositories", existing_config.repositories.len()).green(),
             );
 
             existing_config.save(&self.output)?;
-            println!(
-                "{}",
-                format!("Configuration saved to '{}'", self.output).green()
+            logging::message(
+                Level::Info,
+                format!("Configuration saved to '{}'", self.output).green(),
             );
         }
 
@@ -140,19 +337,57 @@ impl Command for InitCommand {
     }
 }
 
-fn get_git_remote_url(repo_path: &Path) -> Result<String> {
-    use std::process::Command;
+/// Arguments of `repos init`
+#[derive(Debug, Args)]
+pub struct InitArgs {
+    /// Output file name
+    #[arg(short, long, default_value_t = constants::config::DEFAULT_CONFIG_FILE.to_string())]
+    pub output: String,
+
+    /// Overwrite existing file if it exists
+    #[arg(long)]
+    pub overwrite: bool,
+
+    /// Supplement existing config with newly discovered repositories
+    #[arg(long)]
+    pub supplement: bool,
+
+    /// Remote whose URL is recorded for each repository (default: origin, or the first remote); other remotes are recorded under `remotes`
+    #[arg(long)]
+    pub remote: Option<String>,
 
-    let output = Command::new("git")
-        .args(["remote", "get-url", "origin"])
-        .current_dir(repo_path)
-        .output()?;
+    /// Accept, skip or edit each discovered repository, and choose the workspace root
+    #[arg(short, long)]
+    pub interactive: bool,
 
-    if output.status.success() {
-        let url = String::from_utf8(output.stdout)?.trim().to_string();
-        Ok(url)
-    } else {
-        Err(anyhow::anyhow!("Failed to get remote URL"))
+    /// Convert a repo manifest.xml, gita repos.csv, .meta, .mrconfig or list of URLs instead of discovering repositories
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["interactive", "remote"])]
+    pub import: Option<PathBuf>,
+}
+
+impl InitArgs {
+    /// Validate the arguments and run `repos init`
+    pub async fn run(self) -> Result<()> {
+        let Self {
+            output,
+            overwrite,
+            supplement,
+            remote,
+            interactive,
+            import,
+        } = self;
+        // Init command doesn't need config since it creates one
+        let context = CommandContext::new(Config::new());
+        InitCommand {
+            output,
+            overwrite,
+            supplement,
+            remote,
+            interactive,
+            import,
+        }
+        .execute(&context)
+        .await
     }
 }
 
@@ -176,12 +411,32 @@ mod tests {
             output: output_path.to_string_lossy().to_string(),
             overwrite: false,
             supplement: false,
+            remote: None,
+            interactive: false,
+            import: None,
         };
 
         let context = CommandContext {
             config: Config {
                 repositories: vec![],
                 recipes: vec![],
+                artifacts_remote: None,
+                log_sinks: Vec::new(),
+                path_strategy: Default::default(),
+                root: None,
+                files: Vec::new(),
+                branch_protection: Vec::new(),
+                repository_settings: Vec::new(),
+                post_clone: Vec::new(),
+                pr: None,
+                provider: None,
+                provider_url: None,
+                git: Default::default(),
+                exit_codes: Default::default(),
+                notify: None,
+                profiles: Default::default(),
+                secrets: None,
+                profile_token: None,
             },
             tag: vec![],
             exclude_tag: vec![],
@@ -211,12 +466,32 @@ mod tests {
             output: output_path.to_string_lossy().to_string(),
             overwrite: false, // Should not overwrite
             supplement: false,
+            remote: None,
+            interactive: false,
+            import: None,
         };
 
         let context = CommandContext {
             config: Config {
                 repositories: vec![],
                 recipes: vec![],
+                artifacts_remote: None,
+                log_sinks: Vec::new(),
+                path_strategy: Default::default(),
+                root: None,
+                files: Vec::new(),
+                branch_protection: Vec::new(),
+                repository_settings: Vec::new(),
+                post_clone: Vec::new(),
+                pr: None,
+                provider: None,
+                provider_url: None,
+                git: Default::default(),
+                exit_codes: Default::default(),
+                notify: None,
+                profiles: Default::default(),
+                secrets: None,
+                profile_token: None,
             },
             tag: vec![],
             exclude_tag: vec![],
@@ -240,6 +515,9 @@ mod tests {
             output: "test.yaml".to_string(),
             overwrite: true,
             supplement: false,
+            remote: None,
+            interactive: false,
+            import: None,
         };
 
         assert_eq!(command.output, "test.yaml");
@@ -260,6 +538,23 @@ mod tests {
                 "git@github.com:owner/existing-repo.git".to_string(),
             )],
             recipes: vec![],
+            artifacts_remote: None,
+            log_sinks: Vec::new(),
+            path_strategy: Default::default(),
+            root: None,
+            files: Vec::new(),
+            branch_protection: Vec::new(),
+            repository_settings: Vec::new(),
+            post_clone: Vec::new(),
+            pr: None,
+            provider: None,
+            provider_url: None,
+            git: Default::default(),
+            exit_codes: Default::default(),
+            notify: None,
+            profiles: Default::default(),
+            secrets: None,
+            profile_token: None,
         };
         existing_config
             .save(&output_path.to_string_lossy())
@@ -274,12 +569,32 @@ mod tests {
             output: output_path.to_string_lossy().to_string(),
             overwrite: false,
             supplement: true, // Should supplement existing config
+            remote: None,
+            interactive: false,
+            import: None,
         };
 
         let context = CommandContext {
             config: Config {
                 repositories: vec![],
                 recipes: vec![],
+                artifacts_remote: None,
+                log_sinks: Vec::new(),
+                path_strategy: Default::default(),
+                root: None,
+                files: Vec::new(),
+                branch_protection: Vec::new(),
+                repository_settings: Vec::new(),
+                post_clone: Vec::new(),
+                pr: None,
+                provider: None,
+                provider_url: None,
+                git: Default::default(),
+                exit_codes: Default::default(),
+                notify: None,
+                profiles: Default::default(),
+                secrets: None,
+                profile_token: None,
             },
             tag: vec![],
             exclude_tag: vec![],
@@ -313,12 +628,32 @@ mod tests {
             output: output_path.to_string_lossy().to_string(),
             overwrite: false,
             supplement: true, // Should create new config since none exists
+            remote: None,
+            interactive: false,
+            import: None,
         };
 
         let context = CommandContext {
             config: Config {
                 repositories: vec![],
                 recipes: vec![],
+                artifacts_remote: None,
+                log_sinks: Vec::new(),
+                path_strategy: Default::default(),
+                root: None,
+                files: Vec::new(),
+                branch_protection: Vec::new(),
+                repository_settings: Vec::new(),
+                post_clone: Vec::new(),
+                pr: None,
+                provider: None,
+                provider_url: None,
+                git: Default::default(),
+                exit_codes: Default::default(),
+                notify: None,
+                profiles: Default::default(),
+                secrets: None,
+                profile_token: None,
             },
             tag: vec![],
             exclude_tag: vec![],
@@ -335,4 +670,64 @@ mod tests {
         // Restore original directory
         std::env::set_current_dir(original_dir).unwrap();
     }
+
+    #[test]
+    fn test_parse_tags() {
+        assert_eq!(
+            parse_tags("backend, rust  gh:cli,,backend"),
+            vec!["backend", "rust", "gh:cli"]
+        );
+        assert!(parse_tags(" , ").is_empty());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_init_command_interactive_requires_terminal() {
+        // Only meaningful when tests are not attached to a terminal
+        if interactive::is_terminal() {
+            return;
+        }
+        let temp_dir = TempDir::new().unwrap();
+        let original_dir = std::env::current_dir().unwrap();
+        let repo_dir = temp_dir.path().join("api");
+        fs::create_dir_all(&repo_dir).unwrap();
+        for args in [
+            vec!["init", "-q"],
+            vec!["remote", "add", "origin", "git@github.com:org/api.git"],
+        ] {
+            std::process::Command::new("git")
+                .args(&args)
+                .current_dir(&repo_dir)
+                .output()
+                .unwrap();
+        }
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+
+        let output_path = temp_dir.path().join("repos.yaml");
+        let command = InitCommand {
+            output: output_path.to_string_lossy().to_string(),
+            overwrite: false,
+            supplement: false,
+            remote: None,
+            interactive: true,
+            import: None,
+        };
+        let context = CommandContext {
+            config: Config::new(),
+            tag: vec![],
+            exclude_tag: vec![],
+            repos: None,
+            parallel: false,
+        };
+        let result = command.execute(&context).await;
+        std::env::set_current_dir(original_dir).unwrap();
+
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("requires a terminal")
+        );
+        assert!(!output_path.exists());
+    }
 }
@@ -0,0 +1,346 @@
+//! Inventory command implementation
+
+use super::stats::csv_field;
+use super::{Command, CommandContext, GlobalOptions, validators};
+use crate::config::{Config, Provider, Repository};
+use crate::git::common::LoggedOutput;
+use crate::git::get_remote_default_branch;
+use crate::utils::{detect_frameworks, detect_languages};
+use crate::{constants, logging};
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use clap::Args;
+use colored::*;
+use log::Level;
+use serde::Serialize;
+use std::path::Path;
+use std::process::Command as ProcessCommand;
+use std::str::FromStr;
+
+/// File names, without extension, that hold a repository's license
+const LICENSE_FILES: &[&str] = &["LICENSE", "LICENCE", "COPYING", "UNLICENSE"];
+
+/// Output format of the inventory
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InventoryFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+impl FromStr for InventoryFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "json" => Ok(InventoryFormat::Json),
+            "csv" => Ok(InventoryFormat::Csv),
+            _ => anyhow::bail!("Unknown inventory format '{}' (expected json or csv)", s),
+        }
+    }
+}
+
+/// Inventory command exporting repository metadata for other systems
+#[derive(Debug, Clone, Default)]
+pub struct InventoryCommand {
+    pub format: InventoryFormat,
+}
+
+/// Metadata of one repository
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct InventoryEntry {
+    pub name: String,
+    pub url: String,
+    pub provider: Provider,
+    pub tags: Vec<String>,
+    /// False when the repository is not cloned; the fields read from the clone are then empty
+    pub cloned: bool,
+    /// Languages detected from the manifest files at the repository root
+    pub languages: Vec<String>,
+    /// Frameworks detected from the dependencies in those manifests
+    pub frameworks: Vec<String>,
+    /// Default branch of `origin`, or the configured one when the clone does not know it
+    pub default_branch: Option<String>,
+    /// Most recently created tag
+    pub latest_tag: Option<String>,
+    /// License file at the repository root, e.g. `LICENSE.md`
+    pub license_file: Option<String>,
+}
+
+impl InventoryEntry {
+    /// Collect the metadata of a repository from the config and its clone
+    pub fn collect(repo: &Repository) -> Self {
+        let mut entry = InventoryEntry {
+            name: repo.name.clone(),
+            url: repo.url.clone(),
+            provider: repo.provider(),
+            tags: repo.tags.clone(),
+            default_branch: repo.default_branch.clone(),
+            ..Default::default()
+        };
+        let target_dir = repo.get_target_dir();
+        let path = Path::new(&target_dir);
+        if !path.is_dir() {
+            return entry;
+        }
+
+        entry.cloned = true;
+        entry.languages = detect_languages(path);
+        entry.frameworks = detect_frameworks(path);
+        entry.default_branch = get_remote_default_branch(&target_dir).or(entry.default_branch);
+        entry.latest_tag = latest_tag(&target_dir);
+        entry.license_file = license_file(path);
+        entry
+    }
+}
+
+/// The inventory document printed as JSON
+#[derive(Debug, Clone, Serialize)]
+pub struct Inventory {
+    pub generated_at: DateTime<Utc>,
+    pub repositories: Vec<InventoryEntry>,
+}
+
+impl Inventory {
+    pub fn new(repositories: Vec<InventoryEntry>) -> Self {
+        Self {
+            generated_at: Utc::now(),
+            repositories,
+        }
+    }
+
+    /// Render one row per repository as CSV
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "name,url,provider,tags,cloned,languages,frameworks,default_branch,latest_tag,license_file\n",
+        );
+        for entry in &self.repositories {
+            let row = [
+                entry.name.clone(),
+                entry.url.clone(),
+                entry.provider.as_str().to_string(),
+                entry.tags.join(";"),
+                entry.cloned.to_string(),
+                entry.languages.join(";"),
+                entry.frameworks.join(";"),
+                entry.default_branch.clone().unwrap_or_default(),
+                entry.latest_tag.clone().unwrap_or_default(),
+                entry.license_file.clone().unwrap_or_default(),
+            ];
+            let row: Vec<String> = row.iter().map(|field| csv_field(field)).collect();
+            csv.push_str(&row.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+/// The most recently created tag of a clone
+fn latest_tag(repo_path: &str) -> Option<String> {
+    let output = ProcessCommand::new("git")
+        .args([
+            "for-each-ref",
+            "--sort=-creatordate",
+            "--count=1",
+            "--format=%(refname:short)",
+            "refs/tags",
+        ])
+        .current_dir(repo_path)
+        .logged_output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let tag = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!tag.is_empty()).then_some(tag)
+}
+
+/// Name of the license file at the root of a clone, e.g. `LICENSE` or `COPYING.txt`
+fn license_file(path: &Path) -> Option<String> {
+    let mut names: Vec<String> = std::fs::read_dir(path)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_file())
+        .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+        .filter(|name| {
+            let stem = name.split('.').next().unwrap_or(name).to_uppercase();
+            LICENSE_FILES
+                .iter()
+                .any(|license| stem == *license || stem.starts_with(&format!("{}-", license)))
+        })
+        .collect();
+    names.sort();
+    names.into_iter().next()
+}
+
+#[async_trait]
+impl Command for InventoryCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.config.filter_repositories(
+            &context.tag,
+            &context.exclude_tag,
+            context.repos.as_deref(),
+        );
+
+        if repositories.is_empty() {
+            logging::message(Level::Info, "No repositories found".yellow());
+            return Ok(());
+        }
+
+        let mut entries = Vec::new();
+        if context.parallel {
+            let tasks: Vec<_> = repositories
+                .into_iter()
+                .map(|repo| {
+                    tokio::task::spawn_blocking(move || {
+                        logging::with_repository(&repo.name, || InventoryEntry::collect(&repo))
+                    })
+                })
+                .collect();
+
+            for task in tasks {
+                entries.push(task.await?);
+            }
+        } else {
+            for repo in &repositories {
+                entries.push(logging::with_repository(&repo.name, || {
+                    InventoryEntry::collect(repo)
+                }));
+            }
+        }
+
+        let inventory = Inventory::new(entries);
+        match self.format {
+            InventoryFormat::Json => println!("{}", serde_json::to_string_pretty(&inventory)?),
+            InventoryFormat::Csv => print!("{}", inventory.to_csv()),
+        }
+
+        Ok(())
+    }
+}
+
+/// Arguments of `repos inventory`
+#[derive(Debug, Args)]
+pub struct InventoryArgs {
+    /// Specific repository names to include (if not provided, uses tag filter or all repos)
+    pub repos: Vec<String>,
+
+    /// Output format
+    #[arg(long, default_value = "json", value_parser = ["json", "csv"])]
+    pub format: String,
+
+    /// Configuration file path (can be specified multiple times to combine fleets)
+    #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+    pub config: Vec<String>,
+
+    /// Filter repositories by tag (can be specified multiple times)
+    #[arg(short, long)]
+    pub tag: Vec<String>,
+
+    /// Exclude repositories with these tags (can be specified multiple times)
+    #[arg(short = 'e', long)]
+    pub exclude_tag: Vec<String>,
+
+    /// Execute operations in parallel
+    #[arg(short, long)]
+    pub parallel: bool,
+}
+
+impl InventoryArgs {
+    /// Validate the arguments and run `repos inventory`
+    pub async fn run(self, globals: &GlobalOptions) -> Result<()> {
+        let Self {
+            repos,
+            format,
+            config,
+            tag,
+            exclude_tag,
+            parallel,
+        } = self;
+        let config = Config::load_all(&config)?;
+
+        validators::validate_tag_filters(&tag)?;
+        validators::validate_tag_filters(&exclude_tag)?;
+        validators::validate_repository_names(&repos)?;
+
+        let context = globals
+            .context(config, tag, exclude_tag, repos)
+            .with_parallel(parallel);
+
+        InventoryCommand {
+            format: format.parse()?,
+        }
+        .execute(&context)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_collect_reads_clone() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().join("web");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("package.json"),
+            r#"{"dependencies": {"react": "^18.0.0", "express": "^4.0.0"}}"#,
+        )
+        .unwrap();
+        fs::write(dir.join("LICENSE.md"), "MIT").unwrap();
+        fs::write(dir.join("README.md"), "web").unwrap();
+
+        let mut repo = Repository::new("web".to_string(), "git@github.com:org/web.git".to_string());
+        repo.path = Some(dir.to_string_lossy().to_string());
+        repo.tags = vec!["frontend".to_string()];
+        repo.default_branch = Some("main".to_string());
+
+        let entry = InventoryEntry::collect(&repo);
+        assert!(entry.cloned);
+        assert_eq!(entry.languages, vec!["javascript", "node"]);
+        assert_eq!(entry.frameworks, vec!["react", "express"]);
+        assert_eq!(entry.license_file.as_deref(), Some("LICENSE.md"));
+        assert_eq!(entry.default_branch.as_deref(), Some("main"));
+        assert_eq!(entry.latest_tag, None);
+
+        repo.path = Some(
+            temp_dir
+                .path()
+                .join("missing")
+                .to_string_lossy()
+                .to_string(),
+        );
+        let entry = InventoryEntry::collect(&repo);
+        assert!(!entry.cloned);
+        assert!(entry.languages.is_empty());
+        assert_eq!(entry.default_branch.as_deref(), Some("main"));
+    }
+
+    #[test]
+    fn test_to_csv() {
+        let inventory = Inventory::new(vec![InventoryEntry {
+            name: "api".to_string(),
+            url: "git@github.com:org/api.git".to_string(),
+            tags: vec!["backend".to_string(), "rust".to_string()],
+            cloned: true,
+            languages: vec!["rust".to_string()],
+            frameworks: vec!["axum".to_string()],
+            default_branch: Some("main".to_string()),
+            latest_tag: Some("v1.2.0".to_string()),
+            license_file: Some("LICENSE".to_string()),
+            ..Default::default()
+        }]);
+        let csv = inventory.to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            lines[1],
+            "api,git@github.com:org/api.git,github,backend;rust,true,rust,axum,main,v1.2.0,LICENSE"
+        );
+    }
+}
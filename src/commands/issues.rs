@@ -0,0 +1,781 @@
+//! Issue management across repositories
+
+use super::{Command, CommandContext, GlobalOptions, validators};
+use crate::config::{Config, Repository};
+use crate::credentials::Credentials;
+use crate::github::placeholders::Placeholders;
+use crate::{constants, logging};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use clap::Subcommand;
+use colored::*;
+use futures::StreamExt;
+use log::Level;
+use repos_github::{GitHubClient, Issue, NewIssue, parse_github_url};
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Repositories queried at the same time
+const CONCURRENCY: usize = 8;
+
+/// An open issue of one repository
+struct Target {
+    repository: String,
+    owner: String,
+    repo: String,
+    token: Option<String>,
+    issue: Issue,
+}
+
+/// One row of `repos issues list --json`
+#[derive(Debug, Serialize)]
+struct IssueRow<'a> {
+    repository: &'a str,
+    number: u64,
+    title: &'a str,
+    url: &'a str,
+    author: Option<&'a str>,
+    labels: Vec<&'a str>,
+    updated_at: &'a str,
+}
+
+/// GitHub repositories matching the filters, warning about the others
+fn github_repositories(context: &CommandContext) -> Vec<Repository> {
+    let (repositories, other): (Vec<Repository>, Vec<Repository>) = context
+        .config
+        .filter_repositories(&context.tag, &context.exclude_tag, context.repos.as_deref())
+        .into_iter()
+        .partition(|repo| repo.provider().is_github());
+
+    if !other.is_empty() {
+        logging::message(
+            Level::Warn,
+            format!("Skipping {} repositories not hosted on GitHub", other.len()).yellow(),
+        );
+    }
+    repositories
+}
+
+/// Open issues carrying all of `labels` in each repository, logging the repositories that fail
+async fn find_targets(
+    credentials: &Credentials,
+    repositories: &[Repository],
+    labels: &[String],
+    errors: &mut Vec<(String, anyhow::Error)>,
+) -> Vec<Target> {
+    let queries: Vec<_> = repositories
+        .iter()
+        .map(|repo| async move {
+            let (owner, name) = parse_github_url(&repo.url)?;
+            let client = GitHubClient::new(credentials.token_for_repository(repo));
+            let issues = client.list_open_issues(&owner, &name, labels).await?;
+            Ok::<_, anyhow::Error>((owner, name, issues))
+        })
+        .collect();
+    let results: Vec<_> = futures::stream::iter(queries)
+        .buffered(CONCURRENCY)
+        .collect()
+        .await;
+
+    let mut targets = Vec::new();
+    for (repo, result) in repositories.iter().zip(results) {
+        match result {
+            Ok((owner, name, issues)) => {
+                targets.extend(issues.into_iter().map(|issue| Target {
+                    repository: repo.name.clone(),
+                    owner: owner.clone(),
+                    repo: name.clone(),
+                    token: credentials.token_for_repository(repo),
+                    issue,
+                }));
+            }
+            Err(e) => {
+                logging::status(
+                    Level::Error,
+                    &repo.name,
+                    format!("Error: Failed to list issues: {e}").red(),
+                );
+                errors.push((repo.name.clone(), e));
+            }
+        }
+    }
+    targets
+}
+
+fn fail_on_errors(errors: &[(String, anyhow::Error)]) -> Result<()> {
+    if let Some((repo_name, e)) = errors.first() {
+        anyhow::bail!(
+            "{} repository operation(s) failed. First error ({}): {}",
+            errors.len(),
+            repo_name,
+            e
+        );
+    }
+    Ok(())
+}
+
+/// Lists open issues matching labels across repositories
+pub struct IssuesListCommand {
+    pub token: Option<String>,
+    /// Only list issues carrying all of these labels
+    pub labels: Vec<String>,
+    pub json: bool,
+}
+
+#[async_trait]
+impl Command for IssuesListCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = github_repositories(context);
+        if repositories.is_empty() {
+            logging::message(Level::Info, "No GitHub repositories found".yellow());
+            return Ok(());
+        }
+
+        let credentials = Credentials::load(self.token.clone());
+        let mut errors = Vec::new();
+        let targets = find_targets(&credentials, &repositories, &self.labels, &mut errors).await;
+
+        if self.json {
+            let rows: Vec<IssueRow> = targets
+                .iter()
+                .map(|target| IssueRow {
+                    repository: &target.repository,
+                    number: target.issue.number,
+                    title: &target.issue.title,
+                    url: &target.issue.html_url,
+                    author: target.issue.user.as_ref().map(|user| user.login.as_str()),
+                    labels: target
+                        .issue
+                        .labels
+                        .iter()
+                        .map(|label| label.name.as_str())
+                        .collect(),
+                    updated_at: &target.issue.updated_at,
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&rows)?);
+        } else if targets.is_empty() {
+            logging::message(Level::Info, "No matching open issues found".yellow());
+        } else {
+            print_table(&targets);
+            let repository_count = {
+                let mut names: Vec<&str> = targets.iter().map(|t| t.repository.as_str()).collect();
+                names.dedup();
+                names.len()
+            };
+            logging::message(
+                Level::Info,
+                format!(
+                    "{} open issue(s) in {} repositories",
+                    targets.len(),
+                    repository_count
+                )
+                .green(),
+            );
+        }
+
+        fail_on_errors(&errors)
+    }
+}
+
+/// Print one row per issue
+fn print_table(targets: &[Target]) {
+    let name_width = targets
+        .iter()
+        .map(|t| t.repository.len())
+        .max()
+        .unwrap_or(0)
+        .max("REPOSITORY".len());
+
+    println!();
+    println!(
+        "{:<name_width$}  {:>6}  {:<50}  {}",
+        "REPOSITORY".bold(),
+        "ISSUE".bold(),
+        "TITLE".bold(),
+        "LABELS".bold(),
+    );
+    for target in targets {
+        let labels: Vec<&str> = target
+            .issue
+            .labels
+            .iter()
+            .map(|label| label.name.as_str())
+            .collect();
+        println!(
+            "{:<name_width$}  {:>6}  {:<50}  {}",
+            target.repository.cyan(),
+            format!("#{}", target.issue.number),
+            truncate(&target.issue.title, 50),
+            labels.join(", ").dimmed(),
+        );
+    }
+    println!();
+}
+
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        text.to_string()
+    } else {
+        let mut truncated: String = text.chars().take(width - 1).collect();
+        truncated.push('…');
+        truncated
+    }
+}
+
+/// Opens the same templated issue in every selected repository
+///
+/// The title and body may use [`Placeholders`] such as `{{repo.name}}`,
+/// replaced per repository. Repositories that already have an open issue with
+/// the same title are skipped, so the command can be run again after a failure.
+pub struct IssuesCreateCommand {
+    pub token: Option<String>,
+    pub title: String,
+    pub body: String,
+    pub labels: Vec<String>,
+    /// Show the issues that would be opened without opening them
+    pub dry_run: bool,
+}
+
+impl IssuesCreateCommand {
+    async fn create(&self, credentials: &Credentials, repo: &Repository) -> Result<Option<Issue>> {
+        let (owner, name) = parse_github_url(&repo.url)?;
+        let client = GitHubClient::new(credentials.token_for_repository(repo));
+        let title = Placeholders::default().fill(&self.title, repo);
+
+        let existing = client.list_open_issues(&owner, &name, &[]).await?;
+        if existing.iter().any(|issue| issue.title == title) {
+            return Ok(None);
+        }
+        if self.dry_run {
+            logging::status(
+                Level::Info,
+                &repo.name,
+                format!("Would open \"{}\"", title).yellow(),
+            );
+            return Ok(None);
+        }
+
+        let body = Placeholders::default().fill(&self.body, repo);
+        let issue = client
+            .create_issue(
+                &owner,
+                &name,
+                &NewIssue {
+                    title: &title,
+                    body: &body,
+                    labels: &self.labels,
+                },
+            )
+            .await?;
+        Ok(Some(issue))
+    }
+}
+
+#[async_trait]
+impl Command for IssuesCreateCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = github_repositories(context);
+        if repositories.is_empty() {
+            logging::message(Level::Info, "No GitHub repositories found".yellow());
+            return Ok(());
+        }
+
+        let credentials = Credentials::load(self.token.clone());
+        let mut created = 0;
+        let mut skipped = 0;
+        let mut errors = Vec::new();
+        for repo in &repositories {
+            match self.create(&credentials, repo).await {
+                Ok(Some(issue)) => {
+                    logging::status(
+                        Level::Info,
+                        &repo.name,
+                        format!(
+                            "{} {}",
+                            format!("Opened #{}", issue.number).green(),
+                            issue.html_url
+                        ),
+                    );
+                    created += 1;
+                }
+                Ok(None) if self.dry_run => created += 1,
+                Ok(None) => {
+                    logging::status(
+                        Level::Info,
+                        &repo.name,
+                        "An open issue with this title already exists".yellow(),
+                    );
+                    skipped += 1;
+                }
+                Err(e) => {
+                    logging::status(
+                        Level::Error,
+                        &repo.name,
+                        format!("Error: Failed to open issue: {e}").red(),
+                    );
+                    errors.push((repo.name.clone(), e));
+                }
+            }
+        }
+
+        if self.dry_run {
+            if let Some(repo) = repositories.first() {
+                logging::message(Level::Info, "");
+                logging::message(Level::Info, "Body preview:".bold());
+                logging::message(Level::Info, Placeholders::default().fill(&self.body, repo));
+                logging::message(Level::Info, "");
+            }
+            logging::message(
+                Level::Info,
+                format!("Would open {} issue(s)", created).green(),
+            );
+        } else {
+            logging::message(
+                Level::Info,
+                format!(
+                    "Opened {} issue(s), skipped {}, failed {}",
+                    created,
+                    skipped,
+                    errors.len()
+                )
+                .green(),
+            );
+        }
+
+        fail_on_errors(&errors)
+    }
+}
+
+/// What to do with each matching issue
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IssueUpdate {
+    /// Close the issue, commenting first when a comment is given
+    Close { comment: Option<String> },
+    /// Add labels, keeping the ones the issue already has
+    Label { labels: Vec<String> },
+}
+
+impl IssueUpdate {
+    fn describe(&self, number: u64) -> String {
+        match self {
+            IssueUpdate::Close { .. } => format!("close #{}", number),
+            IssueUpdate::Label { labels } => format!("label #{} {}", number, labels.join(", ")),
+        }
+    }
+}
+
+/// Closes or labels every open issue carrying all of the selecting labels
+pub struct IssuesUpdateCommand {
+    pub token: Option<String>,
+    /// Labels selecting the issues; at least one is required
+    pub labels: Vec<String>,
+    pub update: IssueUpdate,
+    /// Show the matching issues without changing them
+    pub dry_run: bool,
+}
+
+impl IssuesUpdateCommand {
+    async fn apply(&self, target: &Target) -> Result<()> {
+        let client = GitHubClient::new(target.token.clone());
+        let (owner, repo, number) = (&target.owner, &target.repo, target.issue.number);
+        match &self.update {
+            IssueUpdate::Close { comment } => {
+                if let Some(comment) = comment {
+                    client
+                        .create_issue_comment(owner, repo, number, comment)
+                        .await?;
+                }
+                client.close_issue(owner, repo, number).await?;
+            }
+            IssueUpdate::Label { labels } => {
+                client.add_issue_labels(owner, repo, number, labels).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Command for IssuesUpdateCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        if self.labels.is_empty() {
+            anyhow::bail!("At least one --label is required to select the issues");
+        }
+
+        let repositories = github_repositories(context);
+        if repositories.is_empty() {
+            logging::message(Level::Info, "No GitHub repositories found".yellow());
+            return Ok(());
+        }
+
+        let credentials = Credentials::load(self.token.clone());
+        let mut errors = Vec::new();
+        let targets = find_targets(&credentials, &repositories, &self.labels, &mut errors).await;
+        if targets.is_empty() {
+            logging::message(Level::Info, "No matching open issues found".yellow());
+        }
+
+        let mut updated = 0;
+        for target in &targets {
+            let action = self.update.describe(target.issue.number);
+            if self.dry_run {
+                logging::status(
+                    Level::Info,
+                    &target.repository,
+                    format!(
+                        "{} {}",
+                        format!("Would {} ({})", action, target.issue.title).yellow(),
+                        target.issue.html_url
+                    ),
+                );
+                updated += 1;
+                continue;
+            }
+            match self.apply(target).await {
+                Ok(()) => {
+                    logging::status(
+                        Level::Info,
+                        &target.repository,
+                        format!("Done: {}", action).green(),
+                    );
+                    updated += 1;
+                }
+                Err(e) => {
+                    logging::status(
+                        Level::Error,
+                        &target.repository,
+                        format!("Error: Failed to {}: {e}", action).red(),
+                    );
+                    errors.push((target.repository.clone(), e));
+                }
+            }
+        }
+
+        let summary = if self.dry_run {
+            format!("Would update {} issue(s)", updated)
+        } else {
+            format!("Updated {} issue(s), failed {}", updated, errors.len())
+        };
+        logging::message(Level::Info, summary.green());
+
+        fail_on_errors(&errors)
+    }
+}
+
+/// Subcommands of `repos issues`
+#[derive(Debug, Subcommand)]
+pub enum IssuesAction {
+    /// List open issues, optionally only those carrying given labels
+    List {
+        /// Specific repository names to list (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Only list issues with this label (can be specified multiple times)
+        #[arg(short, long)]
+        label: Vec<String>,
+
+        /// Output in JSON format for machine consumption
+        #[arg(long)]
+        json: bool,
+
+        /// GitHub token
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Configuration file path (can be specified multiple times to combine fleets)
+        #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+        config: Vec<String>,
+
+        /// Filter repositories by tag (can be specified multiple times)
+        #[arg(short, long)]
+        tag: Vec<String>,
+
+        /// Exclude repositories with these tags (can be specified multiple times)
+        #[arg(short = 'e', long)]
+        exclude_tag: Vec<String>,
+    },
+
+    /// Open the same issue in every repository, skipping those where it is already open
+    Create {
+        /// Specific repository names to open the issue in (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Issue title; supports {{repo.name}}, {{repo.url}}, {{repo.owner}} and {{repo.repo}}
+        #[arg(long)]
+        title: String,
+
+        /// Issue body; supports the same placeholders as the title
+        #[arg(long, default_value = "", conflicts_with = "body_file")]
+        body: String,
+
+        /// Read the issue body from a file
+        #[arg(long, value_name = "FILE")]
+        body_file: Option<PathBuf>,
+
+        /// Label to add to the issue (can be specified multiple times)
+        #[arg(short, long)]
+        label: Vec<String>,
+
+        /// Show the issues that would be opened without opening them
+        #[arg(long)]
+        dry_run: bool,
+
+        /// GitHub token
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Configuration file path (can be specified multiple times to combine fleets)
+        #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+        config: Vec<String>,
+
+        /// Filter repositories by tag (can be specified multiple times)
+        #[arg(short, long)]
+        tag: Vec<String>,
+
+        /// Exclude repositories with these tags (can be specified multiple times)
+        #[arg(short = 'e', long)]
+        exclude_tag: Vec<String>,
+    },
+
+    /// Close every open issue carrying the given labels
+    Close {
+        /// Specific repository names to close issues in (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Close issues with this label (can be specified multiple times, all must match)
+        #[arg(short, long, required = true)]
+        label: Vec<String>,
+
+        /// Comment to post on each issue before closing it
+        #[arg(long)]
+        comment: Option<String>,
+
+        /// List the issues that would be closed without closing them
+        #[arg(long)]
+        dry_run: bool,
+
+        /// GitHub token
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Configuration file path (can be specified multiple times to combine fleets)
+        #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+        config: Vec<String>,
+
+        /// Filter repositories by tag (can be specified multiple times)
+        #[arg(short, long)]
+        tag: Vec<String>,
+
+        /// Exclude repositories with these tags (can be specified multiple times)
+        #[arg(short = 'e', long)]
+        exclude_tag: Vec<String>,
+    },
+
+    /// Add labels to every open issue carrying the given labels
+    Label {
+        /// Specific repository names to label issues in (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Select issues with this label (can be specified multiple times, all must match)
+        #[arg(short, long, required = true)]
+        label: Vec<String>,
+
+        /// Label to add (can be specified multiple times)
+        #[arg(long, required = true)]
+        add: Vec<String>,
+
+        /// List the issues that would be labeled without labeling them
+        #[arg(long)]
+        dry_run: bool,
+
+        /// GitHub token
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Configuration file path (can be specified multiple times to combine fleets)
+        #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+        config: Vec<String>,
+
+        /// Filter repositories by tag (can be specified multiple times)
+        #[arg(short, long)]
+        tag: Vec<String>,
+
+        /// Exclude repositories with these tags (can be specified multiple times)
+        #[arg(short = 'e', long)]
+        exclude_tag: Vec<String>,
+    },
+}
+
+impl IssuesAction {
+    /// Validate the arguments and run `repos issues <action>`
+    pub async fn run(self, globals: &GlobalOptions) -> Result<()> {
+        match self {
+            IssuesAction::List {
+                repos,
+                label,
+                json,
+                token,
+                config,
+                tag,
+                exclude_tag,
+            } => {
+                let config = Config::load_all(&config)?;
+
+                validators::validate_tag_filters(&tag)?;
+                validators::validate_tag_filters(&exclude_tag)?;
+                validators::validate_repository_names(&repos)?;
+
+                let token = token.or_else(|| config.resolve_profile_token());
+                let context = globals.context(config, tag, exclude_tag, repos);
+                IssuesListCommand {
+                    token,
+                    labels: label,
+                    json,
+                }
+                .execute(&context)
+                .await?;
+            }
+            IssuesAction::Create {
+                repos,
+                title,
+                body,
+                body_file,
+                label,
+                dry_run,
+                token,
+                config,
+                tag,
+                exclude_tag,
+            } => {
+                let config = Config::load_all(&config)?;
+
+                validators::validate_tag_filters(&tag)?;
+                validators::validate_tag_filters(&exclude_tag)?;
+                validators::validate_repository_names(&repos)?;
+
+                let body = match body_file {
+                    Some(path) => std::fs::read_to_string(&path)
+                        .with_context(|| format!("Failed to read issue body {}", path.display()))?,
+                    None => body,
+                };
+
+                let token = token.or_else(|| config.resolve_profile_token());
+                let mut context = globals.context(config, tag, exclude_tag, repos);
+                context.skip_archived(globals.include_archived);
+                IssuesCreateCommand {
+                    token,
+                    title,
+                    body,
+                    labels: label,
+                    dry_run,
+                }
+                .execute(&context)
+                .await?;
+            }
+            IssuesAction::Close {
+                repos,
+                label,
+                comment,
+                dry_run,
+                token,
+                config,
+                tag,
+                exclude_tag,
+            } => {
+                let config = Config::load_all(&config)?;
+
+                validators::validate_tag_filters(&tag)?;
+                validators::validate_tag_filters(&exclude_tag)?;
+                validators::validate_repository_names(&repos)?;
+
+                let token = token.or_else(|| config.resolve_profile_token());
+                let mut context = globals.context(config, tag, exclude_tag, repos);
+                context.skip_archived(globals.include_archived);
+                IssuesUpdateCommand {
+                    token,
+                    labels: label,
+                    update: IssueUpdate::Close { comment },
+                    dry_run,
+                }
+                .execute(&context)
+                .await?;
+            }
+            IssuesAction::Label {
+                repos,
+                label,
+                add,
+                dry_run,
+                token,
+                config,
+                tag,
+                exclude_tag,
+            } => {
+                let config = Config::load_all(&config)?;
+
+                validators::validate_tag_filters(&tag)?;
+                validators::validate_tag_filters(&exclude_tag)?;
+                validators::validate_repository_names(&repos)?;
+
+                let token = token.or_else(|| config.resolve_profile_token());
+                let mut context = globals.context(config, tag, exclude_tag, repos);
+                context.skip_archived(globals.include_archived);
+                IssuesUpdateCommand {
+                    token,
+                    labels: label,
+                    update: IssueUpdate::Label { labels: add },
+                    dry_run,
+                }
+                .execute(&context)
+                .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn context() -> CommandContext {
+        CommandContext {
+            config: Config::new(),
+            tag: vec![],
+            exclude_tag: vec![],
+            parallel: false,
+            repos: None,
+        }
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("short", 10), "short");
+        assert_eq!(truncate("a longer title", 8), "a longe…");
+    }
+
+    #[test]
+    fn test_issue_update_describe() {
+        assert_eq!(IssueUpdate::Close { comment: None }.describe(3), "close #3");
+        assert_eq!(
+            IssueUpdate::Label {
+                labels: vec!["stale".into(), "wontfix".into()]
+            }
+            .describe(4),
+            "label #4 stale, wontfix"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_requires_label() {
+        let command = IssuesUpdateCommand {
+            token: None,
+            labels: vec![],
+            update: IssueUpdate::Close { comment: None },
+            dry_run: true,
+        };
+        let err = command.execute(&context()).await.unwrap_err();
+        assert!(err.to_string().contains("--label"));
+    }
+}
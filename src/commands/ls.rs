@@ -1,10 +1,16 @@
 //! List command implementation
 
-use super::{Command, CommandContext};
+use super::stats::csv_field;
+use super::{Command, CommandContext, GlobalOptions, validators};
+use crate::config::{Config, Repository};
+use crate::constants;
 use anyhow::Result;
 use async_trait::async_trait;
+use clap::Args;
 use colored::*;
 use serde::Serialize;
+use std::io::Write;
+use std::str::FromStr;
 
 /// Output format for a repository in JSON mode
 #[derive(Serialize)]
@@ -19,10 +25,154 @@ struct RepositoryOutput {
     branch: Option<String>,
 }
 
+/// Single field printed per repository, for piping into other tools
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListColumn {
+    Urls,
+    /// Absolute local directories
+    Paths,
+}
+
+/// Delimited output format, one row per repository after a header row
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListFormat {
+    Csv,
+    Tsv,
+}
+
+impl FromStr for ListFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "csv" => Ok(ListFormat::Csv),
+            "tsv" => Ok(ListFormat::Tsv),
+            _ => anyhow::bail!("Unknown list format '{}' (expected csv or tsv)", s),
+        }
+    }
+}
+
+/// Field of a repository in delimited output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListField {
+    Name,
+    Url,
+    /// Tags joined with `;`
+    Tags,
+    /// Absolute local directory
+    Path,
+    /// Branch configured for cloning
+    Branch,
+}
+
+impl ListField {
+    /// Fields printed when none are selected
+    pub const DEFAULT: &[ListField] = &[
+        ListField::Name,
+        ListField::Url,
+        ListField::Tags,
+        ListField::Path,
+        ListField::Branch,
+    ];
+
+    fn header(self) -> &'static str {
+        match self {
+            ListField::Name => "name",
+            ListField::Url => "url",
+            ListField::Tags => "tags",
+            ListField::Path => "path",
+            ListField::Branch => "branch",
+        }
+    }
+
+    fn value(self, repo: &Repository) -> Result<String> {
+        Ok(match self {
+            ListField::Name => repo.name.clone(),
+            ListField::Url => repo.url.clone(),
+            ListField::Tags => repo.tags.join(";"),
+            ListField::Path => std::path::absolute(repo.get_target_dir())?
+                .to_string_lossy()
+                .to_string(),
+            ListField::Branch => repo.branch.clone().unwrap_or_default(),
+        })
+    }
+}
+
+impl FromStr for ListField {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        ListField::DEFAULT
+            .iter()
+            .copied()
+            .find(|field| field.header() == s)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Unknown column '{}' (expected name, url, tags, path or branch)",
+                    s
+                )
+            })
+    }
+}
+
 /// List command for displaying repositories with optional filtering
 pub struct ListCommand {
     /// Output in JSON format
     pub json: bool,
+    /// Print only this field, one repository per line
+    pub column: Option<ListColumn>,
+    /// Terminate `column` entries with NUL instead of a newline
+    pub null_separated: bool,
+    /// Print a CSV or TSV table instead
+    pub format: Option<ListFormat>,
+    /// Columns of the table; [`ListField::DEFAULT`] when empty
+    pub fields: Vec<ListField>,
+}
+
+impl ListCommand {
+    /// One entry per repository, each followed by the separator
+    fn column_output(&self, column: ListColumn, repositories: &[Repository]) -> Result<String> {
+        let separator = if self.null_separated { '\0' } else { '\n' };
+        let mut output = String::new();
+        for repo in repositories {
+            match column {
+                ListColumn::Urls => output.push_str(&repo.url),
+                ListColumn::Paths => {
+                    let path = std::path::absolute(repo.get_target_dir())?;
+                    output.push_str(&path.to_string_lossy());
+                }
+            }
+            output.push(separator);
+        }
+        Ok(output)
+    }
+
+    /// A header row and one row per repository
+    fn table_output(&self, format: ListFormat, repositories: &[Repository]) -> Result<String> {
+        let fields = if self.fields.is_empty() {
+            ListField::DEFAULT
+        } else {
+            &self.fields
+        };
+        let (separator, escape): (&str, fn(&str) -> String) = match format {
+            ListFormat::Csv => (",", csv_field),
+            // TSV has no quoting, so tabs and line breaks inside a field become spaces
+            ListFormat::Tsv => ("\t", |field| field.replace(['\t', '\n', '\r'], " ")),
+        };
+
+        let header: Vec<&str> = fields.iter().map(|field| field.header()).collect();
+        let mut output = header.join(separator);
+        output.push('\n');
+        for repo in repositories {
+            let row = fields
+                .iter()
+                .map(|field| field.value(repo).map(|value| escape(&value)))
+                .collect::<Result<Vec<_>>>()?;
+            output.push_str(&row.join(separator));
+            output.push('\n');
+        }
+        Ok(output)
+    }
 }
 
 #[async_trait]
@@ -34,6 +184,19 @@ impl Command for ListCommand {
             context.repos.as_deref(),
         );
 
+        if let Some(column) = self.column {
+            let output = self.column_output(column, &repositories)?;
+            let mut stdout = std::io::stdout().lock();
+            stdout.write_all(output.as_bytes())?;
+            stdout.flush()?;
+            return Ok(());
+        }
+
+        if let Some(format) = self.format {
+            print!("{}", self.table_output(format, &repositories)?);
+            return Ok(());
+        }
+
         if self.json {
             // JSON output mode
             let output: Vec<RepositoryOutput> = repositories
@@ -115,10 +278,102 @@ impl Command for ListCommand {
     }
 }
 
+/// Arguments of `repos ls`
+#[derive(Debug, Args)]
+pub struct LsArgs {
+    /// Specific repository names to list (if not provided, uses tag filter or all repos)
+    pub repos: Vec<String>,
+
+    /// Configuration file path (can be specified multiple times to combine fleets)
+    #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+    pub config: Vec<String>,
+
+    /// Filter repositories by tag (can be specified multiple times)
+    #[arg(short, long)]
+    pub tag: Vec<String>,
+
+    /// Exclude repositories with these tags (can be specified multiple times)
+    #[arg(short = 'e', long)]
+    pub exclude_tag: Vec<String>,
+
+    /// Output in JSON format for machine consumption
+    #[arg(long, conflicts_with_all = ["urls", "paths", "format"])]
+    pub json: bool,
+
+    /// Print a table for spreadsheets and shell pipelines: csv or tsv
+    #[arg(long, conflicts_with_all = ["urls", "paths"])]
+    pub format: Option<String>,
+
+    /// Comma-separated columns of the --format table: name, url, tags, path, branch
+    #[arg(long, value_delimiter = ',', requires = "format")]
+    pub columns: Vec<String>,
+
+    /// Print only the URL of each repository, one per line
+    #[arg(long, conflicts_with = "paths")]
+    pub urls: bool,
+
+    /// Print only the absolute local path of each repository, one per line
+    #[arg(long)]
+    pub paths: bool,
+
+    /// End each --urls or --paths entry with a NUL byte instead of a newline, for `xargs -0`
+    #[arg(short = '0', long)]
+    pub null: bool,
+}
+
+impl LsArgs {
+    /// Validate the arguments and run `repos ls`
+    pub async fn run(self, globals: &GlobalOptions) -> Result<()> {
+        let Self {
+            repos,
+            config,
+            tag,
+            exclude_tag,
+            json,
+            format,
+            columns,
+            urls,
+            paths,
+            null,
+        } = self;
+        let config = Config::load_all(&config)?;
+
+        // Validate list command arguments using centralized validators
+        validators::validate_tag_filters(&tag)?;
+        validators::validate_tag_filters(&exclude_tag)?;
+        validators::validate_repository_names(&repos)?;
+
+        let context = globals.context(config, tag, exclude_tag, repos);
+        let column = if urls {
+            Some(ListColumn::Urls)
+        } else if paths {
+            Some(ListColumn::Paths)
+        } else {
+            None
+        };
+        if null && column.is_none() {
+            anyhow::bail!("--null requires --urls or --paths");
+        }
+        ListCommand {
+            json,
+            column,
+            null_separated: null,
+            format: format.map(|format| format.parse()).transpose()?,
+            fields: columns
+                .iter()
+                .map(|column| column.trim().parse())
+                .collect::<Result<_>>()?,
+        }
+        .execute(&context)
+        .await
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::{Config, Repository};
+    use crate::config::Config;
+    use std::path::Path;
 
     /// Helper function to create a test config with repositories
     fn create_test_config() -> Config {
@@ -143,6 +398,23 @@ mod tests {
         Config {
             repositories: vec![repo1, repo2, repo3],
             recipes: vec![],
+            artifacts_remote: None,
+            log_sinks: Vec::new(),
+            path_strategy: Default::default(),
+            root: None,
+            files: Vec::new(),
+            branch_protection: Vec::new(),
+            repository_settings: Vec::new(),
+            post_clone: Vec::new(),
+            pr: None,
+            provider: None,
+            provider_url: None,
+            git: Default::default(),
+            exit_codes: Default::default(),
+            notify: None,
+            profiles: Default::default(),
+            secrets: None,
+            profile_token: None,
         }
     }
 
@@ -162,10 +434,88 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_column_output() {
+        let mut config = create_test_config();
+        config.repositories[0].path = Some("/src/one".to_string());
+        let command = ListCommand {
+            json: false,
+            column: Some(ListColumn::Urls),
+            null_separated: false,
+            format: None,
+            fields: Vec::new(),
+        };
+
+        assert_eq!(
+            command
+                .column_output(ListColumn::Urls, &config.repositories[..2])
+                .unwrap(),
+            "https://github.com/test/repo1.git\nhttps://github.com/test/repo2.git\n"
+        );
+
+        let command = ListCommand {
+            null_separated: true,
+            format: None,
+            fields: Vec::new(),
+            ..command
+        };
+        let paths = command
+            .column_output(ListColumn::Paths, &config.repositories)
+            .unwrap();
+        let paths: Vec<&str> = paths.split_terminator('\0').collect();
+        assert_eq!(paths.len(), 3);
+        assert_eq!(paths[0], "/src/one");
+        assert!(paths.iter().all(|path| Path::new(path).is_absolute()));
+    }
+
+    #[test]
+    fn test_table_output() {
+        let mut config = create_test_config();
+        config.repositories[0].branch = Some("main".to_string());
+        config.repositories[1].url = "https://example.com/a,b.git".to_string();
+        let command = ListCommand {
+            json: false,
+            column: None,
+            null_separated: false,
+            format: Some(ListFormat::Csv),
+            fields: vec![
+                ListField::Name,
+                ListField::Url,
+                ListField::Tags,
+                ListField::Branch,
+            ],
+        };
+
+        assert_eq!(
+            command
+                .table_output(ListFormat::Csv, &config.repositories[..2])
+                .unwrap(),
+            "name,url,tags,branch\n\
+             test-repo-1,https://github.com/test/repo1.git,frontend;javascript,main\n\
+             test-repo-2,\"https://example.com/a,b.git\",backend;rust,\n"
+        );
+        assert_eq!(
+            command
+                .table_output(ListFormat::Tsv, &config.repositories[..1])
+                .unwrap(),
+            "name\turl\ttags\tbranch\n\
+             test-repo-1\thttps://github.com/test/repo1.git\tfrontend;javascript\tmain\n"
+        );
+
+        assert_eq!("tags".parse::<ListField>().unwrap(), ListField::Tags);
+        assert!("owner".parse::<ListField>().is_err());
+    }
+
     #[tokio::test]
     async fn test_list_command_all_repositories() {
         let config = create_test_config();
-        let command = ListCommand { json: false };
+        let command = ListCommand {
+            json: false,
+            column: None,
+            null_separated: false,
+            format: None,
+            fields: Vec::new(),
+        };
 
         let context = create_context(config, vec![], vec![], None);
 
@@ -176,7 +526,13 @@ mod tests {
     #[tokio::test]
     async fn test_list_command_with_tag_filter() {
         let config = create_test_config();
-        let command = ListCommand { json: false };
+        let command = ListCommand {
+            json: false,
+            column: None,
+            null_separated: false,
+            format: None,
+            fields: Vec::new(),
+        };
 
         let context = create_context(config, vec!["frontend".to_string()], vec![], None);
 
@@ -187,7 +543,13 @@ mod tests {
     #[tokio::test]
     async fn test_list_command_with_exclude_tag() {
         let config = create_test_config();
-        let command = ListCommand { json: false };
+        let command = ListCommand {
+            json: false,
+            column: None,
+            null_separated: false,
+            format: None,
+            fields: Vec::new(),
+        };
 
         let context = create_context(config, vec![], vec!["backend".to_string()], None);
 
@@ -198,7 +560,13 @@ mod tests {
     #[tokio::test]
     async fn test_list_command_with_both_filters() {
         let config = create_test_config();
-        let command = ListCommand { json: false };
+        let command = ListCommand {
+            json: false,
+            column: None,
+            null_separated: false,
+            format: None,
+            fields: Vec::new(),
+        };
 
         let context = create_context(
             config,
@@ -214,7 +582,13 @@ mod tests {
     #[tokio::test]
     async fn test_list_command_no_matches() {
         let config = create_test_config();
-        let command = ListCommand { json: false };
+        let command = ListCommand {
+            json: false,
+            column: None,
+            null_separated: false,
+            format: None,
+            fields: Vec::new(),
+        };
 
         let context = create_context(config, vec!["nonexistent".to_string()], vec![], None);
 
@@ -225,7 +599,13 @@ mod tests {
     #[tokio::test]
     async fn test_list_command_with_repo_filter() {
         let config = create_test_config();
-        let command = ListCommand { json: false };
+        let command = ListCommand {
+            json: false,
+            column: None,
+            null_separated: false,
+            format: None,
+            fields: Vec::new(),
+        };
 
         let context = create_context(
             config,
@@ -243,8 +623,31 @@ mod tests {
         let config = Config {
             repositories: vec![],
             recipes: vec![],
+            artifacts_remote: None,
+            log_sinks: Vec::new(),
+            path_strategy: Default::default(),
+            root: None,
+            files: Vec::new(),
+            branch_protection: Vec::new(),
+            repository_settings: Vec::new(),
+            post_clone: Vec::new(),
+            pr: None,
+            provider: None,
+            provider_url: None,
+            git: Default::default(),
+            exit_codes: Default::default(),
+            notify: None,
+            profiles: Default::default(),
+            secrets: None,
+            profile_token: None,
+        };
+        let command = ListCommand {
+            json: false,
+            column: None,
+            null_separated: false,
+            format: None,
+            fields: Vec::new(),
         };
-        let command = ListCommand { json: false };
 
         let context = create_context(config, vec![], vec![], None);
 
@@ -255,7 +658,13 @@ mod tests {
     #[tokio::test]
     async fn test_list_command_multiple_tags() {
         let config = create_test_config();
-        let command = ListCommand { json: false };
+        let command = ListCommand {
+            json: false,
+            column: None,
+            null_separated: false,
+            format: None,
+            fields: Vec::new(),
+        };
 
         let context = create_context(
             config,
@@ -271,7 +680,13 @@ mod tests {
     #[tokio::test]
     async fn test_list_command_combined_filters() {
         let config = create_test_config();
-        let command = ListCommand { json: false };
+        let command = ListCommand {
+            json: false,
+            column: None,
+            null_separated: false,
+            format: None,
+            fields: Vec::new(),
+        };
 
         let context = create_context(
             config,
@@ -287,7 +702,13 @@ mod tests {
     #[tokio::test]
     async fn test_list_command_json_output() {
         let config = create_test_config();
-        let command = ListCommand { json: true };
+        let command = ListCommand {
+            json: true,
+            column: None,
+            null_separated: false,
+            format: None,
+            fields: Vec::new(),
+        };
 
         let context = create_context(config, vec![], vec![], None);
 
@@ -298,7 +719,13 @@ mod tests {
     #[tokio::test]
     async fn test_list_command_json_with_filters() {
         let config = create_test_config();
-        let command = ListCommand { json: true };
+        let command = ListCommand {
+            json: true,
+            column: None,
+            null_separated: false,
+            format: None,
+            fields: Vec::new(),
+        };
 
         let context = create_context(config, vec!["frontend".to_string()], vec![], None);
 
@@ -311,8 +738,31 @@ mod tests {
         let config = Config {
             repositories: vec![],
             recipes: vec![],
+            artifacts_remote: None,
+            log_sinks: Vec::new(),
+            path_strategy: Default::default(),
+            root: None,
+            files: Vec::new(),
+            branch_protection: Vec::new(),
+            repository_settings: Vec::new(),
+            post_clone: Vec::new(),
+            pr: None,
+            provider: None,
+            provider_url: None,
+            git: Default::default(),
+            exit_codes: Default::default(),
+            notify: None,
+            profiles: Default::default(),
+            secrets: None,
+            profile_token: None,
+        };
+        let command = ListCommand {
+            json: true,
+            column: None,
+            null_separated: false,
+            format: None,
+            fields: Vec::new(),
         };
-        let command = ListCommand { json: true };
 
         let context = create_context(config, vec![], vec![], None);
 
@@ -0,0 +1,347 @@
+//! Merge command implementation
+
+use super::{Command, CommandContext, GlobalOptions, validators};
+use crate::config::Config;
+use crate::credentials::Credentials;
+use crate::github::status::{CiState, PullRequestStatus, ReviewState, fetch_status};
+use crate::state::StateStore;
+use crate::{constants, logging};
+use anyhow::Result;
+use async_trait::async_trait;
+use clap::Args;
+use colored::*;
+use log::Level;
+use repos_github::{GitHubClient, MergeMethod};
+
+/// Merges open pull requests opened by `repos pr` whose checks pass
+///
+/// Pull requests targeting a branch with a merge queue are added to the queue
+/// instead of being merged directly.
+pub struct MergeCommand {
+    pub token: String,
+    pub method: MergeMethod,
+}
+
+#[async_trait]
+impl Command for MergeCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories: Vec<String> = context
+            .config
+            .filter_repositories(&context.tag, &context.exclude_tag, context.repos.as_deref())
+            .into_iter()
+            .map(|repo| repo.name)
+            .collect();
+
+        let store = StateStore::open_default();
+        let records: Vec<_> = store
+            .load()?
+            .pull_requests
+            .into_iter()
+            .filter(|record| repositories.contains(&record.repository))
+            .collect();
+
+        if records.is_empty() {
+            logging::message(Level::Info, "No recorded pull requests found".yellow());
+            return Ok(());
+        }
+
+        let client = GitHubClient::new(Some(self.token.clone()));
+        let mut merged = 0;
+        let mut queued = 0;
+        let mut skipped = 0;
+        let mut errors = Vec::new();
+        let mut finished = Vec::new();
+
+        for record in &records {
+            let status = match fetch_status(&client, record).await {
+                Ok(status) => status,
+                Err(e) => {
+                    logging::status(
+                        Level::Error,
+                        &record.repository,
+                        format!("Error: {e}").red(),
+                    );
+                    errors.push((record.repository.clone(), e));
+                    continue;
+                }
+            };
+
+            if !status.is_open() {
+                finished.push(record.clone());
+                continue;
+            }
+
+            if let Some(reason) = merge_blocker(&status) {
+                logging::status(
+                    Level::Info,
+                    &record.repository,
+                    format!("Skipping #{}: {}", record.number, reason).yellow(),
+                );
+                skipped += 1;
+                continue;
+            }
+
+            // Branches with a merge queue only accept PRs through the queue
+            if let Some(queue) = &status.merge_queue {
+                if let Some(position) = queue.position {
+                    logging::status(
+                        Level::Info,
+                        &record.repository,
+                        format!(
+                            "#{} is already in the merge queue (position {})",
+                            record.number, position
+                        )
+                        .yellow(),
+                    );
+                    skipped += 1;
+                    continue;
+                }
+
+                match client.enqueue_pull_request(&queue.pull_request_id).await {
+                    Ok(position) => {
+                        let position = position.map_or("-".to_string(), |p| p.to_string());
+                        logging::status(
+                            Level::Info,
+                            &record.repository,
+                            format!(
+                                "{} {}",
+                                format!(
+                                    "Added #{} to the merge queue (position {})",
+                                    record.number, position
+                                )
+                                .green(),
+                                record.url
+                            ),
+                        );
+                        queued += 1;
+                    }
+                    Err(e) => {
+                        logging::status(
+                            Level::Error,
+                            &record.repository,
+                            format!("Error: {e}").red(),
+                        );
+                        errors.push((record.repository.clone(), e));
+                    }
+                }
+                continue;
+            }
+
+            match client
+                .merge_pull_request(&record.owner, &record.repo, record.number, self.method)
+                .await
+            {
+                Ok(_) => {
+                    logging::status(
+                        Level::Info,
+                        &record.repository,
+                        format!(
+                            "{} {}",
+                            format!("Merged #{} ({})", record.number, self.method).green(),
+                            record.url
+                        ),
+                    );
+                    merged += 1;
+                    finished.push(record.clone());
+                }
+                Err(e) => {
+                    logging::status(
+                        Level::Error,
+                        &record.repository,
+                        format!("Error: {e}").red(),
+                    );
+                    errors.push((record.repository.clone(), e));
+                }
+            }
+        }
+
+        if !finished.is_empty() {
+            store.remove_pull_requests(|record| finished.contains(record))?;
+        }
+
+        logging::message(
+            Level::Info,
+            format!(
+                "Merged {} pull request(s), queued {}, skipped {}, failed {}",
+                merged,
+                queued,
+                skipped,
+                errors.len()
+            )
+            .green(),
+        );
+
+        if let Some((repo_name, e)) = errors.first() {
+            anyhow::bail!(
+                "Failed to merge {} pull request(s). First error ({}): {}",
+                errors.len(),
+                repo_name,
+                e
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Reason an open pull request should not be merged yet, if any
+fn merge_blocker(status: &PullRequestStatus) -> Option<&'static str> {
+    match (status.ci, status.review, status.mergeable) {
+        (CiState::Failure, _, _) => Some("checks failed"),
+        (CiState::Pending, _, _) => Some("checks pending"),
+        (_, ReviewState::ChangesRequested, _) => Some("changes requested"),
+        (_, _, Some(false)) => Some("not mergeable"),
+        (_, _, None) => Some("mergeability not yet known"),
+        _ => None,
+    }
+}
+
+/// Arguments of `repos merge`
+#[derive(Debug, Args)]
+pub struct MergeArgs {
+    /// Specific repository names to merge PRs for (if not provided, uses tag filter or all repos)
+    pub repos: Vec<String>,
+
+    /// Squash commits into one when merging
+    #[arg(long, group = "merge_method")]
+    pub squash: bool,
+
+    /// Rebase commits onto the base branch when merging
+    #[arg(long, group = "merge_method")]
+    pub rebase: bool,
+
+    /// Create a merge commit (default)
+    #[arg(long, group = "merge_method")]
+    pub merge: bool,
+
+    /// GitHub token
+    #[arg(long)]
+    pub token: Option<String>,
+
+    /// Configuration file path (can be specified multiple times to combine fleets)
+    #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+    pub config: Vec<String>,
+
+    /// Filter repositories by tag (can be specified multiple times)
+    #[arg(short, long)]
+    pub tag: Vec<String>,
+
+    /// Exclude repositories with these tags (can be specified multiple times)
+    #[arg(short = 'e', long)]
+    pub exclude_tag: Vec<String>,
+}
+
+impl MergeArgs {
+    /// Validate the arguments and run `repos merge`
+    pub async fn run(self, globals: &GlobalOptions) -> Result<()> {
+        let Self {
+            repos,
+            squash,
+            rebase,
+            merge: _,
+            token,
+            config,
+            tag,
+            exclude_tag,
+        } = self;
+        let config = Config::load_all(&config)?;
+
+        validators::validate_tag_filters(&tag)?;
+        validators::validate_tag_filters(&exclude_tag)?;
+        validators::validate_repository_names(&repos)?;
+
+        let mut context = globals.context(config, tag, exclude_tag, repos);
+        context.skip_archived(globals.include_archived);
+
+        let token = Credentials::load(token.or_else(|| context.config.resolve_profile_token()))
+            .default_token()
+            .ok_or_else(|| anyhow::anyhow!("GitHub token not provided. Use --token flag, set GITHUB_TOKEN environment variable, or save one with `repos auth login`."))?;
+        let method = if squash {
+            MergeMethod::Squash
+        } else if rebase {
+            MergeMethod::Rebase
+        } else {
+            MergeMethod::Merge
+        };
+
+        MergeCommand { token, method }.execute(&context).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::PullRequestRecord;
+
+    fn status(ci: CiState, review: ReviewState, mergeable: Option<bool>) -> PullRequestStatus {
+        PullRequestStatus {
+            record: PullRequestRecord {
+                repository: "api".to_string(),
+                owner: "org".to_string(),
+                repo: "api".to_string(),
+                number: 1,
+                url: "https://github.com/org/api/pull/1".to_string(),
+                branch: "repos/abc123".to_string(),
+                base: "main".to_string(),
+                created_at: "2024-01-01 00:00:00".to_string(),
+            },
+            state: "open".to_string(),
+            ci,
+            review,
+            mergeable,
+            merge_queue: None,
+        }
+    }
+
+    #[test]
+    fn test_merge_blocker() {
+        assert_eq!(
+            merge_blocker(&status(CiState::Success, ReviewState::Approved, Some(true))),
+            None
+        );
+        // Repositories without CI can still be merged
+        assert_eq!(
+            merge_blocker(&status(CiState::None, ReviewState::Pending, Some(true))),
+            None
+        );
+        assert_eq!(
+            merge_blocker(&status(CiState::Failure, ReviewState::Approved, Some(true))),
+            Some("checks failed")
+        );
+        assert_eq!(
+            merge_blocker(&status(
+                CiState::Success,
+                ReviewState::ChangesRequested,
+                Some(true)
+            )),
+            Some("changes requested")
+        );
+        assert_eq!(
+            merge_blocker(&status(
+                CiState::Success,
+                ReviewState::Approved,
+                Some(false)
+            )),
+            Some("not mergeable")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_merge_without_records() {
+        let context = CommandContext {
+            config: crate::config::Config::new(),
+            tag: vec![],
+            exclude_tag: vec![],
+            repos: None,
+            parallel: false,
+        };
+
+        let result = MergeCommand {
+            token: "test_token".to_string(),
+            method: MergeMethod::Squash,
+        }
+        .execute(&context)
+        .await;
+        assert!(result.is_ok());
+    }
+}
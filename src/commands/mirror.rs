@@ -0,0 +1,376 @@
+//! Mirror command implementation
+
+use super::{Command, CommandContext, GlobalOptions, validators};
+use crate::config::{Config, Repository};
+use crate::constants::git::{DEFAULT_FETCH_JOBS, DEFAULT_MIRROR_DIR};
+use crate::git::{self, MirrorOutcome};
+use crate::logging;
+use crate::utils::notify::{self, BatchReport};
+use crate::{constants, events};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use clap::Args;
+use colored::*;
+use futures::stream::{self, StreamExt};
+use log::Level;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Mirror command keeping bare `--mirror` clones of the fleet as a backup
+///
+/// Each repository is mirrored to `<dir>/<name>.git`, `jobs` at a time.
+/// Existing mirrors are fetched with pruning, so only new objects travel.
+pub struct MirrorCommand {
+    /// Directory holding the mirrors
+    pub dir: PathBuf,
+    /// Number of repositories mirrored at the same time
+    pub jobs: usize,
+    /// Verify every mirror with `git fsck` after updating it
+    pub fsck: bool,
+    /// Remove mirrors of repositories that are no longer in the config
+    pub prune: bool,
+}
+
+impl Default for MirrorCommand {
+    fn default() -> Self {
+        Self {
+            dir: PathBuf::from(DEFAULT_MIRROR_DIR),
+            jobs: DEFAULT_FETCH_JOBS,
+            fsck: true,
+            prune: false,
+        }
+    }
+}
+
+fn mirror_path(dir: &Path, repo: &Repository) -> PathBuf {
+    dir.join(format!("{}.git", repo.name))
+}
+
+fn mirror_one(repo: &Repository, path: &Path, fsck: bool) -> Result<MirrorOutcome> {
+    let outcome = git::mirror_repository(repo, path)?;
+    if fsck {
+        git::fsck(path)?;
+    }
+    Ok(outcome)
+}
+
+/// Mirrors in `dir` whose repository is not among `known`
+///
+/// Only bare mirror clones are considered, so other files kept in the backup
+/// directory are never removed.
+fn stale_mirrors(dir: &Path, known: &HashSet<&str>) -> Result<Vec<PathBuf>> {
+    let mut stale = Vec::new();
+    for entry in std::fs::read_dir(dir).context("Failed to read the mirror directory")? {
+        let path = entry?.path();
+        let Some(name) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_suffix(".git"))
+        else {
+            continue;
+        };
+        if path.is_dir() && !known.contains(name) && git::is_mirror(&path) {
+            stale.push(path);
+        }
+    }
+    stale.sort();
+    Ok(stale)
+}
+
+#[async_trait]
+impl Command for MirrorCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.config.filter_repositories(
+            &context.tag,
+            &context.exclude_tag,
+            context.repos.as_deref(),
+        );
+
+        if repositories.is_empty() {
+            logging::message(Level::Info, "No repositories found".yellow());
+            return Ok(());
+        }
+
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create mirror directory {}", self.dir.display()))?;
+
+        logging::message(
+            Level::Info,
+            format!(
+                "Mirroring {} repositories to {}...",
+                repositories.len(),
+                self.dir.display()
+            )
+            .green(),
+        );
+
+        let fsck = self.fsck;
+        let results: Vec<_> = stream::iter(repositories)
+            .map(|repo| {
+                let path = mirror_path(&self.dir, &repo);
+                tokio::task::spawn_blocking(move || {
+                    let result = events::track(&repo.name, || mirror_one(&repo, &path, fsck));
+                    (repo, result)
+                })
+            })
+            .buffered(self.jobs.max(1))
+            .collect()
+            .await;
+
+        let mut created = 0;
+        let mut updated = 0;
+        let mut errors = Vec::new();
+        for result in results {
+            let (repo, result) = result?;
+            match result {
+                Ok(MirrorOutcome::Created) => {
+                    logging::status(Level::Info, &repo.name, "Mirrored".green());
+                    created += 1;
+                }
+                Ok(MirrorOutcome::Updated { changed: 0 }) => {
+                    logging::status(Level::Info, &repo.name, "Up to date".dimmed());
+                    updated += 1;
+                }
+                Ok(MirrorOutcome::Updated { changed }) => {
+                    logging::status(
+                        Level::Info,
+                        &repo.name,
+                        format!(
+                            "{} {} updated",
+                            changed,
+                            if changed == 1 { "ref" } else { "refs" }
+                        )
+                        .green(),
+                    );
+                    updated += 1;
+                }
+                Err(e) => {
+                    logging::status(Level::Error, &repo.name, format!("Error: {e}").red());
+                    errors.push((repo.name, e));
+                }
+            }
+        }
+
+        let mut pruned = 0;
+        if self.prune {
+            // Compare against the whole config, not just the selected repositories
+            let known: HashSet<&str> = context
+                .config
+                .repositories
+                .iter()
+                .map(|repo| repo.name.as_str())
+                .collect();
+            for path in stale_mirrors(&self.dir, &known)? {
+                std::fs::remove_dir_all(&path)
+                    .with_context(|| format!("Failed to remove {}", path.display()))?;
+                logging::message(Level::Info, format!("Pruned {}", path.display()).yellow());
+                pruned += 1;
+            }
+        }
+
+        let failures = errors.iter().map(|(name, _)| name.clone()).collect();
+        notify::notify(
+            context.config.notify.as_ref(),
+            &BatchReport::new("mirror", created + updated, failures),
+        )
+        .await;
+
+        if let Some((repo_name, e)) = errors.first() {
+            logging::message(
+                Level::Info,
+                format!(
+                    "Completed with {} successful, {} failed",
+                    created + updated,
+                    errors.len()
+                )
+                .yellow(),
+            );
+            anyhow::bail!(
+                "Failed to mirror {} repositories. First error ({}): {}",
+                errors.len(),
+                repo_name,
+                e
+            );
+        }
+
+        let mut summary = format!("Done mirroring: {} created, {} updated", created, updated);
+        if self.fsck {
+            summary.push_str(", all verified");
+        }
+        if self.prune {
+            summary.push_str(&format!(", {} pruned", pruned));
+        }
+        logging::message(Level::Info, summary.green());
+        Ok(())
+    }
+}
+
+/// Arguments of `repos mirror`
+#[derive(Debug, Args)]
+pub struct MirrorArgs {
+    /// Specific repository names to mirror (if not provided, uses tag filter or all repos)
+    pub repos: Vec<String>,
+
+    /// Directory holding the mirrors, one `<name>.git` per repository
+    #[arg(short, long, default_value = constants::git::DEFAULT_MIRROR_DIR)]
+    pub dir: String,
+
+    /// Number of repositories to mirror at the same time
+    #[arg(short, long, default_value_t = constants::git::DEFAULT_FETCH_JOBS)]
+    pub jobs: usize,
+
+    /// Skip verifying the mirrors with git fsck
+    #[arg(long)]
+    pub no_fsck: bool,
+
+    /// Remove mirrors of repositories that are no longer in the config
+    #[arg(long)]
+    pub prune: bool,
+
+    /// Configuration file path (can be specified multiple times to combine fleets)
+    #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+    pub config: Vec<String>,
+
+    /// Filter repositories by tag (can be specified multiple times)
+    #[arg(short, long)]
+    pub tag: Vec<String>,
+
+    /// Exclude repositories with these tags (can be specified multiple times)
+    #[arg(short = 'e', long)]
+    pub exclude_tag: Vec<String>,
+}
+
+impl MirrorArgs {
+    /// Validate the arguments and run `repos mirror`
+    pub async fn run(self, globals: &GlobalOptions) -> Result<()> {
+        let Self {
+            repos,
+            dir,
+            jobs,
+            no_fsck,
+            prune,
+            config,
+            tag,
+            exclude_tag,
+        } = self;
+        let config = Config::load_all(&config)?;
+
+        validators::validate_tag_filters(&tag)?;
+        validators::validate_tag_filters(&exclude_tag)?;
+        validators::validate_repository_names(&repos)?;
+
+        let context = globals
+            .context(config, tag, exclude_tag, repos)
+            .with_parallel(true);
+        MirrorCommand {
+            dir: PathBuf::from(dir),
+            jobs,
+            fsck: !no_fsck,
+            prune,
+        }
+        .execute(&context)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use std::process::Command as Process;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let output = Process::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "git {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    fn push_commit(work: &Path, message: &str) -> String {
+        git(
+            work,
+            &[
+                "-c",
+                "user.name=Test",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "--allow-empty",
+                "-m",
+                message,
+            ],
+        );
+        git(work, &["push", "-q", "origin", "HEAD"]);
+        git(work, &["rev-parse", "HEAD"])
+    }
+
+    #[tokio::test]
+    async fn test_mirror_creates_updates_and_prunes() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        git(root, &["init", "-q", "--bare", "origin.git"]);
+        git(root, &["clone", "-q", "origin.git", "work"]);
+        let work = root.join("work");
+        push_commit(&work, "initial");
+        git(&work, &["push", "-q", "origin", "HEAD:stale"]);
+
+        let backups = root.join("backups");
+        std::fs::create_dir_all(backups.join("notes.git")).unwrap();
+        git(
+            &backups,
+            &["clone", "-q", "--mirror", "../origin.git", "removed.git"],
+        );
+
+        let api = Repository::new(
+            "api".to_string(),
+            root.join("origin.git").to_string_lossy().to_string(),
+        );
+        let mut config = Config::new();
+        config.repositories = vec![api];
+        let context = CommandContext {
+            config,
+            tag: vec![],
+            exclude_tag: vec![],
+            parallel: false,
+            repos: None,
+        };
+        let command = MirrorCommand {
+            dir: backups.clone(),
+            prune: true,
+            ..Default::default()
+        };
+
+        command.execute(&context).await.unwrap();
+        let mirror = backups.join("api.git");
+        assert!(git::is_mirror(&mirror));
+        // Only mirrors of repositories missing from the config are pruned
+        assert!(!backups.join("removed.git").exists());
+        assert!(backups.join("notes.git").exists());
+
+        let latest = push_commit(&work, "second");
+        let branch = git(&work, &["rev-parse", "--abbrev-ref", "HEAD"]);
+        git(&work, &["push", "-q", "origin", ":stale"]);
+
+        assert_eq!(
+            git::mirror_repository(&context.config.repositories[0], &mirror).unwrap(),
+            MirrorOutcome::Updated { changed: 2 }
+        );
+        assert_eq!(git(&mirror, &["rev-parse", &branch]), latest);
+        git::fsck(&mirror).unwrap();
+
+        // A directory that is not a mirror is never overwritten
+        assert!(
+            git::mirror_repository(&context.config.repositories[0], &backups.join("notes.git"))
+                .is_err()
+        );
+    }
+}
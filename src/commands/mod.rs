@@ -1,19 +1,102 @@
 //! Command pattern implementation for CLI operations
 
+pub mod apply;
+pub mod archive;
+pub mod audit;
+pub mod auth;
 pub mod base;
+pub mod bookmark;
+pub mod checkout;
+pub mod ci;
 pub mod clone;
+pub mod config;
+pub mod cp;
+pub mod diff;
+pub mod fetch;
+pub mod files;
 pub mod init;
+pub mod inventory;
+pub mod issues;
 pub mod ls;
+pub mod merge;
+pub mod mirror;
+pub mod new;
+pub mod open;
+pub mod plugin;
 pub mod pr;
+pub mod pr_comment;
+pub mod protect;
+pub mod push;
+pub mod reaper;
+pub mod rebase;
+pub mod release;
+pub mod remote;
 pub mod remove;
+pub mod rollback;
 pub mod run;
+pub mod runs;
+pub mod select;
+pub mod shell;
+pub mod stash;
+pub mod stats;
+pub mod submodule;
+pub mod tags;
+pub mod triage;
+#[cfg(feature = "tui")]
+pub mod ui;
 pub mod validators;
+pub mod watch;
 
 // Re-export the base types and all commands
-pub use base::{Command, CommandContext};
-pub use clone::CloneCommand;
-pub use init::InitCommand;
-pub use ls::ListCommand;
-pub use pr::PrCommand;
-pub use remove::RemoveCommand;
-pub use run::RunCommand;
+pub use apply::{ApplyArgs, ApplyCommand};
+pub use archive::{ArchiveArgs, ArchiveCommand};
+pub use audit::{AuditArgs, AuditCommand};
+pub use auth::{AuthAction, AuthLoginCommand, AuthLogoutCommand, AuthStatusCommand};
+pub use base::{Command, CommandContext, GlobalOptions};
+pub use bookmark::{
+    BookmarkAction, BookmarkDeleteCommand, BookmarkListCommand, BookmarkSaveCommand,
+    BookmarkSwitchCommand,
+};
+pub use checkout::{CheckoutArgs, CheckoutCommand};
+pub use ci::{CiArgs, CiCommand};
+pub use clone::{CloneArgs, CloneCommand};
+pub use config::{
+    ConfigAction, ConfigExportCommand, ConfigReconcileCommand, ConfigValidateCommand,
+};
+pub use cp::{CopyCommand, CpArgs, OverwritePolicy};
+pub use diff::{DiffArgs, DiffCommand};
+pub use fetch::{FetchArgs, FetchCommand};
+pub use files::{FilesAction, FilesCheckCommand};
+pub use init::{InitArgs, InitCommand};
+pub use inventory::{InventoryArgs, InventoryCommand, InventoryFormat};
+pub use issues::{
+    IssueUpdate, IssuesAction, IssuesCreateCommand, IssuesListCommand, IssuesUpdateCommand,
+};
+pub use ls::{ListColumn, ListCommand, ListField, ListFormat, LsArgs};
+pub use merge::{MergeArgs, MergeCommand};
+pub use mirror::{MirrorArgs, MirrorCommand};
+pub use new::{NewArgs, NewCommand};
+pub use open::{OpenArgs, OpenCommand, OpenPage, OpenTarget};
+pub use plugin::PluginAction;
+pub use pr::{PrArgs, PrCommand, PrStatusCommand};
+pub use pr_comment::{PrAction, PrCommentCommand};
+pub use protect::{ProtectArgs, ProtectCommand};
+pub use push::{PushArgs, PushCommand};
+pub use reaper::{ReaperArgs, ReaperCommand, StalePullRequestPolicy};
+pub use rebase::{RebaseArgs, RebaseCommand};
+pub use release::{ReleaseArgs, ReleaseCommand, ReleaseVersion};
+pub use remote::{RemoteAction, RemoteSyncCommand};
+pub use remove::{RemoveCommand, RmArgs};
+pub use rollback::{RollbackArgs, RollbackCommand, RollbackListCommand};
+pub use run::{FailurePolicy, RunArgs, RunCommand};
+pub use runs::{RunsAction, RunsListCommand, RunsShowCommand};
+pub use select::{SelectArgs, SelectCommand};
+pub use shell::{ShellArgs, ShellCommand};
+pub use stash::{StashAction, StashCommand, StashSubcommand};
+pub use stats::{StatsArgs, StatsCommand, StatsFormat};
+pub use submodule::{SubmoduleAction, SubmoduleCommand, SubmoduleSubcommand};
+pub use tags::{TagsAction, TagsEditCommand, TagsListCommand, TagsSyncCommand};
+pub use triage::{TriageArgs, TriageCommand};
+#[cfg(feature = "tui")]
+pub use ui::{UiArgs, UiCommand};
+pub use watch::{WatchArgs, WatchCommand};
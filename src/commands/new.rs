@@ -0,0 +1,387 @@
+//! Scaffolding new repositories from a template
+
+use super::{Command, CommandContext, validators};
+use crate::config::{Config, Repository};
+use crate::credentials::Credentials;
+use crate::git::{
+    RemoteAccess, add_all_changes, clone_repository, commit_changes, create_and_checkout_branch,
+    push_branch,
+};
+use crate::github::placeholders::Placeholders;
+use crate::github::template::render_template;
+use crate::{constants, logging};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use clap::Args;
+use colored::*;
+use log::Level;
+use repos_github::{GitHubClient, NewRepository, TreeFile};
+use std::path::{Path, PathBuf};
+
+/// Creates a GitHub repository from a local template directory and adds it to the config
+///
+/// Template files may use [`Placeholders`] such as `{{repo.name}}`, plus
+/// `{{team}}` and `{{description}}`. The config entry is written as soon as the
+/// GitHub repository exists, so a failed clone or push can be finished by hand
+/// without creating the repository again.
+pub struct NewCommand {
+    /// Config file the new repository is added to
+    pub config_path: PathBuf,
+    pub name: String,
+    /// Organization or user owning the new repository
+    pub owner: String,
+    pub template: PathBuf,
+    pub description: Option<String>,
+    pub team: Option<String>,
+    pub tags: Vec<String>,
+    pub public: bool,
+    /// Show the repository, files and config entry without creating anything
+    pub dry_run: bool,
+    pub token: Option<String>,
+}
+
+impl NewCommand {
+    /// The config entry of the new repository
+    fn repository(&self, url: String) -> Repository {
+        let mut repo = Repository::new(self.name.clone(), url);
+        repo.tags = self.tags.clone();
+        repo
+    }
+
+    /// Template files with every placeholder replaced
+    fn render(&self, repo: &Repository) -> Result<Vec<TreeFile>> {
+        let placeholders = Placeholders::default()
+            .with_value("team", self.team.as_deref().unwrap_or_default())
+            .with_value(
+                "description",
+                self.description.as_deref().unwrap_or_default(),
+            );
+        render_template(&self.template, repo, &placeholders)
+    }
+
+    /// Write the files into the clone, commit them and push the default branch
+    fn publish(
+        &self,
+        repo: &Repository,
+        files: &[TreeFile],
+        branch: &str,
+        token: Option<String>,
+    ) -> Result<()> {
+        clone_repository(repo)?;
+        let target_dir = repo.get_target_dir();
+        for file in files {
+            let path = Path::new(&target_dir).join(&file.path);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)
+                    .with_context(|| format!("Failed to create {}", parent.display()))?;
+            }
+            std::fs::write(&path, &file.content)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+
+        create_and_checkout_branch(&target_dir, branch)?;
+        add_all_changes(&target_dir)?;
+        commit_changes(&target_dir, "Initial commit")?;
+        push_branch(
+            &target_dir,
+            branch,
+            &RemoteAccess::for_repository(repo, token),
+        )
+    }
+}
+
+#[async_trait]
+impl Command for NewCommand {
+    async fn execute(&self, _context: &CommandContext) -> Result<()> {
+        let config_file = self.config_path.to_string_lossy().to_string();
+        // The file as written, without a profile applied, is what gets saved
+        let mut config = Config::load_file(&config_file).with_context(|| {
+            format!(
+                "Failed to load {}; run `repos init` to create a config first",
+                config_file
+            )
+        })?;
+        if config.get_repository(&self.name).is_some() {
+            anyhow::bail!(
+                "Repository '{}' already exists in {}",
+                self.name,
+                config_file
+            );
+        }
+
+        let url = format!("git@github.com:{}/{}.git", self.owner, self.name);
+        let files = self.render(&self.repository(url.clone()))?;
+        let visibility = if self.public { "public" } else { "private" };
+
+        if self.dry_run {
+            logging::status(
+                Level::Info,
+                &self.name,
+                format!(
+                    "Would create {} repository {}/{}",
+                    visibility, self.owner, self.name
+                )
+                .yellow(),
+            );
+            for file in &files {
+                logging::status(
+                    Level::Info,
+                    &self.name,
+                    format!("Would add {}", file.path).yellow(),
+                );
+            }
+            logging::status(
+                Level::Info,
+                &self.name,
+                format!("Would add {} to {}", url, config_file).yellow(),
+            );
+            return Ok(());
+        }
+
+        let token = Credentials::load(self.token.clone())
+            .token_for_repository(&self.repository(url))
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "GitHub token not provided. Use --token flag, set GITHUB_TOKEN environment variable, or save one with `repos auth login`."
+                )
+            })?;
+
+        let client = GitHubClient::new(Some(token.clone()));
+        let created = client
+            .create_repository(
+                &self.owner,
+                &NewRepository {
+                    name: &self.name,
+                    description: self.description.as_deref(),
+                    private: !self.public,
+                },
+            )
+            .await?;
+        logging::status(
+            Level::Info,
+            &self.name,
+            format!(
+                "{} {}",
+                format!("Created {} repository", visibility).green(),
+                created.html_url
+            ),
+        );
+
+        config.add_repository(self.repository(created.ssh_url))?;
+        config.save(&config_file)?;
+        logging::status(
+            Level::Info,
+            &self.name,
+            format!("Added to {}", config_file).green(),
+        );
+
+        // Reload so the entry picks up the config's paths, transport and profile
+        let repo = Config::load(&config_file)?
+            .get_repository(&self.name)
+            .cloned()
+            .with_context(|| format!("Repository '{}' missing after saving", self.name))?;
+        let branch = created.default_branch.as_deref().unwrap_or("main");
+        self.publish(&repo, &files, branch, Some(token))
+            .with_context(|| {
+                format!(
+                    "Created {} but could not push the template; finish with `repos clone {}`",
+                    created.html_url, self.name
+                )
+            })?;
+
+        logging::status(
+            Level::Info,
+            &self.name,
+            format!(
+                "Pushed {} template file(s) to {} in {}",
+                files.len(),
+                branch,
+                repo.get_target_dir()
+            )
+            .green(),
+        );
+        Ok(())
+    }
+}
+
+/// Arguments of `repos new`
+#[derive(Debug, Args)]
+pub struct NewArgs {
+    /// Name of the new repository
+    pub name: String,
+
+    /// Organization or user to create the repository under
+    #[arg(long)]
+    pub owner: String,
+
+    /// Directory whose files become the first commit; supports {{repo.name}}, {{repo.owner}}, {{repo.repo}}, {{repo.url}}, {{team}} and {{description}}
+    #[arg(long, value_name = "DIR")]
+    pub template: PathBuf,
+
+    /// Repository description, also available to the template
+    #[arg(short, long)]
+    pub description: Option<String>,
+
+    /// Owning team, available to the template (e.g. for CODEOWNERS)
+    #[arg(long)]
+    pub team: Option<String>,
+
+    /// Tag to give the repository in the config (can be specified multiple times)
+    #[arg(short, long)]
+    pub tag: Vec<String>,
+
+    /// Create a public repository instead of a private one
+    #[arg(long)]
+    pub public: bool,
+
+    /// Show what would be created without creating anything
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// GitHub token
+    #[arg(long)]
+    pub token: Option<String>,
+
+    /// Configuration file path
+    #[arg(short, long, default_value_t = constants::config::DEFAULT_CONFIG_FILE.to_string())]
+    pub config: String,
+}
+
+impl NewArgs {
+    /// Validate the arguments and run `repos new`
+    pub async fn run(self) -> Result<()> {
+        let Self {
+            name,
+            owner,
+            template,
+            description,
+            team,
+            tag,
+            public,
+            dry_run,
+            token,
+            config,
+        } = self;
+        validators::validate_repository_names(std::slice::from_ref(&name))?;
+        validators::validate_tag_filters(&tag)?;
+
+        let config_path = crate::config::profile::editable_path(&config);
+        let token = token.or_else(|| {
+            Config::load(&config)
+                .ok()
+                .and_then(|config| config.resolve_profile_token())
+        });
+        // The new repository is not in the config yet
+        let context = CommandContext::new(Config::new());
+        NewCommand {
+            config_path,
+            name,
+            owner,
+            template,
+            description,
+            team,
+            tags: tag,
+            public,
+            dry_run,
+            token,
+        }
+        .execute(&context)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn command(temp_dir: &TempDir, name: &str) -> NewCommand {
+        NewCommand {
+            config_path: temp_dir.path().join("config.yaml"),
+            name: name.to_string(),
+            owner: "acme".to_string(),
+            template: temp_dir.path().join("template"),
+            description: Some("Billing API".to_string()),
+            team: Some("payments".to_string()),
+            tags: vec!["backend".to_string()],
+            public: false,
+            dry_run: true,
+            token: None,
+        }
+    }
+
+    fn context() -> CommandContext {
+        CommandContext {
+            config: Config::new(),
+            tag: vec![],
+            exclude_tag: vec![],
+            parallel: false,
+            repos: None,
+        }
+    }
+
+    fn setup() -> TempDir {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("config.yaml"),
+            "repositories:\n  - name: api\n    url: git@github.com:acme/api.git\n    tags: []\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(temp_dir.path().join("template/.github")).unwrap();
+        std::fs::write(
+            temp_dir.path().join("template/README.md"),
+            "# {{name}}\n\n{{description}}, owned by {{team}} ({{owner}}/{{repo}})\n",
+        )
+        .unwrap();
+        std::fs::write(
+            temp_dir.path().join("template/.github/CODEOWNERS"),
+            "* @{{owner}}/{{team}}\n",
+        )
+        .unwrap();
+        temp_dir
+    }
+
+    #[test]
+    fn test_render_replaces_team_and_description() {
+        let temp_dir = setup();
+        let command = command(&temp_dir, "billing");
+        let files = command
+            .render(&command.repository("git@github.com:acme/billing.git".into()))
+            .unwrap();
+
+        let readme = files.iter().find(|f| f.path == "README.md").unwrap();
+        assert_eq!(
+            readme.content,
+            "# billing\n\nBilling API, owned by payments (acme/billing)\n"
+        );
+        let owners = files
+            .iter()
+            .find(|f| f.path == ".github/CODEOWNERS")
+            .unwrap();
+        assert_eq!(owners.content, "* @acme/payments\n");
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_leaves_config_alone() {
+        let temp_dir = setup();
+        let before = std::fs::read_to_string(temp_dir.path().join("config.yaml")).unwrap();
+
+        command(&temp_dir, "billing")
+            .execute(&context())
+            .await
+            .unwrap();
+
+        let after = std::fs::read_to_string(temp_dir.path().join("config.yaml")).unwrap();
+        assert_eq!(before, after);
+    }
+
+    #[tokio::test]
+    async fn test_existing_name_is_rejected() {
+        let temp_dir = setup();
+        let err = command(&temp_dir, "api")
+            .execute(&context())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("already exists"));
+    }
+}
@@ -0,0 +1,366 @@
+//! Open command implementation
+
+use super::{Command, CommandContext, GlobalOptions, validators};
+use crate::config::layout::parse_repository_url;
+use crate::config::{Config, Provider, Repository};
+use crate::{constants, logging};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use clap::Args;
+use colored::*;
+use log::Level;
+use std::path::Path;
+use std::process::Command as ProcessCommand;
+use std::str::FromStr;
+
+/// Most repositories opened at once without `--interactive` or `--print`
+pub const MAX_OPEN: usize = 10;
+
+/// Page of a repository's web interface
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum OpenPage {
+    #[default]
+    Home,
+    PullRequests,
+    Issues,
+    /// CI runs: GitHub and Gitea Actions, or Azure Pipelines
+    Actions,
+    Settings,
+    Branches,
+    /// Any other path below the repository's page, e.g. `tree/main/docs`
+    Path(String),
+}
+
+impl FromStr for OpenPage {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s.trim_matches('/') {
+            "" | "home" => OpenPage::Home,
+            "prs" | "pulls" | "pull-requests" => OpenPage::PullRequests,
+            "issues" => OpenPage::Issues,
+            "actions" | "ci" => OpenPage::Actions,
+            "settings" => OpenPage::Settings,
+            "branches" => OpenPage::Branches,
+            path => OpenPage::Path(path.to_string()),
+        })
+    }
+}
+
+/// What to open for each repository
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OpenTarget {
+    /// A page of the repository on its provider, in the browser
+    Web(OpenPage),
+    /// The local directory, in `$VISUAL`, `$EDITOR` or VS Code
+    Editor,
+}
+
+/// Open command launching repository pages or local directories
+pub struct OpenCommand {
+    pub target: OpenTarget,
+    /// Print the URLs or directories instead of opening them
+    pub print: bool,
+}
+
+/// Home page of a repository on its provider
+pub fn web_url(repo: &Repository) -> Result<String> {
+    match repo.provider() {
+        Provider::GitHub => {
+            let parts = parse_repository_url(&repo.url)
+                .with_context(|| format!("Cannot tell the web page of '{}'", repo.url))?;
+            Ok(format!(
+                "https://{}/{}/{}",
+                parts.host, parts.owner, parts.repo
+            ))
+        }
+        Provider::Gitea => {
+            let (_, repo_ref) = crate::gitea::client_for(repo)?;
+            Ok(format!(
+                "{}/{}/{}",
+                repo_ref.instance_url, repo_ref.owner, repo_ref.repo
+            ))
+        }
+        Provider::Azure => {
+            let (_, repo_ref) = crate::azure::client_for(repo)?;
+            Ok(format!(
+                "{}/{}/_git/{}",
+                repo_ref.organization_url, repo_ref.project, repo_ref.repo
+            ))
+        }
+    }
+}
+
+/// URL of a page of a repository on its provider
+pub fn page_url(repo: &Repository, page: &OpenPage) -> Result<String> {
+    let home = web_url(repo)?;
+    if repo.provider() == Provider::Azure {
+        // Boards, pipelines and settings belong to the project, not the repository
+        let (_, repo_ref) = crate::azure::client_for(repo)?;
+        let project = format!("{}/{}", repo_ref.organization_url, repo_ref.project);
+        return Ok(match page {
+            OpenPage::Home => home,
+            OpenPage::PullRequests => format!("{}/pullrequests", home),
+            OpenPage::Issues => format!("{}/_workitems", project),
+            OpenPage::Actions => format!("{}/_build", project),
+            OpenPage::Settings => format!("{}/_settings/repositories", project),
+            OpenPage::Branches => format!("{}/branches", home),
+            OpenPage::Path(path) => format!("{}/{}", home, path),
+        });
+    }
+
+    Ok(match page {
+        OpenPage::Home => home,
+        OpenPage::PullRequests => format!("{}/pulls", home),
+        OpenPage::Issues => format!("{}/issues", home),
+        OpenPage::Actions => format!("{}/actions", home),
+        OpenPage::Settings => format!("{}/settings", home),
+        OpenPage::Branches => format!("{}/branches", home),
+        OpenPage::Path(path) => format!("{}/{}", home, path),
+    })
+}
+
+/// `$BROWSER`, or the platform's way of opening a URL
+fn browser_command(url: &str) -> ProcessCommand {
+    if let Some(browser) = std::env::var("BROWSER")
+        .ok()
+        .filter(|browser| !browser.trim().is_empty())
+    {
+        let mut words = browser.split_whitespace();
+        let mut command = ProcessCommand::new(words.next().unwrap_or_default());
+        command.args(words).arg(url);
+        return command;
+    }
+
+    let mut command = if cfg!(target_os = "macos") {
+        ProcessCommand::new("open")
+    } else if cfg!(windows) {
+        let mut command = ProcessCommand::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        ProcessCommand::new("xdg-open")
+    };
+    command.arg(url);
+    command
+}
+
+/// `$VISUAL`, then `$EDITOR`, then VS Code, opening `dir`
+fn editor_command(dir: &str) -> ProcessCommand {
+    let editor = ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| "code".to_string());
+    let mut words = editor.split_whitespace();
+    let mut command = ProcessCommand::new(words.next().unwrap_or_default());
+    command.args(words).arg(dir);
+    command
+}
+
+fn launch(mut command: ProcessCommand) -> Result<()> {
+    let program = command.get_program().to_string_lossy().to_string();
+    let status = command
+        .status()
+        .with_context(|| format!("Failed to start '{}'", program))?;
+    if !status.success() {
+        anyhow::bail!(
+            "'{}' failed with exit code {}",
+            program,
+            status.code().unwrap_or(-1)
+        );
+    }
+    Ok(())
+}
+
+impl OpenCommand {
+    /// The URL or directory to open for a repository
+    fn location(&self, repo: &Repository) -> Result<String> {
+        match &self.target {
+            OpenTarget::Web(page) => page_url(repo, page),
+            OpenTarget::Editor => {
+                let target_dir = repo.get_target_dir();
+                if !Path::new(&target_dir).exists() {
+                    anyhow::bail!("Repository is not cloned");
+                }
+                Ok(target_dir)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Command for OpenCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.config.filter_repositories(
+            &context.tag,
+            &context.exclude_tag,
+            context.repos.as_deref(),
+        );
+        if repositories.is_empty() {
+            logging::message(Level::Info, "No repositories found".yellow());
+            return Ok(());
+        }
+        if repositories.len() > MAX_OPEN && !self.print {
+            anyhow::bail!(
+                "{} repositories match; narrow the selection, pick with --interactive or list them with --print",
+                repositories.len()
+            );
+        }
+
+        let mut errors = Vec::new();
+        for repo in &repositories {
+            let result = self.location(repo).and_then(|location| {
+                if self.print {
+                    println!("{}", location);
+                    return Ok(());
+                }
+                logging::status(
+                    Level::Info,
+                    &repo.name,
+                    format!("Opening {}", location).dimmed(),
+                );
+                match self.target {
+                    OpenTarget::Web(_) => launch(browser_command(&location)),
+                    OpenTarget::Editor => launch(editor_command(&location)),
+                }
+            });
+            if let Err(e) = result {
+                logging::status(Level::Error, &repo.name, format!("Error: {e}").red());
+                errors.push((repo.name.clone(), e));
+            }
+        }
+
+        if let Some((repo_name, e)) = errors.first() {
+            anyhow::bail!(
+                "Failed to open {} repositories. First error ({}): {}",
+                errors.len(),
+                repo_name,
+                e
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Arguments of `repos open`
+#[derive(Debug, Args)]
+pub struct OpenArgs {
+    /// Specific repository names to open (if not provided, uses tag filter or all repos)
+    pub repos: Vec<String>,
+
+    /// Open the repository page on its provider (the default)
+    #[arg(long, conflicts_with = "editor")]
+    pub web: bool,
+
+    /// Open the local directory in $VISUAL, $EDITOR or VS Code
+    #[arg(long)]
+    pub editor: bool,
+
+    /// Page to open: prs, issues, actions, settings, branches, or a path such as tree/main/docs
+    #[arg(long, conflicts_with = "editor")]
+    pub page: Option<String>,
+
+    /// Print the URLs or directories instead of opening them
+    #[arg(long)]
+    pub print: bool,
+
+    /// Configuration file path (can be specified multiple times to combine fleets)
+    #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+    pub config: Vec<String>,
+
+    /// Filter repositories by tag (can be specified multiple times)
+    #[arg(short, long)]
+    pub tag: Vec<String>,
+
+    /// Exclude repositories with these tags (can be specified multiple times)
+    #[arg(short = 'e', long)]
+    pub exclude_tag: Vec<String>,
+
+    /// Pick repositories interactively when more than one matches
+    #[arg(short, long)]
+    pub interactive: bool,
+}
+
+impl OpenArgs {
+    /// Validate the arguments and run `repos open`
+    pub async fn run(self, globals: &GlobalOptions) -> Result<()> {
+        let Self {
+            repos,
+            web: _,
+            editor,
+            page,
+            print,
+            config,
+            tag,
+            exclude_tag,
+            interactive,
+        } = self;
+        let config = Config::load_all(&config)?;
+
+        validators::validate_tag_filters(&tag)?;
+        validators::validate_tag_filters(&exclude_tag)?;
+        validators::validate_repository_names(&repos)?;
+
+        let mut context = globals.context(config, tag, exclude_tag, repos);
+        let matching = context.config.filter_repositories(
+            &context.tag,
+            &context.exclude_tag,
+            context.repos.as_deref(),
+        );
+        if interactive && matching.len() > 1 {
+            context.select_interactively()?;
+        }
+
+        let target = if editor {
+            OpenTarget::Editor
+        } else {
+            OpenTarget::Web(page.as_deref().unwrap_or_default().parse()?)
+        };
+        OpenCommand { target, print }.execute(&context).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo(url: &str, provider: Option<Provider>) -> Repository {
+        let mut repo = Repository::new("api".to_string(), url.to_string());
+        repo.provider = provider;
+        repo
+    }
+
+    #[test]
+    fn test_page_url() {
+        let github = repo("git@github.com:org/api.git", None);
+        assert_eq!(web_url(&github).unwrap(), "https://github.com/org/api");
+        assert_eq!(
+            page_url(&github, &"prs".parse().unwrap()).unwrap(),
+            "https://github.com/org/api/pulls"
+        );
+        assert_eq!(
+            page_url(&github, &"tree/main/docs".parse().unwrap()).unwrap(),
+            "https://github.com/org/api/tree/main/docs"
+        );
+
+        let gitea = repo("git@git.example.com:org/api.git", Some(Provider::Gitea));
+        assert_eq!(
+            page_url(&gitea, &OpenPage::Actions).unwrap(),
+            "https://git.example.com/org/api/actions"
+        );
+
+        let azure = repo(
+            "https://dev.azure.com/org/project/_git/api",
+            Some(Provider::Azure),
+        );
+        assert_eq!(
+            page_url(&azure, &OpenPage::PullRequests).unwrap(),
+            "https://dev.azure.com/org/project/_git/api/pullrequests"
+        );
+        assert_eq!(
+            page_url(&azure, &OpenPage::Actions).unwrap(),
+            "https://dev.azure.com/org/project/_build"
+        );
+    }
+}
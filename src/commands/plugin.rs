@@ -0,0 +1,89 @@
+//! Plugin command implementation, managing plugins published as GitHub releases
+
+use crate::ci;
+use crate::plugins::manager::PluginManager;
+use anyhow::Result;
+use clap::Subcommand;
+
+/// Subcommands of `repos plugin`
+#[derive(Debug, Subcommand)]
+pub enum PluginAction {
+    /// Install a plugin from the latest release (or a tagged one) of a GitHub repository
+    Install {
+        /// Repository publishing the plugin, as owner/repo[@tag] or a GitHub URL
+        source: String,
+
+        /// Name to install the plugin under (default: the repository name without a `repos-` prefix)
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Install even if the release publishes no checksum for the asset
+        #[arg(long)]
+        allow_unverified: bool,
+
+        /// GitHub token, e.g. for private repositories or higher rate limits
+        #[arg(long)]
+        token: Option<String>,
+    },
+
+    /// Update installed plugins to their latest releases
+    Update {
+        /// Plugin to update (default: all installed plugins)
+        name: Option<String>,
+
+        /// Install even if the release publishes no checksum for the asset
+        #[arg(long)]
+        allow_unverified: bool,
+
+        /// GitHub token, e.g. for private repositories or higher rate limits
+        #[arg(long)]
+        token: Option<String>,
+    },
+
+    /// Remove an installed plugin
+    Remove {
+        /// Plugin name
+        name: String,
+    },
+
+    /// List installed plugins with their versions and sources
+    List,
+}
+
+impl PluginAction {
+    /// Validate the arguments and run `repos plugin <action>`
+    pub async fn run(self) -> Result<()> {
+        match self {
+            PluginAction::Install {
+                source,
+                name,
+                allow_unverified,
+                token,
+            } => {
+                PluginManager::open_default()?
+                    .with_token(token.or_else(ci::github_token))
+                    .with_allow_unverified(allow_unverified)
+                    .install(&source.parse()?, name.as_deref())
+                    .await?;
+            }
+            PluginAction::Update {
+                name,
+                allow_unverified,
+                token,
+            } => {
+                PluginManager::open_default()?
+                    .with_token(token.or_else(ci::github_token))
+                    .with_allow_unverified(allow_unverified)
+                    .update(name.as_deref())
+                    .await?;
+            }
+            PluginAction::Remove { name } => {
+                PluginManager::open_default()?.remove(&name)?;
+            }
+            PluginAction::List => {
+                PluginManager::open_default()?.print_list()?;
+            }
+        }
+        Ok(())
+    }
+}
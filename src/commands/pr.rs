@@ -1,11 +1,35 @@
 //! Pull request command implementation
 
-use super::{Command, CommandContext};
+use super::pr_comment::PrAction;
+use super::{Command, CommandContext, GlobalOptions, validators};
+use crate::config::{Config, Repository};
+use crate::constants;
+use crate::credentials::Credentials;
+use crate::events;
+use crate::git;
 use crate::github::PrOptions;
-use crate::github::api::create_pr_from_workspace;
-use anyhow::Result;
+use crate::github::api::{create_pr_from_template, create_pr_from_workspace};
+use crate::github::body::{ChangeSummary, SUMMARY_PLACEHOLDER, with_summary_placeholder};
+use crate::github::duplicates::{DuplicatePolicy, ExistingPolicy};
+use crate::github::placeholders::{Placeholders, parse_define};
+use crate::github::reviewers::ReviewRequest;
+use crate::github::rollback::Rollback;
+use crate::github::rollout;
+use crate::github::status::{fetch_statuses, print_status_table};
+use crate::github::template::render_template;
+use crate::logging;
+use crate::state::StateStore;
+use crate::utils::batch::{BatchResult, FailurePolicy};
+use crate::utils::notify;
+use anyhow::{Context, Result};
 use async_trait::async_trait;
+use clap::Args;
 use colored::*;
+use log::Level;
+use repos_github::MergeMethod;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// Pull request command for creating PRs with changes
 pub struct PrCommand {
@@ -15,8 +39,218 @@ pub struct PrCommand {
     pub base_branch: Option<String>,
     pub commit_msg: Option<String>,
     pub draft: bool,
+    /// Token for requests not tied to one repository, such as team lookups
     pub token: String,
+    /// Tokens for each repository's owner, falling back to `token`
+    pub credentials: Credentials,
     pub create_only: bool,
+    /// Commit this template directory through the GitHub API instead of
+    /// opening PRs from local changes
+    pub template: Option<PathBuf>,
+    /// Enable GitHub auto-merge with this method on created PRs
+    pub auto_merge: Option<MergeMethod>,
+    /// Team (`@org/team`) to request reviews from on created PRs
+    pub review_team: Option<String>,
+    /// Assign one team member per PR, spreading reviews across the team
+    pub balance: bool,
+    /// Close the PRs and delete the branches created in this run if any repository fails
+    pub all_or_nothing: bool,
+    /// How many failed repositories fail the command
+    pub failure_policy: FailurePolicy,
+    /// Open PRs from the worktree per repository under this directory instead
+    /// of the working copy, removing each worktree once its PR is created
+    pub worktrees: Option<PathBuf>,
+    /// Look for open PRs with the same change before creating one
+    pub duplicates: Option<DuplicatePolicy>,
+    /// Update or skip an open PR from the same branch into the same base
+    pub existing: Option<ExistingPolicy>,
+    /// Values for the `{{...}}` placeholders in the title, body, branch name and commit message
+    pub placeholders: Placeholders,
+    /// Add a summary of the change to the body, which was read from a Markdown file
+    pub change_summary: bool,
+    /// Keep at most this many of the rollout's PRs open, continuing it on later runs
+    pub batch_size: Option<usize>,
+    /// Open the next batch after this long even if earlier PRs are still open
+    pub batch_delay: Option<Duration>,
+    /// Directory the manifest is written under
+    pub output_dir: PathBuf,
+}
+
+impl PrCommand {
+    /// Undo a failed all-or-nothing run and report the failure
+    async fn roll_back(
+        &self,
+        rollback: &Rollback,
+        (repo_name, e): (&str, &anyhow::Error),
+    ) -> Result<()> {
+        logging::message(
+            Level::Info,
+            "A repository failed; rolling back pull requests and branches created in this run"
+                .yellow(),
+        );
+
+        let client = repos_github::GitHubClient::new(Some(self.token.clone()));
+        let (undone, rollback_errors) = rollback.undo(&client).await;
+
+        if rollback_errors.is_empty() {
+            anyhow::bail!(
+                "Rolled back {} change(s) after {} failed: {}",
+                undone,
+                repo_name,
+                e
+            );
+        }
+        anyhow::bail!(
+            "{} failed: {}. Rolled back {} change(s), but {} could not be undone and need manual cleanup",
+            repo_name,
+            e,
+            undone,
+            rollback_errors.len()
+        );
+    }
+
+    /// Options for one repository, with its token and the placeholders filled in
+    ///
+    /// `checkout` is where the repository's changes are, for the change summary.
+    fn options_for(
+        &self,
+        repo: &Repository,
+        checkout: &Repository,
+        pr_options: &PrOptions,
+    ) -> Result<PrOptions> {
+        let render = |text: &str| self.placeholders.render(text, repo);
+        let render_optional = |text: &Option<String>| text.as_deref().map(render).transpose();
+
+        let body = if self.change_summary {
+            let files = match &self.template {
+                Some(template) => render_template(template, repo, &self.placeholders)?
+                    .into_iter()
+                    .map(|file| file.path)
+                    .collect(),
+                None => git::workspace_files(&checkout.get_target_dir()).unwrap_or_default(),
+            };
+            let summary =
+                ChangeSummary::new(repo, files, Path::new(constants::config::DEFAULT_LOGS_DIR));
+            self.placeholders
+                .clone()
+                .with_value(SUMMARY_PLACEHOLDER, &summary.to_markdown())
+                .render(&with_summary_placeholder(&pr_options.body), repo)?
+        } else {
+            render(&pr_options.body)?
+        };
+
+        let branch_name = render_optional(&pr_options.branch_name)?;
+        validators::validate_branch_name(&branch_name)?;
+        Ok(PrOptions {
+            title: render(&pr_options.title)?,
+            body,
+            branch_name,
+            commit_msg: render_optional(&pr_options.commit_msg)?,
+            token: self
+                .credentials
+                .token_for_repository(repo)
+                .unwrap_or_else(|| pr_options.token.clone()),
+            ..pr_options.clone()
+        })
+    }
+
+    /// Repositories for the next batch of the rollout and how many are left after it,
+    /// or `None` when no batch can open yet
+    async fn next_batch(
+        &self,
+        store: &StateStore,
+        repositories: Vec<Repository>,
+        batch_size: usize,
+    ) -> Result<Option<(Vec<Repository>, usize)>> {
+        let state = store.load()?;
+        let progress = state.rollouts.get(&self.title);
+        let pending: Vec<_> = repositories
+            .into_iter()
+            .filter(|repo| progress.is_none_or(|progress| !progress.done.contains(&repo.name)))
+            .collect();
+
+        if pending.is_empty() {
+            store.remove_rollout(&self.title)?;
+            logging::message(
+                Level::Info,
+                format!("Rollout '{}' is complete", self.title).green(),
+            );
+            return Ok(None);
+        }
+
+        let open = match progress {
+            Some(progress) => {
+                let records: Vec<_> = progress
+                    .pull_requests(&state.pull_requests)
+                    .cloned()
+                    .collect();
+                let client = repos_github::GitHubClient::new(Some(self.token.clone()));
+                // Pull requests whose state is unknown still count as open
+                fetch_statuses(&client, &records)
+                    .await
+                    .iter()
+                    .filter(|result| result.as_ref().ok().is_none_or(|status| status.is_open()))
+                    .count()
+            }
+            None => 0,
+        };
+
+        let capacity =
+            rollout::batch_capacity(batch_size, open, progress, self.batch_delay, rollout::now());
+        if capacity == 0 {
+            let next = match (progress, self.batch_delay) {
+                (Some(progress), Some(delay)) => rollout::next_batch_at(progress, delay)
+                    .map(|at| format!(" or at {}", at.format("%Y-%m-%d %H:%M:%S")))
+                    .unwrap_or_default(),
+                _ => String::new(),
+            };
+            logging::message(
+                Level::Info,
+                format!(
+                    "{} pull request(s) of this rollout are still open; {} repositories wait for the next batch, which opens once some merge{}",
+                    open,
+                    pending.len(),
+                    next
+                )
+                .yellow(),
+            );
+            return Ok(None);
+        }
+
+        store.start_rollout_batch(&self.title)?;
+        let remaining = pending.len().saturating_sub(capacity);
+        let batch: Vec<_> = pending.into_iter().take(capacity).collect();
+        logging::message(
+            Level::Info,
+            format!(
+                "Opening a batch of {} ({} pull request(s) of this rollout open)",
+                batch.len(),
+                open
+            )
+            .green(),
+        );
+        Ok(Some((batch, remaining)))
+    }
+
+    async fn create_pr(&self, repo: &Repository, pr_options: &PrOptions) -> Result<()> {
+        match (&self.template, &self.worktrees) {
+            (Some(template), _) => {
+                let pr_options = self.options_for(repo, repo, pr_options)?;
+                create_pr_from_template(repo, &pr_options, template, &self.placeholders).await
+            }
+            (None, Some(worktrees_dir)) => {
+                let checkout = git::open_worktree(repo, worktrees_dir)?;
+                let pr_options = self.options_for(repo, &checkout, pr_options)?;
+                create_pr_from_workspace(&checkout, &pr_options).await?;
+                // The branch and its commit live on in the repository itself
+                git::close_worktree(repo, worktrees_dir)
+            }
+            (None, None) => {
+                let pr_options = self.options_for(repo, repo, pr_options)?;
+                create_pr_from_workspace(repo, &pr_options).await
+            }
+        }
+    }
 }
 
 #[async_trait]
@@ -47,22 +281,45 @@ impl Command for PrCommand {
                 filter_parts.join(" and ")
             };
 
-            println!(
-                "{}",
-                format!("No repositories found with {filter_desc}").yellow()
+            logging::message(
+                Level::Info,
+                format!("No repositories found with {filter_desc}").yellow(),
             );
             return Ok(());
         }
 
-        println!(
-            "{}",
+        let store = StateStore::open_default();
+        let (repositories, remaining) = match self.batch_size {
+            Some(batch_size) => match self.next_batch(&store, repositories, batch_size).await? {
+                Some(batch) => batch,
+                None => return Ok(()),
+            },
+            None => (repositories, 0),
+        };
+
+        let mut manifest = context.start_manifest("pr")?;
+        logging::message(
+            Level::Info,
             format!(
                 "Checking {} repositories for changes...",
                 repositories.len()
             )
-            .green()
+            .green(),
         );
 
+        // Templates and worktrees leave the working copies alone
+        if self.template.is_none() && self.worktrees.is_none() {
+            super::rollback::checkpoint("pr", &repositories)?;
+        }
+
+        let review_request = match &self.review_team {
+            Some(team) if !self.create_only => {
+                let client = repos_github::GitHubClient::new(Some(self.token.clone()));
+                Some(ReviewRequest::for_team(&client, team, self.balance).await?)
+            }
+            _ => None,
+        };
+
         let pr_options = PrOptions {
             title: self.title.clone(),
             body: self.body.clone(),
@@ -72,74 +329,449 @@ impl Command for PrCommand {
             draft: self.draft,
             token: self.token.clone(),
             create_only: self.create_only,
+            auto_merge: self.auto_merge,
+            review_request,
+            rollback: self.all_or_nothing.then(|| Arc::new(Rollback::new())),
+            duplicates: self.duplicates,
+            existing: self.existing,
         };
 
-        let mut errors = Vec::new();
-        let mut successful = 0;
-
+        let mut batch = BatchResult::new("pr", repositories.len());
         if context.parallel {
             let tasks: Vec<_> = repositories
                 .into_iter()
                 .map(|repo| {
                     let pr_options = pr_options.clone();
                     async move {
-                        (
-                            repo.name.clone(),
-                            create_pr_from_workspace(&repo, &pr_options).await,
-                        )
+                        let started = Instant::now();
+                        let result =
+                            events::track_async(&repo.name, self.create_pr(&repo, &pr_options))
+                                .await;
+                        (repo.name.clone(), result, started.elapsed())
                     }
                 })
                 .collect();
 
-            for task in tasks {
-                let (repo_name, result) = task.await;
-                match result {
-                    Ok(_) => successful += 1,
-                    Err(e) => {
-                        eprintln!("{}", format!("Error: {e}").red());
-                        errors.push((repo_name, e));
-                    }
+            for (repo_name, result, duration) in futures::future::join_all(tasks).await {
+                if let Err(e) = &result {
+                    logging::status(Level::Error, &repo_name, format!("Error: {e}").red());
                 }
+                batch.push(&repo_name, result, Some(duration));
             }
         } else {
+            // Sequential execution stops once the batch failed, as `run` does
+            let stop_early = self.failure_policy.stops_early(false);
             for repo in repositories {
-                match create_pr_from_workspace(&repo, &pr_options).await {
-                    Ok(_) => successful += 1,
-                    Err(e) => {
-                        eprintln!(
-                            "{} | {}",
-                            repo.name.cyan().bold(),
-                            format!("Error: {e}").red()
-                        );
-                        errors.push((repo.name.clone(), e));
-                    }
+                let started = Instant::now();
+                let result =
+                    events::track_async(&repo.name, self.create_pr(&repo, &pr_options)).await;
+                let failed = result.is_err();
+                if let Err(e) = &result {
+                    logging::status(Level::Error, &repo.name, format!("Error: {e}").red());
+                }
+                batch.push(&repo.name, result, Some(started.elapsed()));
+                if !failed {
+                    continue;
+                }
+                // Later repositories would only be rolled back again
+                if self.all_or_nothing
+                    || (stop_early && self.failure_policy.exceeded(batch.failed(), batch.total))
+                {
+                    break;
                 }
             }
         }
 
-        // Report summary
-        if errors.is_empty() {
-            println!("{}", "Done processing pull requests".green());
-        } else {
-            println!(
-                "{}",
+        manifest.finish(batch.manifest_entries());
+        manifest.save(&self.output_dir);
+        notify::notify(context.config.notify.as_ref(), &batch.report()).await;
+
+        if let Some(rollback) = &pr_options.rollback
+            && let Some(failure) = batch.failures().next()
+        {
+            return self.roll_back(rollback, failure).await;
+        }
+
+        // Failed repositories stay pending and are retried with the next batch
+        if self.batch_size.is_some() {
+            for outcome in batch
+                .outcomes
+                .iter()
+                .filter(|outcome| outcome.result.is_ok())
+            {
+                store.record_rollout_progress(&self.title, &outcome.repository)?;
+            }
+            let left = remaining + batch.failed();
+            if left == 0 {
+                store.remove_rollout(&self.title)?;
+                logging::message(
+                    Level::Info,
+                    format!("Rollout '{}' is complete", self.title).green(),
+                );
+            } else {
+                logging::message(
+                    Level::Info,
+                    format!(
+                        "{} repositories left in this rollout; run the same command again to open the next batch",
+                        left
+                    )
+                    .yellow(),
+                );
+            }
+        }
+
+        if batch.failed() == 0 {
+            logging::message(Level::Info, "Done processing pull requests".green());
+        }
+        batch.print_errors();
+        batch.into_result(&self.failure_policy)
+    }
+}
+
+/// Shows the status of pull requests previously opened by `repos pr`
+pub struct PrStatusCommand {
+    pub token: String,
+}
+
+#[async_trait]
+impl Command for PrStatusCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories: Vec<String> = context
+            .config
+            .filter_repositories(&context.tag, &context.exclude_tag, context.repos.as_deref())
+            .into_iter()
+            .map(|repo| repo.name)
+            .collect();
+
+        let store = StateStore::open_default();
+        let records: Vec<_> = store
+            .load()?
+            .pull_requests
+            .into_iter()
+            .filter(|record| repositories.contains(&record.repository))
+            .collect();
+
+        if records.is_empty() {
+            logging::message(Level::Info, "No recorded pull requests found".yellow());
+            return Ok(());
+        }
+
+        let client = repos_github::GitHubClient::new(Some(self.token.clone()));
+        let results = fetch_statuses(&client, &records).await;
+
+        let mut statuses = Vec::new();
+        for (record, result) in records.iter().zip(results) {
+            match result {
+                Ok(status) => statuses.push(status),
+                Err(e) => logging::status(
+                    Level::Error,
+                    &record.repository,
+                    format!("Error: Failed to get status of #{}: {e}", record.number).red(),
+                ),
+            }
+        }
+
+        if !statuses.is_empty() {
+            print_status_table(&statuses);
+        }
+
+        // Merged and closed pull requests are shown once, then forgotten
+        let finished: Vec<_> = statuses
+            .iter()
+            .filter(|status| !status.is_open())
+            .map(|status| status.record.clone())
+            .collect();
+        if !finished.is_empty() {
+            store.remove_pull_requests(|record| finished.contains(record))?;
+            logging::message(
+                Level::Info,
                 format!(
-                    "Completed with {} successful, {} failed",
-                    successful,
-                    errors.len()
+                    "Stopped tracking {} merged or closed pull request(s)",
+                    finished.len()
                 )
-                .yellow()
+                .yellow(),
             );
+        }
 
-            // If all operations failed, return an error to propagate to main
-            if successful == 0 {
-                return Err(anyhow::anyhow!(
-                    "All pull request operations failed. First error: {}",
-                    errors[0].1
-                ));
-            }
+        Ok(())
+    }
+}
+
+/// Arguments of `repos pr`
+#[derive(Debug, Args)]
+pub struct PrArgs {
+    #[command(subcommand)]
+    pub action: Option<Box<PrAction>>,
+
+    /// Specific repository names to create PRs for (if not provided, uses tag filter or all repos)
+    pub repos: Vec<String>,
+
+    /// Title for the pull request; supports {{repo.name}}, {{repo.owner}}, {{repo.repo}}, {{repo.url}}, {{date}} and --define placeholders
+    #[arg(long, default_value = "Automated changes")]
+    pub title: String,
+
+    /// Body text for the pull request; supports the same placeholders as --title [default: This PR was created automatically, or the config's pr.template]
+    #[arg(long)]
+    pub body: Option<String>,
+
+    /// Markdown file to use as the body, with the same placeholders as --title and a summary of the change
+    #[arg(long, value_name = "PATH", conflicts_with = "body")]
+    pub body_file: Option<PathBuf>,
+
+    /// Branch name to create; supports the same placeholders as --title
+    #[arg(long)]
+    pub branch: Option<String>,
+
+    /// Base branch for the PR
+    #[arg(long)]
+    pub base: Option<String>,
+
+    /// Commit message; supports the same placeholders as --title
+    #[arg(long)]
+    pub message: Option<String>,
+
+    /// Value for a {{KEY}} placeholder (can be specified multiple times)
+    #[arg(short = 'D', long = "define", value_name = "KEY=VALUE")]
+    pub define: Vec<String>,
+
+    /// Create PR as draft
+    #[arg(long)]
+    pub draft: bool,
+
+    /// GitHub token
+    #[arg(long)]
+    pub token: Option<String>,
+
+    /// Only create PR, don't commit changes
+    #[arg(long)]
+    pub create_only: bool,
+
+    /// Request reviews on created PRs from this team (@org/team)
+    #[arg(long, value_name = "TEAM")]
+    pub review_team: Option<String>,
+
+    /// Request one team member per PR instead of the whole team, favouring members with the fewest pending reviews
+    #[arg(long, requires = "review_team")]
+    pub balance: bool,
+
+    /// Show the status of pull requests previously opened by repos instead of creating new ones
+    #[arg(long, conflicts_with_all = ["create_only", "api_only", "template", "draft", "auto_merge", "review_team", "all_or_nothing"])]
+    pub status: bool,
+
+    /// Enable GitHub auto-merge on created PRs, optionally with a merge method
+    #[arg(long, value_name = "METHOD", num_args = 0..=1, default_missing_value = "merge", value_parser = ["merge", "squash", "rebase"], conflicts_with = "create_only")]
+    pub auto_merge: Option<String>,
+
+    /// If any repository fails, close the PRs and delete the branches already created in this run
+    #[arg(long, conflicts_with = "create_only")]
+    pub all_or_nothing: bool,
+
+    /// Commit files via the GitHub API instead of from local clones (requires --template)
+    #[arg(long, requires = "template")]
+    pub api_only: bool,
+
+    /// Directory of files to write into each repository; supports the same placeholders as --title
+    #[arg(long, requires = "api_only")]
+    pub template: Option<PathBuf>,
+
+    /// Create PRs from the worktrees left by `repos run --use-worktree` and remove them afterwards
+    #[arg(long, conflicts_with_all = ["template", "status"])]
+    pub use_worktree: bool,
+
+    /// Compare changes with open PRs first and warn about, or skip, repositories where the same change is pending
+    #[arg(long, value_name = "POLICY", num_args = 0..=1, default_missing_value = "warn", value_parser = ["warn", "skip"], conflicts_with_all = ["template", "status"])]
+    pub duplicates: Option<String>,
+
+    /// Update an open PR from the same branch into the same base instead of opening another; without --branch, the branch is named after the title
+    #[arg(long, conflicts_with_all = ["skip_existing", "status"])]
+    pub update_existing: bool,
+
+    /// Skip repositories with an open PR from the same branch into the same base; without --branch, the branch is named after the title
+    #[arg(long, conflicts_with = "status")]
+    pub skip_existing: bool,
+
+    /// Keep at most N PRs of this rollout open; run the same command again to open the next batch as earlier ones merge
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..), conflicts_with_all = ["status", "all_or_nothing"])]
+    pub batch_size: Option<u64>,
+
+    /// Open the next batch once this long has passed since the previous one, even if its PRs are still open (e.g. 30m, 2h, 1d)
+    #[arg(long, value_name = "DURATION", requires = "batch_size")]
+    pub batch_delay: Option<String>,
+
+    /// Number of failed repositories tolerated before the command fails
+    #[arg(long, value_name = "N", conflicts_with = "status")]
+    pub max_failures: Option<usize>,
+
+    /// Percentage of failed repositories tolerated before the command fails, e.g. 20%
+    #[arg(long, value_name = "PERCENT", conflicts_with = "status")]
+    pub failure_threshold: Option<String>,
+
+    /// Directory the manifest is written under (default: output)
+    #[arg(long, conflicts_with = "status")]
+    pub output_dir: Option<String>,
+
+    /// Configuration file path (can be specified multiple times to combine fleets)
+    #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+    pub config: Vec<String>,
+
+    /// Filter repositories by tag (can be specified multiple times)
+    #[arg(short, long)]
+    pub tag: Vec<String>,
+
+    /// Exclude repositories with these tags (can be specified multiple times)
+    #[arg(short = 'e', long)]
+    pub exclude_tag: Vec<String>,
+
+    /// Execute operations in parallel
+    #[arg(short, long)]
+    pub parallel: bool,
+
+    /// Pick repositories interactively from the filtered list before executing
+    #[arg(short, long)]
+    pub interactive: bool,
+}
+
+impl PrArgs {
+    /// Validate the arguments and run `repos pr`
+    pub async fn run(self, globals: &GlobalOptions) -> Result<()> {
+        let Self {
+            action,
+            repos,
+            title,
+            body,
+            body_file,
+            branch,
+            base,
+            message,
+            define,
+            draft,
+            token,
+            create_only,
+            api_only: _,
+            template,
+            use_worktree,
+            duplicates,
+            update_existing,
+            skip_existing,
+            batch_size,
+            batch_delay,
+            max_failures,
+            failure_threshold,
+            output_dir,
+            status,
+            auto_merge,
+            review_team,
+            balance,
+            all_or_nothing,
+            config,
+            tag,
+            exclude_tag,
+            parallel,
+            interactive,
+        } = self;
+        if let Some(action) = action {
+            return action.run(globals).await;
+        }
+        let config = Config::load_all(&config)?;
+
+        // Validate PR command arguments using centralized validators
+        validators::validate_tag_filters(&tag)?;
+        validators::validate_tag_filters(&exclude_tag)?;
+        validators::validate_repository_names(&repos)?;
+        validators::validate_branch_name(&branch)?;
+        validators::validate_branch_name(&base)?;
+        validators::validate_commit_message(&message)?;
+        validators::validate_output_directory(&output_dir)?;
+        let defines = define
+            .iter()
+            .map(|d| parse_define(d))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut context = globals
+            .context(config, tag, exclude_tag, repos)
+            .with_parallel(parallel);
+        if interactive {
+            context.select_interactively()?;
+        }
+
+        let token = token.or_else(|| context.config.resolve_profile_token());
+
+        // Gitea and Azure DevOps repositories read their tokens from GITEA_TOKEN
+        // and AZURE_DEVOPS_TOKEN
+        let needs_github_token = status
+            || context
+                .config
+                .filter_repositories(&context.tag, &context.exclude_tag, context.repos.as_deref())
+                .iter()
+                .any(|repo| repo.provider().is_github());
+        if needs_github_token {
+            validators::validate_pr_args(&token)?;
         }
+        let credentials = Credentials::load(token);
+        let token = credentials.default_token().unwrap_or_default();
 
+        if status {
+            PrStatusCommand { token }.execute(&context).await?;
+        } else {
+            // Bodies from a file get a summary of each repository's change
+            let body_file = body_file.or_else(|| {
+                body.is_none()
+                    .then(|| context.config.pr.as_ref()?.template_path())
+                    .flatten()
+            });
+            let (body, change_summary) = match (body, body_file) {
+                (Some(body), _) => (body, false),
+                (None, Some(path)) => (
+                    std::fs::read_to_string(&path).with_context(|| {
+                        format!("Failed to read PR body from '{}'", path.display())
+                    })?,
+                    true,
+                ),
+                (None, None) => ("This PR was created automatically".to_string(), false),
+            };
+            PrCommand {
+                title,
+                body,
+                branch_name: branch,
+                base_branch: base,
+                commit_msg: message,
+                draft,
+                token,
+                credentials,
+                create_only,
+                template,
+                auto_merge: auto_merge.map(|m| m.parse()).transpose()?,
+                review_team,
+                balance,
+                all_or_nothing,
+                worktrees: use_worktree
+                    .then(|| crate::state::StateStore::open_default().worktrees_dir()),
+                duplicates: duplicates.map(|d| d.parse()).transpose()?,
+                existing: if update_existing {
+                    Some(ExistingPolicy::Update)
+                } else if skip_existing {
+                    Some(ExistingPolicy::Skip)
+                } else {
+                    None
+                },
+                placeholders: Placeholders::new(defines),
+                change_summary,
+                batch_size: batch_size.map(|size| size as usize),
+                batch_delay: batch_delay
+                    .as_deref()
+                    .map(rollout::parse_delay)
+                    .transpose()?,
+                failure_policy: FailurePolicy::from_limits(
+                    max_failures,
+                    failure_threshold.as_deref(),
+                )?,
+                output_dir: PathBuf::from(
+                    output_dir.unwrap_or_else(|| constants::config::DEFAULT_LOGS_DIR.to_string()),
+                ),
+            }
+            .execute(&context)
+            .await?;
+        }
         Ok(())
     }
 }
@@ -148,12 +780,30 @@ impl Command for PrCommand {
 mod tests {
     use super::*;
     use crate::config::{Config, Repository};
+    use tempfile::TempDir;
 
     #[tokio::test]
     async fn test_pr_command_no_repositories() {
         let config = Config {
             repositories: vec![],
             recipes: vec![],
+            artifacts_remote: None,
+            log_sinks: Vec::new(),
+            path_strategy: Default::default(),
+            root: None,
+            files: Vec::new(),
+            branch_protection: Vec::new(),
+            repository_settings: Vec::new(),
+            post_clone: Vec::new(),
+            pr: None,
+            provider: None,
+            provider_url: None,
+            git: Default::default(),
+            exit_codes: Default::default(),
+            notify: None,
+            profiles: Default::default(),
+            secrets: None,
+            profile_token: None,
         };
         let context = CommandContext {
             config,
@@ -163,6 +813,7 @@ mod tests {
             parallel: false,
         };
 
+        let output_dir = TempDir::new().unwrap();
         let pr_command = PrCommand {
             title: "Test PR".to_string(),
             body: "Test body".to_string(),
@@ -172,6 +823,21 @@ mod tests {
             draft: false,
             token: "test_token".to_string(),
             create_only: false,
+            template: None,
+            auto_merge: None,
+            review_team: None,
+            balance: false,
+            all_or_nothing: false,
+            worktrees: None,
+            duplicates: None,
+            existing: None,
+            placeholders: Placeholders::default(),
+            change_summary: false,
+            batch_size: None,
+            batch_delay: None,
+            output_dir: output_dir.path().to_path_buf(),
+            failure_policy: Default::default(),
+            credentials: Credentials::default(),
         };
 
         let result = pr_command.execute(&context).await;
@@ -187,11 +853,39 @@ mod tests {
             branch: None,
             tags: vec!["api".to_string()],
             config_dir: None,
+            path_strategy: Default::default(),
+            default_branch: None,
+            sparse: Vec::new(),
+            provider: None,
+            provider_url: None,
+            provider_defaults: Default::default(),
+            git: Default::default(),
+            depends_on: Vec::new(),
+            post_clone: None,
+            archived: false,
+            remotes: Default::default(),
         };
 
         let config = Config {
             repositories: vec![repository],
             recipes: vec![],
+            artifacts_remote: None,
+            log_sinks: Vec::new(),
+            path_strategy: Default::default(),
+            root: None,
+            files: Vec::new(),
+            branch_protection: Vec::new(),
+            repository_settings: Vec::new(),
+            post_clone: Vec::new(),
+            pr: None,
+            provider: None,
+            provider_url: None,
+            git: Default::default(),
+            exit_codes: Default::default(),
+            notify: None,
+            profiles: Default::default(),
+            secrets: None,
+            profile_token: None,
         };
 
         let context = CommandContext {
@@ -202,6 +896,7 @@ mod tests {
             parallel: false,
         };
 
+        let output_dir = TempDir::new().unwrap();
         let pr_command = PrCommand {
             title: "Test PR".to_string(),
             body: "Test body".to_string(),
@@ -211,6 +906,21 @@ mod tests {
             draft: true,
             token: "test_token".to_string(),
             create_only: true,
+            template: None,
+            auto_merge: None,
+            review_team: None,
+            balance: false,
+            all_or_nothing: false,
+            worktrees: None,
+            duplicates: None,
+            existing: None,
+            placeholders: Placeholders::default(),
+            change_summary: false,
+            batch_size: None,
+            batch_delay: None,
+            output_dir: output_dir.path().to_path_buf(),
+            failure_policy: Default::default(),
+            credentials: Credentials::default(),
         };
 
         let result = pr_command.execute(&context).await;
@@ -226,11 +936,39 @@ mod tests {
             branch: None,
             tags: vec!["backend".to_string()],
             config_dir: None,
+            path_strategy: Default::default(),
+            default_branch: None,
+            sparse: Vec::new(),
+            provider: None,
+            provider_url: None,
+            provider_defaults: Default::default(),
+            git: Default::default(),
+            depends_on: Vec::new(),
+            post_clone: None,
+            archived: false,
+            remotes: Default::default(),
         };
 
         let config = Config {
             repositories: vec![repository],
             recipes: vec![],
+            artifacts_remote: None,
+            log_sinks: Vec::new(),
+            path_strategy: Default::default(),
+            root: None,
+            files: Vec::new(),
+            branch_protection: Vec::new(),
+            repository_settings: Vec::new(),
+            post_clone: Vec::new(),
+            pr: None,
+            provider: None,
+            provider_url: None,
+            git: Default::default(),
+            exit_codes: Default::default(),
+            notify: None,
+            profiles: Default::default(),
+            secrets: None,
+            profile_token: None,
         };
 
         let context = CommandContext {
@@ -241,6 +979,7 @@ mod tests {
             parallel: false,
         };
 
+        let output_dir = TempDir::new().unwrap();
         let pr_command = PrCommand {
             title: "Integration Test PR".to_string(),
             body: "Integration test body".to_string(),
@@ -250,6 +989,21 @@ mod tests {
             draft: false,
             token: "test_token".to_string(),
             create_only: false,
+            template: None,
+            auto_merge: None,
+            review_team: None,
+            balance: false,
+            all_or_nothing: false,
+            worktrees: None,
+            duplicates: None,
+            existing: None,
+            placeholders: Placeholders::default(),
+            change_summary: false,
+            batch_size: None,
+            batch_delay: None,
+            output_dir: output_dir.path().to_path_buf(),
+            failure_policy: Default::default(),
+            credentials: Credentials::default(),
         };
 
         // This will hit the error handling paths since the repo doesn't exist
@@ -257,6 +1011,140 @@ mod tests {
         assert!(result.is_err()); // Expect error due to nonexistent repository
     }
 
+    #[tokio::test]
+    async fn test_pr_command_all_or_nothing() {
+        // One repository without changes succeeds, the missing one fails
+        let clean_dir = TempDir::new().unwrap();
+        std::process::Command::new("git")
+            .args(["init", "--quiet"])
+            .current_dir(clean_dir.path())
+            .status()
+            .unwrap();
+        let mut clean = Repository::new(
+            "clean-repo".to_string(),
+            "https://github.com/test/clean.git".to_string(),
+        );
+        clean.path = Some(clean_dir.path().to_string_lossy().to_string());
+        let mut missing = Repository::new(
+            "missing-repo".to_string(),
+            "https://github.com/test/missing.git".to_string(),
+        );
+        missing.path = Some("./nonexistent-all-or-nothing".to_string());
+
+        let mut config = Config::new();
+        config.repositories = vec![clean, missing];
+        let context = CommandContext {
+            config,
+            tag: vec![],
+            exclude_tag: vec![],
+            repos: None,
+            parallel: false,
+        };
+
+        let output_dir = TempDir::new().unwrap();
+        let mut pr_command = PrCommand {
+            title: "Fleet change".to_string(),
+            body: "Body".to_string(),
+            branch_name: None,
+            base_branch: Some("main".to_string()),
+            commit_msg: None,
+            draft: false,
+            token: "test_token".to_string(),
+            create_only: false,
+            template: None,
+            auto_merge: None,
+            review_team: None,
+            balance: false,
+            all_or_nothing: false,
+            worktrees: None,
+            duplicates: None,
+            existing: None,
+            placeholders: Placeholders::default(),
+            change_summary: false,
+            batch_size: None,
+            batch_delay: None,
+            output_dir: output_dir.path().to_path_buf(),
+            failure_policy: Default::default(),
+            credentials: Credentials::default(),
+        };
+
+        // Partial success fails by default, unless the failures are tolerated
+        let error = pr_command.execute(&context).await.unwrap_err();
+        assert!(
+            error.to_string().ends_with("repositories failed"),
+            "{}",
+            error
+        );
+        pr_command.failure_policy.failure_threshold = Some(100.0);
+        assert!(pr_command.execute(&context).await.is_ok());
+
+        pr_command.all_or_nothing = true;
+        let error = pr_command.execute(&context).await.unwrap_err();
+        assert!(error.to_string().contains("Rolled back 0 change(s)"));
+    }
+
+    #[tokio::test]
+    async fn test_pr_command_sequential_stops_once_failed() {
+        let mut config = Config::new();
+        config.repositories = ["first", "second", "third"]
+            .iter()
+            .map(|name| {
+                let mut repo = Repository::new(
+                    format!("{}-repo", name),
+                    format!("https://github.com/test/{}.git", name),
+                );
+                repo.path = Some(format!("./nonexistent-stop-{}", name));
+                repo
+            })
+            .collect();
+        let context = CommandContext {
+            config,
+            tag: vec![],
+            exclude_tag: vec![],
+            repos: None,
+            parallel: false,
+        };
+
+        let output_dir = TempDir::new().unwrap();
+        let mut pr_command = PrCommand {
+            title: "Fleet change".to_string(),
+            body: "Body".to_string(),
+            branch_name: None,
+            base_branch: Some("main".to_string()),
+            commit_msg: None,
+            draft: false,
+            token: "test_token".to_string(),
+            create_only: false,
+            template: None,
+            auto_merge: None,
+            review_team: None,
+            balance: false,
+            all_or_nothing: false,
+            worktrees: None,
+            duplicates: None,
+            existing: None,
+            placeholders: Placeholders::default(),
+            change_summary: false,
+            batch_size: None,
+            batch_delay: None,
+            output_dir: output_dir.path().to_path_buf(),
+            failure_policy: Default::default(),
+            credentials: Credentials::default(),
+        };
+
+        // The first failure fails the batch, so nothing else is started
+        let error = pr_command.execute(&context).await.unwrap_err();
+        assert_eq!(error.to_string(), "1 of 3 repositories failed");
+
+        // Tolerated failures keep the batch going until the limit is exceeded
+        pr_command.failure_policy.max_failures = Some(1);
+        let error = pr_command.execute(&context).await.unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "2 of 3 repositories failed (at most 1 tolerated)"
+        );
+    }
+
     #[tokio::test]
     async fn test_pr_command_parallel_execution() {
         let repository = Repository {
@@ -266,11 +1154,39 @@ mod tests {
             branch: None,
             tags: vec!["test".to_string()],
             config_dir: None,
+            path_strategy: Default::default(),
+            default_branch: None,
+            sparse: Vec::new(),
+            provider: None,
+            provider_url: None,
+            provider_defaults: Default::default(),
+            git: Default::default(),
+            depends_on: Vec::new(),
+            post_clone: None,
+            archived: false,
+            remotes: Default::default(),
         };
 
         let config = Config {
             repositories: vec![repository],
             recipes: vec![],
+            artifacts_remote: None,
+            log_sinks: Vec::new(),
+            path_strategy: Default::default(),
+            root: None,
+            files: Vec::new(),
+            branch_protection: Vec::new(),
+            repository_settings: Vec::new(),
+            post_clone: Vec::new(),
+            pr: None,
+            provider: None,
+            provider_url: None,
+            git: Default::default(),
+            exit_codes: Default::default(),
+            notify: None,
+            profiles: Default::default(),
+            secrets: None,
+            profile_token: None,
         };
 
         let context = CommandContext {
@@ -281,6 +1197,7 @@ mod tests {
             parallel: true, // Test parallel execution path
         };
 
+        let output_dir = TempDir::new().unwrap();
         let pr_command = PrCommand {
             title: "Parallel Test PR".to_string(),
             body: "Parallel test body".to_string(),
@@ -290,6 +1207,21 @@ mod tests {
             draft: false,
             token: "test_token".to_string(),
             create_only: false,
+            template: None,
+            auto_merge: None,
+            review_team: None,
+            balance: false,
+            all_or_nothing: false,
+            worktrees: None,
+            duplicates: None,
+            existing: None,
+            placeholders: Placeholders::default(),
+            change_summary: false,
+            batch_size: None,
+            batch_delay: None,
+            output_dir: output_dir.path().to_path_buf(),
+            failure_policy: Default::default(),
+            credentials: Credentials::default(),
         };
 
         // This will hit the parallel execution error handling paths
@@ -309,10 +1241,98 @@ mod tests {
             draft: false,
             token: "test_token".to_string(),
             create_only: false,
+            template: None,
+            auto_merge: None,
+            review_team: None,
+            balance: false,
+            all_or_nothing: false,
+            worktrees: None,
+            duplicates: None,
+            existing: None,
+            placeholders: Placeholders::default(),
+            change_summary: false,
+            batch_size: None,
+            batch_delay: None,
+            output_dir: PathBuf::from(constants::config::DEFAULT_LOGS_DIR),
+            failure_policy: Default::default(),
+            credentials: Credentials::default(),
         };
 
         assert_eq!(pr_command.title, "Module Test");
         assert!(!pr_command.draft);
         assert!(!pr_command.create_only);
     }
+
+    #[test]
+    fn test_options_for_renders_placeholders() {
+        let pr_command = PrCommand {
+            title: String::new(),
+            body: String::new(),
+            branch_name: None,
+            base_branch: None,
+            commit_msg: None,
+            draft: false,
+            token: "test_token".to_string(),
+            create_only: false,
+            template: None,
+            auto_merge: None,
+            review_team: None,
+            balance: false,
+            all_or_nothing: false,
+            worktrees: None,
+            duplicates: None,
+            existing: None,
+            placeholders: Placeholders::with_date(
+                "2026-10-16",
+                vec![("ticket".to_string(), "OPS-42".to_string())],
+            ),
+            change_summary: false,
+            batch_size: None,
+            batch_delay: None,
+            output_dir: PathBuf::from(constants::config::DEFAULT_LOGS_DIR),
+            failure_policy: Default::default(),
+            credentials: Credentials::default(),
+        };
+        let repo = Repository::new(
+            "billing".to_string(),
+            "https://github.com/acme/billing.git".to_string(),
+        );
+        let options = PrOptions::new(
+            "{{ticket}}: Bump {{repo.name}}".to_string(),
+            "Scheduled on {{date}}".to_string(),
+            "test_token".to_string(),
+        )
+        .with_branch_name("{{ticket}}/{{repo.name}}".to_string());
+
+        let options = pr_command.options_for(&repo, &repo, &options).unwrap();
+        assert_eq!(options.title, "OPS-42: Bump billing");
+        assert_eq!(options.body, "Scheduled on 2026-10-16");
+        assert_eq!(options.branch_name.as_deref(), Some("OPS-42/billing"));
+        assert_eq!(options.commit_msg, None);
+
+        let options = PrOptions::new(
+            "{{jira}}".to_string(),
+            String::new(),
+            "test_token".to_string(),
+        );
+        assert!(pr_command.options_for(&repo, &repo, &options).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_pr_status_without_records() {
+        let context = CommandContext {
+            config: Config::new(),
+            tag: vec![],
+            exclude_tag: vec![],
+            repos: None,
+            parallel: false,
+        };
+
+        let result = PrStatusCommand {
+            token: "test_token".to_string(),
+        }
+        .execute(&context)
+        .await;
+        assert!(result.is_ok());
+    }
 }
@@ -0,0 +1,368 @@
+//! Bulk commenting on open pull requests
+
+use super::{Command, CommandContext, GlobalOptions, validators};
+use crate::config::{Config, Repository};
+use crate::credentials::Credentials;
+use crate::github::placeholders::Placeholders;
+use crate::{constants, logging};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use clap::Subcommand;
+use colored::*;
+use log::Level;
+use repos_github::{GitHubClient, PullRequestSummary, parse_github_url};
+
+/// Posts the same templated comment on matching open pull requests
+///
+/// The comment may use [`Placeholders`] such as `{{repo.name}}`, plus
+/// `{{number}}`, `{{title}}`, `{{branch}}`, `{{author}}` and `{{url}}` (the pull
+/// request's), replaced per pull request.
+pub struct PrCommentCommand {
+    pub token: String,
+    pub body: String,
+    /// Only comment on pull requests whose head branch matches this glob
+    pub branch: Option<String>,
+    /// Only comment on pull requests carrying all of these labels
+    pub labels: Vec<String>,
+    /// List the pull requests that would be commented on without posting
+    pub dry_run: bool,
+}
+
+/// An open pull request selected for commenting
+struct Target {
+    repository: Repository,
+    owner: String,
+    repo: String,
+    pull_request: PullRequestSummary,
+}
+
+impl PrCommentCommand {
+    fn matches(&self, branch: Option<&glob::Pattern>, pr: &PullRequestSummary) -> bool {
+        branch.is_none_or(|pattern| pattern.matches(&pr.head.branch))
+            && self.labels.iter().all(|label| pr.has_label(label))
+    }
+
+    async fn find_targets(
+        &self,
+        client: &GitHubClient,
+        repo: &Repository,
+        branch: Option<&glob::Pattern>,
+    ) -> Result<Vec<Target>> {
+        let (owner, repo_name) = parse_github_url(&repo.url)?;
+        let pull_requests = client.list_open_pull_requests(&owner, &repo_name).await?;
+
+        Ok(pull_requests
+            .into_iter()
+            .filter(|pr| self.matches(branch, pr))
+            .map(|pull_request| Target {
+                repository: repo.clone(),
+                owner: owner.clone(),
+                repo: repo_name.clone(),
+                pull_request,
+            })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl Command for PrCommentCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.config.filter_repositories(
+            &context.tag,
+            &context.exclude_tag,
+            context.repos.as_deref(),
+        );
+
+        if repositories.is_empty() {
+            logging::message(Level::Info, "No repositories found".yellow());
+            return Ok(());
+        }
+
+        let branch = self
+            .branch
+            .as_deref()
+            .map(glob::Pattern::new)
+            .transpose()
+            .context("Invalid --branch pattern")?;
+
+        let client = GitHubClient::new(Some(self.token.clone()));
+        let results = futures::future::join_all(
+            repositories
+                .iter()
+                .map(|repo| self.find_targets(&client, repo, branch.as_ref())),
+        )
+        .await;
+
+        let mut targets = Vec::new();
+        let mut errors = Vec::new();
+        for (repo, result) in repositories.iter().zip(results) {
+            match result {
+                Ok(found) => targets.extend(found),
+                Err(e) => {
+                    logging::status(
+                        Level::Error,
+                        &repo.name,
+                        format!("Error: Failed to list pull requests: {e}").red(),
+                    );
+                    errors.push((repo.name.clone(), e));
+                }
+            }
+        }
+
+        if targets.is_empty() {
+            logging::message(Level::Info, "No matching open pull requests found".yellow());
+        }
+
+        if self.dry_run {
+            for target in &targets {
+                let pr = &target.pull_request;
+                logging::status(
+                    Level::Info,
+                    &target.repository.name,
+                    format!(
+                        "{} {}",
+                        format!(
+                            "Would comment on #{} {} ({})",
+                            pr.number, pr.title, pr.head.branch
+                        )
+                        .yellow(),
+                        pr.html_url
+                    ),
+                );
+            }
+            if let Some(target) = targets.first() {
+                logging::message(Level::Info, "");
+                logging::message(Level::Info, "Comment preview:".bold());
+                logging::message(Level::Info, render_comment(&self.body, target));
+                logging::message(Level::Info, "");
+            }
+            logging::message(
+                Level::Info,
+                format!("Would comment on {} pull request(s)", targets.len()).green(),
+            );
+        } else {
+            let mut commented = 0;
+            for target in &targets {
+                let number = target.pull_request.number;
+                let body = render_comment(&self.body, target);
+                match client
+                    .create_issue_comment(&target.owner, &target.repo, number, &body)
+                    .await
+                {
+                    Ok(comment) => {
+                        logging::status(
+                            Level::Info,
+                            &target.repository.name,
+                            format!(
+                                "{} {}",
+                                format!("Commented on #{}", number).green(),
+                                comment.html_url
+                            ),
+                        );
+                        commented += 1;
+                    }
+                    Err(e) => {
+                        logging::status(
+                            Level::Error,
+                            &target.repository.name,
+                            format!("Error: Failed to comment on #{}: {e}", number).red(),
+                        );
+                        errors.push((target.repository.name.clone(), e));
+                    }
+                }
+            }
+            logging::message(
+                Level::Info,
+                format!(
+                    "Commented on {} pull request(s), failed {}",
+                    commented,
+                    errors.len()
+                )
+                .green(),
+            );
+        }
+
+        if let Some((repo_name, e)) = errors.first() {
+            anyhow::bail!(
+                "{} repository operation(s) failed. First error ({}): {}",
+                errors.len(),
+                repo_name,
+                e
+            );
+        }
+
+        Ok(())
+    }
+}
+
+fn render_comment(body: &str, target: &Target) -> String {
+    let pr = &target.pull_request;
+    let author = pr.user.as_ref().map_or("", |user| user.login.as_str());
+
+    Placeholders::default()
+        .with_value("number", &pr.number.to_string())
+        .with_value("title", &pr.title)
+        .with_value("branch", &pr.head.branch)
+        .with_value("author", author)
+        .with_value("url", &pr.html_url)
+        .fill(body, &target.repository)
+}
+
+/// Subcommands of `repos pr`
+#[derive(Debug, Subcommand)]
+pub enum PrAction {
+    /// Post a comment on open PRs matching a branch or label
+    Comment {
+        /// Specific repository names to comment in (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Comment text; supports {{repo.name}}, {{repo.owner}}, {{repo.repo}}, {{number}}, {{title}}, {{branch}}, {{author}} and {{url}}
+        #[arg(long)]
+        body: String,
+
+        /// Only comment on PRs whose head branch matches this glob (e.g. "repos/*")
+        #[arg(long, required_unless_present = "label")]
+        branch: Option<String>,
+
+        /// Only comment on PRs with this label (can be specified multiple times)
+        #[arg(long)]
+        label: Vec<String>,
+
+        /// List the PRs that would be commented on without posting
+        #[arg(long)]
+        dry_run: bool,
+
+        /// GitHub token
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Configuration file path (can be specified multiple times to combine fleets)
+        #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+        config: Vec<String>,
+
+        /// Filter repositories by tag (can be specified multiple times)
+        #[arg(short, long)]
+        tag: Vec<String>,
+
+        /// Exclude repositories with these tags (can be specified multiple times)
+        #[arg(short = 'e', long)]
+        exclude_tag: Vec<String>,
+    },
+}
+
+impl PrAction {
+    /// Validate the arguments and run `repos pr <action>`
+    pub async fn run(self, globals: &GlobalOptions) -> Result<()> {
+        match self {
+            PrAction::Comment {
+                repos,
+                body,
+                branch,
+                label,
+                dry_run,
+                token,
+                config,
+                tag,
+                exclude_tag,
+            } => {
+                let config = Config::load_all(&config)?;
+
+                validators::validate_tag_filters(&tag)?;
+                validators::validate_tag_filters(&exclude_tag)?;
+                validators::validate_repository_names(&repos)?;
+
+                let mut context = globals.context(config, tag, exclude_tag, repos);
+                context.skip_archived(globals.include_archived);
+
+                let token = Credentials::load(token.or_else(|| context.config.resolve_profile_token()))
+                .default_token()
+                .ok_or_else(|| anyhow::anyhow!("GitHub token not provided. Use --token flag, set GITHUB_TOKEN environment variable, or save one with `repos auth login`."))?;
+
+                PrCommentCommand {
+                    token,
+                    body,
+                    branch,
+                    labels: label,
+                    dry_run,
+                }
+                .execute(&context)
+                .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn pull_request(branch: &str, labels: &[&str]) -> PullRequestSummary {
+        serde_json::from_value(serde_json::json!({
+            "number": 42,
+            "html_url": "https://github.com/org/api/pull/42",
+            "title": "Upgrade logging",
+            "user": { "login": "alice" },
+            "head": { "ref": branch, "sha": "abc123" },
+            "labels": labels.iter().map(|name| serde_json::json!({ "name": name })).collect::<Vec<_>>()
+        }))
+        .unwrap()
+    }
+
+    fn command(branch: Option<&str>, labels: &[&str]) -> PrCommentCommand {
+        PrCommentCommand {
+            token: "test_token".to_string(),
+            body: "Please merge before the freeze".to_string(),
+            branch: branch.map(str::to_string),
+            labels: labels.iter().map(|l| l.to_string()).collect(),
+            dry_run: true,
+        }
+    }
+
+    #[test]
+    fn test_matches() {
+        let pattern = glob::Pattern::new("repos/*").unwrap();
+        let by_branch = command(Some("repos/*"), &[]);
+        assert!(by_branch.matches(Some(&pattern), &pull_request("repos/abc123", &[])));
+        assert!(!by_branch.matches(Some(&pattern), &pull_request("feature/x", &[])));
+
+        let by_label = command(None, &["release", "deps"]);
+        assert!(by_label.matches(None, &pull_request("x", &["deps", "Release"])));
+        assert!(!by_label.matches(None, &pull_request("x", &["deps"])));
+    }
+
+    #[test]
+    fn test_render_comment() {
+        let target = Target {
+            repository: Repository::new(
+                "api".to_string(),
+                "git@github.com:org/api-service.git".to_string(),
+            ),
+            owner: "org".to_string(),
+            repo: "api-service".to_string(),
+            pull_request: pull_request("repos/abc123", &[]),
+        };
+
+        assert_eq!(
+            render_comment(
+                "@{{author}}: please merge {{owner}}/{{repo}}#{{number}} ({{branch}}) before Friday",
+                &target
+            ),
+            "@alice: please merge org/api-service#42 (repos/abc123) before Friday"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_comment_without_repositories() {
+        let context = CommandContext {
+            config: Config::new(),
+            tag: vec![],
+            exclude_tag: vec![],
+            repos: None,
+            parallel: false,
+        };
+
+        assert!(command(None, &[]).execute(&context).await.is_ok());
+    }
+}
@@ -0,0 +1,565 @@
+//! Protect command implementation
+
+use super::{Command, CommandContext, GlobalOptions, validators};
+use crate::config::{BranchProtectionPolicy, Config, Repository};
+use crate::credentials::Credentials;
+use crate::{constants, logging};
+use anyhow::Result;
+use async_trait::async_trait;
+use clap::Args;
+use colored::*;
+use futures::StreamExt;
+use log::Level;
+use repos_github::{BranchProtection, GitHubClient, parse_github_url};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Repositories queried at the same time
+const CONCURRENCY: usize = 8;
+
+/// Protect command comparing branch protection against the `branch_protection` policies in the config
+pub struct ProtectCommand {
+    pub token: Option<String>,
+    /// Update drifting branches to match the policy
+    pub apply: bool,
+    /// Print the drift report as JSON
+    pub json: bool,
+}
+
+/// A protection setting of a branch that differs from the policy
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProtectionDrift {
+    pub branch: String,
+    pub setting: &'static str,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl fmt::Display for ProtectionDrift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} is {}, expected {}",
+            self.branch, self.setting, self.actual, self.expected
+        )
+    }
+}
+
+/// Drift found in one repository
+#[derive(Debug, Clone, Serialize)]
+pub struct ProtectionReport {
+    pub repository: String,
+    pub drift: Vec<ProtectionDrift>,
+    /// Whether the policy was applied to the drifting branches
+    pub applied: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Protection GitHub reports for a branch without protection rules
+fn unprotected() -> BranchProtection {
+    BranchProtection {
+        allow_force_pushes: true,
+        allow_deletions: true,
+        ..Default::default()
+    }
+}
+
+fn describe_reviews(reviews: Option<u32>) -> String {
+    reviews.map_or_else(|| "not required".to_string(), |count| count.to_string())
+}
+
+/// Required status checks in a comparable form, with no checks meaning none are required
+fn normalize_checks(checks: Option<&[String]>) -> Option<Vec<String>> {
+    let mut checks = checks.filter(|checks| !checks.is_empty())?.to_vec();
+    checks.sort();
+    Some(checks)
+}
+
+fn describe_checks(checks: &Option<Vec<String>>) -> String {
+    match checks {
+        Some(checks) => format!("[{}]", checks.join(", ")),
+        None => "not required".to_string(),
+    }
+}
+
+/// Settings of a branch's protection that differ from a policy
+///
+/// `current` is `None` for a branch without protection rules.
+pub fn check_protection(
+    branch: &str,
+    policy: &BranchProtectionPolicy,
+    current: Option<&BranchProtection>,
+) -> Vec<ProtectionDrift> {
+    let current = current.cloned().unwrap_or_else(unprotected);
+    let mut drift = Vec::new();
+    let mut differs = |setting: &'static str, expected: String, actual: String| {
+        if expected != actual {
+            drift.push(ProtectionDrift {
+                branch: branch.to_string(),
+                setting,
+                expected,
+                actual,
+            });
+        }
+    };
+
+    if let Some(reviews) = policy.required_approving_reviews {
+        differs(
+            "required_approving_reviews",
+            describe_reviews(Some(reviews)),
+            describe_reviews(current.required_approving_reviews),
+        );
+    }
+    if let Some(checks) = &policy.required_status_checks {
+        differs(
+            "required_status_checks",
+            describe_checks(&normalize_checks(Some(checks))),
+            describe_checks(&normalize_checks(current.required_status_checks.as_deref())),
+        );
+    }
+    let flags = [
+        (
+            "dismiss_stale_reviews",
+            policy.dismiss_stale_reviews,
+            current.dismiss_stale_reviews,
+        ),
+        (
+            "require_code_owner_reviews",
+            policy.require_code_owner_reviews,
+            current.require_code_owner_reviews,
+        ),
+        (
+            "strict_status_checks",
+            policy.strict_status_checks,
+            current.strict_status_checks,
+        ),
+        (
+            "enforce_admins",
+            policy.enforce_admins,
+            current.enforce_admins,
+        ),
+        (
+            "required_linear_history",
+            policy.required_linear_history,
+            current.required_linear_history,
+        ),
+        (
+            "allow_force_pushes",
+            policy.allow_force_pushes,
+            current.allow_force_pushes,
+        ),
+        (
+            "allow_deletions",
+            policy.allow_deletions,
+            current.allow_deletions,
+        ),
+        (
+            "required_conversation_resolution",
+            policy.required_conversation_resolution,
+            current.required_conversation_resolution,
+        ),
+    ];
+    for (setting, expected, actual) in flags {
+        if let Some(expected) = expected {
+            differs(setting, expected.to_string(), actual.to_string());
+        }
+    }
+
+    drift
+}
+
+/// Protection of a branch with a policy applied, keeping settings the policy leaves out
+pub fn apply_policy(
+    policy: &BranchProtectionPolicy,
+    current: Option<&BranchProtection>,
+) -> BranchProtection {
+    let mut protection = current.cloned().unwrap_or_default();
+
+    if let Some(reviews) = policy.required_approving_reviews {
+        protection.required_approving_reviews = Some(reviews);
+    }
+    if let Some(checks) = &policy.required_status_checks {
+        protection.required_status_checks = normalize_checks(Some(checks));
+    }
+    let flags = [
+        (
+            policy.dismiss_stale_reviews,
+            &mut protection.dismiss_stale_reviews,
+        ),
+        (
+            policy.require_code_owner_reviews,
+            &mut protection.require_code_owner_reviews,
+        ),
+        (
+            policy.strict_status_checks,
+            &mut protection.strict_status_checks,
+        ),
+        (policy.enforce_admins, &mut protection.enforce_admins),
+        (
+            policy.required_linear_history,
+            &mut protection.required_linear_history,
+        ),
+        (
+            policy.allow_force_pushes,
+            &mut protection.allow_force_pushes,
+        ),
+        (policy.allow_deletions, &mut protection.allow_deletions),
+        (
+            policy.required_conversation_resolution,
+            &mut protection.required_conversation_resolution,
+        ),
+    ];
+    for (value, setting) in flags {
+        if let Some(value) = value {
+            *setting = value;
+        }
+    }
+
+    // Review and status check options only exist while reviews and checks are required
+    if (protection.dismiss_stale_reviews || protection.require_code_owner_reviews)
+        && protection.required_approving_reviews.is_none()
+    {
+        protection.required_approving_reviews = Some(0);
+    }
+    if protection.strict_status_checks && protection.required_status_checks.is_none() {
+        protection.required_status_checks = Some(Vec::new());
+    }
+
+    protection
+}
+
+impl ProtectCommand {
+    /// Check, and with `--apply` fix, the branches the policies cover in one repository
+    async fn protect_repository(
+        &self,
+        credentials: &Credentials,
+        repo: &Repository,
+        policies: &[&BranchProtectionPolicy],
+    ) -> Result<(Vec<ProtectionDrift>, bool)> {
+        let (owner, name) = parse_github_url(&repo.url)?;
+        let client = GitHubClient::new(credentials.token_for_repository(repo));
+
+        let mut default_branch: Option<String> = None;
+        let mut protections: HashMap<String, Option<BranchProtection>> = HashMap::new();
+        let mut drift = Vec::new();
+        let mut applied = false;
+        for policy in policies {
+            let branch = match &policy.branch {
+                Some(branch) => branch.clone(),
+                None => match &default_branch {
+                    Some(branch) => branch.clone(),
+                    None => {
+                        let branch = client
+                            .get_repository_details(&owner, &name)
+                            .await?
+                            .default_branch
+                            .ok_or_else(|| {
+                                anyhow::anyhow!("GitHub did not report a default branch")
+                            })?;
+                        default_branch = Some(branch.clone());
+                        branch
+                    }
+                },
+            };
+
+            let current = match protections.get(&branch) {
+                Some(current) => current.clone(),
+                None => client.get_branch_protection(&owner, &name, &branch).await?,
+            };
+            let branch_drift = check_protection(&branch, policy, current.as_ref());
+
+            let current = if self.apply && !branch_drift.is_empty() {
+                let protection = apply_policy(policy, current.as_ref());
+                client
+                    .update_branch_protection(&owner, &name, &branch, &protection)
+                    .await?;
+                applied = true;
+                Some(protection)
+            } else {
+                current
+            };
+            protections.insert(branch, current);
+            drift.extend(branch_drift);
+        }
+
+        Ok((drift, applied))
+    }
+}
+
+#[async_trait]
+impl Command for ProtectCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let policies = &context.config.branch_protection;
+        if policies.is_empty() {
+            anyhow::bail!(
+                "No branch protection policy configured; add a 'branch_protection' section to the config"
+            );
+        }
+
+        let (repositories, other): (Vec<Repository>, Vec<Repository>) = context
+            .config
+            .filter_repositories(&context.tag, &context.exclude_tag, context.repos.as_deref())
+            .into_iter()
+            .filter(|repo| policies.iter().any(|policy| policy.applies_to(repo)))
+            .partition(|repo| repo.provider().is_github());
+
+        if !other.is_empty() {
+            logging::message(
+                Level::Warn,
+                format!("Skipping {} repositories not hosted on GitHub", other.len()).yellow(),
+            );
+        }
+        if repositories.is_empty() {
+            logging::message(Level::Info, "No repositories found".yellow());
+            return Ok(());
+        }
+
+        let credentials = Credentials::load(self.token.clone());
+        let checks: Vec<_> = repositories
+            .iter()
+            .map(|repo| {
+                let credentials = &credentials;
+                async move {
+                    let applicable: Vec<&BranchProtectionPolicy> = policies
+                        .iter()
+                        .filter(|policy| policy.applies_to(repo))
+                        .collect();
+                    let result = self
+                        .protect_repository(credentials, repo, &applicable)
+                        .await;
+                    match result {
+                        Ok((drift, applied)) => ProtectionReport {
+                            repository: repo.name.clone(),
+                            drift,
+                            applied,
+                            error: None,
+                        },
+                        Err(e) => ProtectionReport {
+                            repository: repo.name.clone(),
+                            drift: Vec::new(),
+                            applied: false,
+                            error: Some(format!("{:#}", e)),
+                        },
+                    }
+                }
+            })
+            .collect();
+        let reports: Vec<ProtectionReport> = futures::stream::iter(checks)
+            .buffered(CONCURRENCY)
+            .collect()
+            .await;
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&reports)?);
+        } else {
+            for report in &reports {
+                if let Some(error) = &report.error {
+                    logging::status(
+                        Level::Error,
+                        &report.repository,
+                        format!("Error: {}", error).red(),
+                    );
+                } else if report.drift.is_empty() {
+                    logging::status(Level::Info, &report.repository, "Up to date".green());
+                } else {
+                    for drift in &report.drift {
+                        logging::status(Level::Warn, &report.repository, drift.to_string().red());
+                    }
+                    if report.applied {
+                        logging::status(
+                            Level::Info,
+                            &report.repository,
+                            "Applied branch protection policy".green(),
+                        );
+                    }
+                }
+            }
+        }
+
+        let drifted = reports.iter().filter(|r| !r.drift.is_empty()).count();
+        let failed: Vec<&ProtectionReport> = reports.iter().filter(|r| r.error.is_some()).collect();
+        if !self.json {
+            logging::message(
+                Level::Info,
+                format!(
+                    "{} of {} repositories drift from the branch protection policy",
+                    drifted,
+                    reports.len() - failed.len()
+                )
+                .bold(),
+            );
+        }
+
+        if let Some(first) = failed.first() {
+            anyhow::bail!(
+                "Failed to check branch protection of {} repositories. First error ({}): {}",
+                failed.len(),
+                first.repository,
+                first.error.as_deref().unwrap_or_default()
+            );
+        }
+        if drifted > 0 && !self.apply {
+            anyhow::bail!(
+                "{} repositories drift from the branch protection policy",
+                drifted
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Arguments of `repos protect`
+#[derive(Debug, Args)]
+pub struct ProtectArgs {
+    /// Specific repository names to check (if not provided, uses tag filter or all repos)
+    pub repos: Vec<String>,
+
+    /// Update drifting branches to match the policy
+    #[arg(long)]
+    pub apply: bool,
+
+    /// Output the drift report in JSON format
+    #[arg(long)]
+    pub json: bool,
+
+    /// GitHub token
+    #[arg(long)]
+    pub token: Option<String>,
+
+    /// Configuration file path (can be specified multiple times to combine fleets)
+    #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+    pub config: Vec<String>,
+
+    /// Filter repositories by tag (can be specified multiple times)
+    #[arg(short, long)]
+    pub tag: Vec<String>,
+
+    /// Exclude repositories with these tags (can be specified multiple times)
+    #[arg(short = 'e', long)]
+    pub exclude_tag: Vec<String>,
+}
+
+impl ProtectArgs {
+    /// Validate the arguments and run `repos protect`
+    pub async fn run(self, globals: &GlobalOptions) -> Result<()> {
+        let Self {
+            repos,
+            apply,
+            json,
+            token,
+            config,
+            tag,
+            exclude_tag,
+        } = self;
+        let config = Config::load_all(&config)?;
+
+        validators::validate_tag_filters(&tag)?;
+        validators::validate_tag_filters(&exclude_tag)?;
+        validators::validate_repository_names(&repos)?;
+
+        let token = token.or_else(|| config.resolve_profile_token());
+        let mut context = globals.context(config, tag, exclude_tag, repos);
+        context.skip_archived(globals.include_archived);
+        ProtectCommand { token, apply, json }
+            .execute(&context)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> BranchProtectionPolicy {
+        BranchProtectionPolicy {
+            required_approving_reviews: Some(2),
+            required_status_checks: Some(vec!["test".to_string(), "build".to_string()]),
+            enforce_admins: Some(true),
+            allow_force_pushes: Some(false),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_check_unprotected_branch() {
+        let drift = check_protection("main", &policy(), None);
+        let settings: Vec<&str> = drift.iter().map(|d| d.setting).collect();
+        assert_eq!(
+            settings,
+            vec![
+                "required_approving_reviews",
+                "required_status_checks",
+                "enforce_admins",
+                "allow_force_pushes",
+            ]
+        );
+        assert_eq!(
+            drift[1].to_string(),
+            "main: required_status_checks is not required, expected [build, test]"
+        );
+    }
+
+    #[test]
+    fn test_check_matching_protection() {
+        let current = BranchProtection {
+            required_approving_reviews: Some(2),
+            required_status_checks: Some(vec!["build".to_string(), "test".to_string()]),
+            enforce_admins: true,
+            // Not part of the policy
+            required_linear_history: true,
+            ..Default::default()
+        };
+        assert!(check_protection("main", &policy(), Some(&current)).is_empty());
+
+        let current = BranchProtection {
+            required_approving_reviews: Some(1),
+            ..current
+        };
+        let drift = check_protection("main", &policy(), Some(&current));
+        assert_eq!(
+            drift,
+            vec![ProtectionDrift {
+                branch: "main".to_string(),
+                setting: "required_approving_reviews",
+                expected: "2".to_string(),
+                actual: "1".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_apply_policy_keeps_other_settings() {
+        let current = BranchProtection {
+            required_approving_reviews: Some(1),
+            required_linear_history: true,
+            strict_status_checks: true,
+            required_status_checks: Some(vec!["lint".to_string()]),
+            ..Default::default()
+        };
+        let applied = apply_policy(&policy(), Some(&current));
+
+        assert_eq!(applied.required_approving_reviews, Some(2));
+        assert_eq!(
+            applied.required_status_checks,
+            Some(vec!["build".to_string(), "test".to_string()])
+        );
+        assert!(applied.strict_status_checks);
+        assert!(applied.required_linear_history);
+        assert!(applied.enforce_admins);
+        assert!(check_protection("main", &policy(), Some(&applied)).is_empty());
+    }
+
+    #[test]
+    fn test_apply_policy_requires_reviews_for_review_options() {
+        let policy = BranchProtectionPolicy {
+            require_code_owner_reviews: Some(true),
+            ..Default::default()
+        };
+        let applied = apply_policy(&policy, None);
+        assert_eq!(applied.required_approving_reviews, Some(0));
+        assert!(applied.require_code_owner_reviews);
+        assert!(!applied.allow_force_pushes);
+    }
+}
@@ -0,0 +1,346 @@
+//! Push command implementation
+
+use super::{Command, CommandContext, GlobalOptions, validators};
+use crate::config::{Config, Repository};
+use crate::git::{self, PushOptions, PushReport, RemoteAccess};
+use crate::logging;
+use crate::{constants, events};
+use anyhow::Result;
+use async_trait::async_trait;
+use clap::Args;
+use colored::*;
+use log::Level;
+use std::path::Path;
+
+/// Push command publishing a branch of every repository to `origin`
+#[derive(Debug, Clone, Default)]
+pub struct PushCommand {
+    /// Branch to push; the checked-out branch of each repository when `None`
+    pub branch: Option<String>,
+    pub options: PushOptions,
+}
+
+/// What happened in one repository
+enum PushOutcome {
+    Pushed(PushReport),
+    UpToDate,
+    Rejected(PushReport),
+}
+
+impl PushCommand {
+    fn push_repository(&self, repo: &Repository) -> Result<PushOutcome> {
+        let target_dir = repo.get_target_dir();
+        let branch = match &self.branch {
+            Some(branch) => branch.clone(),
+            None => {
+                let branch = git::get_current_branch(&target_dir)?;
+                if branch.is_empty() {
+                    anyhow::bail!("HEAD is detached; pass --branch to choose what to push");
+                }
+                branch
+            }
+        };
+
+        let access = RemoteAccess::for_repository(repo, None);
+        let report = git::push(&target_dir, &branch, &self.options, &access)?;
+        Ok(if !report.rejected.is_empty() {
+            PushOutcome::Rejected(report)
+        } else if report.pushed.is_empty() {
+            PushOutcome::UpToDate
+        } else {
+            PushOutcome::Pushed(report)
+        })
+    }
+}
+
+#[async_trait]
+impl Command for PushCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.config.filter_repositories(
+            &context.tag,
+            &context.exclude_tag,
+            context.repos.as_deref(),
+        );
+
+        if repositories.is_empty() {
+            logging::message(Level::Info, "No repositories found".yellow());
+            return Ok(());
+        }
+
+        let mut cloned = Vec::new();
+        for repo in repositories {
+            if Path::new(&repo.get_target_dir()).exists() {
+                cloned.push(repo);
+            } else {
+                logging::status(
+                    Level::Warn,
+                    &repo.name,
+                    "Skipping: repository is not cloned".yellow(),
+                );
+            }
+        }
+
+        let mut results = Vec::new();
+        if context.parallel {
+            let tasks: Vec<_> = cloned
+                .into_iter()
+                .map(|repo| {
+                    let command = self.clone();
+                    tokio::task::spawn_blocking(move || {
+                        let result = events::track(&repo.name, || command.push_repository(&repo));
+                        (repo, result)
+                    })
+                })
+                .collect();
+
+            for task in tasks {
+                results.push(task.await?);
+            }
+        } else {
+            for repo in cloned {
+                let result = events::track(&repo.name, || self.push_repository(&repo));
+                results.push((repo, result));
+            }
+        }
+
+        let mut pushed = 0;
+        let mut up_to_date = 0;
+        let mut rejected = Vec::new();
+        let mut errors = Vec::new();
+        for (repo, result) in results {
+            match result {
+                Ok(PushOutcome::Pushed(report)) => {
+                    logging::status(
+                        Level::Info,
+                        &repo.name,
+                        format!("Pushed {}", report.pushed.join(", ")).green(),
+                    );
+                    pushed += 1;
+                }
+                Ok(PushOutcome::UpToDate) => {
+                    logging::status(Level::Info, &repo.name, "Up to date".dimmed());
+                    up_to_date += 1;
+                }
+                Ok(PushOutcome::Rejected(report)) => {
+                    let refs: Vec<String> = report
+                        .rejected
+                        .iter()
+                        .map(|(name, reason)| format!("{} ({})", name, reason))
+                        .collect();
+                    logging::status(
+                        Level::Warn,
+                        &repo.name,
+                        format!("Rejected {}", refs.join(", ")).yellow(),
+                    );
+                    rejected.push(repo.name);
+                }
+                Err(e) => {
+                    logging::status(Level::Error, &repo.name, format!("Error: {e}").red());
+                    errors.push((repo.name, e));
+                }
+            }
+        }
+
+        let mut summary = format!("Pushed {}, {} up to date", pushed, up_to_date);
+        if !rejected.is_empty() {
+            summary.push_str(&format!(
+                ", {} rejected ({})",
+                rejected.len(),
+                rejected.join(", ")
+            ));
+        }
+        if !errors.is_empty() {
+            summary.push_str(&format!(", {} failed", errors.len()));
+        }
+
+        if rejected.is_empty() && errors.is_empty() {
+            logging::message(Level::Info, summary.green());
+            return Ok(());
+        }
+        logging::message(Level::Info, summary.yellow());
+        match errors.first() {
+            Some((repo_name, e)) => anyhow::bail!(
+                "Failed to push {} repositories. First error ({}): {}",
+                errors.len() + rejected.len(),
+                repo_name,
+                e
+            ),
+            None => anyhow::bail!(
+                "Pushes were rejected in {} repositories: {}",
+                rejected.len(),
+                rejected.join(", ")
+            ),
+        }
+    }
+}
+
+/// Arguments of `repos push`
+#[derive(Debug, Args)]
+pub struct PushArgs {
+    /// Specific repository names to push (if not provided, uses tag filter or all repos)
+    pub repos: Vec<String>,
+
+    /// Branch to push instead of the checked-out one
+    #[arg(short, long)]
+    pub branch: Option<String>,
+
+    /// Make origin/<branch> the upstream of the pushed branch
+    #[arg(short = 'u', long)]
+    pub set_upstream: bool,
+
+    /// Overwrite the remote branch unless it changed since the last fetch
+    #[arg(long)]
+    pub force_with_lease: bool,
+
+    /// Also push all tags
+    #[arg(long)]
+    pub tags: bool,
+
+    /// Configuration file path (can be specified multiple times to combine fleets)
+    #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+    pub config: Vec<String>,
+
+    /// Filter repositories by tag (can be specified multiple times)
+    #[arg(short, long)]
+    pub tag: Vec<String>,
+
+    /// Exclude repositories with these tags (can be specified multiple times)
+    #[arg(short = 'e', long)]
+    pub exclude_tag: Vec<String>,
+
+    /// Execute operations in parallel
+    #[arg(short, long)]
+    pub parallel: bool,
+}
+
+impl PushArgs {
+    /// Validate the arguments and run `repos push`
+    pub async fn run(self, globals: &GlobalOptions) -> Result<()> {
+        let Self {
+            repos,
+            branch,
+            set_upstream,
+            force_with_lease,
+            tags,
+            config,
+            tag,
+            exclude_tag,
+            parallel,
+        } = self;
+        let config = Config::load_all(&config)?;
+
+        validators::validate_tag_filters(&tag)?;
+        validators::validate_tag_filters(&exclude_tag)?;
+        validators::validate_repository_names(&repos)?;
+
+        let mut context = globals
+            .context(config, tag, exclude_tag, repos)
+            .with_parallel(parallel);
+        context.skip_archived(globals.include_archived);
+        PushCommand {
+            branch,
+            options: crate::git::PushOptions {
+                set_upstream,
+                force_with_lease,
+                tags,
+            },
+        }
+        .execute(&context)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use std::process::Command as Process;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let output = Process::new("git")
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "git {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    fn context(root: &Path, names: &[&str]) -> CommandContext {
+        let mut config = Config::new();
+        config.repositories = names
+            .iter()
+            .map(|name| {
+                let mut repo =
+                    Repository::new(name.to_string(), format!("git@github.com:org/{}.git", name));
+                repo.path = Some(root.join(name).to_string_lossy().to_string());
+                repo
+            })
+            .collect();
+        CommandContext {
+            config,
+            tag: vec![],
+            exclude_tag: vec![],
+            parallel: false,
+            repos: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_push_reports_pushed_up_to_date_and_rejected() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        git(root, &["init", "-q", "--bare", "origin.git"]);
+        git(root, &["clone", "-q", "origin.git", "api"]);
+        let api = root.join("api");
+        git(&api, &["checkout", "-q", "-b", "main"]);
+        git(&api, &["commit", "-q", "--allow-empty", "-m", "initial"]);
+
+        let command = PushCommand {
+            branch: None,
+            options: PushOptions {
+                set_upstream: true,
+                tags: true,
+                ..Default::default()
+            },
+        };
+        git(&api, &["tag", "v1.0.0"]);
+        let outcome = command
+            .push_repository(&context(root, &["api"]).config.repositories[0])
+            .unwrap();
+        let PushOutcome::Pushed(report) = outcome else {
+            panic!("expected the branch to be pushed");
+        };
+        assert_eq!(report.pushed, vec!["main", "v1.0.0"]);
+        assert_eq!(
+            git(&api, &["rev-parse", "--abbrev-ref", "main@{upstream}"]),
+            "origin/main"
+        );
+
+        command.execute(&context(root, &["api"])).await.unwrap();
+
+        // A commit pushed from elsewhere makes the next push non-fast-forward
+        git(root, &["clone", "-q", "origin.git", "other"]);
+        let other = root.join("other");
+        git(&other, &["checkout", "-q", "main"]);
+        git(
+            &other,
+            &["commit", "-q", "--allow-empty", "-m", "elsewhere"],
+        );
+        git(&other, &["push", "-q", "origin", "main"]);
+        git(&api, &["commit", "-q", "--allow-empty", "-m", "local"]);
+
+        let error = command.execute(&context(root, &["api"])).await.unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Pushes were rejected in 1 repositories: api"
+        );
+    }
+}
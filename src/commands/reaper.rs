@@ -0,0 +1,564 @@
+//! Reaper command implementation
+
+use super::{Command, CommandContext, GlobalOptions, validators};
+use crate::config::{Config, Repository};
+use crate::credentials::Credentials;
+use crate::{constants, logging};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use clap::Args;
+use colored::*;
+use log::Level;
+use repos_github::{BranchActivity, GitHubClient, PullRequestSummary, parse_github_url};
+
+/// Which open pull requests count as stale
+///
+/// Every criterion that is set must match; with none set, no pull request is closed.
+#[derive(Debug, Clone, Default)]
+pub struct StalePullRequestPolicy {
+    /// Closed when not updated for this many days
+    pub older_than_days: Option<u64>,
+    /// Closed only when carrying all of these labels
+    pub labels: Vec<String>,
+    /// Closed only when opened by a bot account
+    pub bots_only: bool,
+}
+
+impl StalePullRequestPolicy {
+    pub fn is_enabled(&self) -> bool {
+        self.older_than_days.is_some() || !self.labels.is_empty() || self.bots_only
+    }
+
+    /// Why the pull request is stale, or `None` when it should be kept
+    fn stale_reason(&self, pr: &PullRequestSummary, now: DateTime<Utc>) -> Option<String> {
+        if !self.is_enabled() {
+            return None;
+        }
+
+        let mut reasons = Vec::new();
+        if let Some(days) = self.older_than_days {
+            let idle = days_since(&pr.updated_at, now)?;
+            if idle < days {
+                return None;
+            }
+            reasons.push(format!("no activity for {} days", idle));
+        }
+        if !self.labels.is_empty() {
+            if !self.labels.iter().all(|label| pr.has_label(label)) {
+                return None;
+            }
+            reasons.push(format!("labelled {}", self.labels.join(", ")));
+        }
+        if self.bots_only {
+            if !pr.is_from_bot() {
+                return None;
+            }
+            reasons.push("opened by a bot".to_string());
+        }
+
+        Some(reasons.join(", "))
+    }
+}
+
+/// Closes stale pull requests and deletes stale branches across repositories
+///
+/// Nothing is changed unless `apply` is set, so every run starts as a dry run.
+/// The default branch, protected branches, branches with open pull requests and
+/// branches matching the `keep` allowlist are never touched.
+pub struct ReaperCommand {
+    pub token: String,
+    pub pull_requests: StalePullRequestPolicy,
+    /// Delete branches without commits for this many days
+    pub branches_older_than_days: Option<u64>,
+    /// Glob patterns of branches to never close or delete
+    pub keep: Vec<String>,
+    /// Comment posted on pull requests before closing them
+    pub comment: Option<String>,
+    pub apply: bool,
+}
+
+/// Changes the reaper would make in one repository
+#[derive(Debug, Default)]
+struct ReapPlan {
+    pull_requests: Vec<(PullRequestSummary, String)>,
+    branches: Vec<(String, String)>,
+}
+
+impl ReaperCommand {
+    fn is_kept(&self, keep: &[glob::Pattern], branch: &str) -> bool {
+        keep.iter().any(|pattern| pattern.matches(branch))
+    }
+
+    fn plan(
+        &self,
+        keep: &[glob::Pattern],
+        pull_requests: Vec<PullRequestSummary>,
+        branches: &[BranchActivity],
+        now: DateTime<Utc>,
+    ) -> ReapPlan {
+        let mut plan = ReapPlan::default();
+
+        // Base branches of open pull requests are in use even without recent commits
+        let bases: Vec<String> = pull_requests
+            .iter()
+            .filter_map(|pr| pr.base.as_ref().map(|base| base.branch.clone()))
+            .collect();
+
+        if let Some(days) = self.branches_older_than_days {
+            for branch in branches {
+                if branch.is_default
+                    || branch.protected
+                    || branch.open_pull_requests > 0
+                    || bases.contains(&branch.name)
+                    || self.is_kept(keep, &branch.name)
+                {
+                    continue;
+                }
+                if let Some(idle) = days_since(&branch.last_commit_at, now)
+                    && idle >= days
+                {
+                    plan.branches
+                        .push((branch.name.clone(), format!("no commits for {} days", idle)));
+                }
+            }
+        }
+
+        for pr in pull_requests {
+            if self.is_kept(keep, &pr.head.branch) {
+                continue;
+            }
+            if let Some(reason) = self.pull_requests.stale_reason(&pr, now) {
+                plan.pull_requests.push((pr, reason));
+            }
+        }
+
+        plan
+    }
+
+    async fn reap_repository(
+        &self,
+        client: &GitHubClient,
+        repo: &Repository,
+        keep: &[glob::Pattern],
+        now: DateTime<Utc>,
+    ) -> Result<(usize, usize)> {
+        let (owner, repo_name) = parse_github_url(&repo.url)?;
+        let pull_requests = client.list_open_pull_requests(&owner, &repo_name).await?;
+        let branches = if self.branches_older_than_days.is_some() {
+            client.list_branch_activity(&owner, &repo_name).await?
+        } else {
+            Vec::new()
+        };
+
+        let plan = self.plan(keep, pull_requests, &branches, now);
+
+        for (pr, reason) in &plan.pull_requests {
+            if self.apply {
+                if let Some(comment) = &self.comment {
+                    client
+                        .create_issue_comment(&owner, &repo_name, pr.number, comment)
+                        .await?;
+                }
+                client
+                    .close_pull_request(&owner, &repo_name, pr.number)
+                    .await?;
+            }
+            logging::status(
+                Level::Info,
+                &repo.name,
+                format!(
+                    "{} {}",
+                    format!(
+                        "{} #{} {} ({})",
+                        if self.apply { "Closed" } else { "Would close" },
+                        pr.number,
+                        pr.title,
+                        reason
+                    )
+                    .yellow(),
+                    pr.html_url
+                ),
+            );
+        }
+
+        for (branch, reason) in &plan.branches {
+            if self.apply {
+                client.delete_branch(&owner, &repo_name, branch).await?;
+            }
+            logging::status(
+                Level::Info,
+                &repo.name,
+                format!(
+                    "{} branch {} ({})",
+                    if self.apply {
+                        "Deleted"
+                    } else {
+                        "Would delete"
+                    },
+                    branch,
+                    reason
+                )
+                .yellow(),
+            );
+        }
+
+        Ok((plan.pull_requests.len(), plan.branches.len()))
+    }
+}
+
+#[async_trait]
+impl Command for ReaperCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.config.filter_repositories(
+            &context.tag,
+            &context.exclude_tag,
+            context.repos.as_deref(),
+        );
+
+        if repositories.is_empty() {
+            logging::message(Level::Info, "No repositories found".yellow());
+            return Ok(());
+        }
+
+        let keep = self
+            .keep
+            .iter()
+            .map(|pattern| {
+                glob::Pattern::new(pattern)
+                    .with_context(|| format!("Invalid --keep pattern '{}'", pattern))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let client = GitHubClient::new(Some(self.token.clone()));
+        let now = Utc::now();
+        let mut closed = 0;
+        let mut deleted = 0;
+        let mut errors = Vec::new();
+
+        // Repositories are processed one at a time to stay clear of secondary rate limits
+        for repo in &repositories {
+            match self.reap_repository(&client, repo, &keep, now).await {
+                Ok((prs, branches)) => {
+                    closed += prs;
+                    deleted += branches;
+                }
+                Err(e) => {
+                    logging::status(Level::Error, &repo.name, format!("Error: {e}").red());
+                    errors.push((repo.name.clone(), e));
+                }
+            }
+        }
+
+        if self.apply {
+            logging::message(
+                Level::Info,
+                format!(
+                    "Closed {} pull request(s) and deleted {} branch(es)",
+                    closed, deleted
+                )
+                .green(),
+            );
+        } else {
+            logging::message(Level::Info, format!(
+                    "Would close {} pull request(s) and delete {} branch(es). Re-run with --apply to make these changes.",
+                    closed, deleted
+                )
+                .green());
+        }
+
+        if let Some((repo_name, e)) = errors.first() {
+            anyhow::bail!(
+                "{} repository operation(s) failed. First error ({}): {}",
+                errors.len(),
+                repo_name,
+                e
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Whole days between an RFC 3339 timestamp and `now`, or `None` if it cannot be parsed
+fn days_since(timestamp: &str, now: DateTime<Utc>) -> Option<u64> {
+    let then = DateTime::parse_from_rfc3339(timestamp).ok()?;
+    let elapsed = now.signed_duration_since(then);
+    Some(elapsed.max(Duration::zero()).num_days() as u64)
+}
+
+/// Arguments of `repos reaper`
+#[derive(Debug, Args)]
+pub struct ReaperArgs {
+    /// Specific repository names to clean up (if not provided, uses tag filter or all repos)
+    pub repos: Vec<String>,
+
+    /// Close open PRs without activity for this many days
+    #[arg(long, value_name = "DAYS")]
+    pub stale_prs_days: Option<u64>,
+
+    /// Only close PRs with this label (can be specified multiple times)
+    #[arg(long)]
+    pub label: Vec<String>,
+
+    /// Only close PRs opened by bots such as Dependabot
+    #[arg(long)]
+    pub bot: bool,
+
+    /// Delete branches without commits for this many days
+    #[arg(long, value_name = "DAYS")]
+    pub stale_branches_days: Option<u64>,
+
+    /// Never close or delete branches matching this glob (can be specified multiple times)
+    #[arg(long, value_name = "GLOB")]
+    pub keep: Vec<String>,
+
+    /// Comment to post on PRs before closing them
+    #[arg(long)]
+    pub comment: Option<String>,
+
+    /// Close PRs and delete branches instead of only listing them
+    #[arg(long)]
+    pub apply: bool,
+
+    /// GitHub token
+    #[arg(long)]
+    pub token: Option<String>,
+
+    /// Configuration file path (can be specified multiple times to combine fleets)
+    #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+    pub config: Vec<String>,
+
+    /// Filter repositories by tag (can be specified multiple times)
+    #[arg(short, long)]
+    pub tag: Vec<String>,
+
+    /// Exclude repositories with these tags (can be specified multiple times)
+    #[arg(short = 'e', long)]
+    pub exclude_tag: Vec<String>,
+}
+
+impl ReaperArgs {
+    /// Validate the arguments and run `repos reaper`
+    pub async fn run(self, globals: &GlobalOptions) -> Result<()> {
+        let Self {
+            repos,
+            stale_prs_days,
+            label,
+            bot,
+            stale_branches_days,
+            keep,
+            comment,
+            apply,
+            token,
+            config,
+            tag,
+            exclude_tag,
+        } = self;
+        let config = Config::load_all(&config)?;
+
+        validators::validate_tag_filters(&tag)?;
+        validators::validate_tag_filters(&exclude_tag)?;
+        validators::validate_repository_names(&repos)?;
+
+        let mut context = globals.context(config, tag, exclude_tag, repos);
+        context.skip_archived(globals.include_archived);
+
+        let token = Credentials::load(token.or_else(|| context.config.resolve_profile_token()))
+            .default_token()
+            .ok_or_else(|| anyhow::anyhow!("GitHub token not provided. Use --token flag, set GITHUB_TOKEN environment variable, or save one with `repos auth login`."))?;
+
+        ReaperCommand {
+            token,
+            pull_requests: StalePullRequestPolicy {
+                older_than_days: stale_prs_days,
+                labels: label,
+                bots_only: bot,
+            },
+            branches_older_than_days: stale_branches_days,
+            keep,
+            comment,
+            apply,
+        }
+        .execute(&context)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2024-06-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn pull_request(
+        branch: &str,
+        author: &str,
+        updated_at: &str,
+        labels: &[&str],
+    ) -> PullRequestSummary {
+        serde_json::from_value(serde_json::json!({
+            "number": 7,
+            "html_url": "https://github.com/org/api/pull/7",
+            "title": "Bump serde",
+            "user": { "login": author },
+            "head": { "ref": branch, "sha": "abc123" },
+            "base": { "ref": "develop", "sha": "def456" },
+            "labels": labels.iter().map(|name| serde_json::json!({ "name": name })).collect::<Vec<_>>(),
+            "updated_at": updated_at
+        }))
+        .unwrap()
+    }
+
+    fn branch(name: &str, last_commit_at: &str) -> BranchActivity {
+        BranchActivity {
+            name: name.to_string(),
+            last_commit_at: last_commit_at.to_string(),
+            is_default: false,
+            protected: false,
+            open_pull_requests: 0,
+        }
+    }
+
+    fn command(pull_requests: StalePullRequestPolicy, branch_days: Option<u64>) -> ReaperCommand {
+        ReaperCommand {
+            token: "test_token".to_string(),
+            pull_requests,
+            branches_older_than_days: branch_days,
+            keep: vec![],
+            comment: None,
+            apply: false,
+        }
+    }
+
+    #[test]
+    fn test_days_since() {
+        assert_eq!(days_since("2024-05-01T00:00:00Z", now()), Some(31));
+        assert_eq!(days_since("2024-07-01T00:00:00Z", now()), Some(0));
+        assert_eq!(days_since("not a date", now()), None);
+    }
+
+    #[test]
+    fn test_stale_pull_request_policy() {
+        let old_bot = pull_request(
+            "deps/serde",
+            "dependabot[bot]",
+            "2024-01-01T00:00:00Z",
+            &["dependencies"],
+        );
+        let recent_bot = pull_request("deps/serde", "dependabot[bot]", "2024-05-30T00:00:00Z", &[]);
+        let old_human = pull_request("feature/x", "alice", "2024-01-01T00:00:00Z", &[]);
+
+        let by_age = StalePullRequestPolicy {
+            older_than_days: Some(30),
+            ..Default::default()
+        };
+        assert!(by_age.stale_reason(&old_bot, now()).is_some());
+        assert!(by_age.stale_reason(&recent_bot, now()).is_none());
+        assert!(by_age.stale_reason(&old_human, now()).is_some());
+
+        let old_bots = StalePullRequestPolicy {
+            older_than_days: Some(30),
+            bots_only: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            old_bots.stale_reason(&old_bot, now()).as_deref(),
+            Some("no activity for 152 days, opened by a bot")
+        );
+        assert!(old_bots.stale_reason(&old_human, now()).is_none());
+
+        let by_label = StalePullRequestPolicy {
+            labels: vec!["dependencies".to_string()],
+            ..Default::default()
+        };
+        assert!(by_label.stale_reason(&old_bot, now()).is_some());
+        assert!(by_label.stale_reason(&old_human, now()).is_none());
+
+        // Without any criteria nothing is stale
+        assert!(
+            StalePullRequestPolicy::default()
+                .stale_reason(&old_human, now())
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_plan_keeps_branches_in_use() {
+        let reaper = command(StalePullRequestPolicy::default(), Some(90));
+        let keep = vec![glob::Pattern::new("release/*").unwrap()];
+
+        let mut default = branch("main", "2020-01-01T00:00:00Z");
+        default.is_default = true;
+        let mut protected = branch("stable", "2020-01-01T00:00:00Z");
+        protected.protected = true;
+        let mut with_pr = branch("feature/open", "2020-01-01T00:00:00Z");
+        with_pr.open_pull_requests = 1;
+        let branches = vec![
+            default,
+            protected,
+            with_pr,
+            branch("develop", "2020-01-01T00:00:00Z"),
+            branch("release/1.0", "2020-01-01T00:00:00Z"),
+            branch("feature/recent", "2024-05-20T00:00:00Z"),
+            branch("feature/old", "2024-01-01T00:00:00Z"),
+        ];
+        let pull_requests = vec![pull_request(
+            "feature/open",
+            "alice",
+            "2024-05-30T00:00:00Z",
+            &[],
+        )];
+
+        let plan = reaper.plan(&keep, pull_requests, &branches, now());
+        assert!(plan.pull_requests.is_empty());
+        assert_eq!(
+            plan.branches,
+            vec![(
+                "feature/old".to_string(),
+                "no commits for 152 days".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_plan_skips_allowlisted_pull_requests() {
+        let reaper = command(
+            StalePullRequestPolicy {
+                older_than_days: Some(30),
+                ..Default::default()
+            },
+            None,
+        );
+        let keep = vec![glob::Pattern::new("release/*").unwrap()];
+        let pull_requests = vec![
+            pull_request("release/1.0", "alice", "2024-01-01T00:00:00Z", &[]),
+            pull_request("feature/x", "alice", "2024-01-01T00:00:00Z", &[]),
+        ];
+
+        let plan = reaper.plan(&keep, pull_requests, &[], now());
+        assert_eq!(plan.pull_requests.len(), 1);
+        assert_eq!(plan.pull_requests[0].0.head.branch, "feature/x");
+    }
+
+    #[tokio::test]
+    async fn test_reaper_without_repositories() {
+        let context = CommandContext {
+            config: Config::new(),
+            tag: vec![],
+            exclude_tag: vec![],
+            repos: None,
+            parallel: false,
+        };
+
+        let result = command(StalePullRequestPolicy::default(), Some(90))
+            .execute(&context)
+            .await;
+        assert!(result.is_ok());
+    }
+}
@@ -0,0 +1,405 @@
+//! Rebase command implementation
+
+use super::{Command, CommandContext, GlobalOptions, fetch, validators};
+use crate::config::{Config, Repository};
+use crate::constants::git::FALLBACK_BRANCH;
+use crate::git::{self, RebaseOutcome};
+use crate::logging;
+use crate::utils::interactive;
+use crate::{constants, events};
+use anyhow::Result;
+use async_trait::async_trait;
+use clap::Args;
+use colored::*;
+use log::Level;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Rebase command for bringing a branch up to date with the default branch in every repository
+#[derive(Debug, Clone)]
+pub struct RebaseCommand {
+    /// Branch to rebase; the checked-out branch of each repository when `None`
+    pub branch: Option<String>,
+    /// Base branch on `origin`; the repository's default branch when `None`
+    pub onto: Option<String>,
+    /// Force-push (with lease) branches that were rebased
+    pub push: bool,
+    /// Offer to restart conflicting rebases for manual resolution after the run
+    pub resolve: bool,
+}
+
+/// A repository whose rebase stopped on conflicts and was aborted
+#[derive(Debug, Clone)]
+struct Conflict {
+    repo: Repository,
+    branch: String,
+    upstream: String,
+    files: Vec<String>,
+}
+
+/// What happened in one repository
+#[derive(Debug)]
+enum RepoResult {
+    UpToDate,
+    Rebased { pushed: bool },
+    Conflicts(Box<Conflict>),
+}
+
+impl RebaseCommand {
+    /// Base branch for a repository: `--onto`, then the config, then `origin/HEAD`
+    fn base_branch(&self, repo: &Repository, repo_path: &str) -> String {
+        self.onto
+            .clone()
+            .or_else(|| repo.default_branch.clone())
+            .or_else(|| git::get_remote_default_branch(repo_path))
+            .unwrap_or_else(|| FALLBACK_BRANCH.to_string())
+    }
+
+    /// Rebase one repository; `fetched` skips fetching when it was already done
+    fn rebase_repository(&self, repo: &Repository, fetched: bool) -> Result<RepoResult> {
+        let repo_path = repo.get_target_dir();
+        if !Path::new(&repo_path).exists() {
+            anyhow::bail!("Repository directory does not exist: {}", repo_path);
+        }
+        if git::has_changes(&repo_path)? {
+            anyhow::bail!("Uncommitted changes; commit or stash them before rebasing");
+        }
+
+        let current = git::get_current_branch(&repo_path)?;
+        let branch = self.branch.clone().unwrap_or_else(|| current.clone());
+        let base = self.base_branch(repo, &repo_path);
+        if branch == base {
+            anyhow::bail!("Branch '{}' is the base branch; nothing to rebase", branch);
+        }
+
+        if !fetched {
+            git::fetch(&repo_path)?;
+        }
+        if branch != current {
+            git::checkout_branch(&repo_path, &branch)?;
+        }
+
+        let upstream = format!("origin/{}", base);
+        let result = self.rebase_checked_out(repo, &repo_path, &branch, &upstream);
+
+        // Named branches are rebased in passing; leave the working copy where it was
+        if branch != current
+            && let Err(e) = git::checkout_branch(&repo_path, &current)
+        {
+            logging::status(
+                Level::Warn,
+                &repo.name,
+                format!(
+                    "Warning: Failed to restore original branch '{}': {}",
+                    current, e
+                )
+                .yellow(),
+            );
+        }
+
+        result
+    }
+
+    fn rebase_checked_out(
+        &self,
+        repo: &Repository,
+        repo_path: &str,
+        branch: &str,
+        upstream: &str,
+    ) -> Result<RepoResult> {
+        match git::rebase(repo_path, upstream)? {
+            RebaseOutcome::UpToDate => Ok(RepoResult::UpToDate),
+            RebaseOutcome::Rebased => {
+                if self.push {
+                    let access = git::RemoteAccess::for_repository(repo, None);
+                    git::force_push_with_lease(repo_path, branch, &access)?;
+                }
+                Ok(RepoResult::Rebased { pushed: self.push })
+            }
+            RebaseOutcome::Conflicts(files) => {
+                git::abort_rebase(repo_path)?;
+                Ok(RepoResult::Conflicts(Box::new(Conflict {
+                    repo: repo.clone(),
+                    branch: branch.to_string(),
+                    upstream: upstream.to_string(),
+                    files,
+                })))
+            }
+        }
+    }
+
+    /// Restart the rebase of each chosen repository and leave it stopped on its conflicts
+    fn hand_over(conflicts: &[Conflict]) -> Result<usize> {
+        let repos: Vec<Repository> = conflicts.iter().map(|c| c.repo.clone()).collect();
+        let selected = interactive::select_repositories_with_prompt(
+            &repos,
+            "Select repositories to resolve now (the rebase is left in progress)",
+        )?;
+
+        for repo in &selected {
+            let conflict = conflicts
+                .iter()
+                .find(|c| c.repo.name == repo.name)
+                .expect("selected repository has a conflict");
+            let repo_path = repo.get_target_dir();
+            git::checkout_branch(&repo_path, &conflict.branch)?;
+            git::rebase(&repo_path, &conflict.upstream)?;
+            logging::status(
+                Level::Info,
+                &repo.name,
+                format!(
+                    "Rebase in progress in {}; resolve the conflicts, then run `git rebase --continue`",
+                    repo_path
+                )
+                .yellow(),
+            );
+        }
+
+        Ok(selected.len())
+    }
+}
+
+#[async_trait]
+impl Command for RebaseCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.config.filter_repositories(
+            &context.tag,
+            &context.exclude_tag,
+            context.repos.as_deref(),
+        );
+
+        if repositories.is_empty() {
+            logging::message(Level::Info, "No repositories found".yellow());
+            return Ok(());
+        }
+
+        logging::message(
+            Level::Info,
+            format!("Rebasing {} repositories...", repositories.len()).green(),
+        );
+
+        let mut results: Vec<(Repository, Result<RepoResult>)> = Vec::new();
+        if context.parallel {
+            let tasks: Vec<_> = repositories
+                .into_iter()
+                .map(|repo| {
+                    let command = self.clone();
+                    tokio::task::spawn_blocking(move || {
+                        let result =
+                            events::track(&repo.name, || command.rebase_repository(&repo, false));
+                        (repo, result)
+                    })
+                })
+                .collect();
+
+            for task in tasks {
+                results.push(task.await?);
+            }
+        } else {
+            // Fetch everything up front so only the rebases themselves run one at a time
+            let fetched: HashSet<String> = fetch::prefetch(&repositories)
+                .await?
+                .into_iter()
+                .filter(|(_, result)| result.is_ok())
+                .map(|(repo, _)| repo.name)
+                .collect();
+
+            for repo in repositories {
+                let result = events::track(&repo.name, || {
+                    self.rebase_repository(&repo, fetched.contains(&repo.name))
+                });
+                results.push((repo, result));
+            }
+        }
+
+        let mut rebased = 0;
+        let mut conflicts = Vec::new();
+        let mut errors = Vec::new();
+        for (repo, result) in results {
+            match result {
+                Ok(RepoResult::UpToDate) => {
+                    logging::status(Level::Info, &repo.name, "Already up to date".green())
+                }
+                Ok(RepoResult::Rebased { pushed }) => {
+                    let message = if pushed {
+                        "Rebased and pushed"
+                    } else {
+                        "Rebased"
+                    };
+                    logging::status(Level::Info, &repo.name, message.green());
+                    rebased += 1;
+                }
+                Ok(RepoResult::Conflicts(conflict)) => {
+                    logging::status(
+                        Level::Warn,
+                        &repo.name,
+                        format!(
+                            "Conflicts rebasing '{}' onto {} (aborted): {}",
+                            conflict.branch,
+                            conflict.upstream,
+                            conflict.files.join(", ")
+                        )
+                        .yellow(),
+                    );
+                    conflicts.push(*conflict);
+                }
+                Err(e) => {
+                    logging::status(Level::Error, &repo.name, format!("Error: {e}").red());
+                    errors.push((repo.name, e));
+                }
+            }
+        }
+
+        logging::message(
+            Level::Info,
+            format!(
+                "Rebased {}, conflicts {}, failed {}",
+                rebased,
+                conflicts.len(),
+                errors.len()
+            )
+            .bold(),
+        );
+
+        let mut unresolved = conflicts.len();
+        if !conflicts.is_empty() {
+            if self.resolve {
+                unresolved -= Self::hand_over(&conflicts)?;
+            } else {
+                logging::message(
+                    Level::Info,
+                    "Re-run with --resolve to pick conflicting repositories to resolve by hand"
+                        .yellow(),
+                );
+            }
+        }
+
+        if let Some((repo_name, e)) = errors.first() {
+            anyhow::bail!(
+                "Failed to rebase {} repositories. First error ({}): {}",
+                errors.len(),
+                repo_name,
+                e
+            );
+        }
+        if unresolved > 0 {
+            anyhow::bail!("{} repositories have rebase conflicts", unresolved);
+        }
+
+        Ok(())
+    }
+}
+
+/// Arguments of `repos rebase`
+#[derive(Debug, Args)]
+pub struct RebaseArgs {
+    /// Specific repository names to rebase (if not provided, uses tag filter or all repos)
+    pub repos: Vec<String>,
+
+    /// Branch to rebase (defaults to the checked-out branch of each repository)
+    #[arg(short, long)]
+    pub branch: Option<String>,
+
+    /// Base branch on origin (defaults to each repository's default branch)
+    #[arg(long)]
+    pub onto: Option<String>,
+
+    /// Force-push rebased branches with --force-with-lease
+    #[arg(long)]
+    pub push: bool,
+
+    /// Pick repositories with conflicts to leave mid-rebase for manual resolution
+    #[arg(long)]
+    pub resolve: bool,
+
+    /// Configuration file path (can be specified multiple times to combine fleets)
+    #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+    pub config: Vec<String>,
+
+    /// Filter repositories by tag (can be specified multiple times)
+    #[arg(short, long)]
+    pub tag: Vec<String>,
+
+    /// Exclude repositories with these tags (can be specified multiple times)
+    #[arg(short = 'e', long)]
+    pub exclude_tag: Vec<String>,
+
+    /// Execute operations in parallel
+    #[arg(short, long)]
+    pub parallel: bool,
+}
+
+impl RebaseArgs {
+    /// Validate the arguments and run `repos rebase`
+    pub async fn run(self, globals: &GlobalOptions) -> Result<()> {
+        let Self {
+            repos,
+            branch,
+            onto,
+            push,
+            resolve,
+            config,
+            tag,
+            exclude_tag,
+            parallel,
+        } = self;
+        let config = Config::load_all(&config)?;
+
+        validators::validate_tag_filters(&tag)?;
+        validators::validate_tag_filters(&exclude_tag)?;
+        validators::validate_repository_names(&repos)?;
+
+        let mut context = globals
+            .context(config, tag, exclude_tag, repos)
+            .with_parallel(parallel);
+        context.skip_archived(globals.include_archived);
+        RebaseCommand {
+            branch,
+            onto,
+            push,
+            resolve,
+        }
+        .execute(&context)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(onto: Option<&str>) -> RebaseCommand {
+        RebaseCommand {
+            branch: None,
+            onto: onto.map(str::to_string),
+            push: false,
+            resolve: false,
+        }
+    }
+
+    #[test]
+    fn test_base_branch_precedence() {
+        let mut repo = Repository::new("api".to_string(), "git@github.com:org/api.git".to_string());
+
+        // Not a clone, so origin/HEAD is unknown
+        assert_eq!(
+            command(None).base_branch(&repo, "/nonexistent"),
+            FALLBACK_BRANCH
+        );
+
+        repo.default_branch = Some("develop".to_string());
+        assert_eq!(command(None).base_branch(&repo, "/nonexistent"), "develop");
+        assert_eq!(
+            command(Some("release")).base_branch(&repo, "/nonexistent"),
+            "release"
+        );
+    }
+
+    #[test]
+    fn test_missing_repository() {
+        let mut repo = Repository::new("api".to_string(), "git@github.com:org/api.git".to_string());
+        repo.path = Some("/nonexistent/repos/api".to_string());
+
+        assert!(command(None).rebase_repository(&repo, false).is_err());
+    }
+}
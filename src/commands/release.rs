@@ -0,0 +1,425 @@
+//! Release command implementation
+
+use super::{Command, CommandContext, GlobalOptions, validators};
+use crate::config::{Config, Repository};
+use crate::credentials::Credentials;
+use crate::git::{self, Bump, RemoteAccess, Version};
+use crate::github::placeholders::Placeholders;
+use crate::logging;
+use crate::{constants, events};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use clap::Args;
+use colored::*;
+use log::Level;
+use repos_github::{GitHubClient, NewRelease, parse_github_url};
+use std::path::{Path, PathBuf};
+
+/// Release notes used without `--notes`
+pub const DEFAULT_NOTES_TEMPLATE: &str = "## Changes since {{previous_tag}}\n\n{{changes}}\n";
+
+/// Version to release
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReleaseVersion {
+    /// The same version in every repository
+    Exact(Version),
+    /// The next version after each repository's latest version tag
+    Bump(Bump),
+}
+
+/// Release planned for one repository
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReleasePlan {
+    pub previous_tag: Option<String>,
+    pub tag: String,
+    pub version: Version,
+    /// Subjects of the commits since the previous tag, newest first
+    pub changes: Vec<String>,
+    pub notes: String,
+}
+
+/// Release command tagging every repository and publishing a GitHub Release for the tag
+pub struct ReleaseCommand {
+    pub version: ReleaseVersion,
+    /// Put before the version in tag names, e.g. `v`
+    pub prefix: String,
+    /// Release notes with `{{version}}`, `{{tag}}`, `{{previous_tag}}`, `{{changes}}`
+    /// and the repository placeholders of file templates
+    pub notes_template: String,
+    pub draft: bool,
+    pub prerelease: bool,
+    /// Show the planned tags and notes without creating anything
+    pub dry_run: bool,
+    pub token: Option<String>,
+}
+
+impl ReleaseCommand {
+    /// Work out the next tag of a repository, or `None` when nothing changed since the last one
+    fn plan(&self, credentials: &Credentials, repo: &Repository) -> Result<Option<ReleasePlan>> {
+        if !self.dry_run
+            && repo.provider().is_github()
+            && credentials.token_for_repository(repo).is_none()
+        {
+            anyhow::bail!(
+                "GitHub token is required to publish the release. Set GITHUB_TOKEN environment variable."
+            );
+        }
+        let target_dir = repo.get_target_dir();
+        git::fetch_tags(&target_dir)?;
+
+        let latest = git::latest_version_tag(&target_dir, &self.prefix)?;
+        let previous_tag = latest.as_ref().map(|(tag, _)| tag.clone());
+        let changes = git::commits_since(&target_dir, previous_tag.as_deref())?;
+        if changes.is_empty() {
+            return Ok(None);
+        }
+
+        let version = match (self.version, &latest) {
+            (ReleaseVersion::Exact(version), Some((tag, previous))) if version <= *previous => {
+                anyhow::bail!("Version {} is not newer than {}", version, tag)
+            }
+            (ReleaseVersion::Exact(version), _) => version,
+            (ReleaseVersion::Bump(bump), Some((_, previous))) => previous.bump(bump),
+            (ReleaseVersion::Bump(bump), None) => Version::default().bump(bump),
+        };
+        let tag = format!("{}{}", self.prefix, version);
+        if git::tag_exists(&target_dir, &tag)? {
+            anyhow::bail!("Tag {} already exists", tag);
+        }
+
+        let notes = render_notes(
+            &self.notes_template,
+            repo,
+            &tag,
+            &version,
+            previous_tag.as_deref(),
+            &changes,
+        );
+        Ok(Some(ReleasePlan {
+            previous_tag,
+            tag,
+            version,
+            changes,
+            notes,
+        }))
+    }
+
+    /// Create and push the tag, then publish the release on GitHub
+    async fn release(
+        &self,
+        credentials: &Credentials,
+        repo: &Repository,
+        plan: &ReleasePlan,
+    ) -> Result<Option<String>> {
+        let target_dir = repo.get_target_dir();
+        git::create_tag(&target_dir, &plan.tag, &format!("Release {}", plan.tag))?;
+        let access = RemoteAccess::for_repository(repo, self.token.clone());
+        if let Err(e) = git::push_tag(&target_dir, &plan.tag, &access) {
+            // Leave the clone as it was so the release can simply be retried
+            git::delete_tag(&target_dir, &plan.tag)?;
+            return Err(e);
+        }
+
+        if !repo.provider().is_github() {
+            return Ok(None);
+        }
+        let (owner, name) = parse_github_url(&repo.url)?;
+        let client = GitHubClient::new(credentials.token_for_repository(repo));
+        let release = client
+            .create_release(
+                &owner,
+                &name,
+                &NewRelease {
+                    tag_name: plan.tag.clone(),
+                    name: plan.tag.clone(),
+                    body: plan.notes.clone(),
+                    draft: self.draft,
+                    prerelease: self.prerelease,
+                },
+            )
+            .await
+            .map_err(|e| anyhow::anyhow!("Pushed tag {}, but {:#}", plan.tag, e))?;
+        Ok(Some(release.html_url))
+    }
+}
+
+#[async_trait]
+impl Command for ReleaseCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.config.filter_repositories(
+            &context.tag,
+            &context.exclude_tag,
+            context.repos.as_deref(),
+        );
+
+        if repositories.is_empty() {
+            logging::message(Level::Info, "No repositories found".yellow());
+            return Ok(());
+        }
+
+        // Plan every release before creating any, so one bad repository stops them all
+        let credentials = Credentials::load(self.token.clone());
+        let mut plans = Vec::new();
+        let mut errors = Vec::new();
+        for repo in repositories {
+            if !Path::new(&repo.get_target_dir()).exists() {
+                logging::status(
+                    Level::Warn,
+                    &repo.name,
+                    "Skipping: repository is not cloned".yellow(),
+                );
+                continue;
+            }
+            match events::track(&repo.name, || self.plan(&credentials, &repo)) {
+                Ok(Some(plan)) => {
+                    logging::status(
+                        Level::Info,
+                        &repo.name,
+                        format!(
+                            "{} → {} ({} commits)",
+                            plan.previous_tag.as_deref().unwrap_or("no release"),
+                            plan.tag.green(),
+                            plan.changes.len()
+                        ),
+                    );
+                    if self.dry_run {
+                        for line in plan.notes.lines() {
+                            println!("    {}", line.dimmed());
+                        }
+                    }
+                    plans.push((repo, plan));
+                }
+                Ok(None) => logging::status(
+                    Level::Info,
+                    &repo.name,
+                    "Nothing to release since the last tag".dimmed(),
+                ),
+                Err(e) => {
+                    logging::status(Level::Error, &repo.name, format!("Error: {e:#}").red());
+                    errors.push(repo.name);
+                }
+            }
+        }
+
+        if !errors.is_empty() {
+            anyhow::bail!(
+                "Could not plan releases for {} repositories; nothing was released",
+                errors.len()
+            );
+        }
+        if self.dry_run {
+            logging::message(
+                Level::Info,
+                format!(
+                    "Dry run: {} releases planned, nothing was tagged or published",
+                    plans.len()
+                )
+                .yellow(),
+            );
+            return Ok(());
+        }
+
+        let mut released = 0;
+        for (repo, plan) in &plans {
+            match self.release(&credentials, repo, plan).await {
+                Ok(Some(url)) => {
+                    logging::status(
+                        Level::Info,
+                        &repo.name,
+                        format!("Released {}: {}", plan.tag, url).green(),
+                    );
+                    released += 1;
+                }
+                Ok(None) => {
+                    logging::status(
+                        Level::Info,
+                        &repo.name,
+                        format!(
+                            "Tagged {} (releases are only published on GitHub)",
+                            plan.tag
+                        )
+                        .green(),
+                    );
+                    released += 1;
+                }
+                Err(e) => {
+                    logging::status(Level::Error, &repo.name, format!("Error: {e:#}").red());
+                    errors.push(repo.name.clone());
+                }
+            }
+        }
+
+        logging::message(
+            Level::Info,
+            format!("Released {} of {} repositories", released, plans.len()).bold(),
+        );
+        if !errors.is_empty() {
+            anyhow::bail!("Release failed in {} repositories", errors.len());
+        }
+        Ok(())
+    }
+}
+
+/// Fill in the release notes of one repository
+pub fn render_notes(
+    template: &str,
+    repo: &Repository,
+    tag: &str,
+    version: &Version,
+    previous_tag: Option<&str>,
+    changes: &[String],
+) -> String {
+    let changes: Vec<String> = changes
+        .iter()
+        .map(|subject| format!("- {}", subject))
+        .collect();
+    Placeholders::default()
+        .with_value("version", &version.to_string())
+        .with_value("tag", tag)
+        .with_value("previous_tag", previous_tag.unwrap_or("the first commit"))
+        .with_value("changes", &changes.join("\n"))
+        .fill(template, repo)
+}
+
+/// Arguments of `repos release`
+#[derive(Debug, Args)]
+pub struct ReleaseArgs {
+    /// Specific repository names to release (if not provided, uses tag filter or all repos)
+    pub repos: Vec<String>,
+
+    /// Version to release in every repository, e.g. 1.4.0
+    #[arg(long, required_unless_present = "bump", conflicts_with = "bump")]
+    pub version: Option<String>,
+
+    /// Increase each repository's latest version tag (major, minor or patch)
+    #[arg(long, value_name = "PART", value_parser = ["major", "minor", "patch"])]
+    pub bump: Option<String>,
+
+    /// Put before the version in tag names
+    #[arg(long, default_value = "v")]
+    pub prefix: String,
+
+    /// Release notes template file
+    #[arg(long, value_name = "FILE")]
+    pub notes: Option<PathBuf>,
+
+    /// Publish the releases as drafts
+    #[arg(long)]
+    pub draft: bool,
+
+    /// Mark the releases as prereleases
+    #[arg(long)]
+    pub prerelease: bool,
+
+    /// Show the planned tags and notes without creating anything
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// GitHub token
+    #[arg(long)]
+    pub token: Option<String>,
+
+    /// Configuration file path (can be specified multiple times to combine fleets)
+    #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+    pub config: Vec<String>,
+
+    /// Filter repositories by tag (can be specified multiple times)
+    #[arg(short, long)]
+    pub tag: Vec<String>,
+
+    /// Exclude repositories with these tags (can be specified multiple times)
+    #[arg(short = 'e', long)]
+    pub exclude_tag: Vec<String>,
+}
+
+impl ReleaseArgs {
+    /// Validate the arguments and run `repos release`
+    pub async fn run(self, globals: &GlobalOptions) -> Result<()> {
+        let Self {
+            repos,
+            version,
+            bump,
+            prefix,
+            notes,
+            draft,
+            prerelease,
+            dry_run,
+            token,
+            config,
+            tag,
+            exclude_tag,
+        } = self;
+        let config = Config::load_all(&config)?;
+
+        validators::validate_tag_filters(&tag)?;
+        validators::validate_tag_filters(&exclude_tag)?;
+        validators::validate_repository_names(&repos)?;
+
+        let version = match (version, bump) {
+            (Some(version), _) => {
+                ReleaseVersion::Exact(version.strip_prefix(&prefix).unwrap_or(&version).parse()?)
+            }
+            (None, Some(bump)) => ReleaseVersion::Bump(bump.parse()?),
+            (None, None) => unreachable!("clap requires --version or --bump"),
+        };
+        let notes_template = match notes {
+            Some(path) => std::fs::read_to_string(&path).with_context(|| {
+                format!("Failed to read release notes template {}", path.display())
+            })?,
+            None => DEFAULT_NOTES_TEMPLATE.to_string(),
+        };
+
+        let token = token.or_else(|| config.resolve_profile_token());
+        let mut context = globals.context(config, tag, exclude_tag, repos);
+        context.skip_archived(globals.include_archived);
+        ReleaseCommand {
+            version,
+            prefix,
+            notes_template,
+            draft,
+            prerelease,
+            dry_run,
+            token,
+        }
+        .execute(&context)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_notes() {
+        let repo = Repository::new(
+            "billing".to_string(),
+            "https://github.com/acme/billing.git".to_string(),
+        );
+        let changes = ["Add invoices".to_string(), "Fix rounding".to_string()];
+        let version: Version = "1.3.0".parse().unwrap();
+
+        assert_eq!(
+            render_notes(
+                DEFAULT_NOTES_TEMPLATE,
+                &repo,
+                "v1.3.0",
+                &version,
+                Some("v1.2.0"),
+                &changes
+            ),
+            "## Changes since v1.2.0\n\n- Add invoices\n- Fix rounding\n"
+        );
+        assert_eq!(
+            render_notes(
+                "{{owner}}/{{repo}} {{version}} ({{tag}}) after {{previous_tag}}",
+                &repo,
+                "v1.3.0",
+                &version,
+                None,
+                &changes
+            ),
+            "acme/billing 1.3.0 (v1.3.0) after the first commit"
+        );
+    }
+}
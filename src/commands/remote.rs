@@ -0,0 +1,240 @@
+//! Remote command implementation
+
+use super::{Command, CommandContext, GlobalOptions, validators};
+use crate::config::{Config, Repository};
+use crate::git::{self, RemoteAccess, RemoteChange};
+use crate::{constants, logging};
+use anyhow::Result;
+use async_trait::async_trait;
+use clap::Subcommand;
+use colored::*;
+use log::Level;
+use std::path::Path;
+
+/// Command bringing the remotes of cloned repositories in line with the config
+pub struct RemoteSyncCommand {
+    /// Only show the changes
+    pub dry_run: bool,
+    /// Also remove remotes the config does not declare
+    pub prune: bool,
+}
+
+impl RemoteSyncCommand {
+    fn sync_repository(&self, repo: &Repository) -> Result<Vec<RemoteChange>> {
+        let target_dir = repo.get_target_dir();
+        let actual = git::list_remotes(&target_dir)?;
+        let access = RemoteAccess::for_repository(repo, None);
+        let expected = git::expected_remotes(repo, &access);
+        let changes = git::remote_changes(&actual, &expected, self.prune);
+        if !self.dry_run {
+            for change in &changes {
+                git::apply_remote_change(&target_dir, change)?;
+            }
+        }
+        Ok(changes)
+    }
+}
+
+#[async_trait]
+impl Command for RemoteSyncCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.config.filter_repositories(
+            &context.tag,
+            &context.exclude_tag,
+            context.repos.as_deref(),
+        );
+
+        if repositories.is_empty() {
+            logging::message(Level::Info, "No repositories found".yellow());
+            return Ok(());
+        }
+
+        let mut changed = 0;
+        let mut errors = Vec::new();
+        for repo in &repositories {
+            if !Path::new(&repo.get_target_dir()).exists() {
+                logging::status(
+                    Level::Warn,
+                    &repo.name,
+                    "Skipping: repository is not cloned".yellow(),
+                );
+                continue;
+            }
+
+            match self.sync_repository(repo) {
+                Ok(changes) if changes.is_empty() => {
+                    logging::status(Level::Info, &repo.name, "Remotes up to date".dimmed());
+                }
+                Ok(changes) => {
+                    changed += 1;
+                    for change in changes {
+                        logging::status(Level::Info, &repo.name, change.to_string().green());
+                    }
+                }
+                Err(e) => {
+                    logging::status(Level::Error, &repo.name, format!("Error: {e}").red());
+                    errors.push((repo.name.clone(), e));
+                }
+            }
+        }
+
+        if let Some((repo_name, e)) = errors.first() {
+            anyhow::bail!(
+                "Failed to sync remotes of {} repositories. First error ({}): {}",
+                errors.len(),
+                repo_name,
+                e
+            );
+        }
+
+        let verb = if self.dry_run {
+            "Would update"
+        } else {
+            "Updated"
+        };
+        logging::message(
+            Level::Info,
+            format!("{} remotes of {} repositories", verb, changed).green(),
+        );
+        Ok(())
+    }
+}
+
+/// Subcommands of `repos remote`
+#[derive(Debug, Subcommand)]
+pub enum RemoteAction {
+    /// Add and update remotes to match `url` and `remotes` in the config
+    Sync {
+        /// Specific repository names to sync (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Show the changes without making them
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Also remove remotes the config does not declare
+        #[arg(long)]
+        prune: bool,
+
+        /// Configuration file path (can be specified multiple times to combine fleets)
+        #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+        config: Vec<String>,
+
+        /// Filter repositories by tag (can be specified multiple times)
+        #[arg(short, long)]
+        tag: Vec<String>,
+
+        /// Exclude repositories with these tags (can be specified multiple times)
+        #[arg(short = 'e', long)]
+        exclude_tag: Vec<String>,
+    },
+}
+
+impl RemoteAction {
+    /// Validate the arguments and run `repos remote <action>`
+    pub async fn run(self, globals: &GlobalOptions) -> Result<()> {
+        match self {
+            RemoteAction::Sync {
+                repos,
+                dry_run,
+                prune,
+                config,
+                tag,
+                exclude_tag,
+            } => {
+                let config = Config::load_all(&config)?;
+
+                validators::validate_tag_filters(&tag)?;
+                validators::validate_tag_filters(&exclude_tag)?;
+                validators::validate_repository_names(&repos)?;
+
+                let context = globals.context(config, tag, exclude_tag, repos);
+                RemoteSyncCommand { dry_run, prune }
+                    .execute(&context)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use std::process::Command as Process;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let output = Process::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {:?} failed", args);
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_remote_sync_adds_updates_and_prunes() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().join("api");
+        std::fs::create_dir(&dir).unwrap();
+        git(&dir, &["init", "-q"]);
+        git(
+            &dir,
+            &["remote", "add", "origin", "git@github.com:old/api.git"],
+        );
+        git(
+            &dir,
+            &["remote", "add", "stale", "git@github.com:gone/api.git"],
+        );
+
+        let mut repo = Repository::new("api".to_string(), "git@github.com:me/api.git".to_string());
+        repo.path = Some(dir.to_string_lossy().to_string());
+        repo.remotes.insert(
+            "upstream".to_string(),
+            "git@github.com:org/api.git".to_string(),
+        );
+        let mut config = Config::new();
+        config.repositories = vec![repo];
+        let context = CommandContext {
+            config,
+            tag: vec![],
+            exclude_tag: vec![],
+            parallel: false,
+            repos: None,
+        };
+
+        RemoteSyncCommand {
+            dry_run: true,
+            prune: true,
+        }
+        .execute(&context)
+        .await
+        .unwrap();
+        assert_eq!(git(&dir, &["remote"]), "origin\nstale");
+
+        RemoteSyncCommand {
+            dry_run: false,
+            prune: false,
+        }
+        .execute(&context)
+        .await
+        .unwrap();
+        assert_eq!(git(&dir, &["remote"]), "origin\nstale\nupstream");
+        assert_eq!(
+            git(&dir, &["remote", "get-url", "origin"]),
+            "git@github.com:me/api.git"
+        );
+
+        RemoteSyncCommand {
+            dry_run: false,
+            prune: true,
+        }
+        .execute(&context)
+        .await
+        .unwrap();
+        assert_eq!(git(&dir, &["remote"]), "origin\nupstream");
+    }
+}
@@ -1,13 +1,74 @@
 //! Remove command implementation
 
-use super::{Command, CommandContext};
+use super::{Command, CommandContext, GlobalOptions, validators};
+use crate::config::{Config, Repository};
 use crate::git;
+use crate::logging;
+use crate::utils::interactive;
+use crate::{constants, events};
 use anyhow::Result;
 use async_trait::async_trait;
+use clap::Args;
 use colored::*;
+use log::Level;
+use std::path::{Path, PathBuf};
 
 /// Remove command for deleting cloned repositories
-pub struct RemoveCommand;
+#[derive(Debug, Clone, Default)]
+pub struct RemoveCommand {
+    /// Remove repositories with uncommitted, stashed, or unpushed work without asking
+    pub force: bool,
+    /// Move directories here instead of deleting them
+    pub trash: Option<PathBuf>,
+}
+
+impl RemoveCommand {
+    fn remove(&self, repo: &Repository) -> Result<()> {
+        match &self.trash {
+            Some(trash_dir) => git::trash_repository(repo, trash_dir).map(|_| ()),
+            None => git::remove_repository(repo),
+        }
+    }
+
+    /// Stop unless the user accepts losing local work found in the repositories
+    fn confirm_local_work(&self, repositories: &[Repository]) -> Result<()> {
+        let mut at_risk = 0;
+        for repo in repositories {
+            let target_dir = repo.get_target_dir();
+            if !Path::new(&target_dir).exists() {
+                continue;
+            }
+            let work = git::local_work(&target_dir)?;
+            if !work.is_empty() {
+                logging::status(Level::Warn, &repo.name, work.describe().yellow());
+                at_risk += 1;
+            }
+        }
+
+        if at_risk == 0 {
+            return Ok(());
+        }
+        if !interactive::is_terminal() {
+            anyhow::bail!(
+                "{} repositories have local work that would be lost; use --force to remove them anyway",
+                at_risk
+            );
+        }
+
+        let action = if self.trash.is_some() {
+            "Move"
+        } else {
+            "Permanently delete"
+        };
+        if !interactive::confirm(&format!(
+            "{} {} repositories with local work?",
+            action, at_risk
+        ))? {
+            anyhow::bail!("Removal cancelled");
+        }
+        Ok(())
+    }
+}
 
 #[async_trait]
 impl Command for RemoveCommand {
@@ -25,16 +86,20 @@ impl Command for RemoveCommand {
                 (true, Some(repos)) => format!("repositories {repos:?}"),
                 (true, None) => "no repositories found".to_string(),
             };
-            println!(
-                "{}",
-                format!("No repositories found with {filter_desc}").yellow()
+            logging::message(
+                Level::Info,
+                format!("No repositories found with {filter_desc}").yellow(),
             );
             return Ok(());
         }
 
-        println!(
-            "{}",
-            format!("Removing {} repositories...", repositories.len()).green()
+        if !self.force {
+            self.confirm_local_work(&repositories)?;
+        }
+
+        logging::message(
+            Level::Info,
+            format!("Removing {} repositories...", repositories.len()).green(),
         );
 
         let mut errors = Vec::new();
@@ -45,17 +110,19 @@ impl Command for RemoveCommand {
                 .into_iter()
                 .map(|repo| {
                     let repo_name = repo.name.clone();
+                    let command = self.clone();
                     tokio::spawn(async move {
                         let result = tokio::task::spawn_blocking(move || {
-                            match git::remove_repository(&repo) {
+                            match events::track(&repo.name, || command.remove(&repo)) {
                                 Ok(_) => Ok(()),
                                 Err(e)
                                     if e.to_string()
                                         .contains("Repository directory does not exist") =>
                                 {
-                                    println!(
-                                        "{} | Directory does not exist",
-                                        repo.name.cyan().bold()
+                                    logging::status(
+                                        Level::Info,
+                                        &repo.name,
+                                        "Directory does not exist",
                                     );
                                     Ok(()) // Treat as success since desired state is achieved
                                 }
@@ -72,18 +139,18 @@ impl Command for RemoveCommand {
                 match task.await? {
                     Ok((_, Ok(_))) => successful += 1,
                     Ok((repo_name, Err(e))) => {
-                        eprintln!("{}", format!("Error: {e}").red());
+                        logging::status(Level::Error, &repo_name, format!("Error: {e}").red());
                         errors.push((repo_name, e));
                     }
                     Err(e) => {
-                        eprintln!("{}", format!("Task error: {e}").red());
+                        logging::message(Level::Error, format!("Task error: {e}").red());
                         errors.push(("unknown".to_string(), e));
                     }
                 }
             }
         } else {
             for repo in repositories {
-                match git::remove_repository(&repo) {
+                match self.remove(&repo) {
                     Ok(_) => {
                         successful += 1;
                     }
@@ -91,15 +158,11 @@ impl Command for RemoveCommand {
                         if e.to_string()
                             .contains("Repository directory does not exist") =>
                     {
-                        println!("{} | Directory does not exist", repo.name.cyan().bold());
+                        logging::status(Level::Info, &repo.name, "Directory does not exist");
                         successful += 1; // Count as success since the desired state is achieved
                     }
                     Err(e) => {
-                        eprintln!(
-                            "{} | {}",
-                            repo.name.cyan().bold(),
-                            format!("Error: {e}").red()
-                        );
+                        logging::status(Level::Error, &repo.name, format!("Error: {e}").red());
                         errors.push((repo.name.clone(), e));
                     }
                 }
@@ -108,16 +171,16 @@ impl Command for RemoveCommand {
 
         // Report summary
         if errors.is_empty() {
-            println!("{}", "Done removing repositories".green());
+            logging::message(Level::Info, "Done removing repositories".green());
         } else {
-            println!(
-                "{}",
+            logging::message(
+                Level::Info,
                 format!(
                     "Completed with {} successful, {} failed",
                     successful,
                     errors.len()
                 )
-                .yellow()
+                .yellow(),
             );
 
             // If all operations failed, return an error to propagate to main
@@ -133,6 +196,76 @@ impl Command for RemoveCommand {
     }
 }
 
+/// Arguments of `repos rm`
+#[derive(Debug, Args)]
+pub struct RmArgs {
+    /// Specific repository names to remove (if not provided, uses tag filter or all repos)
+    pub repos: Vec<String>,
+
+    /// Configuration file path (can be specified multiple times to combine fleets)
+    #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+    pub config: Vec<String>,
+
+    /// Filter repositories by tag (can be specified multiple times)
+    #[arg(short, long)]
+    pub tag: Vec<String>,
+
+    /// Exclude repositories with these tags (can be specified multiple times)
+    #[arg(short = 'e', long)]
+    pub exclude_tag: Vec<String>,
+
+    /// Execute operations in parallel
+    #[arg(short, long)]
+    pub parallel: bool,
+
+    /// Pick repositories interactively from the filtered list before executing
+    #[arg(short, long)]
+    pub interactive: bool,
+
+    /// Remove repositories with uncommitted, stashed, or unpushed work without asking
+    #[arg(short, long)]
+    pub force: bool,
+
+    /// Move directories to the trash in the state directory instead of deleting them
+    #[arg(long)]
+    pub trash: bool,
+}
+
+impl RmArgs {
+    /// Validate the arguments and run `repos rm`
+    pub async fn run(self, globals: &GlobalOptions) -> Result<()> {
+        let Self {
+            repos,
+            config,
+            tag,
+            exclude_tag,
+            parallel,
+            interactive,
+            force,
+            trash,
+        } = self;
+        let config = Config::load_all(&config)?;
+
+        // Validate remove command arguments using centralized validators
+        validators::validate_tag_filters(&tag)?;
+        validators::validate_tag_filters(&exclude_tag)?;
+        validators::validate_repository_names(&repos)?;
+
+        let mut context = globals
+            .context(config, tag, exclude_tag, repos)
+            .with_parallel(parallel);
+        if interactive {
+            context.select_interactively()?;
+        }
+        RemoveCommand {
+            force,
+            trash: trash.then(|| crate::state::StateStore::open_default().trash_dir()),
+        }
+        .execute(&context)
+        .await
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -156,13 +289,41 @@ mod tests {
             path: Some(repo_dir.to_string_lossy().to_string()),
             branch: None,
             config_dir: None,
+            path_strategy: Default::default(),
+            default_branch: None,
+            sparse: Vec::new(),
+            provider: None,
+            provider_url: None,
+            provider_defaults: Default::default(),
+            git: Default::default(),
+            depends_on: Vec::new(),
+            post_clone: None,
+            archived: false,
+            remotes: Default::default(),
         };
 
-        let command = RemoveCommand;
+        let command = RemoveCommand::default();
         let context = CommandContext {
             config: Config {
                 repositories: vec![repo],
                 recipes: vec![],
+                artifacts_remote: None,
+                log_sinks: Vec::new(),
+                path_strategy: Default::default(),
+                root: None,
+                files: Vec::new(),
+                branch_protection: Vec::new(),
+                repository_settings: Vec::new(),
+                post_clone: Vec::new(),
+                pr: None,
+                provider: None,
+                provider_url: None,
+                git: Default::default(),
+                exit_codes: Default::default(),
+                notify: None,
+                profiles: Default::default(),
+                secrets: None,
+                profile_token: None,
             },
             tag: vec![],
             exclude_tag: vec![],
@@ -199,17 +360,45 @@ mod tests {
                 path: Some(repo_dir.to_string_lossy().to_string()),
                 branch: None,
                 config_dir: None,
+                path_strategy: Default::default(),
+                default_branch: None,
+                sparse: Vec::new(),
+                provider: None,
+                provider_url: None,
+                provider_defaults: Default::default(),
+                git: Default::default(),
+                depends_on: Vec::new(),
+                post_clone: None,
+                archived: false,
+                remotes: Default::default(),
             };
 
             repositories.push(repo);
             repo_dirs.push(repo_dir);
         }
 
-        let command = RemoveCommand;
+        let command = RemoveCommand::default();
         let context = CommandContext {
             config: Config {
                 repositories,
                 recipes: vec![],
+                artifacts_remote: None,
+                log_sinks: Vec::new(),
+                path_strategy: Default::default(),
+                root: None,
+                files: Vec::new(),
+                branch_protection: Vec::new(),
+                repository_settings: Vec::new(),
+                post_clone: Vec::new(),
+                pr: None,
+                provider: None,
+                provider_url: None,
+                git: Default::default(),
+                exit_codes: Default::default(),
+                notify: None,
+                profiles: Default::default(),
+                secrets: None,
+                profile_token: None,
             },
             tag: vec![],
             exclude_tag: vec![],
@@ -251,17 +440,45 @@ mod tests {
                 path: Some(repo_dir.to_string_lossy().to_string()),
                 branch: None,
                 config_dir: None,
+                path_strategy: Default::default(),
+                default_branch: None,
+                sparse: Vec::new(),
+                provider: None,
+                provider_url: None,
+                provider_defaults: Default::default(),
+                git: Default::default(),
+                depends_on: Vec::new(),
+                post_clone: None,
+                archived: false,
+                remotes: Default::default(),
             };
 
             repositories.push(repo);
             repo_dirs.push(repo_dir);
         }
 
-        let command = RemoveCommand;
+        let command = RemoveCommand::default();
         let context = CommandContext {
             config: Config {
                 repositories,
                 recipes: vec![],
+                artifacts_remote: None,
+                log_sinks: Vec::new(),
+                path_strategy: Default::default(),
+                root: None,
+                files: Vec::new(),
+                branch_protection: Vec::new(),
+                repository_settings: Vec::new(),
+                post_clone: Vec::new(),
+                pr: None,
+                provider: None,
+                provider_url: None,
+                git: Default::default(),
+                exit_codes: Default::default(),
+                notify: None,
+                profiles: Default::default(),
+                secrets: None,
+                profile_token: None,
             },
             tag: vec![],
             exclude_tag: vec![],
@@ -297,13 +514,41 @@ mod tests {
             path: Some(repo_dir.to_string_lossy().to_string()),
             branch: None,
             config_dir: None,
+            path_strategy: Default::default(),
+            default_branch: None,
+            sparse: Vec::new(),
+            provider: None,
+            provider_url: None,
+            provider_defaults: Default::default(),
+            git: Default::default(),
+            depends_on: Vec::new(),
+            post_clone: None,
+            archived: false,
+            remotes: Default::default(),
         };
 
-        let command = RemoveCommand;
+        let command = RemoveCommand::default();
         let context = CommandContext {
             config: Config {
                 repositories: vec![repo],
                 recipes: vec![],
+                artifacts_remote: None,
+                log_sinks: Vec::new(),
+                path_strategy: Default::default(),
+                root: None,
+                files: Vec::new(),
+                branch_protection: Vec::new(),
+                repository_settings: Vec::new(),
+                post_clone: Vec::new(),
+                pr: None,
+                provider: None,
+                provider_url: None,
+                git: Default::default(),
+                exit_codes: Default::default(),
+                notify: None,
+                profiles: Default::default(),
+                secrets: None,
+                profile_token: None,
             },
             tag: vec![],
             exclude_tag: vec![],
@@ -332,6 +577,17 @@ mod tests {
             path: Some(matching_repo_dir.to_string_lossy().to_string()),
             branch: None,
             config_dir: None,
+            path_strategy: Default::default(),
+            default_branch: None,
+            sparse: Vec::new(),
+            provider: None,
+            provider_url: None,
+            provider_defaults: Default::default(),
+            git: Default::default(),
+            depends_on: Vec::new(),
+            post_clone: None,
+            archived: false,
+            remotes: Default::default(),
         };
 
         // Create repository with non-matching tag
@@ -345,13 +601,41 @@ mod tests {
             path: Some(non_matching_repo_dir.to_string_lossy().to_string()),
             branch: None,
             config_dir: None,
+            path_strategy: Default::default(),
+            default_branch: None,
+            sparse: Vec::new(),
+            provider: None,
+            provider_url: None,
+            provider_defaults: Default::default(),
+            git: Default::default(),
+            depends_on: Vec::new(),
+            post_clone: None,
+            archived: false,
+            remotes: Default::default(),
         };
 
-        let command = RemoveCommand;
+        let command = RemoveCommand::default();
         let context = CommandContext {
             config: Config {
                 repositories: vec![matching_repo, non_matching_repo],
                 recipes: vec![],
+                artifacts_remote: None,
+                log_sinks: Vec::new(),
+                path_strategy: Default::default(),
+                root: None,
+                files: Vec::new(),
+                branch_protection: Vec::new(),
+                repository_settings: Vec::new(),
+                post_clone: Vec::new(),
+                pr: None,
+                provider: None,
+                provider_url: None,
+                git: Default::default(),
+                exit_codes: Default::default(),
+                notify: None,
+                profiles: Default::default(),
+                secrets: None,
+                profile_token: None,
             },
             tag: vec!["backend".to_string()],
             exclude_tag: vec![],
@@ -388,6 +672,17 @@ mod tests {
             path: Some(repo1_dir.to_string_lossy().to_string()),
             branch: None,
             config_dir: None,
+            path_strategy: Default::default(),
+            default_branch: None,
+            sparse: Vec::new(),
+            provider: None,
+            provider_url: None,
+            provider_defaults: Default::default(),
+            git: Default::default(),
+            depends_on: Vec::new(),
+            post_clone: None,
+            archived: false,
+            remotes: Default::default(),
         };
 
         let repo2 = Repository {
@@ -397,13 +692,41 @@ mod tests {
             path: Some(repo2_dir.to_string_lossy().to_string()),
             branch: None,
             config_dir: None,
+            path_strategy: Default::default(),
+            default_branch: None,
+            sparse: Vec::new(),
+            provider: None,
+            provider_url: None,
+            provider_defaults: Default::default(),
+            git: Default::default(),
+            depends_on: Vec::new(),
+            post_clone: None,
+            archived: false,
+            remotes: Default::default(),
         };
 
-        let command = RemoveCommand;
+        let command = RemoveCommand::default();
         let context = CommandContext {
             config: Config {
                 repositories: vec![repo1, repo2],
                 recipes: vec![],
+                artifacts_remote: None,
+                log_sinks: Vec::new(),
+                path_strategy: Default::default(),
+                root: None,
+                files: Vec::new(),
+                branch_protection: Vec::new(),
+                repository_settings: Vec::new(),
+                post_clone: Vec::new(),
+                pr: None,
+                provider: None,
+                provider_url: None,
+                git: Default::default(),
+                exit_codes: Default::default(),
+                notify: None,
+                profiles: Default::default(),
+                secrets: None,
+                profile_token: None,
             },
             tag: vec![],
             exclude_tag: vec![],
@@ -439,13 +762,41 @@ mod tests {
             ),
             branch: None,
             config_dir: None,
+            path_strategy: Default::default(),
+            default_branch: None,
+            sparse: Vec::new(),
+            provider: None,
+            provider_url: None,
+            provider_defaults: Default::default(),
+            git: Default::default(),
+            depends_on: Vec::new(),
+            post_clone: None,
+            archived: false,
+            remotes: Default::default(),
         };
 
-        let command = RemoveCommand;
+        let command = RemoveCommand::default();
         let context = CommandContext {
             config: Config {
                 repositories: vec![repo],
                 recipes: vec![],
+                artifacts_remote: None,
+                log_sinks: Vec::new(),
+                path_strategy: Default::default(),
+                root: None,
+                files: Vec::new(),
+                branch_protection: Vec::new(),
+                repository_settings: Vec::new(),
+                post_clone: Vec::new(),
+                pr: None,
+                provider: None,
+                provider_url: None,
+                git: Default::default(),
+                exit_codes: Default::default(),
+                notify: None,
+                profiles: Default::default(),
+                secrets: None,
+                profile_token: None,
             },
             tag: vec!["frontend".to_string()], // Non-matching tag
             exclude_tag: vec![],
@@ -459,11 +810,28 @@ mod tests {
 
     #[tokio::test]
     async fn test_remove_command_empty_repositories() {
-        let command = RemoveCommand;
+        let command = RemoveCommand::default();
         let context = CommandContext {
             config: Config {
                 repositories: vec![],
                 recipes: vec![],
+                artifacts_remote: None,
+                log_sinks: Vec::new(),
+                path_strategy: Default::default(),
+                root: None,
+                files: Vec::new(),
+                branch_protection: Vec::new(),
+                repository_settings: Vec::new(),
+                post_clone: Vec::new(),
+                pr: None,
+                provider: None,
+                provider_url: None,
+                git: Default::default(),
+                exit_codes: Default::default(),
+                notify: None,
+                profiles: Default::default(),
+                secrets: None,
+                profile_token: None,
             },
             tag: vec![],
             exclude_tag: vec![],
@@ -495,13 +863,41 @@ mod tests {
             path: Some(repo_dir.to_string_lossy().to_string()),
             branch: None,
             config_dir: None,
+            path_strategy: Default::default(),
+            default_branch: None,
+            sparse: Vec::new(),
+            provider: None,
+            provider_url: None,
+            provider_defaults: Default::default(),
+            git: Default::default(),
+            depends_on: Vec::new(),
+            post_clone: None,
+            archived: false,
+            remotes: Default::default(),
         };
 
-        let command = RemoveCommand;
+        let command = RemoveCommand::default();
         let context = CommandContext {
             config: Config {
                 repositories: vec![repo],
                 recipes: vec![],
+                artifacts_remote: None,
+                log_sinks: Vec::new(),
+                path_strategy: Default::default(),
+                root: None,
+                files: Vec::new(),
+                branch_protection: Vec::new(),
+                repository_settings: Vec::new(),
+                post_clone: Vec::new(),
+                pr: None,
+                provider: None,
+                provider_url: None,
+                git: Default::default(),
+                exit_codes: Default::default(),
+                notify: None,
+                profiles: Default::default(),
+                secrets: None,
+                profile_token: None,
             },
             tag: vec![],
             exclude_tag: vec![],
@@ -530,6 +926,17 @@ mod tests {
             path: Some(matching_repo_dir.to_string_lossy().to_string()),
             branch: None,
             config_dir: None,
+            path_strategy: Default::default(),
+            default_branch: None,
+            sparse: Vec::new(),
+            provider: None,
+            provider_url: None,
+            provider_defaults: Default::default(),
+            git: Default::default(),
+            depends_on: Vec::new(),
+            post_clone: None,
+            archived: false,
+            remotes: Default::default(),
         };
 
         // Create repository with matching tag but wrong name
@@ -543,13 +950,41 @@ mod tests {
             path: Some(wrong_name_repo_dir.to_string_lossy().to_string()),
             branch: None,
             config_dir: None,
+            path_strategy: Default::default(),
+            default_branch: None,
+            sparse: Vec::new(),
+            provider: None,
+            provider_url: None,
+            provider_defaults: Default::default(),
+            git: Default::default(),
+            depends_on: Vec::new(),
+            post_clone: None,
+            archived: false,
+            remotes: Default::default(),
         };
 
-        let command = RemoveCommand;
+        let command = RemoveCommand::default();
         let context = CommandContext {
             config: Config {
                 repositories: vec![matching_repo, wrong_name_repo],
                 recipes: vec![],
+                artifacts_remote: None,
+                log_sinks: Vec::new(),
+                path_strategy: Default::default(),
+                root: None,
+                files: Vec::new(),
+                branch_protection: Vec::new(),
+                repository_settings: Vec::new(),
+                post_clone: Vec::new(),
+                pr: None,
+                provider: None,
+                provider_url: None,
+                git: Default::default(),
+                exit_codes: Default::default(),
+                notify: None,
+                profiles: Default::default(),
+                secrets: None,
+                profile_token: None,
             },
             tag: vec!["backend".to_string()],
             exclude_tag: vec![],
@@ -583,6 +1018,17 @@ mod tests {
             path: Some(success_repo_dir.to_string_lossy().to_string()),
             branch: None,
             config_dir: None,
+            path_strategy: Default::default(),
+            default_branch: None,
+            sparse: Vec::new(),
+            provider: None,
+            provider_url: None,
+            provider_defaults: Default::default(),
+            git: Default::default(),
+            depends_on: Vec::new(),
+            post_clone: None,
+            archived: false,
+            remotes: Default::default(),
         };
 
         // Create a repository pointing to a nonexistent directory (should succeed as desired state)
@@ -599,13 +1045,41 @@ mod tests {
             ),
             branch: None,
             config_dir: None,
+            path_strategy: Default::default(),
+            default_branch: None,
+            sparse: Vec::new(),
+            provider: None,
+            provider_url: None,
+            provider_defaults: Default::default(),
+            git: Default::default(),
+            depends_on: Vec::new(),
+            post_clone: None,
+            archived: false,
+            remotes: Default::default(),
         };
 
-        let command = RemoveCommand;
+        let command = RemoveCommand::default();
         let context = CommandContext {
             config: Config {
                 repositories: vec![success_repo, nonexistent_repo],
                 recipes: vec![],
+                artifacts_remote: None,
+                log_sinks: Vec::new(),
+                path_strategy: Default::default(),
+                root: None,
+                files: Vec::new(),
+                branch_protection: Vec::new(),
+                repository_settings: Vec::new(),
+                post_clone: Vec::new(),
+                pr: None,
+                provider: None,
+                provider_url: None,
+                git: Default::default(),
+                exit_codes: Default::default(),
+                notify: None,
+                profiles: Default::default(),
+                secrets: None,
+                profile_token: None,
             },
             tag: vec![],
             exclude_tag: vec![],
@@ -621,4 +1095,84 @@ mod tests {
         // Success repo should be removed
         assert!(!success_repo_dir.exists());
     }
+
+    fn git_repository(temp_dir: &TempDir, name: &str) -> Repository {
+        let repo_dir = temp_dir.path().join(name);
+        fs::create_dir_all(&repo_dir).unwrap();
+        for args in [
+            vec!["init"],
+            vec!["config", "user.name", "Test User"],
+            vec!["config", "user.email", "test@example.com"],
+        ] {
+            std::process::Command::new("git")
+                .args(args)
+                .current_dir(&repo_dir)
+                .output()
+                .unwrap();
+        }
+        fs::write(repo_dir.join("draft.txt"), "unsaved work").unwrap();
+
+        let mut repo = Repository::new(
+            name.to_string(),
+            format!("https://github.com/user/{name}.git"),
+        );
+        repo.path = Some(repo_dir.to_string_lossy().to_string());
+        repo
+    }
+
+    fn context_for(repo: Repository) -> CommandContext {
+        let mut config = Config::new();
+        config.repositories.push(repo);
+        CommandContext {
+            config,
+            tag: vec![],
+            exclude_tag: vec![],
+            repos: None,
+            parallel: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_remove_command_refuses_local_work() {
+        // Without a terminal there is no one to confirm
+        if interactive::is_terminal() {
+            return;
+        }
+        let temp_dir = TempDir::new().unwrap();
+        let repo = git_repository(&temp_dir, "dirty-repo");
+        let repo_dir = temp_dir.path().join("dirty-repo");
+
+        let result = RemoveCommand::default()
+            .execute(&context_for(repo.clone()))
+            .await;
+        assert!(result.unwrap_err().to_string().contains("--force"));
+        assert!(repo_dir.exists());
+
+        let command = RemoveCommand {
+            force: true,
+            trash: None,
+        };
+        command.execute(&context_for(repo)).await.unwrap();
+        assert!(!repo_dir.exists());
+    }
+
+    #[tokio::test]
+    async fn test_remove_command_trash() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = git_repository(&temp_dir, "trashed-repo");
+        let repo_dir = temp_dir.path().join("trashed-repo");
+        let trash_dir = temp_dir.path().join("trash");
+
+        let command = RemoveCommand {
+            force: true,
+            trash: Some(trash_dir.clone()),
+        };
+        command.execute(&context_for(repo)).await.unwrap();
+
+        assert!(!repo_dir.exists());
+        let trashed: Vec<_> = fs::read_dir(&trash_dir).unwrap().collect();
+        assert_eq!(trashed.len(), 1);
+        let trashed = trashed[0].as_ref().unwrap().path();
+        assert!(trashed.join("draft.txt").exists());
+    }
 }
@@ -0,0 +1,303 @@
+//! Rollback command implementation
+//!
+//! Mutating commands call [`checkpoint`] before they touch working copies. It
+//! snapshots each repository with [`git::snapshot`] and records the snapshots
+//! under a new id in the state store, from where `repos rollback <id>` puts
+//! them back.
+
+use super::{Command, CommandContext, GlobalOptions, validators};
+use crate::config::{Config, Repository};
+use crate::git::{self, WorkingState};
+use crate::state::{Checkpoint, StateStore};
+use crate::{constants, logging};
+use anyhow::Result;
+use async_trait::async_trait;
+use clap::Args;
+use colored::*;
+use log::Level;
+use std::path::Path;
+
+/// Rollback command returning repositories to a checkpoint
+pub struct RollbackCommand {
+    pub id: String,
+}
+
+/// Command listing the checkpoints that can be rolled back to
+pub struct RollbackListCommand;
+
+fn short_sha(commit: &str) -> &str {
+    &commit[..commit.len().min(7)]
+}
+
+fn describe(state: &WorkingState) -> String {
+    let mut description = match &state.branch {
+        Some(branch) => format!("{} @ {}", branch, short_sha(&state.commit)),
+        None => format!("{} (detached)", short_sha(&state.commit)),
+    };
+    if state.stash.is_some() {
+        description.push_str(" with uncommitted changes");
+    }
+    description
+}
+
+/// An id for a new checkpoint that is not taken yet
+fn new_id(existing: &[Checkpoint]) -> String {
+    let base = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
+    let mut id = base.clone();
+    let mut n = 1;
+    while existing.iter().any(|c| c.id == id) {
+        n += 1;
+        id = format!("{}-{}", base, n);
+    }
+    id
+}
+
+/// Snapshot the cloned repositories before `operation` changes them
+///
+/// Repositories that cannot be snapshotted are skipped with a warning, so a
+/// checkpoint never stops the operation itself. Returns the checkpoint id, or
+/// `None` when nothing was saved.
+pub fn checkpoint(operation: &str, repositories: &[Repository]) -> Result<Option<String>> {
+    let store = StateStore::open_default();
+    let mut checkpoint = Checkpoint {
+        id: new_id(&store.load()?.checkpoints),
+        operation: operation.to_string(),
+        created_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        ..Default::default()
+    };
+
+    for repo in repositories {
+        let target_dir = repo.get_target_dir();
+        if !Path::new(&target_dir).exists() {
+            continue;
+        }
+        match git::snapshot(&target_dir, &checkpoint.id) {
+            Ok(state) => {
+                checkpoint.repositories.insert(repo.name.clone(), state);
+            }
+            Err(e) => logging::status(
+                Level::Warn,
+                &repo.name,
+                format!("Warning: Failed to save checkpoint: {}", e).yellow(),
+            ),
+        }
+    }
+    if checkpoint.repositories.is_empty() {
+        return Ok(None);
+    }
+
+    let id = checkpoint.id.clone();
+    let saved = checkpoint.repositories.len();
+    for dropped in store.record_checkpoint(checkpoint)? {
+        for repo in repositories {
+            if dropped.repositories.contains_key(&repo.name) {
+                let _ = git::drop_snapshot(&repo.get_target_dir(), &dropped.id);
+            }
+        }
+    }
+
+    logging::message(
+        Level::Info,
+        format!(
+            "Saved checkpoint {} of {} repositories; undo with 'repos rollback {}'",
+            id, saved, id
+        )
+        .dimmed(),
+    );
+    Ok(Some(id))
+}
+
+#[async_trait]
+impl Command for RollbackCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let checkpoint = StateStore::open_default()
+            .checkpoint(&self.id)?
+            .ok_or_else(|| anyhow::anyhow!("Checkpoint '{}' not found", self.id))?;
+
+        let repositories: Vec<Repository> = context
+            .config
+            .filter_repositories(&context.tag, &context.exclude_tag, context.repos.as_deref())
+            .into_iter()
+            .filter(|repo| checkpoint.repositories.contains_key(&repo.name))
+            .collect();
+
+        if repositories.is_empty() {
+            logging::message(
+                Level::Info,
+                format!(
+                    "No selected repositories are part of checkpoint '{}'",
+                    self.id
+                )
+                .yellow(),
+            );
+            return Ok(());
+        }
+
+        logging::message(
+            Level::Info,
+            format!(
+                "Rolling back {} repositories to before '{}' ({})",
+                repositories.len(),
+                checkpoint.operation,
+                checkpoint.created_at
+            )
+            .green(),
+        );
+
+        let mut errors = Vec::new();
+        for repo in &repositories {
+            let state = &checkpoint.repositories[&repo.name];
+            let target_dir = repo.get_target_dir();
+            let result = if Path::new(&target_dir).exists() {
+                git::restore(&target_dir, &self.id, state)
+            } else {
+                Err(anyhow::anyhow!(
+                    "Repository directory does not exist: {}",
+                    target_dir
+                ))
+            };
+            match result {
+                Ok(report) => {
+                    let mut message = format!("Restored {}", describe(state));
+                    if report.backup.is_some() {
+                        message.push_str("; changes made since were stashed");
+                    }
+                    logging::status(Level::Info, &repo.name, message.green());
+                }
+                Err(e) => {
+                    logging::status(Level::Error, &repo.name, format!("Error: {e}").red());
+                    errors.push((repo.name.clone(), e));
+                }
+            }
+        }
+
+        if let Some((repo_name, e)) = errors.first() {
+            anyhow::bail!(
+                "Failed to roll back {} repositories to checkpoint '{}'. First error ({}): {}",
+                errors.len(),
+                self.id,
+                repo_name,
+                e
+            );
+        }
+
+        logging::message(
+            Level::Info,
+            format!("Rolled back to checkpoint '{}'", self.id).green(),
+        );
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Command for RollbackListCommand {
+    async fn execute(&self, _context: &CommandContext) -> Result<()> {
+        let checkpoints = StateStore::open_default().load()?.checkpoints;
+        if checkpoints.is_empty() {
+            println!("{}", "No checkpoints saved".yellow());
+            return Ok(());
+        }
+
+        for checkpoint in checkpoints.iter().rev() {
+            println!(
+                "{} {} ({} repositories, {})",
+                checkpoint.id.bold(),
+                checkpoint.operation,
+                checkpoint.repositories.len(),
+                checkpoint.created_at
+            );
+            for (repo_name, state) in &checkpoint.repositories {
+                println!("  {} | {}", repo_name.cyan(), describe(state));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Arguments of `repos rollback`
+#[derive(Debug, Args)]
+pub struct RollbackArgs {
+    /// Checkpoint id, as printed when it was saved
+    #[arg(required_unless_present = "list")]
+    pub id: Option<String>,
+
+    /// Specific repository names to restore (if not provided, uses tag filter or all repos in the checkpoint)
+    pub repos: Vec<String>,
+
+    /// List saved checkpoints instead, newest first
+    #[arg(long, conflicts_with = "id")]
+    pub list: bool,
+
+    /// Configuration file path (can be specified multiple times to combine fleets)
+    #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+    pub config: Vec<String>,
+
+    /// Filter repositories by tag (can be specified multiple times)
+    #[arg(short, long)]
+    pub tag: Vec<String>,
+
+    /// Exclude repositories with these tags (can be specified multiple times)
+    #[arg(short = 'e', long)]
+    pub exclude_tag: Vec<String>,
+}
+
+impl RollbackArgs {
+    /// Validate the arguments and run `repos rollback`
+    pub async fn run(self, globals: &GlobalOptions) -> Result<()> {
+        let Self {
+            id,
+            repos,
+            list,
+            config,
+            tag,
+            exclude_tag,
+        } = self;
+        let config = Config::load_all(&config)?;
+
+        validators::validate_tag_filters(&tag)?;
+        validators::validate_tag_filters(&exclude_tag)?;
+        validators::validate_repository_names(&repos)?;
+
+        let context = globals.context(config, tag, exclude_tag, repos);
+
+        match id {
+            Some(id) if !list => RollbackCommand { id }.execute(&context).await?,
+            _ => RollbackListCommand.execute(&context).await?,
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe() {
+        let state = WorkingState {
+            branch: Some("main".to_string()),
+            commit: "0123456789abcdef".to_string(),
+            stash: Some("fedcba9876543210".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(describe(&state), "main @ 0123456 with uncommitted changes");
+
+        let detached = WorkingState {
+            commit: "fedcba9876543210".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(describe(&detached), "fedcba9 (detached)");
+    }
+
+    #[test]
+    fn test_new_id_is_unique() {
+        let first = new_id(&[]);
+        let taken = vec![Checkpoint {
+            id: first.clone(),
+            ..Default::default()
+        }];
+        let second = new_id(&taken);
+        assert_ne!(first, second);
+        assert!(second.starts_with(&first[..8]));
+    }
+}
@@ -1,13 +1,37 @@
 //! Run command implementation
 
-use super::{Command, CommandContext};
+use super::{Command, CommandContext, GlobalOptions, validators};
+use crate::cache::{CachedResult, RunCache};
+use crate::config::{Config, DependencyGraph, Recipe, Repository, SecretEnv, secrets};
+use crate::constants::config::DEFAULT_LOGS_DIR;
+use crate::events;
+use crate::git::{self, Logger};
+use crate::logging;
 use crate::runner::CommandRunner;
+use crate::state::StateStore;
+use crate::utils::artifacts::{ARTIFACTS_DIR, collect_artifacts};
+use crate::utils::batch::BatchResult;
+pub use crate::utils::batch::FailurePolicy;
+use crate::utils::manifest::ManifestEntry;
+use crate::utils::notify;
+use crate::utils::output_sink::{LogSinkConfig, OutputSink};
+use crate::utils::run_summary::{RepoRunResult, RunSummary};
 use crate::utils::sanitizers::{sanitize_for_filename, sanitize_script_name};
+use crate::utils::upload::{ArtifactsRemote, upload_run_dir};
+use crate::utils::{ExitCodeDescriptions, Redactor};
+use crate::{ci, constants};
 use anyhow::Result;
 use async_trait::async_trait;
+use colored::*;
+use futures::StreamExt;
+use futures::stream::FuturesUnordered;
+use log::Level;
 
+use clap::Args;
 use std::fs::create_dir_all;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 #[derive(Debug)]
 pub enum RunType {
@@ -21,6 +45,26 @@ pub struct RunCommand {
     pub run_type: RunType,
     pub no_save: bool,
     pub output_dir: Option<PathBuf>,
+    /// Glob patterns of files to collect from each repository after execution
+    pub artifacts: Vec<String>,
+    /// Reuse the result of a previous successful run at the same commit
+    pub cache: bool,
+    /// Always execute, even when caching is enabled by flag or recipe
+    pub no_cache: bool,
+    /// Remote storage to upload the run directory to after completion
+    pub upload: Option<ArtifactsRemote>,
+    /// Branches or tags to run against, each checked out in a temporary worktree
+    pub refs: Vec<String>,
+    /// Run in a worktree per repository under this directory instead of the working copy
+    pub worktrees: Option<PathBuf>,
+    /// Run repositories after the ones they depend on
+    pub ordered: bool,
+    /// Save a checkpoint of the working copies for `repos rollback` first
+    pub checkpoint: bool,
+    /// Destinations the output of each repository is also written to when saving
+    pub log_sinks: Vec<LogSinkConfig>,
+    /// When the run stops early and when it counts as failed
+    pub failure_policy: FailurePolicy,
 }
 
 impl RunCommand {
@@ -29,6 +73,16 @@ impl RunCommand {
             run_type: RunType::Command(command),
             no_save,
             output_dir,
+            artifacts: Vec::new(),
+            cache: false,
+            no_cache: false,
+            upload: None,
+            refs: Vec::new(),
+            worktrees: None,
+            ordered: false,
+            checkpoint: false,
+            log_sinks: Vec::new(),
+            failure_policy: FailurePolicy::default(),
         }
     }
 
@@ -37,31 +91,149 @@ impl RunCommand {
             run_type: RunType::Recipe(recipe_name),
             no_save,
             output_dir,
+            artifacts: Vec::new(),
+            cache: false,
+            no_cache: false,
+            upload: None,
+            refs: Vec::new(),
+            worktrees: None,
+            ordered: false,
+            checkpoint: false,
+            log_sinks: Vec::new(),
+            failure_policy: FailurePolicy::default(),
         }
     }
+
+    /// Collect files matching the given glob patterns after execution
+    pub fn with_artifacts(mut self, artifacts: Vec<String>) -> Self {
+        self.artifacts = artifacts;
+        self
+    }
+
+    /// Configure result caching; `no_cache` takes precedence over `cache`
+    pub fn with_cache(mut self, cache: bool, no_cache: bool) -> Self {
+        self.cache = cache;
+        self.no_cache = no_cache;
+        self
+    }
+
+    /// Upload the run directory to remote storage after completion
+    pub fn with_upload(mut self, remote: ArtifactsRemote) -> Self {
+        self.upload = Some(remote);
+        self
+    }
+
+    /// Run against each of these branches or tags instead of the current checkout
+    pub fn with_refs(mut self, refs: Vec<String>) -> Self {
+        self.refs = refs;
+        self
+    }
+
+    /// Run in a persistent worktree per repository, kept under `worktrees_dir`
+    pub fn with_worktrees(mut self, worktrees_dir: PathBuf) -> Self {
+        self.worktrees = Some(worktrees_dir);
+        self
+    }
+
+    /// Run repositories after those they declare in `depends_on`
+    pub fn with_ordered(mut self, ordered: bool) -> Self {
+        self.ordered = ordered;
+        self
+    }
+
+    /// Save a checkpoint of the working copies before running
+    pub fn with_checkpoint(mut self, checkpoint: bool) -> Self {
+        self.checkpoint = checkpoint;
+        self
+    }
+
+    /// Also write saved output to these sinks, as configured via `log_sinks:`
+    pub fn with_log_sinks(mut self, log_sinks: Vec<LogSinkConfig>) -> Self {
+        self.log_sinks = log_sinks;
+        self
+    }
+
+    /// Decide when the run stops early and when it counts as failed
+    pub fn with_failure_policy(mut self, failure_policy: FailurePolicy) -> Self {
+        self.failure_policy = failure_policy;
+        self
+    }
 }
 
-#[async_trait]
-impl Command for RunCommand {
-    async fn execute(&self, context: &CommandContext) -> Result<()> {
-        match &self.run_type {
-            RunType::Command(command) => self.execute_command(context, command).await,
-            RunType::Recipe(recipe_name) => self.execute_recipe(context, recipe_name).await,
+/// Work resolved from the run type, executed in every selected repository
+#[derive(Debug, Clone)]
+enum Job {
+    Command(String),
+    Recipe(Recipe),
+}
+
+impl Job {
+    /// Label used to name the run directory
+    fn label(&self) -> &str {
+        match self {
+            Job::Command(command) => command,
+            Job::Recipe(recipe) => &recipe.name,
         }
     }
-}
 
-impl RunCommand {
-    /// Create a new RunCommand with default settings for testing
-    pub fn new_for_test(command: String, output_dir: String) -> Self {
-        Self {
-            run_type: RunType::Command(command),
-            no_save: false,
-            output_dir: Some(PathBuf::from(output_dir)),
+    /// Content that identifies the job for caching purposes
+    fn cache_input(&self) -> String {
+        match self {
+            Job::Command(command) => format!("command:{}", command),
+            Job::Recipe(recipe) => {
+                // Secret references rather than their values are part of the key
+                let env = recipe
+                    .env
+                    .iter()
+                    .map(|(name, value)| format!("env {}={}", name, value));
+                let steps: Vec<String> = env
+                    .chain(recipe.steps.iter().map(|step| match &step.condition {
+                        Some(condition) => format!("if {}: {}", condition, step.run),
+                        None => step.run.clone(),
+                    }))
+                    .collect();
+                format!("recipe:{}\n{}", recipe.name, steps.join("\n"))
+            }
         }
     }
+}
+
+/// Summary record of one repository, along with the result it was made from
+type Outcome = (RepoRunResult, Result<i32>);
+
+/// Everything needed to execute a job in a single repository
+#[derive(Debug, Clone)]
+struct RunPlan {
+    job: Job,
+    run_root: Option<PathBuf>,
+    artifacts: Vec<String>,
+    cache: Option<RunCache>,
+    /// Descriptions reported for exit codes in logs, metadata, and the summary
+    exit_codes: ExitCodeDescriptions,
+    /// Directory of per-repository worktrees to run in, if any
+    worktrees: Option<PathBuf>,
+    /// Variables of the recipe, with secrets resolved
+    env: SecretEnv,
+    /// Masks secrets in saved output and metadata
+    redactor: Redactor,
+    /// Extra destinations of saved output, next to the run directory
+    sinks: Vec<Arc<dyn OutputSink>>,
+}
+
+#[async_trait]
+impl Command for RunCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let job = match &self.run_type {
+            RunType::Command(command) => Job::Command(command.clone()),
+            RunType::Recipe(recipe_name) => Job::Recipe(
+                context
+                    .config
+                    .find_recipe(recipe_name)
+                    .cloned()
+                    .ok_or_else(|| anyhow::anyhow!("Recipe '{}' not found", recipe_name))?,
+            ),
+        };
 
-    async fn execute_command(&self, context: &CommandContext, command: &str) -> Result<()> {
         let repositories = context.config.filter_repositories(
             &context.tag,
             &context.exclude_tag,
@@ -71,173 +243,662 @@ impl RunCommand {
         if repositories.is_empty() {
             return Ok(());
         }
+        let mut manifest = context.start_manifest("run")?;
 
-        let runner = CommandRunner::new();
+        if self.upload.is_some() && self.no_save {
+            anyhow::bail!("Uploading run output requires saving it; remove --no-save");
+        }
+        if self.worktrees.is_some() && !self.refs.is_empty() {
+            anyhow::bail!("--ref already runs in temporary worktrees; remove --use-worktree");
+        }
 
-        // Setup persistent output directory if saving is enabled
-        let run_root = if !self.no_save {
-            // Use local time instead of UTC
-            let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
-            // Sanitize command for directory name
-            let command_suffix = sanitize_for_filename(command);
-            // Use provided output directory or default to "output"
-            let base_dir = self
-                .output_dir
-                .as_ref()
-                .unwrap_or(&PathBuf::from("output"))
-                .join("runs");
-            let run_dir = base_dir.join(format!("{}_{}", timestamp, command_suffix));
-            create_dir_all(&run_dir)?;
-            Some(run_dir)
+        if self.ordered && !self.refs.is_empty() {
+            anyhow::bail!("--ordered cannot be combined with --ref");
+        }
+        let graph = if self.ordered {
+            Some(DependencyGraph::new(
+                &context.config.repositories,
+                &repositories,
+            )?)
         } else {
             None
         };
 
-        if context.parallel {
-            // Parallel execution
-            let tasks: Vec<_> = repositories
+        if self.checkpoint && (!self.refs.is_empty() || self.worktrees.is_some()) {
+            anyhow::bail!("--checkpoint cannot be combined with --ref or --use-worktree");
+        }
+
+        // Resolve secrets up front, so a missing one fails before anything runs
+        let env = match &job {
+            Job::Recipe(recipe) => {
+                secrets::resolve_env(&recipe.env, context.config.secrets.as_ref())?
+            }
+            Job::Command(_) => SecretEnv::default(),
+        };
+        let redactor = context
+            .config
+            .redactor()?
+            .with_values(env.secrets().to_vec());
+        manifest.redact_with(redactor.clone());
+        let plan = self.plan(job, context.config.exit_codes.clone(), env, redactor)?;
+        if self.checkpoint {
+            super::rollback::checkpoint(&format!("run {}", plan.job.label()), &repositories)?;
+        }
+
+        // Every repository runs once, or once per ref when refs are given
+        let targets: Vec<(Repository, Option<String>)> = if let Some(graph) = &graph {
+            graph
+                .order()
+                .into_iter()
+                .map(|index| (repositories[index].clone(), None))
+                .collect()
+        } else if self.refs.is_empty() {
+            repositories
+                .iter()
+                .cloned()
+                .map(|repo| (repo, None))
+                .collect()
+        } else {
+            self.refs
+                .iter()
+                .flat_map(|git_ref| {
+                    repositories
+                        .iter()
+                        .map(move |repo| (repo.clone(), Some(git_ref.clone())))
+                })
+                .collect()
+        };
+
+        let show_table = targets.len() > 1;
+        let total = targets.len();
+        let policy = self.failure_policy;
+        let stop_early = policy.stops_early(context.parallel);
+        let mut outcomes: Vec<Outcome> = Vec::new();
+        let mut failure = None;
+
+        if let (true, Some(graph)) = (context.parallel, &graph) {
+            let stop = stop_early.then_some(&policy);
+            outcomes = plan.run_ordered(&repositories, graph, stop).await;
+        } else if context.parallel {
+            // Parallel execution starts every repository at once, so all of them finish
+            let tasks: Vec<_> = targets
                 .into_iter()
-                .map(|repo| {
-                    let command = command.to_string();
-                    let run_root = run_root.clone();
+                .map(|(repo, git_ref)| {
+                    let plan = plan.clone();
                     async move {
-                        let runner = CommandRunner::new();
-                        if let Some(ref run_root) = run_root {
-                            runner
-                                .run_command_with_capture(
-                                    &repo,
-                                    &command,
-                                    Some(run_root.to_string_lossy().as_ref()),
-                                )
-                                .await
-                        } else {
-                            runner
-                                .run_command_with_capture_no_logs(&repo, &command, None)
-                                .await
-                        }
+                        let name = target_name(&repo, git_ref.as_deref());
+                        logging::scope(&name, plan.run_and_record(&repo, git_ref.as_deref(), true))
+                            .await
                     }
                 })
                 .collect();
 
-            futures::future::join_all(tasks).await;
+            outcomes = futures::future::join_all(tasks).await;
         } else {
-            // Sequential execution
-            for repo in repositories {
-                if let Some(ref run_root) = run_root {
-                    runner
-                        .run_command_with_capture(
-                            &repo,
-                            command,
-                            Some(run_root.to_string_lossy().as_ref()),
+            // Sequential execution stops once the run failed, unless told to keep going
+            let mut failed = 0;
+            for (repo, git_ref) in targets {
+                let name = target_name(&repo, git_ref.as_deref());
+                let outcome = match &graph {
+                    Some(graph) => {
+                        plan.skip_for_dependencies(&repo, &repositories, graph, &outcomes)
+                    }
+                    None => None,
+                };
+                let outcome = match outcome {
+                    Some(outcome) => outcome,
+                    None => {
+                        ci::start_group(&name);
+                        let outcome = logging::scope(
+                            &name,
+                            plan.run_and_record(&repo, git_ref.as_deref(), false),
                         )
-                        .await?;
-                } else {
-                    runner.run_command(&repo, command, None).await?;
+                        .await;
+                        ci::end_group();
+                        outcome
+                    }
+                };
+                let success = outcome.0.is_success();
+                outcomes.push(outcome);
+                if success {
+                    continue;
+                }
+                failed += 1;
+                if stop_early && policy.exceeded(failed, total) {
+                    break;
                 }
             }
         }
 
-        Ok(())
-    }
+        // Every repository's error is kept, including non-zero exit codes
+        let mut batch = BatchResult::new(format!("run {}", plan.job.label()), total);
+        let mut results = Vec::new();
+        for (record, result) in outcomes {
+            let result = match result {
+                Ok(_) if record.is_success() => Ok(()),
+                Ok(code) => Err(match &record.exit_code_description {
+                    Some(description) => {
+                        anyhow::anyhow!("Exited with code {} ({})", code, description)
+                    }
+                    None => anyhow::anyhow!("Exited with code {}", code),
+                }),
+                Err(e) => Err(e),
+            };
+            let duration = Duration::from_secs_f64(record.duration_secs);
+            batch.push(&record.repository, result, Some(duration));
+            results.push(record);
+        }
 
-    async fn execute_recipe(&self, context: &CommandContext, recipe_name: &str) -> Result<()> {
-        // Find the recipe
-        let recipe = context
-            .config
-            .find_recipe(recipe_name)
-            .ok_or_else(|| anyhow::anyhow!("Recipe '{}' not found", recipe_name))?;
+        let mut summary = RunSummary::new(results);
+        clear_recovered(&summary);
+        manifest.finish(summary.results.iter().map(ManifestEntry::from).collect());
+        if plan.run_root.is_none() {
+            manifest.save(
+                self.output_dir
+                    .as_deref()
+                    .unwrap_or(Path::new(DEFAULT_LOGS_DIR)),
+            );
+        }
+        if let Some(run_root) = &plan.run_root {
+            summary.write_json(run_root)?;
+            manifest.write(run_root)?;
+
+            if let Some(remote) = &self.upload {
+                match upload_run_dir(remote, run_root).await {
+                    Ok(location) => {
+                        summary.remote_location = Some(location);
+                        summary.write_json(run_root)?;
+                    }
+                    Err(e) => failure = failure.or(Some(e)),
+                }
+            }
+        }
+        if show_table || summary.remote_location.is_some() {
+            summary.print_table();
+        }
 
-        let repositories = context.config.filter_repositories(
-            &context.tag,
-            &context.exclude_tag,
-            context.repos.as_deref(),
-        );
+        let mut report = batch.report();
+        if let Some(output) = summary.remote_location.clone().or_else(|| {
+            plan.run_root
+                .as_ref()
+                .map(|run_root| run_root.display().to_string())
+        }) {
+            report = report.with_output(output);
+        }
+        notify::notify(context.config.notify.as_ref(), &report).await;
+        if let Err(e) = ci::append_step_summary(&summary.to_markdown(plan.job.label())) {
+            logging::message(
+                Level::Warn,
+                format!("Warning: Failed to write step summary: {}", e).yellow(),
+            );
+        }
 
-        if repositories.is_empty() {
-            return Ok(());
+        if !show_table {
+            batch.print_errors();
         }
+        batch.into_result(&policy)?;
 
-        let runner = CommandRunner::new();
+        match failure {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Clear the known-failing mark of repositories that succeeded in this run
+fn clear_recovered(summary: &RunSummary) {
+    let store = StateStore::open_default();
+    let Ok(state) = store.load() else {
+        return;
+    };
+    for result in summary.results.iter().filter(|result| result.is_success()) {
+        let marked = state
+            .repositories
+            .get(&result.repository)
+            .is_some_and(|repo_state| repo_state.known_failing);
+        if !marked {
+            continue;
+        }
+        match store.record_known_failing(&result.repository, false) {
+            Ok(()) => logging::status(
+                Level::Info,
+                &result.repository,
+                "Passing again; no longer marked as known failing".green(),
+            ),
+            Err(e) => logging::status(
+                Level::Warn,
+                &result.repository,
+                format!("Warning: Failed to clear the known-failing mark: {}", e).yellow(),
+            ),
+        }
+    }
+}
+
+impl RunCommand {
+    /// Create a new RunCommand with default settings for testing
+    pub fn new_for_test(command: String, output_dir: String) -> Self {
+        Self::new_command(command, false, Some(PathBuf::from(output_dir)))
+    }
+
+    /// Resolve output, artifact, and cache settings for a job
+    fn plan(
+        &self,
+        job: Job,
+        exit_codes: ExitCodeDescriptions,
+        env: SecretEnv,
+        redactor: Redactor,
+    ) -> Result<RunPlan> {
+        // Use provided output directory or default to "output"
+        let output_dir = self
+            .output_dir
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_LOGS_DIR));
 
         // Setup persistent output directory if saving is enabled
         let run_root = if !self.no_save {
             // Use local time instead of UTC
             let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
-            // Sanitize recipe name for directory name
-            let recipe_suffix = sanitize_for_filename(recipe_name);
-            // Use provided output directory or default to "output"
-            let base_dir = self
-                .output_dir
-                .as_ref()
-                .unwrap_or(&PathBuf::from("output"))
-                .join("runs");
-            let run_dir = base_dir.join(format!("{}_{}", timestamp, recipe_suffix));
+            // Sanitize command or recipe name for directory name
+            let suffix = sanitize_for_filename(job.label());
+            let run_dir = output_dir
+                .join("runs")
+                .join(format!("{}_{}", timestamp, suffix));
             create_dir_all(&run_dir)?;
             Some(run_dir)
         } else {
             None
         };
 
-        if context.parallel {
-            // Parallel execution
-            let tasks: Vec<_> = repositories
-                .into_iter()
-                .map(|repo| {
-                    let recipe_steps = recipe.steps.clone();
-                    let recipe_name = recipe.name.clone();
-                    let run_root = run_root.clone();
-                    async move {
-                        let script_path =
-                            Self::materialize_script(&repo, &recipe_name, &recipe_steps).await?;
-
-                        // Convert absolute script path to relative path from repository directory
-                        let repo_target_dir = repo.get_target_dir();
-                        let repo_dir = Path::new(&repo_target_dir);
-                        let relative_script_path = script_path
-                            .strip_prefix(repo_dir)
-                            .unwrap_or(&script_path)
-                            .to_string_lossy();
-
-                        // Ensure script path is executable from current directory
-                        let executable_script_path = if relative_script_path.contains('/') {
-                            relative_script_path.to_string()
-                        } else {
-                            format!("./{}", relative_script_path)
-                        };
-
-                        let runner = CommandRunner::new();
-                        let result = if let Some(ref run_root) = run_root {
-                            runner
-                                .run_command_with_recipe_context(
-                                    &repo,
-                                    &executable_script_path,
-                                    Some(run_root.to_string_lossy().as_ref()),
-                                    &recipe_name,
-                                    &recipe_steps,
-                                )
-                                .await
-                        } else {
-                            runner
-                                .run_command_with_capture_no_logs(
-                                    &repo,
-                                    &executable_script_path,
-                                    None,
-                                )
-                                .await
-                        };
-                        // Optionally remove script file after execution
-                        let _ = std::fs::remove_file(script_path);
-                        result
-                    }
-                })
-                .collect();
+        // Artifacts declared by the recipe are collected alongside any passed on the command line
+        let mut artifacts = match &job {
+            Job::Recipe(recipe) => recipe.artifacts.clone(),
+            Job::Command(_) => Vec::new(),
+        };
+        for pattern in &self.artifacts {
+            if !artifacts.contains(pattern) {
+                artifacts.push(pattern.clone());
+            }
+        }
+
+        let cache_enabled = match &job {
+            Job::Recipe(recipe) => self.cache || recipe.cache,
+            Job::Command(_) => self.cache,
+        } && !self.no_cache;
+        let cache = cache_enabled.then(|| RunCache::new(output_dir.join("cache")));
+
+        let sinks = match &run_root {
+            Some(run_dir) => self
+                .log_sinks
+                .iter()
+                .map(|sink| sink.build(run_dir))
+                .collect(),
+            None => Vec::new(),
+        };
+
+        Ok(RunPlan {
+            job,
+            sinks,
+            run_root,
+            artifacts,
+            cache,
+            exit_codes,
+            worktrees: self.worktrees.clone(),
+            env,
+            redactor,
+        })
+    }
+
+    /// Copy declared artifacts from a repository into its folder in the run directory
+    ///
+    /// Collection failures are reported as warnings and never fail the run.
+    fn collect_repo_artifacts(repo: &Repository, patterns: &[String], run_root: &Path) {
+        if patterns.is_empty() {
+            return;
+        }
+
+        let logger = Logger;
+        let repo_dir = repo.get_target_dir();
+        let dest_dir = run_root.join(&repo.name).join(ARTIFACTS_DIR);
+
+        match collect_artifacts(Path::new(&repo_dir), patterns, &dest_dir) {
+            Ok(collected) if collected.is_empty() => {
+                logger.warn(repo, "No artifacts matched");
+            }
+            Ok(collected) => {
+                logger.info(repo, &format!("Collected {} artifact(s)", collected.len()));
+            }
+            Err(e) => {
+                logger.warn(repo, &format!("Failed to collect artifacts: {}", e));
+            }
+        }
+    }
+
+    async fn materialize_script(
+        repo: &Repository,
+        recipe_name: &str,
+        steps: &[String],
+    ) -> Result<PathBuf> {
+        let target_dir = repo.get_target_dir();
+        let repo_path = Path::new(&target_dir);
+
+        // Create script directly in the repository root
+        let script_label = sanitize_script_name(recipe_name);
+        let script_path = repo_path.join(format!("{}.script", script_label));
 
-            futures::future::join_all(tasks).await;
+        // Join all steps with newlines to create the script content
+        let script_content = steps.join("\n");
+        let content = if script_content.starts_with("#!") {
+            script_content
         } else {
-            // Sequential execution
-            for repo in repositories {
+            format!("#!/bin/sh\n{}", script_content)
+        };
+
+        std::fs::write(&script_path, content)?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mut perm = std::fs::metadata(&script_path)?.permissions();
+            perm.set_mode(0o750);
+            std::fs::set_permissions(&script_path, perm)?;
+        }
+
+        Ok(script_path)
+    }
+}
+
+impl RunPlan {
+    /// Execute the job in each repository as soon as its dependencies succeeded
+    ///
+    /// Repositories that do not depend on each other run in parallel. One
+    /// whose dependency failed or was skipped is skipped in turn. Once the
+    /// `stop` policy is exceeded, no further repositories are started and the
+    /// ones still running are awaited.
+    async fn run_ordered(
+        &self,
+        repositories: &[Repository],
+        graph: &DependencyGraph,
+        stop: Option<&FailurePolicy>,
+    ) -> Vec<Outcome> {
+        let mut records: Vec<Option<Outcome>> = (0..repositories.len()).map(|_| None).collect();
+        let mut started = vec![false; repositories.len()];
+        let mut running = FuturesUnordered::new();
+
+        loop {
+            let failed = records
+                .iter()
+                .flatten()
+                .filter(|(record, _)| !record.is_success())
+                .count();
+            let stopped = stop.is_some_and(|policy| policy.exceeded(failed, repositories.len()));
+
+            let mut skipped = false;
+            for (index, repo) in repositories.iter().enumerate() {
+                let dependencies = graph.dependencies(index);
+                if stopped || started[index] || dependencies.iter().any(|&d| records[d].is_none()) {
+                    continue;
+                }
+                started[index] = true;
+
+                let failed: Vec<&str> = dependencies
+                    .iter()
+                    .filter(|&&d| {
+                        !records[d]
+                            .as_ref()
+                            .is_some_and(|(record, _)| record.is_success())
+                    })
+                    .map(|&d| repositories[d].name.as_str())
+                    .collect();
+                if failed.is_empty() {
+                    running.push(async move {
+                        let outcome =
+                            logging::scope(&repo.name, self.run_and_record(repo, None, true)).await;
+                        (index, outcome)
+                    });
+                    continue;
+                }
+
+                records[index] = Some(self.skipped(repo, &failed));
+                skipped = true;
+            }
+
+            // Skipping may have settled the dependencies of repositories seen earlier
+            if skipped {
+                continue;
+            }
+            match running.next().await {
+                Some((index, outcome)) => records[index] = Some(outcome),
+                None => break,
+            }
+        }
+
+        let failed = records
+            .iter()
+            .flatten()
+            .filter(|(record, _)| !record.is_success())
+            .count();
+
+        // Report in the order the repositories were selected
+        records
+            .into_iter()
+            .zip(repositories)
+            .map(|(outcome, repo)| outcome.unwrap_or_else(|| self.stopped(repo, failed)))
+            .collect()
+    }
+
+    /// The outcome of a repository skipped because its dependencies in `failed` did not succeed
+    fn skipped(&self, repo: &Repository, failed: &[&str]) -> Outcome {
+        self.not_run(
+            repo,
+            format!("Skipped: {} did not succeed", failed.join(", ")),
+        )
+    }
+
+    /// The outcome of a repository that was not run, for `reason`
+    fn not_run(&self, repo: &Repository, reason: String) -> Outcome {
+        logging::status(Level::Warn, &repo.name, reason.yellow());
+        let result = Err(anyhow::anyhow!(reason));
+        let record =
+            RepoRunResult::new(&repo.name, &result, Duration::ZERO, None, &self.exit_codes);
+        (record, result)
+    }
+
+    /// Skip `repo` in a sequential ordered run when one of its dependencies did not succeed
+    fn skip_for_dependencies(
+        &self,
+        repo: &Repository,
+        repositories: &[Repository],
+        graph: &DependencyGraph,
+        outcomes: &[Outcome],
+    ) -> Option<Outcome> {
+        let index = repositories.iter().position(|r| r.name == repo.name)?;
+        let failed: Vec<&str> = graph
+            .dependencies(index)
+            .iter()
+            .map(|&d| repositories[d].name.as_str())
+            .filter(|name| {
+                !outcomes
+                    .iter()
+                    .any(|(record, _)| record.repository == *name && record.is_success())
+            })
+            .collect();
+        (!failed.is_empty()).then(|| self.skipped(repo, &failed))
+    }
+
+    /// The outcome of a repository not started because the run failed with `failed` failures
+    fn stopped(&self, repo: &Repository, failed: usize) -> Outcome {
+        self.not_run(
+            repo,
+            format!("Skipped: run stopped after {} failed", failed),
+        )
+    }
+
+    /// Execute the job in one repository, recording its outcome for the run summary
+    async fn run_and_record(
+        &self,
+        repo: &Repository,
+        git_ref: Option<&str>,
+        parallel: bool,
+    ) -> Outcome {
+        let name = target_name(repo, git_ref);
+        events::repo_started(&name);
+        let started = Instant::now();
+        let result = match (git_ref, &self.worktrees) {
+            (Some(git_ref), _) => self.run_at_ref(repo, git_ref, parallel).await,
+            (None, Some(worktrees_dir)) => match git::open_worktree(repo, worktrees_dir) {
+                Ok(checkout) => self.run_in_repository(&checkout, parallel).await,
+                Err(e) => Err(e),
+            },
+            (None, None) => self.run_in_repository(repo, parallel).await,
+        };
+        events::repo_finished(
+            &name,
+            result.as_ref().ok().copied(),
+            started.elapsed(),
+            result.as_ref().err().map(|e| format!("{:#}", e)),
+        );
+        let log_dir = self
+            .run_root
+            .as_ref()
+            .map(|root| root.join(&name))
+            .filter(|dir| dir.exists());
+        if let Some(log_dir) = &log_dir {
+            self.record_settings(repo, log_dir, git_ref, parallel);
+        }
+        let record =
+            RepoRunResult::new(&name, &result, started.elapsed(), log_dir, &self.exit_codes);
+        (record, result)
+    }
+
+    /// Add the settings a retry by `repos triage` needs to a repository's `metadata.json`
+    fn record_settings(
+        &self,
+        repo: &Repository,
+        log_dir: &Path,
+        git_ref: Option<&str>,
+        parallel: bool,
+    ) {
+        let path = log_dir.join("metadata.json");
+        let Some(mut metadata) = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        else {
+            return;
+        };
+
+        if let Some(git_ref) = git_ref {
+            metadata["ref"] = serde_json::json!(git_ref);
+        }
+        if let Some(worktrees_dir) = &self.worktrees {
+            metadata["worktrees_dir"] = serde_json::json!(worktrees_dir);
+        }
+        if !self.env.vars().is_empty() {
+            // Only the names; the values are resolved again from the recipe
+            let names: Vec<&str> = self
+                .env
+                .vars()
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect();
+            metadata["env"] = serde_json::json!(names);
+        }
+        metadata["parallel"] = serde_json::json!(parallel);
+
+        let written = serde_json::to_string_pretty(&metadata)
+            .map_err(anyhow::Error::from)
+            .and_then(|content| Ok(std::fs::write(&path, content)?));
+        if let Err(e) = written {
+            Logger.warn(
+                repo,
+                &format!("Failed to record run settings in {}: {}", path.display(), e),
+            );
+        }
+    }
+
+    /// Execute the job in a temporary worktree of a repository checked out at `git_ref`
+    ///
+    /// The repository's own working copy is never touched, and the worktree is
+    /// removed again once the job has finished.
+    async fn run_at_ref(&self, repo: &Repository, git_ref: &str, parallel: bool) -> Result<i32> {
+        let worktree = git::TemporaryWorktree::create(&repo.get_target_dir(), git_ref)?;
+
+        // Logs, artifacts, and cache entries are kept apart per ref
+        let mut checkout = repo.clone();
+        checkout.name = target_name(repo, Some(git_ref));
+        checkout.path = Some(worktree.path().to_string_lossy().to_string());
+        Logger.debug(
+            &checkout,
+            &format!("Checked out {} in {}", git_ref, worktree.path().display()),
+        );
+
+        self.run_in_repository(&checkout, parallel).await
+    }
+
+    /// Execute the job in one repository and return its exit code
+    ///
+    /// Sequential runs without saved output or events stream command output
+    /// directly to the terminal; every other mode captures it.
+    async fn run_in_repository(&self, repo: &Repository, parallel: bool) -> Result<i32> {
+        let runner = CommandRunner::new()
+            .with_exit_codes(self.exit_codes.clone())
+            .with_env(self.env.clone())
+            .with_redactor(self.redactor.clone())
+            .with_sinks(self.sinks.clone());
+        let log_dir = self
+            .run_root
+            .as_ref()
+            .map(|p| p.to_string_lossy().to_string());
+
+        let cache_key = self.cache.as_ref().and_then(|_| cache_key(repo, &self.job));
+        if let Some(key) = &cache_key {
+            runner.logger().debug(repo, &format!("Cache key {}", key));
+        }
+        if let (Some(cache), Some(key)) = (&self.cache, &cache_key)
+            && let Some(cached) = cache.lookup(key)
+        {
+            runner.logger().info(
+                repo,
+                &format!(
+                    "Reusing cached result from {} (exit code {})",
+                    cached.timestamp, cached.exit_code
+                ),
+            );
+            if let Some(run_root) = &self.run_root {
+                self.write_cached_logs(repo, &cached, run_root, &runner)
+                    .await?;
+            }
+            return Ok(cached.exit_code);
+        }
+
+        let result = match &self.job {
+            Job::Command(command) => {
+                if log_dir.is_some() {
+                    runner
+                        .run_command_with_capture(repo, command, log_dir.as_deref())
+                        .await
+                } else if parallel || cache_key.is_some() || events::enabled() {
+                    runner
+                        .run_command_with_capture_no_logs(repo, command, None)
+                        .await
+                } else {
+                    runner
+                        .run_command(repo, command, None)
+                        .await
+                        .map(|_| (String::new(), String::new(), 0))
+                }
+            }
+            Job::Recipe(recipe) => {
+                let (steps, skipped) = recipe.steps_for(repo);
+                for step in &skipped {
+                    if let Some(condition) = &step.condition {
+                        runner.logger().info(
+                            repo,
+                            &format!("Skipping step '{}' (if {})", step.run.trim(), condition),
+                        );
+                    }
+                }
                 let script_path =
-                    Self::materialize_script(&repo, &recipe.name, &recipe.steps).await?;
+                    RunCommand::materialize_script(repo, &recipe.name, &steps).await?;
 
                 // Convert absolute script path to relative path from repository directory
                 let repo_target_dir = repo.get_target_dir();
@@ -254,61 +915,291 @@ impl RunCommand {
                     format!("./{}", relative_script_path)
                 };
 
-                let result = if let Some(ref run_root) = run_root {
+                let result = if log_dir.is_some() {
                     runner
                         .run_command_with_recipe_context(
-                            &repo,
+                            repo,
                             &executable_script_path,
-                            Some(run_root.to_string_lossy().as_ref()),
+                            log_dir.as_deref(),
                             &recipe.name,
-                            &recipe.steps,
+                            &steps,
+                            &skipped,
                         )
                         .await
                 } else {
                     runner
-                        .run_command_with_capture_no_logs(&repo, &executable_script_path, None)
+                        .run_command_with_capture_no_logs(repo, &executable_script_path, None)
                         .await
                 };
                 // Optionally remove script file after execution
                 let _ = std::fs::remove_file(script_path);
-                result?;
+                result
+            }
+        };
+
+        if let Some(run_root) = &self.run_root {
+            RunCommand::collect_repo_artifacts(repo, &self.artifacts, run_root);
+        }
+
+        let (stdout, stderr, exit_code) = result?;
+
+        // Only successful runs are worth replaying
+        if let (Some(cache), Some(key)) = (&self.cache, &cache_key)
+            && exit_code == 0
+        {
+            let cached = CachedResult::new(&repo.name, exit_code, stdout, stderr);
+            if let Err(e) = cache.store(key, &cached) {
+                runner
+                    .logger()
+                    .warn(repo, &format!("Failed to cache result: {}", e));
             }
         }
 
-        Ok(())
+        Ok(exit_code)
     }
 
-    async fn materialize_script(
-        repo: &crate::config::Repository,
-        recipe_name: &str,
-        steps: &[String],
-    ) -> Result<PathBuf> {
-        let target_dir = repo.get_target_dir();
-        let repo_path = Path::new(&target_dir);
+    /// Write the logs of a cached result into the current run directory and sinks
+    async fn write_cached_logs(
+        &self,
+        repo: &Repository,
+        cached: &CachedResult,
+        run_root: &Path,
+        runner: &CommandRunner,
+    ) -> Result<()> {
+        let mut metadata = serde_json::json!({
+            "exit_code": cached.exit_code,
+            "exit_code_description": self.exit_codes.describe(cached.exit_code),
+            "repository": repo.name,
+            "timestamp": chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            "cached": true,
+            "cached_timestamp": cached.timestamp,
+        });
+        match &self.job {
+            Job::Command(command) => metadata["command"] = serde_json::json!(command),
+            Job::Recipe(recipe) => {
+                let (steps, skipped) = recipe.steps_for(repo);
+                metadata["recipe"] = serde_json::json!(recipe.name);
+                metadata["recipe_steps"] = serde_json::json!(steps);
+                if !skipped.is_empty() {
+                    metadata["skipped_steps"] = serde_json::json!(skipped);
+                }
+            }
+        }
 
-        // Create script directly in the repository root
-        let script_label = sanitize_script_name(recipe_name);
-        let script_path = repo_path.join(format!("{}.script", script_label));
+        // Results cached before redaction was configured may still hold secrets
+        self.redactor.redact_json(&mut metadata);
+        runner
+            .save_output(
+                repo,
+                run_root,
+                &metadata,
+                &self.redactor.redact(&cached.stdout),
+                &self.redactor.redact(&cached.stderr),
+            )
+            .await
+    }
+}
 
-        // Join all steps with newlines to create the script content
-        let script_content = steps.join("\n");
-        let content = if script_content.starts_with("#!") {
-            script_content
-        } else {
-            format!("#!/bin/sh\n{}", script_content)
+/// Name a repository is reported and logged under, qualified by the ref it runs against
+fn target_name(repo: &Repository, git_ref: Option<&str>) -> String {
+    match git_ref {
+        Some(git_ref) => format!("{}@{}", repo.name, sanitize_for_filename(git_ref)),
+        None => repo.name.clone(),
+    }
+}
+
+/// Compute the cache key for a repository, or `None` when its state is not reproducible
+///
+/// Repositories with uncommitted changes are never cached because HEAD alone
+/// does not describe their content.
+fn cache_key(repo: &Repository, job: &Job) -> Option<String> {
+    let repo_dir = repo.get_target_dir();
+    if git::has_changes(&repo_dir).unwrap_or(true) {
+        return None;
+    }
+    let head = git::get_head_commit(&repo_dir).ok()?;
+    Some(RunCache::key(&repo.name, &head, &job.cache_input()))
+}
+
+/// Arguments of `repos run`
+#[derive(Debug, Args)]
+pub struct RunArgs {
+    /// Command to execute
+    #[arg(value_name = "COMMAND", help = "Command to execute")]
+    pub command: Option<String>,
+
+    /// Name of a recipe defined in repos.yaml
+    #[arg(long, help = "Name of a recipe defined in repos.yaml")]
+    pub recipe: Option<String>,
+
+    /// Specific repository names to run command in (if not provided, uses tag filter or all repos)
+    pub repos: Vec<String>,
+
+    /// Configuration file path (can be specified multiple times to combine fleets)
+    #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+    pub config: Vec<String>,
+
+    /// Filter repositories by tag (can be specified multiple times)
+    #[arg(short, long)]
+    pub tag: Vec<String>,
+
+    /// Exclude repositories with these tags (can be specified multiple times)
+    #[arg(short = 'e', long)]
+    pub exclude_tag: Vec<String>,
+
+    /// Execute operations in parallel
+    #[arg(short, long)]
+    pub parallel: bool,
+
+    /// Don't save command outputs to files
+    #[arg(long)]
+    pub no_save: bool,
+
+    /// Custom directory for output files (default: output)
+    #[arg(long)]
+    pub output_dir: Option<String>,
+
+    /// Glob pattern of files to collect into the run output after execution (can be specified multiple times)
+    #[arg(long = "artifact", value_name = "GLOB")]
+    pub artifacts: Vec<String>,
+
+    /// Reuse results of previous successful runs when HEAD and the command are unchanged
+    #[arg(long)]
+    pub cache: bool,
+
+    /// Always execute, even if caching is enabled by flag or recipe
+    #[arg(long, conflicts_with = "cache")]
+    pub no_cache: bool,
+
+    /// Upload the run directory to the configured `artifacts_remote` after completion
+    #[arg(long, conflicts_with = "no_save")]
+    pub upload: bool,
+
+    /// Run against a branch or tag in a temporary worktree instead of the current checkout (can be specified multiple times)
+    #[arg(long = "ref", value_name = "REF")]
+    pub refs: Vec<String>,
+
+    /// Run in a detached worktree per repository under .repos/worktrees, kept for `repos pr --use-worktree`
+    #[arg(long, conflicts_with = "refs")]
+    pub use_worktree: bool,
+
+    /// Pick repositories interactively from the filtered list before executing
+    #[arg(short, long)]
+    pub interactive: bool,
+
+    /// Run repositories after those listed in their `depends_on`; with --parallel, independent ones run concurrently
+    #[arg(long, conflicts_with = "refs")]
+    pub ordered: bool,
+
+    /// Save the branch, commit and uncommitted changes of each repository first, for `repos rollback`
+    #[arg(long, conflicts_with_all = ["refs", "use_worktree"])]
+    pub checkpoint: bool,
+
+    /// Start no further repositories once the run failed; with --parallel, only together with --ordered
+    #[arg(long)]
+    pub fail_fast: bool,
+
+    /// Run every repository even after failures, also without --parallel
+    #[arg(long, conflicts_with = "fail_fast")]
+    pub keep_going: bool,
+
+    /// Number of failed repositories tolerated before the run fails
+    #[arg(long, value_name = "N")]
+    pub max_failures: Option<usize>,
+
+    /// Percentage of failed repositories tolerated before the run fails, e.g. 20%
+    #[arg(long, value_name = "PERCENT")]
+    pub failure_threshold: Option<String>,
+
+    /// Also run repositories marked as known failing with `repos triage`
+    #[arg(long)]
+    pub include_known_failing: bool,
+}
+
+impl RunArgs {
+    /// Validate the arguments and run `repos run`
+    pub async fn run(self, globals: &GlobalOptions) -> Result<()> {
+        let Self {
+            command,
+            recipe,
+            repos,
+            config,
+            tag,
+            exclude_tag,
+            parallel,
+            no_save,
+            output_dir,
+            artifacts,
+            cache,
+            no_cache,
+            upload,
+            refs,
+            use_worktree,
+            interactive,
+            ordered,
+            checkpoint,
+            fail_fast,
+            keep_going,
+            max_failures,
+            failure_threshold,
+            include_known_failing,
+        } = self;
+        let config = Config::load_all(&config)?;
+
+        // Validate run command arguments using centralized validators
+        validators::validate_run_args(&command, &recipe)?;
+        validators::validate_tag_filters(&tag)?;
+        validators::validate_tag_filters(&exclude_tag)?;
+        validators::validate_repository_names(&repos)?;
+        validators::validate_output_directory(&output_dir)?;
+        validators::validate_fail_fast(fail_fast, parallel, ordered)?;
+        for git_ref in &refs {
+            validators::validate_branch_name(&Some(git_ref.clone()))?;
+        }
+        let failure_policy = FailurePolicy {
+            fail_fast,
+            keep_going,
+            ..FailurePolicy::from_limits(max_failures, failure_threshold.as_deref())?
         };
 
-        std::fs::write(&script_path, content)?;
+        let mut context = globals
+            .context(config, tag, exclude_tag, repos)
+            .with_parallel(parallel);
+        if !include_known_failing {
+            context.skip_known_failing();
+        }
+        if interactive {
+            context.select_interactively()?;
+        }
 
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            let mut perm = std::fs::metadata(&script_path)?.permissions();
-            perm.set_mode(0o750);
-            std::fs::set_permissions(&script_path, perm)?;
+        let run_command = if let Some(cmd) = command {
+            RunCommand::new_command(cmd, no_save, output_dir.map(PathBuf::from))
+        } else if let Some(recipe_name) = recipe {
+            RunCommand::new_recipe(recipe_name, no_save, output_dir.map(PathBuf::from))
+        } else {
+            return Ok(());
+        };
+        let mut run_command = run_command
+            .with_artifacts(artifacts)
+            .with_cache(cache, no_cache)
+            .with_refs(refs)
+            .with_ordered(ordered)
+            .with_checkpoint(checkpoint)
+            .with_failure_policy(failure_policy)
+            .with_log_sinks(context.config.log_sinks.clone());
+        if use_worktree {
+            run_command = run_command
+                .with_worktrees(crate::state::StateStore::open_default().worktrees_dir());
         }
 
-        Ok(script_path)
+        if upload {
+            let remote = context.config.artifacts_remote.clone().ok_or_else(|| {
+                anyhow::anyhow!("--upload requires `artifacts_remote` in the config file")
+            })?;
+            run_command = run_command.with_upload(remote);
+        }
+
+        run_command.execute(&context).await
     }
 }
 
@@ -328,21 +1219,40 @@ mod tests {
 
         let recipe = Recipe {
             name: "test-recipe".to_string(),
-            steps: vec!["echo step1".to_string(), "echo step2".to_string()],
+            steps: vec!["echo step1".into(), "echo step2".into()],
+            artifacts: vec![],
+            cache: false,
+            env: Default::default(),
         };
 
         let failing_recipe = Recipe {
             name: "failing-recipe".to_string(),
-            steps: vec![
-                "echo step1".to_string(),
-                "false".to_string(),
-                "echo step3".to_string(),
-            ],
+            steps: vec!["echo step1".into(), "false".into(), "echo step3".into()],
+            artifacts: vec![],
+            cache: false,
+            env: Default::default(),
         };
 
         Config {
             repositories: vec![repo1],
             recipes: vec![recipe, failing_recipe],
+            artifacts_remote: None,
+            log_sinks: Vec::new(),
+            path_strategy: Default::default(),
+            root: None,
+            files: Vec::new(),
+            branch_protection: Vec::new(),
+            repository_settings: Vec::new(),
+            post_clone: Vec::new(),
+            pr: None,
+            provider: None,
+            provider_url: None,
+            git: Default::default(),
+            exit_codes: Default::default(),
+            notify: None,
+            profiles: Default::default(),
+            secrets: None,
+            profile_token: None,
         }
     }
 
@@ -386,6 +1296,23 @@ mod tests {
         let config = Config {
             repositories: vec![],
             recipes: vec![],
+            artifacts_remote: None,
+            log_sinks: Vec::new(),
+            path_strategy: Default::default(),
+            root: None,
+            files: Vec::new(),
+            branch_protection: Vec::new(),
+            repository_settings: Vec::new(),
+            post_clone: Vec::new(),
+            pr: None,
+            provider: None,
+            provider_url: None,
+            git: Default::default(),
+            exit_codes: Default::default(),
+            notify: None,
+            profiles: Default::default(),
+            secrets: None,
+            profile_token: None,
         };
         let context = create_test_context(config);
 
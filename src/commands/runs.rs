@@ -0,0 +1,309 @@
+//! Runs command implementation, browsing the output saved by past `repos run` invocations
+
+use super::{Command, CommandContext};
+use crate::config::Config;
+use crate::constants;
+use crate::utils::run_summary::RepoRunResult;
+use crate::utils::{RunRecord, find_run, list_runs};
+use anyhow::Result;
+use async_trait::async_trait;
+use clap::Subcommand;
+use colored::*;
+use std::path::{Path, PathBuf};
+
+/// Runs list command showing saved runs, newest first
+pub struct RunsListCommand {
+    /// Directory the runs were saved to
+    pub output_dir: PathBuf,
+    /// Show at most this many runs
+    pub limit: Option<usize>,
+}
+
+/// Runs show command printing the metadata and captured output of a run
+pub struct RunsShowCommand {
+    pub output_dir: PathBuf,
+    /// Run id, a unique prefix of it, or `latest`
+    pub id: String,
+    /// Only show this repository
+    pub repository: Option<String>,
+    /// Only show repositories whose command failed
+    pub failed: bool,
+}
+
+#[async_trait]
+impl Command for RunsListCommand {
+    async fn execute(&self, _context: &CommandContext) -> Result<()> {
+        let mut runs = list_runs(&self.output_dir)?;
+        if runs.is_empty() {
+            println!(
+                "{}",
+                format!("No saved runs in {}", self.output_dir.display()).yellow()
+            );
+            return Ok(());
+        }
+        if let Some(limit) = self.limit {
+            runs.truncate(limit);
+        }
+
+        let id_width = runs
+            .iter()
+            .map(|run| run.id.len())
+            .max()
+            .unwrap_or(0)
+            .max("RUN".len());
+        println!(
+            "{:<id_width$}  {:<19}  {:>5}  {:>5}  {:>6}  {}",
+            "RUN".bold(),
+            "STARTED".bold(),
+            "TOTAL".bold(),
+            "OK".bold(),
+            "FAILED".bold(),
+            "COMMAND".bold(),
+        );
+        for run in &runs {
+            let started = run
+                .started
+                .map(|started| started.format("%Y-%m-%d %H:%M:%S").to_string())
+                .unwrap_or_else(|| "-".to_string());
+            let failed = if run.summary.failed > 0 {
+                run.summary.failed.to_string().red()
+            } else {
+                run.summary.failed.to_string().normal()
+            };
+            println!(
+                "{:<id_width$}  {:<19}  {:>5}  {:>5}  {:>6}  {}",
+                run.id.cyan(),
+                started,
+                run.summary.total,
+                run.summary.succeeded.to_string().green(),
+                failed,
+                run.label,
+            );
+        }
+        Ok(())
+    }
+}
+
+impl RunsShowCommand {
+    /// Results of the run selected by the repository and `--failed` filters
+    fn selected<'a>(&self, run: &'a RunRecord) -> Result<Vec<&'a RepoRunResult>> {
+        if let Some(repository) = &self.repository
+            && !run
+                .summary
+                .results
+                .iter()
+                .any(|result| &result.repository == repository)
+        {
+            anyhow::bail!(
+                "Repository '{}' is not part of run '{}'",
+                repository,
+                run.id
+            );
+        }
+
+        Ok(run
+            .summary
+            .results
+            .iter()
+            .filter(|result| {
+                self.repository
+                    .as_ref()
+                    .is_none_or(|repository| &result.repository == repository)
+            })
+            .filter(|result| !self.failed || !result.is_success())
+            .collect())
+    }
+}
+
+#[async_trait]
+impl Command for RunsShowCommand {
+    async fn execute(&self, _context: &CommandContext) -> Result<()> {
+        let run = find_run(&self.output_dir, &self.id)?;
+
+        println!("{} {}", "Run".bold(), run.id.cyan());
+        println!("Command:      {}", run.label);
+        if let Some(started) = run.started {
+            println!("Started:      {}", started.format("%Y-%m-%d %H:%M:%S"));
+        }
+        println!("Directory:    {}", run.dir.display());
+        println!(
+            "Repositories: {} ({} successful, {} failed)",
+            run.summary.total,
+            run.summary.succeeded.to_string().green(),
+            run.summary.failed.to_string().red()
+        );
+        if let Some(location) = &run.summary.remote_location {
+            println!("Uploaded to:  {}", location.cyan());
+        }
+
+        let selected = self.selected(&run)?;
+        if selected.is_empty() && self.failed {
+            println!();
+            println!("{}", "No failed repositories in this run".green());
+        }
+        for result in selected {
+            print_result(&run.repository_dir(&result.repository), result);
+        }
+        Ok(())
+    }
+}
+
+/// Print the outcome and captured output of one repository
+fn print_result(repo_dir: &Path, result: &RepoRunResult) {
+    println!();
+    let exit = match (result.exit_code, &result.exit_code_description) {
+        (Some(code), Some(description)) => format!("exit {} ({})", code, description),
+        (Some(code), None) => format!("exit {}", code),
+        (None, _) => "did not complete".to_string(),
+    };
+    let exit = if result.is_success() {
+        exit.green()
+    } else {
+        exit.red()
+    };
+    println!(
+        "{} {} {}, {:.1}s",
+        "==>".bold(),
+        result.repository.cyan().bold(),
+        exit,
+        result.duration_secs
+    );
+    if let Some(error) = &result.error {
+        println!("{}", error.red());
+    }
+
+    for (stream, file) in [("stdout", "stdout.log"), ("stderr", "stderr.log")] {
+        let Ok(content) = std::fs::read_to_string(repo_dir.join(file)) else {
+            continue;
+        };
+        if content.trim().is_empty() {
+            continue;
+        }
+        println!("{}", format!("--- {} ---", stream).dimmed());
+        print!("{}", content);
+        if !content.ends_with('\n') {
+            println!();
+        }
+    }
+}
+
+/// Subcommands of `repos runs`
+#[derive(Debug, Subcommand)]
+pub enum RunsAction {
+    /// List saved runs, newest first, with their success and failure counts
+    List {
+        /// Show at most this many runs
+        #[arg(short = 'n', long)]
+        limit: Option<usize>,
+
+        /// Directory the runs were saved to (default: output)
+        #[arg(long)]
+        output_dir: Option<String>,
+    },
+
+    /// Print the metadata and captured output of a run
+    Show {
+        /// Run id from `repos runs list`, a unique prefix of it, or `latest`
+        id: String,
+
+        /// Only show this repository
+        repository: Option<String>,
+
+        /// Only show repositories whose command failed
+        #[arg(long)]
+        failed: bool,
+
+        /// Directory the runs were saved to (default: output)
+        #[arg(long)]
+        output_dir: Option<String>,
+    },
+}
+
+impl RunsAction {
+    /// Validate the arguments and run `repos runs <action>`
+    pub async fn run(self) -> Result<()> {
+        // Runs are read from the output directory, so no config is needed
+        let context = CommandContext::new(Config::new());
+        let output_dir = |output_dir: Option<String>| {
+            PathBuf::from(
+                output_dir.unwrap_or_else(|| constants::config::DEFAULT_LOGS_DIR.to_string()),
+            )
+        };
+        match self {
+            RunsAction::List {
+                limit,
+                output_dir: dir,
+            } => {
+                RunsListCommand {
+                    output_dir: output_dir(dir),
+                    limit,
+                }
+                .execute(&context)
+                .await?
+            }
+            RunsAction::Show {
+                id,
+                repository,
+                failed,
+                output_dir: dir,
+            } => {
+                RunsShowCommand {
+                    output_dir: output_dir(dir),
+                    id,
+                    repository,
+                    failed,
+                }
+                .execute(&context)
+                .await?
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{ExitCodeDescriptions, RunSummary};
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn show(repository: Option<&str>, failed: bool) -> RunsShowCommand {
+        RunsShowCommand {
+            output_dir: PathBuf::new(),
+            id: "latest".to_string(),
+            repository: repository.map(str::to_string),
+            failed,
+        }
+    }
+
+    #[test]
+    fn test_show_filters() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().join("runs/20240101-090000_make");
+        std::fs::create_dir_all(&dir).unwrap();
+        let codes = ExitCodeDescriptions::default();
+        RunSummary::new(vec![
+            RepoRunResult::new("api", &Ok(0), Duration::ZERO, None, &codes),
+            RepoRunResult::new("web", &Ok(2), Duration::ZERO, None, &codes),
+        ])
+        .write_json(&dir)
+        .unwrap();
+        let run = RunRecord::load(&dir).unwrap();
+
+        let names = |command: RunsShowCommand| -> Vec<String> {
+            command
+                .selected(&run)
+                .unwrap()
+                .iter()
+                .map(|result| result.repository.clone())
+                .collect()
+        };
+        assert_eq!(names(show(None, false)), vec!["api", "web"]);
+        assert_eq!(names(show(None, true)), vec!["web"]);
+        assert_eq!(names(show(Some("api"), false)), vec!["api"]);
+        assert!(names(show(Some("api"), true)).is_empty());
+        assert!(show(Some("cli"), false).selected(&run).is_err());
+    }
+}
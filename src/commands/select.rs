@@ -0,0 +1,188 @@
+//! Select command implementation
+
+use super::{Command, CommandContext, GlobalOptions, validators};
+use crate::config::{Config, Provider, Repository};
+use crate::constants;
+use anyhow::Result;
+use async_trait::async_trait;
+use clap::Args;
+use std::io::Write;
+
+/// Select command printing only the names of the repositories a filter selects
+///
+/// The output is meant to be piped into other tools, or back into `repos`
+/// with `--repos-from`.
+#[derive(Default)]
+pub struct SelectCommand {
+    /// Only repositories whose name matches one of these glob patterns
+    pub names: Vec<glob::Pattern>,
+    /// Only repositories hosted on this provider
+    pub provider: Option<Provider>,
+    /// Print the selected repositories as JSON instead of their names
+    pub json: bool,
+    /// Terminate names with NUL instead of a newline
+    pub null_separated: bool,
+}
+
+impl SelectCommand {
+    fn matches(&self, repo: &Repository) -> bool {
+        (self.names.is_empty() || self.names.iter().any(|name| name.matches(&repo.name)))
+            && self
+                .provider
+                .is_none_or(|provider| repo.provider() == provider)
+    }
+
+    fn select(&self, context: &CommandContext) -> Vec<Repository> {
+        context
+            .config
+            .filter_repositories(&context.tag, &context.exclude_tag, context.repos.as_deref())
+            .into_iter()
+            .filter(|repo| self.matches(repo))
+            .collect()
+    }
+}
+
+#[async_trait]
+impl Command for SelectCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = self.select(context);
+
+        let output = if self.json {
+            format!("{}\n", serde_json::to_string_pretty(&repositories)?)
+        } else {
+            let separator = if self.null_separated { '\0' } else { '\n' };
+            repositories
+                .iter()
+                .map(|repo| format!("{}{}", repo.name, separator))
+                .collect()
+        };
+
+        let mut stdout = std::io::stdout().lock();
+        stdout.write_all(output.as_bytes())?;
+        stdout.flush()?;
+        Ok(())
+    }
+}
+
+/// Arguments of `repos select`
+#[derive(Debug, Args)]
+pub struct SelectArgs {
+    /// Only repositories whose name matches one of these glob patterns (e.g. 'api-*')
+    pub patterns: Vec<String>,
+
+    /// Configuration file path (can be specified multiple times to combine fleets)
+    #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+    pub config: Vec<String>,
+
+    /// Filter repositories by tag (can be specified multiple times)
+    #[arg(short, long)]
+    pub tag: Vec<String>,
+
+    /// Exclude repositories with these tags (can be specified multiple times)
+    #[arg(short = 'e', long)]
+    pub exclude_tag: Vec<String>,
+
+    /// Only repositories hosted on this provider (github, gitea, forgejo or azure)
+    #[arg(long)]
+    pub provider: Option<String>,
+
+    /// Print the selected repositories as JSON instead of their names
+    #[arg(long)]
+    pub json: bool,
+
+    /// End each name with a NUL byte instead of a newline, for `xargs -0`
+    #[arg(short = '0', long, conflicts_with = "json")]
+    pub null: bool,
+}
+
+impl SelectArgs {
+    /// Validate the arguments and run `repos select`
+    pub async fn run(self, globals: &GlobalOptions) -> Result<()> {
+        let Self {
+            patterns,
+            config,
+            tag,
+            exclude_tag,
+            provider,
+            json,
+            null,
+        } = self;
+        let config = Config::load_all(&config)?;
+
+        validators::validate_tag_filters(&tag)?;
+        validators::validate_tag_filters(&exclude_tag)?;
+
+        let names = patterns
+            .iter()
+            .map(|pattern| {
+                glob::Pattern::new(pattern)
+                    .map_err(|e| anyhow::anyhow!("Invalid pattern '{}': {}", pattern, e))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let context = globals.context(config, tag, exclude_tag, Vec::new());
+        SelectCommand {
+            names,
+            provider: provider.map(|p| p.parse()).transpose()?,
+            json,
+            null_separated: null,
+        }
+        .execute(&context)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn context() -> CommandContext {
+        let mut config = Config::new();
+        for (name, tag, provider) in [
+            ("api-users", "backend", Provider::GitHub),
+            ("api-orders", "backend", Provider::Gitea),
+            ("web", "frontend", Provider::GitHub),
+        ] {
+            let mut repo =
+                Repository::new(name.to_string(), format!("git@github.com:org/{}.git", name));
+            repo.tags = vec![tag.to_string()];
+            repo.provider = Some(provider);
+            config.repositories.push(repo);
+        }
+        CommandContext {
+            config,
+            tag: vec![],
+            exclude_tag: vec![],
+            parallel: false,
+            repos: None,
+        }
+    }
+
+    fn names(command: &SelectCommand, context: &CommandContext) -> Vec<String> {
+        command
+            .select(context)
+            .into_iter()
+            .map(|repo| repo.name)
+            .collect()
+    }
+
+    #[test]
+    fn test_select_filters() {
+        let mut context = context();
+        let by_glob = SelectCommand {
+            names: vec![glob::Pattern::new("api-*").unwrap()],
+            ..Default::default()
+        };
+        assert_eq!(names(&by_glob, &context), vec!["api-users", "api-orders"]);
+
+        let by_provider = SelectCommand {
+            provider: Some(Provider::GitHub),
+            ..Default::default()
+        };
+        assert_eq!(names(&by_provider, &context), vec!["api-users", "web"]);
+
+        context.tag = vec!["backend".to_string()];
+        assert_eq!(names(&by_provider, &context), vec!["api-users"]);
+    }
+}
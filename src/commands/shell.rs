@@ -0,0 +1,248 @@
+//! Shell command implementation
+
+use super::{Command, CommandContext, GlobalOptions, validators};
+use crate::config::{Config, Repository};
+use crate::{constants, logging};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use clap::Args;
+use colored::*;
+use log::Level;
+use std::path::Path;
+use std::process::ExitStatus;
+
+/// Environment variable holding the repository name inside the shell
+pub const REPOSITORY_ENV: &str = "REPOS_REPOSITORY";
+/// Environment variable holding the repository URL inside the shell
+pub const REPOSITORY_URL_ENV: &str = "REPOS_REPOSITORY_URL";
+/// Environment variable holding the repository directory inside the shell
+pub const REPOSITORY_PATH_ENV: &str = "REPOS_REPOSITORY_PATH";
+
+/// Shell command opening an interactive shell in each repository, one after another
+pub struct ShellCommand {
+    /// Program to start; defaults to `$SHELL`, then `sh`
+    pub shell: Option<String>,
+}
+
+impl ShellCommand {
+    fn program(&self) -> String {
+        self.shell
+            .clone()
+            .or_else(|| {
+                std::env::var("SHELL")
+                    .ok()
+                    .filter(|shell| !shell.is_empty())
+            })
+            .unwrap_or_else(|| "sh".to_string())
+    }
+
+    /// Run the shell in a repository until the user exits it
+    pub(crate) fn open(&self, repo: &Repository, target_dir: &str) -> Result<ExitStatus> {
+        let program = self.program();
+        std::process::Command::new(&program)
+            .current_dir(target_dir)
+            .env(REPOSITORY_ENV, &repo.name)
+            .env(REPOSITORY_URL_ENV, &repo.url)
+            .env(REPOSITORY_PATH_ENV, target_dir)
+            // Honored by shells whose startup files do not set their own prompt
+            .env("PS1", format!("({}) $ ", repo.name))
+            .status()
+            .with_context(|| format!("Failed to start shell '{}'", program))
+    }
+}
+
+#[async_trait]
+impl Command for ShellCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.config.filter_repositories(
+            &context.tag,
+            &context.exclude_tag,
+            context.repos.as_deref(),
+        );
+        if repositories.is_empty() {
+            logging::message(Level::Info, "No repositories found".yellow());
+            return Ok(());
+        }
+
+        let total = repositories.len();
+        let mut visited = 0;
+        for (index, repo) in repositories.iter().enumerate() {
+            let target_dir = repo.get_target_dir();
+            if !Path::new(&target_dir).exists() {
+                logging::status(
+                    Level::Warn,
+                    &repo.name,
+                    "Skipping: repository is not cloned".yellow(),
+                );
+                continue;
+            }
+
+            logging::status(
+                Level::Info,
+                &repo.name,
+                format!(
+                    "[{}/{}] Opening shell in {}; exit to continue, `exit 1` to stop",
+                    index + 1,
+                    total,
+                    target_dir
+                )
+                .green(),
+            );
+            let status = self.open(repo, &target_dir)?;
+            visited += 1;
+
+            if !status.success() {
+                logging::message(
+                    Level::Info,
+                    format!(
+                        "Stopped after {} ({} of {} repositories visited)",
+                        repo.name, visited, total
+                    )
+                    .yellow(),
+                );
+                return Ok(());
+            }
+        }
+
+        logging::message(
+            Level::Info,
+            format!("Visited {} of {} repositories", visited, total).green(),
+        );
+        Ok(())
+    }
+}
+
+/// Arguments of `repos shell`
+#[derive(Debug, Args)]
+pub struct ShellArgs {
+    /// Specific repository names to visit (if not provided, uses tag filter or all repos)
+    pub repos: Vec<String>,
+
+    /// Shell to start (default: $SHELL, or sh)
+    #[arg(long)]
+    pub shell: Option<String>,
+
+    /// Configuration file path (can be specified multiple times to combine fleets)
+    #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+    pub config: Vec<String>,
+
+    /// Filter repositories by tag (can be specified multiple times)
+    #[arg(short, long)]
+    pub tag: Vec<String>,
+
+    /// Exclude repositories with these tags (can be specified multiple times)
+    #[arg(short = 'e', long)]
+    pub exclude_tag: Vec<String>,
+
+    /// Pick repositories interactively from the filtered list before starting
+    #[arg(short, long)]
+    pub interactive: bool,
+}
+
+impl ShellArgs {
+    /// Validate the arguments and run `repos shell`
+    pub async fn run(self, globals: &GlobalOptions) -> Result<()> {
+        let Self {
+            repos,
+            shell,
+            config,
+            tag,
+            exclude_tag,
+            interactive,
+        } = self;
+        let config = Config::load_all(&config)?;
+
+        validators::validate_tag_filters(&tag)?;
+        validators::validate_tag_filters(&exclude_tag)?;
+        validators::validate_repository_names(&repos)?;
+
+        let mut context = globals.context(config, tag, exclude_tag, repos);
+        if interactive {
+            context.select_interactively()?;
+        }
+
+        ShellCommand { shell }.execute(&context).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use std::fs;
+    use tempfile::TempDir;
+
+    /// A "shell" that records where it ran and exits with the given status
+    fn recording_shell(temp_dir: &TempDir, exit_code: i32) -> String {
+        let script = temp_dir.path().join("shell.sh");
+        let log = temp_dir.path().join("visits.log");
+        fs::write(
+            &script,
+            format!(
+                "#!/bin/sh\necho \"$REPOS_REPOSITORY $(basename \"$PWD\") $(basename \"$REPOS_REPOSITORY_PATH\")\" >> {}\nexit {}\n",
+                log.display(),
+                exit_code
+            ),
+        )
+        .unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        script.to_string_lossy().to_string()
+    }
+
+    fn context_for(temp_dir: &TempDir, names: &[&str]) -> CommandContext {
+        let repositories = names
+            .iter()
+            .map(|name| {
+                let dir = temp_dir.path().join(name);
+                fs::create_dir_all(&dir).unwrap();
+                let mut repo = Repository::new(
+                    name.to_string(),
+                    format!("git@github.com:owner/{}.git", name),
+                );
+                repo.path = Some(dir.to_string_lossy().to_string());
+                repo
+            })
+            .collect();
+        let mut config = Config::new();
+        config.repositories = repositories;
+        CommandContext {
+            config,
+            tag: vec![],
+            exclude_tag: vec![],
+            parallel: false,
+            repos: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_shell_visits_each_repository() {
+        let temp_dir = TempDir::new().unwrap();
+        let context = context_for(&temp_dir, &["alpha", "beta"]);
+
+        let command = ShellCommand {
+            shell: Some(recording_shell(&temp_dir, 0)),
+        };
+        command.execute(&context).await.unwrap();
+
+        let visits = fs::read_to_string(temp_dir.path().join("visits.log")).unwrap();
+        assert_eq!(visits, "alpha alpha alpha\nbeta beta beta\n");
+    }
+
+    #[tokio::test]
+    async fn test_shell_stops_on_failed_exit() {
+        let temp_dir = TempDir::new().unwrap();
+        let context = context_for(&temp_dir, &["alpha", "beta"]);
+
+        let command = ShellCommand {
+            shell: Some(recording_shell(&temp_dir, 1)),
+        };
+        command.execute(&context).await.unwrap();
+
+        let visits = fs::read_to_string(temp_dir.path().join("visits.log")).unwrap();
+        assert_eq!(visits, "alpha alpha alpha\n");
+    }
+}
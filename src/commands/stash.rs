@@ -0,0 +1,202 @@
+//! Stash command implementation
+
+use super::{Command, CommandContext, GlobalOptions, validators};
+use crate::config::{Config, Repository};
+use crate::logging;
+use crate::{constants, git};
+use anyhow::Result;
+use async_trait::async_trait;
+use clap::Subcommand;
+use colored::*;
+use log::Level;
+use std::path::Path;
+
+/// What to do with the stashes of each repository
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StashAction {
+    /// Stash uncommitted changes
+    Push {
+        message: String,
+        /// Stash untracked files too
+        include_untracked: bool,
+    },
+    /// Restore the newest stash made by `Push`
+    Pop,
+}
+
+/// Stash command setting uncommitted changes aside, and restoring them, across repositories
+pub struct StashCommand {
+    pub action: StashAction,
+}
+
+impl StashCommand {
+    /// Stash or restore the changes of one repository, returning a note when anything happened
+    fn apply(&self, repo: &Repository) -> Result<Option<String>> {
+        let target_dir = repo.get_target_dir();
+        match &self.action {
+            StashAction::Push {
+                message,
+                include_untracked,
+            } => Ok(git::stash_push(&target_dir, message, *include_untracked)?
+                .then(|| format!("Stashed changes ({})", message))),
+            StashAction::Pop => Ok(git::stash_pop(&target_dir)?
+                .map(|message| format!("Restored stashed changes ({})", message))),
+        }
+    }
+}
+
+#[async_trait]
+impl Command for StashCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.config.filter_repositories(
+            &context.tag,
+            &context.exclude_tag,
+            context.repos.as_deref(),
+        );
+        if repositories.is_empty() {
+            logging::message(Level::Info, "No repositories found".yellow());
+            return Ok(());
+        }
+
+        let mut changed = 0;
+        let mut checked = 0;
+        let mut errors = Vec::new();
+        for repo in &repositories {
+            if !Path::new(&repo.get_target_dir()).exists() {
+                logging::status(
+                    Level::Warn,
+                    &repo.name,
+                    "Skipping: repository is not cloned".yellow(),
+                );
+                continue;
+            }
+            checked += 1;
+
+            match self.apply(repo) {
+                Ok(Some(note)) => {
+                    changed += 1;
+                    logging::status(Level::Info, &repo.name, note.green());
+                }
+                Ok(None) => {
+                    let note = match self.action {
+                        StashAction::Push { .. } => "Nothing to stash",
+                        StashAction::Pop => "No stash to restore",
+                    };
+                    logging::status(Level::Info, &repo.name, note.dimmed());
+                }
+                Err(e) => {
+                    logging::status(Level::Error, &repo.name, format!("Error: {e:#}").red());
+                    errors.push((repo.name.clone(), e));
+                }
+            }
+        }
+
+        let summary = match self.action {
+            StashAction::Push { .. } => "Stashed changes in",
+            StashAction::Pop => "Restored changes in",
+        };
+        logging::message(
+            Level::Info,
+            format!("{} {} of {} repositories", summary, changed, checked).bold(),
+        );
+
+        if let Some((repo_name, e)) = errors.first() {
+            anyhow::bail!(
+                "Failed in {} repositories. First error ({}): {:#}",
+                errors.len(),
+                repo_name,
+                e
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Subcommands of `repos stash`
+#[derive(Debug, Subcommand)]
+pub enum StashSubcommand {
+    /// Stash uncommitted changes in each repository that has any
+    Push {
+        /// Specific repository names to stash (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Message describing the stashed changes
+        #[arg(short, long, default_value = "repos stash")]
+        message: String,
+
+        /// Stash untracked files too
+        #[arg(short = 'u', long)]
+        include_untracked: bool,
+
+        /// Configuration file path (can be specified multiple times to combine fleets)
+        #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+        config: Vec<String>,
+
+        /// Filter repositories by tag (can be specified multiple times)
+        #[arg(short, long)]
+        tag: Vec<String>,
+
+        /// Exclude repositories with these tags (can be specified multiple times)
+        #[arg(short = 'e', long)]
+        exclude_tag: Vec<String>,
+    },
+
+    /// Restore the changes stashed with `repos stash push`
+    Pop {
+        /// Specific repository names to restore (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Configuration file path (can be specified multiple times to combine fleets)
+        #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+        config: Vec<String>,
+
+        /// Filter repositories by tag (can be specified multiple times)
+        #[arg(short, long)]
+        tag: Vec<String>,
+
+        /// Exclude repositories with these tags (can be specified multiple times)
+        #[arg(short = 'e', long)]
+        exclude_tag: Vec<String>,
+    },
+}
+
+impl StashSubcommand {
+    /// Validate the arguments and run `repos stash <action>`
+    pub async fn run(self, globals: &GlobalOptions) -> Result<()> {
+        let (action, repos, config, tag, exclude_tag) = match self {
+            StashSubcommand::Push {
+                repos,
+                message,
+                include_untracked,
+                config,
+                tag,
+                exclude_tag,
+            } => (
+                StashAction::Push {
+                    message,
+                    include_untracked,
+                },
+                repos,
+                config,
+                tag,
+                exclude_tag,
+            ),
+            StashSubcommand::Pop {
+                repos,
+                config,
+                tag,
+                exclude_tag,
+            } => (StashAction::Pop, repos, config, tag, exclude_tag),
+        };
+        let config = Config::load_all(&config)?;
+
+        validators::validate_tag_filters(&tag)?;
+        validators::validate_tag_filters(&exclude_tag)?;
+        validators::validate_repository_names(&repos)?;
+
+        let context = globals.context(config, tag, exclude_tag, repos);
+        StashCommand { action }.execute(&context).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,712 @@
+//! Stats command implementation
+
+use super::{Command, CommandContext, GlobalOptions, validators};
+use crate::config::{Config, Repository};
+use crate::credentials::Credentials;
+use crate::logging;
+use crate::{constants, git};
+use anyhow::Result;
+use async_trait::async_trait;
+use clap::Args;
+use colored::*;
+use log::Level;
+use repos_github::{GitHubClient, parse_github_url};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::str::FromStr;
+use walkdir::WalkDir;
+
+/// File extensions counted towards a repository's primary language
+const LANGUAGES: &[(&str, &str)] = &[
+    ("rs", "Rust"),
+    ("go", "Go"),
+    ("py", "Python"),
+    ("java", "Java"),
+    ("kt", "Kotlin"),
+    ("kts", "Kotlin"),
+    ("scala", "Scala"),
+    ("js", "JavaScript"),
+    ("jsx", "JavaScript"),
+    ("mjs", "JavaScript"),
+    ("ts", "TypeScript"),
+    ("tsx", "TypeScript"),
+    ("rb", "Ruby"),
+    ("php", "PHP"),
+    ("cs", "C#"),
+    ("fs", "F#"),
+    ("c", "C"),
+    ("h", "C"),
+    ("cc", "C++"),
+    ("cpp", "C++"),
+    ("hpp", "C++"),
+    ("swift", "Swift"),
+    ("m", "Objective-C"),
+    ("dart", "Dart"),
+    ("ex", "Elixir"),
+    ("exs", "Elixir"),
+    ("erl", "Erlang"),
+    ("hs", "Haskell"),
+    ("clj", "Clojure"),
+    ("lua", "Lua"),
+    ("pl", "Perl"),
+    ("r", "R"),
+    ("sh", "Shell"),
+    ("tf", "HCL"),
+    ("vue", "Vue"),
+    ("svelte", "Svelte"),
+];
+
+/// Output format of the stats report
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StatsFormat {
+    #[default]
+    Table,
+    Json,
+    Csv,
+}
+
+impl FromStr for StatsFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "table" => Ok(StatsFormat::Table),
+            "json" => Ok(StatsFormat::Json),
+            "csv" => Ok(StatsFormat::Csv),
+            _ => anyhow::bail!("Unknown stats format '{}' (expected table, json or csv)", s),
+        }
+    }
+}
+
+/// Stats command aggregating repository statistics across the fleet
+#[derive(Debug, Clone, Default)]
+pub struct StatsCommand {
+    /// Only count commits after this date, in any format `git log --since` accepts
+    pub since: Option<String>,
+    /// Only count commits before this date
+    pub until: Option<String>,
+    pub format: StatsFormat,
+    /// GitHub token for counting open pull requests; skipped when `None`
+    pub token: Option<String>,
+}
+
+/// Statistics of one repository
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RepoStats {
+    pub repository: String,
+    pub tags: Vec<String>,
+    /// False when the repository is not cloned; local statistics are then zero
+    pub cloned: bool,
+    /// Most common language among tracked files
+    pub language: Option<String>,
+    /// Size of the clone on disk, including `.git`
+    pub disk_bytes: u64,
+    /// Commits on the checked-out branch in the date range
+    pub commits: u64,
+    /// Distinct commit authors in the date range
+    pub contributors: u64,
+    /// Open pull requests, when a token is available
+    pub open_pull_requests: Option<u64>,
+    /// Error that prevented collecting the statistics
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip)]
+    author_emails: BTreeSet<String>,
+}
+
+/// Statistics aggregated over all selected repositories
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FleetStats {
+    pub since: Option<String>,
+    pub until: Option<String>,
+    pub repositories: usize,
+    pub cloned: usize,
+    pub disk_bytes: u64,
+    pub commits: u64,
+    /// Distinct commit authors across all repositories
+    pub contributors: u64,
+    pub open_pull_requests: Option<u64>,
+    /// Number of repositories per primary language
+    pub languages: BTreeMap<String, usize>,
+    /// Number of repositories per tag
+    pub tags: BTreeMap<String, usize>,
+    pub results: Vec<RepoStats>,
+}
+
+impl FleetStats {
+    pub fn new(since: Option<String>, until: Option<String>, results: Vec<RepoStats>) -> Self {
+        let mut languages = BTreeMap::new();
+        let mut tags = BTreeMap::new();
+        let mut authors = BTreeSet::new();
+        for stats in &results {
+            if stats.cloned {
+                let language = stats.language.as_deref().unwrap_or("Unknown");
+                *languages.entry(language.to_string()).or_insert(0) += 1;
+            }
+            for tag in &stats.tags {
+                *tags.entry(tag.clone()).or_insert(0) += 1;
+            }
+            authors.extend(stats.author_emails.iter().cloned());
+        }
+
+        let open_pull_requests = results
+            .iter()
+            .filter_map(|s| s.open_pull_requests)
+            .reduce(|a, b| a + b);
+
+        Self {
+            since,
+            until,
+            repositories: results.len(),
+            cloned: results.iter().filter(|s| s.cloned).count(),
+            disk_bytes: results.iter().map(|s| s.disk_bytes).sum(),
+            commits: results.iter().map(|s| s.commits).sum(),
+            contributors: authors.len() as u64,
+            open_pull_requests,
+            languages,
+            tags,
+            results,
+        }
+    }
+
+    /// Print a table with one row per repository followed by totals and breakdowns
+    pub fn print_table(&self) {
+        let name_width = self
+            .results
+            .iter()
+            .map(|s| s.repository.len())
+            .max()
+            .unwrap_or(0)
+            .max("REPOSITORY".len());
+        let language_width = self
+            .results
+            .iter()
+            .filter_map(|s| s.language.as_ref().map(String::len))
+            .max()
+            .unwrap_or(0)
+            .max("not cloned".len());
+
+        println!();
+        println!(
+            "{:<name_width$}  {:<language_width$}  {:>9}  {:>7}  {:>12}  {:>8}",
+            "REPOSITORY".bold(),
+            "LANGUAGE".bold(),
+            "SIZE".bold(),
+            "COMMITS".bold(),
+            "CONTRIBUTORS".bold(),
+            "OPEN PRS".bold(),
+        );
+        for stats in &self.results {
+            let open_prs = optional_count(stats.open_pull_requests);
+            if let Some(error) = &stats.error {
+                println!("{:<name_width$}  {}", stats.repository.cyan(), error.red());
+            } else if !stats.cloned {
+                println!(
+                    "{:<name_width$}  {:<language_width$}  {:>9}  {:>7}  {:>12}  {:>8}",
+                    stats.repository.cyan(),
+                    "not cloned".yellow(),
+                    "-",
+                    "-",
+                    "-",
+                    open_prs,
+                );
+            } else {
+                println!(
+                    "{:<name_width$}  {:<language_width$}  {:>9}  {:>7}  {:>12}  {:>8}",
+                    stats.repository.cyan(),
+                    stats.language.as_deref().unwrap_or("-"),
+                    format_size(stats.disk_bytes),
+                    stats.commits,
+                    stats.contributors,
+                    open_prs,
+                );
+            }
+        }
+
+        println!();
+        println!(
+            "{} repositories ({} cloned), {} on disk, {} commits by {} contributors{}",
+            self.repositories,
+            self.cloned,
+            format_size(self.disk_bytes),
+            self.commits,
+            self.contributors,
+            describe_range(&self.since, &self.until),
+        );
+        if let Some(open_pull_requests) = self.open_pull_requests {
+            println!("Open pull requests: {}", open_pull_requests);
+        }
+        if !self.languages.is_empty() {
+            println!("Languages: {}", describe_counts(&self.languages));
+        }
+        if !self.tags.is_empty() {
+            println!("Tags: {}", describe_counts(&self.tags));
+        }
+    }
+
+    /// Render one row per repository as CSV
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "repository,tags,cloned,language,disk_bytes,commits,contributors,open_pull_requests,error\n",
+        );
+        for stats in &self.results {
+            let row = [
+                stats.repository.clone(),
+                stats.tags.join(";"),
+                stats.cloned.to_string(),
+                stats.language.clone().unwrap_or_default(),
+                stats.disk_bytes.to_string(),
+                stats.commits.to_string(),
+                stats.contributors.to_string(),
+                stats
+                    .open_pull_requests
+                    .map(|n| n.to_string())
+                    .unwrap_or_default(),
+                stats.error.clone().unwrap_or_default(),
+            ];
+            let row: Vec<String> = row.iter().map(|field| csv_field(field)).collect();
+            csv.push_str(&row.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+impl StatsCommand {
+    /// Collect the statistics that only need the local clone
+    fn collect_local(&self, repo: &Repository) -> RepoStats {
+        let mut stats = RepoStats {
+            repository: repo.name.clone(),
+            tags: repo.tags.clone(),
+            ..Default::default()
+        };
+        let target_dir = repo.get_target_dir();
+        if !Path::new(&target_dir).is_dir() {
+            return stats;
+        }
+        stats.cloned = true;
+
+        if let Err(e) = self.inspect_clone(&target_dir, &mut stats) {
+            stats.error = Some(e.to_string());
+        }
+        stats
+    }
+
+    fn inspect_clone(&self, target_dir: &str, stats: &mut RepoStats) -> Result<()> {
+        stats.disk_bytes = disk_usage(Path::new(target_dir));
+
+        let mut args = vec!["log".to_string(), "--format=%aE".to_string()];
+        if let Some(since) = &self.since {
+            args.push(format!("--since={}", since));
+        }
+        if let Some(until) = &self.until {
+            args.push(format!("--until={}", until));
+        }
+        let authors = git::git_lines(target_dir, &args)?;
+        stats.commits = authors.len() as u64;
+        stats.author_emails = authors.iter().map(|a| a.to_lowercase()).collect();
+        stats.contributors = stats.author_emails.len() as u64;
+
+        let files = git::git_lines(target_dir, &["ls-files"])?;
+        stats.language = primary_language(files.iter().map(String::as_str));
+        Ok(())
+    }
+
+    /// Count open pull requests of GitHub repositories
+    async fn count_open_pull_requests(
+        client: &GitHubClient,
+        repo: &Repository,
+        stats: &mut RepoStats,
+    ) {
+        if !repo.provider().is_github() {
+            return;
+        }
+        let result = match parse_github_url(&repo.url) {
+            Ok((owner, name)) => client.list_open_pull_requests(&owner, &name).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(pull_requests) => stats.open_pull_requests = Some(pull_requests.len() as u64),
+            Err(e) => logging::status(
+                Level::Warn,
+                &repo.name,
+                format!("Warning: Failed to count open pull requests: {}", e).yellow(),
+            ),
+        }
+    }
+}
+
+#[async_trait]
+impl Command for StatsCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.config.filter_repositories(
+            &context.tag,
+            &context.exclude_tag,
+            context.repos.as_deref(),
+        );
+
+        if repositories.is_empty() {
+            logging::message(Level::Info, "No repositories found".yellow());
+            return Ok(());
+        }
+
+        let mut results = Vec::new();
+        if context.parallel {
+            let tasks: Vec<_> = repositories
+                .iter()
+                .cloned()
+                .map(|repo| {
+                    let command = self.clone();
+                    tokio::task::spawn_blocking(move || {
+                        logging::with_repository(&repo.name, || command.collect_local(&repo))
+                    })
+                })
+                .collect();
+
+            for task in tasks {
+                results.push(task.await?);
+            }
+        } else {
+            for repo in &repositories {
+                results.push(logging::with_repository(&repo.name, || {
+                    self.collect_local(repo)
+                }));
+            }
+        }
+
+        if let Some(token) = &self.token {
+            let client = GitHubClient::new(Some(token.clone()));
+            for (repo, stats) in repositories.iter().zip(results.iter_mut()) {
+                logging::scope(
+                    &repo.name,
+                    Self::count_open_pull_requests(&client, repo, stats),
+                )
+                .await;
+            }
+        }
+
+        let fleet = FleetStats::new(self.since.clone(), self.until.clone(), results);
+        match self.format {
+            StatsFormat::Table => fleet.print_table(),
+            StatsFormat::Json => println!("{}", serde_json::to_string_pretty(&fleet)?),
+            StatsFormat::Csv => print!("{}", fleet.to_csv()),
+        }
+
+        Ok(())
+    }
+}
+
+/// Total size of the files below `path`
+pub(crate) fn disk_usage(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// Language with the most files, by extension
+fn primary_language<'a>(files: impl Iterator<Item = &'a str>) -> Option<String> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for file in files {
+        let Some(extension) = Path::new(file).extension().and_then(|e| e.to_str()) else {
+            continue;
+        };
+        let extension = extension.to_lowercase();
+        if let Some((_, language)) = LANGUAGES.iter().find(|(ext, _)| *ext == extension) {
+            *counts.entry(language).or_insert(0) += 1;
+        }
+    }
+    // Ties go to the alphabetically first language, so the result is stable
+    counts
+        .into_iter()
+        .rev()
+        .max_by_key(|(_, count)| *count)
+        .map(|(language, _)| language.to_string())
+}
+
+pub(crate) fn format_size(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+fn optional_count(count: Option<u64>) -> String {
+    count
+        .map(|n| n.to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn describe_range(since: &Option<String>, until: &Option<String>) -> String {
+    match (since, until) {
+        (Some(since), Some(until)) => format!(" between {} and {}", since, until),
+        (Some(since), None) => format!(" since {}", since),
+        (None, Some(until)) => format!(" until {}", until),
+        (None, None) => String::new(),
+    }
+}
+
+/// Counts as `a (3), b (1)`, most common first
+fn describe_counts(counts: &BTreeMap<String, usize>) -> String {
+    let mut counts: Vec<_> = counts.iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+    counts
+        .iter()
+        .map(|(name, count)| format!("{} ({})", name, count))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+pub(crate) fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Arguments of `repos stats`
+#[derive(Debug, Args)]
+pub struct StatsArgs {
+    /// Specific repository names to include (if not provided, uses tag filter or all repos)
+    pub repos: Vec<String>,
+
+    /// Only count commits after this date, e.g. 2024-01-01 or "3 months ago"
+    #[arg(long)]
+    pub since: Option<String>,
+
+    /// Only count commits before this date
+    #[arg(long)]
+    pub until: Option<String>,
+
+    /// Output format
+    #[arg(long, default_value = "table", value_parser = ["table", "json", "csv"])]
+    pub format: String,
+
+    /// GitHub token for counting open pull requests (skipped without a token)
+    #[arg(long)]
+    pub token: Option<String>,
+
+    /// Configuration file path (can be specified multiple times to combine fleets)
+    #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+    pub config: Vec<String>,
+
+    /// Filter repositories by tag (can be specified multiple times)
+    #[arg(short, long)]
+    pub tag: Vec<String>,
+
+    /// Exclude repositories with these tags (can be specified multiple times)
+    #[arg(short = 'e', long)]
+    pub exclude_tag: Vec<String>,
+
+    /// Execute operations in parallel
+    #[arg(short, long)]
+    pub parallel: bool,
+}
+
+impl StatsArgs {
+    /// Validate the arguments and run `repos stats`
+    pub async fn run(self, globals: &GlobalOptions) -> Result<()> {
+        let Self {
+            repos,
+            since,
+            until,
+            format,
+            token,
+            config,
+            tag,
+            exclude_tag,
+            parallel,
+        } = self;
+        let config = Config::load_all(&config)?;
+
+        validators::validate_tag_filters(&tag)?;
+        validators::validate_tag_filters(&exclude_tag)?;
+        validators::validate_repository_names(&repos)?;
+
+        let context = globals
+            .context(config, tag, exclude_tag, repos)
+            .with_parallel(parallel);
+
+        StatsCommand {
+            since,
+            until,
+            format: format.parse()?,
+            token: Credentials::load(token.or_else(|| context.config.resolve_profile_token()))
+                .default_token(),
+        }
+        .execute(&context)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo_stats(name: &str, language: Option<&str>, emails: &[&str]) -> RepoStats {
+        RepoStats {
+            repository: name.to_string(),
+            tags: vec!["backend".to_string()],
+            cloned: true,
+            language: language.map(str::to_string),
+            disk_bytes: 2048,
+            commits: emails.len() as u64,
+            contributors: emails.len() as u64,
+            author_emails: emails.iter().map(|e| e.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_primary_language() {
+        let files = [
+            "src/main.rs",
+            "src/lib.rs",
+            "build.sh",
+            "README.md",
+            "Makefile",
+        ];
+        assert_eq!(
+            primary_language(files.into_iter()),
+            Some("Rust".to_string())
+        );
+        assert_eq!(
+            primary_language(["a.py", "b.go"].into_iter()),
+            Some("Go".to_string())
+        );
+        assert_eq!(primary_language(["README.md"].into_iter()), None);
+    }
+
+    #[test]
+    fn test_fleet_stats_totals() {
+        let mut web = repo_stats(
+            "web",
+            Some("TypeScript"),
+            &["a@example.com", "c@example.com"],
+        );
+        web.open_pull_requests = Some(3);
+        let fleet = FleetStats::new(
+            Some("2024-01-01".to_string()),
+            None,
+            vec![
+                repo_stats("api", Some("Rust"), &["a@example.com", "b@example.com"]),
+                web,
+                repo_stats("docs", None, &[]),
+            ],
+        );
+
+        assert_eq!(fleet.repositories, 3);
+        assert_eq!(fleet.disk_bytes, 6144);
+        assert_eq!(fleet.commits, 4);
+        assert_eq!(fleet.contributors, 3);
+        assert_eq!(fleet.open_pull_requests, Some(3));
+        assert_eq!(fleet.languages.get("Unknown"), Some(&1));
+        assert_eq!(fleet.tags.get("backend"), Some(&3));
+        assert_eq!(
+            describe_counts(&fleet.languages),
+            "Rust (1), TypeScript (1), Unknown (1)"
+        );
+    }
+
+    #[test]
+    fn test_to_csv() {
+        let mut stats = repo_stats("api", Some("Rust"), &["a@example.com"]);
+        stats.error = Some("Failed to inspect repository: bad, \"object\"".to_string());
+        let csv = FleetStats::new(None, None, vec![stats]).to_csv();
+        assert_eq!(
+            csv.lines().nth(1).unwrap(),
+            "api,backend,true,Rust,2048,1,1,,\"Failed to inspect repository: bad, \"\"object\"\"\""
+        );
+    }
+
+    #[test]
+    fn test_collect_local() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let repo_dir = temp_dir.path();
+        std::process::Command::new("git")
+            .args(["init", "--quiet"])
+            .current_dir(repo_dir)
+            .output()
+            .unwrap();
+        for (file, author, date) in [
+            ("main.rs", "Ann <ann@example.com>", "2024-01-10T12:00:00"),
+            ("lib.rs", "Bob <bob@example.com>", "2024-03-10T12:00:00"),
+            ("util.rs", "Ann <ANN@example.com>", "2024-05-10T12:00:00"),
+        ] {
+            std::fs::write(repo_dir.join(file), "fn main() {}\n").unwrap();
+            for args in [
+                vec!["add", file],
+                vec![
+                    "-c",
+                    "user.name=Test",
+                    "-c",
+                    "user.email=test@example.com",
+                    "commit",
+                    "--quiet",
+                    "-m",
+                    file,
+                    "--author",
+                    author,
+                    "--date",
+                    date,
+                ],
+            ] {
+                std::process::Command::new("git")
+                    .args(args)
+                    .env("GIT_COMMITTER_DATE", date)
+                    .current_dir(repo_dir)
+                    .output()
+                    .unwrap();
+            }
+        }
+
+        let mut repo = Repository::new("api".to_string(), "git@github.com:org/api.git".to_string());
+        repo.path = Some(repo_dir.to_string_lossy().to_string());
+
+        let stats = StatsCommand::default().collect_local(&repo);
+        assert_eq!(stats.error, None);
+        assert!(stats.cloned);
+        assert_eq!(stats.language, Some("Rust".to_string()));
+        assert_eq!(stats.commits, 3);
+        assert_eq!(stats.contributors, 2);
+        assert!(stats.disk_bytes > 0);
+
+        let command = StatsCommand {
+            since: Some("2024-02-01".to_string()),
+            until: Some("2024-04-01".to_string()),
+            ..Default::default()
+        };
+        let stats = command.collect_local(&repo);
+        assert_eq!(stats.commits, 1);
+        assert_eq!(stats.contributors, 1);
+
+        repo.path = Some(
+            temp_dir
+                .path()
+                .join("missing")
+                .to_string_lossy()
+                .to_string(),
+        );
+        assert!(!StatsCommand::default().collect_local(&repo).cloned);
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(5 * 1024 * 1024 * 1024), "5.0 GiB");
+    }
+}
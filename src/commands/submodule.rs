@@ -0,0 +1,495 @@
+//! Submodule command implementation
+
+use super::{Command, CommandContext, GlobalOptions, validators};
+use crate::config::{Config, Repository};
+use crate::git::{self, RemoteAccess, Submodule, SubmoduleState};
+use crate::logging;
+use crate::{constants, events};
+use anyhow::Result;
+use async_trait::async_trait;
+use clap::Subcommand;
+use colored::*;
+use log::Level;
+use std::path::Path;
+
+/// What to do with the submodules of each repository
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubmoduleAction {
+    /// Report submodules that are not checked out at the recorded commit
+    Status,
+    /// Initialize and check out submodules
+    Update {
+        /// Move submodules to the tip of their remote branch instead of the recorded commit
+        remote: bool,
+    },
+    /// Copy submodule URLs from `.gitmodules` to each clone's config
+    Sync,
+}
+
+/// Submodule command updating, syncing and reporting submodules across repositories
+#[derive(Debug, Clone)]
+pub struct SubmoduleCommand {
+    pub action: SubmoduleAction,
+}
+
+/// What happened in one repository
+#[derive(Debug, PartialEq, Eq)]
+enum RepoResult {
+    /// The repository declares no submodules
+    NoSubmodules,
+    Status(Vec<Submodule>),
+    /// Number of submodules checked out at another commit than before, and in total
+    Updated {
+        changed: usize,
+        total: usize,
+    },
+    /// Paths whose URL was synchronized
+    Synced(Vec<String>),
+}
+
+/// Submodules whose checkout moved, appeared or disappeared between two reports
+fn changed_submodules(before: &[Submodule], after: &[Submodule]) -> usize {
+    after
+        .iter()
+        .filter(|submodule| {
+            !before.iter().any(|old| {
+                old.path == submodule.path
+                    && old.commit == submodule.commit
+                    && old.state == submodule.state
+            })
+        })
+        .count()
+}
+
+/// One line per submodule that is not checked out at the recorded commit
+fn describe_status(submodules: &[Submodule]) -> Vec<String> {
+    submodules
+        .iter()
+        .filter_map(|submodule| {
+            let state = match submodule.state {
+                SubmoduleState::Current => return None,
+                SubmoduleState::Modified => "checked out at another commit",
+                SubmoduleState::Uninitialized => "not initialized",
+                SubmoduleState::Conflicted => "merge conflict",
+            };
+            Some(format!("{}: {}", submodule.path, state))
+        })
+        .collect()
+}
+
+impl SubmoduleCommand {
+    fn apply(&self, repo: &Repository) -> Result<RepoResult> {
+        let repo_path = repo.get_target_dir();
+        if !Path::new(&repo_path).exists() {
+            anyhow::bail!("Repository directory does not exist: {}", repo_path);
+        }
+        if !git::has_submodules(&repo_path) {
+            return Ok(RepoResult::NoSubmodules);
+        }
+
+        match self.action {
+            SubmoduleAction::Status => Ok(RepoResult::Status(git::submodule_status(&repo_path)?)),
+            SubmoduleAction::Update { remote } => {
+                let before = git::submodule_status(&repo_path)?;
+                git::update_submodules(
+                    &repo_path,
+                    remote,
+                    &RemoteAccess::for_repository(repo, None),
+                )?;
+                let after = git::submodule_status(&repo_path)?;
+                Ok(RepoResult::Updated {
+                    changed: changed_submodules(&before, &after),
+                    total: after.len(),
+                })
+            }
+            SubmoduleAction::Sync => Ok(RepoResult::Synced(git::sync_submodules(&repo_path)?)),
+        }
+    }
+
+    /// Log the result of one repository, returning whether it needs attention
+    fn report(&self, repo: &Repository, result: &RepoResult) -> bool {
+        match result {
+            RepoResult::NoSubmodules => {
+                logging::status(Level::Info, &repo.name, "No submodules".dimmed());
+                false
+            }
+            RepoResult::Status(submodules) => {
+                let lines = describe_status(submodules);
+                if lines.is_empty() {
+                    logging::status(
+                        Level::Info,
+                        &repo.name,
+                        format!("{} submodules up to date", submodules.len()).green(),
+                    );
+                    return false;
+                }
+                logging::status(
+                    Level::Warn,
+                    &repo.name,
+                    format!(
+                        "{} of {} submodules need attention",
+                        lines.len(),
+                        submodules.len()
+                    )
+                    .yellow(),
+                );
+                for line in lines {
+                    logging::status(Level::Warn, &repo.name, format!("  {}", line).yellow());
+                }
+                true
+            }
+            RepoResult::Updated { changed, total } => {
+                let message = format!("Updated {} of {} submodules", changed, total);
+                if *changed == 0 {
+                    logging::status(Level::Info, &repo.name, message.dimmed());
+                } else {
+                    logging::status(Level::Info, &repo.name, message.green());
+                }
+                false
+            }
+            RepoResult::Synced(paths) => {
+                logging::status(
+                    Level::Info,
+                    &repo.name,
+                    format!("Synchronized {} submodule URLs", paths.len()).green(),
+                );
+                false
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Command for SubmoduleCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.config.filter_repositories(
+            &context.tag,
+            &context.exclude_tag,
+            context.repos.as_deref(),
+        );
+        if repositories.is_empty() {
+            logging::message(Level::Info, "No repositories found".yellow());
+            return Ok(());
+        }
+
+        let cloned: Vec<Repository> = repositories
+            .into_iter()
+            .filter(|repo| {
+                let exists = Path::new(&repo.get_target_dir()).exists();
+                if !exists {
+                    logging::status(
+                        Level::Warn,
+                        &repo.name,
+                        "Skipping: repository is not cloned".yellow(),
+                    );
+                }
+                exists
+            })
+            .collect();
+
+        let mut results: Vec<(Repository, Result<RepoResult>)> = Vec::new();
+        if context.parallel {
+            let tasks: Vec<_> = cloned
+                .into_iter()
+                .map(|repo| {
+                    let command = self.clone();
+                    tokio::task::spawn_blocking(move || {
+                        let result = events::track(&repo.name, || command.apply(&repo));
+                        (repo, result)
+                    })
+                })
+                .collect();
+
+            for task in tasks {
+                results.push(task.await?);
+            }
+        } else {
+            for repo in cloned {
+                let result = events::track(&repo.name, || self.apply(&repo));
+                results.push((repo, result));
+            }
+        }
+
+        let mut with_submodules = 0;
+        let mut attention = 0;
+        let mut errors = Vec::new();
+        for (repo, result) in &results {
+            match result {
+                Ok(result) => {
+                    if *result != RepoResult::NoSubmodules {
+                        with_submodules += 1;
+                    }
+                    if self.report(repo, result) {
+                        attention += 1;
+                    }
+                }
+                Err(e) => {
+                    logging::status(Level::Error, &repo.name, format!("Error: {e:#}").red());
+                    errors.push((repo.name.clone(), e));
+                }
+            }
+        }
+
+        let summary = match self.action {
+            SubmoduleAction::Status => format!(
+                "{} of {} repositories with submodules need attention",
+                attention, with_submodules
+            ),
+            SubmoduleAction::Update { .. } => {
+                format!("Updated submodules in {} repositories", with_submodules)
+            }
+            SubmoduleAction::Sync => {
+                format!(
+                    "Synchronized submodules in {} repositories",
+                    with_submodules
+                )
+            }
+        };
+        logging::message(Level::Info, summary.bold());
+
+        if let Some((repo_name, e)) = errors.first() {
+            anyhow::bail!(
+                "Failed in {} repositories. First error ({}): {:#}",
+                errors.len(),
+                repo_name,
+                e
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Subcommands of `repos submodule`
+#[derive(Debug, Subcommand)]
+pub enum SubmoduleSubcommand {
+    /// Report submodules that are not initialized or not at the recorded commit
+    Status {
+        /// Specific repository names to check (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Execute operations in parallel
+        #[arg(short, long)]
+        parallel: bool,
+
+        /// Configuration file path (can be specified multiple times to combine fleets)
+        #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+        config: Vec<String>,
+
+        /// Filter repositories by tag (can be specified multiple times)
+        #[arg(short, long)]
+        tag: Vec<String>,
+
+        /// Exclude repositories with these tags (can be specified multiple times)
+        #[arg(short = 'e', long)]
+        exclude_tag: Vec<String>,
+    },
+
+    /// Initialize and check out submodules, including nested ones
+    Update {
+        /// Specific repository names to update (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Move submodules to the tip of their remote branch instead of the recorded commit
+        #[arg(long)]
+        remote: bool,
+
+        /// Execute operations in parallel
+        #[arg(short, long)]
+        parallel: bool,
+
+        /// Configuration file path (can be specified multiple times to combine fleets)
+        #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+        config: Vec<String>,
+
+        /// Filter repositories by tag (can be specified multiple times)
+        #[arg(short, long)]
+        tag: Vec<String>,
+
+        /// Exclude repositories with these tags (can be specified multiple times)
+        #[arg(short = 'e', long)]
+        exclude_tag: Vec<String>,
+    },
+
+    /// Copy changed submodule URLs from .gitmodules to each clone's config
+    Sync {
+        /// Specific repository names to sync (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Execute operations in parallel
+        #[arg(short, long)]
+        parallel: bool,
+
+        /// Configuration file path (can be specified multiple times to combine fleets)
+        #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+        config: Vec<String>,
+
+        /// Filter repositories by tag (can be specified multiple times)
+        #[arg(short, long)]
+        tag: Vec<String>,
+
+        /// Exclude repositories with these tags (can be specified multiple times)
+        #[arg(short = 'e', long)]
+        exclude_tag: Vec<String>,
+    },
+}
+
+impl SubmoduleSubcommand {
+    /// Validate the arguments and run `repos submodule <action>`
+    pub async fn run(self, globals: &GlobalOptions) -> Result<()> {
+        let (action, repos, parallel, config, tag, exclude_tag) = match self {
+            SubmoduleSubcommand::Status {
+                repos,
+                parallel,
+                config,
+                tag,
+                exclude_tag,
+            } => (
+                SubmoduleAction::Status,
+                repos,
+                parallel,
+                config,
+                tag,
+                exclude_tag,
+            ),
+            SubmoduleSubcommand::Update {
+                repos,
+                remote,
+                parallel,
+                config,
+                tag,
+                exclude_tag,
+            } => (
+                SubmoduleAction::Update { remote },
+                repos,
+                parallel,
+                config,
+                tag,
+                exclude_tag,
+            ),
+            SubmoduleSubcommand::Sync {
+                repos,
+                parallel,
+                config,
+                tag,
+                exclude_tag,
+            } => (
+                SubmoduleAction::Sync,
+                repos,
+                parallel,
+                config,
+                tag,
+                exclude_tag,
+            ),
+        };
+        let config = Config::load_all(&config)?;
+
+        validators::validate_tag_filters(&tag)?;
+        validators::validate_tag_filters(&exclude_tag)?;
+        validators::validate_repository_names(&repos)?;
+
+        let context = globals
+            .context(config, tag, exclude_tag, repos)
+            .with_parallel(parallel);
+        SubmoduleCommand { action }.execute(&context).await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command as ProcessCommand;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = ProcessCommand::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    fn init(dir: &Path) {
+        std::fs::create_dir_all(dir).unwrap();
+        git(dir, &["init", "-q", "-b", "main"]);
+        git(dir, &["config", "user.email", "test@example.com"]);
+        git(dir, &["config", "user.name", "Test"]);
+        std::fs::write(dir.join("README.md"), "hello\n").unwrap();
+        git(dir, &["add", "."]);
+        git(dir, &["commit", "-q", "-m", "Initial commit"]);
+    }
+
+    fn command(action: SubmoduleAction) -> SubmoduleCommand {
+        SubmoduleCommand { action }
+    }
+
+    #[test]
+    fn test_submodule_outcomes() {
+        let temp_dir = TempDir::new().unwrap();
+        let library = temp_dir.path().join("lib");
+        let app = temp_dir.path().join("app");
+        init(&library);
+        init(&app);
+        git(
+            &app,
+            &[
+                "-c",
+                "protocol.file.allow=always",
+                "submodule",
+                "add",
+                "-q",
+                library.to_str().unwrap(),
+                "vendor/lib",
+            ],
+        );
+        git(&app, &["commit", "-q", "-m", "Add submodule"]);
+
+        let mut repo = Repository::new("app".to_string(), "git@github.com:org/app.git".to_string());
+        repo.path = Some(app.to_string_lossy().to_string());
+
+        let RepoResult::Status(submodules) = command(SubmoduleAction::Status).apply(&repo).unwrap()
+        else {
+            panic!("expected a status");
+        };
+        assert_eq!(submodules.len(), 1);
+        assert_eq!(submodules[0].path, "vendor/lib");
+        assert_eq!(submodules[0].state, SubmoduleState::Current);
+        assert!(describe_status(&submodules).is_empty());
+
+        assert_eq!(
+            command(SubmoduleAction::Sync).apply(&repo).unwrap(),
+            RepoResult::Synced(vec!["vendor/lib".to_string()])
+        );
+
+        let mut plain =
+            Repository::new("lib".to_string(), "git@github.com:org/lib.git".to_string());
+        plain.path = Some(library.to_string_lossy().to_string());
+        assert_eq!(
+            command(SubmoduleAction::Status).apply(&plain).unwrap(),
+            RepoResult::NoSubmodules
+        );
+    }
+
+    #[test]
+    fn test_changed_submodules() {
+        let submodule = |commit: &str, state| Submodule {
+            path: "vendor/lib".to_string(),
+            commit: commit.to_string(),
+            state,
+        };
+        let before = vec![submodule("aaa", SubmoduleState::Uninitialized)];
+
+        assert_eq!(changed_submodules(&before, &before), 0);
+        assert_eq!(
+            changed_submodules(&before, &[submodule("aaa", SubmoduleState::Current)]),
+            1
+        );
+        assert_eq!(
+            describe_status(&before),
+            vec!["vendor/lib: not initialized".to_string()]
+        );
+    }
+}
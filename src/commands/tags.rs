@@ -0,0 +1,577 @@
+//! Tags command implementation
+
+use super::{Command, CommandContext, GlobalOptions, validators};
+use crate::config::edit::set_repository_tags;
+use crate::config::{Config, Provider, Repository};
+use crate::credentials::Credentials;
+use crate::{constants, logging};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use clap::Subcommand;
+use colored::*;
+use log::Level;
+use repos_github::{GitHubClient, parse_github_url};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Prefix of the tags that mirror repository topics
+pub const TOPIC_TAG_PREFIX: &str = "gh:";
+
+/// Tags to add and remove so a repository's topic tags match its topics
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct TagChange {
+    pub add: Vec<String>,
+    pub remove: Vec<String>,
+}
+
+impl TagChange {
+    pub fn is_empty(&self) -> bool {
+        self.add.is_empty() && self.remove.is_empty()
+    }
+
+    /// The part of the change that is not already true of `tags`
+    pub fn against(&self, tags: &[String]) -> TagChange {
+        TagChange {
+            add: self
+                .add
+                .iter()
+                .filter(|tag| !tags.contains(tag))
+                .cloned()
+                .collect(),
+            remove: self
+                .remove
+                .iter()
+                .filter(|tag| tags.contains(tag))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// The tags after the change, keeping the order of the existing ones
+    pub fn apply(&self, tags: &[String]) -> Vec<String> {
+        tags.iter()
+            .filter(|tag| !self.remove.contains(tag))
+            .chain(&self.add)
+            .cloned()
+            .collect()
+    }
+
+    fn describe(&self) -> String {
+        self.add
+            .iter()
+            .map(|tag| format!("+{}", tag).green().to_string())
+            .chain(
+                self.remove
+                    .iter()
+                    .map(|tag| format!("-{}", tag).red().to_string()),
+            )
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Compare the topic tags of a repository with its topics
+///
+/// Tags without the [`TOPIC_TAG_PREFIX`] are never touched.
+pub fn topic_tag_changes(tags: &[String], topics: &[String]) -> TagChange {
+    let wanted: Vec<String> = topics
+        .iter()
+        .map(|topic| format!("{}{}", TOPIC_TAG_PREFIX, topic))
+        .collect();
+    TagChange {
+        add: wanted
+            .iter()
+            .filter(|tag| !tags.contains(tag))
+            .cloned()
+            .collect(),
+        remove: tags
+            .iter()
+            .filter(|tag| tag.starts_with(TOPIC_TAG_PREFIX) && !wanted.contains(tag))
+            .cloned()
+            .collect(),
+    }
+}
+
+/// Tags sync command mirroring repository topics into `gh:` tags of the config
+pub struct TagsSyncCommand {
+    /// Config file the tags are written to
+    pub config_path: PathBuf,
+    /// Write the changes instead of only showing them
+    pub apply: bool,
+    pub token: Option<String>,
+}
+
+/// Topics of a repository, or `None` for providers without topics
+pub async fn repository_topics(
+    repo: &Repository,
+    credentials: &Credentials,
+) -> Result<Option<Vec<String>>> {
+    match repo.provider() {
+        Provider::GitHub => {
+            let (owner, name) = parse_github_url(&repo.url)?;
+            let client = GitHubClient::new(credentials.token_for_repository(repo));
+            Ok(Some(
+                client.get_repository_details(&owner, &name).await?.topics,
+            ))
+        }
+        Provider::Gitea => {
+            let (client, repo_ref) = crate::gitea::client_for(repo)?;
+            Ok(Some(
+                client
+                    .get_repository_details(&repo_ref.owner, &repo_ref.repo)
+                    .await?
+                    .topics,
+            ))
+        }
+        Provider::Azure => Ok(None),
+    }
+}
+
+/// Tags edit command adding or removing tags of repositories in the config
+pub struct TagsEditCommand {
+    /// Config file the tags are written to
+    pub config_path: PathBuf,
+    pub change: TagChange,
+}
+
+/// Tags list command showing how many repositories carry each tag
+pub struct TagsListCommand;
+
+/// Write new tags of repositories to a config file, keeping its layout
+fn write_tags(path: &Path, changes: &[(&Repository, TagChange)]) -> Result<()> {
+    let mut source = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config file {}", path.display()))?;
+    for (repo, change) in changes {
+        source = set_repository_tags(&source, &repo.name, &change.apply(&repo.tags))
+            .with_context(|| format!("Failed to update {}", path.display()))?;
+    }
+    std::fs::write(path, source)
+        .with_context(|| format!("Failed to write config file {}", path.display()))
+}
+
+#[async_trait]
+impl Command for TagsEditCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.config.filter_repositories(
+            &context.tag,
+            &context.exclude_tag,
+            context.repos.as_deref(),
+        );
+        if repositories.is_empty() {
+            logging::message(Level::Info, "No repositories found".yellow());
+            return Ok(());
+        }
+
+        let mut changes = Vec::new();
+        for repo in &repositories {
+            let change = self.change.against(&repo.tags);
+            if change.is_empty() {
+                logging::status(Level::Info, &repo.name, "Unchanged".dimmed());
+            } else {
+                logging::status(Level::Info, &repo.name, change.describe());
+                changes.push((repo, change));
+            }
+        }
+
+        if changes.is_empty() {
+            logging::message(Level::Info, "No tags to change".green());
+            return Ok(());
+        }
+        write_tags(&self.config_path, &changes)?;
+        logging::message(
+            Level::Info,
+            format!(
+                "Updated the tags of {} repositories in {}",
+                changes.len(),
+                self.config_path.display()
+            )
+            .bold(),
+        );
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Command for TagsListCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.config.filter_repositories(
+            &context.tag,
+            &context.exclude_tag,
+            context.repos.as_deref(),
+        );
+
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for repo in &repositories {
+            for tag in &repo.tags {
+                *counts.entry(tag).or_default() += 1;
+            }
+        }
+        if counts.is_empty() {
+            logging::message(Level::Info, "No tags found".yellow());
+            return Ok(());
+        }
+
+        let width = counts.keys().map(|tag| tag.len()).max().unwrap_or(0);
+        for (tag, count) in counts {
+            println!("{:<width$}  {}", tag.cyan(), count);
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Command for TagsSyncCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.config.filter_repositories(
+            &context.tag,
+            &context.exclude_tag,
+            context.repos.as_deref(),
+        );
+        if repositories.is_empty() {
+            logging::message(Level::Info, "No repositories found".yellow());
+            return Ok(());
+        }
+
+        logging::message(
+            Level::Info,
+            format!(
+                "Comparing tags with the topics of {} repositories...",
+                repositories.len()
+            )
+            .green(),
+        );
+
+        let credentials = Credentials::load(self.token.clone());
+        let mut changes = Vec::new();
+        let mut errors = Vec::new();
+        for repo in &repositories {
+            match repository_topics(repo, &credentials).await {
+                Ok(Some(topics)) => {
+                    let change = topic_tag_changes(&repo.tags, &topics);
+                    if change.is_empty() {
+                        logging::status(Level::Info, &repo.name, "In sync".dimmed());
+                    } else {
+                        logging::status(Level::Info, &repo.name, change.describe());
+                        changes.push((repo, change));
+                    }
+                }
+                Ok(None) => logging::status(
+                    Level::Info,
+                    &repo.name,
+                    format!("Skipping: {} repositories have no topics", repo.provider()).dimmed(),
+                ),
+                Err(e) => {
+                    logging::status(Level::Error, &repo.name, format!("Error: {e}").red());
+                    errors.push((repo.name.clone(), e));
+                }
+            }
+        }
+
+        if changes.is_empty() {
+            logging::message(Level::Info, "All tags match their topics".green());
+        } else if !self.apply {
+            logging::message(
+                Level::Info,
+                format!(
+                    "{} repositories out of sync; run with --apply to update {}",
+                    changes.len(),
+                    self.config_path.display()
+                )
+                .bold(),
+            );
+        } else {
+            let path = &self.config_path;
+            write_tags(path, &changes)?;
+            logging::message(
+                Level::Info,
+                format!(
+                    "Updated the tags of {} repositories in {}",
+                    changes.len(),
+                    path.display()
+                )
+                .bold(),
+            );
+        }
+
+        if let Some((repo_name, e)) = errors.first() {
+            anyhow::bail!(
+                "Failed to fetch topics of {} repositories. First error ({}): {}",
+                errors.len(),
+                repo_name,
+                e
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Subcommands of `repos tags`
+#[derive(Debug, Subcommand)]
+pub enum TagsAction {
+    /// Mirror GitHub and Gitea topics into gh:-prefixed tags, showing the changes
+    Sync {
+        /// Specific repository names to sync (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Write the changes to the config file, keeping its comments and layout
+        #[arg(long)]
+        apply: bool,
+
+        /// GitHub token
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Configuration file path
+        #[arg(short, long, default_value_t = constants::config::DEFAULT_CONFIG_FILE.to_string())]
+        config: String,
+
+        /// Filter repositories by tag (can be specified multiple times)
+        #[arg(short, long)]
+        tag: Vec<String>,
+
+        /// Exclude repositories with these tags (can be specified multiple times)
+        #[arg(short = 'e', long)]
+        exclude_tag: Vec<String>,
+    },
+
+    /// Add a tag to repositories in the config file, keeping its comments and layout
+    Add {
+        /// Tag to add
+        #[arg(value_name = "TAG")]
+        name: String,
+
+        /// Specific repository names to tag (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Configuration file path
+        #[arg(short, long, default_value_t = constants::config::DEFAULT_CONFIG_FILE.to_string())]
+        config: String,
+
+        /// Filter repositories by tag (can be specified multiple times)
+        #[arg(short, long)]
+        tag: Vec<String>,
+
+        /// Exclude repositories with these tags (can be specified multiple times)
+        #[arg(short = 'e', long)]
+        exclude_tag: Vec<String>,
+    },
+
+    /// Remove a tag from repositories in the config file, keeping its comments and layout
+    Rm {
+        /// Tag to remove
+        #[arg(value_name = "TAG")]
+        name: String,
+
+        /// Specific repository names to untag (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Configuration file path
+        #[arg(short, long, default_value_t = constants::config::DEFAULT_CONFIG_FILE.to_string())]
+        config: String,
+
+        /// Filter repositories by tag (can be specified multiple times)
+        #[arg(short, long)]
+        tag: Vec<String>,
+
+        /// Exclude repositories with these tags (can be specified multiple times)
+        #[arg(short = 'e', long)]
+        exclude_tag: Vec<String>,
+    },
+
+    /// List the tags in use with the number of repositories carrying each
+    Ls {
+        /// Specific repository names to count (if not provided, uses tag filter or all repos)
+        repos: Vec<String>,
+
+        /// Configuration file path
+        #[arg(short, long, default_value_t = constants::config::DEFAULT_CONFIG_FILE.to_string())]
+        config: String,
+
+        /// Filter repositories by tag (can be specified multiple times)
+        #[arg(short, long)]
+        tag: Vec<String>,
+
+        /// Exclude repositories with these tags (can be specified multiple times)
+        #[arg(short = 'e', long)]
+        exclude_tag: Vec<String>,
+    },
+}
+
+impl TagsAction {
+    /// Validate the arguments and run `repos tags <action>`
+    pub async fn run(self, globals: &GlobalOptions) -> Result<()> {
+        match self {
+            TagsAction::Sync {
+                repos,
+                apply,
+                token,
+                config,
+                tag,
+                exclude_tag,
+            } => {
+                let config_path = crate::config::profile::editable_path(&config);
+                let config = Config::load(&config)?;
+
+                validators::validate_tag_filters(&tag)?;
+                validators::validate_tag_filters(&exclude_tag)?;
+                validators::validate_repository_names(&repos)?;
+
+                let token = token.or_else(|| config.resolve_profile_token());
+                let context = globals.context(config, tag, exclude_tag, repos);
+
+                TagsSyncCommand {
+                    config_path,
+                    apply,
+                    token,
+                }
+                .execute(&context)
+                .await?;
+            }
+            TagsAction::Add {
+                name,
+                repos,
+                config,
+                tag,
+                exclude_tag,
+            } => {
+                let config_path = crate::config::profile::editable_path(&config);
+                let config = Config::load(&config)?;
+
+                validators::validate_tag_filters(std::slice::from_ref(&name))?;
+                validators::validate_tag_filters(&tag)?;
+                validators::validate_tag_filters(&exclude_tag)?;
+                validators::validate_repository_names(&repos)?;
+
+                let context = globals.context(config, tag, exclude_tag, repos);
+
+                TagsEditCommand {
+                    config_path,
+                    change: crate::commands::tags::TagChange {
+                        add: vec![name],
+                        ..Default::default()
+                    },
+                }
+                .execute(&context)
+                .await?;
+            }
+            TagsAction::Rm {
+                name,
+                repos,
+                config,
+                tag,
+                exclude_tag,
+            } => {
+                let config_path = crate::config::profile::editable_path(&config);
+                let config = Config::load(&config)?;
+
+                validators::validate_tag_filters(std::slice::from_ref(&name))?;
+                validators::validate_tag_filters(&tag)?;
+                validators::validate_tag_filters(&exclude_tag)?;
+                validators::validate_repository_names(&repos)?;
+
+                let context = globals.context(config, tag, exclude_tag, repos);
+
+                TagsEditCommand {
+                    config_path,
+                    change: crate::commands::tags::TagChange {
+                        remove: vec![name],
+                        ..Default::default()
+                    },
+                }
+                .execute(&context)
+                .await?;
+            }
+            TagsAction::Ls {
+                repos,
+                config,
+                tag,
+                exclude_tag,
+            } => {
+                let config = Config::load(&config)?;
+
+                validators::validate_tag_filters(&tag)?;
+                validators::validate_tag_filters(&exclude_tag)?;
+                validators::validate_repository_names(&repos)?;
+
+                let context = globals.context(config, tag, exclude_tag, repos);
+
+                TagsListCommand.execute(&context).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_topic_tag_changes() {
+        let tags = strings(&["backend", "gh:rust", "gh:old"]);
+        let change = topic_tag_changes(&tags, &strings(&["rust", "cli"]));
+        assert_eq!(
+            change,
+            TagChange {
+                add: strings(&["gh:cli"]),
+                remove: strings(&["gh:old"]),
+            }
+        );
+        assert_eq!(
+            change.apply(&tags),
+            strings(&["backend", "gh:rust", "gh:cli"])
+        );
+
+        // Removing every topic drops every topic tag, but nothing else
+        let change = topic_tag_changes(&tags, &[]);
+        assert_eq!(change.apply(&tags), strings(&["backend"]));
+        assert!(topic_tag_changes(&strings(&["gh:rust"]), &strings(&["rust"])).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_tags_edit_writes_config() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("repos.yaml");
+        std::fs::write(
+            &path,
+            "repositories:\n  # The API\n  - name: api\n    url: git@github.com:org/api.git\n    tags: [backend, legacy]\n  - name: web\n    url: git@github.com:org/web.git\n    tags: [frontend]\n",
+        )
+        .unwrap();
+        let context = |repos: Option<Vec<String>>| CommandContext {
+            config: crate::config::Config::load(&path.to_string_lossy()).unwrap(),
+            tag: vec![],
+            exclude_tag: vec![],
+            parallel: false,
+            repos,
+        };
+
+        let add = TagsEditCommand {
+            config_path: path.clone(),
+            change: TagChange {
+                add: strings(&["team-a"]),
+                ..Default::default()
+            },
+        };
+        add.execute(&context(None)).await.unwrap();
+        let remove = TagsEditCommand {
+            config_path: path.clone(),
+            change: TagChange {
+                remove: strings(&["legacy"]),
+                ..Default::default()
+            },
+        };
+        remove
+            .execute(&context(Some(strings(&["api"]))))
+            .await
+            .unwrap();
+
+        let source = std::fs::read_to_string(&path).unwrap();
+        assert!(source.contains("  # The API\n"));
+        assert!(source.contains("tags: [backend, team-a]"));
+        assert!(source.contains("tags: [frontend, team-a]"));
+    }
+}
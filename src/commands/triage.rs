@@ -0,0 +1,439 @@
+//! Triage command implementation, stepping through the failures of a saved run
+
+use super::run::RunCommand;
+use super::shell::ShellCommand;
+use super::{Command, CommandContext};
+use crate::config::{Config, Repository};
+use crate::constants;
+use crate::state::StateStore;
+use crate::utils::run_summary::RepoRunResult;
+use crate::utils::sanitizers::sanitize_for_filename;
+use crate::utils::{RunRecord, find_run, interactive};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use clap::Args;
+use colored::*;
+use std::path::{Path, PathBuf};
+
+/// Triage command offering retries, shells and logs for each repository that failed in a run
+pub struct TriageCommand {
+    /// Directory the runs were saved to
+    pub output_dir: PathBuf,
+    /// Run id, a unique prefix of it, or `latest`
+    pub id: String,
+    /// Number of lines of captured output shown per repository
+    pub tail: usize,
+}
+
+/// What to do with a failed repository
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Retry,
+    Shell,
+    Logs,
+    MarkKnownFailing,
+    Skip,
+    Quit,
+}
+
+const ACTIONS: [(Action, &str); 6] = [
+    (Action::Retry, "Retry"),
+    (Action::Shell, "Open a shell in the repository"),
+    (Action::Logs, "Open the logs"),
+    (Action::MarkKnownFailing, "Mark as known failing"),
+    (Action::Skip, "Skip"),
+    (Action::Quit, "Quit"),
+];
+
+/// What was run, as recorded in a repository's `metadata.json`
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Job {
+    Command(String),
+    Recipe(String),
+}
+
+/// A repository's run and the settings it ran with, enough to run it again the same way
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RecordedRun {
+    job: Job,
+    /// Branch or tag the run checked out in a temporary worktree
+    git_ref: Option<String>,
+    /// Directory of the persistent worktrees the run used
+    worktrees_dir: Option<PathBuf>,
+    parallel: bool,
+}
+
+fn recorded_run(repo_dir: &Path) -> Option<RecordedRun> {
+    let content = std::fs::read_to_string(repo_dir.join("metadata.json")).ok()?;
+    let metadata: serde_json::Value = serde_json::from_str(&content).ok()?;
+    let job = if let Some(command) = metadata["command"].as_str() {
+        Job::Command(command.to_string())
+    } else {
+        Job::Recipe(metadata["recipe"].as_str()?.to_string())
+    };
+    Some(RecordedRun {
+        job,
+        git_ref: metadata["ref"].as_str().map(str::to_string),
+        worktrees_dir: metadata["worktrees_dir"].as_str().map(PathBuf::from),
+        parallel: metadata["parallel"].as_bool().unwrap_or(false),
+    })
+}
+
+/// Name of the configured repository behind a result, which runs against a ref qualify
+fn repository_name<'a>(result: &'a str, git_ref: Option<&str>) -> &'a str {
+    git_ref
+        .and_then(|git_ref| result.strip_suffix(&format!("@{}", sanitize_for_filename(git_ref))))
+        .unwrap_or(result)
+}
+
+/// The last `count` lines of `content`
+fn tail_lines(content: &str, count: usize) -> Vec<&str> {
+    let lines: Vec<&str> = content.lines().collect();
+    lines[lines.len().saturating_sub(count)..].to_vec()
+}
+
+/// How the triage session went
+#[derive(Debug, Default)]
+struct Tally {
+    passing: usize,
+    marked: usize,
+    skipped: usize,
+}
+
+impl TriageCommand {
+    /// Print the outcome of a failed repository with the end of its captured output
+    fn print_failure(&self, repo_dir: &Path, result: &RepoRunResult, index: usize, total: usize) {
+        println!();
+        let exit = match (result.exit_code, &result.exit_code_description) {
+            (Some(code), Some(description)) => format!("exit {} ({})", code, description),
+            (Some(code), None) => format!("exit {}", code),
+            (None, _) => "did not complete".to_string(),
+        };
+        println!(
+            "{} [{}/{}] {} {}",
+            "==>".bold(),
+            index + 1,
+            total,
+            result.repository.cyan().bold(),
+            exit.red()
+        );
+        if let Some(error) = &result.error {
+            println!("{}", error.red());
+        }
+
+        // Failures usually explain themselves on stderr; fall back to stdout otherwise
+        for file in ["stderr.log", "stdout.log"] {
+            let Ok(content) = std::fs::read_to_string(repo_dir.join(file)) else {
+                continue;
+            };
+            if content.trim().is_empty() {
+                continue;
+            }
+            println!(
+                "{}",
+                format!("--- last {} lines of {} ---", self.tail, file).dimmed()
+            );
+            for line in tail_lines(&content, self.tail) {
+                println!("{}", line);
+            }
+            break;
+        }
+    }
+
+    /// Run the recorded command or recipe again in one repository, returning whether it passed
+    ///
+    /// The retry checks out the same ref or worktree as the run did. Recipes
+    /// resolve their environment again from the config.
+    async fn retry(
+        &self,
+        context: &CommandContext,
+        repo: &Repository,
+        recorded: Option<&RecordedRun>,
+    ) -> bool {
+        let Some(recorded) = recorded else {
+            println!(
+                "{}",
+                "The run did not record its command; retry with `repos run`".yellow()
+            );
+            return false;
+        };
+        let run_command = match &recorded.job {
+            Job::Command(command) => RunCommand::new_command(command.clone(), true, None),
+            Job::Recipe(recipe) => RunCommand::new_recipe(recipe.clone(), true, None),
+        };
+        let mut run_command = run_command.with_refs(recorded.git_ref.iter().cloned().collect());
+        if let Some(worktrees_dir) = &recorded.worktrees_dir {
+            run_command = run_command.with_worktrees(worktrees_dir.clone());
+        }
+
+        let mut retry_context = context.clone();
+        retry_context.parallel = recorded.parallel;
+        retry_context.repos = Some(vec![repo.name.clone()]);
+        match run_command.execute(&retry_context).await {
+            Ok(()) => {
+                println!("{}", format!("{} passes now", repo.name).green());
+                true
+            }
+            Err(e) => {
+                println!("{}", format!("Still failing: {}", e).red());
+                false
+            }
+        }
+    }
+
+    /// Show the saved output in `$PAGER`, or `less`
+    fn open_logs(repo_dir: &Path) -> Result<()> {
+        let files: Vec<PathBuf> = ["stderr.log", "stdout.log"]
+            .iter()
+            .map(|file| repo_dir.join(file))
+            .filter(|path| path.exists())
+            .collect();
+        if files.is_empty() {
+            println!("{}", "No output was saved for this repository".yellow());
+            return Ok(());
+        }
+
+        let pager = std::env::var("PAGER")
+            .ok()
+            .filter(|pager| !pager.is_empty())
+            .unwrap_or_else(|| "less".to_string());
+        std::process::Command::new(&pager)
+            .args(&files)
+            .status()
+            .with_context(|| format!("Failed to start pager '{}'", pager))?;
+        Ok(())
+    }
+
+    /// Offer actions for one repository until one of them moves on, returning false to stop
+    async fn triage(
+        &self,
+        context: &CommandContext,
+        run: &RunRecord,
+        result: &RepoRunResult,
+        tally: &mut Tally,
+    ) -> Result<bool> {
+        let repo_dir = run.repository_dir(&result.repository);
+        let recorded = recorded_run(&repo_dir);
+        let name = repository_name(
+            &result.repository,
+            recorded.as_ref().and_then(|run| run.git_ref.as_deref()),
+        );
+        let repo = context
+            .config
+            .repositories
+            .iter()
+            .find(|repo| repo.name == name);
+        let labels: Vec<&str> = ACTIONS.iter().map(|(_, label)| *label).collect();
+
+        loop {
+            let Some(choice) = interactive::choose("What now?", &labels, 0)? else {
+                return Ok(false);
+            };
+            let action = ACTIONS[choice].0;
+            match (action, repo) {
+                (Action::Retry | Action::Shell, None) => {
+                    println!("{}", format!("'{}' is not in the config", name).yellow())
+                }
+                (Action::Retry, Some(repo)) => {
+                    if self.retry(context, repo, recorded.as_ref()).await {
+                        tally.passing += 1;
+                        return Ok(true);
+                    }
+                }
+                (Action::Shell, Some(repo)) => {
+                    let target_dir = repo.get_target_dir();
+                    if !Path::new(&target_dir).exists() {
+                        println!("{}", "The repository is not cloned".yellow());
+                        continue;
+                    }
+                    ShellCommand { shell: None }.open(repo, &target_dir)?;
+                }
+                (Action::Logs, _) => {
+                    if let Err(e) = Self::open_logs(&repo_dir) {
+                        println!("{}", format!("{:#}", e).red());
+                    }
+                }
+                (Action::MarkKnownFailing, _) => {
+                    StateStore::open_default().record_known_failing(name, true)?;
+                    println!(
+                        "{}",
+                        format!(
+                            "Marked '{}' as known failing; `repos run` leaves it out until it passes with --include-known-failing",
+                            name
+                        )
+                        .yellow()
+                    );
+                    tally.marked += 1;
+                    return Ok(true);
+                }
+                (Action::Skip, _) => {
+                    tally.skipped += 1;
+                    return Ok(true);
+                }
+                (Action::Quit, _) => return Ok(false),
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Command for TriageCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let run = find_run(&self.output_dir, &self.id)?;
+        let failed: Vec<&RepoRunResult> = run
+            .summary
+            .results
+            .iter()
+            .filter(|result| !result.is_success())
+            .collect();
+        if failed.is_empty() {
+            println!(
+                "{}",
+                format!("No failed repositories in run {}", run.id).green()
+            );
+            return Ok(());
+        }
+
+        println!(
+            "{} {} ({}): {} of {} repositories failed",
+            "Run".bold(),
+            run.id.cyan(),
+            run.label,
+            failed.len(),
+            run.summary.total
+        );
+
+        let mut tally = Tally::default();
+        let mut triaged = 0;
+        for (index, result) in failed.iter().enumerate() {
+            self.print_failure(
+                &run.repository_dir(&result.repository),
+                result,
+                index,
+                failed.len(),
+            );
+            let keep_going = self.triage(context, &run, result, &mut tally).await?;
+            if !keep_going {
+                break;
+            }
+            triaged += 1;
+        }
+
+        println!();
+        println!(
+            "{}",
+            format!(
+                "Triaged {} of {} failed repositories: {} passing after a retry, {} marked as known failing, {} skipped",
+                triaged,
+                failed.len(),
+                tally.passing,
+                tally.marked,
+                tally.skipped
+            )
+            .bold()
+        );
+        Ok(())
+    }
+}
+
+/// Arguments of `repos triage`
+#[derive(Debug, Args)]
+pub struct TriageArgs {
+    /// Run id, a unique prefix of it, or `latest`
+    #[arg(default_value = "latest")]
+    pub id: String,
+
+    /// Number of lines of captured output shown per repository
+    #[arg(long, default_value_t = 20)]
+    pub tail: usize,
+
+    /// Directory the runs were saved to (default: output)
+    #[arg(long)]
+    pub output_dir: Option<String>,
+
+    /// Configuration file path (can be specified multiple times to combine fleets)
+    #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+    pub config: Vec<String>,
+}
+
+impl TriageArgs {
+    /// Validate the arguments and run `repos triage`
+    pub async fn run(self) -> Result<()> {
+        let Self {
+            id,
+            tail,
+            output_dir,
+            config,
+        } = self;
+        let context = CommandContext::new(Config::load_all(&config)?);
+        TriageCommand {
+            output_dir: PathBuf::from(
+                output_dir.unwrap_or_else(|| constants::config::DEFAULT_LOGS_DIR.to_string()),
+            ),
+            id,
+            tail,
+        }
+        .execute(&context)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_tail_lines() {
+        assert_eq!(tail_lines("a\nb\nc\n", 2), vec!["b", "c"]);
+        assert_eq!(tail_lines("a\nb\n", 5), vec!["a", "b"]);
+        assert!(tail_lines("", 3).is_empty());
+    }
+
+    #[test]
+    fn test_recorded_run() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        assert_eq!(recorded_run(dir), None);
+
+        std::fs::write(
+            dir.join("metadata.json"),
+            r#"{"command": "make test", "exit_code": 2, "repository": "api"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            recorded_run(dir),
+            Some(RecordedRun {
+                job: Job::Command("make test".to_string()),
+                git_ref: None,
+                worktrees_dir: None,
+                parallel: false,
+            })
+        );
+
+        std::fs::write(
+            dir.join("metadata.json"),
+            r#"{"recipe": "lint", "exit_code": 1, "repository": "api@release_2.3", "recipe_steps": ["cargo clippy"],
+                "ref": "release/2.3", "worktrees_dir": ".repos/worktrees", "env": ["API_TOKEN"], "parallel": true}"#,
+        )
+        .unwrap();
+        let recorded = recorded_run(dir).unwrap();
+        assert_eq!(recorded.job, Job::Recipe("lint".to_string()));
+        assert_eq!(recorded.git_ref.as_deref(), Some("release/2.3"));
+        assert_eq!(
+            recorded.worktrees_dir,
+            Some(PathBuf::from(".repos/worktrees"))
+        );
+        assert!(recorded.parallel);
+    }
+
+    #[test]
+    fn test_repository_name() {
+        assert_eq!(repository_name("api", None), "api");
+        assert_eq!(
+            repository_name("api@release_2.3", Some("release/2.3")),
+            "api"
+        );
+        assert_eq!(repository_name("api@main", Some("release/2.3")), "api@main");
+    }
+}
@@ -0,0 +1,95 @@
+//! UI command implementation
+
+use super::{Command, CommandContext, GlobalOptions, validators};
+use crate::config::Config;
+use crate::constants;
+use crate::tui::{App, Dashboard};
+use anyhow::Result;
+use async_trait::async_trait;
+use clap::Args;
+use std::io::IsTerminal;
+use std::path::PathBuf;
+
+/// UI command showing the fleet on a full-screen dashboard
+pub struct UiCommand {
+    pub dashboard: Dashboard,
+}
+
+#[async_trait]
+impl Command for UiCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        if !std::io::stdout().is_terminal() || !std::io::stdin().is_terminal() {
+            anyhow::bail!("repos ui needs an interactive terminal");
+        }
+
+        let repositories = context.config.filter_repositories(
+            &context.tag,
+            &context.exclude_tag,
+            context.repos.as_deref(),
+        );
+        let recipes = context
+            .config
+            .recipes
+            .iter()
+            .map(|recipe| recipe.name.clone())
+            .collect();
+        let app = App::new(repositories, recipes);
+
+        // The dashboard blocks on terminal input
+        let dashboard = self.dashboard.clone();
+        tokio::task::spawn_blocking(move || dashboard.run(app)).await?
+    }
+}
+
+/// Arguments of `repos ui`
+#[derive(Debug, Args)]
+pub struct UiArgs {
+    /// Specific repository names to show (if not provided, uses tag filter or all repos)
+    pub repos: Vec<String>,
+
+    /// Directory runs are saved to and read from (default: output)
+    #[arg(long)]
+    pub output_dir: Option<String>,
+
+    /// Configuration file path (can be specified multiple times to combine fleets)
+    #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+    pub config: Vec<String>,
+
+    /// Filter repositories by tag (can be specified multiple times)
+    #[arg(short, long)]
+    pub tag: Vec<String>,
+
+    /// Exclude repositories with these tags (can be specified multiple times)
+    #[arg(short = 'e', long)]
+    pub exclude_tag: Vec<String>,
+}
+
+impl UiArgs {
+    /// Validate the arguments and run `repos ui`
+    pub async fn run(self, globals: &GlobalOptions) -> Result<()> {
+        let Self {
+            repos,
+            output_dir,
+            config: config_paths,
+            tag,
+            exclude_tag,
+        } = self;
+        let config = Config::load_all(&config_paths)?;
+
+        validators::validate_tag_filters(&tag)?;
+        validators::validate_tag_filters(&exclude_tag)?;
+        validators::validate_repository_names(&repos)?;
+
+        let context = globals.context(config, tag, exclude_tag, repos);
+        UiCommand {
+            dashboard: crate::tui::Dashboard {
+                config_paths,
+                output_dir: PathBuf::from(
+                    output_dir.unwrap_or_else(|| constants::config::DEFAULT_LOGS_DIR.to_string()),
+                ),
+            },
+        }
+        .execute(&context)
+        .await
+    }
+}
@@ -89,17 +89,37 @@ pub fn validate_run_args(command: &Option<String>, recipe: &Option<String>) -> R
     }
 }
 
+/// Validate the failure policy flags of `repos run`
+///
+/// Plain parallel runs start every repository at once, so only `--ordered`
+/// parallel runs have further repositories that `--fail-fast` could hold back.
+pub fn validate_fail_fast(fail_fast: bool, parallel: bool, ordered: bool) -> Result<()> {
+    if fail_fast && parallel && !ordered {
+        return Err(validation_error_to_anyhow(
+            CommandValidationError::MutualExclusivity {
+                first: "--fail-fast".to_string(),
+                second: "--parallel without --ordered".to_string(),
+            },
+        ));
+    }
+    Ok(())
+}
+
 /// Validate PR command arguments
 ///
 /// Ensures that required GitHub authentication is available
 pub fn validate_pr_args(token: &Option<String>) -> Result<()> {
-    if token.is_none() && std::env::var("GITHUB_TOKEN").is_err() {
+    if token.is_none()
+        && crate::ci::github_token().is_none()
+        && !crate::credentials::has_saved_identities()
+    {
         return Err(validation_error_to_anyhow(
             CommandValidationError::MissingRequired {
                 argument: "GitHub token".to_string(),
                 alternatives: vec![
                     "--token".to_string(),
                     "GITHUB_TOKEN environment variable".to_string(),
+                    "`repos auth login`".to_string(),
                 ],
             },
         ));
@@ -241,6 +261,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_validate_fail_fast() {
+        assert!(validate_fail_fast(true, false, false).is_ok());
+        assert!(validate_fail_fast(true, true, true).is_ok());
+        assert!(validate_fail_fast(false, true, false).is_ok());
+        assert_eq!(
+            validate_fail_fast(true, true, false)
+                .unwrap_err()
+                .to_string(),
+            "Cannot specify both --fail-fast and --parallel without --ordered"
+        );
+    }
+
     #[test]
     fn test_validate_run_args_missing_required() {
         let command = None;
@@ -440,13 +473,20 @@ mod tests {
         // Save the current environment variable state
         let original_token = std::env::var("GITHUB_TOKEN").ok();
 
-        // Temporarily remove the environment variable
+        // Temporarily remove the environment variable and any saved identities
         unsafe {
             std::env::remove_var("GITHUB_TOKEN");
+            std::env::set_var(
+                crate::constants::credentials::IDENTITIES_FILE_ENV,
+                "/nonexistent/identities.json",
+            );
         }
 
         let token = None;
         let result = validate_pr_args(&token);
+        unsafe {
+            std::env::remove_var(crate::constants::credentials::IDENTITIES_FILE_ENV);
+        }
 
         // Restore the original environment variable if it existed
         if let Some(token_value) = original_token {
@@ -0,0 +1,361 @@
+//! Watch command implementation
+
+use super::{Command, CommandContext, GlobalOptions, validators};
+use crate::config::{Config, Repository};
+use crate::runner::CommandRunner;
+use crate::{constants, logging};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use clap::Args;
+use colored::*;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use log::Level;
+use notify::event::{EventKind, ModifyKind};
+use notify::{RecursiveMode, Watcher};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+/// Watch command rerunning a command in repositories whose files change
+pub struct WatchCommand {
+    pub command: String,
+    /// Rerun the command in every repository on any change
+    pub all: bool,
+    /// Time without further changes before the command runs
+    pub debounce: Duration,
+}
+
+/// A watched repository and the ignore rules of its working tree
+struct WatchedRepository {
+    repo: Repository,
+    root: PathBuf,
+    ignore: Gitignore,
+}
+
+impl WatchedRepository {
+    fn new(repo: Repository) -> Result<Self> {
+        let root = std::fs::canonicalize(repo.get_target_dir())
+            .with_context(|| format!("Failed to resolve {}", repo.get_target_dir()))?;
+        let mut builder = GitignoreBuilder::new(&root);
+        for file in [root.join(".gitignore"), root.join(".git/info/exclude")] {
+            if file.exists() {
+                builder.add(file);
+            }
+        }
+        Ok(Self {
+            repo,
+            ignore: builder.build()?,
+            root,
+        })
+    }
+
+    /// Whether a change to `path`, inside this repository, should trigger a run
+    ///
+    /// Git's own files and anything the repository ignores, such as build
+    /// output, do not count.
+    fn is_relevant(&self, path: &Path) -> bool {
+        let Ok(relative) = path.strip_prefix(&self.root) else {
+            return false;
+        };
+        if relative.components().any(|c| c.as_os_str() == ".git") {
+            return false;
+        }
+        !self
+            .ignore
+            .matched_path_or_any_parents(path, path.is_dir())
+            .is_ignore()
+    }
+}
+
+/// Index of the repository containing `path`; the innermost one for nested clones
+fn repository_for(watched: &[WatchedRepository], path: &Path) -> Option<usize> {
+    watched
+        .iter()
+        .enumerate()
+        .filter(|(_, w)| path.starts_with(&w.root))
+        .max_by_key(|(_, w)| w.root.components().count())
+        .map(|(index, _)| index)
+}
+
+/// Whether an event changes file contents or the file tree
+fn is_change(kind: &EventKind) -> bool {
+    match kind {
+        EventKind::Create(_) | EventKind::Remove(_) => true,
+        EventKind::Modify(modify) => !matches!(modify, ModifyKind::Metadata(_)),
+        _ => false,
+    }
+}
+
+/// Outcome of the latest run in a repository
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RunState {
+    Passed,
+    Failed,
+}
+
+/// One line summarizing the latest run in every watched repository
+fn status_line(names: &[&str], states: &BTreeMap<String, RunState>) -> String {
+    let passed = states.values().filter(|s| **s == RunState::Passed).count();
+    let failed: Vec<&str> = names
+        .iter()
+        .copied()
+        .filter(|name| states.get(*name) == Some(&RunState::Failed))
+        .collect();
+    let mut line = format!("Watching {} repositories: {} passed", names.len(), passed);
+    if !failed.is_empty() {
+        line.push_str(&format!(
+            ", {} failed ({})",
+            failed.len(),
+            failed.join(", ")
+        ));
+    }
+    let not_run = names.len() - passed - failed.len();
+    if not_run > 0 {
+        line.push_str(&format!(", {} not run", not_run));
+    }
+    line
+}
+
+#[async_trait]
+impl Command for WatchCommand {
+    async fn execute(&self, context: &CommandContext) -> Result<()> {
+        let repositories = context.config.filter_repositories(
+            &context.tag,
+            &context.exclude_tag,
+            context.repos.as_deref(),
+        );
+
+        let mut watched = Vec::new();
+        for repo in repositories {
+            if !Path::new(&repo.get_target_dir()).exists() {
+                logging::status(
+                    Level::Warn,
+                    &repo.name,
+                    "Skipping: repository is not cloned".yellow(),
+                );
+                continue;
+            }
+            watched.push(WatchedRepository::new(repo)?);
+        }
+        if watched.is_empty() {
+            logging::message(Level::Info, "No repositories found".yellow());
+            return Ok(());
+        }
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                if let Ok(event) = event {
+                    let _ = tx.send(event);
+                }
+            })?;
+        for w in &watched {
+            watcher
+                .watch(&w.root, RecursiveMode::Recursive)
+                .with_context(|| format!("Failed to watch {}", w.root.display()))?;
+        }
+
+        let runner = CommandRunner::new().with_exit_codes(context.config.exit_codes.clone());
+        let names: Vec<&str> = watched.iter().map(|w| w.repo.name.as_str()).collect();
+        let mut states: BTreeMap<String, RunState> = BTreeMap::new();
+
+        logging::message(
+            Level::Info,
+            format!(
+                "Watching {} repositories for changes to run '{}' (Ctrl+C to stop)",
+                watched.len(),
+                self.command
+            )
+            .green(),
+        );
+
+        loop {
+            // Wait for the first change, then until changes stop for the debounce period
+            let mut changed: BTreeMap<usize, PathBuf> = BTreeMap::new();
+            let mut deadline: Option<Instant> = None;
+            loop {
+                let event = match deadline {
+                    None => tokio::select! {
+                        event = rx.recv() => event,
+                        _ = tokio::signal::ctrl_c() => return Ok(()),
+                    },
+                    Some(deadline) => tokio::select! {
+                        event = rx.recv() => event,
+                        _ = tokio::time::sleep_until(deadline.into()) => break,
+                        _ = tokio::signal::ctrl_c() => return Ok(()),
+                    },
+                };
+                let Some(event) = event else {
+                    return Ok(());
+                };
+                if !is_change(&event.kind) {
+                    continue;
+                }
+                for path in &event.paths {
+                    if let Some(index) = repository_for(&watched, path)
+                        && watched[index].is_relevant(path)
+                    {
+                        changed.entry(index).or_insert_with(|| path.clone());
+                        deadline = Some(Instant::now() + self.debounce);
+                    }
+                }
+            }
+
+            let targets: Vec<usize> = if self.all {
+                (0..watched.len()).collect()
+            } else {
+                changed.keys().copied().collect()
+            };
+            for (index, path) in &changed {
+                let w = &watched[*index];
+                let relative = path.strip_prefix(&w.root).unwrap_or(path);
+                logging::status(
+                    Level::Info,
+                    &w.repo.name,
+                    format!("Changed: {}", relative.display()).dimmed(),
+                );
+            }
+
+            for index in targets {
+                let repo = &watched[index].repo;
+                let state = match runner.run_command(repo, &self.command, None).await {
+                    Ok(()) => RunState::Passed,
+                    Err(e) => {
+                        logging::status(Level::Error, &repo.name, format!("Error: {e}").red());
+                        RunState::Failed
+                    }
+                };
+                states.insert(repo.name.clone(), state);
+            }
+
+            // Changes made by the command itself do not trigger another run
+            while rx.try_recv().is_ok() {}
+
+            let line = status_line(&names, &states);
+            if states.values().any(|s| *s == RunState::Failed) {
+                logging::message(Level::Info, line.yellow());
+            } else {
+                logging::message(Level::Info, line.green());
+            }
+        }
+    }
+}
+
+/// Arguments of `repos watch`
+#[derive(Debug, Args)]
+pub struct WatchArgs {
+    /// Command to run, e.g. "cargo test"
+    #[arg(value_name = "COMMAND")]
+    pub command: String,
+
+    /// Specific repository names to watch (if not provided, uses tag filter or all repos)
+    pub repos: Vec<String>,
+
+    /// Rerun the command in all watched repositories, not only the one that changed
+    #[arg(long)]
+    pub all: bool,
+
+    /// Milliseconds to wait for further changes before running the command
+    #[arg(long, default_value_t = constants::watch::DEFAULT_DEBOUNCE_MS)]
+    pub debounce: u64,
+
+    /// Configuration file path (can be specified multiple times to combine fleets)
+    #[arg(short, long, default_value = constants::config::DEFAULT_CONFIG_FILE)]
+    pub config: Vec<String>,
+
+    /// Filter repositories by tag (can be specified multiple times)
+    #[arg(short, long)]
+    pub tag: Vec<String>,
+
+    /// Exclude repositories with these tags (can be specified multiple times)
+    #[arg(short = 'e', long)]
+    pub exclude_tag: Vec<String>,
+}
+
+impl WatchArgs {
+    /// Validate the arguments and run `repos watch`
+    pub async fn run(self, globals: &GlobalOptions) -> Result<()> {
+        let Self {
+            command,
+            repos,
+            all,
+            debounce,
+            config,
+            tag,
+            exclude_tag,
+        } = self;
+        let config = Config::load_all(&config)?;
+
+        validators::validate_tag_filters(&tag)?;
+        validators::validate_tag_filters(&exclude_tag)?;
+        validators::validate_repository_names(&repos)?;
+
+        let context = globals.context(config, tag, exclude_tag, repos);
+        WatchCommand {
+            command,
+            all,
+            debounce: Duration::from_millis(debounce),
+        }
+        .execute(&context)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn watched(root: &Path, name: &str) -> WatchedRepository {
+        let dir = root.join(name);
+        fs::create_dir_all(dir.join(".git")).unwrap();
+        let mut repo =
+            Repository::new(name.to_string(), format!("git@github.com:org/{}.git", name));
+        repo.path = Some(dir.to_string_lossy().to_string());
+        WatchedRepository::new(repo).unwrap()
+    }
+
+    #[test]
+    fn test_repository_for_and_ignored_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = fs::canonicalize(temp_dir.path()).unwrap();
+        fs::create_dir_all(root.join("api")).unwrap();
+        fs::write(root.join("api/.gitignore"), "target/\n*.log\n").unwrap();
+        let repos = vec![watched(&root, "api"), watched(&root, "api/vendor/lib")];
+
+        assert_eq!(
+            repository_for(&repos, &root.join("api/src/main.rs")),
+            Some(0)
+        );
+        assert_eq!(
+            repository_for(&repos, &root.join("api/vendor/lib/mod.rs")),
+            Some(1)
+        );
+        assert_eq!(repository_for(&repos, &root.join("web/index.js")), None);
+
+        let api = &repos[0];
+        assert!(api.is_relevant(&root.join("api/src/main.rs")));
+        assert!(!api.is_relevant(&root.join("api/.git/index")));
+        assert!(!api.is_relevant(&root.join("api/target/debug/api")));
+        assert!(!api.is_relevant(&root.join("api/build.log")));
+    }
+
+    #[test]
+    fn test_status_line() {
+        let names = ["api", "web", "docs"];
+        let mut states = BTreeMap::new();
+        assert_eq!(
+            status_line(&names, &states),
+            "Watching 3 repositories: 0 passed, 3 not run"
+        );
+
+        states.insert("api".to_string(), RunState::Passed);
+        states.insert("web".to_string(), RunState::Failed);
+        assert_eq!(
+            status_line(&names, &states),
+            "Watching 3 repositories: 1 passed, 1 failed (web), 1 not run"
+        );
+    }
+}
@@ -0,0 +1,90 @@
+//! User-defined commands from the config file
+//!
+//! The `aliases` section names whole `repos` invocations, like git aliases:
+//!
+//! ```yaml
+//! aliases:
+//!   weekly-update: run --recipe update-deps -t backend --parallel
+//! ```
+//!
+//! `repos weekly-update -e legacy` then runs
+//! `repos run --recipe update-deps -t backend --parallel -e legacy`. Aliases are
+//! looked up before external plugins; built-in commands cannot be redefined.
+
+use super::import::shell_words;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+#[derive(Deserialize)]
+struct AliasesFile {
+    #[serde(default)]
+    aliases: BTreeMap<String, String>,
+}
+
+/// The `aliases` of a config file, by name; none when the file does not exist
+pub fn load_aliases(path: &str) -> Result<BTreeMap<String, String>> {
+    if !Path::new(path).exists() {
+        return Ok(BTreeMap::new());
+    }
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config file '{}'", path))?;
+    let file: AliasesFile = serde_yaml::from_str(&content)
+        .with_context(|| format!("Failed to read 'aliases' from '{}'", path))?;
+    Ok(file.aliases)
+}
+
+/// The arguments an alias stands for, followed by the ones given after it
+pub fn expand_alias(definition: &str, args: &[String]) -> Result<Vec<String>> {
+    let mut expanded = shell_words(definition);
+    if expanded.is_empty() {
+        anyhow::bail!("Alias is empty");
+    }
+    expanded.extend(args.iter().cloned());
+    Ok(expanded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_load_and_expand_alias() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("repos.yaml");
+        std::fs::write(
+            &path,
+            "aliases:\n  weekly-update: run --recipe update-deps -t backend --parallel\n  hello: run \"echo 'hello world'\"\nrepositories: []\n",
+        )
+        .unwrap();
+
+        let aliases = load_aliases(&path.to_string_lossy()).unwrap();
+        assert_eq!(
+            expand_alias(
+                &aliases["weekly-update"],
+                &["-e".to_string(), "legacy".to_string()]
+            )
+            .unwrap(),
+            vec![
+                "run",
+                "--recipe",
+                "update-deps",
+                "-t",
+                "backend",
+                "--parallel",
+                "-e",
+                "legacy"
+            ]
+        );
+        assert_eq!(
+            expand_alias(&aliases["hello"], &[]).unwrap(),
+            vec!["run", "echo 'hello world'"]
+        );
+        assert!(expand_alias("  ", &[]).is_err());
+
+        let missing = temp_dir.path().join("missing.yaml");
+        assert!(load_aliases(&missing.to_string_lossy()).unwrap().is_empty());
+    }
+}
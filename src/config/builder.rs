@@ -1,6 +1,7 @@
 //! Repository builder utilities
 
-use super::Repository;
+use super::{Provider, Repository};
+use std::collections::BTreeMap;
 
 /// Builder for creating repository configurations
 pub struct RepositoryBuilder {
@@ -9,6 +10,10 @@ pub struct RepositoryBuilder {
     tags: Vec<String>,
     path: Option<String>,
     branch: Option<String>,
+    sparse: Vec<String>,
+    provider: Option<Provider>,
+    provider_url: Option<String>,
+    remotes: BTreeMap<String, String>,
 }
 
 impl RepositoryBuilder {
@@ -20,6 +25,10 @@ impl RepositoryBuilder {
             tags: Vec::new(),
             path: None,
             branch: None,
+            sparse: Vec::new(),
+            provider: None,
+            provider_url: None,
+            remotes: BTreeMap::new(),
         }
     }
 
@@ -41,6 +50,25 @@ impl RepositoryBuilder {
         self
     }
 
+    /// Only check out these directories when cloning
+    pub fn with_sparse(mut self, sparse: Vec<String>) -> Self {
+        self.sparse = sparse;
+        self
+    }
+
+    /// Set the hosting provider, optionally with the base URL of a self-hosted instance
+    pub fn with_provider(mut self, provider: Provider, provider_url: Option<String>) -> Self {
+        self.provider = Some(provider);
+        self.provider_url = provider_url;
+        self
+    }
+
+    /// Record additional remotes by name
+    pub fn with_remotes(mut self, remotes: BTreeMap<String, String>) -> Self {
+        self.remotes = remotes;
+        self
+    }
+
     /// Build the repository
     pub fn build(self) -> Repository {
         Repository {
@@ -50,6 +78,17 @@ impl RepositoryBuilder {
             path: self.path,
             branch: self.branch,
             config_dir: None,
+            path_strategy: Default::default(),
+            default_branch: None,
+            sparse: self.sparse,
+            provider: self.provider,
+            provider_url: self.provider_url,
+            remotes: self.remotes,
+            provider_defaults: Default::default(),
+            git: Default::default(),
+            depends_on: Vec::new(),
+            post_clone: None,
+            archived: false,
         }
     }
 }
@@ -105,6 +144,32 @@ mod tests {
         assert_eq!(repo.branch, Some("feature-branch".to_string()));
     }
 
+    #[test]
+    fn test_repository_builder_with_sparse() {
+        let repo = RepositoryBuilder::new(
+            "monorepo".to_string(),
+            "https://github.com/company/monorepo.git".to_string(),
+        )
+        .with_sparse(vec!["services/api".to_string(), "libs".to_string()])
+        .build();
+        assert_eq!(repo.sparse, vec!["services/api", "libs"]);
+    }
+
+    #[test]
+    fn test_repository_builder_with_provider() {
+        let repo = RepositoryBuilder::new(
+            "gitea-repo".to_string(),
+            "git@git.example.com:company/gitea-repo.git".to_string(),
+        )
+        .with_provider(Provider::Gitea, Some("https://git.example.com".to_string()))
+        .build();
+        assert_eq!(repo.provider(), Provider::Gitea);
+        assert_eq!(
+            repo.provider_url(),
+            Some("https://git.example.com".to_string())
+        );
+    }
+
     #[test]
     fn test_repository_builder_with_all_options() {
         let tags = vec!["frontend".to_string(), "javascript".to_string()];
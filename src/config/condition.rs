@@ -0,0 +1,347 @@
+//! Conditions that decide per repository whether a recipe step runs
+//!
+//! A condition compares a property of the repository with a quoted value, or
+//! checks for a file, and conditions combine with `&&`, `||`, `!` and
+//! parentheses:
+//!
+//! ```text
+//! tag == 'node'
+//! os != 'windows' && file_exists('package.json')
+//! !(name == 'legacy' || tag == 'archived')
+//! ```
+//!
+//! `tag` compares against each of the repository's tags, `name` against its
+//! name and `os` against the platform `repos` runs on (`linux`, `macos` or
+//! `windows`). `file_exists` takes a path relative to the repository root.
+
+use super::Repository;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+/// A parsed step condition, kept together with its source text
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Condition {
+    source: String,
+    expr: Expr,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Property {
+    Tag,
+    Name,
+    Os,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Expr {
+    Compare {
+        property: Property,
+        equal: bool,
+        value: String,
+    },
+    FileExists(String),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Eq,
+    Ne,
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Ident(name) => write!(f, "'{}'", name),
+            Token::Str(value) => write!(f, "string '{}'", value),
+            Token::Eq => write!(f, "'=='"),
+            Token::Ne => write!(f, "'!='"),
+            Token::And => write!(f, "'&&'"),
+            Token::Or => write!(f, "'||'"),
+            Token::Not => write!(f, "'!'"),
+            Token::Open => write!(f, "'('"),
+            Token::Close => write!(f, "')'"),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+    while let Some(c) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '=' if chars.next_if_eq(&'=').is_some() => Token::Eq,
+            '!' if chars.next_if_eq(&'=').is_some() => Token::Ne,
+            '!' => Token::Not,
+            '&' if chars.next_if_eq(&'&').is_some() => Token::And,
+            '|' if chars.next_if_eq(&'|').is_some() => Token::Or,
+            '\'' | '"' => {
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some(end) if end == c => break,
+                        Some(ch) => value.push(ch),
+                        None => anyhow::bail!("unterminated string"),
+                    }
+                }
+                Token::Str(value)
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut name = c.to_string();
+                while let Some(ch) = chars.next_if(|ch| ch.is_ascii_alphanumeric() || *ch == '_') {
+                    name.push(ch);
+                }
+                Token::Ident(name)
+            }
+            c => anyhow::bail!("unexpected character '{}'", c),
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent parser; `&&` binds tighter than `||`
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn accept(&mut self, token: &Token) -> bool {
+        if self.tokens.get(self.position) == Some(token) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, expected: &Token) -> Result<()> {
+        match self.next() {
+            Some(token) if &token == expected => Ok(()),
+            Some(token) => anyhow::bail!("expected {} but found {}", expected, token),
+            None => anyhow::bail!("expected {} at the end", expected),
+        }
+    }
+
+    fn string(&mut self) -> Result<String> {
+        match self.next() {
+            Some(Token::Str(value)) => Ok(value),
+            Some(token) => anyhow::bail!("expected a quoted string but found {}", token),
+            None => anyhow::bail!("expected a quoted string at the end"),
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr> {
+        let mut expr = self.and()?;
+        while self.accept(&Token::Or) {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr> {
+        let mut expr = self.unary()?;
+        while self.accept(&Token::And) {
+            expr = Expr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        if self.accept(&Token::Not) {
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        if self.accept(&Token::Open) {
+            let expr = self.or()?;
+            self.expect(&Token::Close)?;
+            return Ok(expr);
+        }
+
+        let name = match self.next() {
+            Some(Token::Ident(name)) => name,
+            Some(token) => anyhow::bail!("expected a condition but found {}", token),
+            None => anyhow::bail!("expected a condition at the end"),
+        };
+        if name == "file_exists" {
+            self.expect(&Token::Open)?;
+            let path = self.string()?;
+            self.expect(&Token::Close)?;
+            return Ok(Expr::FileExists(path));
+        }
+
+        let property = match name.as_str() {
+            "tag" => Property::Tag,
+            "name" => Property::Name,
+            "os" => Property::Os,
+            _ => anyhow::bail!(
+                "unknown name '{}' (expected tag, name, os or file_exists)",
+                name
+            ),
+        };
+        let equal = match self.next() {
+            Some(Token::Eq) => true,
+            Some(Token::Ne) => false,
+            Some(token) => anyhow::bail!("expected '==' or '!=' but found {}", token),
+            None => anyhow::bail!("expected '==' or '!=' after '{}'", name),
+        };
+        Ok(Expr::Compare {
+            property,
+            equal,
+            value: self.string()?,
+        })
+    }
+}
+
+impl Expr {
+    fn matches(&self, repo: &Repository, dir: &Path) -> bool {
+        match self {
+            Expr::Compare {
+                property,
+                equal,
+                value,
+            } => {
+                let found = match property {
+                    Property::Tag => repo.has_tag(value),
+                    Property::Name => &repo.name == value,
+                    Property::Os => std::env::consts::OS == value,
+                };
+                found == *equal
+            }
+            Expr::FileExists(path) => dir.join(path).exists(),
+            Expr::Not(expr) => !expr.matches(repo, dir),
+            Expr::And(left, right) => left.matches(repo, dir) && right.matches(repo, dir),
+            Expr::Or(left, right) => left.matches(repo, dir) || right.matches(repo, dir),
+        }
+    }
+}
+
+impl Condition {
+    /// Whether the condition holds for `repo`, checked out in `dir`
+    pub fn matches(&self, repo: &Repository, dir: &Path) -> bool {
+        self.expr.matches(repo, dir)
+    }
+}
+
+impl FromStr for Condition {
+    type Err = anyhow::Error;
+
+    fn from_str(source: &str) -> Result<Self> {
+        let parse = || -> Result<Expr> {
+            let mut parser = Parser {
+                tokens: tokenize(source)?,
+                position: 0,
+            };
+            let expr = parser.or()?;
+            if let Some(token) = parser.next() {
+                anyhow::bail!("unexpected {} after the condition", token);
+            }
+            Ok(expr)
+        };
+        let expr = parse().map_err(|e| anyhow::anyhow!("Invalid condition '{}': {}", source, e))?;
+        Ok(Self {
+            source: source.trim().to_string(),
+            expr,
+        })
+    }
+}
+
+impl TryFrom<String> for Condition {
+    type Error = anyhow::Error;
+
+    fn try_from(source: String) -> Result<Self> {
+        source.parse()
+    }
+}
+
+impl From<Condition> for String {
+    fn from(condition: Condition) -> Self {
+        condition.source
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn holds(condition: &str, repo: &Repository, dir: &Path) -> bool {
+        condition.parse::<Condition>().unwrap().matches(repo, dir)
+    }
+
+    #[test]
+    fn test_conditions() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        std::fs::write(dir.join("package.json"), "{}").unwrap();
+        let mut repo = Repository::new("web".to_string(), "git@github.com:org/web.git".to_string());
+        repo.tags = vec!["node".to_string(), "frontend".to_string()];
+
+        assert!(holds("tag == 'node'", &repo, dir));
+        assert!(!holds("tag == \"java\"", &repo, dir));
+        assert!(holds("tag != 'java'", &repo, dir));
+        assert!(holds("file_exists('package.json')", &repo, dir));
+        assert!(!holds("file_exists('Cargo.toml')", &repo, dir));
+        assert!(holds(
+            &format!("os == '{}'", std::env::consts::OS),
+            &repo,
+            dir
+        ));
+        assert!(holds(
+            "tag == 'java' || name == 'web' && !file_exists('Cargo.toml')",
+            &repo,
+            dir
+        ));
+        assert!(!holds(
+            "(tag == 'java' || name == 'web') && file_exists('Cargo.toml')",
+            &repo,
+            dir
+        ));
+    }
+
+    #[test]
+    fn test_invalid_conditions() {
+        for (source, message) in [
+            ("tag = 'node'", "unexpected character '='"),
+            ("tag == node", "expected a quoted string but found 'node'"),
+            ("branch == 'main'", "unknown name 'branch'"),
+            ("tag == 'node' &&", "expected a condition at the end"),
+            ("(os == 'linux'", "expected ')' at the end"),
+            (
+                "file_exists('a') 'b'",
+                "unexpected string 'b' after the condition",
+            ),
+        ] {
+            let error = source.parse::<Condition>().unwrap_err().to_string();
+            assert!(error.contains(message), "{}: {}", source, error);
+        }
+    }
+}
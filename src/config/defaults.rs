@@ -0,0 +1,266 @@
+//! Default command-line flags from the config file
+//!
+//! The `defaults` section seeds option values so they need not be repeated on
+//! every invocation:
+//!
+//! ```yaml
+//! defaults:
+//!   parallel: true        # every command with --parallel
+//!   jobs: 8               # every command with --jobs
+//!   run:
+//!     no_save: true       # only `repos run`
+//!   pr:
+//!     draft: true
+//!     base: develop
+//! ```
+//!
+//! Scalars and lists at the top of the section apply to every command that
+//! has the option; mappings apply to one command and its subcommands, and win
+//! over the global values. Keys are option names, with `-` or `_`. The values
+//! become the options' defaults, so flags given on the command line still win.
+
+use anyhow::{Context, Result};
+use clap::Command;
+use serde::Deserialize;
+use serde_yaml::Value;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// The `defaults` section of one or more config files
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CliDefaults {
+    /// Values for every command that has the option
+    global: BTreeMap<String, Value>,
+    /// Values for one top-level command, by command name
+    commands: BTreeMap<String, BTreeMap<String, Value>>,
+}
+
+#[derive(Deserialize)]
+struct DefaultsFile {
+    #[serde(default)]
+    defaults: BTreeMap<String, Value>,
+}
+
+/// Option id of a key, which clap derives from the field name
+fn option_id(key: &str) -> String {
+    key.replace('-', "_")
+}
+
+/// The command-line form of a default value
+fn option_values(key: &str, value: &Value) -> Result<Vec<String>> {
+    match value {
+        Value::Null => Ok(Vec::new()),
+        Value::Bool(flag) => Ok(vec![flag.to_string()]),
+        Value::Number(number) => Ok(vec![number.to_string()]),
+        Value::String(text) => Ok(vec![text.clone()]),
+        Value::Sequence(items) => items
+            .iter()
+            .map(|item| match item {
+                Value::Sequence(_) | Value::Mapping(_) => {
+                    anyhow::bail!("Default '{}' must be a value or a list of values", key)
+                }
+                item => Ok(option_values(key, item)?.concat()),
+            })
+            .collect(),
+        Value::Mapping(_) | Value::Tagged(_) => {
+            anyhow::bail!("Default '{}' must be a value or a list of values", key)
+        }
+    }
+}
+
+/// Whether `command` or one of its subcommands has the option `id`
+fn has_option(command: &Command, id: &str) -> bool {
+    command
+        .get_arguments()
+        .any(|arg| arg.get_id() == id && !arg.is_positional())
+        || command
+            .get_subcommands()
+            .any(|subcommand| has_option(subcommand, id))
+}
+
+/// Set the default of option `id` in `command` and all its subcommands
+fn set_default(mut command: Command, id: &str, values: &[String]) -> Command {
+    if command
+        .get_arguments()
+        .any(|arg| arg.get_id() == id && !arg.is_positional())
+    {
+        let values = values.to_vec();
+        command = command.mut_arg(id, |arg| arg.default_values(values));
+    }
+    let names: Vec<String> = command
+        .get_subcommands()
+        .map(|subcommand| subcommand.get_name().to_string())
+        .collect();
+    for name in names {
+        command = command.mut_subcommand(name, |subcommand| set_default(subcommand, id, values));
+    }
+    command
+}
+
+impl CliDefaults {
+    /// Read the `defaults` of the given config files; later files win
+    ///
+    /// Files that do not exist are skipped, since `repos init` and others run
+    /// without a config.
+    pub fn load(paths: &[String]) -> Result<Self> {
+        let mut defaults = CliDefaults::default();
+        for path in paths.iter().filter(|path| Path::new(path).exists()) {
+            let content = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read config file '{}'", path))?;
+            let file: DefaultsFile = serde_yaml::from_str(&content)
+                .with_context(|| format!("Failed to read 'defaults' from '{}'", path))?;
+            defaults.merge(file.defaults);
+        }
+        Ok(defaults)
+    }
+
+    fn merge(&mut self, section: BTreeMap<String, Value>) {
+        for (key, value) in section {
+            match value {
+                Value::Mapping(options) => {
+                    let command = self.commands.entry(key).or_default();
+                    for (option, value) in options {
+                        if let Some(option) = option.as_str() {
+                            command.insert(option.to_string(), value);
+                        }
+                    }
+                }
+                value => {
+                    self.global.insert(key, value);
+                }
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.global.is_empty() && self.commands.is_empty()
+    }
+
+    /// Make the defaults the default values of the options of `command`
+    ///
+    /// Unknown commands and options are errors, so typos do not go unnoticed.
+    pub fn apply(&self, mut command: Command) -> Result<Command> {
+        for (key, value) in &self.global {
+            let id = option_id(key);
+            if !has_option(&command, &id) {
+                anyhow::bail!("Unknown option '{}' in 'defaults'", key);
+            }
+            command = set_default(command, &id, &option_values(key, value)?);
+        }
+
+        for (name, options) in &self.commands {
+            let Some(subcommand) = command.find_subcommand(name) else {
+                anyhow::bail!("Unknown command '{}' in 'defaults'", name);
+            };
+            for key in options.keys() {
+                if !has_option(subcommand, &option_id(key)) {
+                    anyhow::bail!(
+                        "Unknown option '{}' for 'repos {}' in 'defaults'",
+                        key,
+                        name
+                    );
+                }
+            }
+            let options = options
+                .iter()
+                .map(|(key, value)| Ok((option_id(key), option_values(key, value)?)))
+                .collect::<Result<Vec<_>>>()?;
+            command = command.mut_subcommand(name, |mut subcommand| {
+                for (id, values) in &options {
+                    subcommand = set_default(subcommand, id, values);
+                }
+                subcommand
+            });
+        }
+        Ok(command)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{Arg, ArgAction};
+
+    fn cli() -> Command {
+        Command::new("repos")
+            .subcommand(
+                Command::new("run")
+                    .arg(Arg::new("command"))
+                    .arg(
+                        Arg::new("parallel")
+                            .long("parallel")
+                            .action(ArgAction::SetTrue),
+                    )
+                    .arg(
+                        Arg::new("no_save")
+                            .long("no-save")
+                            .action(ArgAction::SetTrue),
+                    )
+                    .arg(Arg::new("jobs").long("jobs").default_value("4")),
+            )
+            .subcommand(
+                Command::new("pr")
+                    .arg(Arg::new("base").long("base"))
+                    .arg(Arg::new("tag").long("tag").action(ArgAction::Append)),
+            )
+    }
+
+    fn defaults(yaml: &str) -> CliDefaults {
+        let file: DefaultsFile = serde_yaml::from_str(yaml).unwrap();
+        let mut defaults = CliDefaults::default();
+        defaults.merge(file.defaults);
+        defaults
+    }
+
+    #[test]
+    fn test_apply_seeds_defaults_that_flags_override() {
+        let defaults = defaults(
+            "defaults:\n  parallel: true\n  jobs: 8\n  run:\n    no-save: true\n    jobs: 2\n  pr:\n    base: develop\n    tag: [backend, core]\n",
+        );
+        let command = defaults.apply(cli()).unwrap();
+
+        let matches = command.clone().get_matches_from(["repos", "run", "ls"]);
+        let (_, run) = matches.subcommand().unwrap();
+        assert!(run.get_flag("parallel"));
+        assert!(run.get_flag("no_save"));
+        // The command's own section wins over the global value
+        assert_eq!(run.get_one::<String>("jobs").unwrap(), "2");
+
+        let matches = command
+            .clone()
+            .get_matches_from(["repos", "run", "--jobs", "16", "ls"]);
+        let (_, run) = matches.subcommand().unwrap();
+        assert_eq!(run.get_one::<String>("jobs").unwrap(), "16");
+
+        let matches = command.get_matches_from(["repos", "pr", "--base", "main"]);
+        let (_, pr) = matches.subcommand().unwrap();
+        assert_eq!(pr.get_one::<String>("base").unwrap(), "main");
+        let tags: Vec<_> = pr.get_many::<String>("tag").unwrap().collect();
+        assert_eq!(tags, vec!["backend", "core"]);
+    }
+
+    #[test]
+    fn test_apply_rejects_unknown_names() {
+        assert!(
+            defaults("defaults:\n  paralel: true\n")
+                .apply(cli())
+                .is_err()
+        );
+        assert!(
+            defaults("defaults:\n  runn:\n    jobs: 2\n")
+                .apply(cli())
+                .is_err()
+        );
+        assert!(
+            defaults("defaults:\n  pr:\n    no_save: true\n")
+                .apply(cli())
+                .is_err()
+        );
+        // Positional arguments are not options
+        assert!(
+            defaults("defaults:\n  run:\n    command: ls\n")
+                .apply(cli())
+                .is_err()
+        );
+    }
+}
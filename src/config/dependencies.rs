@@ -0,0 +1,223 @@
+//! Dependencies between repositories declared with `depends_on`
+
+use super::Repository;
+use anyhow::Result;
+use std::collections::HashMap;
+
+/// Dependencies among a selection of repositories
+///
+/// A selected repository depends on every selected repository it reaches
+/// through `depends_on`, also when the path leads through repositories that
+/// are not selected, so the order holds for any filter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyGraph {
+    /// Indices into the selection of the repositories each one depends on
+    dependencies: Vec<Vec<usize>>,
+}
+
+impl DependencyGraph {
+    /// Build the graph of `selected` from the `depends_on` of all repositories
+    ///
+    /// Fails when a repository depends on one that does not exist, or when
+    /// the dependencies form a cycle.
+    pub fn new(all: &[Repository], selected: &[Repository]) -> Result<Self> {
+        let index: HashMap<&str, usize> = all
+            .iter()
+            .enumerate()
+            .map(|(i, repo)| (repo.name.as_str(), i))
+            .collect();
+
+        let mut edges: Vec<Vec<usize>> = Vec::with_capacity(all.len());
+        for repo in all {
+            let mut targets = Vec::new();
+            for dependency in &repo.depends_on {
+                let Some(&target) = index.get(dependency.as_str()) else {
+                    anyhow::bail!(
+                        "Repository '{}' depends on unknown repository '{}'",
+                        repo.name,
+                        dependency
+                    );
+                };
+                targets.push(target);
+            }
+            edges.push(targets);
+        }
+
+        if let Some(cycle) = find_cycle(&edges) {
+            let names: Vec<&str> = cycle.iter().map(|&i| all[i].name.as_str()).collect();
+            anyhow::bail!(
+                "Dependency cycle between repositories: {}",
+                names.join(" -> ")
+            );
+        }
+
+        let position: HashMap<&str, usize> = selected
+            .iter()
+            .enumerate()
+            .map(|(i, repo)| (repo.name.as_str(), i))
+            .collect();
+
+        let dependencies = selected
+            .iter()
+            .map(|repo| {
+                let mut found = Vec::new();
+                let mut visited = vec![false; all.len()];
+                let mut stack: Vec<usize> = index
+                    .get(repo.name.as_str())
+                    .map(|&i| edges[i].clone())
+                    .unwrap_or_default();
+                while let Some(node) = stack.pop() {
+                    if std::mem::replace(&mut visited[node], true) {
+                        continue;
+                    }
+                    match position.get(all[node].name.as_str()) {
+                        Some(&selected_index) => found.push(selected_index),
+                        // Look through repositories left out by the filters
+                        None => stack.extend(&edges[node]),
+                    }
+                }
+                found.sort_unstable();
+                found
+            })
+            .collect();
+
+        Ok(Self { dependencies })
+    }
+
+    /// Indices of the selected repositories that `index` depends on
+    pub fn dependencies(&self, index: usize) -> &[usize] {
+        &self.dependencies[index]
+    }
+
+    /// Selection indices with dependencies before their dependents
+    ///
+    /// Among repositories whose dependencies are done, the one selected first
+    /// comes first.
+    pub fn order(&self) -> Vec<usize> {
+        let mut done = vec![false; self.dependencies.len()];
+        let mut order = Vec::with_capacity(self.dependencies.len());
+        while order.len() < self.dependencies.len() {
+            let next = (0..self.dependencies.len())
+                .find(|&i| !done[i] && self.dependencies[i].iter().all(|&d| done[d]))
+                .expect("dependency graph has no cycles");
+            done[next] = true;
+            order.push(next);
+        }
+        order
+    }
+}
+
+/// A cycle in a graph given as adjacency lists, starting and ending at the same node
+fn find_cycle(edges: &[Vec<usize>]) -> Option<Vec<usize>> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum State {
+        New,
+        Active,
+        Done,
+    }
+
+    fn visit(
+        node: usize,
+        edges: &[Vec<usize>],
+        state: &mut [State],
+        path: &mut Vec<usize>,
+    ) -> Option<Vec<usize>> {
+        state[node] = State::Active;
+        path.push(node);
+        for &next in &edges[node] {
+            match state[next] {
+                State::Active => {
+                    let start = path.iter().position(|&n| n == next).unwrap_or(0);
+                    let mut cycle = path[start..].to_vec();
+                    cycle.push(next);
+                    return Some(cycle);
+                }
+                State::New => {
+                    if let Some(cycle) = visit(next, edges, state, path) {
+                        return Some(cycle);
+                    }
+                }
+                State::Done => {}
+            }
+        }
+        path.pop();
+        state[node] = State::Done;
+        None
+    }
+
+    let mut state = vec![State::New; edges.len()];
+    for node in 0..edges.len() {
+        if state[node] == State::New
+            && let Some(cycle) = visit(node, edges, &mut state, &mut Vec::new())
+        {
+            return Some(cycle);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo(name: &str, depends_on: &[&str]) -> Repository {
+        let mut repo =
+            Repository::new(name.to_string(), format!("git@github.com:org/{}.git", name));
+        repo.depends_on = depends_on.iter().map(|d| d.to_string()).collect();
+        repo
+    }
+
+    #[test]
+    fn test_order_puts_dependencies_first() {
+        let all = vec![
+            repo("app", &["api", "web"]),
+            repo("web", &["ui-kit"]),
+            repo("api", &["core"]),
+            repo("ui-kit", &[]),
+            repo("core", &[]),
+        ];
+        let graph = DependencyGraph::new(&all, &all).unwrap();
+        assert_eq!(graph.dependencies(0), &[1, 2]);
+        let names: Vec<&str> = graph
+            .order()
+            .into_iter()
+            .map(|i| all[i].name.as_str())
+            .collect();
+        assert_eq!(names, vec!["ui-kit", "web", "core", "api", "app"]);
+    }
+
+    #[test]
+    fn test_dependencies_through_unselected_repositories() {
+        let all = vec![
+            repo("app", &["api"]),
+            repo("api", &["core"]),
+            repo("core", &[]),
+        ];
+        let selected = vec![all[0].clone(), all[2].clone()];
+        let graph = DependencyGraph::new(&all, &selected).unwrap();
+        assert_eq!(graph.dependencies(0), &[1]);
+        assert_eq!(graph.order(), vec![1, 0]);
+    }
+
+    #[test]
+    fn test_cycles_and_unknown_dependencies_fail() {
+        let all = vec![
+            repo("a", &["b"]),
+            repo("b", &["c"]),
+            repo("c", &["a"]),
+            repo("d", &[]),
+        ];
+        let error = DependencyGraph::new(&all, &all).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Dependency cycle between repositories: a -> b -> c -> a"
+        );
+
+        let all = vec![repo("a", &["missing"])];
+        let error = DependencyGraph::new(&all, &all).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Repository 'a' depends on unknown repository 'missing'"
+        );
+    }
+}
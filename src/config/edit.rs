@@ -0,0 +1,1077 @@
+//! In-place edits of configuration files
+//!
+//! Saving a config through serde drops its comments, blank lines and key order.
+//! The edits here patch the source text instead, so only the lines that change
+//! are touched.
+
+use anyhow::{Context, Result};
+use serde_yaml::Value;
+use std::collections::HashMap;
+use yaml_rust2::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust2::scanner::{Marker, TScalarStyle};
+
+/// YAML node with the byte range it spans in the source
+#[derive(Debug)]
+struct Node {
+    start: usize,
+    end: usize,
+    kind: NodeKind,
+    /// What the node resolves to, with aliases expanded
+    value: Value,
+}
+
+#[derive(Debug)]
+enum NodeKind {
+    Scalar(String),
+    Sequence {
+        flow: bool,
+        items: Vec<Node>,
+    },
+    Mapping {
+        flow: bool,
+        entries: Vec<(Node, Node)>,
+    },
+}
+
+impl Node {
+    fn as_str(&self) -> Option<&str> {
+        match &self.kind {
+            NodeKind::Scalar(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<&Node> {
+        self.entry(key).map(|(_, value)| value)
+    }
+
+    fn entry(&self, key: &str) -> Option<&(Node, Node)> {
+        match &self.kind {
+            NodeKind::Mapping { entries, .. } => {
+                entries.iter().find(|(k, _)| k.as_str() == Some(key))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A collection being built, with the key waiting for its value
+struct Open {
+    node: Node,
+    pending_key: Option<Node>,
+    anchor: usize,
+}
+
+/// Builds a [`Node`] tree with byte ranges from parser events
+struct SpanBuilder<'a> {
+    source: &'a str,
+    /// Byte offset of every character, as markers count characters
+    offsets: Vec<usize>,
+    stack: Vec<Open>,
+    root: Option<Node>,
+    anchors: HashMap<usize, Value>,
+}
+
+impl<'a> SpanBuilder<'a> {
+    fn new(source: &'a str) -> Self {
+        let mut offsets: Vec<usize> = source.char_indices().map(|(i, _)| i).collect();
+        offsets.push(source.len());
+        Self {
+            source,
+            offsets,
+            stack: Vec::new(),
+            root: None,
+            anchors: HashMap::new(),
+        }
+    }
+
+    fn byte(&self, marker: &Marker) -> usize {
+        self.offsets
+            .get(marker.index())
+            .copied()
+            .unwrap_or(self.source.len())
+    }
+
+    fn in_flow(&self) -> bool {
+        self.stack.iter().any(|open| match &open.node.kind {
+            NodeKind::Sequence { flow, .. } | NodeKind::Mapping { flow, .. } => *flow,
+            NodeKind::Scalar(_) => false,
+        })
+    }
+
+    fn push(&mut self, node: Node, anchor: usize) {
+        if anchor > 0 {
+            self.anchors.insert(anchor, node.value.clone());
+        }
+        let Some(open) = self.stack.last_mut() else {
+            self.root = Some(node);
+            return;
+        };
+        match &mut open.node.kind {
+            NodeKind::Sequence { items, .. } => items.push(node),
+            NodeKind::Mapping { entries, .. } => match open.pending_key.take() {
+                Some(key) => entries.push((key, node)),
+                None => open.pending_key = Some(node),
+            },
+            NodeKind::Scalar(_) => {}
+        }
+    }
+
+    /// Where the value of a mapping entry starts: just after the key's colon
+    fn after_pending_colon(&self) -> Option<usize> {
+        let key = self.stack.last()?.pending_key.as_ref()?;
+        colon_after(self.source, key.end).map(|colon| colon + 1)
+    }
+
+    fn scalar(&mut self, value: String, style: TScalarStyle, anchor: usize, marker: &Marker) {
+        let start = self.byte(marker);
+        let resolved = match style {
+            TScalarStyle::Plain => resolve_plain(&value),
+            _ => Value::String(value.clone()),
+        };
+        let (start, end) = match style {
+            // Empty values are reported at the next token; anchor them to the colon
+            TScalarStyle::Plain if value.is_empty() => {
+                let position = self.after_pending_colon().unwrap_or(start);
+                (position, position)
+            }
+            TScalarStyle::Plain => (start, plain_end(self.source, start, self.in_flow())),
+            TScalarStyle::SingleQuoted => (start, quoted_end(self.source, start, '\'')),
+            TScalarStyle::DoubleQuoted => (start, quoted_end(self.source, start, '"')),
+            _ => (start, block_scalar_end(self.source, start)),
+        };
+        self.push(
+            Node {
+                start,
+                end,
+                kind: NodeKind::Scalar(value),
+                value: resolved,
+            },
+            anchor,
+        );
+    }
+
+    fn open(&mut self, marker: &Marker, sequence: bool, anchor: usize) {
+        let start = self.byte(marker);
+        let flow = self.source[start..].starts_with(if sequence { '[' } else { '{' });
+        let kind = if sequence {
+            NodeKind::Sequence {
+                flow,
+                items: Vec::new(),
+            }
+        } else {
+            NodeKind::Mapping {
+                flow,
+                entries: Vec::new(),
+            }
+        };
+        self.stack.push(Open {
+            node: Node {
+                start,
+                end: start,
+                kind,
+                value: Value::Null,
+            },
+            pending_key: None,
+            anchor,
+        });
+    }
+
+    fn close(&mut self, marker: &Marker) {
+        let end_marker = self.byte(marker);
+        let Some(open) = self.stack.pop() else {
+            return;
+        };
+        let mut node = open.node;
+        node.value = match &node.kind {
+            NodeKind::Sequence { items, .. } => {
+                Value::Sequence(items.iter().map(|item| item.value.clone()).collect())
+            }
+            NodeKind::Mapping { entries, .. } => Value::Mapping(
+                entries
+                    .iter()
+                    .map(|(key, value)| (key.value.clone(), value.value.clone()))
+                    .collect(),
+            ),
+            NodeKind::Scalar(_) => Value::Null,
+        };
+        match &node.kind {
+            NodeKind::Sequence { flow: true, .. } | NodeKind::Mapping { flow: true, .. } => {
+                node.end = end_marker + 1;
+            }
+            NodeKind::Sequence { items, .. } => {
+                node.end = items.last().map_or(node.start, |item| item.end);
+            }
+            NodeKind::Mapping { entries, .. } => {
+                // Block mappings are reported at the colon of their first key
+                if let Some((key, value)) = entries.first() {
+                    node.start = key.start;
+                    node.end = entries.last().map_or(value.end, |(_, value)| value.end);
+                }
+            }
+            NodeKind::Scalar(_) => {}
+        }
+        self.push(node, open.anchor);
+    }
+}
+
+impl MarkedEventReceiver for SpanBuilder<'_> {
+    fn on_event(&mut self, event: Event, marker: Marker) {
+        match event {
+            Event::Scalar(value, style, anchor, _) => self.scalar(value, style, anchor, &marker),
+            Event::Alias(anchor) => {
+                let start = self.byte(&marker);
+                let end = plain_end(self.source, start, true);
+                let value = self.anchors.get(&anchor).cloned().unwrap_or(Value::Null);
+                self.push(
+                    Node {
+                        start,
+                        end,
+                        kind: NodeKind::Scalar(String::new()),
+                        value,
+                    },
+                    0,
+                );
+            }
+            Event::SequenceStart(anchor, _) => self.open(&marker, true, anchor),
+            Event::MappingStart(anchor, _) => self.open(&marker, false, anchor),
+            Event::SequenceEnd | Event::MappingEnd => self.close(&marker),
+            _ => {}
+        }
+    }
+}
+
+fn parse(source: &str) -> Result<Node> {
+    let mut builder = SpanBuilder::new(source);
+    Parser::new_from_str(source)
+        .load(&mut builder, false)
+        .map_err(|e| {
+            anyhow::anyhow!(
+                "{}:{}: {}",
+                e.marker().line(),
+                e.marker().col() + 1,
+                e.info()
+            )
+        })
+        .context("Failed to parse config file")?;
+    builder.root.context("Config file is empty")
+}
+
+/// Resolve a plain scalar the way YAML does, e.g. `42` to a number and `~` to null
+fn resolve_plain(value: &str) -> Value {
+    serde_yaml::from_str(value).unwrap_or_else(|_| Value::String(value.to_string()))
+}
+
+/// Position of the `:` following a mapping key that ends at `key_end`
+fn colon_after(source: &str, key_end: usize) -> Option<usize> {
+    source[key_end..]
+        .find(|c: char| !c.is_whitespace())
+        .map(|offset| key_end + offset)
+        .filter(|&position| source[position..].starts_with(':'))
+}
+
+/// End of a plain scalar: the line end, a comment, or in flow context a delimiter
+fn plain_end(source: &str, start: usize, flow: bool) -> usize {
+    let bytes = source.as_bytes();
+    let mut end = start;
+    let mut position = start;
+    while position < bytes.len() {
+        let c = bytes[position];
+        let next = bytes.get(position + 1).copied();
+        if c == b'\n' || c == b'\r' {
+            break;
+        }
+        if c == b'#' && position > start && bytes[position - 1].is_ascii_whitespace() {
+            break;
+        }
+        if c == b':'
+            && next.is_none_or(|n| n.is_ascii_whitespace() || (flow && b",]}".contains(&n)))
+        {
+            break;
+        }
+        if flow && b",]}".contains(&c) {
+            break;
+        }
+        if !c.is_ascii_whitespace() {
+            end = position + 1;
+        }
+        position += 1;
+    }
+    // Keep multi-byte characters whole
+    while !source.is_char_boundary(end) {
+        end += 1;
+    }
+    end
+}
+
+/// End of a quoted scalar, after its closing quote
+fn quoted_end(source: &str, start: usize, quote: char) -> usize {
+    let mut chars = source[start..].char_indices().skip(1).peekable();
+    while let Some((offset, c)) = chars.next() {
+        if quote == '"' && c == '\\' {
+            chars.next();
+        } else if c == quote {
+            // A doubled single quote is an escaped quote
+            if quote == '\'' && chars.peek().is_some_and(|(_, next)| *next == '\'') {
+                chars.next();
+                continue;
+            }
+            return start + offset + 1;
+        }
+    }
+    source.len()
+}
+
+/// End of a literal or folded block scalar whose content starts at `start`
+fn block_scalar_end(source: &str, start: usize) -> usize {
+    let indent = start - line_start(source, start);
+    let mut end = line_end(source, start);
+    let mut position = end;
+    while position < source.len() {
+        let next_start = position + 1;
+        let next_end = line_end(source, next_start);
+        let line = &source[next_start..next_end];
+        if !line.trim().is_empty() {
+            if line.len() - line.trim_start().len() < indent {
+                break;
+            }
+            end = next_end;
+        }
+        position = next_end;
+    }
+    end
+}
+
+fn line_start(source: &str, position: usize) -> usize {
+    source[..position].rfind('\n').map_or(0, |i| i + 1)
+}
+
+fn line_end(source: &str, position: usize) -> usize {
+    source[position..]
+        .find('\n')
+        .map_or(source.len(), |i| position + i)
+}
+
+/// A scalar as written in YAML, quoted when it would not read back as a plain string
+fn render_scalar(value: &str) -> String {
+    let yaml = serde_yaml::to_string(value).unwrap_or_default();
+    let rendered = yaml.trim_end();
+    if value.contains(['\n', ',', '[', ']', '{', '}']) && !rendered.starts_with(['"', '\'']) {
+        return serde_json::to_string(value).unwrap_or_default();
+    }
+    rendered.to_string()
+}
+
+fn render_flow_sequence(values: &[String]) -> String {
+    let items: Vec<String> = values.iter().map(|v| render_scalar(v)).collect();
+    format!("[{}]", items.join(", "))
+}
+
+/// A replacement of `source[start..end]`
+struct Edit {
+    start: usize,
+    end: usize,
+    text: String,
+}
+
+fn apply_edits(source: &str, mut edits: Vec<Edit>) -> String {
+    // Apply from the end so earlier positions stay valid
+    edits.sort_by_key(|edit| (edit.start, edit.end));
+    let mut result = source.to_string();
+    for edit in edits.into_iter().rev() {
+        result.replace_range(edit.start..edit.end, &edit.text);
+    }
+    result
+}
+
+/// Entries of every `repositories` list in the document named `name`
+///
+/// Besides the top-level list, this finds repositories declared in profiles.
+fn find_repositories<'n>(node: &'n Node, name: &str, found: &mut Vec<&'n Node>) {
+    match &node.kind {
+        NodeKind::Mapping { entries, .. } => {
+            for (key, value) in entries {
+                if key.as_str() == Some("repositories")
+                    && let NodeKind::Sequence { items, .. } = &value.kind
+                {
+                    found.extend(
+                        items
+                            .iter()
+                            .filter(|item| item.get("name").and_then(Node::as_str) == Some(name)),
+                    );
+                }
+                find_repositories(value, name, found);
+            }
+        }
+        NodeKind::Sequence { items, .. } => {
+            for item in items {
+                find_repositories(item, name, found);
+            }
+        }
+        NodeKind::Scalar(_) => {}
+    }
+}
+
+/// Set the tags of a repository in config file contents
+///
+/// Tags that are kept stay where they are, removed tags lose their line or list
+/// entry, and new tags are appended in the style the list already uses. The rest
+/// of the file, including comments, is left as it was.
+pub fn set_repository_tags(source: &str, repository: &str, tags: &[String]) -> Result<String> {
+    let root = parse(source)?;
+    let mut found = Vec::new();
+    find_repositories(&root, repository, &mut found);
+    if found.is_empty() {
+        anyhow::bail!("Repository '{}' is not defined in this file", repository);
+    }
+
+    let mut edits = Vec::new();
+    for entry in found {
+        edits.extend(tag_edits(source, entry, tags).with_context(|| {
+            format!("Failed to update the tags of repository '{}'", repository)
+        })?);
+    }
+    Ok(apply_edits(source, edits))
+}
+
+/// Set the `url` of a repository in config file contents
+///
+/// Only the value is replaced; a quoted URL keeps quotes only when it needs them.
+pub fn set_repository_url(source: &str, repository: &str, url: &str) -> Result<String> {
+    let root = parse(source)?;
+    let mut found = Vec::new();
+    find_repositories(&root, repository, &mut found);
+    if found.is_empty() {
+        anyhow::bail!("Repository '{}' is not defined in this file", repository);
+    }
+
+    let mut edits = Vec::new();
+    for entry in found {
+        let Some((_, value)) = entry.entry("url") else {
+            anyhow::bail!("Repository '{}' has no url", repository);
+        };
+        if value.as_str().is_none() {
+            anyhow::bail!("The url of repository '{}' is not a string", repository);
+        }
+        edits.push(Edit {
+            start: value.start,
+            end: value.end,
+            text: render_scalar(url),
+        });
+    }
+    Ok(apply_edits(source, edits))
+}
+
+fn tag_edits(source: &str, repository: &Node, tags: &[String]) -> Result<Vec<Edit>> {
+    let NodeKind::Mapping { flow, entries } = &repository.kind else {
+        anyhow::bail!("Repository entry is not a mapping");
+    };
+    if *flow {
+        anyhow::bail!("Repository entries written as {{...}} cannot be edited in place");
+    }
+
+    let Some((key, value)) = repository.entry("tags") else {
+        // Add the key after the last one, at the same indentation
+        let last_end = entries
+            .last()
+            .map_or(repository.end, |(_, value)| value.end);
+        let position = line_end(source, last_end);
+        let indent = " ".repeat(repository.start - line_start(source, repository.start));
+        return Ok(vec![Edit {
+            start: position,
+            end: position,
+            text: format!("\n{}tags: {}", indent, render_flow_sequence(tags)),
+        }]);
+    };
+
+    let value_start = colon_after(source, key.end).map_or(value.start, |colon| colon + 1);
+    let replace_value = |text: String| Edit {
+        start: value_start,
+        end: value.end,
+        text: format!(" {}", text),
+    };
+
+    match &value.kind {
+        NodeKind::Sequence { flow: false, items } if !tags.is_empty() => {
+            Ok(block_sequence_edits(source, items, tags))
+        }
+        NodeKind::Sequence { flow: true, .. } => Ok(vec![Edit {
+            start: value.start,
+            end: value.end,
+            text: render_flow_sequence(tags),
+        }]),
+        _ => Ok(vec![replace_value(render_flow_sequence(tags))]),
+    }
+}
+
+/// Remove the lines of dropped items and append new items to a block sequence
+fn block_sequence_edits(source: &str, items: &[Node], values: &[String]) -> Vec<Edit> {
+    let mut edits: Vec<Edit> = items
+        .iter()
+        .filter(|item| {
+            item.as_str()
+                .is_some_and(|v| !values.iter().any(|x| x == v))
+        })
+        .map(|item| remove_lines(source, item.start, item.end))
+        .collect();
+
+    let existing: Vec<Option<&str>> = items.iter().map(Node::as_str).collect();
+    let added: Vec<Value> = values
+        .iter()
+        .filter(|v| !existing.contains(&Some(v.as_str())))
+        .map(|v| Value::String(v.clone()))
+        .collect();
+    if let Some(last) = items.last()
+        && !added.is_empty()
+    {
+        // The dash of the last item sets the indentation of new ones
+        let block = render_block(&Value::Sequence(added), dash_column(source, last));
+        edits.push(insert_after_line(source, last.end, block));
+    }
+    edits
+}
+
+fn column(source: &str, position: usize) -> usize {
+    position - line_start(source, position)
+}
+
+/// Column of the `-` that starts a block sequence item
+fn dash_column(source: &str, item: &Node) -> usize {
+    let line = line_start(source, item.start);
+    source[line..item.start].rfind('-').unwrap_or(0)
+}
+
+/// Remove the whole lines from `start` to `end`
+fn remove_lines(source: &str, start: usize, end: usize) -> Edit {
+    Edit {
+        start: line_start(source, start),
+        end: (line_end(source, end) + 1).min(source.len()),
+        text: String::new(),
+    }
+}
+
+/// Insert block lines after the line holding `position`
+fn insert_after_line(source: &str, position: usize, block: String) -> Edit {
+    let end = line_end(source, position);
+    if end < source.len() {
+        Edit {
+            start: end + 1,
+            end: end + 1,
+            text: block,
+        }
+    } else {
+        Edit {
+            start: end,
+            end,
+            text: format!("\n{}", block.trim_end_matches('\n')),
+        }
+    }
+}
+
+/// A value written on one line, as a scalar or a flow collection
+fn render_inline(value: &Value) -> String {
+    match value {
+        Value::String(s) => render_scalar(s),
+        Value::Sequence(items) => format!(
+            "[{}]",
+            items
+                .iter()
+                .map(render_inline)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Value::Mapping(map) => format!(
+            "{{{}}}",
+            map.iter()
+                .map(|(key, value)| format!("{}: {}", render_inline(key), render_inline(value)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Value::Tagged(tagged) => render_inline(&tagged.value),
+        other => serde_yaml::to_string(other)
+            .map(|yaml| yaml.trim_end().to_string())
+            .unwrap_or_default(),
+    }
+}
+
+/// Whether a value is written on the line of its key or dash
+fn is_inline(value: &Value) -> bool {
+    match value {
+        Value::Sequence(items) => items.is_empty(),
+        Value::Mapping(map) => map.is_empty(),
+        Value::Tagged(tagged) => is_inline(&tagged.value),
+        _ => true,
+    }
+}
+
+fn is_empty(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::Sequence(items) => items.is_empty(),
+        Value::Mapping(map) => map.is_empty(),
+        _ => false,
+    }
+}
+
+/// Block lines of a value at `indent`, each ending in a newline
+///
+/// Sequences are indented below their key, as in the files `repos init` writes,
+/// and keys with empty values are left out.
+fn render_block(value: &Value, indent: usize) -> String {
+    let pad = " ".repeat(indent);
+    let mut out = String::new();
+    match value {
+        Value::Mapping(map) => {
+            // Empty values are left out, as saving does for most fields
+            let all_empty = map.values().all(is_empty);
+            for (key, value) in map {
+                if is_empty(value) && !all_empty {
+                    continue;
+                }
+                out.push_str(&format!("{}{}:", pad, render_inline(key)));
+                if is_inline(value) {
+                    out.push_str(&format!(" {}\n", render_inline(value)));
+                } else {
+                    out.push('\n');
+                    out.push_str(&render_block(value, indent + 2));
+                }
+            }
+        }
+        Value::Sequence(items) => {
+            for item in items {
+                if is_inline(item) {
+                    out.push_str(&format!("{}- {}\n", pad, render_inline(item)));
+                } else {
+                    // The first line of the item follows the dash
+                    let block = render_block(item, indent + 2);
+                    out.push_str(&format!("{}- {}", pad, &block[indent + 2..]));
+                }
+            }
+        }
+        Value::Tagged(tagged) => return render_block(&tagged.value, indent),
+        other => out.push_str(&format!("{}{}\n", pad, render_inline(other))),
+    }
+    out
+}
+
+/// Where a node sits in its parent, which decides how it is rewritten
+#[derive(Debug, Clone, Copy)]
+enum Slot {
+    Root,
+    /// Value of a mapping entry whose key starts at column `indent`
+    Value {
+        indent: usize,
+        colon: usize,
+    },
+    /// Item of a block sequence whose dash is at column `indent`
+    Item {
+        indent: usize,
+    },
+}
+
+/// Rewrite config file contents to hold `updated`, changing as little as possible
+///
+/// Entries whose values did not change are left byte for byte, so their
+/// comments, anchors, quoting and key order survive. Changed scalars are
+/// replaced where they stand, removed keys and list items lose their lines, and
+/// new keys and items are added after their neighbours. Sequence items that are
+/// mappings are matched by their `name`, so a repository keeps its place and
+/// comments when other repositories are added or removed. Keys with empty values
+/// that the file does not have are not added.
+pub fn patch_document(source: &str, updated: &Value) -> Result<String> {
+    let root = parse(source)?;
+    let mut edits = Vec::new();
+    patch_node(source, &root, updated, Slot::Root, &mut edits)?;
+    let patched = apply_edits(source, edits);
+
+    // Never write a file that reads back differently from what was saved
+    let reparsed: Value =
+        serde_yaml::from_str(&patched).context("Edited config file does not parse")?;
+    if normalized(&reparsed) != normalized(updated) {
+        anyhow::bail!("Edited config file does not match the saved config");
+    }
+    Ok(patched)
+}
+
+/// A value with merge keys applied, as it reads after loading
+fn merged(value: &Value) -> Value {
+    let mut value = value.clone();
+    let _ = value.apply_merge();
+    value
+}
+
+/// A value without the empty entries saving leaves out, for comparisons
+fn normalized(value: &Value) -> Value {
+    match merged(value) {
+        Value::Mapping(map) => Value::Mapping(
+            map.into_iter()
+                .filter(|(_, value)| !is_empty(value))
+                .map(|(key, value)| (key, normalized(&value)))
+                .collect(),
+        ),
+        Value::Sequence(items) => Value::Sequence(items.iter().map(normalized).collect()),
+        other => other,
+    }
+}
+
+fn patch_node(
+    source: &str,
+    node: &Node,
+    updated: &Value,
+    slot: Slot,
+    edits: &mut Vec<Edit>,
+) -> Result<()> {
+    let current = merged(&node.value);
+    if current == *updated || (is_empty(&current) && is_empty(updated)) {
+        return Ok(());
+    }
+
+    match (&node.kind, updated) {
+        (
+            NodeKind::Mapping {
+                flow: false,
+                entries,
+            },
+            Value::Mapping(map),
+        ) if entries.iter().any(|(key, _)| map.contains_key(&key.value)) => {
+            patch_mapping(source, node, entries, map, edits)
+        }
+        (NodeKind::Sequence { flow: false, items }, Value::Sequence(values))
+            if !values.is_empty() =>
+        {
+            patch_sequence(source, items, values, edits)
+        }
+        _ => replace_node(node, updated, slot, edits),
+    }
+}
+
+fn replace_node(node: &Node, updated: &Value, slot: Slot, edits: &mut Vec<Edit>) -> Result<()> {
+    let flow = matches!(
+        node.kind,
+        NodeKind::Sequence { flow: true, .. } | NodeKind::Mapping { flow: true, .. }
+    );
+    // Flow collections stay on one line
+    let inline = flow || is_inline(updated);
+    let (start, text) = match slot {
+        Slot::Root => anyhow::bail!("The whole document would change"),
+        Slot::Value { indent, colon } => {
+            let text = if inline {
+                format!(" {}", render_inline(updated))
+            } else {
+                let block = render_block(updated, indent + 2);
+                format!("\n{}", block.trim_end_matches('\n'))
+            };
+            (colon + 1, text)
+        }
+        Slot::Item { indent } => {
+            let text = if inline {
+                render_inline(updated)
+            } else {
+                let block = render_block(updated, indent + 2);
+                block[indent + 2..].trim_end_matches('\n').to_string()
+            };
+            (node.start, text)
+        }
+    };
+    edits.push(Edit {
+        start,
+        end: node.end,
+        text,
+    });
+    Ok(())
+}
+
+fn patch_mapping(
+    source: &str,
+    node: &Node,
+    entries: &[(Node, Node)],
+    updated: &serde_yaml::Mapping,
+    edits: &mut Vec<Edit>,
+) -> Result<()> {
+    let indent = column(source, node.start);
+    let mut last_kept = None;
+    for (index, (key, value)) in entries.iter().enumerate() {
+        if key.as_str() == Some("<<") {
+            last_kept = Some(value.end);
+            continue;
+        }
+        match updated.get(&key.value) {
+            Some(new_value) => {
+                let colon =
+                    colon_after(source, key.end).context("Mapping key is not followed by ':'")?;
+                patch_node(
+                    source,
+                    value,
+                    new_value,
+                    Slot::Value { indent, colon },
+                    edits,
+                )?;
+                last_kept = Some(value.end);
+            }
+            None => edits.push(remove_entry(source, entries, index)),
+        }
+    }
+
+    // Keys the file does not have yet go after the last remaining entry
+    let current = merged(&node.value);
+    let added: serde_yaml::Mapping = updated
+        .iter()
+        .filter(|(key, value)| {
+            !is_empty(value)
+                && !entries.iter().any(|(k, _)| k.value == **key)
+                && current.get(*key) != Some(*value)
+        })
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    if !added.is_empty() {
+        let anchor = last_kept.context("Mapping has no entry left to add keys after")?;
+        let position = line_end(source, anchor);
+        let block = render_block(&Value::Mapping(added), indent);
+        edits.push(Edit {
+            start: position,
+            end: position,
+            text: format!("\n{}", block.trim_end_matches('\n')),
+        });
+    }
+    Ok(())
+}
+
+/// Remove one entry of a block mapping
+fn remove_entry(source: &str, entries: &[(Node, Node)], index: usize) -> Edit {
+    let (key, value) = &entries[index];
+    let line = line_start(source, key.start);
+    if source[line..key.start].trim().is_empty() {
+        return remove_lines(source, key.start, value.end);
+    }
+    // The first key of a sequence item shares the dash's line; the next key moves up
+    let end = entries
+        .get(index + 1)
+        .map_or(value.end, |(next, _)| next.start);
+    Edit {
+        start: key.start,
+        end,
+        text: String::new(),
+    }
+}
+
+/// What identifies a sequence item across saves: the `name` of a mapping, or the value
+fn identity(value: &Value) -> (bool, Value) {
+    match value.as_mapping().and_then(|map| map.get("name")) {
+        Some(name) => (true, name.clone()),
+        None => (false, value.clone()),
+    }
+}
+
+/// Pairs of indices of a longest common subsequence
+fn common_subsequence<T: PartialEq>(old: &[T], new: &[T]) -> Vec<(usize, usize)> {
+    let mut lengths = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i][j] = if old[i] == new[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut pairs = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            pairs.push((i, j));
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    pairs
+}
+
+fn patch_sequence(
+    source: &str,
+    items: &[Node],
+    values: &[Value],
+    edits: &mut Vec<Edit>,
+) -> Result<()> {
+    let old: Vec<_> = items
+        .iter()
+        .map(|item| identity(&merged(&item.value)))
+        .collect();
+    let new: Vec<_> = values.iter().map(identity).collect();
+    let pairs = common_subsequence(&old, &new);
+    let indent = items.first().map_or(0, |item| dash_column(source, item));
+
+    for (index, item) in items.iter().enumerate() {
+        if !pairs.iter().any(|(i, _)| *i == index) {
+            edits.push(remove_lines(source, item.start, item.end));
+        }
+    }
+    for (i, j) in &pairs {
+        let slot = Slot::Item {
+            indent: dash_column(source, &items[*i]),
+        };
+        patch_node(source, &items[*i], &values[*j], slot, edits)?;
+    }
+
+    // New items go after the item they follow in the saved list
+    let mut index = 0;
+    while index < values.len() {
+        if pairs.iter().any(|(_, j)| *j == index) {
+            index += 1;
+            continue;
+        }
+        let after = pairs
+            .iter()
+            .rev()
+            .find(|(_, j)| *j < index)
+            .map(|(i, _)| &items[*i]);
+        let mut group = Vec::new();
+        while index < values.len() && !pairs.iter().any(|(_, j)| *j == index) {
+            group.push(values[index].clone());
+            index += 1;
+        }
+        let block = render_block(&Value::Sequence(group), indent);
+        edits.push(match after {
+            Some(item) => insert_after_line(source, item.end, block),
+            None => {
+                let position = line_start(source, items[0].start);
+                Edit {
+                    start: position,
+                    end: position,
+                    text: block,
+                }
+            }
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_set_flow_tags_keeps_comments() {
+        let source = "# Fleet\nrepositories:\n  # Services\n  - name: api # main API\n    url: git@github.com:org/api.git\n    tags: [backend, gh:old]\n  - name: web\n    url: git@github.com:org/web.git\n";
+        let updated = set_repository_tags(source, "api", &tags(&["backend", "gh:rust"])).unwrap();
+        assert_eq!(
+            updated,
+            source.replace("[backend, gh:old]", "[backend, gh:rust]")
+        );
+    }
+
+    #[test]
+    fn test_set_block_tags() {
+        let source = "repositories:\n  - name: api\n    tags:\n      - backend # team\n      - gh:old\n    url: git@github.com:org/api.git\n";
+        let updated =
+            set_repository_tags(source, "api", &tags(&["backend", "gh:rust", "gh:cli"])).unwrap();
+        assert_eq!(
+            updated,
+            "repositories:\n  - name: api\n    tags:\n      - backend # team\n      - gh:rust\n      - gh:cli\n    url: git@github.com:org/api.git\n"
+        );
+
+        let cleared = set_repository_tags(source, "api", &[]).unwrap();
+        assert_eq!(
+            cleared,
+            "repositories:\n  - name: api\n    tags: []\n    url: git@github.com:org/api.git\n"
+        );
+    }
+
+    #[test]
+    fn test_add_missing_tags_key() {
+        let source = "repositories:\n  - name: api\n    url: git@github.com:org/api.git # ssh\n\nrecipes: []";
+        let updated = set_repository_tags(source, "api", &tags(&["gh:rust"])).unwrap();
+        assert_eq!(
+            updated,
+            "repositories:\n  - name: api\n    url: git@github.com:org/api.git # ssh\n    tags: [gh:rust]\n\nrecipes: []"
+        );
+    }
+
+    #[test]
+    fn test_set_tags_in_profiles_and_quoted_values() {
+        let source = "profiles:\n  oss:\n    repositories:\n      - name: \"dotfiles\"\n        url: 'git@github.com:me/dotfiles.git'\n        tags:\n";
+        let updated = set_repository_tags(source, "dotfiles", &tags(&["a,b"])).unwrap();
+        assert!(updated.ends_with("        tags: [\"a,b\"]\n"));
+
+        assert!(set_repository_tags(source, "missing", &[]).is_err());
+    }
+
+    #[test]
+    fn test_set_repository_url() {
+        let source = "repositories:\n  - name: api # main API\n    url: 'git@github.com:org/api.git' # ssh\n    tags: [backend]\n  - name: web\n    url: git@github.com:org/web.git\n";
+        let updated = set_repository_url(source, "api", "git@github.com:platform/api.git").unwrap();
+        assert_eq!(
+            updated,
+            source.replace(
+                "'git@github.com:org/api.git'",
+                "git@github.com:platform/api.git"
+            )
+        );
+        assert!(set_repository_url(source, "missing", "x").is_err());
+    }
+
+    fn patched(source: &str, updated: &str) -> String {
+        let updated: Value = serde_yaml::from_str(updated).unwrap();
+        patch_document(source, &updated).unwrap()
+    }
+
+    #[test]
+    fn test_patch_keeps_comments_and_order() {
+        let source = "# Fleet\nroot: ~/src # checkouts\nrepositories:\n  # Services\n  - name: api\n    url: git@github.com:org/api.git # ssh\n    tags: [backend]\n\n  # Tools\n  - name: cli\n    url: git@github.com:org/cli.git\n    branch: main\n";
+        let result = patched(
+            source,
+            "repositories:\n- name: api\n  url: git@github.com:org/api.git\n  tags: [backend, rust]\n  branch: develop\n- name: web\n  url: git@github.com:org/web.git\n  tags: [frontend]\nroot: ~/code\nrecipes: []\n",
+        );
+        assert_eq!(
+            result,
+            "# Fleet\nroot: ~/code # checkouts\nrepositories:\n  # Services\n  - name: api\n    url: git@github.com:org/api.git # ssh\n    tags: [backend, rust]\n    branch: develop\n  - name: web\n    url: git@github.com:org/web.git\n    tags:\n      - frontend\n\n  # Tools\n"
+        );
+    }
+
+    #[test]
+    fn test_patch_inserts_items_in_place() {
+        let source = "repositories:\n  - name: b # second\n    url: u2\n";
+        let result = patched(
+            source,
+            "repositories:\n- {name: a, url: u1}\n- {name: b, url: u2}\n- {name: c, url: u3}\n",
+        );
+        assert_eq!(
+            result,
+            "repositories:\n  - name: a\n    url: u1\n  - name: b # second\n    url: u2\n  - name: c\n    url: u3\n"
+        );
+    }
+
+    #[test]
+    fn test_patch_keeps_anchors_and_merges() {
+        let source = "defaults: &defaults\n  provider: gitea\nrepositories:\n  - <<: *defaults\n    name: api\n    url: u1\n";
+        let result = patched(
+            source,
+            "defaults: {provider: gitea}\nrepositories:\n- {provider: gitea, name: api, url: u1, tags: [x]}\n",
+        );
+        assert_eq!(
+            result,
+            "defaults: &defaults\n  provider: gitea\nrepositories:\n  - <<: *defaults\n    name: api\n    url: u1\n    tags:\n      - x\n"
+        );
+    }
+
+    #[test]
+    fn test_patch_removes_keys() {
+        let source = "repositories:\n  - name: api\n    branch: main # pinned\n    url: u1\nrecipes:\n  - name: test\n    steps: [make]\n";
+        let result = patched(
+            source,
+            "repositories:\n- {url: u1, name: api}\nrecipes: []\n",
+        );
+        assert_eq!(
+            result,
+            "repositories:\n  - name: api\n    url: u1\nrecipes: []\n"
+        );
+    }
+}
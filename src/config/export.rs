@@ -0,0 +1,234 @@
+//! Converting repositories into formats other systems read
+//!
+//! `repos config export` is the counterpart of [`super::import`]:
+//!
+//! - A Google `repo` manifest, with one `<remote>` per distinct URL prefix and
+//!   tags as `groups`, which `repos init --import` reads back
+//! - Plain JSON of the repositories as they are configured
+//! - A Markdown table for documentation
+
+use super::Repository;
+use super::layout::parse_repository_url;
+use anyhow::Result;
+use serde::Serialize;
+use std::str::FromStr;
+
+/// Output format of `repos config export`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExportFormat {
+    #[default]
+    RepoManifest,
+    Json,
+    Markdown,
+}
+
+impl FromStr for ExportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "repo-manifest" => Ok(ExportFormat::RepoManifest),
+            "json" => Ok(ExportFormat::Json),
+            "markdown" => Ok(ExportFormat::Markdown),
+            _ => anyhow::bail!(
+                "Unknown export format '{}' (expected repo-manifest, json or markdown)",
+                s
+            ),
+        }
+    }
+}
+
+/// Render `repositories` in `format`
+pub fn export(repositories: &[Repository], format: ExportFormat) -> Result<String> {
+    Ok(match format {
+        ExportFormat::RepoManifest => repo_manifest(repositories),
+        ExportFormat::Json => json(repositories)?,
+        ExportFormat::Markdown => markdown(repositories),
+    })
+}
+
+/// Where a repository is checked out, relative to the workspace root unless absolute
+fn relative_path(repo: &Repository) -> String {
+    repo.path
+        .clone()
+        .unwrap_or_else(|| repo.path_strategy.relative_dir(&repo.name, &repo.url))
+}
+
+/// Split a URL into the prefix a manifest remote fetches from and the project name
+///
+/// `git@github.com:org/api.git` gives `git@github.com:org` and `api.git`.
+fn split_url(url: &str) -> (&str, &str) {
+    let url = url.trim_end_matches('/');
+    match url.rfind('/') {
+        Some(index) if !url[..index].ends_with('/') => (&url[..index], &url[index + 1..]),
+        _ => match url.rfind(':') {
+            Some(index) => (&url[..=index], &url[index + 1..]),
+            None => ("", url),
+        },
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn repo_manifest(repositories: &[Repository]) -> String {
+    // One remote per fetch prefix, named after its owner where possible
+    let mut remotes: Vec<(String, &str)> = Vec::new();
+    for repo in repositories {
+        let (fetch, _) = split_url(&repo.url);
+        if remotes.iter().any(|(_, known)| *known == fetch) {
+            continue;
+        }
+        let base = parse_repository_url(&repo.url)
+            .map(|parts| parts.owner.replace('/', "-"))
+            .unwrap_or_else(|| "origin".to_string());
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while remotes.iter().any(|(known, _)| *known == name) {
+            name = format!("{}-{}", base, suffix);
+            suffix += 1;
+        }
+        remotes.push((name, fetch));
+    }
+
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<manifest>\n");
+    for (name, fetch) in &remotes {
+        out.push_str(&format!(
+            "  <remote name=\"{}\" fetch=\"{}\" />\n",
+            escape_xml(name),
+            escape_xml(fetch)
+        ));
+    }
+    if let Some((name, _)) = remotes.first() {
+        out.push_str(&format!("  <default remote=\"{}\" />\n", escape_xml(name)));
+    }
+    for repo in repositories {
+        let (fetch, project) = split_url(&repo.url);
+        let remote = remotes
+            .iter()
+            .find(|(_, known)| *known == fetch)
+            .map(|(name, _)| name.as_str())
+            .unwrap_or_default();
+        out.push_str(&format!(
+            "  <project name=\"{}\" path=\"{}\"",
+            escape_xml(project),
+            escape_xml(&relative_path(repo))
+        ));
+        if Some(remote) != remotes.first().map(|(name, _)| name.as_str()) {
+            out.push_str(&format!(" remote=\"{}\"", escape_xml(remote)));
+        }
+        if let Some(branch) = &repo.branch {
+            out.push_str(&format!(" revision=\"{}\"", escape_xml(branch)));
+        }
+        if !repo.tags.is_empty() {
+            out.push_str(&format!(" groups=\"{}\"", escape_xml(&repo.tags.join(","))));
+        }
+        out.push_str(" />\n");
+    }
+    out.push_str("</manifest>\n");
+    out
+}
+
+#[derive(Serialize)]
+struct JsonExport<'a> {
+    repositories: &'a [Repository],
+}
+
+fn json(repositories: &[Repository]) -> Result<String> {
+    let mut out = serde_json::to_string_pretty(&JsonExport { repositories })?;
+    out.push('\n');
+    Ok(out)
+}
+
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+fn markdown(repositories: &[Repository]) -> String {
+    let mut out = String::from("| Name | URL | Branch | Tags | Path |\n");
+    out.push_str("|------|-----|--------|------|------|\n");
+    for repo in repositories {
+        let tags: Vec<String> = repo
+            .tags
+            .iter()
+            .map(|tag| format!("`{}`", escape_cell(tag)))
+            .collect();
+        out.push_str(&format!(
+            "| {} | {} | {} | {} | `{}` |\n",
+            escape_cell(&repo.name),
+            escape_cell(&repo.url),
+            repo.branch.as_deref().map(escape_cell).unwrap_or_default(),
+            tags.join(", "),
+            escape_cell(&relative_path(repo))
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RepositoryBuilder;
+
+    fn fleet() -> Vec<Repository> {
+        vec![
+            RepositoryBuilder::new("api".to_string(), "git@github.com:org/api.git".to_string())
+                .with_tags(vec!["backend".to_string(), "core".to_string()])
+                .with_branch("main".to_string())
+                .build(),
+            RepositoryBuilder::new("web".to_string(), "https://gitlab.com/team/web".to_string())
+                .with_path("frontend/web".to_string())
+                .build(),
+        ]
+    }
+
+    #[test]
+    fn test_split_url() {
+        assert_eq!(
+            split_url("git@github.com:org/api.git"),
+            ("git@github.com:org", "api.git")
+        );
+        assert_eq!(
+            split_url("https://github.com/org/api/"),
+            ("https://github.com/org", "api")
+        );
+        assert_eq!(split_url("git@host:api.git"), ("git@host:", "api.git"));
+    }
+
+    #[test]
+    fn test_repo_manifest() {
+        let manifest = export(&fleet(), ExportFormat::RepoManifest).unwrap();
+        assert_eq!(
+            manifest,
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<manifest>
+  <remote name="org" fetch="git@github.com:org" />
+  <remote name="team" fetch="https://gitlab.com/team" />
+  <default remote="org" />
+  <project name="api.git" path="api" revision="main" groups="backend,core" />
+  <project name="web" path="frontend/web" remote="team" />
+</manifest>
+"#
+        );
+    }
+
+    #[test]
+    fn test_json_and_markdown() {
+        let json: serde_json::Value =
+            serde_json::from_str(&export(&fleet(), ExportFormat::Json).unwrap()).unwrap();
+        assert_eq!(json["repositories"][0]["name"], "api");
+        assert_eq!(json["repositories"][1]["path"], "frontend/web");
+
+        let markdown = export(&fleet(), ExportFormat::Markdown).unwrap();
+        assert!(
+            markdown.contains(
+                "| api | git@github.com:org/api.git | main | `backend`, `core` | `api` |"
+            )
+        );
+        assert!(markdown.contains("| web | https://gitlab.com/team/web |  |  | `frontend/web` |"));
+    }
+}
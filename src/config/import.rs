@@ -0,0 +1,517 @@
+//! Converting the configuration of other multi-repo tools
+//!
+//! `repos init --import <file>` reads one of these formats and maps each entry
+//! onto a [`Repository`]:
+//!
+//! - Google `repo` manifests (`manifest.xml`): `<project>` elements with their
+//!   remote's `fetch` URL, `path`, `revision` as branch and `groups` as tags
+//! - gita's `repos.csv`: paths of local clones, whose primary remote becomes the
+//!   URL, with groups from a `groups.csv` beside it as tags
+//! - meta's `.meta` JSON: the `projects` map of directories to URLs
+//! - myrepos' `.mrconfig`: sections named after directories with a
+//!   `checkout = git clone <url>` command
+//! - A plain list of URLs, one per line, with `#` comments
+//!
+//! A `path` is only recorded when it differs from where the path strategy
+//! would put the repository anyway.
+
+use super::{PathStrategy, Repository, RepositoryBuilder, layout::parse_repository_url};
+use crate::logging;
+use crate::utils::repository_discovery::{get_remotes, primary_remote};
+use anyhow::{Context, Result};
+use colored::*;
+use log::Level;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+/// A configuration format `repos init --import` understands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// Google `repo` manifest XML
+    Manifest,
+    /// gita `repos.csv`
+    Gita,
+    /// meta `.meta` JSON
+    Meta,
+    /// myrepos `.mrconfig`
+    MrConfig,
+    /// One repository URL per line
+    UrlList,
+}
+
+impl ImportFormat {
+    /// Recognize a format by file name, falling back to the content
+    pub fn detect(path: &Path, content: &str) -> Self {
+        let file_name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default();
+        let content = content.trim_start();
+        if file_name.ends_with(".xml") || content.starts_with('<') {
+            ImportFormat::Manifest
+        } else if file_name.ends_with(".csv") {
+            ImportFormat::Gita
+        } else if file_name == ".meta" || content.starts_with('{') {
+            ImportFormat::Meta
+        } else if file_name == ".mrconfig" || content.starts_with('[') {
+            ImportFormat::MrConfig
+        } else {
+            ImportFormat::UrlList
+        }
+    }
+}
+
+/// One repository as described by the imported file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Imported {
+    name: String,
+    url: String,
+    path: Option<String>,
+    branch: Option<String>,
+    tags: Vec<String>,
+}
+
+impl Imported {
+    fn repository(self, strategy: &PathStrategy) -> Repository {
+        let path = self
+            .path
+            .filter(|path| *path != strategy.relative_dir(&self.name, &self.url));
+        let mut builder = RepositoryBuilder::new(self.name, self.url).with_tags(self.tags);
+        if let Some(path) = path {
+            builder = builder.with_path(path);
+        }
+        if let Some(branch) = self.branch {
+            builder = builder.with_branch(branch);
+        }
+        builder.build()
+    }
+}
+
+/// Read `path` and convert its entries into repositories
+///
+/// Entries whose name was already taken by an earlier one are skipped with a
+/// warning, as are gita entries whose directory has no remote.
+pub fn import_repositories(path: &Path, strategy: &PathStrategy) -> Result<Vec<Repository>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read '{}'", path.display()))?;
+    let imported = match ImportFormat::detect(path, &content) {
+        ImportFormat::Manifest => parse_manifest(&content)?,
+        ImportFormat::Gita => parse_gita(path, &content)?,
+        ImportFormat::Meta => parse_meta(&content)?,
+        ImportFormat::MrConfig => parse_mrconfig(&content),
+        ImportFormat::UrlList => parse_url_list(&content),
+    };
+
+    let mut repositories: Vec<Repository> = Vec::new();
+    for entry in imported {
+        if repositories.iter().any(|repo| repo.name == entry.name) {
+            logging::status(
+                Level::Warn,
+                &entry.name,
+                format!(
+                    "Skipping {}: a repository with this name was already imported",
+                    entry.url
+                )
+                .yellow(),
+            );
+            continue;
+        }
+        repositories.push(entry.repository(strategy));
+    }
+    Ok(repositories)
+}
+
+/// Last component of a path or URL, without a `.git` suffix
+fn last_segment(text: &str) -> String {
+    let text = text.trim_end_matches('/');
+    let segment = text.rsplit(['/', ':']).next().unwrap_or(text);
+    segment.strip_suffix(".git").unwrap_or(segment).to_string()
+}
+
+fn name_from_url(url: &str) -> String {
+    parse_repository_url(url)
+        .map(|parts| parts.repo)
+        .unwrap_or_else(|| last_segment(url))
+}
+
+/// Convert a Google `repo` manifest
+fn parse_manifest(content: &str) -> Result<Vec<Imported>> {
+    let document = roxmltree::Document::parse(content).context("Invalid manifest XML")?;
+    let root = document.root_element();
+    if !root.has_tag_name("manifest") {
+        anyhow::bail!(
+            "Expected a <manifest> element, found <{}>",
+            root.tag_name().name()
+        );
+    }
+
+    let elements = |tag: &'static str| {
+        root.children()
+            .filter(move |node| node.is_element() && node.has_tag_name(tag))
+    };
+    let remotes: HashMap<&str, (&str, Option<&str>)> = elements("remote")
+        .filter_map(|remote| {
+            Some((
+                remote.attribute("name")?,
+                (remote.attribute("fetch")?, remote.attribute("revision")),
+            ))
+        })
+        .collect();
+    let default = elements("default").next();
+    let default_remote = default.and_then(|node| node.attribute("remote"));
+    let default_revision = default.and_then(|node| node.attribute("revision"));
+    if elements("include").next().is_some() {
+        logging::message(
+            Level::Warn,
+            "Included manifests are not imported; import each of them separately".yellow(),
+        );
+    }
+
+    let mut imported = Vec::new();
+    for project in elements("project") {
+        let name = project
+            .attribute("name")
+            .context("A <project> has no name")?;
+        let remote_name = project
+            .attribute("remote")
+            .or(default_remote)
+            .with_context(|| format!("Project '{}' has no remote and there is no default", name))?;
+        let Some((fetch, remote_revision)) = remotes.get(remote_name) else {
+            anyhow::bail!("Project '{}' uses unknown remote '{}'", name, remote_name);
+        };
+        if !fetch.contains("://") && !fetch.contains('@') {
+            anyhow::bail!(
+                "Remote '{}' has the relative fetch URL '{}', which depends on where the manifest was cloned from; replace it with the full URL",
+                remote_name,
+                fetch
+            );
+        }
+        let url = if fetch.ends_with(':') {
+            format!("{}{}", fetch, name)
+        } else {
+            format!("{}/{}", fetch.trim_end_matches('/'), name)
+        };
+        let path = project.attribute("path").unwrap_or(name);
+
+        // Pinned commits and tags are not branches to check out
+        let branch = project
+            .attribute("revision")
+            .or(*remote_revision)
+            .or(default_revision)
+            .filter(|revision| {
+                let pinned =
+                    revision.len() == 40 && revision.chars().all(|c| c.is_ascii_hexdigit());
+                !pinned && !revision.starts_with("refs/tags/")
+            })
+            .map(|revision| revision.trim_start_matches("refs/heads/").to_string());
+
+        let tags = project
+            .attribute("groups")
+            .unwrap_or_default()
+            .split([',', ' '])
+            .map(str::trim)
+            .filter(|group| !group.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        imported.push(Imported {
+            name: last_segment(path),
+            url,
+            path: Some(path.to_string()),
+            branch,
+            tags,
+        });
+    }
+    Ok(imported)
+}
+
+/// Convert gita's `repos.csv` (`path,name,...` per line)
+///
+/// gita only knows local directories, so URLs come from their remotes.
+fn parse_gita(path: &Path, content: &str) -> Result<Vec<Imported>> {
+    let groups_file = path.with_file_name("groups.csv");
+    let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    if let Ok(groups_content) = std::fs::read_to_string(&groups_file) {
+        // `group:repo1 repo2[:path]`
+        for line in groups_content.lines() {
+            let mut fields = line.split(':');
+            let (Some(group), Some(members)) = (fields.next(), fields.next()) else {
+                continue;
+            };
+            for member in members.split_whitespace() {
+                groups
+                    .entry(member.to_string())
+                    .or_default()
+                    .push(group.trim().to_string());
+            }
+        }
+    }
+
+    let mut imported = Vec::new();
+    for line in content.lines().filter(|line| !line.trim().is_empty()) {
+        let mut fields = line.split(',');
+        let dir = fields.next().unwrap_or_default().trim();
+        let name = fields
+            .next()
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(str::to_string)
+            .unwrap_or_else(|| last_segment(dir));
+
+        let remotes = get_remotes(Path::new(dir)).unwrap_or_default();
+        let Some((_, url)) = primary_remote(&remotes, None) else {
+            logging::status(
+                Level::Warn,
+                &name,
+                format!("Skipping {}: no git remote found", dir).yellow(),
+            );
+            continue;
+        };
+        imported.push(Imported {
+            tags: groups.get(&name).cloned().unwrap_or_default(),
+            url: url.clone(),
+            path: Some(dir.to_string()),
+            name,
+            branch: None,
+        });
+    }
+    Ok(imported)
+}
+
+#[derive(Deserialize)]
+struct MetaFile {
+    #[serde(default)]
+    projects: BTreeMap<String, String>,
+}
+
+/// Convert meta's `.meta` file
+fn parse_meta(content: &str) -> Result<Vec<Imported>> {
+    let meta: MetaFile = serde_json::from_str(content).context("Invalid .meta JSON")?;
+    Ok(meta
+        .projects
+        .into_iter()
+        .map(|(dir, url)| Imported {
+            name: last_segment(&dir),
+            url,
+            path: Some(dir),
+            ..Default::default()
+        })
+        .collect())
+}
+
+/// Split a shell command line into words, honoring single and double quotes
+pub(crate) fn shell_words(line: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote = None;
+    for c in line.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => word.push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    words
+}
+
+/// `git clone` options whose value is a separate argument
+const CLONE_OPTIONS_WITH_VALUE: &[&str] = &[
+    "-b",
+    "--branch",
+    "-o",
+    "--origin",
+    "-c",
+    "--config",
+    "-u",
+    "--upload-pack",
+    "-j",
+    "--jobs",
+    "--depth",
+    "--reference",
+    "--separate-git-dir",
+];
+
+/// Convert a myrepos `.mrconfig`
+///
+/// Sections without a `git clone` checkout command are skipped.
+fn parse_mrconfig(content: &str) -> Vec<Imported> {
+    let mut sections: Vec<(String, String)> = Vec::new();
+    let mut current: Option<String> = None;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if let Some(section) = trimmed
+            .strip_prefix('[')
+            .and_then(|rest| rest.strip_suffix(']'))
+        {
+            current = (section != "DEFAULT").then(|| section.to_string());
+            continue;
+        }
+        let Some(dir) = &current else {
+            continue;
+        };
+        if let Some((key, value)) = trimmed.split_once('=')
+            && key.trim() == "checkout"
+        {
+            sections.push((dir.clone(), value.trim().to_string()));
+        }
+    }
+
+    sections
+        .into_iter()
+        .filter_map(|(dir, checkout)| {
+            let words = shell_words(&checkout);
+            let clone = words.iter().position(|word| word == "clone")?;
+            // The first argument that is neither an option nor an option's value
+            let mut args = words[clone + 1..].iter();
+            let url = loop {
+                let word = args.next()?;
+                if CLONE_OPTIONS_WITH_VALUE.contains(&word.as_str()) {
+                    args.next();
+                } else if !word.starts_with('-') {
+                    break word.clone();
+                }
+            };
+            Some(Imported {
+                name: last_segment(&dir),
+                url,
+                path: Some(dir),
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+/// Convert a list of URLs, one per line
+fn parse_url_list(content: &str) -> Vec<Imported> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|url| Imported {
+            name: name_from_url(url),
+            url: url.to_string(),
+            ..Default::default()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_detect() {
+        let detect = |name: &str, content: &str| ImportFormat::detect(Path::new(name), content);
+        assert_eq!(detect("default.xml", ""), ImportFormat::Manifest);
+        assert_eq!(
+            detect("manifest", "<?xml version=\"1.0\"?>"),
+            ImportFormat::Manifest
+        );
+        assert_eq!(detect("repos.csv", "/src/api,api"), ImportFormat::Gita);
+        assert_eq!(detect(".meta", ""), ImportFormat::Meta);
+        assert_eq!(detect(".mrconfig", ""), ImportFormat::MrConfig);
+        assert_eq!(detect("mr.conf", "[src/api]"), ImportFormat::MrConfig);
+        assert_eq!(
+            detect("repos.txt", "git@github.com:org/api.git"),
+            ImportFormat::UrlList
+        );
+    }
+
+    #[test]
+    fn test_parse_manifest() {
+        let manifest = r#"<?xml version="1.0" encoding="UTF-8"?>
+<manifest>
+  <remote name="github" fetch="https://github.com/org" />
+  <remote name="internal" fetch="git@git.example.com:" revision="develop" />
+  <default remote="github" revision="refs/heads/main" />
+  <project name="api" groups="backend,core" />
+  <project name="tools/build" path="build/make" remote="internal" />
+  <project name="web" revision="0123456789abcdef0123456789abcdef01234567" />
+</manifest>"#;
+        let imported = parse_manifest(manifest).unwrap();
+        assert_eq!(
+            imported[0],
+            Imported {
+                name: "api".to_string(),
+                url: "https://github.com/org/api".to_string(),
+                path: Some("api".to_string()),
+                branch: Some("main".to_string()),
+                tags: vec!["backend".to_string(), "core".to_string()],
+            }
+        );
+        assert_eq!(imported[1].name, "make");
+        assert_eq!(imported[1].url, "git@git.example.com:tools/build");
+        assert_eq!(imported[1].path.as_deref(), Some("build/make"));
+        assert_eq!(imported[1].branch.as_deref(), Some("develop"));
+        // Pinned commits are not branches
+        assert_eq!(imported[2].branch, None);
+
+        let relative = r#"<manifest><remote name="o" fetch=".." /><default remote="o" /><project name="api" /></manifest>"#;
+        assert!(parse_manifest(relative).is_err());
+    }
+
+    #[test]
+    fn test_parse_meta_mrconfig_and_url_list() {
+        let meta = r#"{"projects": {"services/api": "git@github.com:org/api.git"}, "ignore": []}"#;
+        assert_eq!(
+            parse_meta(meta).unwrap(),
+            vec![Imported {
+                name: "api".to_string(),
+                url: "git@github.com:org/api.git".to_string(),
+                path: Some("services/api".to_string()),
+                ..Default::default()
+            }]
+        );
+
+        let mrconfig = "[DEFAULT]\nlib = true\n\n[src/web]\ncheckout = git clone --depth 1 'https://github.com/org/web.git' 'web'\n\n[src/notes]\nupdate = true\n";
+        let imported = parse_mrconfig(mrconfig);
+        assert_eq!(imported.len(), 1);
+        assert_eq!(imported[0].name, "web");
+        assert_eq!(imported[0].url, "https://github.com/org/web.git");
+        assert_eq!(imported[0].path.as_deref(), Some("src/web"));
+
+        let list = "# Services\ngit@github.com:org/api.git\n\nhttps://github.com/org/web\n";
+        let names: Vec<_> = parse_url_list(list).into_iter().map(|i| i.name).collect();
+        assert_eq!(names, vec!["api", "web"]);
+    }
+
+    #[test]
+    fn test_import_repositories_records_only_unusual_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join(".meta");
+        std::fs::write(
+            &file,
+            r#"{"projects": {"api": "git@github.com:org/api.git", "libs/core": "git@github.com:org/core.git", "other/api": "git@github.com:fork/api.git"}}"#,
+        )
+        .unwrap();
+
+        let repositories = import_repositories(&file, &PathStrategy::Flat).unwrap();
+        assert_eq!(repositories.len(), 2);
+        assert_eq!(repositories[0].name, "api");
+        assert_eq!(repositories[0].path, None);
+        assert_eq!(repositories[1].name, "core");
+        assert_eq!(repositories[1].path.as_deref(), Some("libs/core"));
+    }
+}
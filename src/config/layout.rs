@@ -0,0 +1,239 @@
+//! Strategies for resolving where repositories live on disk
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::PathBuf;
+
+/// How a repository's directory is derived when it has no explicit `path:`
+///
+/// Configured with the top-level `path_strategy:` key:
+/// - `flat` (default): `<name>`
+/// - `host/owner/name`: `<host>/<owner>/<repo>` from the repository URL
+/// - any other value is a template using `{host}`, `{owner}`, `{repo}` and `{name}`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum PathStrategy {
+    #[default]
+    Flat,
+    HostOwnerName,
+    Template(String),
+}
+
+impl PathStrategy {
+    pub fn is_flat(&self) -> bool {
+        *self == PathStrategy::Flat
+    }
+
+    /// Relative directory for a repository with the given name and URL
+    ///
+    /// Falls back to the repository name when the URL cannot be parsed.
+    pub fn relative_dir(&self, name: &str, url: &str) -> String {
+        let template = match self {
+            PathStrategy::Flat => return name.to_string(),
+            PathStrategy::HostOwnerName => "{host}/{owner}/{repo}",
+            PathStrategy::Template(template) => template.as_str(),
+        };
+
+        match parse_repository_url(url) {
+            Some(parts) => template
+                .replace("{host}", &parts.host)
+                .replace("{owner}", &parts.owner)
+                .replace("{repo}", &parts.repo)
+                .replace("{name}", name),
+            None => name.to_string(),
+        }
+    }
+}
+
+impl From<String> for PathStrategy {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "flat" | "" => PathStrategy::Flat,
+            "host/owner/name" => PathStrategy::HostOwnerName,
+            _ => PathStrategy::Template(value),
+        }
+    }
+}
+
+impl From<PathStrategy> for String {
+    fn from(strategy: PathStrategy) -> Self {
+        strategy.to_string()
+    }
+}
+
+impl fmt::Display for PathStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathStrategy::Flat => write!(f, "flat"),
+            PathStrategy::HostOwnerName => write!(f, "host/owner/name"),
+            PathStrategy::Template(template) => write!(f, "{}", template),
+        }
+    }
+}
+
+/// Components of a git remote URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepositoryUrlParts {
+    pub host: String,
+    /// Owner or group path; nested groups are joined with `/`
+    pub owner: String,
+    /// Repository name without a `.git` suffix
+    pub repo: String,
+}
+
+/// Split an SSH (`git@host:owner/repo.git`) or URL-style remote into its parts
+pub fn parse_repository_url(url: &str) -> Option<RepositoryUrlParts> {
+    let (host, path) = if let Some((_, rest)) = url.split_once("://") {
+        let rest = rest.rsplit_once('@').map_or(rest, |(_, r)| r);
+        rest.split_once('/')?
+    } else {
+        let rest = url.split_once('@').map_or(url, |(_, r)| r);
+        rest.split_once(':')?
+    };
+    // Drop any port from the host
+    let host = host.split(':').next().unwrap_or(host);
+
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    let (owner, repo) = path.rsplit_once('/')?;
+
+    if host.is_empty() || owner.is_empty() || repo.is_empty() {
+        return None;
+    }
+
+    Some(RepositoryUrlParts {
+        host: host.to_string(),
+        owner: owner.to_string(),
+        repo: repo.to_string(),
+    })
+}
+
+/// Expand a leading `~` and `$VAR` / `${VAR}` references in a configured path
+///
+/// Referencing an unset environment variable is an error rather than silently
+/// producing a path somewhere unexpected.
+pub fn expand_path(path: &str) -> Result<PathBuf> {
+    let pattern =
+        regex::Regex::new(r"\$(?:\{([A-Za-z_][A-Za-z0-9_]*)\}|([A-Za-z_][A-Za-z0-9_]*))").unwrap();
+
+    let mut missing = None;
+    let expanded = pattern.replace_all(path, |caps: &regex::Captures| {
+        let name = caps.get(1).or_else(|| caps.get(2)).unwrap().as_str();
+        std::env::var(name).unwrap_or_else(|_| {
+            missing.get_or_insert_with(|| name.to_string());
+            String::new()
+        })
+    });
+    if let Some(name) = missing {
+        anyhow::bail!(
+            "Environment variable '{}' used in path '{}' is not set",
+            name,
+            path
+        );
+    }
+
+    if expanded == "~" || expanded.starts_with("~/") {
+        let home = std::env::var("HOME").map_err(|_| {
+            anyhow::anyhow!("Cannot expand '~' in path '{}': HOME is not set", path)
+        })?;
+        return Ok(
+            PathBuf::from(home).join(expanded.trim_start_matches('~').trim_start_matches('/'))
+        );
+    }
+
+    Ok(PathBuf::from(expanded.as_ref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_repository_url() {
+        let expected = RepositoryUrlParts {
+            host: "github.com".to_string(),
+            owner: "org".to_string(),
+            repo: "api".to_string(),
+        };
+        assert_eq!(
+            parse_repository_url("git@github.com:org/api.git"),
+            Some(expected.clone())
+        );
+        assert_eq!(
+            parse_repository_url("https://github.com/org/api.git"),
+            Some(expected.clone())
+        );
+        assert_eq!(
+            parse_repository_url("ssh://git@github.com:22/org/api"),
+            Some(expected)
+        );
+        assert_eq!(
+            parse_repository_url("https://gitlab.com/group/sub/api")
+                .unwrap()
+                .owner,
+            "group/sub"
+        );
+        assert_eq!(parse_repository_url("not a url"), None);
+    }
+
+    #[test]
+    fn test_relative_dir() {
+        let url = "git@github.com:org/api-service.git";
+
+        assert_eq!(PathStrategy::Flat.relative_dir("api", url), "api");
+        assert_eq!(
+            PathStrategy::HostOwnerName.relative_dir("api", url),
+            "github.com/org/api-service"
+        );
+        assert_eq!(
+            PathStrategy::Template("src/{owner}/{name}".to_string()).relative_dir("api", url),
+            "src/org/api"
+        );
+        // Unparseable URLs fall back to the name
+        assert_eq!(
+            PathStrategy::HostOwnerName.relative_dir("api", "local"),
+            "api"
+        );
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        for (yaml, strategy) in [
+            ("flat", PathStrategy::Flat),
+            ("host/owner/name", PathStrategy::HostOwnerName),
+            (
+                "\"{owner}/{repo}\"",
+                PathStrategy::Template("{owner}/{repo}".to_string()),
+            ),
+        ] {
+            let parsed: PathStrategy = serde_yaml::from_str(yaml).unwrap();
+            assert_eq!(parsed, strategy);
+            let serialized = serde_yaml::to_string(&strategy).unwrap();
+            assert_eq!(
+                serde_yaml::from_str::<PathStrategy>(&serialized).unwrap(),
+                strategy
+            );
+        }
+    }
+
+    #[test]
+    fn test_expand_path() {
+        let home = std::env::var("HOME").unwrap();
+
+        assert_eq!(expand_path("src/api").unwrap(), PathBuf::from("src/api"));
+        assert_eq!(expand_path("~").unwrap(), PathBuf::from(&home));
+        assert_eq!(
+            expand_path("~/work").unwrap(),
+            PathBuf::from(&home).join("work")
+        );
+        assert_eq!(
+            expand_path("${HOME}/work").unwrap(),
+            PathBuf::from(&home).join("work")
+        );
+        assert_eq!(expand_path("$HOME").unwrap(), PathBuf::from(&home));
+        // Only a leading tilde is expanded
+        assert_eq!(expand_path("a/~/b").unwrap(), PathBuf::from("a/~/b"));
+        assert!(expand_path("$REPOS_TEST_UNSET_VARIABLE/work").is_err());
+    }
+}
@@ -1,16 +1,337 @@
 //! Configuration file loading and saving
 
-use super::Repository;
+use super::condition::Condition;
+use super::edit::patch_document;
+use super::workspace::{self, WorkspaceFile, WorkspaceMember};
+use super::{
+    PathStrategy, Profile, Provider, ProviderDefaults, Repository, SecretsConfig, expand_path,
+    profile,
+};
+use crate::git::GitTransport;
+use crate::logging;
+use crate::state::StateStore;
 use crate::utils::filters;
+use crate::utils::output_sink::LogSinkConfig;
+use crate::utils::upload::ArtifactsRemote;
 use crate::utils::validators;
+use crate::utils::{ExitCodeDescriptions, NotifyConfig, Redactor};
 use anyhow::Result;
+use colored::*;
+use log::Level;
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Recipe {
     pub name: String,
-    pub steps: Vec<String>,
+    pub steps: Vec<RecipeStep>,
+    /// Glob patterns of files to collect from each repository after the recipe runs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub artifacts: Vec<String>,
+    /// Reuse results of previous successful runs at the same commit
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cache: bool,
+    /// Environment variables set for the steps; values may reference secrets
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+}
+
+/// One step of a recipe, written as a plain command or as `run` with an `if` condition
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecipeStep {
+    pub run: String,
+    /// Only run the step in repositories for which this holds
+    pub condition: Option<Condition>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConditionalStep {
+    run: String,
+    #[serde(rename = "if", default, skip_serializing_if = "Option::is_none")]
+    condition: Option<Condition>,
+}
+
+impl RecipeStep {
+    /// Whether the step runs in `repo`, checked out in `dir`
+    pub fn applies_to(&self, repo: &Repository, dir: &Path) -> bool {
+        self.condition
+            .as_ref()
+            .is_none_or(|condition| condition.matches(repo, dir))
+    }
+}
+
+impl From<&str> for RecipeStep {
+    fn from(run: &str) -> Self {
+        run.to_string().into()
+    }
+}
+
+impl From<String> for RecipeStep {
+    fn from(run: String) -> Self {
+        Self {
+            run,
+            condition: None,
+        }
+    }
+}
+
+impl Serialize for RecipeStep {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match &self.condition {
+            None => serializer.serialize_str(&self.run),
+            Some(condition) => ConditionalStep {
+                run: self.run.clone(),
+                condition: Some(condition.clone()),
+            }
+            .serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for RecipeStep {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct StepVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for StepVisitor {
+            type Value = RecipeStep;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a command or a mapping with 'run' and 'if'")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, run: &str) -> Result<RecipeStep, E> {
+                Ok(run.into())
+            }
+
+            fn visit_map<A: serde::de::MapAccess<'de>>(
+                self,
+                map: A,
+            ) -> Result<RecipeStep, A::Error> {
+                let step = ConditionalStep::deserialize(
+                    serde::de::value::MapAccessDeserializer::new(map),
+                )?;
+                Ok(RecipeStep {
+                    run: step.run,
+                    condition: step.condition,
+                })
+            }
+        }
+
+        deserializer.deserialize_any(StepVisitor)
+    }
+}
+
+impl Recipe {
+    /// Commands of the steps that run in `repo`, and the steps skipped there
+    pub fn steps_for(&self, repo: &Repository) -> (Vec<String>, Vec<RecipeStep>) {
+        let target_dir = repo.get_target_dir();
+        let (run, skipped): (Vec<&RecipeStep>, Vec<&RecipeStep>) = self
+            .steps
+            .iter()
+            .partition(|step| step.applies_to(repo, Path::new(&target_dir)));
+        (
+            run.into_iter().map(|step| step.run.clone()).collect(),
+            skipped.into_iter().cloned().collect(),
+        )
+    }
+
+    /// Commands of all steps, whatever their conditions
+    pub fn commands(&self) -> Vec<String> {
+        self.steps.iter().map(|step| step.run.clone()).collect()
+    }
+}
+
+/// A file every matching repository is expected to contain, checked by `repos files check`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileRequirement {
+    /// Path relative to the repository root
+    pub path: String,
+    /// Snippets the file must contain verbatim
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub contains: Vec<String>,
+    /// Only check repositories with any of these tags
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// File written to `path` when fixing drift, relative to the config file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    /// Directory of the config file, for resolving `template`
+    #[serde(skip)]
+    pub config_dir: Option<PathBuf>,
+}
+
+impl FileRequirement {
+    /// Whether the requirement applies to a repository
+    pub fn applies_to(&self, repo: &Repository) -> bool {
+        self.tags.is_empty() || repo.has_any_tag(&self.tags)
+    }
+
+    /// Location of the template file, if one is configured
+    pub fn template_path(&self) -> Option<PathBuf> {
+        self.template
+            .as_ref()
+            .map(|template| match &self.config_dir {
+                Some(config_dir) => config_dir.join(template),
+                None => PathBuf::from(template),
+            })
+    }
+}
+
+/// A recipe `repos clone` runs in every matching repository it clones
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostCloneRule {
+    /// Name of the recipe to run
+    pub recipe: String,
+    /// Only run in repositories with any of these tags
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl PostCloneRule {
+    /// Whether the rule applies to a repository
+    pub fn applies_to(&self, repo: &Repository) -> bool {
+        self.tags.is_empty() || repo.has_any_tag(&self.tags)
+    }
+}
+
+/// Defaults for the pull requests opened by `repos pr`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PrConfig {
+    /// Markdown file used as the body when neither `--body` nor `--body-file`
+    /// is given, relative to the config file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    /// Directory of the config file, for resolving `template`
+    #[serde(skip)]
+    pub config_dir: Option<PathBuf>,
+}
+
+impl PrConfig {
+    /// Location of the body template, if one is configured
+    pub fn template_path(&self) -> Option<PathBuf> {
+        self.template
+            .as_ref()
+            .map(|template| match &self.config_dir {
+                Some(config_dir) => config_dir.join(template),
+                None => PathBuf::from(template),
+            })
+    }
+}
+
+/// Branch protection every matching repository is expected to have, checked by `repos protect`
+///
+/// Settings left out are not checked, and are kept as they are when the
+/// policy is applied.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BranchProtectionPolicy {
+    /// Branch to protect, the repository's default branch when not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// Only check repositories with any of these tags
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Approving reviews a pull request needs before merging
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub required_approving_reviews: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dismiss_stale_reviews: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub require_code_owner_reviews: Option<bool>,
+    /// Status checks that must pass; an empty list turns required checks off
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub required_status_checks: Option<Vec<String>>,
+    /// Whether branches must be up to date with the protected branch before merging
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strict_status_checks: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enforce_admins: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub required_linear_history: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_force_pushes: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_deletions: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub required_conversation_resolution: Option<bool>,
+}
+
+impl BranchProtectionPolicy {
+    /// Whether the policy applies to a repository
+    pub fn applies_to(&self, repo: &Repository) -> bool {
+        self.tags.is_empty() || repo.has_any_tag(&self.tags)
+    }
+}
+
+/// Repository settings every matching repository is expected to have, checked by `repos audit`
+///
+/// Settings left out are not checked.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SettingsSpec {
+    /// Only check repositories with any of these tags
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Labels that must exist, with their color and description when given
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<LabelSpec>,
+    /// Topics the repository must have, next to any others
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub topics: Vec<String>,
+    /// Name of the default branch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_branch: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_merge_commit: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_squash_merge: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_rebase_merge: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_auto_merge: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delete_branch_on_merge: Option<bool>,
+    /// Approving reviews pull requests into the default branch need
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub required_approving_reviews: Option<u32>,
+    /// Webhooks that must be configured
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub webhooks: Vec<WebhookSpec>,
+}
+
+impl SettingsSpec {
+    /// Whether the spec applies to a repository
+    pub fn applies_to(&self, repo: &Repository) -> bool {
+        self.tags.is_empty() || repo.has_any_tag(&self.tags)
+    }
+}
+
+/// A label in a [`SettingsSpec`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LabelSpec {
+    pub name: String,
+    /// Hex color, with or without the leading `#`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// A webhook in a [`SettingsSpec`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookSpec {
+    pub url: String,
+    /// Events delivered to the webhook when it is created
+    #[serde(default = "default_webhook_events")]
+    pub events: Vec<String>,
+    /// Secret signing the payloads when the webhook is created, with `${VAR}` expanded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+}
+
+fn default_webhook_events() -> Vec<String> {
+    vec!["push".to_string()]
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,30 +339,265 @@ pub struct Config {
     pub repositories: Vec<Repository>,
     #[serde(default)]
     pub recipes: Vec<Recipe>,
+    /// Remote storage that run outputs are uploaded to with `repos run --upload`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub artifacts_remote: Option<ArtifactsRemote>,
+    /// Extra destinations for the output of saved runs, next to the run directory
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub log_sinks: Vec<LogSinkConfig>,
+    /// How repository directories are derived when `path` is not set
+    #[serde(default, skip_serializing_if = "PathStrategy::is_flat")]
+    pub path_strategy: PathStrategy,
+    /// Directory repositories are cloned under; relative to the config file,
+    /// with `~` and environment variables expanded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root: Option<String>,
+    /// Files and snippets each repository must contain
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<FileRequirement>,
+    /// Branch protection rules each repository must have
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub branch_protection: Vec<BranchProtectionPolicy>,
+    /// Labels, topics, merge settings and webhooks each repository must have
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub repository_settings: Vec<SettingsSpec>,
+    /// Recipes run in repositories right after they are cloned, by tag
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub post_clone: Vec<PostCloneRule>,
+    /// Defaults for pull requests, such as the body template
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pr: Option<PrConfig>,
+    /// Hosting provider for repositories that do not set their own
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<Provider>,
+    /// Base URL of the self-hosted instance for repositories that do not set their own
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider_url: Option<String>,
+    /// How git reaches the remotes of repositories that do not set their own
+    #[serde(default, skip_serializing_if = "GitTransport::is_default")]
+    pub git: GitTransport,
+    /// Descriptions of exit codes used by the organization's tooling, e.g. `42: database migration pending`
+    #[serde(default, skip_serializing_if = "ExitCodeDescriptions::is_empty")]
+    pub exit_codes: ExitCodeDescriptions,
+    /// Where to report finished clone, run, and pull request batches
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notify: Option<NotifyConfig>,
+    /// Named variants of this config, selected with `--profile`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, Profile>,
+    /// Encrypted files holding the secrets recipes reference
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secrets: Option<SecretsConfig>,
+    /// Token reference of the profile the config was loaded with
+    #[serde(skip)]
+    pub profile_token: Option<String>,
 }
 
 impl Config {
-    /// Load configuration from a file
+    /// Load configuration from a file, which may be a workspace file listing other configs
+    ///
+    /// The profile selected with `--profile` is applied.
     pub fn load(path: &str) -> Result<Self> {
+        let config = match profile::active() {
+            Some(name) => Self::load_profile(path, name)?,
+            None => Self::load_without_profile(path)?,
+        };
+        Ok(config.with_archived_state(&StateStore::open_default()))
+    }
+
+    /// Mark the repositories the state store remembers as archived
+    ///
+    /// They then carry the `archived` pseudo-tag. A state file that cannot be
+    /// read leaves every repository unmarked.
+    pub fn with_archived_state(mut self, store: &StateStore) -> Self {
+        if let Ok(state) = store.load() {
+            for repo in &mut self.repositories {
+                repo.archived = state
+                    .repositories
+                    .get(&repo.name)
+                    .is_some_and(|repo_state| repo_state.archived);
+            }
+        }
+        self
+    }
+
+    fn load_without_profile(path: &str) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+
+        if let Ok(file) = serde_yaml::from_str::<WorkspaceFile>(&content) {
+            let workspace_dir =
+                std::env::current_dir()?.join(Path::new(path).parent().unwrap_or(Path::new("")));
+            return workspace::load_members(&file.workspace, &workspace_dir);
+        }
+
+        Self::load_file(path)
+    }
+
+    /// Load configuration with a profile applied
+    ///
+    /// A `<config>.<profile>.yaml` file next to `path` is loaded instead when it
+    /// exists; otherwise the profile must be defined under `profiles` in `path`.
+    pub fn load_profile(path: &str, name: &str) -> Result<Self> {
+        let separate = profile::profile_path(path, name);
+        if separate.exists() {
+            return Self::load_file_with_profile(&separate.to_string_lossy(), name, false);
+        }
+
+        let content = std::fs::read_to_string(path)?;
+        if serde_yaml::from_str::<WorkspaceFile>(&content).is_ok() {
+            anyhow::bail!(
+                "Workspace file {} cannot define profiles; create {} instead",
+                path,
+                separate.display()
+            );
+        }
+        Self::load_file_with_profile(path, name, true)
+    }
+
+    /// Load configuration from several files as one workspace
+    ///
+    /// A single path is loaded as is; with more, each file's repositories are
+    /// namespaced as in a workspace file.
+    pub fn load_all(paths: &[String]) -> Result<Self> {
+        match paths {
+            [path] => Self::load(path),
+            _ if profile::active().is_some() => {
+                anyhow::bail!("--profile cannot be used with more than one --config")
+            }
+            _ => {
+                let members: Vec<WorkspaceMember> = paths
+                    .iter()
+                    .map(|path| WorkspaceMember {
+                        config: path.clone(),
+                        name: None,
+                    })
+                    .collect();
+                let config = workspace::load_members(&members, &std::env::current_dir()?)?;
+                Ok(config.with_archived_state(&StateStore::open_default()))
+            }
+        }
+    }
+
+    /// Load a single configuration file
+    pub(crate) fn load_file(path: &str) -> Result<Self> {
+        Self::load_file_as(path, None)
+    }
+
+    /// Load a single configuration file with a profile applied
+    ///
+    /// With `required`, the file must define the profile.
+    fn load_file_with_profile(path: &str, name: &str, required: bool) -> Result<Self> {
+        Self::load_file_as(path, Some((name, required)))
+    }
+
+    fn load_file_as(path: &str, profile: Option<(&str, bool)>) -> Result<Self> {
         let content = std::fs::read_to_string(path)?;
 
         let mut config: Config = serde_yaml::from_str(&content)?;
+        if let Some((name, required)) = profile {
+            match config.profiles.get(name).cloned() {
+                Some(profile) => config.apply_profile(profile),
+                None if required => anyhow::bail!(
+                    "Unknown profile '{}' in {}{}",
+                    name,
+                    path,
+                    if config.profiles.is_empty() {
+                        String::new()
+                    } else {
+                        format!(
+                            " (available: {})",
+                            config
+                                .profiles
+                                .keys()
+                                .cloned()
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        )
+                    }
+                ),
+                None => {}
+            }
+        }
 
-        // Set the config directory for each repository
-        let config_path = Path::new(path);
-        let config_dir = config_path.parent().map(|p| p.to_path_buf());
+        // Relative repository paths resolve against the root directory, so the
+        // result does not depend on where the CLI is invoked from
+        let config_dir =
+            std::env::current_dir()?.join(Path::new(path).parent().unwrap_or(Path::new("")));
+        let root_dir = config.root_dir(&config_dir)?;
 
         for repo in &mut config.repositories {
-            repo.set_config_dir(config_dir.clone());
+            if let Some(repo_path) = &repo.path {
+                expand_path(repo_path)
+                    .map_err(|e| anyhow::anyhow!("Repository '{}': {}", repo.name, e))?;
+            }
+            repo.set_config_dir(Some(root_dir.clone()));
+            repo.set_path_strategy(config.path_strategy.clone());
+            repo.set_provider_defaults(ProviderDefaults {
+                provider: config.provider,
+                url: config.provider_url.clone(),
+                git: config.git.clone(),
+            });
+        }
+        for requirement in &mut config.files {
+            requirement.config_dir = Some(config_dir.clone());
+        }
+        if let Some(secrets) = &mut config.secrets {
+            secrets.config_dir = Some(config_dir.clone());
+        }
+        if let Some(pr) = &mut config.pr {
+            pr.config_dir = Some(config_dir.clone());
         }
 
         // Validate the loaded configuration
         validators::validate_repositories(&config.repositories)
             .map_err(validators::validation_errors_to_anyhow)?;
+        validators::validate_artifact_patterns(&config.recipes)
+            .map_err(validators::validation_errors_to_anyhow)?;
 
         Ok(config)
     }
 
+    /// Replace and narrow the top-level settings with those of a profile
+    fn apply_profile(&mut self, profile: Profile) {
+        if profile.root.is_some() {
+            self.root = profile.root;
+        }
+        if !profile.tags.is_empty() {
+            self.repositories
+                .retain(|repo| repo.has_any_tag(&profile.tags));
+        }
+        self.repositories.extend(profile.repositories);
+        self.profile_token = profile.token;
+    }
+
+    /// Token of the profile the config was loaded with, if it names one
+    ///
+    /// A token that cannot be looked up is reported and ignored.
+    pub fn resolve_profile_token(&self) -> Option<String> {
+        let reference = self.profile_token.as_ref()?;
+        match profile::resolve_token(reference) {
+            Ok(token) => Some(token),
+            Err(e) => {
+                logging::message(
+                    Level::Warn,
+                    format!("Warning: Ignoring the profile's token: {}", e).yellow(),
+                );
+                None
+            }
+        }
+    }
+
+    /// Directory relative repository paths are resolved against
+    ///
+    /// This is the `root` setting when present (itself relative to `config_dir`),
+    /// otherwise `config_dir`.
+    pub fn root_dir(&self, config_dir: &Path) -> Result<PathBuf> {
+        match &self.root {
+            Some(root) => Ok(config_dir.join(expand_path(root)?)),
+            None => Ok(config_dir.to_path_buf()),
+        }
+    }
+
     /// Save configuration to a file
     pub fn save(&self, path: &str) -> Result<()> {
         save_config(self, path)
@@ -123,6 +679,23 @@ impl Config {
         Self {
             repositories: Vec::new(),
             recipes: Vec::new(),
+            artifacts_remote: None,
+            log_sinks: Vec::new(),
+            path_strategy: PathStrategy::default(),
+            root: None,
+            files: Vec::new(),
+            branch_protection: Vec::new(),
+            repository_settings: Vec::new(),
+            post_clone: Vec::new(),
+            pr: None,
+            provider: None,
+            provider_url: None,
+            git: Default::default(),
+            exit_codes: ExitCodeDescriptions::default(),
+            notify: None,
+            profiles: BTreeMap::new(),
+            secrets: None,
+            profile_token: None,
         }
     }
 
@@ -131,6 +704,29 @@ impl Config {
         self.recipes.iter().find(|r| r.name == name)
     }
 
+    /// Name of the recipe to run after cloning a repository
+    ///
+    /// The repository's own `post_clone` wins over the first top-level rule
+    /// matching its tags.
+    pub fn post_clone_recipe<'a>(&'a self, repo: &'a Repository) -> Option<&'a str> {
+        repo.post_clone.as_deref().or_else(|| {
+            self.post_clone
+                .iter()
+                .find(|rule| rule.applies_to(repo))
+                .map(|rule| rule.recipe.as_str())
+        })
+    }
+
+    /// Redactor for the built-in token formats and the patterns under `secrets.redact`
+    pub fn redactor(&self) -> Result<Redactor> {
+        let patterns = self
+            .secrets
+            .as_ref()
+            .map(|secrets| secrets.redact.as_slice())
+            .unwrap_or_default();
+        Redactor::new().with_patterns(patterns)
+    }
+
     /// Alias for load method for backwards compatibility
     pub fn load_config(path: &str) -> Result<Self> {
         Self::load(path)
@@ -158,16 +754,31 @@ impl Default for Config {
     }
 }
 
-/// Save a config to a file with proper YAML formatting and comment preservation
+/// Save a config to a file, keeping the comments and layout of an existing one
 ///
-/// This is the centralized function for writing repos.yaml files. It ensures:
-/// - Leading comments are preserved
-/// - YAML document start marker (---) is added after comments
+/// This is the centralized function for writing repos.yaml files. An existing
+/// file is edited in place with [`patch_document`]: unchanged entries keep their
+/// comments, anchors and key order, and only changed values are rewritten. A new
+/// file, or one that cannot be edited in place, is written from scratch with:
+/// - Leading comments preserved
+/// - YAML document start marker (---) added after comments
 /// - Proper indentation for yamllint compliance
 /// - Trailing newline
 ///
 /// Use this function or Config::save() for all config file writes to ensure consistency.
 pub fn save_config<T: Serialize>(config: &T, path: &str) -> Result<()> {
+    let value = serde_yaml::to_value(config)?;
+
+    if let Ok(existing) = std::fs::read_to_string(path) {
+        match patch_document(&existing, &value) {
+            Ok(patched) => {
+                std::fs::write(path, patched)?;
+                return Ok(());
+            }
+            Err(e) => log::debug!("Rewriting {} instead of editing it in place: {:#}", path, e),
+        }
+    }
+
     // Read existing file to preserve leading comments
     let existing_comments = if Path::new(path).exists() {
         extract_leading_comments(path)?
@@ -176,7 +787,7 @@ pub fn save_config<T: Serialize>(config: &T, path: &str) -> Result<()> {
     };
 
     // Serialize config to YAML
-    let yaml = serde_yaml::to_string(config)?;
+    let yaml = serde_yaml::to_string(&value)?;
 
     // Apply minimal indentation fix for yamllint compliance
     let fixed_yaml = yaml
@@ -266,6 +877,23 @@ mod tests {
         Config {
             repositories: vec![repo1, repo2],
             recipes: Vec::new(),
+            artifacts_remote: None,
+            log_sinks: Vec::new(),
+            path_strategy: Default::default(),
+            root: None,
+            files: Vec::new(),
+            branch_protection: Vec::new(),
+            repository_settings: Vec::new(),
+            post_clone: Vec::new(),
+            pr: None,
+            provider: None,
+            provider_url: None,
+            git: Default::default(),
+            exit_codes: Default::default(),
+            notify: None,
+            profiles: Default::default(),
+            secrets: None,
+            profile_token: None,
         }
     }
 
@@ -281,6 +909,24 @@ mod tests {
         assert_eq!(all_repos.len(), 2);
     }
 
+    #[test]
+    fn test_archived_pseudo_tag() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let store = StateStore::new(temp_dir.path());
+        store.record_archived("repo2", true).unwrap();
+
+        let config = create_test_config().with_archived_state(&store);
+
+        let archived = config.filter_repositories(&["archived".to_string()], &[], None);
+        assert_eq!(archived.len(), 1);
+        assert_eq!(archived[0].name, "repo2");
+
+        let active = config.filter_repositories(&[], &["archived".to_string()], None);
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].name, "repo1");
+        assert!(!active[0].tags.contains(&"archived".to_string()));
+    }
+
     #[test]
     fn test_filter_by_any_tag() {
         let config = create_test_config();
@@ -527,7 +1173,10 @@ mod tests {
         let mut config = Config::new();
         let recipe = Recipe {
             name: "test-recipe".to_string(),
-            steps: vec!["echo hello".to_string()],
+            steps: vec!["echo hello".into()],
+            artifacts: vec![],
+            cache: false,
+            env: Default::default(),
         };
         config.recipes.push(recipe);
 
@@ -539,6 +1188,36 @@ mod tests {
         assert!(not_found.is_none());
     }
 
+    #[test]
+    fn test_recipe_steps_with_conditions() {
+        let yaml = "\
+name: install
+steps:
+  - echo start
+  - run: npm ci
+    if: tag == 'node'
+";
+        let recipe: Recipe = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(recipe.steps[0], RecipeStep::from("echo start"));
+        assert_eq!(recipe.steps[1].run, "npm ci");
+
+        let mut repo = Repository::new("web".to_string(), "git@github.com:org/web.git".to_string());
+        let (steps, skipped) = recipe.steps_for(&repo);
+        assert_eq!(steps, vec!["echo start"]);
+        assert_eq!(skipped, vec![recipe.steps[1].clone()]);
+        repo.tags = vec!["node".to_string()];
+        assert_eq!(recipe.steps_for(&repo).0, vec!["echo start", "npm ci"]);
+
+        // Plain steps stay plain when written back
+        let written = serde_yaml::to_string(&recipe).unwrap();
+        assert!(written.contains("- echo start\n- run: npm ci\n  if: tag == 'node'\n"));
+
+        let error =
+            serde_yaml::from_str::<Recipe>("name: x\nsteps:\n  - run: make\n    if: tag =\n")
+                .unwrap_err();
+        assert!(error.to_string().contains("Invalid condition 'tag ='"));
+    }
+
     #[test]
     fn test_config_new_default() {
         let config1 = Config::new();
@@ -654,6 +1333,27 @@ mod tests {
         std::fs::remove_file(&config_path).unwrap();
     }
 
+    #[test]
+    fn test_save_config_edits_in_place() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("repos.yaml");
+        let content = "repositories:\n  # Core services\n  - name: api # owned by platform\n    url: git@github.com:org/api.git\n    tags: [backend]\n";
+        std::fs::write(&config_path, content).unwrap();
+        let path = config_path.to_str().unwrap();
+
+        let mut config = Config::load(path).unwrap();
+        config.repositories.push(Repository::new(
+            "web".to_string(),
+            "git@github.com:org/web.git".to_string(),
+        ));
+        config.save(path).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&config_path).unwrap(),
+            format!("{content}  - name: web\n    url: git@github.com:org/web.git\n")
+        );
+    }
+
     #[test]
     fn test_save_config_without_existing_file() {
         let temp_dir = std::env::temp_dir();
@@ -684,4 +1384,93 @@ mod tests {
         // Cleanup
         std::fs::remove_file(&config_path).unwrap();
     }
+
+    #[test]
+    fn test_load_resolves_paths_against_root() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.yaml");
+        std::fs::write(
+            &config_path,
+            "root: workspace\nrepositories:\n  - name: api\n    url: git@github.com:org/api.git\n    tags: []\n  - name: web\n    url: git@github.com:org/web.git\n    tags: []\n    path: frontend/web\n",
+        )
+        .unwrap();
+
+        let config = Config::load(config_path.to_str().unwrap()).unwrap();
+        let root = temp_dir.path().join("workspace");
+
+        assert_eq!(
+            config.repositories[0].get_target_dir(),
+            root.join("api").to_string_lossy()
+        );
+        assert_eq!(
+            config.repositories[1].get_target_dir(),
+            root.join("frontend/web").to_string_lossy()
+        );
+    }
+
+    #[test]
+    fn test_load_rejects_unset_variable_in_root() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.yaml");
+        std::fs::write(
+            &config_path,
+            "root: ${REPOS_TEST_UNSET_VARIABLE}/src\nrepositories: []\n",
+        )
+        .unwrap();
+
+        assert!(Config::load(config_path.to_str().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_load_rejects_artifact_patterns_outside_repository() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.yaml");
+        std::fs::write(
+            &config_path,
+            "repositories: []\nrecipes:\n  - name: build\n    steps: [make]\n    artifacts: [\"../shared/*.log\"]\n",
+        )
+        .unwrap();
+
+        let error = Config::load(config_path.to_str().unwrap()).unwrap_err();
+        assert!(error.to_string().contains("'../shared/*.log'"));
+    }
+
+    #[test]
+    fn test_load_profile() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("repos.yaml");
+        std::fs::write(
+            &config_path,
+            "repositories:\n  - name: api\n    url: git@github.com:org/api.git\n    tags: [work]\n  - name: blog\n    url: git@github.com:me/blog.git\n    tags: [oss]\nprofiles:\n  work:\n    root: work\n    token: work-github\n    tags: [work]\n    repositories:\n      - name: infra\n        url: git@github.com:org/infra.git\n        tags: []\n",
+        )
+        .unwrap();
+        let path = config_path.to_str().unwrap();
+
+        let config = Config::load_profile(path, "work").unwrap();
+        let names: Vec<_> = config
+            .repositories
+            .iter()
+            .map(|r| r.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["api", "infra"]);
+        assert_eq!(
+            config.repositories[0].get_target_dir(),
+            temp_dir.path().join("work/api").to_string_lossy()
+        );
+        assert_eq!(config.profile_token.as_deref(), Some("work-github"));
+
+        let err = Config::load_profile(path, "oss").unwrap_err();
+        assert!(err.to_string().contains("available: work"));
+
+        // A separate file wins over the profiles section
+        std::fs::write(
+            temp_dir.path().join("repos.oss.yaml"),
+            "repositories:\n  - name: dotfiles\n    url: git@github.com:me/dotfiles.git\n    tags: []\n",
+        )
+        .unwrap();
+        let config = Config::load_profile(path, "oss").unwrap();
+        assert_eq!(config.repositories.len(), 1);
+        assert_eq!(config.repositories[0].name, "dotfiles");
+        assert_eq!(config.profile_token, None);
+    }
 }
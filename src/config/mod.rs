@@ -1,9 +1,31 @@
 //! Configuration management module
 
+pub mod aliases;
 pub mod builder;
+pub mod condition;
+pub mod defaults;
+pub mod dependencies;
+pub mod edit;
+pub mod export;
+pub mod import;
+pub mod layout;
 pub mod loader;
+pub mod profile;
+pub mod provider;
 pub mod repository;
+pub mod schema;
+pub mod secrets;
+pub mod workspace;
 
 pub use builder::RepositoryBuilder;
-pub use loader::{Config, Recipe};
+pub use condition::Condition;
+pub use dependencies::DependencyGraph;
+pub use layout::{PathStrategy, expand_path};
+pub use loader::{
+    BranchProtectionPolicy, Config, FileRequirement, LabelSpec, PostCloneRule, PrConfig, Recipe,
+    RecipeStep, SettingsSpec, WebhookSpec,
+};
+pub use profile::Profile;
+pub use provider::{Provider, ProviderDefaults};
 pub use repository::Repository;
+pub use secrets::{SecretEnv, SecretsConfig};
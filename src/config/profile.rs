@@ -0,0 +1,133 @@
+//! Named profiles selected with `--profile`
+//!
+//! A profile adapts one config to an environment or team:
+//!
+//! ```yaml
+//! profiles:
+//!   work:
+//!     root: ~/work
+//!     token: work-github
+//!     tags: [work]
+//!   oss:
+//!     root: ~/oss
+//!     token: ${OSS_GITHUB_TOKEN}
+//!     repositories:
+//!       - name: dotfiles
+//!         url: git@github.com:me/dotfiles.git
+//! ```
+//!
+//! A file next to the config named after the profile, such as
+//! `repos.work.yaml` for `repos.yaml`, is loaded instead when it exists, for
+//! fleets that share nothing.
+
+use super::Repository;
+use crate::credentials::IdentityStore;
+use crate::utils::upload::expand_env;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Settings that replace or narrow the top-level config when a profile is selected
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    /// Directory repositories are cloned under, instead of the top-level `root`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root: Option<String>,
+    /// Name of an identity saved with `repos auth login`, or `${VAR}` to read
+    /// the token from an environment variable
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    /// Only top-level repositories with any of these tags belong to the profile
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Repositories that only belong to this profile
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub repositories: Vec<Repository>,
+}
+
+static ACTIVE: OnceLock<String> = OnceLock::new();
+
+/// Select the profile every config is loaded with for the rest of the process
+pub fn select(name: &str) {
+    let _ = ACTIVE.set(name.to_string());
+}
+
+/// Name of the selected profile
+pub fn active() -> Option<&'static str> {
+    ACTIVE.get().map(String::as_str)
+}
+
+/// The file commands that edit the config write to: the active profile's file when it exists, else the config
+pub fn editable_path(config_path: &str) -> PathBuf {
+    match active() {
+        Some(name) if profile_path(config_path, name).exists() => profile_path(config_path, name),
+        _ => PathBuf::from(config_path),
+    }
+}
+
+/// Config file for a profile next to `config_path`, e.g. `repos.work.yaml`
+pub fn profile_path(config_path: &str, name: &str) -> PathBuf {
+    let path = Path::new(config_path);
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let file_name = match path.extension() {
+        Some(extension) => format!("{}.{}.{}", stem, name, extension.to_string_lossy()),
+        None => format!("{}.{}", stem, name),
+    };
+    path.with_file_name(file_name)
+}
+
+/// Look up the token a profile refers to
+pub fn resolve_token(reference: &str) -> Result<String> {
+    if reference.contains("${") {
+        let token = expand_env(reference);
+        if token.is_empty() {
+            anyhow::bail!("Token reference '{}' expands to nothing", reference);
+        }
+        return Ok(token);
+    }
+
+    let store = IdentityStore::open_default()?;
+    let identity = store
+        .list()?
+        .into_iter()
+        .find(|identity| identity.name == reference)
+        .with_context(|| {
+            format!(
+                "No identity '{}'; save one with `repos auth login {}`",
+                reference, reference
+            )
+        })?;
+    store
+        .token(&identity)?
+        .with_context(|| format!("No token in the keychain for identity '{}'", reference))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_path() {
+        assert_eq!(
+            profile_path("config/repos.yaml", "work"),
+            PathBuf::from("config/repos.work.yaml")
+        );
+        assert_eq!(profile_path("repos", "oss"), PathBuf::from("repos.oss"));
+    }
+
+    #[test]
+    fn test_resolve_env_token() {
+        // SAFETY: the variable is unique to this test
+        unsafe { std::env::set_var("REPOS_TEST_PROFILE_TOKEN", "secret") };
+        assert_eq!(
+            resolve_token("${REPOS_TEST_PROFILE_TOKEN}").unwrap(),
+            "secret"
+        );
+        assert!(resolve_token("${REPOS_TEST_PROFILE_TOKEN_UNSET}").is_err());
+    }
+}
@@ -0,0 +1,97 @@
+//! Hosting providers repositories can live on
+
+use crate::git::GitTransport;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Service hosting a repository, which decides the API used for pull requests
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Provider {
+    #[default]
+    #[serde(rename = "github")]
+    GitHub,
+    /// Gitea and its fork Forgejo, which share an API
+    #[serde(alias = "forgejo")]
+    Gitea,
+    /// Azure Repos in Azure DevOps
+    Azure,
+}
+
+impl Provider {
+    /// Names accepted in the config
+    pub const NAMES: &[&str] = &["github", "gitea", "forgejo", "azure"];
+
+    pub fn is_github(&self) -> bool {
+        *self == Provider::GitHub
+    }
+
+    /// Name of the provider as written in the config
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Provider::GitHub => "github",
+            Provider::Gitea => "gitea",
+            Provider::Azure => "azure",
+        }
+    }
+}
+
+impl FromStr for Provider {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "github" => Ok(Provider::GitHub),
+            "gitea" | "forgejo" => Ok(Provider::Gitea),
+            "azure" => Ok(Provider::Azure),
+            _ => anyhow::bail!(
+                "Unknown provider '{}' (expected {})",
+                s,
+                Self::NAMES.join(", ")
+            ),
+        }
+    }
+}
+
+impl fmt::Display for Provider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Provider::GitHub => f.write_str("GitHub"),
+            Provider::Gitea => f.write_str("Gitea"),
+            Provider::Azure => f.write_str("Azure DevOps"),
+        }
+    }
+}
+
+/// Top-level provider and git settings inherited by repositories that do not set their own
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProviderDefaults {
+    pub provider: Option<Provider>,
+    pub url: Option<String>,
+    pub git: GitTransport,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_provider_names() {
+        for name in Provider::NAMES {
+            assert!(serde_yaml::from_str::<Provider>(name).is_ok());
+        }
+        assert_eq!(
+            serde_yaml::from_str::<Provider>("forgejo").unwrap(),
+            Provider::Gitea
+        );
+        assert_eq!(
+            serde_yaml::to_string(&Provider::GitHub).unwrap(),
+            "github\n"
+        );
+        assert_eq!(serde_yaml::to_string(&Provider::Azure).unwrap(), "azure\n");
+        assert!(serde_yaml::from_str::<Provider>("gitlab").is_err());
+        assert_eq!("forgejo".parse::<Provider>().unwrap(), Provider::Gitea);
+        assert!("gitlab".parse::<Provider>().is_err());
+    }
+}
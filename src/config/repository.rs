@@ -1,7 +1,12 @@
 //! Repository configuration and utilities
 
+use super::layout::{PathStrategy, expand_path};
+use super::provider::{Provider, ProviderDefaults};
+use crate::constants::config::ARCHIVED_TAG;
+use crate::git::GitTransport;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,8 +18,41 @@ pub struct Repository {
     pub path: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub branch: Option<String>,
+    /// Base branch for pull requests, overriding detection
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_branch: Option<String>,
+    /// Directories to check out with git sparse-checkout; empty checks out everything
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sparse: Vec<String>,
+    /// Hosting provider; the top-level `provider` (or GitHub) when not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<Provider>,
+    /// Base URL of a self-hosted instance; derived from `url` when not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider_url: Option<String>,
+    /// Remotes besides the one `url` points to, by name; added when cloning
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub remotes: BTreeMap<String, String>,
+    /// How git reaches the remotes, over the top-level `git` settings
+    #[serde(default, skip_serializing_if = "GitTransport::is_default")]
+    pub git: GitTransport,
+    /// Repositories that `run --ordered` must finish before this one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+    /// Recipe `repos clone` runs after cloning, over the top-level `post_clone` rules
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_clone: Option<String>,
+    /// Archived on the provider, as last remembered in the state store
+    #[serde(skip)]
+    pub archived: bool,
     #[serde(skip)]
     pub config_dir: Option<PathBuf>,
+    /// Strategy used to derive the directory when `path` is not set
+    #[serde(skip)]
+    pub path_strategy: PathStrategy,
+    /// Provider settings from the top level of the config
+    #[serde(skip)]
+    pub provider_defaults: ProviderDefaults,
 }
 
 impl Repository {
@@ -26,13 +64,26 @@ impl Repository {
             tags: Vec::new(),
             path: None,
             branch: None,
+            default_branch: None,
+            sparse: Vec::new(),
+            provider: None,
+            provider_url: None,
+            remotes: BTreeMap::new(),
             config_dir: None,
+            path_strategy: PathStrategy::default(),
+            provider_defaults: ProviderDefaults::default(),
+            git: GitTransport::default(),
+            depends_on: Vec::new(),
+            post_clone: None,
+            archived: false,
         }
     }
 
     /// Check if repository has a specific tag
+    ///
+    /// Archived repositories also carry the [`ARCHIVED_TAG`] pseudo-tag.
     pub fn has_tag(&self, tag: &str) -> bool {
-        self.tags.iter().any(|t| t == tag)
+        (self.archived && tag == ARCHIVED_TAG) || self.tags.iter().any(|t| t == tag)
     }
 
     /// Check if repository has any of the specified tags
@@ -54,47 +105,62 @@ impl Repository {
     }
 
     /// Get the target directory for cloning
+    ///
+    /// An explicit `path` (with `~` and environment variables expanded) wins;
+    /// otherwise the directory comes from the path strategy. Relative results
+    /// are resolved against the configured root directory, or the current
+    /// directory when that is unknown.
     pub fn get_target_dir(&self) -> String {
-        match &self.path {
-            Some(path) => {
-                let path_buf = PathBuf::from(path);
-                if path_buf.is_absolute() {
-                    // If it's already an absolute path, use it as-is
-                    path.clone()
-                } else {
-                    // If it's a relative path, resolve it relative to the config file directory
-                    if let Some(config_dir) = &self.config_dir {
-                        config_dir.join(path).to_string_lossy().to_string()
-                    } else {
-                        // Fallback to current directory if config_dir is not available
-                        std::env::current_dir()
-                            .unwrap_or_else(|_| PathBuf::from("."))
-                            .join(path)
-                            .to_string_lossy()
-                            .to_string()
-                    }
-                }
-            }
-            None => {
-                // Default to repository name as relative path
-                if let Some(config_dir) = &self.config_dir {
-                    config_dir.join(&self.name).to_string_lossy().to_string()
-                } else {
-                    std::env::current_dir()
-                        .unwrap_or_else(|_| PathBuf::from("."))
-                        .join(&self.name)
-                        .to_string_lossy()
-                        .to_string()
-                }
-            }
+        let relative = match &self.path {
+            Some(path) => expand_path(path).unwrap_or_else(|_| PathBuf::from(path)),
+            None => PathBuf::from(self.path_strategy.relative_dir(&self.name, &self.url)),
+        };
+
+        if relative.is_absolute() {
+            return relative.to_string_lossy().to_string();
         }
+
+        let base = match &self.config_dir {
+            Some(config_dir) => config_dir.clone(),
+            None => std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+        };
+        base.join(relative).to_string_lossy().to_string()
     }
 
-    /// Set the configuration directory (used by config loader)
+    /// Set the directory relative paths resolve against (used by config loader)
     pub fn set_config_dir(&mut self, config_dir: Option<PathBuf>) {
         self.config_dir = config_dir;
     }
 
+    /// Set the path strategy (used by config loader)
+    pub fn set_path_strategy(&mut self, path_strategy: PathStrategy) {
+        self.path_strategy = path_strategy;
+    }
+
+    /// Set the top-level provider settings (used by config loader)
+    pub fn set_provider_defaults(&mut self, provider_defaults: ProviderDefaults) {
+        self.provider_defaults = provider_defaults;
+    }
+
+    /// Hosting provider: the repository's own, then the top-level one, then GitHub
+    pub fn provider(&self) -> Provider {
+        self.provider
+            .or(self.provider_defaults.provider)
+            .unwrap_or_default()
+    }
+
+    /// Configured base URL of the provider instance, if any
+    pub fn provider_url(&self) -> Option<String> {
+        self.provider_url
+            .clone()
+            .or_else(|| self.provider_defaults.url.clone())
+    }
+
+    /// Git transport settings: the repository's own, then the top-level ones
+    pub fn git_transport(&self) -> GitTransport {
+        self.git.merged(&self.provider_defaults.git)
+    }
+
     /// Add a tag to the repository
     pub fn add_tag(&mut self, tag: String) {
         if !self.tags.contains(&tag) {
@@ -127,6 +193,17 @@ mod tests {
             path: Some("journey".to_string()),
             branch: None,
             config_dir: Some(PathBuf::from("/some/config/dir")),
+            path_strategy: Default::default(),
+            default_branch: None,
+            sparse: Vec::new(),
+            provider: None,
+            provider_url: None,
+            provider_defaults: Default::default(),
+            git: Default::default(),
+            depends_on: Vec::new(),
+            post_clone: None,
+            archived: false,
+            remotes: Default::default(),
         };
 
         let target_dir = repo.get_target_dir();
@@ -143,6 +220,22 @@ mod tests {
         assert_eq!(target_dir, "/some/config/dir/test-repo");
     }
 
+    #[test]
+    fn test_path_strategy_resolution() {
+        let mut repo = Repository::new(
+            "test-repo".to_string(),
+            "git@github.com:owner/repo.git".to_string(),
+        );
+        repo.set_config_dir(Some(PathBuf::from("/work")));
+        repo.set_path_strategy(PathStrategy::HostOwnerName);
+
+        assert_eq!(repo.get_target_dir(), "/work/github.com/owner/repo");
+
+        // An explicit path still takes precedence over the strategy
+        repo.path = Some("custom".to_string());
+        assert_eq!(repo.get_target_dir(), "/work/custom");
+    }
+
     #[test]
     fn test_no_config_dir_fallback() {
         let current_dir = env::current_dir().unwrap();
@@ -154,6 +247,17 @@ mod tests {
             path: Some("journey".to_string()),
             branch: None,
             config_dir: None,
+            path_strategy: Default::default(),
+            default_branch: None,
+            sparse: Vec::new(),
+            provider: None,
+            provider_url: None,
+            provider_defaults: Default::default(),
+            git: Default::default(),
+            depends_on: Vec::new(),
+            post_clone: None,
+            archived: false,
+            remotes: Default::default(),
         };
 
         let target_dir = repo.get_target_dir();
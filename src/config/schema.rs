@@ -0,0 +1,1427 @@
+//! Strict schema validation of configuration files with source locations
+//!
+//! Unlike [`Config::load`](super::Config::load), which stops at the first serde
+//! error, this walks the parsed YAML document and reports every problem it finds
+//! with the line and column it was found at. A document the walk accepts is then
+//! deserialized like the loader does, so it is never reported valid when the
+//! loader would reject it.
+
+use super::secrets::SecretRef;
+use super::{Condition, Config, PathStrategy, Provider};
+use crate::utils::artifacts::is_contained_pattern;
+use regex::Regex;
+use std::collections::HashMap;
+use std::fmt;
+use yaml_rust2::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust2::scanner::Marker;
+
+const TOP_LEVEL_KEYS: &[&str] = &[
+    "repositories",
+    "recipes",
+    "artifacts_remote",
+    "log_sinks",
+    "path_strategy",
+    "root",
+    "files",
+    "branch_protection",
+    "repository_settings",
+    "post_clone",
+    "defaults",
+    "aliases",
+    "provider",
+    "provider_url",
+    "exit_codes",
+    "notify",
+    "profiles",
+    "git",
+    "secrets",
+    "pr",
+];
+const PROFILE_KEYS: &[&str] = &["root", "token", "tags", "repositories"];
+const REPOSITORY_KEYS: &[&str] = &[
+    "name",
+    "url",
+    "tags",
+    "path",
+    "branch",
+    "default_branch",
+    "sparse",
+    "provider",
+    "provider_url",
+    "remotes",
+    "git",
+    "depends_on",
+    "post_clone",
+];
+const RECIPE_KEYS: &[&str] = &["name", "steps", "artifacts", "cache", "env"];
+const STEP_KEYS: &[&str] = &["run", "if"];
+const FILE_KEYS: &[&str] = &["path", "contains", "tags", "template"];
+const BRANCH_PROTECTION_KEYS: &[&str] = &[
+    "branch",
+    "tags",
+    "required_approving_reviews",
+    "dismiss_stale_reviews",
+    "require_code_owner_reviews",
+    "required_status_checks",
+    "strict_status_checks",
+    "enforce_admins",
+    "required_linear_history",
+    "allow_force_pushes",
+    "allow_deletions",
+    "required_conversation_resolution",
+];
+const SETTINGS_KEYS: &[&str] = &[
+    "tags",
+    "labels",
+    "topics",
+    "default_branch",
+    "allow_merge_commit",
+    "allow_squash_merge",
+    "allow_rebase_merge",
+    "allow_auto_merge",
+    "delete_branch_on_merge",
+    "required_approving_reviews",
+    "webhooks",
+];
+const POST_CLONE_KEYS: &[&str] = &["recipe", "tags"];
+const LABEL_KEYS: &[&str] = &["name", "color", "description"];
+const WEBHOOK_KEYS: &[&str] = &["url", "events", "secret"];
+const GIT_KEYS: &[&str] = &["protocol", "rewrite", "https_token", "ssh_key"];
+const SECRETS_KEYS: &[&str] = &["sops", "age", "age_identity", "redact"];
+const PR_KEYS: &[&str] = &["template"];
+const NOTIFY_KEYS: &[&str] = &["slack_webhook", "webhooks", "on"];
+const NOTIFY_WEBHOOK_KEYS: &[&str] = &["url", "headers"];
+const NOTIFY_EVENTS: &[&str] = &["failure", "completion"];
+const ARTIFACTS_REMOTE_KEYS: &[&str] = &["type", "bucket", "prefix", "url", "headers"];
+const LOG_SINK_KEYS: &[&str] = &[
+    "type", "path", "bucket", "prefix", "endpoint", "url", "headers", "tag",
+];
+
+/// A problem found in a configuration file
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaIssue {
+    /// Line in the source file (1-based)
+    pub line: usize,
+    /// Column in the source file (1-based)
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for SchemaIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+/// Validate configuration file contents, returning all issues found
+pub fn validate_config_source(source: &str) -> Vec<SchemaIssue> {
+    let mut builder = NodeBuilder::default();
+    if let Err(e) = Parser::new_from_str(source).load(&mut builder, false) {
+        let marker = e.marker();
+        return vec![SchemaIssue {
+            line: marker.line(),
+            column: marker.col() + 1,
+            message: format!("invalid YAML: {}", e.info()),
+        }];
+    }
+
+    let mut checker = Checker::default();
+    match builder.root {
+        Some(root) => checker.check_root(&root),
+        None => checker.issue_at(1, 1, "configuration file is empty"),
+    }
+
+    let mut issues = checker.issues;
+    if issues.is_empty() {
+        issues.extend(deserialization_issue(source));
+    }
+    issues.sort_by_key(|issue| (issue.line, issue.column));
+    issues
+}
+
+/// The error the loader's deserialization stops at, for problems the walk does not cover
+fn deserialization_issue(source: &str) -> Option<SchemaIssue> {
+    let error = serde_yaml::from_str::<Config>(source).err()?;
+    let (line, column) = error
+        .location()
+        .map_or((1, 1), |location| (location.line(), location.column()));
+    // The location is reported separately, so drop serde's " at line .. column .." suffix
+    let message = error.to_string();
+    let message = match message.rfind(" at line ") {
+        Some(end) => message[..end].to_string(),
+        None => message,
+    };
+    Some(SchemaIssue {
+        line,
+        column,
+        message,
+    })
+}
+
+/// YAML node annotated with the position it starts at
+#[derive(Debug)]
+struct Node {
+    line: usize,
+    column: usize,
+    kind: NodeKind,
+}
+
+#[derive(Debug)]
+enum NodeKind {
+    Scalar(String),
+    Sequence(Vec<Node>),
+    Mapping(Vec<(Node, Node)>),
+}
+
+impl Node {
+    fn as_str(&self) -> Option<&str> {
+        match &self.kind {
+            NodeKind::Scalar(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<&Node> {
+        match &self.kind {
+            NodeKind::Mapping(entries) => entries
+                .iter()
+                .find(|(k, _)| k.as_str() == Some(key))
+                .map(|(_, v)| v),
+            _ => None,
+        }
+    }
+}
+
+/// Builds a [`Node`] tree from parser events
+#[derive(Default)]
+struct NodeBuilder {
+    stack: Vec<Node>,
+    root: Option<Node>,
+}
+
+impl NodeBuilder {
+    fn push_value(&mut self, node: Node) {
+        match self.stack.last_mut() {
+            Some(Node {
+                kind: NodeKind::Sequence(items),
+                ..
+            }) => items.push(node),
+            Some(Node {
+                kind: NodeKind::Mapping(entries),
+                ..
+            }) => {
+                // Keys and values arrive in turn; a placeholder key marks a pending value
+                match entries.last_mut() {
+                    Some((_, value)) if is_placeholder(value) => *value = node,
+                    _ => entries.push((node, placeholder())),
+                }
+            }
+            _ => self.root = Some(node),
+        }
+    }
+}
+
+fn placeholder() -> Node {
+    Node {
+        line: 0,
+        column: 0,
+        kind: NodeKind::Sequence(Vec::new()),
+    }
+}
+
+fn is_placeholder(node: &Node) -> bool {
+    node.line == 0
+}
+
+impl MarkedEventReceiver for NodeBuilder {
+    fn on_event(&mut self, event: Event, marker: Marker) {
+        let (line, column) = (marker.line(), marker.col() + 1);
+        match event {
+            Event::Scalar(value, ..) => self.push_value(Node {
+                line,
+                column,
+                kind: NodeKind::Scalar(value),
+            }),
+            Event::SequenceStart(..) => self.stack.push(Node {
+                line,
+                column,
+                kind: NodeKind::Sequence(Vec::new()),
+            }),
+            Event::MappingStart(..) => self.stack.push(Node {
+                line,
+                column,
+                kind: NodeKind::Mapping(Vec::new()),
+            }),
+            Event::SequenceEnd | Event::MappingEnd => {
+                if let Some(mut node) = self.stack.pop() {
+                    // Block mappings are reported where their first key starts
+                    if let NodeKind::Mapping(entries) = &node.kind
+                        && let Some((key, _)) = entries.first()
+                    {
+                        (node.line, node.column) = (key.line, key.column);
+                    }
+                    self.push_value(node);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[derive(Default)]
+struct Checker {
+    issues: Vec<SchemaIssue>,
+}
+
+impl Checker {
+    fn issue_at(&mut self, line: usize, column: usize, message: impl Into<String>) {
+        self.issues.push(SchemaIssue {
+            line,
+            column,
+            message: message.into(),
+        });
+    }
+
+    fn issue(&mut self, node: &Node, message: impl Into<String>) {
+        self.issue_at(node.line, node.column, message);
+    }
+
+    /// Check that a node is a mapping with only known keys, returning its entries
+    fn mapping<'a>(
+        &mut self,
+        node: &'a Node,
+        what: &str,
+        allowed: &[&str],
+    ) -> Option<&'a [(Node, Node)]> {
+        let NodeKind::Mapping(entries) = &node.kind else {
+            self.issue(node, format!("{} must be a mapping", what));
+            return None;
+        };
+
+        let mut seen = HashMap::new();
+        for (key, _) in entries {
+            let Some(name) = key.as_str() else {
+                self.issue(key, format!("{} keys must be strings", what));
+                continue;
+            };
+            if !allowed.contains(&name) {
+                self.issue(
+                    key,
+                    format!(
+                        "unknown key '{}' in {} (expected one of: {})",
+                        name,
+                        what,
+                        allowed.join(", ")
+                    ),
+                );
+            }
+            if let Some(first_line) = seen.insert(name, key.line) {
+                self.issue(
+                    key,
+                    format!(
+                        "duplicate key '{}' in {} (first defined on line {})",
+                        name, what, first_line
+                    ),
+                );
+            }
+        }
+        Some(entries)
+    }
+
+    fn sequence<'a>(&mut self, node: &'a Node, what: &str) -> Option<&'a [Node]> {
+        match &node.kind {
+            NodeKind::Sequence(items) => Some(items),
+            _ => {
+                self.issue(node, format!("{} must be a list", what));
+                None
+            }
+        }
+    }
+
+    fn required_string<'a>(&mut self, parent: &'a Node, key: &str, what: &str) -> Option<&'a str> {
+        match parent.get(key) {
+            None => {
+                self.issue(
+                    parent,
+                    format!("{} is missing required key '{}'", what, key),
+                );
+                None
+            }
+            Some(node) => match node.as_str() {
+                Some(value) if !value.trim().is_empty() => Some(value),
+                Some(_) => {
+                    self.issue(node, format!("'{}' in {} cannot be empty", key, what));
+                    None
+                }
+                None => {
+                    self.issue(node, format!("'{}' in {} must be a string", key, what));
+                    None
+                }
+            },
+        }
+    }
+
+    fn check_root(&mut self, root: &Node) {
+        if self
+            .mapping(root, "configuration", TOP_LEVEL_KEYS)
+            .is_none()
+        {
+            return;
+        }
+
+        let strategy = match root.get("path_strategy") {
+            Some(node) => match node.as_str() {
+                Some(value) => PathStrategy::from(value.to_string()),
+                None => {
+                    self.issue(node, "'path_strategy' must be a string");
+                    PathStrategy::default()
+                }
+            },
+            None => PathStrategy::default(),
+        };
+
+        self.check_provider(root);
+        self.check_git(root);
+        match root.get("repositories") {
+            Some(repositories) => self.check_repositories(repositories, &strategy),
+            None => self.issue(root, "configuration is missing required key 'repositories'"),
+        }
+        if let Some(recipes) = root.get("recipes") {
+            self.check_recipes(recipes);
+        }
+        if let Some(files) = root.get("files") {
+            self.check_files(files);
+        }
+        if let Some(policies) = root.get("branch_protection") {
+            for policy in self
+                .sequence(policies, "'branch_protection'")
+                .unwrap_or_default()
+            {
+                self.mapping(policy, "branch protection policy", BRANCH_PROTECTION_KEYS);
+            }
+        }
+        if let Some(specs) = root.get("repository_settings") {
+            self.check_repository_settings(specs);
+        }
+        if let Some(rules) = root.get("post_clone") {
+            for rule in self.sequence(rules, "'post_clone'").unwrap_or_default() {
+                if self
+                    .mapping(rule, "post-clone rule", POST_CLONE_KEYS)
+                    .is_some()
+                {
+                    self.required_string(rule, "recipe", "post-clone rule");
+                }
+            }
+        }
+        if let Some(defaults) = root.get("defaults")
+            && !matches!(defaults.kind, NodeKind::Mapping(_))
+        {
+            self.issue(
+                defaults,
+                "'defaults' must be a mapping of options or commands",
+            );
+        }
+        if let Some(aliases) = root.get("aliases") {
+            match &aliases.kind {
+                NodeKind::Mapping(entries) => {
+                    for (name, definition) in entries {
+                        if definition
+                            .as_str()
+                            .is_none_or(|value| value.trim().is_empty())
+                        {
+                            self.issue(
+                                definition,
+                                format!(
+                                    "alias '{}' must be a repos command line",
+                                    name.as_str().unwrap_or_default()
+                                ),
+                            );
+                        }
+                    }
+                }
+                _ => self.issue(aliases, "'aliases' must be a mapping of names to commands"),
+            }
+        }
+        if let Some(exit_codes) = root.get("exit_codes") {
+            self.check_exit_codes(exit_codes);
+        }
+        if let Some(profiles) = root.get("profiles") {
+            self.check_profiles(profiles, &strategy);
+        }
+        if let Some(remote) = root.get("artifacts_remote") {
+            self.mapping(remote, "artifacts_remote", ARTIFACTS_REMOTE_KEYS);
+        }
+        if let Some(sinks) = root.get("log_sinks") {
+            for sink in self.sequence(sinks, "'log_sinks'").unwrap_or_default() {
+                self.mapping(sink, "log sink", LOG_SINK_KEYS);
+            }
+        }
+        if let Some(notify) = root.get("notify") {
+            self.check_notify(notify);
+        }
+        if let Some(pr) = root.get("pr") {
+            self.mapping(pr, "'pr'", PR_KEYS);
+        }
+        if let Some(secrets) = root.get("secrets")
+            && self.mapping(secrets, "'secrets'", SECRETS_KEYS).is_some()
+            && let Some(patterns) = secrets.get("redact")
+        {
+            for pattern in self.sequence(patterns, "'redact'").unwrap_or_default() {
+                match pattern.as_str() {
+                    Some(source) => {
+                        if let Err(e) = Regex::new(source) {
+                            self.issue(pattern, format!("invalid redaction pattern: {}", e));
+                        }
+                    }
+                    None => self.issue(pattern, "'redact' entries must be strings"),
+                }
+            }
+        }
+    }
+
+    fn check_repositories(&mut self, node: &Node, strategy: &PathStrategy) {
+        let Some(items) = self.sequence(node, "'repositories'") else {
+            return;
+        };
+
+        let mut names: HashMap<String, usize> = HashMap::new();
+        let mut paths: HashMap<String, (String, usize)> = HashMap::new();
+
+        for item in items {
+            if self.mapping(item, "repository", REPOSITORY_KEYS).is_none() {
+                continue;
+            }
+
+            let name = self.required_string(item, "name", "repository");
+            if let Some(url) = self.required_string(item, "url", "repository")
+                && !is_well_formed_url(url)
+                && let Some(url_node) = item.get("url")
+            {
+                self.issue(url_node, format!("malformed repository URL '{}'", url));
+            }
+
+            if let Some(tags) = item.get("tags")
+                && let Some(tags) = self.sequence(tags, "'tags'")
+            {
+                for tag in tags {
+                    match tag.as_str() {
+                        Some(value) if is_valid_tag(value) => {}
+                        Some(value) => self.issue(
+                            tag,
+                            format!(
+                                "invalid tag '{}' (use letters, digits, '.', '_', '-', or '/')",
+                                value
+                            ),
+                        ),
+                        None => self.issue(tag, "tags must be strings"),
+                    }
+                }
+            }
+
+            if let Some(sparse) = item.get("sparse")
+                && let Some(directories) = self.sequence(sparse, "'sparse'")
+            {
+                for directory in directories {
+                    match directory.as_str() {
+                        Some(value) if is_relative_path(value) => {}
+                        Some(value) => self.issue(
+                            directory,
+                            format!(
+                                "invalid sparse directory '{}' (use a path relative to the repository root)",
+                                value
+                            ),
+                        ),
+                        None => self.issue(directory, "sparse directories must be strings"),
+                    }
+                }
+            }
+
+            if let Some(remotes) = item.get("remotes") {
+                match &remotes.kind {
+                    NodeKind::Mapping(entries) => {
+                        for (remote, url) in entries {
+                            match url.as_str() {
+                                Some(value) if is_well_formed_url(value) => {}
+                                Some(value) => {
+                                    self.issue(url, format!("malformed remote URL '{}'", value))
+                                }
+                                None => self.issue(remote, "remote URLs must be strings"),
+                            }
+                        }
+                    }
+                    _ => self.issue(
+                        remotes,
+                        "'remotes' must be a mapping of remote names to URLs",
+                    ),
+                }
+            }
+
+            self.check_provider(item);
+            self.check_git(item);
+            if let Some(recipe) = item.get("post_clone")
+                && recipe.as_str().is_none()
+            {
+                self.issue(recipe, "'post_clone' must be the name of a recipe");
+            }
+
+            let Some(name) = name else {
+                continue;
+            };
+
+            // Dependencies may live in another config file, so only their form is checked here
+            if let Some(depends_on) = item.get("depends_on")
+                && let Some(dependencies) = self.sequence(depends_on, "'depends_on'")
+            {
+                for dependency in dependencies {
+                    match dependency.as_str() {
+                        Some(value) if value == name => self.issue(
+                            dependency,
+                            format!("repository '{}' depends on itself", name),
+                        ),
+                        Some(_) => {}
+                        None => self.issue(dependency, "'depends_on' must list repository names"),
+                    }
+                }
+            }
+            if let Some(first_line) = names.insert(name.to_string(), item.line) {
+                self.issue(
+                    item.get("name").unwrap_or(item),
+                    format!(
+                        "duplicate repository name '{}' (first defined on line {})",
+                        name, first_line
+                    ),
+                );
+            }
+
+            // Repositories without a path are placed according to the path strategy
+            let path_node = item.get("path");
+            let url = item.get("url").and_then(|u| u.as_str()).unwrap_or_default();
+            let path = match path_node.and_then(|p| p.as_str()) {
+                Some(path) => path.to_string(),
+                None => strategy.relative_dir(name, url),
+            };
+            let normalized = normalize_path(&path);
+            if let Some((other, first_line)) = paths.get(&normalized) {
+                self.issue(
+                    path_node.unwrap_or(item),
+                    format!(
+                        "repository '{}' resolves to the same path '{}' as '{}' (line {})",
+                        name, path, other, first_line
+                    ),
+                );
+            } else {
+                paths.insert(normalized, (name.to_string(), item.line));
+            }
+        }
+    }
+
+    fn check_provider(&mut self, item: &Node) {
+        if let Some(node) = item.get("provider") {
+            match node.as_str() {
+                Some(value) if Provider::NAMES.contains(&value) => {}
+                Some(value) => self.issue(
+                    node,
+                    format!(
+                        "unknown provider '{}' (expected one of: {})",
+                        value,
+                        Provider::NAMES.join(", ")
+                    ),
+                ),
+                None => self.issue(node, "'provider' must be a string"),
+            }
+        }
+        if let Some(node) = item.get("provider_url") {
+            match node.as_str() {
+                Some(value) if is_instance_url(value) => {}
+                _ => self.issue(
+                    node,
+                    "'provider_url' must be an http(s) URL such as https://git.example.com",
+                ),
+            }
+        }
+    }
+
+    /// A recipe step is a command, or a mapping with `run` and an `if` condition
+    fn check_step(&mut self, step: &Node) {
+        if step.as_str().is_some() {
+            return;
+        }
+        if !matches!(step.kind, NodeKind::Mapping(_)) {
+            self.issue(step, "recipe steps must be commands or mappings with 'run'");
+            return;
+        }
+        if self.mapping(step, "recipe step", STEP_KEYS).is_none() {
+            return;
+        }
+        self.required_string(step, "run", "recipe step");
+        if let Some(condition) = step.get("if") {
+            match condition.as_str() {
+                Some(source) => {
+                    if let Err(e) = source.parse::<Condition>() {
+                        self.issue(condition, e.to_string());
+                    }
+                }
+                None => self.issue(condition, "'if' in recipe step must be a string"),
+            }
+        }
+    }
+
+    fn check_git(&mut self, item: &Node) {
+        let Some(node) = item.get("git") else {
+            return;
+        };
+        if self.mapping(node, "'git'", GIT_KEYS).is_none() {
+            return;
+        }
+        if let Some(protocol) = node.get("protocol") {
+            match protocol.as_str() {
+                Some("ssh" | "https") => {}
+                _ => self.issue(protocol, "'protocol' must be 'ssh' or 'https'"),
+            }
+        }
+        if let Some(rewrite) = node.get("rewrite")
+            && !matches!(rewrite.kind, NodeKind::Mapping(_))
+        {
+            self.issue(
+                rewrite,
+                "'rewrite' must map URL prefixes to their replacements",
+            );
+        }
+    }
+
+    fn check_profiles(&mut self, node: &Node, strategy: &PathStrategy) {
+        let NodeKind::Mapping(entries) = &node.kind else {
+            self.issue(node, "'profiles' must be a mapping");
+            return;
+        };
+        for (_, profile) in entries {
+            if self.mapping(profile, "profile", PROFILE_KEYS).is_none() {
+                continue;
+            }
+            if let Some(repositories) = profile.get("repositories") {
+                self.check_repositories(repositories, strategy);
+            }
+        }
+    }
+
+    fn check_exit_codes(&mut self, node: &Node) {
+        let NodeKind::Mapping(entries) = &node.kind else {
+            self.issue(node, "'exit_codes' must be a mapping");
+            return;
+        };
+        for (code, description) in entries {
+            if code.as_str().and_then(|c| c.parse::<i32>().ok()).is_none() {
+                self.issue(code, "'exit_codes' keys must be integer exit codes");
+            }
+            if description.as_str().is_none() {
+                self.issue(description, "exit code descriptions must be strings");
+            }
+        }
+    }
+
+    fn check_files(&mut self, node: &Node) {
+        let Some(items) = self.sequence(node, "'files'") else {
+            return;
+        };
+
+        for item in items {
+            if self.mapping(item, "file requirement", FILE_KEYS).is_none() {
+                continue;
+            }
+            if let Some(path) = self.required_string(item, "path", "file requirement")
+                && !is_relative_path(path)
+                && let Some(path_node) = item.get("path")
+            {
+                self.issue(
+                    path_node,
+                    format!(
+                        "invalid file path '{}' (use a path relative to the repository root)",
+                        path
+                    ),
+                );
+            }
+            for key in ["contains", "tags"] {
+                if let Some(values) = item.get(key)
+                    && let Some(values) = self.sequence(values, &format!("'{}'", key))
+                {
+                    for value in values.iter().filter(|v| v.as_str().is_none()) {
+                        self.issue(value, format!("'{}' entries must be strings", key));
+                    }
+                }
+            }
+        }
+    }
+
+    fn check_repository_settings(&mut self, node: &Node) {
+        let Some(specs) = self.sequence(node, "'repository_settings'") else {
+            return;
+        };
+
+        for spec in specs {
+            if self
+                .mapping(spec, "repository settings", SETTINGS_KEYS)
+                .is_none()
+            {
+                continue;
+            }
+            if let Some(labels) = spec.get("labels") {
+                for label in self.sequence(labels, "'labels'").unwrap_or_default() {
+                    if self.mapping(label, "label", LABEL_KEYS).is_some() {
+                        self.required_string(label, "name", "label");
+                    }
+                }
+            }
+            if let Some(webhooks) = spec.get("webhooks") {
+                for webhook in self.sequence(webhooks, "'webhooks'").unwrap_or_default() {
+                    if self.mapping(webhook, "webhook", WEBHOOK_KEYS).is_some() {
+                        self.required_string(webhook, "url", "webhook");
+                    }
+                }
+            }
+        }
+    }
+
+    fn check_notify(&mut self, node: &Node) {
+        if self.mapping(node, "'notify'", NOTIFY_KEYS).is_none() {
+            return;
+        }
+        if let Some(url) = node.get("slack_webhook")
+            && url.as_str().is_none()
+        {
+            self.issue(url, "'slack_webhook' must be a string");
+        }
+        if let Some(webhooks) = node.get("webhooks") {
+            for webhook in self.sequence(webhooks, "'webhooks'").unwrap_or_default() {
+                if self
+                    .mapping(webhook, "notification webhook", NOTIFY_WEBHOOK_KEYS)
+                    .is_some()
+                {
+                    self.required_string(webhook, "url", "notification webhook");
+                }
+            }
+        }
+        if let Some(events) = node.get("on") {
+            for event in self.sequence(events, "'on'").unwrap_or_default() {
+                match event.as_str() {
+                    Some(value) if NOTIFY_EVENTS.contains(&value) => {}
+                    _ => self.issue(
+                        event,
+                        format!(
+                            "notification events must be one of: {}",
+                            NOTIFY_EVENTS.join(", ")
+                        ),
+                    ),
+                }
+            }
+        }
+    }
+
+    fn check_recipes(&mut self, node: &Node) {
+        let Some(items) = self.sequence(node, "'recipes'") else {
+            return;
+        };
+
+        let mut names: HashMap<String, usize> = HashMap::new();
+        for item in items {
+            if self.mapping(item, "recipe", RECIPE_KEYS).is_none() {
+                continue;
+            }
+            if let Some(name) = self.required_string(item, "name", "recipe")
+                && let Some(first_line) = names.insert(name.to_string(), item.line)
+            {
+                self.issue(
+                    item.get("name").unwrap_or(item),
+                    format!(
+                        "duplicate recipe name '{}' (first defined on line {})",
+                        name, first_line
+                    ),
+                );
+            }
+            match item.get("steps") {
+                None => self.issue(item, "recipe is missing required key 'steps'"),
+                Some(steps) if steps.as_str().is_none() => {
+                    for step in self.sequence(steps, "'steps'").unwrap_or_default() {
+                        self.check_step(step);
+                    }
+                }
+                Some(_) => {}
+            }
+            if let Some(env) = item.get("env") {
+                self.check_env(env);
+            }
+            if let Some(artifacts) = item.get("artifacts") {
+                for pattern in self.sequence(artifacts, "'artifacts'").unwrap_or_default() {
+                    match pattern.as_str() {
+                        Some(value) if is_contained_pattern(value) => {}
+                        Some(value) => self.issue(
+                            pattern,
+                            format!(
+                                "invalid artifact pattern '{}' (use a path relative to the repository root)",
+                                value
+                            ),
+                        ),
+                        None => self.issue(pattern, "'artifacts' entries must be strings"),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Recipe variables map names to values or secret references
+    fn check_env(&mut self, node: &Node) {
+        let NodeKind::Mapping(entries) = &node.kind else {
+            self.issue(node, "'env' must be a mapping");
+            return;
+        };
+        for (_, value) in entries {
+            match value.as_str() {
+                Some(source) => {
+                    if let Err(e) = SecretRef::parse(source) {
+                        self.issue(value, e.to_string());
+                    }
+                }
+                None => self.issue(value, "'env' values must be strings"),
+            }
+        }
+    }
+}
+
+/// Check that a repository URL is a well-formed SSH or HTTP(S) git URL
+fn is_well_formed_url(url: &str) -> bool {
+    if let Some(rest) = url.strip_prefix("git@") {
+        return match rest.split_once(':') {
+            Some((host, path)) => {
+                !host.is_empty() && !path.is_empty() && !url.chars().any(char::is_whitespace)
+            }
+            None => false,
+        };
+    }
+    match reqwest::Url::parse(url) {
+        Ok(parsed) => {
+            matches!(parsed.scheme(), "https" | "http" | "ssh")
+                && parsed.host_str().is_some_and(|h| !h.is_empty())
+                && parsed.path().trim_matches('/').contains('/')
+        }
+        Err(_) => false,
+    }
+}
+
+/// Check that a provider instance URL is an absolute HTTP(S) URL
+fn is_instance_url(url: &str) -> bool {
+    reqwest::Url::parse(url).is_ok_and(|parsed| {
+        matches!(parsed.scheme(), "https" | "http") && parsed.host_str().is_some()
+    })
+}
+
+fn is_valid_tag(tag: &str) -> bool {
+    let pattern = Regex::new(r"^[A-Za-z0-9][A-Za-z0-9._/-]*$").unwrap();
+    pattern.is_match(tag)
+}
+
+/// Sparse directories and required files are relative to the repository root and stay inside it
+fn is_relative_path(path: &str) -> bool {
+    let path = path.trim();
+    !path.is_empty()
+        && !path.starts_with('/')
+        && !path.split('/').any(|component| component == "..")
+}
+
+fn normalize_path(path: &str) -> String {
+    let trimmed = path.trim().trim_end_matches('/');
+    trimmed.strip_prefix("./").unwrap_or(trimmed).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(source: &str) -> Vec<String> {
+        validate_config_source(source)
+            .into_iter()
+            .map(|i| i.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_valid_config() {
+        let source = "\
+repositories:
+  - name: api
+    url: git@github.com:org/api.git
+    tags: [backend, rust]
+  - name: web
+    url: https://github.com/org/web.git
+    tags: [frontend]
+    path: apps/web
+recipes:
+  - name: test
+    steps:
+      - cargo test
+";
+        assert!(validate_config_source(source).is_empty());
+    }
+
+    #[test]
+    fn test_unknown_keys_with_locations() {
+        let source = "\
+repositories:
+  - name: api
+    url: git@github.com:org/api.git
+    tgas: [backend]
+extra: true
+";
+        let issues = messages(source);
+        assert_eq!(issues.len(), 2);
+        assert!(issues[0].starts_with("4:5: unknown key 'tgas' in repository"));
+        assert!(issues[1].starts_with("5:1: unknown key 'extra' in configuration"));
+    }
+
+    #[test]
+    fn test_duplicate_names_and_conflicting_paths() {
+        let source = "\
+repositories:
+  - name: api
+    url: git@github.com:org/api.git
+  - name: api
+    url: git@github.com:other/api.git
+    path: ./elsewhere
+  - name: web
+    url: git@github.com:org/web.git
+    path: api/
+";
+        let issues = messages(source);
+        assert_eq!(
+            issues,
+            vec![
+                "4:11: duplicate repository name 'api' (first defined on line 2)",
+                "9:11: repository 'web' resolves to the same path 'api/' as 'api' (line 2)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_conflicting_paths_use_path_strategy() {
+        let source = "\
+path_strategy: \"{repo}\"
+repositories:
+  - name: api-v1
+    url: git@github.com:org/api.git
+  - name: api-v2
+    url: git@github.com:other/api.git
+";
+        let issues = messages(source);
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].contains("resolves to the same path 'api'"));
+    }
+
+    #[test]
+    fn test_malformed_urls_and_invalid_tags() {
+        let source = "\
+repositories:
+  - name: api
+    url: github.com/org/api
+    tags: [ok, 'has space']
+  - name: web
+    url: https://github.com
+";
+        let issues = messages(source);
+        assert_eq!(issues.len(), 3);
+        assert!(issues[0].starts_with("3:10: malformed repository URL"));
+        assert!(issues[1].starts_with("4:16: invalid tag 'has space'"));
+        assert!(issues[2].starts_with("6:10: malformed repository URL"));
+    }
+
+    #[test]
+    fn test_invalid_sparse_directories() {
+        let source = "\
+repositories:
+  - name: api
+    url: https://github.com/org/api.git
+    sparse: [services/api, /etc, ../web]
+  - name: web
+    url: https://github.com/org/web.git
+    sparse: docs
+";
+        let issues = messages(source);
+        assert_eq!(issues.len(), 3);
+        assert!(issues[0].starts_with("4:28: invalid sparse directory '/etc'"));
+        assert!(issues[1].starts_with("4:34: invalid sparse directory '../web'"));
+        assert!(issues[2].starts_with("7:13: 'sparse' must be a list"));
+    }
+
+    #[test]
+    fn test_file_requirements() {
+        let source = "\
+repositories:
+  - name: api
+    url: https://github.com/org/api.git
+files:
+  - path: SECURITY.md
+    contains: [Reporting a Vulnerability]
+    template: templates/SECURITY.md
+  - path: ../outside
+  - contains: [x]
+    mode: strict
+";
+        let issues = messages(source);
+        assert_eq!(issues.len(), 3);
+        assert!(issues[0].starts_with("8:11: invalid file path '../outside'"));
+        assert!(issues[1].starts_with("9:5: file requirement is missing required key 'path'"));
+        assert!(issues[2].starts_with("10:5: unknown key 'mode' in file requirement"));
+    }
+
+    #[test]
+    fn test_branch_protection() {
+        let source = "\
+repositories:
+  - name: api
+    url: https://github.com/org/api.git
+branch_protection:
+  - required_approving_reviews: 2
+    required_status_checks: [ci/build]
+  - branch: release
+    require_signatures: true
+";
+        let issues = messages(source);
+        assert_eq!(issues.len(), 1);
+        assert!(
+            issues[0]
+                .starts_with("8:5: unknown key 'require_signatures' in branch protection policy")
+        );
+    }
+
+    #[test]
+    fn test_repository_settings() {
+        let source = "\
+repositories:
+  - name: api
+    url: https://github.com/org/api.git
+repository_settings:
+  - topics: [backend]
+    labels:
+      - name: security
+        color: d73a4a
+      - color: ffffff
+    webhooks:
+      - url: https://hooks.example.com/github
+        filter: push
+";
+        let issues = messages(source);
+        assert_eq!(issues.len(), 2);
+        assert!(issues[0].starts_with("9:9: label is missing required key 'name'"));
+        assert!(issues[1].starts_with("12:9: unknown key 'filter' in webhook"));
+    }
+
+    #[test]
+    fn test_post_clone() {
+        let source = "\
+repositories:
+  - name: api
+    url: https://github.com/org/api.git
+    post_clone: [setup]
+post_clone:
+  - recipe: npm-setup
+    tags: [frontend]
+  - tags: [backend]
+";
+        let issues = messages(source);
+        assert_eq!(issues.len(), 2);
+        assert!(issues[0].starts_with("4:17: 'post_clone' must be the name of a recipe"));
+        assert!(issues[1].starts_with("8:5: post-clone rule is missing required key 'recipe'"));
+    }
+
+    #[test]
+    fn test_aliases() {
+        let source = "\
+repositories: []
+aliases:
+  weekly-update: run --recipe update-deps -t backend
+  broken: [run, ls]
+";
+        let issues = messages(source);
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].starts_with("4:11: alias 'broken' must be a repos command line"));
+    }
+
+    #[test]
+    fn test_providers() {
+        let source = "\
+provider: gitea
+provider_url: https://git.example.com
+repositories:
+  - name: api
+    url: git@git.example.com:org/api.git
+  - name: web
+    url: https://codeberg.org/org/web.git
+    provider: forgejo
+    provider_url: codeberg.org
+  - name: ops
+    url: https://gitlab.com/org/ops.git
+    provider: gitlab
+";
+        let issues = messages(source);
+        assert_eq!(issues.len(), 2);
+        assert!(issues[0].starts_with("9:19: 'provider_url' must be an http(s) URL"));
+        assert!(issues[1].starts_with("12:15: unknown provider 'gitlab'"));
+    }
+
+    #[test]
+    fn test_git_transport() {
+        let source = "\
+git:
+  protocol: ssh
+  rewrite:
+    \"https://github.com/\": \"git@github.com:\"
+repositories:
+  - name: api
+    url: https://github.com/org/api.git
+    git:
+      protocol: git
+      ssh_key: ~/.ssh/id_api
+      token: true
+";
+        let issues = messages(source);
+        assert_eq!(issues.len(), 2);
+        assert!(issues[0].starts_with("9:17: 'protocol' must be 'ssh' or 'https'"));
+        assert!(issues[1].starts_with("11:7: unknown key 'token' in 'git'"));
+    }
+
+    #[test]
+    fn test_remotes() {
+        let source = "\
+repositories:
+  - name: api
+    url: https://github.com/org/api.git
+    remotes:
+      origin: git@github.com:me/api.git
+      mirror: not a url
+  - name: web
+    url: https://github.com/org/web.git
+    remotes: [origin]
+";
+        let issues = messages(source);
+        assert_eq!(issues.len(), 2);
+        assert!(issues[0].starts_with("6:15: malformed remote URL 'not a url'"));
+        assert!(issues[1].starts_with("9:14: 'remotes' must be a mapping"));
+    }
+
+    #[test]
+    fn test_depends_on() {
+        let source = "\
+repositories:
+  - name: api
+    url: https://github.com/org/api.git
+    depends_on: [core, api]
+  - name: web
+    url: https://github.com/org/web.git
+    depends_on: api
+";
+        let issues = messages(source);
+        assert_eq!(issues.len(), 2);
+        assert!(issues[0].starts_with("4:24: repository 'api' depends on itself"));
+        assert!(issues[1].starts_with("7:17: 'depends_on' must be a list"));
+    }
+
+    #[test]
+    fn test_profiles() {
+        let source = "\
+repositories: []
+profiles:
+  work:
+    root: ~/work
+    tags: [work]
+    token: work-github
+  oss:
+    repositories:
+      - name: dotfiles
+    branch: main
+";
+        let issues = messages(source);
+        assert_eq!(issues.len(), 2);
+        assert!(issues[0].starts_with("9:9: repository is missing required key 'url'"));
+        assert!(issues[1].starts_with("10:5: unknown key 'branch' in profile"));
+    }
+
+    #[test]
+    fn test_exit_codes() {
+        let source = "\
+repositories: []
+exit_codes:
+  42: database migration pending
+  lint: failed
+  3: [a]
+";
+        let issues = messages(source);
+        assert_eq!(issues.len(), 2);
+        assert!(issues[0].starts_with("4:3: 'exit_codes' keys must be integer exit codes"));
+        assert!(issues[1].starts_with("5:6: exit code descriptions must be strings"));
+    }
+
+    #[test]
+    fn test_conditional_steps() {
+        let source = "\
+repositories:
+  - name: web
+    url: https://github.com/org/web.git
+recipes:
+  - name: install
+    steps:
+      - run: npm ci
+        if: file_exists('package.json') && os != 'windows'
+      - run: bundle install
+        if: tag = 'ruby'
+      - when: always
+      - [make]
+";
+        let issues = messages(source);
+        assert_eq!(issues.len(), 4);
+        assert!(issues[0].starts_with("10:13: Invalid condition 'tag = 'ruby''"));
+        assert!(issues[1].starts_with("11:9: unknown key 'when' in recipe step"));
+        assert!(issues[2].starts_with("11:9: recipe step is missing required key 'run'"));
+        assert!(issues[3].starts_with("12:9: recipe steps must be commands"));
+    }
+
+    #[test]
+    fn test_pr_section() {
+        let source = "\
+repositories: []
+pr:
+  template: .github/pr.md
+  summary: true
+";
+        let issues = messages(source);
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].starts_with("4:3: unknown key 'summary' in 'pr'"));
+    }
+
+    #[test]
+    fn test_notify() {
+        let source = "\
+repositories: []
+notify:
+  slack_webhook: ${SLACK_WEBHOOK}
+  webhooks:
+    - url: https://hooks.example.com/repos
+      headers:
+        Authorization: Bearer ${HOOK_TOKEN}
+  on: [failure, completion]
+";
+        assert!(validate_config_source(source).is_empty());
+
+        let source = "\
+repositories: []
+notify:
+  webhooks:
+    - headers: {}
+  on: [failure, always]
+  email: ops@example.com
+";
+        let issues = messages(source);
+        assert_eq!(issues.len(), 3);
+        assert!(issues[0].starts_with("4:7: notification webhook is missing required key 'url'"));
+        assert!(issues[1].starts_with("5:17: notification events must be one of"));
+        assert!(issues[2].starts_with("6:3: unknown key 'email' in 'notify'"));
+    }
+
+    #[test]
+    fn test_recipe_env_and_secrets() {
+        let source = "\
+repositories: []
+secrets:
+  sops: secrets.enc.yaml
+  vault: https://vault.example.com
+  redact: ['ACME-\\d+', '(']
+recipes:
+  - name: publish
+    env:
+      MODE: release
+      NPM_TOKEN: secret://sops/npm/token
+      REGISTRY: secret://vault/registry
+      GH_TOKEN: env://
+      PORTS: [1, 2]
+    steps: [npm publish]
+";
+        let issues = messages(source);
+        assert_eq!(issues.len(), 5);
+        assert!(issues[0].starts_with("4:3: unknown key 'vault' in 'secrets'"));
+        assert!(issues[1].starts_with("5:24: invalid redaction pattern"));
+        assert!(issues[2].starts_with("11:17: Invalid secret reference 'secret://vault/registry'"));
+        assert!(issues[3].starts_with("12:17: Invalid secret reference 'env://'"));
+        assert!(issues[4].starts_with("13:14: 'env' values must be strings"));
+    }
+
+    #[test]
+    fn test_missing_required_keys() {
+        let source = "\
+repositories:
+  - name: api
+recipes:
+  - name: build
+";
+        let issues = messages(source);
+        assert_eq!(
+            issues,
+            vec![
+                "2:5: repository is missing required key 'url'",
+                "4:5: recipe is missing required key 'steps'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rejected_by_loader_is_never_valid() {
+        let source = "\
+repositories:
+  - name: api
+    url: git@github.com:org/api.git
+";
+        assert!(serde_yaml::from_str::<Config>(source).is_err());
+        assert_eq!(
+            messages(source),
+            vec!["2:5: repositories[0]: missing field `tags`"]
+        );
+
+        let source = "\
+repositories: []
+recipes:
+  - name: build
+    steps: [make]
+    cache: sometimes
+    artifacts: [/tmp/out.log]
+";
+        let issues = messages(source);
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].starts_with("6:17: invalid artifact pattern '/tmp/out.log'"));
+
+        let source = "\
+repositories: []
+recipes:
+  - name: build
+    steps: [make]
+    cache: sometimes
+";
+        let issues = messages(source);
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].starts_with("5:12: recipes[0].cache: invalid type"));
+    }
+
+    #[test]
+    fn test_yaml_syntax_error() {
+        let issues = validate_config_source("repositories:\n  - name: [unclosed\n");
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].message.starts_with("invalid YAML"));
+        assert!(issues[0].line >= 2);
+    }
+
+    #[test]
+    fn test_is_well_formed_url() {
+        assert!(is_well_formed_url("git@github.com:org/repo.git"));
+        assert!(is_well_formed_url("https://github.com/org/repo.git"));
+        assert!(is_well_formed_url(
+            "ssh://git@host.example.com/org/repo.git"
+        ));
+        assert!(!is_well_formed_url("git@github.com"));
+        assert!(!is_well_formed_url("https://github.com/org"));
+        assert!(!is_well_formed_url("ftp://github.com/org/repo"));
+    }
+}
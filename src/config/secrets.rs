@@ -0,0 +1,324 @@
+//! Secrets referenced from the config and resolved when recipes run
+//!
+//! Values in a recipe's `env` are used as they are, unless they reference a
+//! secret:
+//!
+//! ```text
+//! env://GITHUB_TOKEN              environment variable of the repos process
+//! keychain://npm-token            OS keychain entry saved under the `repos-secrets` service
+//! secret://sops/registry/password key in the `secrets.sops` file, decrypted with `sops`
+//! secret://age/registry/password  key in the `secrets.age` file, decrypted with `age`
+//! ```
+//!
+//! The path after `secret://sops/` or `secret://age/` walks the nested
+//! mappings of the decrypted YAML or JSON document. Secrets are resolved once
+//! per run and only handed to the recipe's environment; wherever their values
+//! show up in captured output, a [`Redactor`](crate::utils::Redactor) masks
+//! them before it is logged or saved.
+
+use super::expand_path;
+use crate::credentials::keychain;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Encrypted files holding the values of `secret://` references
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SecretsConfig {
+    /// File encrypted with sops, relative to the config file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sops: Option<String>,
+    /// File encrypted with age, relative to the config file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub age: Option<String>,
+    /// age identity file that decrypts `age`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub age_identity: Option<String>,
+    /// Regular expressions masked in run output, on top of the built-in token formats
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redact: Vec<String>,
+    /// Directory of the config file, for resolving the paths above
+    #[serde(skip)]
+    pub config_dir: Option<PathBuf>,
+}
+
+impl SecretsConfig {
+    fn resolve_path(&self, path: &str) -> Result<PathBuf> {
+        let path = expand_path(path)?;
+        Ok(match &self.config_dir {
+            Some(config_dir) => config_dir.join(path),
+            None => path,
+        })
+    }
+}
+
+/// Encrypted file a `secret://` reference reads from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SecretFile {
+    Sops,
+    Age,
+}
+
+impl SecretFile {
+    fn name(&self) -> &'static str {
+        match self {
+            SecretFile::Sops => "sops",
+            SecretFile::Age => "age",
+        }
+    }
+}
+
+/// Where the value of a secret comes from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SecretRef {
+    Env(String),
+    Keychain(String),
+    File { file: SecretFile, key: Vec<String> },
+}
+
+impl SecretRef {
+    /// The secret `value` refers to, or `None` for a literal value
+    pub fn parse(value: &str) -> Result<Option<Self>> {
+        let reference = if let Some(name) = value.strip_prefix("env://") {
+            SecretRef::Env(name.to_string())
+        } else if let Some(name) = value.strip_prefix("keychain://") {
+            SecretRef::Keychain(name.to_string())
+        } else if let Some(rest) = value.strip_prefix("secret://") {
+            let (file, key) = rest.split_once('/').unwrap_or((rest, ""));
+            let file = match file {
+                "sops" => SecretFile::Sops,
+                "age" => SecretFile::Age,
+                _ => anyhow::bail!(
+                    "Invalid secret reference '{}': expected secret://sops/... or secret://age/...",
+                    value
+                ),
+            };
+            SecretRef::File {
+                file,
+                key: key
+                    .split('/')
+                    .filter(|part| !part.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            }
+        } else {
+            return Ok(None);
+        };
+
+        let empty = match &reference {
+            SecretRef::Env(name) | SecretRef::Keychain(name) => name.is_empty(),
+            SecretRef::File { key, .. } => key.is_empty(),
+        };
+        if empty {
+            anyhow::bail!("Invalid secret reference '{}': nothing to look up", value);
+        }
+        Ok(Some(reference))
+    }
+}
+
+/// Environment of a recipe with its secrets resolved
+#[derive(Debug, Clone, Default)]
+pub struct SecretEnv {
+    vars: Vec<(String, String)>,
+    secrets: Vec<String>,
+}
+
+impl SecretEnv {
+    /// Variables to set, secrets included
+    pub fn vars(&self) -> &[(String, String)] {
+        &self.vars
+    }
+
+    /// Resolved secret values, to be masked in output
+    pub fn secrets(&self) -> &[String] {
+        &self.secrets
+    }
+}
+
+/// Resolve a recipe's `env`, decrypting each secrets file at most once
+pub fn resolve_env(
+    env: &BTreeMap<String, String>,
+    secrets: Option<&SecretsConfig>,
+) -> Result<SecretEnv> {
+    let mut documents = HashMap::new();
+    let mut resolved = SecretEnv::default();
+    for (name, value) in env {
+        let Some(reference) = SecretRef::parse(value)? else {
+            resolved.vars.push((name.clone(), value.clone()));
+            continue;
+        };
+        let secret = resolve(&reference, secrets, &mut documents)
+            .with_context(|| format!("Failed to resolve {} for '{}'", value, name))?;
+        if !secret.is_empty() {
+            resolved.secrets.push(secret.clone());
+        }
+        resolved.vars.push((name.clone(), secret));
+    }
+    Ok(resolved)
+}
+
+fn resolve(
+    reference: &SecretRef,
+    secrets: Option<&SecretsConfig>,
+    documents: &mut HashMap<SecretFile, serde_yaml::Value>,
+) -> Result<String> {
+    match reference {
+        SecretRef::Env(name) => {
+            std::env::var(name).with_context(|| format!("Environment variable {} is not set", name))
+        }
+        SecretRef::Keychain(name) => keychain::get_secret(name)?.with_context(|| {
+            format!(
+                "No keychain entry '{}' under the service '{}'",
+                name,
+                crate::constants::credentials::SECRETS_KEYCHAIN_SERVICE
+            )
+        }),
+        SecretRef::File { file, key } => {
+            let document = match documents.entry(*file) {
+                std::collections::hash_map::Entry::Occupied(entry) => entry.into_mut(),
+                std::collections::hash_map::Entry::Vacant(entry) => {
+                    entry.insert(decrypt(*file, secrets)?)
+                }
+            };
+            lookup(document, key)
+                .with_context(|| format!("No value at '{}' in the secrets file", key.join("/")))
+        }
+    }
+}
+
+/// Decrypt a secrets file with the `sops` or `age` command
+fn decrypt(file: SecretFile, secrets: Option<&SecretsConfig>) -> Result<serde_yaml::Value> {
+    let secrets = secrets.cloned().unwrap_or_default();
+    let configured = match file {
+        SecretFile::Sops => &secrets.sops,
+        SecretFile::Age => &secrets.age,
+    };
+    let path = secrets.resolve_path(configured.as_deref().with_context(|| {
+        format!(
+            "No secrets file configured; set 'secrets.{}' in the config",
+            file.name()
+        )
+    })?)?;
+
+    let mut command = Command::new(file.name());
+    match file {
+        SecretFile::Sops => {
+            command.arg("--decrypt");
+        }
+        SecretFile::Age => {
+            let identity = secrets.age_identity.as_deref().with_context(
+                || "No age identity configured; set 'secrets.age_identity' in the config",
+            )?;
+            command
+                .arg("--decrypt")
+                .arg("--identity")
+                .arg(secrets.resolve_path(identity)?);
+        }
+    }
+    command.arg(&path);
+
+    log::debug!("Decrypting {} with {}", path.display(), file.name());
+    let output = command
+        .output()
+        .with_context(|| format!("Failed to run {}; is it installed?", file.name()))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to decrypt {}: {}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    serde_yaml::from_slice(&output.stdout)
+        .with_context(|| format!("Decrypted {} is not YAML or JSON", path.display()))
+}
+
+/// Scalar at `key` in nested mappings
+fn lookup(document: &serde_yaml::Value, key: &[String]) -> Option<String> {
+    let value = key
+        .iter()
+        .try_fold(document, |value, part| value.get(part.as_str()))?;
+    match value {
+        serde_yaml::Value::String(value) => Some(value.clone()),
+        serde_yaml::Value::Number(value) => Some(value.to_string()),
+        serde_yaml::Value::Bool(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_references() {
+        assert_eq!(SecretRef::parse("plain value").unwrap(), None);
+        assert_eq!(
+            SecretRef::parse("env://NPM_TOKEN").unwrap(),
+            Some(SecretRef::Env("NPM_TOKEN".to_string()))
+        );
+        assert_eq!(
+            SecretRef::parse("keychain://npm").unwrap(),
+            Some(SecretRef::Keychain("npm".to_string()))
+        );
+        assert_eq!(
+            SecretRef::parse("secret://sops/registry/password").unwrap(),
+            Some(SecretRef::File {
+                file: SecretFile::Sops,
+                key: vec!["registry".to_string(), "password".to_string()],
+            })
+        );
+
+        for invalid in ["env://", "secret://vault/key", "secret://age/"] {
+            assert!(SecretRef::parse(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_lookup() {
+        let document: serde_yaml::Value =
+            serde_yaml::from_str("registry:\n  password: hunter2\n  port: 5000\n").unwrap();
+        let key = |path: &str| path.split('/').map(str::to_string).collect::<Vec<_>>();
+
+        assert_eq!(
+            lookup(&document, &key("registry/password")).as_deref(),
+            Some("hunter2")
+        );
+        assert_eq!(
+            lookup(&document, &key("registry/port")).as_deref(),
+            Some("5000")
+        );
+        assert_eq!(lookup(&document, &key("registry")), None);
+        assert_eq!(lookup(&document, &key("registry/user")), None);
+    }
+
+    #[test]
+    fn test_resolve_env() {
+        unsafe { std::env::set_var("REPOS_TEST_SECRET", "s3cr3t") };
+        let env = BTreeMap::from([
+            ("MODE".to_string(), "release".to_string()),
+            ("TOKEN".to_string(), "env://REPOS_TEST_SECRET".to_string()),
+        ]);
+
+        let resolved = resolve_env(&env, None).unwrap();
+        assert_eq!(
+            resolved.vars(),
+            &[
+                ("MODE".to_string(), "release".to_string()),
+                ("TOKEN".to_string(), "s3cr3t".to_string()),
+            ]
+        );
+        assert_eq!(resolved.secrets(), &["s3cr3t".to_string()]);
+
+        let missing =
+            BTreeMap::from([("TOKEN".to_string(), "env://REPOS_TEST_MISSING".to_string())]);
+        let error = format!("{:#}", resolve_env(&missing, None).unwrap_err());
+        assert!(error.contains("REPOS_TEST_MISSING is not set"), "{}", error);
+
+        let no_file = BTreeMap::from([("TOKEN".to_string(), "secret://age/token".to_string())]);
+        let error = format!("{:#}", resolve_env(&no_file, None).unwrap_err());
+        assert!(error.contains("set 'secrets.age'"), "{}", error);
+    }
+}
@@ -0,0 +1,201 @@
+//! Workspaces combining the repositories of several config files
+//!
+//! A workspace file lists config files instead of repositories:
+//!
+//! ```yaml
+//! workspace:
+//!   - config: work/repos.yaml
+//!     name: work
+//!   - config: ~/oss/repos.yaml
+//! ```
+//!
+//! Each repository is renamed to `<namespace>/<name>` and tagged with its
+//! namespace, so repositories with the same name in different fleets do not
+//! collide and `-t <namespace>` selects one fleet. Passing `--config` more than
+//! once builds the same workspace on the fly.
+
+use super::{Config, expand_path};
+use crate::constants::config::DEFAULT_CONFIG_FILE;
+use anyhow::Result;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Separator between the namespace and the repository name
+pub const NAMESPACE_SEPARATOR: char = '/';
+
+/// Contents of a workspace file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WorkspaceFile {
+    pub workspace: Vec<WorkspaceMember>,
+}
+
+/// A config file that is part of a workspace
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WorkspaceMember {
+    /// Path of the config file, relative to the workspace file
+    pub config: String,
+    /// Namespace for the config's repositories; derived from the path when not set
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+impl WorkspaceMember {
+    pub fn namespace(&self) -> String {
+        self.name
+            .clone()
+            .unwrap_or_else(|| default_namespace(&self.config))
+    }
+}
+
+/// Namespace for a config file: its name without extension, or the name of its
+/// directory for the default `repos.yaml`
+pub fn default_namespace(path: &str) -> String {
+    let path = Path::new(path);
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let default_stem = Path::new(DEFAULT_CONFIG_FILE)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    if stem != default_stem {
+        return stem;
+    }
+
+    std::env::current_dir()
+        .unwrap_or_default()
+        .join(path)
+        .parent()
+        .and_then(|dir| dir.canonicalize().ok().or_else(|| Some(dir.to_path_buf())))
+        .and_then(|dir| dir.file_name().map(|n| n.to_string_lossy().to_string()))
+        .unwrap_or(stem)
+}
+
+/// Load and combine the configs of a workspace
+///
+/// `base_dir` is what relative member paths are resolved against.
+pub fn load_members(members: &[WorkspaceMember], base_dir: &Path) -> Result<Config> {
+    if members.is_empty() {
+        anyhow::bail!("Workspace does not list any config files");
+    }
+
+    let mut configs = Vec::new();
+    for member in members {
+        let path = base_dir.join(expand_path(&member.config)?);
+        let config = Config::load_file(&path.to_string_lossy())
+            .map_err(|e| anyhow::anyhow!("Workspace member '{}': {}", member.config, e))?;
+        configs.push((member.namespace(), config));
+    }
+    merge(configs)
+}
+
+/// Combine configs, namespacing their repositories
+///
+/// Recipes, file requirements, log sinks and exit code descriptions are combined, with
+/// earlier configs taking precedence for recipes and exit codes of the same
+/// name. `artifacts_remote`, `notify`, `secrets` and `pr` are taken from the
+/// first config that sets them.
+pub fn merge(configs: Vec<(String, Config)>) -> Result<Config> {
+    let mut namespaces = HashSet::new();
+    let mut merged = Config::new();
+
+    for (namespace, config) in configs {
+        if namespace.is_empty() || namespace.contains(NAMESPACE_SEPARATOR) {
+            anyhow::bail!("Invalid workspace namespace '{}'", namespace);
+        }
+        if !namespaces.insert(namespace.clone()) {
+            anyhow::bail!(
+                "Two workspace configs use the namespace '{}'; set `name` for one of them in a workspace file",
+                namespace
+            );
+        }
+
+        for mut repo in config.repositories {
+            // Pin the directory before renaming, since it may be derived from the name
+            repo.path = Some(repo.get_target_dir());
+            repo.name = format!("{}{}{}", namespace, NAMESPACE_SEPARATOR, repo.name);
+            if !repo.tags.contains(&namespace) {
+                repo.tags.push(namespace.clone());
+            }
+            merged.repositories.push(repo);
+        }
+
+        for recipe in config.recipes {
+            if merged.find_recipe(&recipe.name).is_none() {
+                merged.recipes.push(recipe);
+            }
+        }
+        merged.files.extend(config.files);
+        merged.branch_protection.extend(config.branch_protection);
+        merged
+            .repository_settings
+            .extend(config.repository_settings);
+        merged.post_clone.extend(config.post_clone);
+        merged.exit_codes.merge(config.exit_codes);
+        merged.artifacts_remote = merged.artifacts_remote.or(config.artifacts_remote);
+        merged.log_sinks.extend(config.log_sinks);
+        merged.notify = merged.notify.or(config.notify);
+        merged.secrets = merged.secrets.or(config.secrets);
+        merged.pr = merged.pr.or(config.pr);
+    }
+
+    Ok(merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Repository;
+
+    fn config_with(names: &[&str]) -> Config {
+        let mut config = Config::new();
+        config.repositories = names
+            .iter()
+            .map(|name| {
+                let mut repo =
+                    Repository::new(name.to_string(), format!("git@github.com:org/{}.git", name));
+                repo.set_config_dir(Some("/fleet".into()));
+                repo
+            })
+            .collect();
+        config
+    }
+
+    #[test]
+    fn test_merge_namespaces_repositories() {
+        let merged = merge(vec![
+            ("work".to_string(), config_with(&["api", "web"])),
+            ("oss".to_string(), config_with(&["api"])),
+        ])
+        .unwrap();
+
+        let names: Vec<&str> = merged
+            .repositories
+            .iter()
+            .map(|r| r.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["work/api", "work/web", "oss/api"]);
+        assert!(merged.repositories[2].tags.contains(&"oss".to_string()));
+        // Directories stay where the original configs put them
+        assert_eq!(merged.repositories[0].get_target_dir(), "/fleet/api");
+    }
+
+    #[test]
+    fn test_merge_rejects_duplicate_namespaces() {
+        let result = merge(vec![
+            ("work".to_string(), config_with(&["api"])),
+            ("work".to_string(), config_with(&["web"])),
+        ]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_default_namespace() {
+        assert_eq!(default_namespace("configs/clients.yaml"), "clients");
+        assert_eq!(default_namespace("/home/me/work/repos.yaml"), "work");
+    }
+}
@@ -7,6 +7,15 @@ pub mod git {
 
     /// Default commit message when none is provided
     pub const DEFAULT_COMMIT_MSG: &str = "Automated changes";
+
+    /// Default number of repositories `repos fetch` fetches at the same time
+    pub const DEFAULT_FETCH_JOBS: usize = 8;
+
+    /// Default number of repositories `repos clone --parallel` clones at the same time
+    pub const DEFAULT_CLONE_JOBS: usize = 4;
+
+    /// Default directory `repos mirror` keeps bare mirror clones in
+    pub const DEFAULT_MIRROR_DIR: &str = "mirrors";
 }
 
 /// Default values for GitHub operations
@@ -22,6 +31,18 @@ pub mod github {
 
     /// Default User-Agent header for API requests
     pub const DEFAULT_USER_AGENT: &str = concat!("repos/", env!("CARGO_PKG_VERSION"));
+
+    /// Directory of cached API responses, relative to the home directory
+    pub const CACHE_DIR: &str = ".repos/cache/github";
+
+    /// Seconds cached API responses are used without asking GitHub whether they changed
+    pub const DEFAULT_CACHE_TTL_SECS: u64 = 300;
+
+    /// Environment variable overriding the cache TTL in seconds
+    pub const CACHE_TTL_ENV: &str = "REPOS_CACHE_TTL";
+
+    /// Seconds a remembered archived state is trusted before `repos pr` asks GitHub again
+    pub const ARCHIVED_TTL_SECS: u64 = 24 * 60 * 60;
 }
 
 /// Default values for configuration
@@ -31,4 +52,61 @@ pub mod config {
 
     /// Default output directory
     pub const DEFAULT_LOGS_DIR: &str = "output";
+
+    /// Environment variable selecting a profile when `--profile` is not given
+    pub const PROFILE_ENV: &str = "REPOS_PROFILE";
+
+    /// Pseudo-tag of repositories archived on their provider
+    pub const ARCHIVED_TAG: &str = "archived";
+}
+
+/// Locations of the local state store
+pub mod state {
+    /// Directory holding local state, next to the config file
+    pub const STATE_DIR: &str = ".repos";
+
+    /// State file name inside the state directory
+    pub const STATE_FILE: &str = "state.json";
+
+    /// Environment variable overriding the state directory
+    pub const STATE_DIR_ENV: &str = "REPOS_STATE_DIR";
+
+    /// Directory inside the state directory holding repositories removed with `rm --trash`
+    pub const TRASH_DIR: &str = "trash";
+
+    /// Directory inside the state directory holding worktrees created with `--use-worktree`
+    pub const WORKTREES_DIR: &str = "worktrees";
+
+    /// Number of checkpoints kept for `repos rollback`; older ones are dropped
+    pub const MAX_CHECKPOINTS: usize = 20;
+}
+
+/// Named tokens managed by `repos auth`
+pub mod credentials {
+    /// File listing the saved identities, relative to the home directory
+    pub const IDENTITIES_FILE: &str = ".repos/identities.json";
+
+    /// Environment variable overriding the identities file
+    pub const IDENTITIES_FILE_ENV: &str = "REPOS_IDENTITIES_FILE";
+
+    /// Service name tokens are stored under in the OS keychain
+    pub const KEYCHAIN_SERVICE: &str = "repos";
+
+    /// Service name `keychain://` secrets in recipe environments are read from
+    pub const SECRETS_KEYCHAIN_SERVICE: &str = "repos-secrets";
+
+    /// Host identities are for when none is given
+    pub const DEFAULT_HOST: &str = "github.com";
+}
+
+/// Plugin discovery
+pub mod plugins {
+    /// Directory holding installed and WASM plugins, relative to the home directory
+    pub const PLUGIN_DIR: &str = ".repos/plugins";
+}
+
+/// Default values for `repos watch`
+pub mod watch {
+    /// Milliseconds without further changes before the command runs
+    pub const DEFAULT_DEBOUNCE_MS: u64 = 300;
 }
@@ -0,0 +1,403 @@
+//! Named GitHub tokens for different organizations and hosts
+//!
+//! `repos auth login` saves a token under a name in the OS keychain and records
+//! which host, and optionally which owner, it belongs to in
+//! `~/.repos/identities.json`. Commands talking to GitHub pick the token of the
+//! identity matching each repository, so repositories of different
+//! organizations can use different tokens in one run. A token passed with
+//! `--token` or set in `GITHUB_TOKEN` takes precedence over saved identities.
+
+use crate::config::Repository;
+use crate::constants::credentials::{DEFAULT_HOST, IDENTITIES_FILE, IDENTITIES_FILE_ENV};
+use crate::logging;
+use anyhow::{Context, Result};
+use colored::*;
+use log::Level;
+use repos_github::parse_github_url;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// A named token, stored in the keychain, for a host and optionally one owner
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Identity {
+    pub name: String,
+    /// Host the token is for, e.g. `github.com` or a GitHub Enterprise host
+    pub host: String,
+    /// Organization or user the token is limited to; all owners on the host when not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+}
+
+impl Identity {
+    /// How well the identity fits a repository: 2 for its owner, 1 for any
+    /// owner on its host, `None` when it does not apply
+    fn rank(&self, host: &str, owner: Option<&str>) -> Option<u8> {
+        if !self.host.eq_ignore_ascii_case(host) {
+            return None;
+        }
+        match (&self.owner, owner) {
+            (None, _) => Some(1),
+            (Some(mine), Some(owner)) if mine.eq_ignore_ascii_case(owner) => Some(2),
+            _ => None,
+        }
+    }
+
+    /// Host and owner the identity is used for, e.g. `github.com/acme`
+    pub fn scope(&self) -> String {
+        match &self.owner {
+            Some(owner) => format!("{}/{}", self.host, owner),
+            None => self.host.clone(),
+        }
+    }
+}
+
+/// The saved identities; their tokens live in the keychain
+#[derive(Debug, Clone)]
+pub struct IdentityStore {
+    path: PathBuf,
+}
+
+impl IdentityStore {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Store at `REPOS_IDENTITIES_FILE`, or `~/.repos/identities.json`
+    pub fn open_default() -> Result<Self> {
+        if let Ok(path) = std::env::var(IDENTITIES_FILE_ENV) {
+            return Ok(Self::new(path));
+        }
+        let home = std::env::var_os("HOME")
+            .ok_or_else(|| anyhow::anyhow!("HOME is not set; cannot locate saved identities"))?;
+        Ok(Self::new(PathBuf::from(home).join(IDENTITIES_FILE)))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Saved identities, in the order they were added; a missing file yields none
+    pub fn list(&self) -> Result<Vec<Identity>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = std::fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read {}", self.path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", self.path.display()))
+    }
+
+    fn write(&self, identities: &[Identity]) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(identities)?)
+            .with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+
+    /// Save an identity and its token, replacing an identity with the same name
+    pub fn save(&self, identity: Identity, token: &str) -> Result<()> {
+        keychain::set(&identity.name, token)?;
+
+        let mut identities = self.list()?;
+        match identities.iter_mut().find(|i| i.name == identity.name) {
+            Some(existing) => *existing = identity,
+            None => identities.push(identity),
+        }
+        self.write(&identities)
+    }
+
+    /// Forget an identity and delete its token; `false` when there is no such identity
+    pub fn remove(&self, name: &str) -> Result<bool> {
+        let mut identities = self.list()?;
+        let before = identities.len();
+        identities.retain(|identity| identity.name != name);
+        if identities.len() == before {
+            return Ok(false);
+        }
+
+        keychain::delete(name)?;
+        self.write(&identities)?;
+        Ok(true)
+    }
+
+    /// Token of an identity, or `None` when the keychain has no token for it
+    pub fn token(&self, identity: &Identity) -> Result<Option<String>> {
+        keychain::get(&identity.name)
+    }
+}
+
+/// Whether any identity has been saved with `repos auth login`
+pub fn has_saved_identities() -> bool {
+    IdentityStore::open_default()
+        .and_then(|store| store.list())
+        .is_ok_and(|identities| !identities.is_empty())
+}
+
+/// Tokens available to a command
+#[derive(Debug, Clone, Default)]
+pub struct Credentials {
+    /// Token from `--token` or the environment, used for every repository
+    explicit: Option<String>,
+    identities: Vec<(Identity, String)>,
+}
+
+impl Credentials {
+    pub fn new(explicit: Option<String>, identities: Vec<(Identity, String)>) -> Self {
+        Self {
+            explicit,
+            identities,
+        }
+    }
+
+    /// Credentials from `--token` or the environment, or else from the saved identities
+    ///
+    /// The keychain is only read when no token is given. Identities whose token
+    /// cannot be read are skipped with a warning.
+    pub fn load(token: Option<String>) -> Self {
+        let explicit = token.or_else(crate::ci::github_token);
+        if explicit.is_some() {
+            return Self::new(explicit, Vec::new());
+        }
+
+        let store = match IdentityStore::open_default() {
+            Ok(store) => store,
+            Err(_) => return Self::default(),
+        };
+        let identities = match store.list() {
+            Ok(identities) => identities,
+            Err(e) => {
+                logging::message(
+                    Level::Warn,
+                    format!("Warning: Ignoring saved identities: {}", e).yellow(),
+                );
+                return Self::default();
+            }
+        };
+
+        let mut loaded = Vec::new();
+        for identity in identities {
+            match store.token(&identity) {
+                Ok(Some(token)) => loaded.push((identity, token)),
+                Ok(None) => logging::message(
+                    Level::Warn,
+                    format!(
+                        "Warning: No token in the keychain for identity '{}'",
+                        identity.name
+                    )
+                    .yellow(),
+                ),
+                Err(e) => logging::message(
+                    Level::Warn,
+                    format!(
+                        "Warning: Failed to read token of identity '{}': {}",
+                        identity.name, e
+                    )
+                    .yellow(),
+                ),
+            }
+        }
+        Self::new(None, loaded)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.explicit.is_none() && self.identities.is_empty()
+    }
+
+    /// Token for a host and owner: the explicit one, or the identity for the
+    /// owner, or the identity for the whole host
+    pub fn token_for(&self, host: &str, owner: Option<&str>) -> Option<String> {
+        if let Some(token) = &self.explicit {
+            return Some(token.clone());
+        }
+        self.identities
+            .iter()
+            .filter_map(|(identity, token)| identity.rank(host, owner).map(|rank| (rank, token)))
+            // The first identity saved wins among equally good ones
+            .min_by_key(|(rank, _)| std::cmp::Reverse(*rank))
+            .map(|(_, token)| token.clone())
+    }
+
+    /// Token for a repository, matched by the host and owner in its URL
+    pub fn token_for_repository(&self, repo: &Repository) -> Option<String> {
+        let host = url_host(&repo.url).unwrap_or_else(|| DEFAULT_HOST.to_string());
+        let owner = parse_github_url(&repo.url).ok().map(|(owner, _)| owner);
+        self.token_for(&host, owner.as_deref())
+    }
+
+    /// Token for requests not tied to one repository, such as team lookups
+    ///
+    /// Falls back to the first identity saved for an owner on `github.com`
+    /// when none covers the whole host.
+    pub fn default_token(&self) -> Option<String> {
+        self.token_for(DEFAULT_HOST, None).or_else(|| {
+            self.identities
+                .iter()
+                .find(|(identity, _)| identity.host.eq_ignore_ascii_case(DEFAULT_HOST))
+                .map(|(_, token)| token.clone())
+        })
+    }
+}
+
+/// Host part of a git URL, e.g. `github.com` for `git@github.com:owner/repo.git`
+pub fn url_host(url: &str) -> Option<String> {
+    let rest = match url.split_once("://") {
+        Some((_, rest)) => rest,
+        // scp-like syntax: user@host:path
+        None => url.split_once(':').map(|(host, _)| host)?,
+    };
+    let authority = rest.split('/').next()?;
+    let host = authority.rsplit('@').next()?;
+    let host = host.split(':').next()?;
+    (!host.is_empty()).then(|| host.to_lowercase())
+}
+
+#[cfg(feature = "keychain")]
+pub(crate) mod keychain {
+    use crate::constants::credentials::{KEYCHAIN_SERVICE, SECRETS_KEYCHAIN_SERVICE};
+    use anyhow::{Context, Result};
+    use keyring::{Entry, Error};
+
+    pub fn set(name: &str, token: &str) -> Result<()> {
+        Entry::new(KEYCHAIN_SERVICE, name)?
+            .set_password(token)
+            .context("Failed to store token in the keychain")
+    }
+
+    pub fn get(name: &str) -> Result<Option<String>> {
+        match Entry::new(KEYCHAIN_SERVICE, name)?.get_password() {
+            Ok(token) => Ok(Some(token)),
+            Err(Error::NoEntry) => Ok(None),
+            Err(e) => Err(e).context("Failed to read token from the keychain"),
+        }
+    }
+
+    pub fn delete(name: &str) -> Result<()> {
+        match Entry::new(KEYCHAIN_SERVICE, name)?.delete_credential() {
+            Ok(()) | Err(Error::NoEntry) => Ok(()),
+            Err(e) => Err(e).context("Failed to delete token from the keychain"),
+        }
+    }
+
+    /// Secret referenced as `keychain://name` from a recipe's environment
+    pub fn get_secret(name: &str) -> Result<Option<String>> {
+        match Entry::new(SECRETS_KEYCHAIN_SERVICE, name)?.get_password() {
+            Ok(secret) => Ok(Some(secret)),
+            Err(Error::NoEntry) => Ok(None),
+            Err(e) => Err(e).context("Failed to read secret from the keychain"),
+        }
+    }
+}
+
+#[cfg(not(feature = "keychain"))]
+pub(crate) mod keychain {
+    use anyhow::Result;
+
+    pub fn set(_name: &str, _token: &str) -> Result<()> {
+        anyhow::bail!("repos was built without keychain support")
+    }
+
+    pub fn get(_name: &str) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    pub fn delete(_name: &str) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn get_secret(_name: &str) -> Result<Option<String>> {
+        anyhow::bail!("repos was built without keychain support")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn identity(name: &str, host: &str, owner: Option<&str>) -> (Identity, String) {
+        (
+            Identity {
+                name: name.to_string(),
+                host: host.to_string(),
+                owner: owner.map(str::to_string),
+            },
+            format!("{}-token", name),
+        )
+    }
+
+    #[test]
+    fn test_url_host() {
+        assert_eq!(
+            url_host("git@github.com:owner/repo.git").as_deref(),
+            Some("github.com")
+        );
+        assert_eq!(
+            url_host("https://GHE.example.com/owner/repo").as_deref(),
+            Some("ghe.example.com")
+        );
+        assert_eq!(
+            url_host("ssh://git@git.example.com:2222/owner/repo.git").as_deref(),
+            Some("git.example.com")
+        );
+        assert_eq!(url_host("repo"), None);
+    }
+
+    #[test]
+    fn test_token_for_prefers_owner() {
+        let credentials = Credentials::new(
+            None,
+            vec![
+                identity("personal", "github.com", None),
+                identity("acme", "github.com", Some("acme")),
+                identity("enterprise", "ghe.example.com", None),
+            ],
+        );
+
+        let token = |url: &str| {
+            credentials.token_for_repository(&Repository::new("r".to_string(), url.to_string()))
+        };
+        assert_eq!(
+            token("git@github.com:acme/api.git").as_deref(),
+            Some("acme-token")
+        );
+        assert_eq!(
+            token("git@github.com:ACME/web.git").as_deref(),
+            Some("acme-token")
+        );
+        assert_eq!(
+            token("https://github.com/other/cli").as_deref(),
+            Some("personal-token")
+        );
+        assert_eq!(
+            token("https://ghe.example.com/team/svc.git").as_deref(),
+            Some("enterprise-token")
+        );
+        assert_eq!(token("https://gitlab.com/team/svc.git"), None);
+        assert_eq!(
+            credentials.default_token().as_deref(),
+            Some("personal-token")
+        );
+    }
+
+    #[test]
+    fn test_explicit_token_wins() {
+        let credentials = Credentials::new(
+            Some("explicit".to_string()),
+            vec![identity("acme", "github.com", Some("acme"))],
+        );
+        assert_eq!(
+            credentials.token_for("github.com", Some("acme")).as_deref(),
+            Some("explicit")
+        );
+    }
+
+    #[test]
+    fn test_default_token_falls_back_to_owner_identity() {
+        let credentials =
+            Credentials::new(None, vec![identity("acme", "github.com", Some("acme"))]);
+        assert_eq!(credentials.default_token().as_deref(), Some("acme-token"));
+        assert!(Credentials::default().default_token().is_none());
+    }
+}
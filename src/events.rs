@@ -0,0 +1,336 @@
+//! Structured event stream (`--events ndjson`)
+//!
+//! Wrapper tooling and dashboards can follow a command through one JSON
+//! object per line instead of scraping status lines:
+//!
+//! ```text
+//! {"timestamp":"...","command":"run","event":"repo_started","repository":"api"}
+//! {"timestamp":"...","command":"run","event":"command_output","repository":"api","stream":"stdout","line":"ok"}
+//! {"timestamp":"...","command":"run","event":"repo_finished","repository":"api","success":true,"exit_code":0,"duration_ms":812}
+//! ```
+//!
+//! Every status line is also reported as a `status` event, so commands without
+//! dedicated events can be followed too. When events go to stdout, the human
+//! readable output moves to stderr to keep the stream parseable.
+
+use crate::logging;
+use crate::utils::notify::BatchReport;
+use anyhow::{Context, Result};
+use log::Level;
+use regex::Regex;
+use serde::Serialize;
+use std::fmt::Display;
+use std::future::Future;
+use std::io::Write;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Supported event formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventFormat {
+    /// One JSON object per line
+    Ndjson,
+}
+
+impl FromStr for EventFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "ndjson" | "jsonl" => Ok(EventFormat::Ndjson),
+            _ => anyhow::bail!("Unknown event format '{}' (expected ndjson)", s),
+        }
+    }
+}
+
+/// Stream of command output an event carries a line of
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+/// Something that happened while a command was running
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    /// Work on a repository began
+    RepoStarted { repository: String },
+    /// Work on a repository ended
+    RepoFinished {
+        repository: String,
+        success: bool,
+        /// Exit code of the command, for commands running one
+        #[serde(skip_serializing_if = "Option::is_none")]
+        exit_code: Option<i32>,
+        duration_ms: u64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+    /// A line a command printed in a repository, with secrets masked
+    CommandOutput {
+        repository: String,
+        stream: OutputStream,
+        line: String,
+    },
+    /// A status line, without colors
+    Status {
+        level: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        repository: Option<String>,
+        message: String,
+    },
+    /// Outcome of a batch, as sent to notifications
+    RunSummary {
+        #[serde(flatten)]
+        report: BatchReport,
+    },
+}
+
+impl Event {
+    fn repo_finished(
+        repository: &str,
+        exit_code: Option<i32>,
+        duration: Duration,
+        error: Option<String>,
+    ) -> Self {
+        Event::RepoFinished {
+            repository: repository.to_string(),
+            success: error.is_none() && exit_code.is_none_or(|code| code == 0),
+            exit_code,
+            duration_ms: duration.as_millis() as u64,
+            error,
+        }
+    }
+}
+
+/// An event with the fields every line carries
+#[derive(Serialize)]
+struct Envelope<'a> {
+    timestamp: String,
+    /// Subcommand being run, e.g. `clone`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    command: Option<&'a str>,
+    #[serde(flatten)]
+    event: &'a Event,
+}
+
+struct EventBus {
+    command: Option<String>,
+    to_stdout: bool,
+    out: Mutex<Box<dyn Write + Send>>,
+}
+
+static BUS: OnceLock<EventBus> = OnceLock::new();
+
+/// Emit events for the rest of the process
+///
+/// Events are written to `path`, appending to it, or to stdout when no path
+/// or `-` is given. `command` names the subcommand on every event.
+pub fn init(format: EventFormat, path: Option<&Path>, command: Option<&str>) -> Result<()> {
+    let EventFormat::Ndjson = format;
+    let path = path.filter(|path| path.as_os_str() != "-");
+    let out: Box<dyn Write + Send> = match path {
+        Some(path) => Box::new(
+            std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .with_context(|| format!("Failed to open event file {}", path.display()))?,
+        ),
+        None => Box::new(std::io::stdout()),
+    };
+    let _ = BUS.set(EventBus {
+        command: command.map(str::to_string),
+        to_stdout: path.is_none(),
+        out: Mutex::new(out),
+    });
+    Ok(())
+}
+
+/// Whether events are being emitted
+pub fn enabled() -> bool {
+    BUS.get().is_some()
+}
+
+/// Whether events take over stdout, so human readable output must go elsewhere
+pub fn on_stdout() -> bool {
+    BUS.get().is_some_and(|bus| bus.to_stdout)
+}
+
+/// Write an event, if events are enabled
+pub fn emit(event: Event) {
+    let Some(bus) = BUS.get() else {
+        return;
+    };
+    let line = render(&event, bus.command.as_deref());
+    let mut out = bus.out.lock().unwrap_or_else(|e| e.into_inner());
+    if let Err(e) = writeln!(out, "{}", line).and_then(|_| out.flush()) {
+        log::debug!("Failed to write event: {}", e);
+    }
+}
+
+fn render(event: &Event, command: Option<&str>) -> String {
+    let envelope = Envelope {
+        timestamp: chrono::Local::now().to_rfc3339(),
+        command,
+        event,
+    };
+    serde_json::to_string(&envelope).unwrap_or_default()
+}
+
+/// Report a status line printed through [`logging`]
+pub(crate) fn status(level: Level, repository: Option<&str>, message: &str) {
+    if !enabled() {
+        return;
+    }
+    emit(Event::Status {
+        level: level.as_str().to_lowercase(),
+        repository: repository.map(str::to_string),
+        message: strip_colors(message),
+    });
+}
+
+/// Report a line of command output
+pub fn output(repository: &str, stream: OutputStream, line: &str) {
+    if !enabled() {
+        return;
+    }
+    emit(Event::CommandOutput {
+        repository: repository.to_string(),
+        stream,
+        line: line.to_string(),
+    });
+}
+
+/// Report a finished batch
+pub fn summary(report: &BatchReport) {
+    emit(Event::RunSummary {
+        report: report.clone(),
+    });
+}
+
+/// Report the start of work on a repository
+pub fn repo_started(repository: &str) {
+    emit(Event::RepoStarted {
+        repository: repository.to_string(),
+    });
+}
+
+/// Report the end of work on a repository
+pub fn repo_finished(
+    repository: &str,
+    exit_code: Option<i32>,
+    duration: Duration,
+    error: Option<String>,
+) {
+    emit(Event::repo_finished(repository, exit_code, duration, error));
+}
+
+/// Run blocking work on a repository between `repo_started` and `repo_finished`
+///
+/// Diagnostics emitted by `f` are attributed to the repository as with
+/// [`logging::with_repository`].
+pub fn track<T, E: Display>(repository: &str, f: impl FnOnce() -> Result<T, E>) -> Result<T, E> {
+    repo_started(repository);
+    let started = Instant::now();
+    let result = logging::with_repository(repository, f);
+    repo_finished(
+        repository,
+        None,
+        started.elapsed(),
+        result.as_ref().err().map(|e| format!("{:#}", e)),
+    );
+    result
+}
+
+/// Run async work on a repository between `repo_started` and `repo_finished`
+///
+/// Diagnostics emitted by `future` are attributed to the repository as with
+/// [`logging::scope`].
+pub async fn track_async<T, E: Display>(
+    repository: &str,
+    future: impl Future<Output = Result<T, E>>,
+) -> Result<T, E> {
+    repo_started(repository);
+    let started = Instant::now();
+    let result = logging::scope(repository, future).await;
+    repo_finished(
+        repository,
+        None,
+        started.elapsed(),
+        result.as_ref().err().map(|e| format!("{:#}", e)),
+    );
+    result
+}
+
+/// Remove terminal color codes
+fn strip_colors(text: &str) -> String {
+    static ANSI: OnceLock<Regex> = OnceLock::new();
+    ANSI.get_or_init(|| Regex::new(r"\x1b\[[0-9;]*m").unwrap())
+        .replace_all(text, "")
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_format_from_str() {
+        assert_eq!(
+            "ndjson".parse::<EventFormat>().unwrap(),
+            EventFormat::Ndjson
+        );
+        assert!("xml".parse::<EventFormat>().is_err());
+    }
+
+    #[test]
+    fn test_render_events() {
+        let line = render(
+            &Event::repo_finished("api", Some(2), Duration::from_millis(1500), None),
+            Some("run"),
+        );
+        let json: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(json["event"], "repo_finished");
+        assert_eq!(json["command"], "run");
+        assert_eq!(json["repository"], "api");
+        assert_eq!(json["success"], false);
+        assert_eq!(json["exit_code"], 2);
+        assert_eq!(json["duration_ms"], 1500);
+        assert!(json.get("error").is_none());
+        assert!(json["timestamp"].is_string());
+
+        let line = render(
+            &Event::CommandOutput {
+                repository: "api".to_string(),
+                stream: OutputStream::Stderr,
+                line: "warning: unused".to_string(),
+            },
+            None,
+        );
+        let json: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(json["event"], "command_output");
+        assert_eq!(json["stream"], "stderr");
+        assert!(json.get("command").is_none());
+
+        let report = BatchReport::new("clone", 2, vec!["web".to_string()]);
+        let line = render(&Event::RunSummary { report }, Some("clone"));
+        let json: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(json["event"], "run_summary");
+        assert_eq!(json["operation"], "clone");
+        assert_eq!(json["total"], 3);
+        assert_eq!(json["failures"][0], "web");
+    }
+
+    #[test]
+    fn test_strip_colors() {
+        assert_eq!(
+            strip_colors("\x1b[1;36mapi\x1b[0m | \x1b[32mCloned\x1b[0m"),
+            "api | Cloned"
+        );
+    }
+}
@@ -0,0 +1,123 @@
+//! Git operations for switching working copies between branches
+//!
+//! ## Functions
+//!
+//! - [`branch_location`]: Whether a branch exists locally, only on `origin`, or not at all
+//! - [`switch_branch`]: Check out a branch, reporting files in the way instead of failing
+//! - [`start_point`]: The ref a new branch is created from
+
+use super::common::LoggedOutput;
+use anyhow::{Context, Result};
+use std::process::Command;
+
+/// Where a branch exists in a clone
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BranchLocation {
+    /// A local branch
+    Local,
+    /// Only the remote-tracking branch `origin/<branch>`
+    Remote,
+    Missing,
+}
+
+/// Result of a checkout that did not fail outright
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SwitchOutcome {
+    Switched,
+    /// Git refused to switch because it would overwrite these files
+    Blocked(Vec<String>),
+}
+
+/// Whether `full_ref` names an existing ref in the clone
+fn ref_exists(repo_path: &str, full_ref: &str) -> Result<bool> {
+    let output = Command::new("git")
+        .args(["show-ref", "--verify", "--quiet", full_ref])
+        .current_dir(repo_path)
+        .logged_output()
+        .context("Failed to execute git show-ref command")?;
+    Ok(output.status.success())
+}
+
+/// Find a branch among the local and the `origin` remote-tracking branches
+pub fn branch_location(repo_path: &str, branch: &str) -> Result<BranchLocation> {
+    if ref_exists(repo_path, &format!("refs/heads/{}", branch))? {
+        Ok(BranchLocation::Local)
+    } else if ref_exists(repo_path, &format!("refs/remotes/origin/{}", branch))? {
+        Ok(BranchLocation::Remote)
+    } else {
+        Ok(BranchLocation::Missing)
+    }
+}
+
+/// Files git lists as being in the way of a checkout
+fn blocking_files(stderr: &str) -> Vec<String> {
+    stderr
+        .lines()
+        .filter_map(|line| line.strip_prefix('\t'))
+        .map(|file| file.trim().to_string())
+        .collect()
+}
+
+/// Check out `branch`, creating it first when `location` is not [`BranchLocation::Local`]
+///
+/// A branch only on `origin` is created tracking `origin/<branch>`. A missing
+/// one is created from `start_point`, which is required in that case. When
+/// uncommitted or untracked files would be overwritten, the working copy is
+/// left as it was and the files are returned.
+pub fn switch_branch(
+    repo_path: &str,
+    branch: &str,
+    location: BranchLocation,
+    start_point: Option<&str>,
+) -> Result<SwitchOutcome> {
+    let remote_branch = format!("origin/{}", branch);
+    let args: Vec<&str> = match location {
+        BranchLocation::Local => vec!["checkout", branch],
+        BranchLocation::Remote => vec!["checkout", "-b", branch, "--track", &remote_branch],
+        BranchLocation::Missing => {
+            let start_point =
+                start_point.with_context(|| format!("Branch '{}' does not exist", branch))?;
+            vec!["checkout", "--no-track", "-b", branch, start_point]
+        }
+    };
+
+    let output = Command::new("git")
+        .args(&args)
+        .current_dir(repo_path)
+        .logged_output()
+        .context("Failed to execute git checkout command")?;
+
+    if output.status.success() {
+        return Ok(SwitchOutcome::Switched);
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let files = blocking_files(&stderr);
+    if stderr.contains("would be overwritten") && !files.is_empty() {
+        return Ok(SwitchOutcome::Blocked(files));
+    }
+    anyhow::bail!("Failed to checkout branch '{}': {}", branch, stderr.trim());
+}
+
+/// The ref to create a branch from: `origin/<base>` if it exists, else the local `base`
+pub fn start_point(repo_path: &str, base: &str) -> Result<String> {
+    if ref_exists(repo_path, &format!("refs/remotes/origin/{}", base))? {
+        Ok(format!("origin/{}", base))
+    } else if ref_exists(repo_path, &format!("refs/heads/{}", base))? {
+        Ok(base.to_string())
+    } else {
+        anyhow::bail!("Base branch '{}' does not exist", base)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocking_files() {
+        let stderr = "error: The following untracked working tree files would be overwritten by checkout:\n\tREADME.md\n\tsrc/lib.rs\nPlease move or remove them before you switch branches.\nAborting\n";
+        assert_eq!(blocking_files(stderr), vec!["README.md", "src/lib.rs"]);
+        assert!(blocking_files("fatal: invalid reference: nope").is_empty());
+    }
+}
@@ -0,0 +1,240 @@
+//! Git operations for snapshotting a working copy and putting it back later
+//!
+//! ## Functions
+//!
+//! - [`snapshot`]: Record the branch, commit and uncommitted changes of a working copy
+//! - [`restore`]: Return a working copy to a snapshot, stashing whatever is there now
+//! - [`drop_snapshot`]: Release the objects a snapshot keeps alive
+//!
+//! Uncommitted changes to tracked files are saved with `git stash create`,
+//! and untracked files are written to a tree through a temporary index, so
+//! the working tree is left alone. A commit holding both is kept under
+//! `refs/repos/checkpoints/<id>` so that garbage collection does not remove
+//! them.
+
+use super::common::{LoggedOutput, git};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+
+/// Namespace of the refs that keep snapshots reachable
+const CHECKPOINT_REFS: &str = "refs/repos/checkpoints";
+
+/// Where a working copy was, and what it held, when a snapshot was taken
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkingState {
+    /// Checked-out branch; `None` when HEAD was detached
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    pub commit: String,
+    /// Stash commit with the uncommitted changes to tracked files
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stash: Option<String>,
+    /// Untracked files, which are kept when the snapshot is restored
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub untracked: Vec<String>,
+    /// Tree with the contents of the untracked files
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub untracked_tree: Option<String>,
+}
+
+/// How [`restore`] dealt with a working copy
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RestoreReport {
+    /// Stash entry holding the changes that were in the working copy before
+    pub backup: Option<String>,
+    /// The snapshot's uncommitted changes were applied again
+    pub reapplied_changes: bool,
+}
+
+fn checkpoint_ref(id: &str) -> String {
+    format!("{}/{}", CHECKPOINT_REFS, id)
+}
+
+/// Write the untracked files to a tree without touching the real index
+fn untracked_tree(repo_path: &str, paths: &[String]) -> Result<String> {
+    let index = git(
+        repo_path,
+        &["rev-parse", "--git-path", "repos-checkpoint-index"],
+    )?;
+    let index = Path::new(repo_path).join(index);
+    let run = |args: &[&str]| -> Result<String> {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(repo_path)
+            .env("GIT_INDEX_FILE", &index)
+            .logged_output()
+            .with_context(|| format!("Failed to execute git {} command", args[0]))?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "git {} failed: {}",
+                args[0],
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+
+    let mut args = vec!["add", "--"];
+    args.extend(paths.iter().map(String::as_str));
+    let tree = run(&args).and_then(|_| run(&["write-tree"]));
+    let _ = std::fs::remove_file(&index);
+    tree
+}
+
+/// Record the state of a working copy under checkpoint `id`
+pub fn snapshot(repo_path: &str, id: &str) -> Result<WorkingState> {
+    let commit = git(repo_path, &["rev-parse", "HEAD"])?;
+    let branch = git(repo_path, &["branch", "--show-current"])?;
+    let stash = git(repo_path, &["stash", "create"])?;
+    let untracked: Vec<String> = git(repo_path, &["ls-files", "--others", "--exclude-standard"])?
+        .lines()
+        .map(str::to_string)
+        .collect();
+
+    let mut state = WorkingState {
+        branch: (!branch.is_empty()).then_some(branch),
+        commit,
+        stash: (!stash.is_empty()).then_some(stash),
+        untracked_tree: None,
+        untracked,
+    };
+    let mut keep = state.stash.clone().unwrap_or_else(|| state.commit.clone());
+    if !state.untracked.is_empty() {
+        let tree = untracked_tree(repo_path, &state.untracked)?;
+        let message = format!("repos checkpoint {}", id);
+        keep = git(
+            repo_path,
+            &["commit-tree", &tree, "-p", &keep, "-m", &message],
+        )?;
+        state.untracked_tree = Some(tree);
+    }
+    git(repo_path, &["update-ref", &checkpoint_ref(id), &keep])?;
+    Ok(state)
+}
+
+/// Return a working copy to a snapshot
+///
+/// Whatever the working copy holds now, including untracked files, is first
+/// saved as a stash entry, so nothing is lost. The branch is then reset to the
+/// snapshot's commit, created again if it was deleted, and the snapshot's
+/// uncommitted changes and untracked files are brought back.
+pub fn restore(repo_path: &str, id: &str, state: &WorkingState) -> Result<RestoreReport> {
+    let mut report = RestoreReport::default();
+
+    if !git(repo_path, &["status", "--porcelain"])?.is_empty() {
+        let message = format!("repos rollback {}: changes before rollback", id);
+        git(
+            repo_path,
+            &["stash", "push", "--include-untracked", "-m", &message],
+        )?;
+        report.backup = Some(git(repo_path, &["rev-parse", "stash@{0}"])?);
+    }
+
+    match &state.branch {
+        Some(branch) => git(repo_path, &["checkout", "-q", "-B", branch, &state.commit])?,
+        None => git(repo_path, &["checkout", "-q", "--detach", &state.commit])?,
+    };
+
+    if let Some(stash) = &state.stash {
+        git(repo_path, &["stash", "apply", "-q", stash])?;
+        report.reapplied_changes = true;
+    }
+
+    if let Some(tree) = &state.untracked_tree {
+        let source = format!("--source={}", tree);
+        let mut args = vec!["restore", "--worktree", source.as_str(), "--"];
+        args.extend(state.untracked.iter().map(String::as_str));
+        git(repo_path, &args)?;
+    }
+
+    Ok(report)
+}
+
+/// Delete the ref keeping the objects of checkpoint `id` alive
+pub fn drop_snapshot(repo_path: &str, id: &str) -> Result<()> {
+    git(repo_path, &["update-ref", "-d", &checkpoint_ref(id)])?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, String) {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().to_string_lossy().to_string();
+        for args in [
+            vec!["init", "-q", "-b", "main"],
+            vec!["config", "user.name", "Test"],
+            vec!["config", "user.email", "test@example.com"],
+        ] {
+            git(&path, &args).unwrap();
+        }
+        fs::write(temp_dir.path().join("lib.rs"), "v1").unwrap();
+        git(&path, &["add", "."]).unwrap();
+        git(&path, &["commit", "-q", "-m", "initial"]).unwrap();
+        (temp_dir, path)
+    }
+
+    fn read(dir: &Path, file: &str) -> String {
+        fs::read_to_string(dir.join(file)).unwrap()
+    }
+
+    #[test]
+    fn test_snapshot_and_restore() {
+        let (temp_dir, path) = setup();
+        let dir = temp_dir.path();
+        fs::write(dir.join("lib.rs"), "work in progress").unwrap();
+        fs::write(dir.join("notes.txt"), "todo").unwrap();
+
+        let state = snapshot(&path, "op-1").unwrap();
+        assert_eq!(state.branch.as_deref(), Some("main"));
+        assert!(state.stash.is_some());
+        assert_eq!(state.untracked, vec!["notes.txt"]);
+        assert!(state.untracked_tree.is_some());
+        assert_eq!(read(dir, "lib.rs"), "work in progress");
+
+        // An operation commits on a new branch and leaves a stray file behind
+        git(&path, &["checkout", "-q", "-b", "automated"]).unwrap();
+        fs::write(dir.join("lib.rs"), "rewritten").unwrap();
+        git(&path, &["commit", "-q", "-am", "codemod"]).unwrap();
+        git(&path, &["branch", "-q", "-f", "main", "automated"]).unwrap();
+        fs::write(dir.join("generated.rs"), "// generated").unwrap();
+
+        let report = restore(&path, "op-1", &state).unwrap();
+        assert!(report.backup.is_some());
+        assert!(report.reapplied_changes);
+        assert_eq!(git(&path, &["branch", "--show-current"]).unwrap(), "main");
+        assert_eq!(git(&path, &["rev-parse", "HEAD"]).unwrap(), state.commit);
+        assert_eq!(read(dir, "lib.rs"), "work in progress");
+        assert_eq!(read(dir, "notes.txt"), "todo");
+        assert!(!dir.join("generated.rs").exists());
+
+        // The stray file is kept in the backup stash
+        let backup = git(&path, &["stash", "list"]).unwrap();
+        assert!(backup.contains("repos rollback op-1"));
+
+        // Untracked files come back even when nothing had to be stashed first
+        git(&path, &["stash", "clear"]).unwrap();
+        git(&path, &["checkout", "-q", "-b", "clean"]).unwrap();
+        git(&path, &["add", "-A"]).unwrap();
+        git(&path, &["commit", "-q", "-m", "everything"]).unwrap();
+        let report = restore(&path, "op-1", &state).unwrap();
+        assert_eq!(report.backup, None);
+        assert_eq!(read(dir, "notes.txt"), "todo");
+        assert_eq!(read(dir, "lib.rs"), "work in progress");
+
+        drop_snapshot(&path, "op-1").unwrap();
+        assert!(
+            git(
+                &path,
+                &["rev-parse", "-q", "--verify", "refs/repos/checkpoints/op-1"]
+            )
+            .is_err()
+        );
+    }
+}
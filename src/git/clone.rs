@@ -7,57 +7,246 @@
 //! ## Functions
 //!
 //! - [`clone_repository`]: Clone a repository from its remote URL
+//! - [`clone_repository_with_progress`]: Clone, reporting git's progress as it goes
+//! - [`is_interrupted_clone`]: Detect a clone that stopped before it finished
 //! - [`remove_repository`]: Remove a cloned repository directory
+//! - [`trash_repository`]: Move a cloned repository directory aside instead
+//! - [`local_work`]: Find work in a clone that removing it would lose
 //!
 //! Both functions work with the [`Repository`] configuration type and
-//! provide detailed logging throughout the operation.
+//! provide detailed logging throughout the operation. Repositories with a
+//! `sparse` list are cloned without blobs outside those directories and with
+//! git sparse-checkout enabled. Extra `remotes` are added after cloning. Remote
+//! URLs and credentials follow the repository's `git` transport settings.
+//! A directory left behind by an interrupted clone is removed and cloned again.
 
 use crate::config::Repository;
 use anyhow::{Context, Result};
-use std::path::Path;
-use std::process::Command;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
 
-use super::common::Logger;
+use super::common::{LoggedOutput, Logger, command_line, git_lines};
+use super::remote::{
+    apply_remote_change, expected_remotes, list_remotes, remote_changes, url_remote_name,
+};
+use super::transport::RemoteAccess;
+
+/// What [`clone_repository`] did with a repository
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloneOutcome {
+    Cloned,
+    /// The directory already existed and was left alone
+    AlreadyCloned,
+    /// An interrupted clone was removed and the repository cloned again
+    Resumed,
+}
+
+/// One progress update of `git clone`, e.g. `Receiving objects:  45% (450/1000)`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CloneProgress {
+    /// Phase of the clone, e.g. `Receiving objects` or `Resolving deltas`
+    pub phase: String,
+    pub percent: u8,
+    /// What follows the percentage, e.g. `(450/1000), 1.20 MiB | 2.00 MiB/s`
+    pub detail: String,
+}
+
+impl CloneProgress {
+    /// Parse a line of git's progress output, including `remote:` lines
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = line.trim();
+        let line = line.strip_prefix("remote:").unwrap_or(line).trim();
+        let (phase, rest) = line.split_once(':')?;
+        let (percent, detail) = rest.split_once('%')?;
+        Some(CloneProgress {
+            phase: phase.trim().to_string(),
+            percent: percent.trim().parse().ok()?,
+            detail: detail
+                .trim()
+                .trim_end_matches(", done.")
+                .trim_end_matches(',')
+                .to_string(),
+        })
+    }
+}
+
+/// Whether `target_dir` holds a clone that stopped before checking out
+///
+/// That is a directory with nothing but a `.git` directory, with a remote set
+/// but whose HEAD does not point at a commit yet. A `git init` without a
+/// remote, or a clone with files in it, never qualifies.
+pub fn is_interrupted_clone(target_dir: &str) -> bool {
+    let Ok(entries) = std::fs::read_dir(target_dir) else {
+        return false;
+    };
+    let names: Vec<_> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name())
+        .collect();
+    if names.len() != 1 || names[0] != ".git" || !Path::new(target_dir).join(".git").is_dir() {
+        return false;
+    }
+
+    let succeeds = |args: &[&str]| {
+        Command::new("git")
+            .args(args)
+            .current_dir(target_dir)
+            .logged_output()
+            .is_ok_and(|output| output.status.success())
+    };
+    succeeds(&["config", "--get-regexp", r"^remote\..*\.url$"])
+        && !succeeds(&["rev-parse", "-q", "--verify", "HEAD^{commit}"])
+}
 
 /// Clone a repository from its URL to the target directory
-pub fn clone_repository(repo: &Repository) -> Result<()> {
+pub fn clone_repository(repo: &Repository) -> Result<CloneOutcome> {
+    clone_repository_with_progress(repo, |_| {})
+}
+
+/// Clone a repository, passing each progress update git prints to `on_progress`
+pub fn clone_repository_with_progress(
+    repo: &Repository,
+    mut on_progress: impl FnMut(&CloneProgress),
+) -> Result<CloneOutcome> {
     let logger = Logger;
     let target_dir = repo.get_target_dir();
+    let mut outcome = CloneOutcome::Cloned;
 
     // Check if directory already exists
     if Path::new(&target_dir).exists() {
-        logger.warn(repo, "Repository directory already exists, skipping");
-        return Ok(());
+        if !is_interrupted_clone(&target_dir) {
+            logger.warn(repo, "Repository directory already exists, skipping");
+            return Ok(CloneOutcome::AlreadyCloned);
+        }
+        logger.warn(repo, "Found an interrupted clone, cloning again");
+        std::fs::remove_dir_all(&target_dir).context("Failed to remove the interrupted clone")?;
+        outcome = CloneOutcome::Resumed;
     }
 
-    let mut args = vec!["clone"];
+    let access = RemoteAccess::for_repository(repo, None);
+    let url = access.remote_url(&repo.url);
+
+    let mut args = vec!["clone", "--progress", "--origin", url_remote_name(repo)];
+
+    // Partial clone: blobs outside the sparse directories are never downloaded
+    if !repo.sparse.is_empty() {
+        args.extend_from_slice(&["--filter=blob:none", "--sparse"]);
+    }
 
     // Add branch flag if a branch is specified
     if let Some(branch) = &repo.branch {
         args.extend_from_slice(&["-b", branch]);
-        logger.info(
-            repo,
-            &format!("Cloning branch '{}' from {}", branch, repo.url),
-        );
+        logger.info(repo, &format!("Cloning branch '{}' from {}", branch, url));
     } else {
-        logger.info(repo, &format!("Cloning default branch from {}", repo.url));
+        logger.info(repo, &format!("Cloning default branch from {}", url));
     }
 
     // Add repository URL and target directory
-    args.push(&repo.url);
+    args.push(&url);
     args.push(&target_dir);
 
-    let output = Command::new("git")
-        .args(&args)
-        .output()
+    let mut command = Command::new("git");
+    command.args(&args);
+    access.configure(&mut command, &url)?;
+    log::debug!("Running {}", command_line(&command));
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to execute git clone command")?;
+
+    // Progress lines end in a carriage return; everything else is kept for errors
+    let mut stderr = child
+        .stderr
+        .take()
+        .context("Failed to read git clone output")?;
+    let mut messages = String::new();
+    let mut pending = Vec::new();
+    let mut chunk = [0u8; 4096];
+    loop {
+        let read = stderr
+            .read(&mut chunk)
+            .context("Failed to read git clone output")?;
+        if read == 0 {
+            break;
+        }
+        for &byte in &chunk[..read] {
+            if byte != b'\r' && byte != b'\n' {
+                pending.push(byte);
+                continue;
+            }
+            let line = String::from_utf8_lossy(&pending).to_string();
+            pending.clear();
+            match CloneProgress::parse(&line) {
+                Some(progress) => on_progress(&progress),
+                None if !line.trim().is_empty() => {
+                    messages.push_str(line.trim());
+                    messages.push('\n');
+                }
+                None => {}
+            }
+        }
+    }
+    messages.push_str(String::from_utf8_lossy(&pending).trim());
+
+    let status = child
+        .wait()
         .context("Failed to execute git clone command")?;
+    log::trace!("git clone exited with {}: {}", status, messages.trim());
+    if !status.success() {
+        anyhow::bail!("Failed to clone repository: {}", messages.trim());
+    }
+
+    if !repo.sparse.is_empty() {
+        set_sparse_checkout(&target_dir, &repo.sparse)?;
+        logger.info(
+            repo,
+            &format!("Sparse checkout of {}", repo.sparse.join(", ")),
+        );
+    }
+
+    add_remotes(repo, &access, &target_dir, &logger)?;
+
+    logger.success(repo, "Successfully cloned");
+    Ok(outcome)
+}
+
+/// Add the repository's extra `remotes` to a fresh clone
+///
+/// A remote that cannot be added is reported without failing the clone.
+fn add_remotes(
+    repo: &Repository,
+    access: &RemoteAccess,
+    target_dir: &str,
+    logger: &Logger,
+) -> Result<()> {
+    let actual = list_remotes(target_dir)?;
+    for change in remote_changes(&actual, &expected_remotes(repo, access), false) {
+        if let Err(e) = apply_remote_change(target_dir, &change) {
+            logger.warn(repo, &e.to_string());
+        }
+    }
+    Ok(())
+}
+
+/// Restrict the working tree to the given directories (cone mode)
+///
+/// Files at the repository root are always checked out as well.
+pub fn set_sparse_checkout(repo_path: &str, directories: &[String]) -> Result<()> {
+    let output = Command::new("git")
+        .args(["sparse-checkout", "set", "--cone", "--"])
+        .args(directories)
+        .current_dir(repo_path)
+        .logged_output()
+        .context("Failed to execute git sparse-checkout command")?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("Failed to clone repository: {}", stderr);
+        anyhow::bail!("Failed to configure sparse checkout: {}", stderr);
     }
 
-    logger.success(repo, "Successfully cloned");
     Ok(())
 }
 
@@ -75,3 +264,101 @@ pub fn remove_repository(repo: &Repository) -> Result<()> {
         anyhow::bail!("Repository directory does not exist: {}", target_dir);
     }
 }
+
+/// Move a cloned repository directory into `trash_dir` instead of deleting it
+///
+/// The directory is renamed to `<name>-<timestamp>` so repeated removals of the
+/// same repository do not collide. Returns the new location.
+pub fn trash_repository(repo: &Repository, trash_dir: &Path) -> Result<PathBuf> {
+    let logger = Logger;
+    let target_dir = repo.get_target_dir();
+
+    if !Path::new(&target_dir).exists() {
+        logger.info(repo, "Directory does not exist");
+        anyhow::bail!("Repository directory does not exist: {}", target_dir);
+    }
+
+    std::fs::create_dir_all(trash_dir).context("Failed to create trash directory")?;
+    let destination = trash_dir.join(format!(
+        "{}-{}",
+        repo.name,
+        chrono::Local::now().format("%Y%m%d-%H%M%S%.3f")
+    ));
+    std::fs::rename(&target_dir, &destination).with_context(|| {
+        format!(
+            "Failed to move repository directory to {}",
+            destination.display()
+        )
+    })?;
+
+    logger.success(repo, &format!("Moved to {}", destination.display()));
+    Ok(destination)
+}
+
+/// Work in a local clone that exists nowhere else
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LocalWork {
+    /// Modified, staged, or untracked files
+    pub uncommitted: bool,
+    /// Number of stash entries
+    pub stashes: usize,
+    /// Local branches with commits not on any remote-tracking branch
+    pub unpushed_branches: Vec<String>,
+}
+
+impl LocalWork {
+    pub fn is_empty(&self) -> bool {
+        !self.uncommitted && self.stashes == 0 && self.unpushed_branches.is_empty()
+    }
+
+    /// One-line description, e.g. `uncommitted changes, 2 stashes`
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if self.uncommitted {
+            parts.push("uncommitted changes".to_string());
+        }
+        match self.stashes {
+            0 => {}
+            1 => parts.push("1 stash".to_string()),
+            n => parts.push(format!("{} stashes", n)),
+        }
+        if !self.unpushed_branches.is_empty() {
+            parts.push(format!(
+                "unpushed branches: {}",
+                self.unpushed_branches.join(", ")
+            ));
+        }
+        parts.join(", ")
+    }
+}
+
+/// Find uncommitted changes, stashes, and unpushed branches in a clone
+///
+/// Directories that are not git work trees have nothing to inspect and yield
+/// an empty [`LocalWork`].
+pub fn local_work(repo_path: &str) -> Result<LocalWork> {
+    if !Path::new(repo_path).join(".git").exists() {
+        return Ok(LocalWork::default());
+    }
+
+    let stashes = git_lines(repo_path, &["stash", "list"])?.len();
+    let mut unpushed_branches = Vec::new();
+    for branch in git_lines(
+        repo_path,
+        &["for-each-ref", "--format=%(refname:short)", "refs/heads"],
+    )? {
+        let ahead = git_lines(
+            repo_path,
+            &["rev-list", "--count", &branch, "--not", "--remotes"],
+        )?;
+        if ahead.first().is_some_and(|count| count != "0") {
+            unpushed_branches.push(branch);
+        }
+    }
+
+    Ok(LocalWork {
+        uncommitted: super::has_changes(repo_path)?,
+        stashes,
+        unpushed_branches,
+    })
+}
@@ -4,14 +4,19 @@
 //! such as logging and error handling helpers.
 
 use crate::config::Repository;
+use crate::logging;
+use anyhow::{Context, Result};
 use colored::*;
+use log::Level;
+use std::process::{Command, Output};
 
 /// Logger for git operations with consistent formatting
 ///
 /// Provides standardized logging methods for git operations, ensuring
 /// consistent output formatting across all git workflows. Each log
 /// message is prefixed with the repository name in cyan/bold for
-/// easy identification.
+/// easy identification. Messages go through [`crate::logging`], so they
+/// honor `--quiet`, `-v` and `REPOS_LOG`.
 ///
 /// ## Example
 ///
@@ -29,19 +34,94 @@ pub struct Logger;
 
 impl Logger {
     pub fn info(&self, repo: &Repository, msg: &str) {
-        println!("{} | {}", repo.name.cyan().bold(), msg);
+        logging::status(Level::Info, &repo.name, msg);
     }
 
     pub fn success(&self, repo: &Repository, msg: &str) {
-        println!("{} | {}", repo.name.cyan().bold(), msg.green());
+        logging::status(Level::Info, &repo.name, msg.green());
     }
 
     pub fn warn(&self, repo: &Repository, msg: &str) {
-        println!("{} | {}", repo.name.cyan().bold(), msg.yellow());
+        logging::status(Level::Warn, &repo.name, msg.yellow());
     }
 
-    #[allow(dead_code)]
     pub fn error(&self, repo: &Repository, msg: &str) {
-        eprintln!("{} | {}", repo.name.cyan().bold(), msg.red());
+        logging::status(Level::Error, &repo.name, msg.red());
     }
+
+    /// Diagnostic detail, only shown with `-v`
+    pub fn debug(&self, repo: &Repository, msg: &str) {
+        logging::with_repository(&repo.name, || log::debug!("{}", msg));
+    }
+}
+
+/// The program and arguments of a command, as shown in logs
+///
+/// Environment variables are left out, since they carry tokens for remotes.
+pub(crate) fn command_line(command: &Command) -> String {
+    std::iter::once(command.get_program())
+        .chain(command.get_args())
+        .map(|arg| format!("{:?}", arg))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Run a command like [`Command::output`], logging the invocation at debug
+/// level and its exit status at trace level
+pub(crate) trait LoggedOutput {
+    fn logged_output(&mut self) -> std::io::Result<Output>;
+}
+
+impl LoggedOutput for Command {
+    fn logged_output(&mut self) -> std::io::Result<Output> {
+        log::debug!("Running {}", command_line(self));
+        let output = self.output()?;
+        log::trace!(
+            "{:?} exited with {}: {}",
+            self.get_program(),
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+        Ok(output)
+    }
+}
+
+/// Run git in `repo_path` and return its trimmed output, failing with its error message
+pub(crate) fn git(repo_path: &str, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(repo_path)
+        .logged_output()
+        .with_context(|| format!("Failed to execute git {} command", args[0]))?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {} failed: {}",
+            args[0],
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Run git in `repo_path` and return its non-empty output lines
+pub fn git_lines<S: AsRef<str>>(repo_path: &str, args: &[S]) -> Result<Vec<String>> {
+    let output = Command::new("git")
+        .args(args.iter().map(AsRef::as_ref))
+        .current_dir(repo_path)
+        .logged_output()
+        .with_context(|| format!("Failed to execute git {} command", args[0].as_ref()))?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to inspect repository: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
 }
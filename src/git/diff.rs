@@ -0,0 +1,91 @@
+//! Diffs of uncommitted changes, including files git does not track yet
+//!
+//! The working tree is staged into a throwaway index, so new files show up in
+//! the diff just as they would in the commit `repos pr` creates, while the
+//! repository's own index is left untouched.
+
+use super::common::LoggedOutput;
+use anyhow::{Context, Result};
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Run `git diff` of all working tree changes against `HEAD`
+///
+/// `args` are passed on to `git diff`, e.g. `--stat` or `--name-only`.
+pub fn workspace_diff(repo_path: &str, args: &[&str]) -> Result<String> {
+    let index_dir = tempfile::tempdir().context("Failed to create temporary directory")?;
+    let index = index_dir.path().join("index");
+
+    git_with_index(repo_path, &index, &["read-tree", "HEAD"])?;
+    git_with_index(repo_path, &index, &["add", "--all"])?;
+
+    let mut diff_args = vec!["diff", "--cached"];
+    diff_args.extend_from_slice(args);
+    diff_args.push("HEAD");
+    git_with_index(repo_path, &index, &diff_args)
+}
+
+/// Paths changed in the working tree, sorted
+pub fn workspace_files(repo_path: &str) -> Result<Vec<String>> {
+    let mut files: Vec<String> = workspace_diff(repo_path, &["--name-only"])?
+        .lines()
+        .map(str::to_string)
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+fn git_with_index(repo_path: &str, index: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .env("GIT_INDEX_FILE", index)
+        .current_dir(repo_path)
+        .logged_output()
+        .context("Failed to execute git command")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Stable patch ID of a unified diff, or `None` when the diff is empty
+///
+/// Patch IDs ignore line numbers and whitespace, so the same change made on
+/// top of slightly different commits has the same ID.
+pub fn patch_id(repo_path: &str, diff: &str) -> Result<Option<String>> {
+    let mut child = Command::new("git")
+        .args(["patch-id", "--stable"])
+        .current_dir(repo_path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to execute git patch-id command")?;
+
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(diff.as_bytes())
+        .context("Failed to write diff to git patch-id")?;
+    let output = child.wait_with_output()?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to compute patch ID: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .next()
+        .map(str::to_string))
+}
@@ -0,0 +1,144 @@
+//! Bare mirror clones for backups
+//!
+//! `repos mirror` keeps a `git clone --mirror` of every repository in a backup
+//! directory. Mirrors have no working tree and track every ref of the remote,
+//! including branches and tags deleted upstream, which `--prune` removes.
+//!
+//! ## Functions
+//!
+//! - [`mirror_repository`]: Create a mirror, or bring an existing one up to date
+//! - [`is_mirror`]: Whether a directory is a mirror clone
+//! - [`fsck`]: Verify the objects of a mirror
+
+use crate::config::Repository;
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Command;
+
+use super::common::LoggedOutput;
+use super::transport::RemoteAccess;
+
+/// What [`mirror_repository`] did with a repository
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MirrorOutcome {
+    Created,
+    /// An existing mirror was fetched; `changed` refs were added, moved or pruned
+    Updated {
+        changed: usize,
+    },
+}
+
+fn git_dir(path: &Path) -> Command {
+    let mut command = Command::new("git");
+    command.arg("--git-dir").arg(path);
+    command
+}
+
+fn last_line(stderr: &[u8]) -> String {
+    String::from_utf8_lossy(stderr)
+        .lines()
+        .rev()
+        .find(|line| !line.trim().is_empty())
+        .unwrap_or_default()
+        .trim()
+        .to_string()
+}
+
+/// Whether `path` is a bare clone made with `git clone --mirror`
+pub fn is_mirror(path: &Path) -> bool {
+    git_dir(path)
+        .args(["config", "--bool", "remote.origin.mirror"])
+        .logged_output()
+        .is_ok_and(|output| {
+            output.status.success() && String::from_utf8_lossy(&output.stdout).trim() == "true"
+        })
+}
+
+fn list_refs(path: &Path) -> Result<BTreeMap<String, String>> {
+    let output = git_dir(path)
+        .args(["for-each-ref", "--format=%(objectname) %(refname)"])
+        .logged_output()
+        .context("Failed to execute git for-each-ref command")?;
+    if !output.status.success() {
+        anyhow::bail!("Failed to list refs: {}", last_line(&output.stderr));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .map(|(object, name)| (name.to_string(), object.to_string()))
+        .collect())
+}
+
+/// Mirror a repository into `path`, or update the mirror already there
+///
+/// Updates fetch every ref with `--prune`, after pointing `origin` at the
+/// configured URL in case it changed. A directory at `path` that is not a
+/// mirror is an error rather than being replaced.
+pub fn mirror_repository(repo: &Repository, path: &Path) -> Result<MirrorOutcome> {
+    let access = RemoteAccess::for_repository(repo, None);
+    let url = access.remote_url(&repo.url);
+
+    if !path.exists() {
+        let mut command = Command::new("git");
+        command
+            .args(["clone", "--mirror", "--quiet", &url])
+            .arg(path);
+        access.configure(&mut command, &url)?;
+        let output = command
+            .logged_output()
+            .context("Failed to execute git clone command")?;
+        if !output.status.success() {
+            // Do not leave a partial mirror that the next run would reject
+            let _ = std::fs::remove_dir_all(path);
+            anyhow::bail!("Failed to mirror repository: {}", last_line(&output.stderr));
+        }
+        return Ok(MirrorOutcome::Created);
+    }
+
+    if !is_mirror(path) {
+        anyhow::bail!("{} exists but is not a mirror clone", path.display());
+    }
+
+    let output = git_dir(path)
+        .args(["remote", "set-url", "origin", &url])
+        .logged_output()
+        .context("Failed to execute git remote command")?;
+    if !output.status.success() {
+        anyhow::bail!("Failed to update origin: {}", last_line(&output.stderr));
+    }
+
+    let before = list_refs(path)?;
+    let mut command = git_dir(path);
+    command.args(["fetch", "--prune", "--quiet", "origin"]);
+    access.configure(&mut command, &url)?;
+    let output = command
+        .logged_output()
+        .context("Failed to execute git fetch command")?;
+    if !output.status.success() {
+        anyhow::bail!("Failed to update mirror: {}", last_line(&output.stderr));
+    }
+    let after = list_refs(path)?;
+
+    let changed = after
+        .iter()
+        .filter(|(name, object)| before.get(*name) != Some(*object))
+        .count()
+        + before
+            .keys()
+            .filter(|name| !after.contains_key(*name))
+            .count();
+    Ok(MirrorOutcome::Updated { changed })
+}
+
+/// Check the connectivity and validity of a mirror's objects with `git fsck`
+pub fn fsck(path: &Path) -> Result<()> {
+    let output = git_dir(path)
+        .args(["fsck", "--no-progress", "--no-dangling"])
+        .logged_output()
+        .context("Failed to execute git fsck command")?;
+    if !output.status.success() {
+        anyhow::bail!("git fsck failed: {}", last_line(&output.stderr));
+    }
+    Ok(())
+}
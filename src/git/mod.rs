@@ -4,20 +4,86 @@
 //!
 //! ## Sub-modules
 //!
+//! - [`checkpoint`]: Snapshots of working copies for `repos rollback`
+//!   - `snapshot()` - Record the branch, commit and uncommitted changes
+//!   - `restore()` - Return to a snapshot, stashing the current changes first
+//!   - `drop_snapshot()` - Release a snapshot's objects
+//!
+//! - [`checkout`]: Switching working copies between branches
+//!   - `branch_location()` - Whether a branch exists locally or only on `origin`
+//!   - `switch_branch()` - Check out a branch, reporting files in the way
+//!   - `start_point()` - The ref a new branch is created from
+//!
 //! - [`clone`]: Repository cloning and removal operations
 //!   - `clone_repository()` - Clone a repository from URL
 //!   - `remove_repository()` - Remove a cloned repository directory
+//!   - `set_sparse_checkout()` - Restrict a clone to some directories
+//!   - `trash_repository()` - Move a clone aside instead of deleting it
+//!   - `local_work()` - Find uncommitted, stashed, or unpushed work
+//!
+//! - [`diff`]: Uncommitted changes, including untracked files
+//!   - `workspace_diff()` - `git diff` of the working tree against `HEAD`
+//!   - `workspace_files()` - Paths changed in the working tree
+//!   - `patch_id()` - Identify a change independently of where it was made
+//!
+//! - [`mirror`]: Bare mirror clones for backups
+//!   - `mirror_repository()` - Create a mirror or fetch it with pruning
+//!   - `is_mirror()` - Recognize a mirror clone
+//!   - `fsck()` - Verify a mirror's objects
 //!
 //! - [`pull_request`]: Git operations specific to pull request workflows
 //!   - `has_changes()` - Check for uncommitted changes
 //!   - `create_and_checkout_branch()` - Create and switch to new branch
+//!   - `reset_and_checkout_branch()` - Switch to a branch moved to the current commit
 //!   - `add_all_changes()` - Stage all changes
 //!   - `commit_changes()` - Commit staged changes
 //!   - `push_branch()` - Push branch to remote
 //!   - `get_default_branch()` - Get repository's default branch
+//!   - `get_remote_default_branch()` - Get `origin/HEAD` from a local clone
+//!
+//! - [`rebase`]: Keeping branches up to date with their base
+//!   - `fetch()` - Update remote-tracking branches
+//!   - `rebase()` - Rebase, reporting conflicts instead of failing
+//!   - `abort_rebase()` - Abandon a rebase in progress
+//!   - `force_push_with_lease()` - Publish a rebased branch
+//!
+//! - [`remote`]: Refreshing remote state without touching working trees
+//!   - `fetch_all()` - Fetch every remote, reporting new, moved and pruned refs
+//!   - `upstream_summary()` - Commits on remote branches since a date
+//!   - `push()` - Push a branch and tags, reporting pushed, up to date and rejected refs
+//!   - `expected_remotes()` / `remote_changes()` - Compare a clone's remotes with the config
+//!
+//! - [`stash`]: Setting changes aside across repositories
+//!   - `stash_push()` - Stash uncommitted changes, reporting whether there were any
+//!   - `stash_pop()` - Restore the newest stash made by `stash_push()`
+//!
+//! - [`status`]: The state of a working copy at a glance
+//!   - `working_status()` - Branch, distance from upstream and changed files
+//!   - `pull()` - Fast-forward the checked-out branch
+//!
+//! - [`submodule`]: Repositories with submodules
+//!   - `submodule_status()` - Recorded commit and state of every submodule
+//!   - `update_submodules()` - Initialize and check out submodules
+//!   - `sync_submodules()` / `fetch_submodules()` - Follow URL changes and fetch submodule remotes
+//!
+//! - [`tag`]: Release tags
+//!   - `latest_version_tag()` - Highest `MAJOR.MINOR.PATCH` tag, with `Version` and `Bump`
+//!   - `commits_since()` - Subjects of the commits since a tag
+//!   - `create_tag()` / `push_tag()` / `delete_tag()` - Manage annotated tags
+//!
+//! - [`transport`]: How git reaches remotes
+//!   - `GitTransport` - Forced protocol, URL rewrites, HTTPS token and SSH key settings
+//!   - `RemoteAccess` - The effective settings and token of one repository
+//!
+//! - [`worktree`]: Checking out refs beside the main working copy
+//!   - `resolve_ref()` - Resolve a branch or tag, fetching it if needed
+//!   - `add_worktree()` / `remove_worktree()` - Manage detached worktrees
+//!   - `open_worktree()` / `close_worktree()` - Per-repository worktrees kept between commands
+//!   - `TemporaryWorktree` - A worktree removed again when dropped
 //!
 //! - [`common`]: Shared utilities and helpers
 //!   - `Logger` - Consistent logging for git operations
+//!   - `git_lines()` - Non-empty output lines of a git command
 //!
 //! ## Benefits of this organization
 //!
@@ -26,14 +92,55 @@
 //! - **Maintainability**: Clear separation of concerns between different git operations
 //! - **Backward compatibility**: All functions are re-exported at the module level
 
+pub mod checkout;
+pub mod checkpoint;
 pub mod clone;
 pub mod common;
+pub mod diff;
+pub mod mirror;
 pub mod pull_request;
+pub mod rebase;
+pub mod remote;
+pub mod stash;
+pub mod status;
+pub mod submodule;
+pub mod tag;
+pub mod transport;
+pub mod worktree;
 
 // Re-export all public functions to maintain backward compatibility
-pub use clone::{clone_repository, remove_repository};
-pub use common::Logger;
+pub use checkout::{BranchLocation, SwitchOutcome, branch_location, start_point, switch_branch};
+pub use checkpoint::{RestoreReport, WorkingState, drop_snapshot, restore, snapshot};
+pub use clone::{
+    CloneOutcome, CloneProgress, LocalWork, clone_repository, clone_repository_with_progress,
+    is_interrupted_clone, local_work, remove_repository, set_sparse_checkout, trash_repository,
+};
+pub use common::{Logger, git_lines};
+pub use diff::{patch_id, workspace_diff, workspace_files};
+pub use mirror::{MirrorOutcome, fsck, is_mirror, mirror_repository};
 pub use pull_request::{
-    add_all_changes, checkout_branch, commit_changes, create_and_checkout_branch,
-    get_current_branch, get_default_branch, has_changes, push_branch,
+    add_all_changes, checkout_branch, checkout_commit, commit_changes, create_and_checkout_branch,
+    get_current_branch, get_default_branch, get_head_commit, get_remote_default_branch,
+    has_changes, push_branch, reset_and_checkout_branch,
+};
+pub use rebase::{RebaseOutcome, abort_rebase, fetch, force_push_with_lease, rebase};
+pub use remote::{
+    PushOptions, PushReport, RefUpdates, RemoteChange, UpstreamSummary, apply_remote_change,
+    expected_remotes, fetch_all, list_remotes, push, remote_changes, upstream_summary,
+    url_remote_name,
+};
+pub use stash::{stash_pop, stash_push};
+pub use status::{WorkingStatus, pull, working_status};
+pub use submodule::{
+    Submodule, SubmoduleState, fetch_submodules, has_submodules, submodule_status, sync_submodules,
+    update_submodules,
+};
+pub use tag::{
+    Bump, Version, commits_since, create_tag, delete_tag, fetch_tags, latest_version_tag, push_tag,
+    tag_exists,
+};
+pub use transport::{GitTransport, Protocol, RemoteAccess};
+pub use worktree::{
+    TemporaryWorktree, add_worktree, close_worktree, open_worktree, remove_worktree, resolve_ref,
+    worktree_path,
 };
@@ -15,7 +15,10 @@
 //! ## Additional Utilities
 //!
 //! - [`get_default_branch`] - Determine the repository's default branch
+//! - [`get_remote_default_branch`] - Read `origin/HEAD` from a local clone
 
+use super::common::LoggedOutput;
+use super::transport::{RemoteAccess, remote_url};
 use anyhow::{Context, Result};
 use std::process::Command;
 
@@ -26,7 +29,7 @@ pub fn has_changes(repo_path: &str) -> Result<bool> {
         .arg("status")
         .arg("--porcelain")
         .current_dir(repo_path)
-        .output()
+        .logged_output()
         .context("Failed to execute git status command")?;
 
     if !output.status.success() {
@@ -48,7 +51,7 @@ pub fn create_and_checkout_branch(repo_path: &str, branch_name: &str) -> Result<
         .arg("-b")
         .arg(branch_name)
         .current_dir(repo_path)
-        .output()
+        .logged_output()
         .context("Failed to execute git checkout command")?;
 
     if !output.status.success() {
@@ -62,6 +65,29 @@ pub fn create_and_checkout_branch(repo_path: &str, branch_name: &str) -> Result<
     Ok(())
 }
 
+/// Check out a branch at the current commit, creating it or moving it there
+///
+/// Uncommitted changes are carried over, like with [`create_and_checkout_branch`].
+pub fn reset_and_checkout_branch(repo_path: &str, branch_name: &str) -> Result<()> {
+    let output = Command::new("git")
+        .arg("checkout")
+        .arg("-B")
+        .arg(branch_name)
+        .current_dir(repo_path)
+        .logged_output()
+        .context("Failed to execute git checkout command")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to reset and checkout branch '{}': {}",
+            branch_name,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    Ok(())
+}
+
 /// Add all changes to the staging area
 pub fn add_all_changes(repo_path: &str) -> Result<()> {
     // Add all changes using git add .
@@ -69,7 +95,7 @@ pub fn add_all_changes(repo_path: &str) -> Result<()> {
         .arg("add")
         .arg(".")
         .current_dir(repo_path)
-        .output()
+        .logged_output()
         .context("Failed to execute git add command")?;
 
     if !output.status.success() {
@@ -90,7 +116,7 @@ pub fn commit_changes(repo_path: &str, message: &str) -> Result<()> {
         .arg("-m")
         .arg(message)
         .current_dir(repo_path)
-        .output()
+        .logged_output()
         .context("Failed to execute git commit command")?;
 
     if !output.status.success() {
@@ -104,15 +130,22 @@ pub fn commit_changes(repo_path: &str, message: &str) -> Result<()> {
 }
 
 /// Push a branch to remote and set upstream
-pub fn push_branch(repo_path: &str, branch_name: &str) -> Result<()> {
+///
+/// `access` supplies the SSH key or HTTPS token configured for the repository.
+pub fn push_branch(repo_path: &str, branch_name: &str, access: &RemoteAccess) -> Result<()> {
     // Push branch using git push
-    let output = Command::new("git")
+    let mut command = Command::new("git");
+    command
         .arg("push")
         .arg("--set-upstream")
         .arg("origin")
         .arg(branch_name)
-        .current_dir(repo_path)
-        .output()
+        .current_dir(repo_path);
+    if let Some(url) = remote_url(repo_path, "origin") {
+        access.configure(&mut command, &url)?;
+    }
+    let output = command
+        .logged_output()
         .context("Failed to execute git push command")?;
 
     if !output.status.success() {
@@ -131,26 +164,15 @@ pub fn push_branch(repo_path: &str, branch_name: &str) -> Result<()> {
 
 /// Get the default branch of a repository
 pub fn get_default_branch(repo_path: &str) -> Result<String> {
-    // Try to get the default branch using git symbolic-ref
-    let output = Command::new("git")
-        .args(["symbolic-ref", "refs/remotes/origin/HEAD"])
-        .current_dir(repo_path)
-        .output();
-
-    if let Ok(output) = output
-        && output.status.success()
-    {
-        let branch_ref = String::from_utf8_lossy(&output.stdout).trim().to_string();
-        if let Some(branch) = branch_ref.strip_prefix("refs/remotes/origin/") {
-            return Ok(branch.to_string());
-        }
+    if let Some(branch) = get_remote_default_branch(repo_path) {
+        return Ok(branch);
     }
 
     // Fallback: try to get the current branch
     let output = Command::new("git")
         .args(["branch", "--show-current"])
         .current_dir(repo_path)
-        .output()
+        .logged_output()
         .context("Failed to execute git branch command")?;
 
     if output.status.success() {
@@ -164,12 +186,32 @@ pub fn get_default_branch(repo_path: &str) -> Result<String> {
     Ok(crate::constants::git::FALLBACK_BRANCH.to_string())
 }
 
+/// Get the default branch recorded for `origin` in a local clone
+///
+/// Returns `None` when the clone is missing or `origin/HEAD` is not set.
+pub fn get_remote_default_branch(repo_path: &str) -> Option<String> {
+    let output = Command::new("git")
+        .args(["symbolic-ref", "refs/remotes/origin/HEAD"])
+        .current_dir(repo_path)
+        .logged_output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .strip_prefix("refs/remotes/origin/")
+        .map(str::to_string)
+}
+
 /// Get the current branch name
 pub fn get_current_branch(repo_path: &str) -> Result<String> {
     let output = Command::new("git")
         .args(["branch", "--show-current"])
         .current_dir(repo_path)
-        .output()
+        .logged_output()
         .context("Failed to execute git branch command")?;
 
     if !output.status.success() {
@@ -187,12 +229,49 @@ pub fn get_current_branch(repo_path: &str) -> Result<String> {
     Ok(branch)
 }
 
+/// Get the commit hash that HEAD currently points to
+pub fn get_head_commit(repo_path: &str) -> Result<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(repo_path)
+        .logged_output()
+        .context("Failed to execute git rev-parse command")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to resolve HEAD: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Checkout a commit with a detached HEAD
+pub fn checkout_commit(repo_path: &str, commit: &str) -> Result<()> {
+    let output = Command::new("git")
+        .args(["checkout", "--detach", commit])
+        .current_dir(repo_path)
+        .logged_output()
+        .context("Failed to execute git checkout command")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to checkout commit '{}': {}",
+            commit,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    Ok(())
+}
+
 /// Checkout an existing branch
 pub fn checkout_branch(repo_path: &str, branch_name: &str) -> Result<()> {
     let output = Command::new("git")
         .args(["checkout", branch_name])
         .current_dir(repo_path)
-        .output()
+        .logged_output()
         .context("Failed to execute git checkout command")?;
 
     if !output.status.success() {
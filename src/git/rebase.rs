@@ -0,0 +1,138 @@
+//! Git operations for keeping branches up to date with their base
+//!
+//! ## Functions
+//!
+//! - [`fetch`]: Update remote-tracking branches from `origin`
+//! - [`rebase`]: Rebase the checked-out branch, reporting conflicts instead of failing
+//! - [`abort_rebase`]: Abandon a rebase in progress
+//! - [`force_push_with_lease`]: Publish a rebased branch
+
+use super::common::LoggedOutput;
+use super::transport::{RemoteAccess, remote_url};
+use anyhow::{Context, Result};
+use std::process::Command;
+
+/// Result of a rebase that did not fail outright
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RebaseOutcome {
+    /// The branch already contained the upstream
+    UpToDate,
+    /// The branch was replayed onto the upstream
+    Rebased,
+    /// The rebase stopped on conflicts in these files and is still in progress
+    Conflicts(Vec<String>),
+}
+
+/// Fetch `origin`, pruning deleted remote branches
+pub fn fetch(repo_path: &str) -> Result<()> {
+    let output = Command::new("git")
+        .args(["fetch", "--prune", "origin"])
+        .current_dir(repo_path)
+        .logged_output()
+        .context("Failed to execute git fetch command")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to fetch from 'origin': {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(())
+}
+
+/// Rebase the checked-out branch onto `upstream`
+///
+/// Clean rebases run to completion. When a commit does not apply, the rebase is
+/// left in progress and the conflicting files are returned, so the caller can
+/// decide whether to abort it or hand it to the user.
+pub fn rebase(repo_path: &str, upstream: &str) -> Result<RebaseOutcome> {
+    let before = super::get_head_commit(repo_path)?;
+    let output = Command::new("git")
+        .args(["rebase", upstream])
+        .current_dir(repo_path)
+        .logged_output()
+        .context("Failed to execute git rebase command")?;
+
+    if output.status.success() {
+        let after = super::get_head_commit(repo_path)?;
+        return Ok(if before == after {
+            RebaseOutcome::UpToDate
+        } else {
+            RebaseOutcome::Rebased
+        });
+    }
+
+    let conflicts = conflicted_files(repo_path)?;
+    if conflicts.is_empty() {
+        // Not a conflict, e.g. an unknown upstream; never leave a half-done rebase behind
+        let _ = abort_rebase(repo_path);
+        anyhow::bail!(
+            "Failed to rebase onto '{}': {}",
+            upstream,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(RebaseOutcome::Conflicts(conflicts))
+}
+
+/// Abandon a rebase in progress, restoring the branch as it was
+pub fn abort_rebase(repo_path: &str) -> Result<()> {
+    let output = Command::new("git")
+        .args(["rebase", "--abort"])
+        .current_dir(repo_path)
+        .logged_output()
+        .context("Failed to execute git rebase command")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to abort rebase: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(())
+}
+
+/// Push a rewritten branch, refusing if the remote has commits we have not seen
+pub fn force_push_with_lease(
+    repo_path: &str,
+    branch_name: &str,
+    access: &RemoteAccess,
+) -> Result<()> {
+    let mut command = Command::new("git");
+    command
+        .args(["push", "--force-with-lease", "origin", branch_name])
+        .current_dir(repo_path);
+    if let Some(url) = remote_url(repo_path, "origin") {
+        access.configure(&mut command, &url)?;
+    }
+    let output = command
+        .logged_output()
+        .context("Failed to execute git push command")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to push branch '{}' to remote 'origin': {}",
+            branch_name,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(())
+}
+
+/// Files with unresolved merge conflicts
+fn conflicted_files(repo_path: &str) -> Result<Vec<String>> {
+    let output = Command::new("git")
+        .args(["diff", "--name-only", "--diff-filter=U"])
+        .current_dir(repo_path)
+        .logged_output()
+        .context("Failed to execute git diff command")?;
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::to_string)
+        .collect())
+}
@@ -0,0 +1,515 @@
+//! Git operations for refreshing remote state without touching working trees
+//!
+//! ## Functions
+//!
+//! - [`fetch_all`]: Fetch every remote and report the refs it added, moved or pruned
+//! - [`upstream_summary`]: Summarize the commits on remote branches since a date
+//! - [`push`]: Push a branch, and optionally all tags, to `origin`
+//! - [`list_remotes`]: The configured remotes and their fetch URLs
+//! - [`expected_remotes`]: The remotes the config declares for a repository
+//! - [`remote_changes`] / [`apply_remote_change`]: Reconcile remotes with the expected ones
+
+use super::common::LoggedOutput;
+use super::transport::{RemoteAccess, remote_url};
+use crate::config::Repository;
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::process::Command;
+
+/// Refs that changed during a fetch
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RefUpdates {
+    /// Remote-tracking branches that did not exist before, e.g. `origin/feature`
+    pub new_branches: Vec<String>,
+    /// Remote-tracking branches that point to another commit
+    pub updated_branches: Vec<String>,
+    /// Remote-tracking branches removed by `--prune`
+    pub pruned_branches: Vec<String>,
+    pub new_tags: Vec<String>,
+}
+
+impl RefUpdates {
+    pub fn is_empty(&self) -> bool {
+        self.new_branches.is_empty()
+            && self.updated_branches.is_empty()
+            && self.pruned_branches.is_empty()
+            && self.new_tags.is_empty()
+    }
+
+    /// Compare two snapshots taken with [`list_refs`]
+    fn between(before: &BTreeMap<String, String>, after: &BTreeMap<String, String>) -> Self {
+        let mut updates = RefUpdates::default();
+        for (name, commit) in after {
+            match (before.get(name), name.strip_prefix("refs/tags/")) {
+                (None, Some(tag)) => updates.new_tags.push(tag.to_string()),
+                (None, None) => updates.new_branches.push(short_branch(name)),
+                (Some(old), None) if old != commit => {
+                    updates.updated_branches.push(short_branch(name))
+                }
+                _ => {}
+            }
+        }
+        updates.pruned_branches = before
+            .keys()
+            .filter(|name| name.starts_with("refs/remotes/") && !after.contains_key(*name))
+            .map(|name| short_branch(name))
+            .collect();
+        updates
+    }
+}
+
+/// Commits that landed on remote branches within a period
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UpstreamSummary {
+    pub commits: usize,
+    /// Distinct commit authors, in order of their latest commit
+    pub authors: Vec<String>,
+}
+
+fn short_branch(name: &str) -> String {
+    name.trim_start_matches("refs/remotes/").to_string()
+}
+
+/// Remote-tracking branches and tags with the commits they point to
+///
+/// The symbolic `<remote>/HEAD` refs are left out, since they only mirror
+/// another branch.
+fn list_refs(repo_path: &str) -> Result<BTreeMap<String, String>> {
+    let output = Command::new("git")
+        .args([
+            "for-each-ref",
+            "--format=%(objectname) %(refname)",
+            "refs/remotes",
+            "refs/tags",
+        ])
+        .current_dir(repo_path)
+        .logged_output()
+        .context("Failed to execute git for-each-ref command")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to list refs: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .filter(|(_, name)| !name.ends_with("/HEAD"))
+        .map(|(commit, name)| (name.to_string(), commit.to_string()))
+        .collect())
+}
+
+/// Fetch all remotes with their tags, pruning deleted branches
+///
+/// Only remote-tracking branches and tags are updated; local branches and the
+/// working tree are left alone.
+pub fn fetch_all(repo_path: &str) -> Result<RefUpdates> {
+    let before = list_refs(repo_path)?;
+
+    let output = Command::new("git")
+        .args(["fetch", "--all", "--prune", "--tags", "--quiet"])
+        .current_dir(repo_path)
+        .logged_output()
+        .context("Failed to execute git fetch command")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to fetch remotes: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(RefUpdates::between(&before, &list_refs(repo_path)?))
+}
+
+/// Summarize the commits on any remote branch since `since`
+///
+/// `since` is passed to `git log --since`, so it accepts dates such as
+/// `2024-01-01` as well as `"1 week ago"`.
+pub fn upstream_summary(repo_path: &str, since: &str) -> Result<UpstreamSummary> {
+    let output = Command::new("git")
+        .args(["log", "--remotes", "--format=%an"])
+        .arg(format!("--since={}", since))
+        .current_dir(repo_path)
+        .logged_output()
+        .context("Failed to execute git log command")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to read upstream history: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    let mut summary = UpstreamSummary::default();
+    for author in String::from_utf8_lossy(&output.stdout).lines() {
+        summary.commits += 1;
+        if !summary.authors.iter().any(|known| known == author) {
+            summary.authors.push(author.to_string());
+        }
+    }
+    Ok(summary)
+}
+
+/// How [`push`] pushes a branch
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PushOptions {
+    /// Make `origin/<branch>` the upstream of the branch
+    pub set_upstream: bool,
+    /// Overwrite the remote branch, unless it moved since it was last fetched
+    pub force_with_lease: bool,
+    /// Also push all tags
+    pub tags: bool,
+}
+
+/// Refs a push updated, found unchanged, or had rejected, by short name
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PushReport {
+    pub pushed: Vec<String>,
+    pub up_to_date: Vec<String>,
+    /// Rejected refs with git's reason, e.g. `non-fast-forward`
+    pub rejected: Vec<(String, String)>,
+}
+
+impl PushReport {
+    /// Read the output of `git push --porcelain`
+    ///
+    /// Each ref is reported on a line `<flag>\t<from>:<to>\t<summary>`, where
+    /// the flag is `=` for up to date refs and `!` for rejected ones.
+    fn parse(output: &str) -> Self {
+        let mut report = PushReport::default();
+        for line in output.lines() {
+            let mut fields = line.splitn(3, '\t');
+            let (Some(flag), Some(refs), summary) = (fields.next(), fields.next(), fields.next())
+            else {
+                continue;
+            };
+            let name = refs.rsplit(':').next().unwrap_or(refs);
+            let name = name
+                .strip_prefix("refs/heads/")
+                .or_else(|| name.strip_prefix("refs/tags/"))
+                .unwrap_or(name)
+                .to_string();
+            match flag {
+                "=" => report.up_to_date.push(name),
+                "!" => {
+                    let summary = summary.unwrap_or_default();
+                    let reason = summary
+                        .split_once('(')
+                        .and_then(|(_, reason)| reason.strip_suffix(')'))
+                        .unwrap_or(summary);
+                    report.rejected.push((name, reason.to_string()));
+                }
+                " " | "+" | "*" | "-" => report.pushed.push(name),
+                _ => {}
+            }
+        }
+        report
+    }
+}
+
+/// Push `branch` to `origin`
+///
+/// Rejected refs are part of the report rather than an error; failures to
+/// reach the remote at all are errors.
+pub fn push(
+    repo_path: &str,
+    branch: &str,
+    options: &PushOptions,
+    access: &RemoteAccess,
+) -> Result<PushReport> {
+    let mut command = Command::new("git");
+    command.args(["push", "--porcelain"]).current_dir(repo_path);
+    if options.set_upstream {
+        command.arg("--set-upstream");
+    }
+    if options.force_with_lease {
+        command.arg("--force-with-lease");
+    }
+    if options.tags {
+        command.arg("--tags");
+    }
+    command.args(["origin", branch]);
+    if let Some(url) = remote_url(repo_path, "origin") {
+        access.configure(&mut command, &url)?;
+    }
+    let output = command
+        .logged_output()
+        .context("Failed to execute git push command")?;
+
+    let report = PushReport::parse(&String::from_utf8_lossy(&output.stdout));
+    if !output.status.success() && report.rejected.is_empty() {
+        anyhow::bail!(
+            "Failed to push branch '{}' to remote 'origin': {}",
+            branch,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(report)
+}
+
+/// Configured remotes of a repository, with their fetch URLs
+pub fn list_remotes(repo_path: &str) -> Result<BTreeMap<String, String>> {
+    let output = Command::new("git")
+        .args(["config", "--get-regexp", r"^remote\..*\.url$"])
+        .current_dir(repo_path)
+        .logged_output()
+        .context("Failed to execute git config command")?;
+
+    // Exit code 1 means no remote is configured
+    if !output.status.success() && output.status.code() != Some(1) {
+        anyhow::bail!(
+            "Failed to list remotes: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .filter_map(|(key, url)| {
+            let name = key.strip_prefix("remote.")?.strip_suffix(".url")?;
+            Some((name.to_string(), url.to_string()))
+        })
+        .collect())
+}
+
+/// Name of the remote that points to a repository's `url`
+///
+/// This is `origin`, unless `remotes` declares an `origin` of its own, as for
+/// forks set up with `repos init --remote upstream`; then it is `upstream`.
+pub fn url_remote_name(repo: &Repository) -> &'static str {
+    if repo.remotes.contains_key("origin") {
+        "upstream"
+    } else {
+        "origin"
+    }
+}
+
+/// Remotes a clone of `repo` should have, with URLs as `access` rewrites them
+///
+/// Every entry of `remotes` becomes a remote, next to the one for `url` named
+/// by [`url_remote_name`]. An entry with that same name wins.
+pub fn expected_remotes(repo: &Repository, access: &RemoteAccess) -> BTreeMap<String, String> {
+    let mut remotes = BTreeMap::new();
+    remotes.insert(
+        url_remote_name(repo).to_string(),
+        access.remote_url(&repo.url),
+    );
+    for (name, url) in &repo.remotes {
+        remotes.insert(name.clone(), access.remote_url(url));
+    }
+    remotes
+}
+
+/// A difference between the remotes of a clone and the expected ones
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RemoteChange {
+    Add {
+        name: String,
+        url: String,
+    },
+    SetUrl {
+        name: String,
+        from: String,
+        to: String,
+    },
+    Remove {
+        name: String,
+    },
+}
+
+impl std::fmt::Display for RemoteChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RemoteChange::Add { name, url } => write!(f, "+{} {}", name, url),
+            RemoteChange::SetUrl { name, from, to } => write!(f, "~{} {} -> {}", name, from, to),
+            RemoteChange::Remove { name } => write!(f, "-{}", name),
+        }
+    }
+}
+
+/// Changes that turn the `actual` remotes into the `expected` ones
+///
+/// Remotes that are not expected are only removed with `prune`.
+pub fn remote_changes(
+    actual: &BTreeMap<String, String>,
+    expected: &BTreeMap<String, String>,
+    prune: bool,
+) -> Vec<RemoteChange> {
+    let mut changes = Vec::new();
+    for (name, url) in expected {
+        match actual.get(name) {
+            None => changes.push(RemoteChange::Add {
+                name: name.clone(),
+                url: url.clone(),
+            }),
+            Some(current) if current != url => changes.push(RemoteChange::SetUrl {
+                name: name.clone(),
+                from: current.clone(),
+                to: url.clone(),
+            }),
+            Some(_) => {}
+        }
+    }
+    if prune {
+        changes.extend(
+            actual
+                .keys()
+                .filter(|name| !expected.contains_key(*name))
+                .map(|name| RemoteChange::Remove { name: name.clone() }),
+        );
+    }
+    changes
+}
+
+/// Add, update or remove a remote
+pub fn apply_remote_change(repo_path: &str, change: &RemoteChange) -> Result<()> {
+    let args: Vec<&str> = match change {
+        RemoteChange::Add { name, url } => vec!["remote", "add", name, url],
+        RemoteChange::SetUrl { name, to, .. } => vec!["remote", "set-url", name, to],
+        RemoteChange::Remove { name } => vec!["remote", "remove", name],
+    };
+    let output = Command::new("git")
+        .args(&args)
+        .current_dir(repo_path)
+        .logged_output()
+        .context("Failed to execute git remote command")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to {} remote '{}': {}",
+            args[1],
+            match change {
+                RemoteChange::Add { name, .. }
+                | RemoteChange::SetUrl { name, .. }
+                | RemoteChange::Remove { name } => name,
+            },
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn refs(entries: &[(&str, &str)]) -> BTreeMap<String, String> {
+        entries
+            .iter()
+            .map(|(name, commit)| (name.to_string(), commit.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_ref_updates_between() {
+        let before = refs(&[
+            ("refs/remotes/origin/main", "a1"),
+            ("refs/remotes/origin/stale", "b1"),
+            ("refs/remotes/origin/same", "c1"),
+            ("refs/tags/v1.0.0", "d1"),
+        ]);
+        let after = refs(&[
+            ("refs/remotes/origin/main", "a2"),
+            ("refs/remotes/origin/feature", "e1"),
+            ("refs/remotes/origin/same", "c1"),
+            ("refs/tags/v1.0.0", "d1"),
+            ("refs/tags/v1.1.0", "f1"),
+        ]);
+
+        assert_eq!(
+            RefUpdates::between(&before, &after),
+            RefUpdates {
+                new_branches: vec!["origin/feature".to_string()],
+                updated_branches: vec!["origin/main".to_string()],
+                pruned_branches: vec!["origin/stale".to_string()],
+                new_tags: vec!["v1.1.0".to_string()],
+            }
+        );
+        assert!(RefUpdates::between(&after, &after).is_empty());
+    }
+
+    #[test]
+    fn test_push_report_parse() {
+        let output = "To github.com:org/api.git\n\
+*\trefs/heads/feature:refs/heads/feature\t[new branch]\n\
+=\trefs/tags/v1.0.0:refs/tags/v1.0.0\t[up to date]\n\
+!\trefs/heads/main:refs/heads/main\t[rejected] (fetch first)\n\
++\trefs/heads/topic:refs/heads/topic\tabc123...def456 (forced update)\n\
+Done\n";
+        assert_eq!(
+            PushReport::parse(output),
+            PushReport {
+                pushed: vec!["feature".to_string(), "topic".to_string()],
+                up_to_date: vec!["v1.0.0".to_string()],
+                rejected: vec![("main".to_string(), "fetch first".to_string())],
+            }
+        );
+    }
+
+    #[test]
+    fn test_expected_remotes() {
+        let mut repo = Repository::new("api".to_string(), "git@github.com:org/api.git".to_string());
+        let access = RemoteAccess::for_repository(&repo, None);
+        assert_eq!(
+            expected_remotes(&repo, &access),
+            refs(&[("origin", "git@github.com:org/api.git")])
+        );
+
+        // A fork: the config's url is the upstream repository
+        repo.remotes.insert(
+            "origin".to_string(),
+            "git@github.com:me/api.git".to_string(),
+        );
+        assert_eq!(url_remote_name(&repo), "upstream");
+        assert_eq!(
+            expected_remotes(&repo, &access),
+            refs(&[
+                ("origin", "git@github.com:me/api.git"),
+                ("upstream", "git@github.com:org/api.git"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_remote_changes() {
+        let actual = refs(&[
+            ("origin", "git@github.com:me/api.git"),
+            ("upstream", "git@github.com:old/api.git"),
+            ("stale", "git@github.com:gone/api.git"),
+        ]);
+        let expected = refs(&[
+            ("origin", "git@github.com:me/api.git"),
+            ("upstream", "git@github.com:org/api.git"),
+            ("mirror", "git@example.com:org/api.git"),
+        ]);
+
+        let changes = remote_changes(&actual, &expected, false);
+        assert_eq!(
+            changes,
+            vec![
+                RemoteChange::Add {
+                    name: "mirror".to_string(),
+                    url: "git@example.com:org/api.git".to_string(),
+                },
+                RemoteChange::SetUrl {
+                    name: "upstream".to_string(),
+                    from: "git@github.com:old/api.git".to_string(),
+                    to: "git@github.com:org/api.git".to_string(),
+                },
+            ]
+        );
+
+        let pruned = remote_changes(&actual, &expected, true);
+        assert_eq!(
+            pruned.last(),
+            Some(&RemoteChange::Remove {
+                name: "stale".to_string()
+            })
+        );
+        assert!(remote_changes(&expected, &expected, true).is_empty());
+    }
+}
@@ -0,0 +1,116 @@
+//! Git operations for setting changes aside across repositories
+//!
+//! ## Functions
+//!
+//! - [`stash_push`]: Stash uncommitted changes under a message marking them as ours
+//! - [`stash_pop`]: Restore the newest stash made by [`stash_push`]
+//!
+//! Stashes made by `repos stash push` carry a `[repos]` marker, so that
+//! popping never restores a stash the user made by hand in a repository that
+//! had nothing to stash.
+
+use super::common::git;
+use anyhow::{Context, Result};
+
+/// Prefix of the messages of stashes made by `repos stash push`
+const STASH_MARKER: &str = "[repos] ";
+
+/// Commit of the newest stash entry, if there is one
+fn latest_stash(repo_path: &str) -> Option<String> {
+    git(repo_path, &["rev-parse", "-q", "--verify", "refs/stash"]).ok()
+}
+
+/// Stash the uncommitted changes of a working copy, returning whether there were any
+///
+/// Untracked files are only stashed with `include_untracked`.
+pub fn stash_push(repo_path: &str, message: &str, include_untracked: bool) -> Result<bool> {
+    let before = latest_stash(repo_path);
+    let message = format!("{}{}", STASH_MARKER, message);
+    let mut args = vec!["stash", "push", "-m", &message];
+    if include_untracked {
+        args.push("--include-untracked");
+    }
+    git(repo_path, &args).context("Failed to stash changes")?;
+    Ok(latest_stash(repo_path) != before)
+}
+
+/// Find the newest stash made by `repos stash push` in `git stash list --format=%gd%x1f%gs`
+///
+/// Returns the stash reference, e.g. `stash@{1}`, and the message it was pushed with.
+fn find_stash(list: &str) -> Option<(&str, &str)> {
+    list.lines().find_map(|line| {
+        let (reference, subject) = line.split_once('\x1f')?;
+        // Subjects read `On <branch>: <message>`; branch names cannot contain ':'
+        let (_, message) = subject.split_once(": ")?;
+        Some((reference, message.strip_prefix(STASH_MARKER)?))
+    })
+}
+
+/// Restore and drop the newest stash made by [`stash_push`]
+///
+/// Returns the message of the restored stash, or `None` when there is none.
+/// When the changes conflict with the working copy, the stash is kept.
+pub fn stash_pop(repo_path: &str) -> Result<Option<String>> {
+    let list = git(repo_path, &["stash", "list", "--format=%gd%x1f%gs"])?;
+    let Some((reference, message)) = find_stash(&list) else {
+        return Ok(None);
+    };
+
+    git(repo_path, &["stash", "pop", "-q", reference]).with_context(|| {
+        format!(
+            "Failed to restore {} ({}); it is kept in the stash",
+            reference, message
+        )
+    })?;
+    Ok(Some(message.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn run(path: &str, args: &[&str]) -> String {
+        git(path, args).unwrap()
+    }
+
+    #[test]
+    fn test_find_stash() {
+        let list = "stash@{0}\x1fOn main: by hand\n\
+                    stash@{1}\x1fOn feature: [repos] before sync\n\
+                    stash@{2}\x1fWIP on main: 1234567 initial\n";
+        assert_eq!(find_stash(list), Some(("stash@{1}", "before sync")));
+        assert_eq!(find_stash("stash@{0}\x1fOn main: by hand\n"), None);
+        assert_eq!(find_stash(""), None);
+    }
+
+    #[test]
+    fn test_stash_push_and_pop() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let path = dir.to_string_lossy().to_string();
+        run(&path, &["init", "-q", "-b", "main"]);
+        run(&path, &["config", "user.name", "Test"]);
+        run(&path, &["config", "user.email", "test@example.com"]);
+        fs::write(dir.join("lib.rs"), "v1").unwrap();
+        run(&path, &["add", "."]);
+        run(&path, &["commit", "-q", "-m", "initial"]);
+
+        // Only untracked files, which are left alone by default
+        fs::write(dir.join("notes.txt"), "todo").unwrap();
+        assert!(!stash_push(&path, "wip", false).unwrap());
+        assert!(stash_push(&path, "wip", true).unwrap());
+        assert!(!dir.join("notes.txt").exists());
+
+        // A stash made by hand on top is not popped
+        fs::write(dir.join("lib.rs"), "v2").unwrap();
+        run(&path, &["stash", "push", "-q", "-m", "by hand"]);
+
+        assert_eq!(stash_pop(&path).unwrap().as_deref(), Some("wip"));
+        assert_eq!(fs::read_to_string(dir.join("notes.txt")).unwrap(), "todo");
+        assert_eq!(fs::read_to_string(dir.join("lib.rs")).unwrap(), "v1");
+        assert_eq!(stash_pop(&path).unwrap(), None);
+        assert!(run(&path, &["stash", "list"]).contains("by hand"));
+    }
+}
@@ -0,0 +1,169 @@
+//! Git operations describing the state of a working copy at a glance
+//!
+//! ## Functions
+//!
+//! - [`working_status`]: Branch, upstream distance and changed files of a clone
+//! - [`pull`]: Fast-forward the checked-out branch from its upstream
+
+use super::common::LoggedOutput;
+use anyhow::{Context, Result};
+use std::process::Command;
+
+/// Branch and changes of a working copy, from `git status --porcelain=v2`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WorkingStatus {
+    /// Checked-out branch; `None` when HEAD is detached
+    pub branch: Option<String>,
+    /// Commits ahead of and behind the upstream branch, if there is one
+    pub upstream: Option<(usize, usize)>,
+    /// Staged or modified tracked files
+    pub changed: usize,
+    pub untracked: usize,
+    /// Files with merge conflicts
+    pub conflicted: usize,
+}
+
+impl WorkingStatus {
+    /// Read the output of `git status --porcelain=v2 --branch`
+    fn parse(output: &str) -> Self {
+        let mut status = WorkingStatus::default();
+        for line in output.lines() {
+            if let Some(head) = line.strip_prefix("# branch.head ") {
+                status.branch = (head != "(detached)").then(|| head.to_string());
+            } else if let Some(counts) = line.strip_prefix("# branch.ab ") {
+                let mut counts = counts
+                    .split_whitespace()
+                    .map(|count| count.trim_start_matches(['+', '-']).parse().unwrap_or(0));
+                status.upstream = Some((
+                    counts.next().unwrap_or_default(),
+                    counts.next().unwrap_or_default(),
+                ));
+            } else if line.starts_with("1 ") || line.starts_with("2 ") {
+                status.changed += 1;
+            } else if line.starts_with("u ") {
+                status.conflicted += 1;
+            } else if line.starts_with("? ") {
+                status.untracked += 1;
+            }
+        }
+        status
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.changed == 0 && self.untracked == 0 && self.conflicted == 0
+    }
+
+    /// Short description, e.g. `3 changed, 1 untracked, ↑2 ↓1`
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if self.conflicted > 0 {
+            parts.push(format!("{} conflicted", self.conflicted));
+        }
+        if self.changed > 0 {
+            parts.push(format!("{} changed", self.changed));
+        }
+        if self.untracked > 0 {
+            parts.push(format!("{} untracked", self.untracked));
+        }
+        if parts.is_empty() {
+            parts.push("clean".to_string());
+        }
+        match self.upstream {
+            Some((0, 0)) => {}
+            Some((ahead, behind)) => {
+                let mut distance = Vec::new();
+                if ahead > 0 {
+                    distance.push(format!("↑{}", ahead));
+                }
+                if behind > 0 {
+                    distance.push(format!("↓{}", behind));
+                }
+                parts.push(distance.join(" "));
+            }
+            None => parts.push("no upstream".to_string()),
+        }
+        parts.join(", ")
+    }
+}
+
+/// Branch, distance from upstream and changed files of a working copy
+pub fn working_status(repo_path: &str) -> Result<WorkingStatus> {
+    let output = Command::new("git")
+        .args(["status", "--porcelain=v2", "--branch"])
+        .current_dir(repo_path)
+        .logged_output()
+        .context("Failed to execute git status command")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to read status: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(WorkingStatus::parse(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Fast-forward the checked-out branch from its upstream, returning git's summary
+pub fn pull(repo_path: &str) -> Result<String> {
+    let output = Command::new("git")
+        .args(["pull", "--ff-only"])
+        .current_dir(repo_path)
+        .logged_output()
+        .context("Failed to execute git pull command")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to pull: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout
+        .lines()
+        .rfind(|line| !line.trim().is_empty())
+        .unwrap_or_default()
+        .trim()
+        .to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_working_status_parse() {
+        let output = "\
+# branch.oid 1234567890abcdef
+# branch.head feature
+# branch.upstream origin/feature
+# branch.ab +2 -1
+1 .M N... 100644 100644 100644 abc abc src/lib.rs
+2 R. N... 100644 100644 100644 abc abc R100 new.rs\told.rs
+u UU N... 100644 100644 100644 100644 a b c conflict.rs
+? notes.txt
+? todo.txt
+";
+        let status = WorkingStatus::parse(output);
+        assert_eq!(
+            status,
+            WorkingStatus {
+                branch: Some("feature".to_string()),
+                upstream: Some((2, 1)),
+                changed: 2,
+                untracked: 2,
+                conflicted: 1,
+            }
+        );
+        assert_eq!(
+            status.describe(),
+            "1 conflicted, 2 changed, 2 untracked, ↑2 ↓1"
+        );
+
+        let detached = WorkingStatus::parse("# branch.oid abc\n# branch.head (detached)\n");
+        assert_eq!(detached.branch, None);
+        assert!(detached.is_clean());
+        assert_eq!(detached.describe(), "clean, no upstream");
+    }
+}
@@ -0,0 +1,202 @@
+//! Git operations for repositories with submodules
+//!
+//! ## Functions
+//!
+//! - [`has_submodules`]: Whether a clone declares any submodules
+//! - [`submodule_status`]: The recorded commit and state of every submodule
+//! - [`update_submodules`]: Initialize and check out submodules, optionally at their remote branch
+//! - [`sync_submodules`]: Copy submodule URLs from `.gitmodules` to the clone's config
+//! - [`fetch_submodules`]: Fetch the remotes of checked-out submodules
+//!
+//! All of them work recursively, on submodules of submodules too. Commands
+//! that reach a remote use the superproject's transport settings, so HTTPS
+//! submodules on the same host get the same token.
+
+use super::common::LoggedOutput;
+use super::transport::{RemoteAccess, remote_url};
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::process::Command;
+
+/// How a submodule's checkout compares with the commit the superproject records
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubmoduleState {
+    /// Checked out at the recorded commit
+    Current,
+    /// Checked out at another commit
+    Modified,
+    /// Declared but not checked out
+    Uninitialized,
+    /// The recorded commit has merge conflicts
+    Conflicted,
+}
+
+/// One submodule as reported by `git submodule status`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Submodule {
+    /// Path relative to the superproject
+    pub path: String,
+    /// The checked-out commit, or the recorded one when not checked out
+    pub commit: String,
+    pub state: SubmoduleState,
+}
+
+/// Whether the clone at `repo_path` declares submodules in `.gitmodules`
+pub fn has_submodules(repo_path: &str) -> bool {
+    Path::new(repo_path).join(".gitmodules").is_file()
+}
+
+/// Parse `git submodule status` output
+fn parse_status(output: &str) -> Vec<Submodule> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut chars = line.chars();
+            let state = match chars.next()? {
+                ' ' => SubmoduleState::Current,
+                '+' => SubmoduleState::Modified,
+                '-' => SubmoduleState::Uninitialized,
+                'U' => SubmoduleState::Conflicted,
+                _ => return None,
+            };
+            let (commit, rest) = chars.as_str().split_once(' ')?;
+            // The path is followed by `git describe` output in parentheses, when there is any
+            let path = match rest.rfind(" (") {
+                Some(end) if rest.ends_with(')') => &rest[..end],
+                _ => rest,
+            };
+            Some(Submodule {
+                path: path.to_string(),
+                commit: commit.to_string(),
+                state,
+            })
+        })
+        .collect()
+}
+
+/// The submodules of a clone, including nested ones, with their state
+pub fn submodule_status(repo_path: &str) -> Result<Vec<Submodule>> {
+    let output = Command::new("git")
+        .args(["submodule", "status", "--recursive"])
+        .current_dir(repo_path)
+        .logged_output()
+        .context("Failed to execute git submodule command")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to read submodule status: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(parse_status(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Run a git command that talks to the submodules' remotes
+fn run_with_access(
+    repo_path: &str,
+    args: &[&str],
+    access: &RemoteAccess,
+    action: &str,
+) -> Result<()> {
+    let mut command = Command::new("git");
+    command.args(args).current_dir(repo_path);
+    if let Some(url) = remote_url(repo_path, "origin") {
+        access.configure(&mut command, &url)?;
+    }
+    let output = command
+        .logged_output()
+        .context("Failed to execute git submodule command")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to {}: {}",
+            action,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(())
+}
+
+/// Initialize and check out every submodule at the commit the superproject records
+///
+/// With `remote`, submodules are moved to the tip of their remote-tracking
+/// branch instead, which leaves the superproject with changes to commit.
+pub fn update_submodules(repo_path: &str, remote: bool, access: &RemoteAccess) -> Result<()> {
+    let mut args = vec!["submodule", "update", "--init", "--recursive"];
+    if remote {
+        args.push("--remote");
+    }
+    run_with_access(repo_path, &args, access, "update submodules")
+}
+
+/// Copy the submodule URLs in `.gitmodules` to the clone's config, returning the synchronized paths
+///
+/// Needed after a submodule's URL changes upstream; until then, fetches keep
+/// going to the old URL.
+pub fn sync_submodules(repo_path: &str) -> Result<Vec<String>> {
+    let output = Command::new("git")
+        .args(["submodule", "sync", "--recursive"])
+        .current_dir(repo_path)
+        .logged_output()
+        .context("Failed to execute git submodule command")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to sync submodules: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.split('\'').nth(1))
+        .map(str::to_string)
+        .collect())
+}
+
+/// Fetch the remotes of every checked-out submodule, leaving their working trees alone
+pub fn fetch_submodules(repo_path: &str, access: &RemoteAccess) -> Result<()> {
+    run_with_access(
+        repo_path,
+        &[
+            "submodule",
+            "foreach",
+            "--recursive",
+            "--quiet",
+            "git fetch --all --prune --quiet",
+        ],
+        access,
+        "fetch submodules",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_status() {
+        let output = " 1111111111111111111111111111111111111111 vendor/lib (v1.2.0)\n\
++2222222222222222222222222222222222222222 vendor/tools (heads/main)\n\
+-3333333333333333333333333333333333333333 docs/theme\n\
+U0000000000000000000000000000000000000000 vendor/proto\n";
+
+        let submodules = parse_status(output);
+        assert_eq!(submodules.len(), 4);
+        assert_eq!(submodules[0].path, "vendor/lib");
+        assert_eq!(submodules[0].state, SubmoduleState::Current);
+        assert_eq!(submodules[1].state, SubmoduleState::Modified);
+        assert_eq!(
+            submodules[2],
+            Submodule {
+                path: "docs/theme".to_string(),
+                commit: "3333333333333333333333333333333333333333".to_string(),
+                state: SubmoduleState::Uninitialized,
+            }
+        );
+        assert_eq!(submodules[3].state, SubmoduleState::Conflicted);
+        assert!(parse_status("").is_empty());
+    }
+}
@@ -0,0 +1,224 @@
+//! Git operations for release tags
+//!
+//! ## Functions
+//!
+//! - [`fetch_tags`]: Update tags from `origin`
+//! - [`latest_version_tag`]: Highest semantic version tag with a prefix
+//! - [`commits_since`]: Subjects of the commits since a tag
+//! - [`create_tag`] / [`delete_tag`]: Manage annotated tags
+//! - [`push_tag`]: Publish a tag to `origin`
+
+use super::common::{LoggedOutput, git};
+use super::transport::{RemoteAccess, remote_url};
+use anyhow::{Context, Result};
+use std::fmt;
+use std::process::Command;
+use std::str::FromStr;
+
+/// A `MAJOR.MINOR.PATCH` version; tags with pre-release or build suffixes are not versions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl Version {
+    /// The next version after a change of the given size
+    pub fn bump(&self, bump: Bump) -> Self {
+        match bump {
+            Bump::Major => Version {
+                major: self.major + 1,
+                minor: 0,
+                patch: 0,
+            },
+            Bump::Minor => Version {
+                minor: self.minor + 1,
+                patch: 0,
+                ..*self
+            },
+            Bump::Patch => Version {
+                patch: self.patch + 1,
+                ..*self
+            },
+        }
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl FromStr for Version {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let parts: Vec<&str> = s.split('.').collect();
+        let numbers: Option<Vec<u64>> = parts
+            .iter()
+            .map(|part| {
+                (!part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()))
+                    .then(|| part.parse().ok())
+                    .flatten()
+            })
+            .collect();
+        match numbers.as_deref() {
+            Some(&[major, minor, patch]) => Ok(Version {
+                major,
+                minor,
+                patch,
+            }),
+            _ => anyhow::bail!("Invalid version '{}': expected MAJOR.MINOR.PATCH", s),
+        }
+    }
+}
+
+/// Which part of a version to increase
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bump {
+    Major,
+    Minor,
+    Patch,
+}
+
+impl FromStr for Bump {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "major" => Ok(Bump::Major),
+            "minor" => Ok(Bump::Minor),
+            "patch" => Ok(Bump::Patch),
+            _ => anyhow::bail!(
+                "Unknown version bump '{}' (expected major, minor or patch)",
+                s
+            ),
+        }
+    }
+}
+
+/// Fetch the tags of `origin`, so versions released elsewhere are known
+pub fn fetch_tags(repo_path: &str) -> Result<()> {
+    git(repo_path, &["fetch", "--tags", "--quiet", "origin"]).map(|_| ())
+}
+
+/// Whether a tag exists locally
+pub fn tag_exists(repo_path: &str, tag: &str) -> Result<bool> {
+    Ok(!git(repo_path, &["tag", "--list", tag])?.is_empty())
+}
+
+/// Tag with the highest version among those named `<prefix>MAJOR.MINOR.PATCH`
+pub fn latest_version_tag(repo_path: &str, prefix: &str) -> Result<Option<(String, Version)>> {
+    let tags = git(repo_path, &["tag", "--list"])?;
+    Ok(tags
+        .lines()
+        .filter_map(|tag| {
+            let version = tag.strip_prefix(prefix)?.parse().ok()?;
+            Some((tag.to_string(), version))
+        })
+        .max_by_key(|(_, version)| *version))
+}
+
+/// Subjects of the commits on `HEAD` since `tag`, newest first; all of them without a tag
+pub fn commits_since(repo_path: &str, tag: Option<&str>) -> Result<Vec<String>> {
+    let range = match tag {
+        Some(tag) => format!("{}..HEAD", tag),
+        None => "HEAD".to_string(),
+    };
+    let log = git(repo_path, &["log", "--no-merges", "--format=%s", &range])?;
+    Ok(log.lines().map(str::to_string).collect())
+}
+
+/// Create an annotated tag on `HEAD`
+pub fn create_tag(repo_path: &str, tag: &str, message: &str) -> Result<()> {
+    git(repo_path, &["tag", "--annotate", tag, "--message", message]).map(|_| ())
+}
+
+/// Delete a local tag
+pub fn delete_tag(repo_path: &str, tag: &str) -> Result<()> {
+    git(repo_path, &["tag", "--delete", tag]).map(|_| ())
+}
+
+/// Push a tag to `origin`
+pub fn push_tag(repo_path: &str, tag: &str, access: &RemoteAccess) -> Result<()> {
+    let mut command = Command::new("git");
+    command
+        .args(["push", "origin", &format!("refs/tags/{}", tag)])
+        .current_dir(repo_path);
+    if let Some(url) = remote_url(repo_path, "origin") {
+        access.configure(&mut command, &url)?;
+    }
+    let output = command
+        .logged_output()
+        .context("Failed to execute git push command")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to push tag '{}' to remote 'origin': {}",
+            tag,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn commit(path: &str, message: &str) {
+        git(path, &["commit", "-q", "--allow-empty", "-m", message]).unwrap();
+    }
+
+    #[test]
+    fn test_version_parse_and_bump() {
+        let version: Version = "1.4.2".parse().unwrap();
+        assert_eq!(version.bump(Bump::Patch).to_string(), "1.4.3");
+        assert_eq!(version.bump(Bump::Minor).to_string(), "1.5.0");
+        assert_eq!(version.bump(Bump::Major).to_string(), "2.0.0");
+        for invalid in ["1.4", "1.4.2-rc.1", "v1.4.2", "1..2", "1.4.2.0"] {
+            assert!(invalid.parse::<Version>().is_err(), "{}", invalid);
+        }
+        assert!("huge".parse::<Bump>().is_err());
+    }
+
+    #[test]
+    fn test_version_tags() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().to_string_lossy().to_string();
+        for args in [
+            vec!["init", "-q", "-b", "main"],
+            vec!["config", "user.name", "Test"],
+            vec!["config", "user.email", "test@example.com"],
+        ] {
+            git(&path, &args).unwrap();
+        }
+        commit(&path, "initial");
+        assert_eq!(latest_version_tag(&path, "v").unwrap(), None);
+
+        create_tag(&path, "v1.9.0", "Release v1.9.0").unwrap();
+        commit(&path, "fix parser");
+        create_tag(&path, "v1.10.0", "Release v1.10.0").unwrap();
+        create_tag(&path, "v2.0.0-rc.1", "Release candidate").unwrap();
+        commit(&path, "add flag");
+        commit(&path, "update docs");
+
+        assert_eq!(
+            latest_version_tag(&path, "v").unwrap(),
+            Some(("v1.10.0".to_string(), "1.10.0".parse().unwrap()))
+        );
+        assert_eq!(
+            commits_since(&path, Some("v1.10.0")).unwrap(),
+            vec!["update docs", "add flag"]
+        );
+        assert_eq!(commits_since(&path, None).unwrap().len(), 4);
+
+        assert!(tag_exists(&path, "v1.9.0").unwrap());
+        delete_tag(&path, "v1.9.0").unwrap();
+        assert!(!tag_exists(&path, "v1.9.0").unwrap());
+        assert!(create_tag(&path, "v1.10.0", "again").is_err());
+    }
+}
@@ -0,0 +1,355 @@
+//! How git reaches the remotes of a repository
+//!
+//! The `git` settings of the config, at the top level or per repository,
+//! decide the URL a repository is cloned from and the credentials git uses:
+//!
+//! - `protocol` forces SSH or HTTPS URLs, whichever form `url` is written in
+//! - `rewrite` replaces URL prefixes, like git's `url.<base>.insteadOf`
+//! - `https_token` authenticates clones and pushes over HTTPS with the
+//!   repository's token, for machines without an interactive credential helper
+//! - `ssh_key` selects the SSH key, or with a `.pub` file the ssh-agent identity
+//!
+//! The token is handed to git through the environment, never on the command
+//! line or in the remote URL, so it does not end up in `.git/config`.
+
+use super::common::LoggedOutput;
+use crate::config::{Provider, Repository, expand_path};
+use crate::credentials::Credentials;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::process::Command;
+
+/// Environment variable the credential helper reads the token from
+const TOKEN_ENV: &str = "REPOS_GIT_TOKEN";
+
+/// Environment variable the credential helper reads the user name from
+const USERNAME_ENV: &str = "REPOS_GIT_USERNAME";
+
+/// User name sent with a token when the URL does not name one
+const TOKEN_USERNAME: &str = "x-access-token";
+
+/// Transport protocol of remote URLs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Protocol {
+    Ssh,
+    Https,
+}
+
+/// Git transport settings, from the top level of the config or a repository
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GitTransport {
+    /// Rewrite remote URLs to this protocol
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protocol: Option<Protocol>,
+    /// URL prefixes to replace, mapped to their replacements
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub rewrite: BTreeMap<String, String>,
+    /// Authenticate over HTTPS with the repository's token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub https_token: Option<bool>,
+    /// SSH private key, or public key of an ssh-agent identity
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ssh_key: Option<String>,
+}
+
+impl GitTransport {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// These settings, falling back to `defaults` for those not set
+    ///
+    /// Rewrites are combined; a repository's own rewrite of a prefix wins.
+    pub fn merged(&self, defaults: &GitTransport) -> GitTransport {
+        let mut rewrite = defaults.rewrite.clone();
+        rewrite.extend(self.rewrite.clone());
+        GitTransport {
+            protocol: self.protocol.or(defaults.protocol),
+            rewrite,
+            https_token: self.https_token.or(defaults.https_token),
+            ssh_key: self.ssh_key.clone().or_else(|| defaults.ssh_key.clone()),
+        }
+    }
+
+    /// The URL git should use for a configured remote URL
+    ///
+    /// As with `insteadOf`, the longest matching prefix is rewritten. The
+    /// protocol is changed afterwards, so a rewrite can also fix up hosts the
+    /// protocol conversion does not know about.
+    pub fn remote_url(&self, url: &str) -> String {
+        let rewritten = self
+            .rewrite
+            .iter()
+            .filter(|(prefix, _)| url.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(prefix, replacement)| format!("{}{}", replacement, &url[prefix.len()..]))
+            .unwrap_or_else(|| url.to_string());
+
+        match self.protocol {
+            Some(Protocol::Https) => to_https(&rewritten).unwrap_or(rewritten),
+            Some(Protocol::Ssh) => to_ssh(&rewritten).unwrap_or(rewritten),
+            None => rewritten,
+        }
+    }
+}
+
+/// `host` and `path` of an SSH URL, either `git@host:path` or `ssh://git@host[:port]/path`
+fn ssh_parts(url: &str) -> Option<(&str, &str)> {
+    if let Some(rest) = url.strip_prefix("ssh://") {
+        let (authority, path) = rest.split_once('/')?;
+        let host = authority.rsplit('@').next()?;
+        let host = host.split(':').next()?;
+        return Some((host, path));
+    }
+    if url.contains("://") {
+        return None;
+    }
+    let (user_host, path) = url.split_once(':')?;
+    let (_, host) = user_host.split_once('@')?;
+    Some((host, path.trim_start_matches('/')))
+}
+
+/// `host` and `path` of an HTTP(S) URL, leaving out any user name
+fn https_parts(url: &str) -> Option<(&str, &str)> {
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))?;
+    let (authority, path) = rest.split_once('/')?;
+    Some((authority.rsplit('@').next()?, path))
+}
+
+fn to_https(url: &str) -> Option<String> {
+    let (host, path) = ssh_parts(url)?;
+    Some(format!("https://{}/{}", host, path))
+}
+
+fn to_ssh(url: &str) -> Option<String> {
+    let (host, path) = https_parts(url)?;
+    Some(format!("git@{}:{}", host, path))
+}
+
+/// User name in an HTTP(S) URL, e.g. `org` in `https://org@dev.azure.com/...`
+fn url_username(url: &str) -> Option<&str> {
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))?;
+    let authority = rest.split('/').next()?;
+    let (user, _) = authority.rsplit_once('@')?;
+    Some(user.split(':').next().unwrap_or(user))
+}
+
+/// Everything git needs to reach one repository's remotes
+#[derive(Debug, Clone, Default)]
+pub struct RemoteAccess {
+    transport: GitTransport,
+    token: Option<String>,
+}
+
+impl RemoteAccess {
+    /// Access to a repository with its effective transport settings
+    ///
+    /// With `https_token`, the token is `token` when given, otherwise the one
+    /// the repository's provider uses for its API: a saved identity or
+    /// `GITHUB_TOKEN` for GitHub, `GITEA_TOKEN` or `AZURE_DEVOPS_TOKEN` for the
+    /// others.
+    pub fn for_repository(repo: &Repository, token: Option<String>) -> Self {
+        let transport = repo.git_transport();
+        let token = if transport.https_token.unwrap_or(false) {
+            token
+                .filter(|t| !t.is_empty())
+                .or_else(|| match repo.provider() {
+                    Provider::GitHub => Credentials::load(None).token_for_repository(repo),
+                    Provider::Gitea => std::env::var(repos_gitea::TOKEN_ENV).ok(),
+                    Provider::Azure => std::env::var(repos_azure::TOKEN_ENV).ok(),
+                })
+        } else {
+            None
+        };
+        Self { transport, token }
+    }
+
+    /// The URL to clone or push to for a configured remote URL
+    pub fn remote_url(&self, url: &str) -> String {
+        self.transport.remote_url(url)
+    }
+
+    /// Set up a git command that talks to the remote at `url`
+    ///
+    /// HTTPS remotes get the token through a credential helper that replaces
+    /// any configured ones, and git does not prompt when it is rejected.
+    pub(crate) fn configure(&self, command: &mut Command, url: &str) -> Result<()> {
+        if let Some(key) = &self.transport.ssh_key {
+            let key = expand_path(key)?;
+            command.env(
+                "GIT_SSH_COMMAND",
+                format!(
+                    "ssh -i '{}' -o IdentitiesOnly=yes",
+                    key.to_string_lossy().replace('\'', r"'\''")
+                ),
+            );
+        }
+
+        if let Some(token) = &self.token
+            && https_parts(url).is_some()
+        {
+            command
+                .env("GIT_TERMINAL_PROMPT", "0")
+                .env("GIT_CONFIG_COUNT", "2")
+                .env("GIT_CONFIG_KEY_0", "credential.helper")
+                .env("GIT_CONFIG_VALUE_0", "")
+                .env("GIT_CONFIG_KEY_1", "credential.helper")
+                .env(
+                    "GIT_CONFIG_VALUE_1",
+                    format!(
+                        "!f() {{ test \"$1\" = get && echo \"username=${}\" && echo \"password=${}\"; }}; f",
+                        USERNAME_ENV, TOKEN_ENV
+                    ),
+                )
+                .env(USERNAME_ENV, url_username(url).unwrap_or(TOKEN_USERNAME))
+                .env(TOKEN_ENV, token);
+        }
+        Ok(())
+    }
+}
+
+/// URL of a remote of a local clone
+pub(crate) fn remote_url(repo_path: &str, remote: &str) -> Option<String> {
+    let output = Command::new("git")
+        .args(["remote", "get-url", "--push", remote])
+        .current_dir(repo_path)
+        .logged_output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::common::command_line;
+
+    fn transport(yaml: &str) -> GitTransport {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_protocol_conversion() {
+        let https = transport("protocol: https");
+        assert_eq!(
+            https.remote_url("git@github.com:org/api.git"),
+            "https://github.com/org/api.git"
+        );
+        assert_eq!(
+            https.remote_url("ssh://git@gitea.example.com:2222/org/api.git"),
+            "https://gitea.example.com/org/api.git"
+        );
+        assert_eq!(
+            https.remote_url("https://github.com/org/api.git"),
+            "https://github.com/org/api.git"
+        );
+
+        let ssh = transport("protocol: ssh");
+        assert_eq!(
+            ssh.remote_url("https://user@github.com/org/api.git"),
+            "git@github.com:org/api.git"
+        );
+        assert_eq!(
+            ssh.remote_url("git@github.com:org/api.git"),
+            "git@github.com:org/api.git"
+        );
+    }
+
+    #[test]
+    fn test_rewrite_and_merge() {
+        let defaults = transport(
+            r#"
+rewrite:
+  "https://github.com/": "git@github.com:"
+  "https://github.com/acme/": "git@github-acme:acme/"
+ssh_key: ~/.ssh/id_work
+https_token: true
+"#,
+        );
+        // The longest matching prefix wins
+        assert_eq!(
+            defaults.remote_url("https://github.com/acme/api.git"),
+            "git@github-acme:acme/api.git"
+        );
+        assert_eq!(
+            defaults.remote_url("https://github.com/other/api.git"),
+            "git@github.com:other/api.git"
+        );
+
+        let repo = transport(
+            r#"
+rewrite:
+  "https://github.com/": "https://mirror.example.com/"
+https_token: false
+"#,
+        );
+        let merged = repo.merged(&defaults);
+        assert_eq!(merged.rewrite.len(), 2);
+        assert_eq!(
+            merged.remote_url("https://github.com/other/api.git"),
+            "https://mirror.example.com/other/api.git"
+        );
+        assert_eq!(merged.https_token, Some(false));
+        assert_eq!(merged.ssh_key.as_deref(), Some("~/.ssh/id_work"));
+        assert!(GitTransport::default().is_default());
+        assert!(serde_yaml::from_str::<GitTransport>("protocol: ftp").is_err());
+    }
+
+    #[test]
+    fn test_configure_token_only_for_https() {
+        let access = RemoteAccess {
+            transport: GitTransport::default(),
+            token: Some("secret".to_string()),
+        };
+        let env = |url: &str| {
+            let mut command = Command::new("git");
+            access.configure(&mut command, url).unwrap();
+            command
+                .get_envs()
+                .map(|(key, value)| {
+                    (
+                        key.to_string_lossy().to_string(),
+                        value.map(|v| v.to_string_lossy().to_string()),
+                    )
+                })
+                .collect::<BTreeMap<_, _>>()
+        };
+
+        let https = env("https://org@dev.azure.com/org/project/_git/api");
+        assert_eq!(https[TOKEN_ENV].as_deref(), Some("secret"));
+        assert_eq!(https[USERNAME_ENV].as_deref(), Some("org"));
+        assert_eq!(https["GIT_TERMINAL_PROMPT"].as_deref(), Some("0"));
+        assert_eq!(
+            env("https://github.com/org/api.git")[USERNAME_ENV].as_deref(),
+            Some(TOKEN_USERNAME)
+        );
+        assert!(env("git@github.com:org/api.git").is_empty());
+    }
+
+    #[test]
+    fn test_logged_command_line_leaves_out_token() {
+        let access = RemoteAccess {
+            transport: GitTransport::default(),
+            token: Some("ghp_secret_token".to_string()),
+        };
+        let mut command = Command::new("git");
+        command.args(["push", "origin", "main"]);
+        access
+            .configure(&mut command, "https://github.com/org/api.git")
+            .unwrap();
+
+        let line = command_line(&command);
+        assert_eq!(line, r#""git" "push" "origin" "main""#);
+        assert!(format!("{:?}", command).contains("ghp_secret_token"));
+        assert!(!line.contains("ghp_secret_token"));
+    }
+}
@@ -0,0 +1,194 @@
+//! Git worktrees for working on a ref without touching the main checkout
+//!
+//! A worktree shares the repository's object store but has its own working
+//! directory and HEAD, so commands can run against another branch or tag
+//! while a developer's checkout, including uncommitted changes, is left alone.
+//!
+//! [`TemporaryWorktree`] lives only as long as a single job, while
+//! [`open_worktree`] keeps one worktree per repository under a shared
+//! directory so that changes made by one command can be picked up by the next.
+
+use super::common::{LoggedOutput, Logger};
+use crate::config::Repository;
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tempfile::TempDir;
+
+/// Resolve a branch, tag, or commit to a commit hash
+///
+/// Refs that only exist on `origin` (e.g. release branches that were never
+/// checked out locally) are found as remote-tracking branches, and anything
+/// else is fetched from `origin` before giving up.
+pub fn resolve_ref(repo_path: &str, git_ref: &str) -> Result<String> {
+    for candidate in [git_ref.to_string(), format!("origin/{}", git_ref)] {
+        if let Some(commit) = rev_parse_commit(repo_path, &candidate)? {
+            return Ok(commit);
+        }
+    }
+
+    let output = Command::new("git")
+        .args(["fetch", "origin", git_ref])
+        .current_dir(repo_path)
+        .logged_output()
+        .context("Failed to execute git fetch command")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Unknown ref '{}': {}",
+            git_ref,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    rev_parse_commit(repo_path, "FETCH_HEAD")?
+        .ok_or_else(|| anyhow::anyhow!("Unknown ref '{}'", git_ref))
+}
+
+fn rev_parse_commit(repo_path: &str, rev: &str) -> Result<Option<String>> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--verify", "--quiet"])
+        .arg(format!("{}^{{commit}}", rev))
+        .current_dir(repo_path)
+        .logged_output()
+        .context("Failed to execute git rev-parse command")?;
+
+    Ok(output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string()))
+}
+
+/// Check out a commit with a detached HEAD in a new worktree at `path`
+pub fn add_worktree(repo_path: &str, path: &Path, commit: &str) -> Result<()> {
+    let output = Command::new("git")
+        .args(["worktree", "add", "--detach"])
+        .arg(path)
+        .arg(commit)
+        .current_dir(repo_path)
+        .logged_output()
+        .context("Failed to execute git worktree command")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to create worktree at {}: {}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(())
+}
+
+/// Remove a worktree, discarding any changes made in it
+pub fn remove_worktree(repo_path: &str, path: &Path) -> Result<()> {
+    let output = Command::new("git")
+        .args(["worktree", "remove", "--force"])
+        .arg(path)
+        .current_dir(repo_path)
+        .logged_output()
+        .context("Failed to execute git worktree command")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to remove worktree at {}: {}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(())
+}
+
+/// Directory of a repository's worktree under `worktrees_dir`
+pub fn worktree_path(repo: &Repository, worktrees_dir: &Path) -> Result<PathBuf> {
+    let path = worktrees_dir.join(&repo.name);
+    if path.is_absolute() {
+        return Ok(path);
+    }
+    // git resolves relative paths against the repository, not the current directory
+    Ok(std::env::current_dir()?.join(path))
+}
+
+/// Open the repository's worktree under `worktrees_dir`, creating it from HEAD if needed
+///
+/// The worktree has a detached HEAD and is kept after the command finishes.
+/// Returns a copy of the repository whose target directory is the worktree.
+pub fn open_worktree(repo: &Repository, worktrees_dir: &Path) -> Result<Repository> {
+    let repo_path = repo.get_target_dir();
+    if !Path::new(&repo_path).exists() {
+        anyhow::bail!("Repository directory does not exist: {}", repo_path);
+    }
+
+    let path = worktree_path(repo, worktrees_dir)?;
+    if !path.join(".git").exists() {
+        // Forget worktrees whose directories were deleted by hand
+        let _ = Command::new("git")
+            .args(["worktree", "prune"])
+            .current_dir(&repo_path)
+            .logged_output();
+        std::fs::create_dir_all(worktrees_dir).context("Failed to create worktrees directory")?;
+        add_worktree(&repo_path, &path, "HEAD")?;
+        Logger.info(repo, &format!("Created worktree at {}", path.display()));
+    }
+
+    let mut checkout = repo.clone();
+    checkout.path = Some(path.to_string_lossy().to_string());
+    Ok(checkout)
+}
+
+/// Remove the repository's worktree under `worktrees_dir`, if there is one
+pub fn close_worktree(repo: &Repository, worktrees_dir: &Path) -> Result<()> {
+    let path = worktree_path(repo, worktrees_dir)?;
+    if !path.exists() {
+        return Ok(());
+    }
+    remove_worktree(&repo.get_target_dir(), &path)?;
+    Logger.info(repo, &format!("Removed worktree at {}", path.display()));
+    Ok(())
+}
+
+/// A detached worktree in a temporary directory, removed when dropped
+///
+/// Removal also deletes the worktree's administrative files in the
+/// repository, so the repository is left exactly as it was.
+pub struct TemporaryWorktree {
+    repo_path: String,
+    dir: TempDir,
+}
+
+impl TemporaryWorktree {
+    /// Check out `git_ref` of the repository at `repo_path` in a temporary worktree
+    pub fn create(repo_path: &str, git_ref: &str) -> Result<Self> {
+        let commit = resolve_ref(repo_path, git_ref)?;
+        let dir = tempfile::Builder::new()
+            .prefix("repos-worktree-")
+            .tempdir()
+            .context("Failed to create temporary directory")?;
+        add_worktree(repo_path, dir.path(), &commit)?;
+
+        Ok(Self {
+            repo_path: repo_path.to_string(),
+            dir,
+        })
+    }
+
+    /// Directory of the checked out worktree
+    pub fn path(&self) -> PathBuf {
+        self.dir.path().to_path_buf()
+    }
+}
+
+impl Drop for TemporaryWorktree {
+    fn drop(&mut self) {
+        if let Err(e) = remove_worktree(&self.repo_path, self.dir.path()) {
+            log::warn!("{}", e);
+            // Delete the directory ourselves and let git forget about it
+            let _ = std::fs::remove_dir_all(self.dir.path());
+            let _ = Command::new("git")
+                .args(["worktree", "prune"])
+                .current_dir(&self.repo_path)
+                .logged_output();
+        }
+    }
+}
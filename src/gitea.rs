@@ -0,0 +1,106 @@
+//! Gitea and Forgejo API operations
+//!
+//! Repositories with `provider: gitea` (or `forgejo`) use these instead of the
+//! GitHub API. The instance is the repository's `provider_url`, or the host of
+//! its clone URL, and the token is read from `GITEA_TOKEN`.
+
+use crate::config::Repository;
+use crate::github::PrOptions;
+use crate::logging;
+use crate::state::{PullRequestRecord, StateStore};
+use anyhow::Result;
+use colored::*;
+use log::Level;
+use repos_gitea::{GiteaClient, GiteaRepoRef, parse_gitea_url};
+
+/// Client for the instance hosting a repository, with the repository's owner and name
+pub fn client_for(repo: &Repository) -> Result<(GiteaClient, GiteaRepoRef)> {
+    let mut repo_ref = parse_gitea_url(&repo.url)?;
+    if let Some(url) = repo.provider_url() {
+        repo_ref.instance_url = url.trim_end_matches('/').to_string();
+    }
+    Ok((GiteaClient::new(&repo_ref.instance_url, None), repo_ref))
+}
+
+/// Open a pull request and record it in the state store, returning its URL
+pub async fn create_pull_request(
+    repo: &Repository,
+    branch_name: &str,
+    base_branch: &str,
+    options: &PrOptions,
+) -> Result<String> {
+    let (client, repo_ref) = client_for(repo)?;
+    let params = repos_gitea::PullRequestParams::new(
+        &repo_ref.owner,
+        &repo_ref.repo,
+        &options.title,
+        branch_name,
+        base_branch,
+        &options.body,
+        options.draft,
+    );
+    let result = client.create_pull_request(params).await?;
+
+    let record = PullRequestRecord {
+        repository: repo.name.clone(),
+        owner: repo_ref.owner,
+        repo: repo_ref.repo,
+        number: result.number,
+        url: result.html_url.clone(),
+        branch: branch_name.to_string(),
+        base: base_branch.to_string(),
+        created_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+    };
+    if let Err(e) = StateStore::open_default().record_pull_request(record) {
+        logging::status(
+            Level::Warn,
+            &repo.name,
+            format!("Warning: Failed to record pull request: {}", e).yellow(),
+        );
+    }
+
+    Ok(result.html_url)
+}
+
+/// Default branch reported by the instance
+pub async fn fetch_default_branch(repo: &Repository) -> Result<String> {
+    let (client, repo_ref) = client_for(repo)?;
+    client
+        .get_repository_details(&repo_ref.owner, &repo_ref.repo)
+        .await?
+        .default_branch
+        .ok_or_else(|| anyhow::anyhow!("No default branch reported for {}", repo.url))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Provider, RepositoryBuilder};
+
+    #[test]
+    fn test_client_for_instance_url() {
+        let repo = RepositoryBuilder::new(
+            "api".to_string(),
+            "git@git.example.com:org/api.git".to_string(),
+        )
+        .with_provider(Provider::Gitea, None)
+        .build();
+        let (_, repo_ref) = client_for(&repo).unwrap();
+        assert_eq!(repo_ref.instance_url, "https://git.example.com");
+        assert_eq!(repo_ref.owner, "org");
+        assert_eq!(repo_ref.repo, "api");
+
+        // The SSH host often differs from the web host
+        let repo = RepositoryBuilder::new(
+            "api".to_string(),
+            "git@ssh.example.com:org/api.git".to_string(),
+        )
+        .with_provider(
+            Provider::Gitea,
+            Some("https://git.example.com/gitea/".to_string()),
+        )
+        .build();
+        let (_, repo_ref) = client_for(&repo).unwrap();
+        assert_eq!(repo_ref.instance_url, "https://git.example.com/gitea");
+    }
+}
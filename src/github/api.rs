@@ -1,13 +1,29 @@
 //! GitHub API operations
 
+use super::duplicates::{DuplicatePolicy, ExistingPolicy, Match, find_duplicate};
+use super::placeholders::Placeholders;
+use super::reviewers::ReviewRequest;
+use super::rollback::RemoteChange;
+use super::template::render_template;
 use super::types::PrOptions;
-use crate::config::Repository;
-use crate::constants::github::{DEFAULT_BRANCH_PREFIX, UUID_LENGTH};
+use crate::azure;
+use crate::config::{Provider, Repository};
+use crate::constants::github::{ARCHIVED_TTL_SECS, DEFAULT_BRANCH_PREFIX, UUID_LENGTH};
 use crate::git;
+use crate::gitea;
+use crate::logging;
+use crate::state::{PullRequestRecord, StateStore};
 use anyhow::Result;
 use colored::*;
+use log::Level;
+use repos_github::{PullRequestSummary, TreeFile};
+use std::path::Path;
+use std::time::Duration;
 use uuid::Uuid;
 
+/// Longest title slug used in a generated branch name
+const TITLE_SLUG_LENGTH: usize = 40;
+
 /// RAII guard to automatically restore the original branch on drop
 struct BranchGuard<'a> {
     repo_path: String,
@@ -20,35 +36,77 @@ impl Drop for BranchGuard<'_> {
         if let Some(ref original) = self.original_branch
             && let Err(e) = git::checkout_branch(&self.repo_path, original)
         {
-            eprintln!(
-                "{} | {}",
-                self.repo_name.cyan().bold(),
+            logging::status(
+                Level::Warn,
+                self.repo_name,
                 format!(
                     "Warning: Failed to restore original branch '{}': {}",
                     original, e
                 )
-                .yellow()
+                .yellow(),
             );
         }
     }
 }
 
+/// Reject pull request options that only the GitHub API supports
+fn check_pr_options(provider: Provider, options: &PrOptions) -> Result<()> {
+    if provider.is_github() {
+        return Ok(());
+    }
+    let unsupported = [
+        (options.auto_merge.is_some(), "--auto-merge"),
+        (options.review_request.is_some(), "--review-team"),
+        (options.rollback.is_some(), "--all-or-nothing"),
+        (options.duplicates.is_some(), "--duplicates"),
+        (
+            options.existing == Some(ExistingPolicy::Update),
+            "--update-existing",
+        ),
+        (
+            options.existing == Some(ExistingPolicy::Skip),
+            "--skip-existing",
+        ),
+    ];
+    if let Some((_, flag)) = unsupported.iter().find(|(set, _)| *set) {
+        anyhow::bail!("{} is not supported for {} repositories", flag, provider);
+    }
+    Ok(())
+}
+
 /// High-level function to create a PR from local changes
 ///
 /// This function encapsulates the entire pull request creation flow:
 /// 1. Check for changes in the workspace
 /// 2. Create branch, add, commit, and push changes
-/// 3. Create GitHub PR via API
+/// 3. Create the PR via the GitHub, Gitea or Azure DevOps API, depending on the
+///    repository's provider
 pub async fn create_pr_from_workspace(repo: &Repository, options: &PrOptions) -> Result<()> {
     let repo_path = repo.get_target_dir();
+    let provider = repo.provider();
+    check_pr_options(provider, options)?;
 
     // Check if repository has changes
     if !git::has_changes(&repo_path)? {
-        println!(
-            "{} | {}",
-            repo.name.cyan().bold(),
-            "No changes detected".yellow()
-        );
+        logging::status(Level::Info, &repo.name, "No changes detected".yellow());
+        return Ok(());
+    }
+    ensure_not_archived(repo, options, &StateStore::open_default()).await?;
+
+    if let Some(policy) = options.duplicates
+        && skip_duplicate(repo, &repo_path, policy, options).await?
+    {
+        return Ok(());
+    }
+
+    // Resolve the base branch before touching the workspace
+    let base_branch = resolve_base_branch(repo, options, &StateStore::open_default()).await;
+    let branch_name = branch_name_for(options);
+    let existing = find_existing_pr(repo, &branch_name, &base_branch, options).await?;
+    if options.existing == Some(ExistingPolicy::Skip)
+        && let Some(pr) = &existing
+    {
+        skip_existing(repo, pr);
         return Ok(());
     }
 
@@ -60,54 +118,441 @@ pub async fn create_pr_from_workspace(repo: &Repository, options: &PrOptions) ->
         repo_name: &repo.name,
     };
 
-    // Generate branch name if not provided
-    let branch_name = options.branch_name.clone().unwrap_or_else(|| {
-        format!(
-            "{}-{}",
-            DEFAULT_BRANCH_PREFIX,
-            &Uuid::new_v4().simple().to_string()[..UUID_LENGTH]
-        )
-    });
+    ensure_unprotected_branch(
+        repo,
+        &branch_name,
+        &base_branch,
+        options,
+        &StateStore::open_default(),
+    )
+    .await?;
 
-    // Create and checkout new branch
-    git::create_and_checkout_branch(&repo_path, &branch_name)?;
+    // Create and checkout new branch, or start the existing PR's branch over
+    if existing.is_some() {
+        git::reset_and_checkout_branch(&repo_path, &branch_name)?;
+    } else {
+        git::create_and_checkout_branch(&repo_path, &branch_name)?;
+    }
 
     // Add all changes
     git::add_all_changes(&repo_path)?;
 
     // Commit changes
-    let commit_message = options
-        .commit_msg
-        .clone()
-        .unwrap_or_else(|| options.title.clone());
-    git::commit_changes(&repo_path, &commit_message)?;
+    git::commit_changes(&repo_path, &commit_message_for(options))?;
 
     if !options.create_only {
         // Push branch
-        git::push_branch(&repo_path, &branch_name)?;
-
-        // Create PR via GitHub API
-        let pr_url = create_github_pr(repo, &branch_name, options).await?;
-        println!(
-            "{} | {} {}",
-            repo.name.cyan().bold(),
-            "Pull request created:".green(),
-            pr_url
+        let access = git::RemoteAccess::for_repository(
+            repo,
+            provider.is_github().then(|| options.token.clone()),
+        );
+        if let Some(pr) = existing {
+            // The lease needs the remote branch as last fetched
+            git::fetch(&repo_path)?;
+            git::force_push_with_lease(&repo_path, &branch_name, &access)?;
+            update_existing_pr(repo, &pr, options).await?;
+            return Ok(());
+        }
+        git::push_branch(&repo_path, &branch_name, &access)?;
+        record_branch(repo, &branch_name, options)?;
+
+        let pr_url = match provider {
+            Provider::GitHub => create_github_pr(repo, &branch_name, &base_branch, options).await?,
+            Provider::Gitea => {
+                gitea::create_pull_request(repo, &branch_name, &base_branch, options).await?
+            }
+            Provider::Azure => {
+                azure::create_pull_request(repo, &branch_name, &base_branch, options).await?
+            }
+        };
+        logging::status(
+            Level::Info,
+            &repo.name,
+            format!("{} {}", "Pull request created:".green(), pr_url),
         );
     } else {
-        println!(
-            "{} | {}",
-            repo.name.cyan().bold(),
-            "Branch created (not pushed, --create-only mode)".yellow()
+        logging::status(
+            Level::Info,
+            &repo.name,
+            format!(
+                "Branch created (not pushed, --create-only mode, base '{}')",
+                base_branch
+            )
+            .yellow(),
+        );
+    }
+
+    Ok(())
+}
+
+/// Create a PR from a file template using only the GitHub API
+///
+/// The rendered template files are committed on top of the base branch via the
+/// Git data API, so no local clone is needed. Repositories where the template
+/// changes nothing are skipped.
+pub async fn create_pr_from_template(
+    repo: &Repository,
+    options: &PrOptions,
+    template_dir: &Path,
+    placeholders: &Placeholders,
+) -> Result<()> {
+    let files = render_template(template_dir, repo, placeholders)?;
+    create_pr_from_files(repo, options, &files).await
+}
+
+/// Create a PR that writes `files` on top of the base branch using only the GitHub API
+///
+/// Repositories where the files change nothing are skipped.
+pub async fn create_pr_from_files(
+    repo: &Repository,
+    options: &PrOptions,
+    files: &[TreeFile],
+) -> Result<()> {
+    if !repo.provider().is_github() {
+        anyhow::bail!(
+            "Committing files through the API is only supported for GitHub repositories, not {}",
+            repo.provider()
+        );
+    }
+    ensure_not_archived(repo, options, &StateStore::open_default()).await?;
+    let client = repos_github::GitHubClient::new(Some(options.token.clone()));
+    let (owner, repo_name) = parse_github_url(&repo.url)?;
+
+    let base_branch = resolve_base_branch(repo, options, &StateStore::open_default()).await;
+    let base_sha = client
+        .get_branch_sha(&owner, &repo_name, &base_branch)
+        .await?;
+    let base_tree = client
+        .get_commit_tree_sha(&owner, &repo_name, &base_sha)
+        .await?;
+
+    let tree = client
+        .create_tree(&owner, &repo_name, &base_tree, files)
+        .await?;
+    if tree == base_tree {
+        logging::status(Level::Info, &repo.name, "No changes detected".yellow());
+        return Ok(());
+    }
+
+    let commit = client
+        .create_commit(
+            &owner,
+            &repo_name,
+            &commit_message_for(options),
+            &tree,
+            &base_sha,
+        )
+        .await?;
+    let branch_name = branch_name_for(options);
+    let existing = find_existing_pr(repo, &branch_name, &base_branch, options).await?;
+    if options.existing == Some(ExistingPolicy::Skip)
+        && let Some(pr) = &existing
+    {
+        skip_existing(repo, pr);
+        return Ok(());
+    }
+    ensure_unprotected_branch(
+        repo,
+        &branch_name,
+        &base_branch,
+        options,
+        &StateStore::open_default(),
+    )
+    .await?;
+    if let Some(pr) = existing {
+        client
+            .force_update_branch(&owner, &repo_name, &branch_name, &commit)
+            .await?;
+        return update_existing_pr(repo, &pr, options).await;
+    }
+    client
+        .create_branch(&owner, &repo_name, &branch_name, &commit)
+        .await?;
+    record_branch(repo, &branch_name, options)?;
+
+    if options.create_only {
+        logging::status(
+            Level::Info,
+            &repo.name,
+            format!(
+                "Branch '{}' created on GitHub (no PR, --create-only mode)",
+                branch_name
+            )
+            .yellow(),
+        );
+        return Ok(());
+    }
+
+    let pr_url = create_github_pr(repo, &branch_name, &base_branch, options).await?;
+    logging::status(
+        Level::Info,
+        &repo.name,
+        format!("{} {}", "Pull request created:".green(), pr_url),
+    );
+
+    Ok(())
+}
+
+/// Open PR from `branch_name` into `base_branch`, looked up only when an existing-PR policy is set
+async fn find_existing_pr(
+    repo: &Repository,
+    branch_name: &str,
+    base_branch: &str,
+    options: &PrOptions,
+) -> Result<Option<PullRequestSummary>> {
+    if options.existing.is_none() {
+        return Ok(None);
+    }
+    let client = repos_github::GitHubClient::new(Some(options.token.clone()));
+    let (owner, repo_name) = parse_github_url(&repo.url)?;
+    client
+        .find_open_pull_request(&owner, &repo_name, branch_name, base_branch)
+        .await
+}
+
+fn skip_existing(repo: &Repository, pr: &PullRequestSummary) {
+    logging::status(
+        Level::Info,
+        &repo.name,
+        format!("Skipping: pull request already open: {}", pr.html_url).yellow(),
+    );
+}
+
+/// Bring the title and body of a PR whose branch was just replaced up to date
+async fn update_existing_pr(
+    repo: &Repository,
+    pr: &PullRequestSummary,
+    options: &PrOptions,
+) -> Result<()> {
+    let client = repos_github::GitHubClient::new(Some(options.token.clone()));
+    let (owner, repo_name) = parse_github_url(&repo.url)?;
+    client
+        .update_pull_request(&owner, &repo_name, pr.number, &options.title, &options.body)
+        .await?;
+    logging::status(
+        Level::Info,
+        &repo.name,
+        format!("{} {}", "Pull request updated:".green(), pr.html_url),
+    );
+    Ok(())
+}
+
+/// Report an open PR that already contains the local changes
+///
+/// Returns whether the repository should be skipped, which only happens for
+/// identical changes under [`DuplicatePolicy::Skip`].
+async fn skip_duplicate(
+    repo: &Repository,
+    repo_path: &str,
+    policy: DuplicatePolicy,
+    options: &PrOptions,
+) -> Result<bool> {
+    let client = repos_github::GitHubClient::new(Some(options.token.clone()));
+    let (owner, repo_name) = parse_github_url(&repo.url)?;
+    let Some(duplicate) = find_duplicate(&client, &owner, &repo_name, repo_path).await? else {
+        return Ok(false);
+    };
+
+    match (duplicate.kind, policy) {
+        (Match::Identical, DuplicatePolicy::Skip) => {
+            logging::status(
+                Level::Info,
+                &repo.name,
+                format!(
+                    "Skipping: the same change is already pending in {}",
+                    duplicate.html_url
+                )
+                .yellow(),
+            );
+            Ok(true)
+        }
+        (Match::Identical, DuplicatePolicy::Warn) => {
+            logging::status(
+                Level::Warn,
+                &repo.name,
+                format!(
+                    "Warning: the same change is already pending in {}",
+                    duplicate.html_url
+                )
+                .yellow(),
+            );
+            Ok(false)
+        }
+        (Match::SameFiles, _) => {
+            logging::status(
+                Level::Warn,
+                &repo.name,
+                format!(
+                    "Warning: {} changes the same files; check it is not the same change",
+                    duplicate.html_url
+                )
+                .yellow(),
+            );
+            Ok(false)
+        }
+    }
+}
+
+/// Request reviews on a created PR; failures are reported but do not fail the PR
+async fn request_review(
+    client: &repos_github::GitHubClient,
+    repo: &Repository,
+    owner: &str,
+    repo_name: &str,
+    number: u64,
+    review_request: &ReviewRequest,
+) {
+    let (reviewers, teams, label) = match review_request {
+        ReviewRequest::Team { org, slug } => {
+            (Vec::new(), vec![slug.clone()], format!("@{}/{}", org, slug))
+        }
+        ReviewRequest::Balanced(pool) => match pool.next_reviewer() {
+            Some(reviewer) => (vec![reviewer.clone()], Vec::new(), reviewer),
+            None => return,
+        },
+    };
+
+    match client
+        .request_reviewers(owner, repo_name, number, &reviewers, &teams)
+        .await
+    {
+        Ok(()) => logging::status(
+            Level::Info,
+            &repo.name,
+            format!("Review requested from {}", label).green(),
+        ),
+        Err(e) => logging::status(
+            Level::Warn,
+            &repo.name,
+            format!("Warning: Failed to request review from {}: {}", label, e).yellow(),
+        ),
+    }
+}
+
+/// Refuse to use the base branch or a protected branch as the PR branch
+///
+/// Protection is looked up via the GitHub API only for branch names given with
+/// `--branch`, since generated names never exist yet. Protected branches are
+/// cached in the state store; lookup failures are not treated as protected.
+async fn ensure_unprotected_branch(
+    repo: &Repository,
+    branch_name: &str,
+    base_branch: &str,
+    options: &PrOptions,
+    store: &StateStore,
+) -> Result<()> {
+    if branch_name == base_branch {
+        anyhow::bail!(
+            "Refusing to push to '{}', the base branch of {}; choose a different --branch",
+            branch_name,
+            repo.name
+        );
+    }
+
+    let cached = store
+        .repository(&repo.name)
+        .map(|state| state.protected_branches.iter().any(|b| b == branch_name))
+        .unwrap_or(false);
+    if cached {
+        anyhow::bail!(
+            "Refusing to push to protected branch '{}' of {}; choose a different --branch",
+            branch_name,
+            repo.name
+        );
+    }
+
+    // Gitea and Azure DevOps refuse pushes to protected branches themselves
+    if options.branch_name.is_none() || !repo.provider().is_github() {
+        return Ok(());
+    }
+
+    let client = repos_github::GitHubClient::new(Some(options.token.clone()));
+    let (owner, repo_name) = parse_github_url(&repo.url)?;
+    if let Ok(Some(branch)) = client.get_branch(&owner, &repo_name, branch_name).await
+        && branch.protected
+    {
+        if let Err(e) = store.update_repository(&repo.name, |state| {
+            state.protected_branches.push(branch_name.to_string())
+        }) {
+            logging::status(
+                Level::Warn,
+                &repo.name,
+                format!("Warning: Failed to cache protected branch: {}", e).yellow(),
+            );
+        }
+        anyhow::bail!(
+            "Refusing to push to protected branch '{}' of {}; choose a different --branch",
+            branch_name,
+            repo.name
         );
     }
 
     Ok(())
 }
 
+/// Record a branch created on GitHub so an all-or-nothing run can delete it
+fn record_branch(repo: &Repository, branch_name: &str, options: &PrOptions) -> Result<()> {
+    if let Some(rollback) = &options.rollback {
+        let (owner, repo_name) = parse_github_url(&repo.url)?;
+        rollback.record(RemoteChange::Branch {
+            repository: repo.name.clone(),
+            owner,
+            repo: repo_name,
+            branch: branch_name.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Branch name for a PR, generated when not provided
+///
+/// With an existing-PR policy the name is derived from the title instead of
+/// being random, so that running the same change again finds the same PR.
+fn branch_name_for(options: &PrOptions) -> String {
+    options.branch_name.clone().unwrap_or_else(|| {
+        let suffix = if options.existing.is_some() {
+            title_slug(&options.title)
+        } else {
+            Uuid::new_v4().simple().to_string()[..UUID_LENGTH].to_string()
+        };
+        format!("{}-{}", DEFAULT_BRANCH_PREFIX, suffix)
+    })
+}
+
+/// Lowercase words of a title joined by dashes, short enough for a branch name
+fn title_slug(title: &str) -> String {
+    let mut slug = String::new();
+    for word in title
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+    {
+        if !slug.is_empty() && slug.len() + word.len() >= TITLE_SLUG_LENGTH {
+            break;
+        }
+        if !slug.is_empty() {
+            slug.push('-');
+        }
+        slug.push_str(&word.to_ascii_lowercase());
+    }
+    slug.truncate(TITLE_SLUG_LENGTH);
+    if slug.is_empty() {
+        slug.push_str("update");
+    }
+    slug
+}
+
+/// Commit message for a PR, falling back to the title
+fn commit_message_for(options: &PrOptions) -> String {
+    options
+        .commit_msg
+        .clone()
+        .unwrap_or_else(|| options.title.clone())
+}
+
 async fn create_github_pr(
     repo: &Repository,
     branch_name: &str,
+    base_branch: &str,
     options: &PrOptions,
 ) -> Result<String> {
     let client = repos_github::GitHubClient::new(Some(options.token.clone()));
@@ -115,28 +560,197 @@ async fn create_github_pr(
     // Extract owner and repo name from URL
     let (owner, repo_name) = parse_github_url(&repo.url)?;
 
-    // Determine base branch - get actual default branch if not specified
-    let base_branch = if let Some(ref base) = options.base_branch {
-        base.clone()
-    } else {
-        git::get_default_branch(&repo.get_target_dir())?
-    };
-
     let params = repos_github::PullRequestParams::new(
         &owner,
         &repo_name,
         &options.title,
         branch_name,
-        &base_branch,
+        base_branch,
         &options.body,
         options.draft,
     );
 
     let result = client.create_pull_request(params).await?;
 
+    if let Some(rollback) = &options.rollback {
+        rollback.record(RemoteChange::PullRequest {
+            repository: repo.name.clone(),
+            owner: owner.clone(),
+            repo: repo_name.clone(),
+            number: result.number,
+        });
+    }
+
+    let record = PullRequestRecord {
+        repository: repo.name.clone(),
+        owner: owner.clone(),
+        repo: repo_name.clone(),
+        number: result.number,
+        url: result.html_url.clone(),
+        branch: branch_name.to_string(),
+        base: base_branch.to_string(),
+        created_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+    };
+    if let Err(e) = StateStore::open_default().record_pull_request(record) {
+        logging::status(
+            Level::Warn,
+            &repo.name,
+            format!("Warning: Failed to record pull request: {}", e).yellow(),
+        );
+    }
+
+    if let Some(review_request) = &options.review_request {
+        request_review(
+            &client,
+            repo,
+            &owner,
+            &repo_name,
+            result.number,
+            review_request,
+        )
+        .await;
+    }
+
+    if let Some(method) = options.auto_merge {
+        match client.enable_auto_merge(&result.node_id, method).await {
+            Ok(()) => logging::status(
+                Level::Info,
+                &repo.name,
+                format!("Auto-merge enabled ({})", method).green(),
+            ),
+            Err(e) => logging::status(
+                Level::Warn,
+                &repo.name,
+                format!("Warning: Failed to enable auto-merge: {}", e).yellow(),
+            ),
+        }
+    }
+
     Ok(result.html_url)
 }
 
+/// Determine the base branch for a pull request
+///
+/// Resolution order: `--base`, the repository's `default_branch` setting, the
+/// value cached in the state store, `origin/HEAD` of the local clone, the GitHub
+/// API, and finally the current branch or fallback branch. Detected branches are
+/// cached so later runs skip the lookup.
+pub async fn resolve_base_branch(
+    repo: &Repository,
+    options: &PrOptions,
+    store: &StateStore,
+) -> String {
+    if let Some(branch) = known_base_branch(repo, options, store) {
+        return branch;
+    }
+
+    match fetch_default_branch(repo, &options.token).await {
+        Ok(branch) => {
+            cache_default_branch(store, repo, &branch);
+            branch
+        }
+        Err(_) => git::get_default_branch(&repo.get_target_dir())
+            .unwrap_or_else(|_| crate::constants::git::FALLBACK_BRANCH.to_string()),
+    }
+}
+
+/// Resolve the base branch without network access
+fn known_base_branch(repo: &Repository, options: &PrOptions, store: &StateStore) -> Option<String> {
+    if let Some(base) = options
+        .base_branch
+        .as_ref()
+        .or(repo.default_branch.as_ref())
+    {
+        return Some(base.clone());
+    }
+
+    if let Some(branch) = store
+        .repository(&repo.name)
+        .ok()
+        .and_then(|state| state.default_branch)
+    {
+        return Some(branch);
+    }
+
+    let branch = git::get_remote_default_branch(&repo.get_target_dir())?;
+    cache_default_branch(store, repo, &branch);
+    Some(branch)
+}
+
+async fn fetch_default_branch(repo: &Repository, token: &str) -> Result<String> {
+    match repo.provider() {
+        Provider::GitHub => {}
+        Provider::Gitea => return gitea::fetch_default_branch(repo).await,
+        Provider::Azure => return azure::fetch_default_branch(repo).await,
+    }
+    let client = repos_github::GitHubClient::new(Some(token.to_string()));
+    let (owner, repo_name) = parse_github_url(&repo.url)?;
+    client
+        .get_repository_details(&owner, &repo_name)
+        .await?
+        .default_branch
+        .ok_or_else(|| anyhow::anyhow!("No default branch reported for {}", repo.url))
+}
+
+/// Fail before touching the workspace when a repository is archived
+///
+/// Archived repositories accept neither pushes nor pull requests. GitHub is
+/// asked unless the state store already knows the repository is archived, or
+/// checked it within [`ARCHIVED_TTL_SECS`], and the answer is remembered along
+/// with the default branch, which spares [`resolve_base_branch`] a lookup of
+/// its own. Lookups that fail leave the problem to the pull request itself.
+async fn ensure_not_archived(
+    repo: &Repository,
+    options: &PrOptions,
+    store: &StateStore,
+) -> Result<()> {
+    let mut archived = repo.archived;
+    let checked_recently = store
+        .repository(&repo.name)
+        .is_ok_and(|state| state.archived_checked_within(Duration::from_secs(ARCHIVED_TTL_SECS)));
+    if !archived && !checked_recently && repo.provider().is_github() {
+        let client = repos_github::GitHubClient::new(Some(options.token.clone()));
+        let (owner, repo_name) = parse_github_url(&repo.url)?;
+        if let Ok(Some(details)) = client.find_repository(&owner, &repo_name).await {
+            cache_archived(store, repo, details.archived);
+            if let Some(branch) = &details.default_branch {
+                cache_default_branch(store, repo, branch);
+            }
+            archived = details.archived;
+        }
+    }
+    if archived {
+        anyhow::bail!(
+            "Repository is archived on {}; unarchive it to open pull requests",
+            repo.provider()
+        );
+    }
+    Ok(())
+}
+
+/// Remember whether a repository is archived, for the `archived` pseudo-tag
+pub(crate) fn cache_archived(store: &StateStore, repo: &Repository, archived: bool) {
+    if let Err(e) = store.record_archived(&repo.name, archived) {
+        logging::status(
+            Level::Warn,
+            &repo.name,
+            format!("Warning: Failed to cache archived state: {}", e).yellow(),
+        );
+    }
+}
+
+fn cache_default_branch(store: &StateStore, repo: &Repository, branch: &str) {
+    if let Err(e) = store.update_repository(&repo.name, |state| {
+        state.default_branch = Some(branch.to_string())
+    }) {
+        logging::status(
+            Level::Warn,
+            &repo.name,
+            format!("Warning: Failed to cache default branch: {}", e).yellow(),
+        );
+    }
+}
+
 /// Parse a GitHub URL to extract owner and repository name
 ///
 /// Supports both SSH (git@host:owner/repo) and HTTPS (https://host/owner/repo) formats.
@@ -204,6 +818,11 @@ mod tests {
             commit_msg: None,
             create_only: false,
             draft: false,
+            auto_merge: None,
+            review_request: None,
+            rollback: None,
+            duplicates: None,
+            existing: None,
         }
     }
 
@@ -231,7 +850,7 @@ mod tests {
         let options = create_test_pr_options();
 
         // This should exercise the GitHub client creation and URL parsing
-        let result = create_github_pr(&repo, "test-branch", &options).await;
+        let result = create_github_pr(&repo, "test-branch", "main", &options).await;
 
         // This will likely fail due to actual GitHub API call, but exercises the path
         assert!(result.is_err()); // Expected to fail without real API setup
@@ -249,6 +868,11 @@ mod tests {
             commit_msg: None,
             create_only: false,
             draft: false,
+            auto_merge: None,
+            review_request: None,
+            rollback: None,
+            duplicates: None,
+            existing: None,
         };
 
         // Simulate the branch name generation logic
@@ -280,6 +904,11 @@ mod tests {
             commit_msg: None,
             create_only: false,
             draft: false,
+            auto_merge: None,
+            review_request: None,
+            rollback: None,
+            duplicates: None,
+            existing: None,
         };
 
         let branch_name = options.branch_name.clone().unwrap_or_else(|| {
@@ -293,6 +922,30 @@ mod tests {
         assert_eq!(branch_name, custom_branch);
     }
 
+    #[test]
+    fn test_branch_name_with_existing_policy() {
+        let options = PrOptions {
+            title: "Bump serde to 1.0.200 (security fix)".to_string(),
+            ..create_test_pr_options()
+        }
+        .with_existing_policy(ExistingPolicy::Update);
+        assert_eq!(
+            branch_name_for(&options),
+            format!(
+                "{}-bump-serde-to-1-0-200-security-fix",
+                DEFAULT_BRANCH_PREFIX
+            )
+        );
+        // Stable across runs, so the next run finds the same PR
+        assert_eq!(branch_name_for(&options), branch_name_for(&options));
+
+        assert_eq!(
+            title_slug("Update all the dependencies of every workspace crate to latest"),
+            "update-all-the-dependencies-of-every"
+        );
+        assert_eq!(title_slug("!!!"), "update");
+    }
+
     #[test]
     fn test_commit_message_generation() {
         // Test commit message falls back to title when not provided
@@ -305,6 +958,11 @@ mod tests {
             commit_msg: None, // Should fall back to title
             create_only: false,
             draft: false,
+            auto_merge: None,
+            review_request: None,
+            rollback: None,
+            duplicates: None,
+            existing: None,
         };
 
         let commit_message = options_no_commit
@@ -324,6 +982,11 @@ mod tests {
             commit_msg: Some("Custom commit message".to_string()),
             create_only: false,
             draft: false,
+            auto_merge: None,
+            review_request: None,
+            rollback: None,
+            duplicates: None,
+            existing: None,
         };
 
         let commit_message = options_with_commit
@@ -346,6 +1009,11 @@ mod tests {
             commit_msg: None,
             create_only: true, // This should skip push and PR creation
             draft: false,
+            auto_merge: None,
+            review_request: None,
+            rollback: None,
+            duplicates: None,
+            existing: None,
         };
 
         assert!(options_create_only.create_only);
@@ -359,6 +1027,11 @@ mod tests {
             commit_msg: None,
             create_only: false, // This should do full flow
             draft: false,
+            auto_merge: None,
+            review_request: None,
+            rollback: None,
+            duplicates: None,
+            existing: None,
         };
 
         assert!(!options_full_flow.create_only);
@@ -376,6 +1049,11 @@ mod tests {
             commit_msg: None,
             create_only: false,
             draft: false,
+            auto_merge: None,
+            review_request: None,
+            rollback: None,
+            duplicates: None,
+            existing: None,
         };
 
         assert!(options_no_base.base_branch.is_none());
@@ -389,6 +1067,11 @@ mod tests {
             commit_msg: None,
             create_only: false,
             draft: false,
+            auto_merge: None,
+            review_request: None,
+            rollback: None,
+            duplicates: None,
+            existing: None,
         };
 
         assert_eq!(options_with_base.base_branch.unwrap(), "develop");
@@ -449,4 +1132,98 @@ mod tests {
         // These would fail at the API call level, not at URL parsing level
         // To catch these, we'd need to validate against known hosts or check for empty strings
     }
+
+    #[test]
+    fn test_known_base_branch_order() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let store = StateStore::new(temp_dir.path());
+        let mut repo = create_test_repository();
+        repo.path = Some(
+            temp_dir
+                .path()
+                .join("missing")
+                .to_string_lossy()
+                .to_string(),
+        );
+        let mut options = create_test_pr_options();
+
+        // Nothing known and no local clone
+        assert_eq!(known_base_branch(&repo, &options, &store), None);
+
+        // Cached value is used
+        store
+            .update_repository(&repo.name, |state| {
+                state.default_branch = Some("trunk".to_string())
+            })
+            .unwrap();
+        assert_eq!(
+            known_base_branch(&repo, &options, &store).as_deref(),
+            Some("trunk")
+        );
+
+        // Config override beats the cache
+        repo.default_branch = Some("develop".to_string());
+        assert_eq!(
+            known_base_branch(&repo, &options, &store).as_deref(),
+            Some("develop")
+        );
+
+        // --base beats everything
+        options.base_branch = Some("release".to_string());
+        assert_eq!(
+            known_base_branch(&repo, &options, &store).as_deref(),
+            Some("release")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ensure_unprotected_branch() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let store = StateStore::new(temp_dir.path());
+        let repo = create_test_repository();
+        let options = create_test_pr_options();
+
+        // Generated branch names are fine without any lookup
+        assert!(
+            ensure_unprotected_branch(&repo, "repos/abc123", "main", &options, &store)
+                .await
+                .is_ok()
+        );
+
+        // The base branch is always refused
+        let err = ensure_unprotected_branch(&repo, "main", "main", &options, &store)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("base branch"));
+
+        // Cached protected branches are refused
+        store
+            .update_repository(&repo.name, |state| {
+                state.protected_branches.push("release".to_string())
+            })
+            .unwrap();
+        let err = ensure_unprotected_branch(&repo, "release", "main", &options, &store)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("protected branch 'release'"));
+    }
+
+    #[test]
+    fn test_check_pr_options() {
+        let options = create_test_pr_options().with_auto_merge(repos_github::MergeMethod::Squash);
+        assert!(check_pr_options(Provider::GitHub, &options).is_ok());
+        let err = check_pr_options(Provider::Azure, &options).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "--auto-merge is not supported for Azure DevOps repositories"
+        );
+        assert!(check_pr_options(Provider::Gitea, &create_test_pr_options().as_draft()).is_ok());
+        let options = create_test_pr_options().with_existing_policy(ExistingPolicy::Skip);
+        assert_eq!(
+            check_pr_options(Provider::Gitea, &options)
+                .unwrap_err()
+                .to_string(),
+            "--skip-existing is not supported for Gitea repositories"
+        );
+    }
 }
@@ -0,0 +1,133 @@
+//! Pull request bodies from Markdown files
+//!
+//! A body read from `--body-file` or the `pr.template` setting gets a summary
+//! of the change: the files it touches and the saved `repos run` that produced
+//! it. The summary goes where the file has a `{{summary}}` placeholder, or at
+//! the end when it has none.
+
+use crate::config::Repository;
+use crate::utils::list_runs;
+use std::path::Path;
+
+/// Placeholder marking where the change summary goes
+pub const SUMMARY_PLACEHOLDER: &str = "summary";
+
+/// What a pull request changes in one repository
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChangeSummary {
+    /// Paths of the changed files, sorted
+    pub files: Vec<String>,
+    /// Command or recipe of the newest saved run that included the repository
+    pub produced_by: Option<String>,
+    /// Id of that run
+    pub run_id: Option<String>,
+}
+
+impl ChangeSummary {
+    /// Summary of `files`, attributed to the newest saved run in `output_dir` that included `repo`
+    ///
+    /// Unreadable run directories leave the summary without a producer.
+    pub fn new(repo: &Repository, files: Vec<String>, output_dir: &Path) -> Self {
+        let run = list_runs(output_dir).ok().and_then(|runs| {
+            runs.into_iter().find(|run| {
+                run.summary
+                    .results
+                    .iter()
+                    .any(|result| result.repository == repo.name)
+            })
+        });
+        Self {
+            files,
+            produced_by: run.as_ref().map(|run| run.label.clone()),
+            run_id: run.map(|run| run.id),
+        }
+    }
+
+    /// The summary as a Markdown section
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::from("### Change summary\n");
+        if let Some(produced_by) = &self.produced_by {
+            markdown.push_str(&format!("\nProduced by `{}`", produced_by));
+            if let Some(run_id) = &self.run_id {
+                markdown.push_str(&format!(" (run `{}`)", run_id));
+            }
+            markdown.push('\n');
+        }
+        if self.files.is_empty() {
+            markdown.push_str("\nNo files changed.\n");
+        } else {
+            markdown.push_str(&format!("\nFiles touched ({}):\n\n", self.files.len()));
+            for file in &self.files {
+                markdown.push_str(&format!("- `{}`\n", file));
+            }
+        }
+        markdown
+    }
+}
+
+/// Append a placeholder for the summary to a body that does not place it itself
+pub fn with_summary_placeholder(body: &str) -> String {
+    let placeholder = format!("{{{{{}}}}}", SUMMARY_PLACEHOLDER);
+    if body.contains(&placeholder) {
+        body.to_string()
+    } else {
+        format!("{}\n\n{}", body.trim_end(), placeholder)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_change_summary_from_latest_run() {
+        let temp_dir = TempDir::new().unwrap();
+        for (id, command, repos) in [
+            ("20261015-090000_cargo_update", "cargo update", vec!["api"]),
+            ("20261016-090000_make_fmt", "make fmt", vec!["web"]),
+        ] {
+            for repo in repos {
+                let dir = temp_dir.path().join("runs").join(id).join(repo);
+                std::fs::create_dir_all(&dir).unwrap();
+                std::fs::write(
+                    dir.join("metadata.json"),
+                    serde_json::json!({"command": command, "exit_code": 0, "repository": repo})
+                        .to_string(),
+                )
+                .unwrap();
+            }
+        }
+
+        let repo = Repository::new("api".to_string(), "git@github.com:org/api.git".to_string());
+        let summary = ChangeSummary::new(&repo, vec!["Cargo.lock".to_string()], temp_dir.path());
+        assert_eq!(summary.produced_by.as_deref(), Some("cargo update"));
+        assert_eq!(
+            summary.to_markdown(),
+            "### Change summary\n\n\
+             Produced by `cargo update` (run `20261015-090000_cargo_update`)\n\n\
+             Files touched (1):\n\n\
+             - `Cargo.lock`\n"
+        );
+
+        let other = Repository::new(
+            "docs".to_string(),
+            "git@github.com:org/docs.git".to_string(),
+        );
+        let summary = ChangeSummary::new(&other, Vec::new(), temp_dir.path());
+        assert_eq!(
+            summary.to_markdown(),
+            "### Change summary\n\nNo files changed.\n"
+        );
+    }
+
+    #[test]
+    fn test_with_summary_placeholder() {
+        assert_eq!(
+            with_summary_placeholder("## Why\n\nSecurity fix\n"),
+            "## Why\n\nSecurity fix\n\n{{summary}}"
+        );
+        let body = "{{summary}}\n\n## Why\n";
+        assert_eq!(with_summary_placeholder(body), body);
+    }
+}
@@ -0,0 +1,59 @@
+//! Setup of the on-disk cache of GitHub API responses
+//!
+//! Repository details, topics and open pull request lists are cached under
+//! `~/.repos/cache/github`. See [`repos_github::cache`] for how entries are
+//! served and revalidated.
+
+use crate::constants::github::{CACHE_DIR, CACHE_TTL_ENV, DEFAULT_CACHE_TTL_SECS};
+use anyhow::{Context, Result};
+use repos_github::{ResponseCache, set_default_cache};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Cache in the home directory, kept for `ttl` seconds or `REPOS_CACHE_TTL`
+pub fn default_cache(ttl: Option<u64>) -> Result<Option<ResponseCache>> {
+    let Some(home) = std::env::var_os("HOME") else {
+        return Ok(None);
+    };
+    let ttl = match ttl {
+        Some(ttl) => ttl,
+        None => match std::env::var(CACHE_TTL_ENV) {
+            Ok(value) => value.trim().parse().with_context(|| {
+                format!(
+                    "{} must be a number of seconds, got '{}'",
+                    CACHE_TTL_ENV, value
+                )
+            })?,
+            Err(_) => DEFAULT_CACHE_TTL_SECS,
+        },
+    };
+    Ok(Some(ResponseCache::new(
+        PathBuf::from(home).join(CACHE_DIR),
+        Duration::from_secs(ttl),
+    )))
+}
+
+/// Make every GitHub client of this process use the cache, unless `disabled`
+pub fn init(disabled: bool, ttl: Option<u64>) -> Result<()> {
+    let cache = if disabled { None } else { default_cache(ttl)? };
+    if let Some(cache) = &cache {
+        log::debug!("Caching GitHub responses in {}", cache.dir().display());
+    }
+    set_default_cache(cache);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_cache_ttl() {
+        let cache = default_cache(Some(0)).unwrap().unwrap();
+        assert!(cache.dir().ends_with(CACHE_DIR));
+        assert_eq!(
+            cache,
+            ResponseCache::new(cache.dir(), Duration::from_secs(0))
+        );
+    }
+}
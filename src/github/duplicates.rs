@@ -0,0 +1,128 @@
+//! Detection of open pull requests that already contain a change
+//!
+//! Re-running a codemod and `repos pr` would otherwise open a second pull
+//! request next to the one still waiting for review. The uncommitted changes in
+//! a repository are compared with each open pull request: first by the set of
+//! files changed, which needs one cheap API call per pull request, and for
+//! pull requests touching the same files by the patch ID of their diff.
+//!
+//! A pull request from the same branch into the same base is found directly,
+//! and [`ExistingPolicy`] decides whether it is updated or left alone.
+
+use crate::git;
+use anyhow::Result;
+use repos_github::{GitHubClient, PullRequestSummary};
+use std::str::FromStr;
+
+/// What to do when an open pull request already contains the same change
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    /// Open the pull request anyway, with a warning
+    Warn,
+    /// Leave repositories with an identical pending change alone
+    Skip,
+}
+
+impl FromStr for DuplicatePolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "warn" => Ok(DuplicatePolicy::Warn),
+            "skip" => Ok(DuplicatePolicy::Skip),
+            _ => anyhow::bail!("Unknown duplicate policy '{}' (expected warn or skip)", s),
+        }
+    }
+}
+
+/// What to do when a pull request from the same branch into the same base is already open
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExistingPolicy {
+    /// Replace the branch with the new changes and update the title and body
+    Update,
+    /// Leave the repository alone
+    Skip,
+}
+
+/// How closely an open pull request matches the local changes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Match {
+    /// The pull request makes exactly the same change
+    Identical,
+    /// The pull request changes the same files, but differently
+    SameFiles,
+}
+
+/// An open pull request that overlaps with the local changes
+#[derive(Debug, Clone)]
+pub struct Duplicate {
+    pub number: u64,
+    pub html_url: String,
+    pub kind: Match,
+}
+
+impl Duplicate {
+    fn new(pr: &PullRequestSummary, kind: Match) -> Self {
+        Self {
+            number: pr.number,
+            html_url: pr.html_url.clone(),
+            kind,
+        }
+    }
+}
+
+/// Find the open pull request closest to the uncommitted changes in `repo_path`
+///
+/// An identical change is preferred over one that only touches the same files.
+pub async fn find_duplicate(
+    client: &GitHubClient,
+    owner: &str,
+    repo: &str,
+    repo_path: &str,
+) -> Result<Option<Duplicate>> {
+    let local_files = git::workspace_files(repo_path)?;
+    if local_files.is_empty() {
+        return Ok(None);
+    }
+    let local_patch_id = git::patch_id(repo_path, &git::workspace_diff(repo_path, &[])?)?;
+
+    let mut same_files = None;
+    for pr in client.list_open_pull_requests(owner, repo).await? {
+        let mut files: Vec<String> = client
+            .list_pull_request_files(owner, repo, pr.number)
+            .await?
+            .into_iter()
+            .map(|file| file.filename)
+            .collect();
+        files.sort();
+        if files != local_files {
+            continue;
+        }
+
+        let diff = client.get_pull_request_diff(owner, repo, pr.number).await?;
+        if local_patch_id.is_some() && git::patch_id(repo_path, &diff)? == local_patch_id {
+            return Ok(Some(Duplicate::new(&pr, Match::Identical)));
+        }
+        same_files.get_or_insert_with(|| Duplicate::new(&pr, Match::SameFiles));
+    }
+
+    Ok(same_files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duplicate_policy() {
+        assert_eq!(
+            "warn".parse::<DuplicatePolicy>().unwrap(),
+            DuplicatePolicy::Warn
+        );
+        assert_eq!(
+            "skip".parse::<DuplicatePolicy>().unwrap(),
+            DuplicatePolicy::Skip
+        );
+        assert!("ignore".parse::<DuplicatePolicy>().is_err());
+    }
+}
@@ -6,15 +6,33 @@
 //! ## Architecture
 //!
 //! - [`api`]: High-level workflow functions (e.g., create PR from workspace)
+//! - [`body`]: PR bodies from Markdown files, with a summary of the change
+//! - [`cache`]: Setup of the on-disk cache of API responses
+//! - [`duplicates`]: Detection of open pull requests with the same change
+//! - [`placeholders`]: Per-repository placeholders in PR titles, bodies, branches and commit messages
+//! - [`reviewers`]: Reviewer assignment for fleet pull requests
+//! - [`rollback`]: Undo log for all-or-nothing fleet pull requests
+//! - [`rollout`]: Batched rollouts of fleet pull requests
+//! - [`status`]: Status of pull requests previously opened by `repos pr`
+//! - [`template`]: File templates for API-only pull requests
 //! - [`types`]: Workflow-specific types like PrOptions
 //!
 //! For low-level GitHub API operations, see the `repos-github` crate.
 
 pub mod api;
+pub mod body;
+pub mod cache;
+pub mod duplicates;
+pub mod placeholders;
+pub mod reviewers;
+pub mod rollback;
+pub mod rollout;
+pub mod status;
+pub mod template;
 pub mod types;
 
 // Re-export commonly used items for convenience
-pub use api::create_pr_from_workspace;
+pub use api::{create_pr_from_files, create_pr_from_template, create_pr_from_workspace};
 pub use types::PrOptions;
 
 // Re-export constants for easy access
@@ -0,0 +1,237 @@
+//! Placeholders in pull request titles, bodies, branch names and commit messages,
+//! and in every other per-repository template
+//!
+//! `{{repo.name}}`, `{{repo.url}}`, `{{repo.owner}}`, `{{repo.repo}}` and
+//! `{{date}}` are always available; values passed with `-D key=value`, or
+//! added by a command, are available as `{{key}}`. `{{name}}`, `{{url}}`,
+//! `{{owner}}` and `{{repo}}` still work as aliases of the `repo.` names unless
+//! a command gives them another meaning. In pull request text an unknown
+//! placeholder is an error rather than being left in place, so a typo never
+//! ends up in fifty branch names; file contents keep unknown placeholders, as
+//! they may belong to another template language. GitHub Actions expressions
+//! such as `${{ github.ref }}` are left alone. Bodies read from a file also get
+//! `{{summary}}`; see [`crate::github::body`].
+
+use crate::config::Repository;
+use crate::config::layout::parse_repository_url;
+use anyhow::Result;
+use std::collections::BTreeMap;
+
+/// Placeholder names that `-D` cannot redefine
+const BUILT_IN: &[&str] = &[
+    "repo.name",
+    "repo.url",
+    "repo.owner",
+    "repo.repo",
+    "date",
+    "summary",
+];
+
+/// Older names of the built-in placeholders, used when no value has that name
+const ALIASES: &[(&str, &str)] = &[
+    ("name", "repo.name"),
+    ("url", "repo.url"),
+    ("owner", "repo.owner"),
+    ("repo", "repo.repo"),
+];
+
+/// Values for the placeholders shared by every repository of a run
+#[derive(Debug, Clone)]
+pub struct Placeholders {
+    date: String,
+    defines: BTreeMap<String, String>,
+}
+
+/// Parse a `-D key=value` define
+pub fn parse_define(define: &str) -> Result<(String, String)> {
+    let Some((key, value)) = define.split_once('=') else {
+        anyhow::bail!("Invalid define '{}': expected KEY=VALUE", define);
+    };
+    let key = key.trim();
+    if key.is_empty() || key.contains(['{', '}']) {
+        anyhow::bail!("Invalid define '{}': missing or invalid key", define);
+    }
+    if BUILT_IN.contains(&key) {
+        anyhow::bail!("Cannot redefine the built-in placeholder '{{{{{}}}}}'", key);
+    }
+    Ok((key.to_string(), value.to_string()))
+}
+
+impl Default for Placeholders {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+impl Placeholders {
+    /// Placeholders for a run today, with the given `-D` defines
+    pub fn new(defines: Vec<(String, String)>) -> Self {
+        Self::with_date(
+            &chrono::Local::now().format("%Y-%m-%d").to_string(),
+            defines,
+        )
+    }
+
+    /// Placeholders with a fixed `{{date}}`
+    pub fn with_date(date: &str, defines: Vec<(String, String)>) -> Self {
+        Self {
+            date: date.to_string(),
+            defines: defines.into_iter().collect(),
+        }
+    }
+
+    /// Add a value that only this run, or repository, needs
+    pub fn with_value(mut self, key: &str, value: &str) -> Self {
+        self.defines.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    fn value(&self, key: &str, repo: &Repository) -> Option<String> {
+        let url_part = |owner: bool| {
+            parse_repository_url(&repo.url)
+                .map(|parts| if owner { parts.owner } else { parts.repo })
+                .unwrap_or_default()
+        };
+        match key {
+            "repo.name" => Some(repo.name.clone()),
+            "repo.url" => Some(repo.url.clone()),
+            "repo.owner" => Some(url_part(true)),
+            "repo.repo" => Some(url_part(false)),
+            "date" => Some(self.date.clone()),
+            _ => self.defines.get(key).cloned().or_else(|| {
+                let (_, name) = ALIASES.iter().find(|(alias, _)| *alias == key)?;
+                self.value(name, repo)
+            }),
+        }
+    }
+
+    /// `text` with every placeholder that has a value replaced, and the first unknown one
+    fn replace(&self, text: &str, repo: &Repository) -> (String, Option<String>) {
+        let mut rendered = String::with_capacity(text.len());
+        let mut unknown = None;
+        let mut rest = text;
+        while let Some(start) = rest.find("{{") {
+            let Some(len) = rest[start + 2..].find("}}") else {
+                break;
+            };
+            let end = start + 2 + len + 2;
+            let key = rest[start + 2..start + 2 + len].trim();
+            match self.value(key, repo) {
+                Some(value) if !rest[..start].ends_with('$') => {
+                    rendered.push_str(&rest[..start]);
+                    rendered.push_str(&value);
+                }
+                None if !rest[..start].ends_with('$') => {
+                    unknown.get_or_insert_with(|| key.to_string());
+                    rendered.push_str(&rest[..end]);
+                }
+                _ => rendered.push_str(&rest[..end]),
+            }
+            rest = &rest[end..];
+        }
+        rendered.push_str(rest);
+        (rendered, unknown)
+    }
+
+    /// Replace the placeholders in `text` for one repository, failing on unknown ones
+    pub fn render(&self, text: &str, repo: &Repository) -> Result<String> {
+        match self.replace(text, repo) {
+            (rendered, None) => Ok(rendered),
+            (_, Some(key)) => anyhow::bail!(
+                "Unknown placeholder '{{{{{}}}}}' in '{}'; define it with -D {}=VALUE",
+                key,
+                text,
+                key
+            ),
+        }
+    }
+
+    /// Replace the placeholders in `text` for one repository, leaving unknown ones in place
+    pub fn fill(&self, text: &str, repo: &Repository) -> String {
+        self.replace(text, repo).0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo() -> Repository {
+        Repository::new(
+            "api".to_string(),
+            "git@github.com:org/api-service.git".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_render() {
+        let placeholders =
+            Placeholders::with_date("2026-10-16", vec![("ticket".into(), "OPS-42".into())]);
+        assert_eq!(
+            placeholders
+                .render("{{ticket}}/{{repo.name}}-{{ date }}", &repo())
+                .unwrap(),
+            "OPS-42/api-2026-10-16"
+        );
+        assert_eq!(
+            placeholders
+                .render("Bump {{repo.owner}}/{{repo.repo}}", &repo())
+                .unwrap(),
+            "Bump org/api-service"
+        );
+        assert_eq!(
+            placeholders.render("no {{ closing", &repo()).unwrap(),
+            "no {{ closing"
+        );
+        assert_eq!(
+            placeholders
+                .render("Runs on ${{ github.ref }} for {{repo.name}}", &repo())
+                .unwrap(),
+            "Runs on ${{ github.ref }} for api"
+        );
+
+        let err = placeholders.render("{{tciket}}: fix", &repo()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unknown placeholder '{{tciket}}' in '{{tciket}}: fix'; define it with -D tciket=VALUE"
+        );
+    }
+
+    #[test]
+    fn test_fill_and_aliases() {
+        let placeholders = Placeholders::with_date("2026-10-16", Vec::new());
+        assert_eq!(
+            placeholders.fill("* @{{owner}}/{{name}}-team {{ .Values.image }}", &repo()),
+            "* @org/api-team {{ .Values.image }}"
+        );
+        assert_eq!(
+            placeholders.render("{{repo}} at {{url}}", &repo()).unwrap(),
+            "api-service at git@github.com:org/api-service.git"
+        );
+
+        // Values a command adds win over the aliases
+        let placeholders = placeholders.with_value("url", "https://github.com/org/api/pull/7");
+        assert_eq!(
+            placeholders.fill("{{url}} in {{repo.url}}", &repo()),
+            "https://github.com/org/api/pull/7 in git@github.com:org/api-service.git"
+        );
+    }
+
+    #[test]
+    fn test_parse_define() {
+        assert_eq!(
+            parse_define("ticket=OPS-42").unwrap(),
+            ("ticket".to_string(), "OPS-42".to_string())
+        );
+        assert_eq!(
+            parse_define("note=a=b").unwrap(),
+            ("note".to_string(), "a=b".to_string())
+        );
+        assert!(parse_define("ticket").is_err());
+        assert!(parse_define("=value").is_err());
+        assert_eq!(
+            parse_define("date=tomorrow").unwrap_err().to_string(),
+            "Cannot redefine the built-in placeholder '{{date}}'"
+        );
+    }
+}
@@ -0,0 +1,133 @@
+//! Reviewer assignment for fleet pull requests
+
+use anyhow::Result;
+use repos_github::GitHubClient;
+use std::sync::{Arc, Mutex};
+
+/// Who is asked to review each created pull request
+#[derive(Debug, Clone)]
+pub enum ReviewRequest {
+    /// Request a review from the whole team
+    Team { org: String, slug: String },
+    /// Request a review from one team member per pull request, spreading the load
+    Balanced(Arc<ReviewerPool>),
+}
+
+impl ReviewRequest {
+    /// Build the review request for `--review-team`, fetching team members when balancing
+    pub async fn for_team(client: &GitHubClient, team: &str, balance: bool) -> Result<Self> {
+        let (org, slug) = parse_team(team)?;
+        if !balance {
+            return Ok(ReviewRequest::Team { org, slug });
+        }
+
+        let mut members = client.list_team_members(&org, &slug).await?;
+        // Authors cannot review their own pull requests
+        if let Ok(login) = client.get_authenticated_user().await {
+            members.retain(|member| *member != login);
+        }
+        if members.is_empty() {
+            anyhow::bail!("Team '{}' has no members who can review", team);
+        }
+
+        // Start from each member's current review queue; without it, assignment is round-robin
+        let mut loads = Vec::new();
+        for member in members {
+            let load = client.count_review_requests(&member).await.unwrap_or(0);
+            loads.push((member, load));
+        }
+
+        Ok(ReviewRequest::Balanced(Arc::new(ReviewerPool::new(loads))))
+    }
+}
+
+/// Team members with the number of reviews each is waiting on
+#[derive(Debug)]
+pub struct ReviewerPool {
+    loads: Mutex<Vec<(String, u64)>>,
+}
+
+impl ReviewerPool {
+    pub fn new(loads: Vec<(String, u64)>) -> Self {
+        Self {
+            loads: Mutex::new(loads),
+        }
+    }
+
+    /// Pick the member with the fewest pending reviews and count the new one
+    ///
+    /// Ties go to the member listed first, and the picked member moves to the
+    /// back of the list so equal loads rotate round-robin.
+    pub fn next_reviewer(&self) -> Option<String> {
+        let mut loads = self.loads.lock().unwrap_or_else(|e| e.into_inner());
+        let index = loads
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, (_, load))| *load)
+            .map(|(index, _)| index)?;
+
+        let (member, load) = loads.remove(index);
+        loads.push((member.clone(), load + 1));
+        Some(member)
+    }
+}
+
+/// Split `@org/team` (the `@` is optional) into organization and team slug
+fn parse_team(team: &str) -> Result<(String, String)> {
+    let trimmed = team.trim_start_matches('@');
+    match trimmed.split_once('/') {
+        Some((org, slug)) if !org.is_empty() && !slug.is_empty() && !slug.contains('/') => {
+            Ok((org.to_string(), slug.to_string()))
+        }
+        _ => anyhow::bail!("Invalid team '{}', expected @org/team", team),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_team() {
+        assert_eq!(
+            parse_team("@org/platform").unwrap(),
+            ("org".to_string(), "platform".to_string())
+        );
+        assert_eq!(
+            parse_team("org/platform").unwrap(),
+            ("org".to_string(), "platform".to_string())
+        );
+        assert!(parse_team("@org").is_err());
+        assert!(parse_team("@org/").is_err());
+        assert!(parse_team("@org/a/b").is_err());
+    }
+
+    #[test]
+    fn test_round_robin_with_equal_loads() {
+        let pool = ReviewerPool::new(vec![
+            ("alice".to_string(), 0),
+            ("bob".to_string(), 0),
+            ("carol".to_string(), 0),
+        ]);
+
+        let picks: Vec<String> = (0..6).filter_map(|_| pool.next_reviewer()).collect();
+        assert_eq!(picks, ["alice", "bob", "carol", "alice", "bob", "carol"]);
+    }
+
+    #[test]
+    fn test_least_loaded_first() {
+        let pool = ReviewerPool::new(vec![
+            ("alice".to_string(), 3),
+            ("bob".to_string(), 0),
+            ("carol".to_string(), 1),
+        ]);
+
+        let picks: Vec<String> = (0..6).filter_map(|_| pool.next_reviewer()).collect();
+        assert_eq!(picks, ["bob", "carol", "bob", "carol", "bob", "alice"]);
+    }
+
+    #[test]
+    fn test_empty_pool() {
+        assert_eq!(ReviewerPool::new(Vec::new()).next_reviewer(), None);
+    }
+}
@@ -0,0 +1,154 @@
+//! Undo log for all-or-nothing fleet pull requests
+
+use crate::logging;
+use crate::state::StateStore;
+use anyhow::Result;
+use colored::*;
+use log::Level;
+use repos_github::GitHubClient;
+use std::sync::Mutex;
+
+/// A change made on GitHub while creating a fleet pull request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RemoteChange {
+    /// A branch pushed or created on GitHub
+    Branch {
+        repository: String,
+        owner: String,
+        repo: String,
+        branch: String,
+    },
+    /// An opened pull request
+    PullRequest {
+        repository: String,
+        owner: String,
+        repo: String,
+        number: u64,
+    },
+}
+
+impl RemoteChange {
+    fn repository(&self) -> &str {
+        match self {
+            RemoteChange::Branch { repository, .. }
+            | RemoteChange::PullRequest { repository, .. } => repository,
+        }
+    }
+}
+
+/// Remote changes recorded during a run, so they can be undone if any repository fails
+#[derive(Debug, Default)]
+pub struct Rollback {
+    changes: Mutex<Vec<RemoteChange>>,
+}
+
+impl Rollback {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, change: RemoteChange) {
+        self.changes
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(change);
+    }
+
+    /// Changes recorded so far, oldest first
+    pub fn changes(&self) -> Vec<RemoteChange> {
+        self.changes
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Close recorded pull requests and delete recorded branches, newest first
+    ///
+    /// Every change is attempted even if earlier ones fail. Returns the number of
+    /// changes undone and the failures.
+    pub async fn undo(&self, client: &GitHubClient) -> (usize, Vec<(String, anyhow::Error)>) {
+        let changes = std::mem::take(&mut *self.changes.lock().unwrap_or_else(|e| e.into_inner()));
+        let mut undone = 0;
+        let mut errors = Vec::new();
+
+        for change in changes.iter().rev() {
+            match undo_change(client, change).await {
+                Ok(message) => {
+                    logging::status(Level::Info, change.repository(), message.yellow());
+                    undone += 1;
+                }
+                Err(e) => {
+                    logging::status(
+                        Level::Error,
+                        change.repository(),
+                        format!("Error: Rollback failed: {e}").red(),
+                    );
+                    errors.push((change.repository().to_string(), e));
+                }
+            }
+        }
+
+        (undone, errors)
+    }
+}
+
+async fn undo_change(client: &GitHubClient, change: &RemoteChange) -> Result<String> {
+    match change {
+        RemoteChange::PullRequest {
+            owner,
+            repo,
+            number,
+            ..
+        } => {
+            client.close_pull_request(owner, repo, *number).await?;
+            StateStore::open_default().remove_pull_requests(|record| {
+                record.owner == *owner && record.repo == *repo && record.number == *number
+            })?;
+            Ok(format!("Rolled back: closed pull request #{}", number))
+        }
+        RemoteChange::Branch {
+            owner,
+            repo,
+            branch,
+            ..
+        } => {
+            client.delete_branch(owner, repo, branch).await?;
+            Ok(format!("Rolled back: deleted branch '{}'", branch))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_changes() {
+        let rollback = Rollback::new();
+        let branch = RemoteChange::Branch {
+            repository: "api".to_string(),
+            owner: "org".to_string(),
+            repo: "api".to_string(),
+            branch: "automated-changes-abc123".to_string(),
+        };
+        let pull_request = RemoteChange::PullRequest {
+            repository: "api".to_string(),
+            owner: "org".to_string(),
+            repo: "api".to_string(),
+            number: 12,
+        };
+
+        rollback.record(branch.clone());
+        rollback.record(pull_request.clone());
+
+        assert_eq!(rollback.changes(), vec![branch, pull_request]);
+        assert_eq!(rollback.changes()[0].repository(), "api");
+    }
+
+    #[tokio::test]
+    async fn test_undo_without_changes() {
+        let (undone, errors) = Rollback::new().undo(&GitHubClient::new(None)).await;
+        assert_eq!(undone, 0);
+        assert!(errors.is_empty());
+    }
+}
@@ -0,0 +1,123 @@
+//! Batched rollouts of fleet pull requests
+//!
+//! `repos pr --batch-size N` opens pull requests for at most N repositories at
+//! a time. Progress is kept in the state file under the PR title, so running
+//! the same command again continues the rollout: the next batch fills the
+//! slots of merged or closed pull requests, or, with `--batch-delay`, opens a
+//! full batch once the delay has passed since the previous one.
+
+use crate::state::Rollout;
+use anyhow::Result;
+use chrono::NaiveDateTime;
+use std::time::Duration;
+
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Parse a `--batch-delay` such as `90s`, `30m`, `2h` or `1d`; bare numbers are seconds
+pub fn parse_delay(text: &str) -> Result<Duration> {
+    let text = text.trim();
+    let (number, unit) = text.split_at(
+        text.find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(text.len()),
+    );
+    let Ok(number) = number.parse::<u64>() else {
+        anyhow::bail!(
+            "Invalid delay '{}': expected a number with an optional s, m, h or d suffix",
+            text
+        );
+    };
+    let seconds = match unit {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => anyhow::bail!(
+            "Invalid delay '{}': unknown unit '{}', expected s, m, h or d",
+            text,
+            unit
+        ),
+    };
+    Ok(Duration::from_secs(number * seconds))
+}
+
+/// When the batch after this one may open regardless of open pull requests
+pub fn next_batch_at(rollout: &Rollout, delay: Duration) -> Option<NaiveDateTime> {
+    let started =
+        NaiveDateTime::parse_from_str(&rollout.batch_started_at, TIMESTAMP_FORMAT).ok()?;
+    Some(started + chrono::Duration::from_std(delay).ok()?)
+}
+
+/// How many repositories the next batch of a rollout may take
+///
+/// `open` is the number of the rollout's pull requests that are still open.
+pub fn batch_capacity(
+    batch_size: usize,
+    open: usize,
+    rollout: Option<&Rollout>,
+    delay: Option<Duration>,
+    now: NaiveDateTime,
+) -> usize {
+    let Some(rollout) = rollout else {
+        return batch_size;
+    };
+    let delay_passed = delay
+        .and_then(|delay| next_batch_at(rollout, delay))
+        .is_some_and(|at| at <= now);
+    if delay_passed {
+        batch_size
+    } else {
+        batch_size.saturating_sub(open)
+    }
+}
+
+/// Current local time, which stored timestamps are in
+pub fn now() -> NaiveDateTime {
+    chrono::Local::now().naive_local()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(timestamp: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT).unwrap()
+    }
+
+    #[test]
+    fn test_parse_delay() {
+        assert_eq!(parse_delay("45").unwrap(), Duration::from_secs(45));
+        assert_eq!(parse_delay("90s").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse_delay("30m").unwrap(), Duration::from_secs(1800));
+        assert_eq!(parse_delay("2h").unwrap(), Duration::from_secs(7200));
+        assert_eq!(parse_delay("1d").unwrap(), Duration::from_secs(86400));
+        assert!(parse_delay("").is_err());
+        assert!(parse_delay("h").is_err());
+        assert!(parse_delay("3w").is_err());
+    }
+
+    #[test]
+    fn test_batch_capacity() {
+        let rollout = Rollout {
+            started_at: "2026-10-16 09:00:00".to_string(),
+            batch_started_at: "2026-10-16 12:00:00".to_string(),
+            done: vec!["api".to_string()],
+        };
+        let noon = at("2026-10-16 12:30:00");
+        let hour = Some(Duration::from_secs(3600));
+
+        // A new rollout opens a full batch
+        assert_eq!(batch_capacity(5, 0, None, None, noon), 5);
+        // Otherwise only the slots of finished pull requests are filled
+        assert_eq!(batch_capacity(5, 3, Some(&rollout), None, noon), 2);
+        assert_eq!(batch_capacity(5, 7, Some(&rollout), hour, noon), 0);
+        // Until the delay has passed
+        assert_eq!(
+            batch_capacity(5, 7, Some(&rollout), hour, at("2026-10-16 13:00:00")),
+            5
+        );
+        assert_eq!(
+            next_batch_at(&rollout, Duration::from_secs(3600)),
+            Some(at("2026-10-16 13:00:00"))
+        );
+    }
+}
@@ -0,0 +1,368 @@
+//! Status of pull requests previously opened by `repos pr`
+
+use crate::state::PullRequestRecord;
+use anyhow::Result;
+use colored::*;
+use repos_github::{
+    CheckRun, CombinedStatus, GitHubClient, MergeQueueInfo, PullRequestOverview, PullRequestRef,
+    PullRequestReview,
+};
+use std::collections::HashMap;
+
+/// Current state of a recorded pull request
+#[derive(Debug, Clone)]
+pub struct PullRequestStatus {
+    pub record: PullRequestRecord,
+    /// `open`, `merged` or `closed`
+    pub state: String,
+    pub ci: CiState,
+    pub review: ReviewState,
+    /// `None` while GitHub is still computing mergeability
+    pub mergeable: Option<bool>,
+    /// Merge queue of the base branch, if it uses one
+    pub merge_queue: Option<MergeQueueInfo>,
+}
+
+impl PullRequestStatus {
+    pub fn is_open(&self) -> bool {
+        self.state == "open"
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CiState {
+    Success,
+    Pending,
+    Failure,
+    /// No statuses or check runs reported
+    None,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewState {
+    Approved,
+    ChangesRequested,
+    /// No decisive review yet
+    Pending,
+}
+
+/// Query GitHub for the current state of many recorded pull requests, in order
+///
+/// The pull requests are looked up in batched GraphQL queries; when GraphQL
+/// is unavailable each one is looked up over REST instead.
+pub async fn fetch_statuses(
+    client: &GitHubClient,
+    records: &[PullRequestRecord],
+) -> Vec<Result<PullRequestStatus>> {
+    let refs: Vec<PullRequestRef> = records
+        .iter()
+        .map(|record| PullRequestRef {
+            owner: record.owner.clone(),
+            repo: record.repo.clone(),
+            number: record.number,
+            base: record.base.clone(),
+        })
+        .collect();
+
+    match client.get_pull_requests(&refs).await {
+        Ok(results) => records
+            .iter()
+            .zip(results)
+            .map(|(record, result)| Ok(status_from_overview(record, result?)))
+            .collect(),
+        Err(e) => {
+            log::debug!("Falling back to REST for pull request status: {:#}", e);
+            futures::future::join_all(records.iter().map(|record| fetch_status(client, record)))
+                .await
+        }
+    }
+}
+
+fn status_from_overview(record: &PullRequestRecord, pr: PullRequestOverview) -> PullRequestStatus {
+    let (ci, review, merge_queue) = if pr.is_open() {
+        (
+            rollup_ci_state(pr.checks.as_deref()),
+            review_state(&pr.reviews),
+            pr.merge_queue.filter(|queue| queue.enabled),
+        )
+    } else {
+        (CiState::None, ReviewState::Pending, None)
+    };
+
+    PullRequestStatus {
+        record: record.clone(),
+        state: pr.state,
+        ci,
+        review,
+        mergeable: pr.mergeable,
+        merge_queue,
+    }
+}
+
+/// Query GitHub for the current state of a recorded pull request
+pub async fn fetch_status(
+    client: &GitHubClient,
+    record: &PullRequestRecord,
+) -> Result<PullRequestStatus> {
+    let (owner, repo) = (&record.owner, &record.repo);
+    let pr = client.get_pull_request(owner, repo, record.number).await?;
+
+    let (ci, review, merge_queue) = if pr.state == "open" {
+        let status = client
+            .get_combined_status(owner, repo, &pr.head.sha)
+            .await?;
+        let check_runs = client.list_check_runs(owner, repo, &pr.head.sha).await?;
+        let reviews = client
+            .list_pull_request_reviews(owner, repo, record.number)
+            .await?;
+        // Merge queues are GraphQL-only and absent on older GitHub Enterprise
+        // servers, so a failed lookup is treated as "no queue"
+        let merge_queue = client
+            .get_merge_queue_info(owner, repo, record.number, &record.base)
+            .await
+            .ok()
+            .filter(|queue| queue.enabled);
+        (
+            ci_state(&status, &check_runs),
+            review_state(&reviews),
+            merge_queue,
+        )
+    } else {
+        (CiState::None, ReviewState::Pending, None)
+    };
+
+    let state = if pr.merged {
+        "merged".to_string()
+    } else {
+        pr.state
+    };
+
+    Ok(PullRequestStatus {
+        record: record.clone(),
+        state,
+        ci,
+        review,
+        mergeable: pr.mergeable,
+        merge_queue,
+    })
+}
+
+/// Combine commit statuses and check runs into a single CI state
+fn ci_state(status: &CombinedStatus, check_runs: &[CheckRun]) -> CiState {
+    let check_failed = check_runs.iter().any(|run| {
+        matches!(
+            run.conclusion.as_deref(),
+            Some("failure" | "timed_out" | "cancelled" | "action_required")
+        )
+    });
+    let check_pending = check_runs.iter().any(|run| run.status != "completed");
+    let has_statuses = status.total_count > 0;
+
+    if check_failed || (has_statuses && status.state == "failure") {
+        CiState::Failure
+    } else if check_pending || (has_statuses && status.state == "pending") {
+        CiState::Pending
+    } else if has_statuses || !check_runs.is_empty() {
+        CiState::Success
+    } else {
+        CiState::None
+    }
+}
+
+/// CI state of the combined status check rollup GraphQL reports for a commit
+fn rollup_ci_state(rollup: Option<&str>) -> CiState {
+    match rollup {
+        Some("SUCCESS") => CiState::Success,
+        Some("PENDING" | "EXPECTED") => CiState::Pending,
+        Some(_) => CiState::Failure,
+        None => CiState::None,
+    }
+}
+
+/// Summarise reviews using each reviewer's latest decisive review
+fn review_state(reviews: &[PullRequestReview]) -> ReviewState {
+    let mut latest: HashMap<&str, &str> = HashMap::new();
+    for review in reviews {
+        if let Some(user) = &review.user
+            && matches!(
+                review.state.as_str(),
+                "APPROVED" | "CHANGES_REQUESTED" | "DISMISSED"
+            )
+        {
+            latest.insert(&user.login, &review.state);
+        }
+    }
+
+    if latest.values().any(|state| *state == "CHANGES_REQUESTED") {
+        ReviewState::ChangesRequested
+    } else if latest.values().any(|state| *state == "APPROVED") {
+        ReviewState::Approved
+    } else {
+        ReviewState::Pending
+    }
+}
+
+/// Print a table with one row per pull request
+pub fn print_status_table(statuses: &[PullRequestStatus]) {
+    let name_width = statuses
+        .iter()
+        .map(|s| s.record.repository.len())
+        .max()
+        .unwrap_or(0)
+        .max("REPOSITORY".len());
+    let branch_width = statuses
+        .iter()
+        .map(|s| s.record.branch.len())
+        .max()
+        .unwrap_or(0)
+        .max("BRANCH".len());
+
+    println!();
+    println!(
+        "{:<name_width$}  {:>6}  {:<branch_width$}  {:<7}  {:<8}  {:<17}  {:<9}  {}",
+        "REPOSITORY".bold(),
+        "PR".bold(),
+        "BRANCH".bold(),
+        "STATE".bold(),
+        "CI".bold(),
+        "REVIEW".bold(),
+        "MERGEABLE".bold(),
+        "QUEUE".bold(),
+    );
+    for status in statuses {
+        let state = match status.state.as_str() {
+            "open" => status.state.green(),
+            "merged" => status.state.magenta(),
+            _ => status.state.red(),
+        };
+        let ci = match status.ci {
+            CiState::Success => "success".green(),
+            CiState::Pending => "pending".yellow(),
+            CiState::Failure => "failure".red(),
+            CiState::None => "-".normal(),
+        };
+        let review = match status.review {
+            ReviewState::Approved => "approved".green(),
+            ReviewState::ChangesRequested => "changes requested".red(),
+            ReviewState::Pending => "pending".yellow(),
+        };
+        let mergeable = match (status.is_open(), status.mergeable) {
+            (false, _) => "-".normal(),
+            (true, Some(true)) => "yes".green(),
+            (true, Some(false)) => "no".red(),
+            (true, None) => "unknown".yellow(),
+        };
+        let queue = match &status.merge_queue {
+            None => "-".normal(),
+            Some(MergeQueueInfo {
+                position: Some(position),
+                ..
+            }) => format!("#{}", position).green(),
+            Some(_) => "not queued".yellow(),
+        };
+        println!(
+            "{:<name_width$}  {:>6}  {:<branch_width$}  {:<7}  {:<8}  {:<17}  {:<9}  {}",
+            status.record.repository.cyan(),
+            format!("#{}", status.record.number),
+            status.record.branch,
+            state,
+            ci,
+            review,
+            mergeable,
+            queue,
+        );
+    }
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use repos_github::ReviewUser;
+
+    fn combined(state: &str, total_count: u64) -> CombinedStatus {
+        CombinedStatus {
+            state: state.to_string(),
+            total_count,
+        }
+    }
+
+    fn check_run(status: &str, conclusion: Option<&str>) -> CheckRun {
+        CheckRun {
+            name: "build".to_string(),
+            status: status.to_string(),
+            conclusion: conclusion.map(str::to_string),
+        }
+    }
+
+    fn review(login: &str, state: &str) -> PullRequestReview {
+        PullRequestReview {
+            user: Some(ReviewUser {
+                login: login.to_string(),
+            }),
+            state: state.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_ci_state() {
+        // A repository without any CI reports a pending combined status with no entries
+        assert_eq!(ci_state(&combined("pending", 0), &[]), CiState::None);
+        assert_eq!(ci_state(&combined("success", 2), &[]), CiState::Success);
+        assert_eq!(
+            ci_state(
+                &combined("pending", 0),
+                &[check_run("completed", Some("success"))]
+            ),
+            CiState::Success
+        );
+        assert_eq!(
+            ci_state(&combined("success", 1), &[check_run("in_progress", None)]),
+            CiState::Pending
+        );
+        assert_eq!(
+            ci_state(
+                &combined("pending", 1),
+                &[check_run("completed", Some("failure"))]
+            ),
+            CiState::Failure
+        );
+    }
+
+    #[test]
+    fn test_rollup_ci_state() {
+        assert_eq!(rollup_ci_state(None), CiState::None);
+        assert_eq!(rollup_ci_state(Some("SUCCESS")), CiState::Success);
+        assert_eq!(rollup_ci_state(Some("EXPECTED")), CiState::Pending);
+        assert_eq!(rollup_ci_state(Some("ERROR")), CiState::Failure);
+    }
+
+    #[test]
+    fn test_review_state() {
+        assert_eq!(review_state(&[]), ReviewState::Pending);
+        assert_eq!(
+            review_state(&[review("alice", "COMMENTED")]),
+            ReviewState::Pending
+        );
+        assert_eq!(
+            review_state(&[review("alice", "APPROVED")]),
+            ReviewState::Approved
+        );
+        // The latest decisive review from each reviewer counts
+        assert_eq!(
+            review_state(&[
+                review("alice", "CHANGES_REQUESTED"),
+                review("bob", "APPROVED"),
+                review("alice", "APPROVED"),
+            ]),
+            ReviewState::Approved
+        );
+        assert_eq!(
+            review_state(&[
+                review("alice", "APPROVED"),
+                review("bob", "CHANGES_REQUESTED")
+            ]),
+            ReviewState::ChangesRequested
+        );
+    }
+}
@@ -0,0 +1,162 @@
+//! File templates for API-only pull requests
+//!
+//! A template is a directory whose files are written into each repository at the
+//! same relative path, or a single file written to a given path. File contents
+//! may use the [`Placeholders`] of the command, such as `{{repo.name}}`, which
+//! are replaced per repository.
+
+use super::placeholders::Placeholders;
+use crate::config::Repository;
+use anyhow::{Context, Result};
+use repos_github::TreeFile;
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// Read a template directory into the files to write for one repository
+pub fn render_template(
+    template_dir: &Path,
+    repo: &Repository,
+    placeholders: &Placeholders,
+) -> Result<Vec<TreeFile>> {
+    if !template_dir.is_dir() {
+        anyhow::bail!(
+            "Template directory '{}' does not exist",
+            template_dir.display()
+        );
+    }
+
+    let mut files = Vec::new();
+    for entry in WalkDir::new(template_dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| e.file_name() != ".git")
+    {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let relative = entry.path().strip_prefix(template_dir)?;
+        let content = std::fs::read_to_string(entry.path()).with_context(|| {
+            format!(
+                "Template file '{}' is not valid UTF-8 text",
+                entry.path().display()
+            )
+        })?;
+
+        files.push(TreeFile {
+            path: relative.to_string_lossy().replace('\\', "/"),
+            content: placeholders.fill(&content, repo),
+        });
+    }
+
+    if files.is_empty() {
+        anyhow::bail!(
+            "Template directory '{}' contains no files",
+            template_dir.display()
+        );
+    }
+
+    Ok(files)
+}
+
+/// Read a single template file into the file to write at `path` for one repository
+pub fn render_file(
+    template_file: &Path,
+    path: &str,
+    repo: &Repository,
+    placeholders: &Placeholders,
+) -> Result<TreeFile> {
+    let content = std::fs::read_to_string(template_file)
+        .with_context(|| format!("Failed to read template file '{}'", template_file.display()))?;
+
+    Ok(TreeFile {
+        path: path.to_string(),
+        content: placeholders.fill(&content, repo),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_render_template() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::create_dir_all(temp_dir.path().join(".github")).unwrap();
+        std::fs::write(
+            temp_dir.path().join(".github/CODEOWNERS"),
+            "* @{{owner}}/{{name}}-team\n",
+        )
+        .unwrap();
+        std::fs::write(temp_dir.path().join("VERSION"), "1.0\n").unwrap();
+
+        let repo = Repository::new(
+            "api".to_string(),
+            "git@github.com:org/api-service.git".to_string(),
+        );
+        let files = render_template(temp_dir.path(), &repo, &Placeholders::default()).unwrap();
+
+        assert_eq!(
+            files,
+            vec![
+                TreeFile {
+                    path: ".github/CODEOWNERS".to_string(),
+                    content: "* @org/api-team\n".to_string(),
+                },
+                TreeFile {
+                    path: "VERSION".to_string(),
+                    content: "1.0\n".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_template_errors() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::new("api".to_string(), "git@github.com:org/api.git".to_string());
+
+        // Empty directory
+        assert!(render_template(temp_dir.path(), &repo, &Placeholders::default()).is_err());
+        // Missing directory
+        assert!(
+            render_template(
+                &temp_dir.path().join("missing"),
+                &repo,
+                &Placeholders::default()
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_render_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let template = temp_dir.path().join("SECURITY.md");
+        std::fs::write(
+            &template,
+            "Report issues in {{repo}} to security@example.com\n",
+        )
+        .unwrap();
+        let repo = Repository::new("api".to_string(), "git@github.com:org/api.git".to_string());
+
+        assert_eq!(
+            render_file(&template, "SECURITY.md", &repo, &Placeholders::default()).unwrap(),
+            TreeFile {
+                path: "SECURITY.md".to_string(),
+                content: "Report issues in api to security@example.com\n".to_string(),
+            }
+        );
+        assert!(
+            render_file(
+                &temp_dir.path().join("missing"),
+                "SECURITY.md",
+                &repo,
+                &Placeholders::default()
+            )
+            .is_err()
+        );
+    }
+}
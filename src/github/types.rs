@@ -3,6 +3,12 @@
 //! This module contains workflow-specific types for GitHub operations.
 //! For low-level GitHub API types, see the `repos-github` crate.
 
+use super::duplicates::{DuplicatePolicy, ExistingPolicy};
+use super::reviewers::ReviewRequest;
+use super::rollback::Rollback;
+use repos_github::MergeMethod;
+use std::sync::Arc;
+
 /// Pull request options for creation workflow
 #[derive(Debug, Clone)]
 pub struct PrOptions {
@@ -14,6 +20,16 @@ pub struct PrOptions {
     pub draft: bool,
     pub token: String,
     pub create_only: bool,
+    /// Enable GitHub auto-merge with this method once the PR is created
+    pub auto_merge: Option<MergeMethod>,
+    /// Reviewers to request on each created PR
+    pub review_request: Option<ReviewRequest>,
+    /// Undo log that branches and PRs created on GitHub are recorded in
+    pub rollback: Option<Arc<Rollback>>,
+    /// Look for open PRs with the same change before creating one
+    pub duplicates: Option<DuplicatePolicy>,
+    /// Update or skip an open PR from the same branch into the same base
+    pub existing: Option<ExistingPolicy>,
 }
 
 impl PrOptions {
@@ -27,6 +43,11 @@ impl PrOptions {
             draft: false,
             token,
             create_only: false,
+            auto_merge: None,
+            review_request: None,
+            rollback: None,
+            duplicates: None,
+            existing: None,
         }
     }
 
@@ -54,4 +75,29 @@ impl PrOptions {
         self.create_only = true;
         self
     }
+
+    pub fn with_auto_merge(mut self, method: MergeMethod) -> Self {
+        self.auto_merge = Some(method);
+        self
+    }
+
+    pub fn with_review_request(mut self, review_request: ReviewRequest) -> Self {
+        self.review_request = Some(review_request);
+        self
+    }
+
+    pub fn with_rollback(mut self, rollback: Arc<Rollback>) -> Self {
+        self.rollback = Some(rollback);
+        self
+    }
+
+    pub fn with_duplicate_policy(mut self, policy: DuplicatePolicy) -> Self {
+        self.duplicates = Some(policy);
+        self
+    }
+
+    pub fn with_existing_policy(mut self, policy: ExistingPolicy) -> Self {
+        self.existing = Some(policy);
+        self
+    }
 }
@@ -1,12 +1,22 @@
 //! Repos - A CLI tool for managing multiple GitHub repositories
 
+pub mod azure;
+pub mod cache;
+pub mod ci;
 pub mod commands;
 pub mod config;
 pub mod constants;
+pub mod credentials;
+pub mod events;
 pub mod git;
+pub mod gitea;
 pub mod github;
+pub mod logging;
 pub mod plugins;
 pub mod runner;
+pub mod state;
+#[cfg(feature = "tui")]
+pub mod tui;
 pub mod utils;
 
 pub type Result<T> = anyhow::Result<T>;
@@ -0,0 +1,215 @@
+//! Process-wide logging with verbosity levels
+//!
+//! Status lines (`<repo> | message`) and diagnostics both go through the
+//! [`log`] facade. The level comes from `-v` / `-vv` / `--quiet`, falling back
+//! to the `REPOS_LOG` environment variable (`off`, `error`, `warn`, `info`,
+//! `debug` or `trace`) and then to `info`.
+//!
+//! Diagnostics (debug and trace records) are written to stderr and prefixed
+//! with the repository being worked on, so output of parallel runs stays
+//! attributable. The repository comes from [`scope`] for async work and from
+//! [`with_repository`] for blocking work.
+
+use crate::events;
+use anyhow::Result;
+use colored::*;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::future::Future;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Environment variable selecting the log level when no flag is given
+pub const LOG_ENV: &str = "REPOS_LOG";
+
+/// Target used for user-facing status lines, printed without decoration
+pub const STATUS_TARGET: &str = "repos::status";
+
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+tokio::task_local! {
+    static TASK_REPOSITORY: String;
+}
+
+thread_local! {
+    static THREAD_REPOSITORY: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Resolve the log level from the command-line flags and `REPOS_LOG`
+pub fn resolve_level(verbose: u8, quiet: bool, env: Option<&str>) -> Result<LevelFilter> {
+    if quiet {
+        return Ok(LevelFilter::Warn);
+    }
+    match verbose {
+        0 => {}
+        1 => return Ok(LevelFilter::Debug),
+        _ => return Ok(LevelFilter::Trace),
+    }
+    match env.map(str::trim).filter(|value| !value.is_empty()) {
+        Some(value) => LevelFilter::from_str(value).map_err(|_| {
+            anyhow::anyhow!(
+                "Invalid {} value '{}' (expected off, error, warn, info, debug or trace)",
+                LOG_ENV,
+                value
+            )
+        }),
+        None => Ok(LevelFilter::Info),
+    }
+}
+
+/// Install the logger for the rest of the process
+pub fn init(verbose: u8, quiet: bool) -> Result<()> {
+    let level = resolve_level(verbose, quiet, std::env::var(LOG_ENV).ok().as_deref())?;
+    if log::set_logger(&ReposLogger).is_ok() {
+        INSTALLED.store(true, Ordering::Relaxed);
+    }
+    log::set_max_level(level);
+    Ok(())
+}
+
+/// Run `future` with `repository` attached to the diagnostics it emits
+pub async fn scope<F: Future>(repository: &str, future: F) -> F::Output {
+    TASK_REPOSITORY.scope(repository.to_string(), future).await
+}
+
+/// Run blocking `f` with `repository` attached to the diagnostics it emits
+pub fn with_repository<T>(repository: &str, f: impl FnOnce() -> T) -> T {
+    let previous = THREAD_REPOSITORY.with(|current| current.replace(Some(repository.to_string())));
+    let result = f();
+    THREAD_REPOSITORY.with(|current| *current.borrow_mut() = previous);
+    result
+}
+
+/// Repository the current task or thread is working on, if any
+pub fn current_repository() -> Option<String> {
+    TASK_REPOSITORY
+        .try_with(|repository| repository.clone())
+        .ok()
+        .or_else(|| THREAD_REPOSITORY.with(|current| current.borrow().clone()))
+}
+
+/// Print a status line for a repository, e.g. `api | Successfully cloned`
+pub fn status(level: Level, repository: &str, message: impl Display) {
+    let message = message.to_string();
+    events::status(level, Some(repository), &message);
+    emit(level, format!("{} | {}", repository.cyan().bold(), message));
+}
+
+/// Print a status line that is not tied to a repository
+pub fn message(level: Level, message: impl Display) {
+    let message = message.to_string();
+    events::status(level, None, &message);
+    emit(level, message);
+}
+
+fn emit(level: Level, line: String) {
+    if INSTALLED.load(Ordering::Relaxed) {
+        log::log!(target: STATUS_TARGET, level, "{}", line);
+    } else if level <= Level::Info {
+        // Library use without `init` keeps the default verbosity
+        write_line(level, &line);
+    }
+}
+
+/// Errors and warnings go to stderr, everything else to stdout unless events use it
+fn write_line(level: Level, line: &str) {
+    if level <= Level::Warn || events::on_stdout() {
+        eprintln!("{}", line);
+    } else {
+        println!("{}", line);
+    }
+}
+
+/// Format a diagnostic record as `[LEVEL] repo | target: message`
+fn format_diagnostic(level: Level, target: &str, repository: Option<&str>, args: &str) -> String {
+    let context = repository
+        .map(|repository| format!("{} | ", repository))
+        .unwrap_or_default();
+    format!("[{}] {}{}: {}", level, context, target, args)
+}
+
+struct ReposLogger;
+
+impl Log for ReposLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+            && (metadata.target() == STATUS_TARGET || metadata.target().starts_with("repos"))
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        if record.target() == STATUS_TARGET {
+            write_line(record.level(), &record.args().to_string());
+        } else {
+            let line = format_diagnostic(
+                record.level(),
+                record.target(),
+                current_repository().as_deref(),
+                &record.args().to_string(),
+            );
+            eprintln!("{}", line.dimmed());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_level() {
+        assert_eq!(resolve_level(0, false, None).unwrap(), LevelFilter::Info);
+        assert_eq!(resolve_level(1, false, None).unwrap(), LevelFilter::Debug);
+        assert_eq!(resolve_level(2, false, None).unwrap(), LevelFilter::Trace);
+        assert_eq!(resolve_level(0, true, None).unwrap(), LevelFilter::Warn);
+        assert_eq!(
+            resolve_level(0, false, Some("debug")).unwrap(),
+            LevelFilter::Debug
+        );
+        // Flags take precedence over the environment
+        assert_eq!(
+            resolve_level(0, true, Some("trace")).unwrap(),
+            LevelFilter::Warn
+        );
+        assert_eq!(
+            resolve_level(0, false, Some("")).unwrap(),
+            LevelFilter::Info
+        );
+        assert!(resolve_level(0, false, Some("loud")).is_err());
+    }
+
+    #[test]
+    fn test_with_repository() {
+        assert_eq!(current_repository(), None);
+        let inner = with_repository("api", || {
+            with_repository("web", current_repository);
+            current_repository()
+        });
+        assert_eq!(inner.as_deref(), Some("api"));
+        assert_eq!(current_repository(), None);
+    }
+
+    #[tokio::test]
+    async fn test_scope() {
+        let repository = scope("api", async { current_repository() }).await;
+        assert_eq!(repository.as_deref(), Some("api"));
+    }
+
+    #[test]
+    fn test_format_diagnostic() {
+        assert_eq!(
+            format_diagnostic(Level::Debug, "repos::git", Some("api"), "git fetch"),
+            "[DEBUG] api | repos::git: git fetch"
+        );
+        assert_eq!(
+            format_diagnostic(Level::Trace, "repos::runner", None, "spawned"),
+            "[TRACE] repos::runner: spawned"
+        );
+    }
+}
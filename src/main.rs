@@ -1,9 +1,14 @@
-use anyhow::Result;
-use clap::{CommandFactory, Parser, Subcommand};
+use anyhow::{Context, Result};
+use clap::{ArgAction, ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand};
 use clap_complete::{Shell, generate};
-use repos::commands::validators;
-use repos::{commands::*, config::Config, constants, plugins};
-use std::{env, io, path::PathBuf};
+use repos::config::aliases;
+use repos::config::defaults::CliDefaults;
+use repos::{ci, commands::*, config::Config, constants, logging, plugins};
+use std::{
+    ffi::OsString,
+    io,
+    path::{Path, PathBuf},
+};
 
 #[derive(Parser)]
 #[command(name = "repos")]
@@ -15,183 +20,219 @@ struct Cli {
     #[arg(long)]
     list_plugins: bool,
 
+    /// Adapt output for a CI provider (github: log groups, annotations, step summaries)
+    #[arg(long, global = true, value_name = "PROVIDER")]
+    ci: Option<ci::CiProvider>,
+
+    /// Show debug output (-vv for trace output); overrides REPOS_LOG
+    #[arg(short, long, global = true, action = ArgAction::Count)]
+    verbose: u8,
+
+    /// Only show warnings and errors; overrides REPOS_LOG
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// Load the config with this profile (e.g. work or oss); defaults to REPOS_PROFILE
+    #[arg(long, global = true, value_name = "NAME")]
+    profile: Option<String>,
+
+    /// Always ask GitHub instead of using cached repository details and pull request lists
+    #[arg(long, global = true)]
+    no_cache: bool,
+
+    /// Seconds cached GitHub responses are used without revalidating them (default: 300, or REPOS_CACHE_TTL)
+    #[arg(
+        long,
+        global = true,
+        value_name = "SECONDS",
+        conflicts_with = "no_cache"
+    )]
+    cache_ttl: Option<u64>,
+
+    /// Also operate on the repositories named in this file, one per line (e.g. the output of `repos select`); `-` reads standard input
+    #[arg(long, global = true, value_name = "FILE")]
+    repos_from: Option<PathBuf>,
+
+    /// Also operate on archived repositories, which commands that change remotes skip
+    #[arg(long, global = true)]
+    include_archived: bool,
+
+    /// Emit machine-readable events (ndjson) for wrapper tooling and dashboards
+    #[arg(long, global = true, value_name = "FORMAT")]
+    events: Option<repos::events::EventFormat>,
+
+    /// Write events to this file instead of stdout (appended to)
+    #[arg(long, global = true, value_name = "FILE", requires = "events")]
+    events_file: Option<PathBuf>,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
 
+// Parsed once per invocation, so the size of the largest variant does not matter
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand)]
 enum Commands {
     /// Clone repositories specified in config
-    Clone {
-        /// Specific repository names to clone (if not provided, uses tag filter or all repos)
-        repos: Vec<String>,
+    Clone(CloneArgs),
 
-        /// Configuration file path
-        #[arg(short, long, default_value_t = constants::config::DEFAULT_CONFIG_FILE.to_string())]
-        config: String,
+    /// Run a command in each repository
+    Run(RunArgs),
 
-        /// Filter repositories by tag (can be specified multiple times)
-        #[arg(short, long)]
-        tag: Vec<String>,
+    /// Browse the output saved by past runs
+    Runs {
+        #[command(subcommand)]
+        action: RunsAction,
+    },
 
-        /// Exclude repositories with these tags (can be specified multiple times)
-        #[arg(short = 'e', long)]
-        exclude_tag: Vec<String>,
+    /// Step through the repositories that failed in a run: retry, open a shell or the logs, or mark them as known failing
+    Triage(TriageArgs),
 
-        /// Execute operations in parallel
-        #[arg(short, long)]
-        parallel: bool,
-    },
+    /// Show the uncommitted changes of each repository, e.g. to review them before `repos pr`
+    Diff(DiffArgs),
 
-    /// Run a command in each repository
-    Run {
-        /// Command to execute
-        #[arg(value_name = "COMMAND", help = "Command to execute")]
-        command: Option<String>,
+    /// Create pull requests for repositories with changes
+    #[command(args_conflicts_with_subcommands = true)]
+    Pr(PrArgs),
 
-        /// Name of a recipe defined in repos.yaml
-        #[arg(long, help = "Name of a recipe defined in repos.yaml")]
-        recipe: Option<String>,
+    /// Merge open pull requests created by repos whose checks pass
+    Merge(MergeArgs),
 
-        /// Specific repository names to run command in (if not provided, uses tag filter or all repos)
-        repos: Vec<String>,
+    /// Close stale pull requests and delete stale branches (dry run unless --apply)
+    #[command(group = clap::ArgGroup::new("policy").multiple(true).required(true).args(["stale_prs_days", "label", "bot", "stale_branches_days"]))]
+    Reaper(ReaperArgs),
 
-        /// Configuration file path
-        #[arg(short, long, default_value_t = constants::config::DEFAULT_CONFIG_FILE.to_string())]
-        config: String,
+    /// Write a compressed archive of each repository
+    Archive(ArchiveArgs),
 
-        /// Filter repositories by tag (can be specified multiple times)
-        #[arg(short, long)]
-        tag: Vec<String>,
+    /// Aggregate statistics such as languages, disk usage, commits and contributors across repositories
+    Stats(StatsArgs),
 
-        /// Exclude repositories with these tags (can be specified multiple times)
-        #[arg(short = 'e', long)]
-        exclude_tag: Vec<String>,
+    /// Export repository metadata such as languages, default branch and latest tag as JSON or CSV
+    Inventory(InventoryArgs),
 
-        /// Execute operations in parallel
-        #[arg(short, long)]
-        parallel: bool,
+    /// Copy a local file or directory into the same path of every repository
+    Cp(CpArgs),
 
-        /// Don't save command outputs to files
-        #[arg(long)]
-        no_save: bool,
+    /// Apply a codemod spec of find/replace rewrites and post-commands to every repository
+    Apply(ApplyArgs),
 
-        /// Custom directory for output files (default: output)
-        #[arg(long)]
-        output_dir: Option<String>,
-    },
+    /// Restore repositories to a checkpoint saved before `run --checkpoint`, `pr` or `apply`
+    Rollback(RollbackArgs),
 
-    /// Create pull requests for repositories with changes
-    Pr {
-        /// Specific repository names to create PRs for (if not provided, uses tag filter or all repos)
-        repos: Vec<String>,
+    /// Open an interactive shell in each repository in turn, moving on when it exits
+    Shell(ShellArgs),
 
-        /// Title for the pull request
-        #[arg(long, default_value = "Automated changes")]
-        title: String,
+    /// Open repositories' web pages in the browser, or their directories in an editor
+    Open(OpenArgs),
 
-        /// Body text for the pull request
-        #[arg(long, default_value = "This PR was created automatically")]
-        body: String,
+    /// Tag a release in every repository and publish it as a GitHub Release
+    Release(ReleaseArgs),
 
-        /// Branch name to create
-        #[arg(long)]
-        branch: Option<String>,
+    /// Show the latest GitHub Actions results of each repository's branch
+    Ci(CiArgs),
 
-        /// Base branch for the PR
-        #[arg(long)]
-        base: Option<String>,
+    /// Audit branch protection against the policy in the config, optionally applying it
+    Protect(ProtectArgs),
 
-        /// Commit message
-        #[arg(long)]
-        message: Option<String>,
+    /// Audit labels, topics, merge settings and webhooks against the config, optionally fixing them
+    Audit(AuditArgs),
 
-        /// Create PR as draft
-        #[arg(long)]
-        draft: bool,
+    /// List, open, close and label GitHub issues across repositories
+    Issues {
+        #[command(subcommand)]
+        action: IssuesAction,
+    },
 
-        /// GitHub token
-        #[arg(long)]
-        token: Option<String>,
+    /// Show the fleet on a full-screen dashboard with git status, last runs and actions
+    #[cfg(feature = "tui")]
+    Ui(UiArgs),
 
-        /// Only create PR, don't commit changes
-        #[arg(long)]
-        create_only: bool,
+    /// Remove cloned repositories
+    Rm(RmArgs),
 
-        /// Configuration file path
-        #[arg(short, long, default_value_t = constants::config::DEFAULT_CONFIG_FILE.to_string())]
-        config: String,
+    /// Fetch from origin in all repositories at once, without touching working trees
+    Fetch(FetchArgs),
 
-        /// Filter repositories by tag (can be specified multiple times)
-        #[arg(short, long)]
-        tag: Vec<String>,
+    /// Keep bare mirror clones of every repository in a backup directory
+    Mirror(MirrorArgs),
 
-        /// Exclude repositories with these tags (can be specified multiple times)
-        #[arg(short = 'e', long)]
-        exclude_tag: Vec<String>,
+    /// Push the current or a given branch of every repository to origin
+    Push(PushArgs),
 
-        /// Execute operations in parallel
-        #[arg(short, long)]
-        parallel: bool,
-    },
+    /// Rerun a command in repositories whose files change
+    Watch(WatchArgs),
 
-    /// Remove cloned repositories
-    Rm {
-        /// Specific repository names to remove (if not provided, uses tag filter or all repos)
-        repos: Vec<String>,
+    /// Switch every repository to the same branch
+    Checkout(CheckoutArgs),
 
-        /// Configuration file path
-        #[arg(short, long, default_value_t = constants::config::DEFAULT_CONFIG_FILE.to_string())]
-        config: String,
+    /// Rebase a branch onto the updated default branch in each repository
+    Rebase(RebaseArgs),
 
-        /// Filter repositories by tag (can be specified multiple times)
-        #[arg(short, long)]
-        tag: Vec<String>,
+    /// List repositories with optional filtering
+    Ls(LsArgs),
+
+    /// Print the names of the repositories a filter selects, for piping into other tools or `--repos-from`
+    Select(SelectArgs),
 
-        /// Exclude repositories with these tags (can be specified multiple times)
-        #[arg(short = 'e', long)]
-        exclude_tag: Vec<String>,
+    /// Create a repos.yaml file from discovered Git repositories
+    Init(InitArgs),
 
-        /// Execute operations in parallel
-        #[arg(short, long)]
-        parallel: bool,
+    /// Create a GitHub repository from a template directory, push it and add it to the config
+    New(NewArgs),
+
+    /// Validate the configuration file or export it for other tools
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
     },
 
-    /// List repositories with optional filtering
-    Ls {
-        /// Specific repository names to list (if not provided, uses tag filter or all repos)
-        repos: Vec<String>,
+    /// Keep repository tags in sync with the topics of their remotes
+    Tags {
+        #[command(subcommand)]
+        action: TagsAction,
+    },
 
-        /// Configuration file path
-        #[arg(short, long, default_value_t = constants::config::DEFAULT_CONFIG_FILE.to_string())]
-        config: String,
+    /// Manage the git remotes of cloned repositories
+    Remote {
+        #[command(subcommand)]
+        action: RemoteAction,
+    },
 
-        /// Filter repositories by tag (can be specified multiple times)
-        #[arg(short, long)]
-        tag: Vec<String>,
+    /// Audit required files and content declared in the config
+    Files {
+        #[command(subcommand)]
+        action: FilesAction,
+    },
 
-        /// Exclude repositories with these tags (can be specified multiple times)
-        #[arg(short = 'e', long)]
-        exclude_tag: Vec<String>,
+    /// Stash uncommitted changes across repositories and restore them later
+    Stash {
+        #[command(subcommand)]
+        action: StashSubcommand,
+    },
 
-        /// Output in JSON format for machine consumption
-        #[arg(long)]
-        json: bool,
+    /// Update, sync and report the submodules of every repository
+    Submodule {
+        #[command(subcommand)]
+        action: SubmoduleSubcommand,
     },
 
-    /// Create a repos.yaml file from discovered Git repositories
-    Init {
-        /// Output file name
-        #[arg(short, long, default_value_t = constants::config::DEFAULT_CONFIG_FILE.to_string())]
-        output: String,
-
-        /// Overwrite existing file if it exists
-        #[arg(long)]
-        overwrite: bool,
-
-        /// Supplement existing config with newly discovered repositories
-        #[arg(long)]
-        supplement: bool,
+    /// Save and return to named checkpoints of each repository's branch and commit
+    Bookmark {
+        #[command(subcommand)]
+        action: BookmarkAction,
+    },
+
+    /// Install, update, remove and list plugins published as GitHub releases
+    Plugin {
+        #[command(subcommand)]
+        action: PluginAction,
+    },
+
+    /// Save, inspect and remove named GitHub tokens kept in the OS keychain
+    Auth {
+        #[command(subcommand)]
+        action: AuthAction,
     },
 
     /// Generate shell completions
@@ -206,9 +247,121 @@ enum Commands {
     External(Vec<String>),
 }
 
+/// Parse a command line, with the `defaults` of the selected config files as option defaults
+fn cli_matches_from(args: Vec<OsString>) -> Result<clap::ArgMatches> {
+    // A lenient first pass finds the config files before their defaults are known;
+    // help is left to the second pass so that it shows those defaults
+    let lenient = args.iter().filter(|arg| {
+        !["-h", "--help", "-V", "--version"].contains(&arg.to_str().unwrap_or_default())
+    });
+    let first = Cli::command().ignore_errors(true).get_matches_from(lenient);
+    let mut paths = Vec::new();
+    let mut level = Some(&first);
+    while let Some(matches) = level {
+        if let Ok(Some(values)) = matches.try_get_raw("config") {
+            paths.extend(values.map(|value| value.to_string_lossy().to_string()));
+        }
+        level = matches.subcommand().map(|(_, matches)| matches);
+    }
+
+    let defaults = CliDefaults::load(&paths)?;
+    if defaults.is_empty() {
+        return Ok(Cli::command().get_matches_from(args));
+    }
+    Ok(defaults.apply(Cli::command())?.get_matches_from(args))
+}
+
+/// The first `--config` of the invoked subcommand, however deeply it is nested
+fn config_path(matches: &ArgMatches) -> Option<String> {
+    let (_, subcommand) = matches.subcommand()?;
+    config_path(subcommand).or_else(|| {
+        subcommand
+            .try_get_many::<String>("config")
+            .ok()??
+            .next()
+            .cloned()
+    })
+}
+
+/// The `--config` given to an external command or alias, or the default config
+fn external_config_path(args: &[String]) -> String {
+    args.iter()
+        .position(|arg| arg == "--config" || arg == "-c")
+        .and_then(|index| args.get(index + 1))
+        .cloned()
+        .unwrap_or_else(|| constants::config::DEFAULT_CONFIG_FILE.to_string())
+}
+
+/// Parse the command line, replacing aliases with the commands they stand for
+///
+/// Aliases come before plugins and may expand to other aliases. The expansion
+/// takes the alias's place in the command line and is parsed again, so global
+/// options before the alias and in its definition both apply. Returns the
+/// expanded command line when an alias was used.
+fn parse_command_line(mut argv: Vec<OsString>) -> Result<(ArgMatches, Cli, Option<Vec<OsString>>)> {
+    let mut expanded: Vec<String> = Vec::new();
+    loop {
+        let matches = cli_matches_from(argv.clone())?;
+        let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+        let definition = match &cli.command {
+            Some(Commands::External(args)) if !args.is_empty() => {
+                aliases::load_aliases(&external_config_path(args))?
+                    .remove(&args[0])
+                    .map(|definition| (args.clone(), definition))
+            }
+            _ => None,
+        };
+        let Some((args, definition)) = definition else {
+            let expansion = (!expanded.is_empty()).then_some(argv);
+            return Ok((matches, cli, expansion));
+        };
+
+        if expanded.contains(&args[0]) {
+            anyhow::bail!("Alias '{}' expands to itself", args[0]);
+        }
+        expanded.push(args[0].clone());
+        // The external command's arguments are the tail of the command line
+        argv.truncate(argv.len() - args.len());
+        argv.extend(
+            aliases::expand_alias(&definition, &args[1..])
+                .with_context(|| format!("Invalid alias '{}'", args[0]))?
+                .into_iter()
+                .map(OsString::from),
+        );
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
-    let cli = Cli::parse();
+    let (matches, cli, expansion) = parse_command_line(std::env::args_os().collect())?;
+    logging::init(cli.verbose, cli.quiet)?;
+    if let Some(argv) = expansion {
+        log::debug!("Aliases expand to {:?}", argv);
+    }
+
+    if let Some(format) = cli.events {
+        repos::events::init(
+            format,
+            cli.events_file.as_deref(),
+            matches.subcommand_name(),
+        )?;
+    }
+
+    if let Some(provider) = cli.ci {
+        ci::init(provider)?;
+    }
+
+    repos::github::cache::init(cli.no_cache, cli.cache_ttl)?;
+
+    if let Some(profile) = cli
+        .profile
+        .clone()
+        .or_else(|| std::env::var(constants::config::PROFILE_ENV).ok())
+        .filter(|profile| !profile.is_empty())
+    {
+        log::debug!("Using profile '{}'", profile);
+        repos::config::profile::select(&profile);
+    }
 
     // Handle list-plugins option first
     if cli.list_plugins {
@@ -218,6 +371,9 @@ async fn main() -> Result<()> {
             println!(
                 "To create a plugin, make an executable named 'repos-<name>' available in your PATH."
             );
+            println!("To install a published plugin, run 'repos plugin install <owner>/<repo>'.");
+            #[cfg(feature = "wasm-plugins")]
+            println!("WASM plugins are loaded from ~/.repos/plugins/<name>.wasm.");
         } else {
             println!("Available external plugins:");
             for plugin in plugins {
@@ -227,6 +383,33 @@ async fn main() -> Result<()> {
         return Ok(());
     }
 
+    // State belongs to the fleet, wherever the command is run from
+    if let Some(config) = config_path(&matches) {
+        repos::state::anchor_to_config(Path::new(&config));
+    }
+
+    let repos_from = match &cli.repos_from {
+        Some(path) => {
+            let names = repos::utils::read_repository_list(path)?;
+            if names.is_empty() {
+                logging::message(
+                    log::Level::Info,
+                    format!(
+                        "No repositories listed in {}",
+                        repos::utils::describe_source(path)
+                    ),
+                );
+                return Ok(());
+            }
+            Some(names)
+        }
+        None => None,
+    };
+    let globals = GlobalOptions {
+        repos_from,
+        include_archived: cli.include_archived,
+    };
+
     // Handle commands
     match cli.command {
         Some(Commands::Completions { shell }) => {
@@ -294,10 +477,17 @@ async fn main() -> Result<()> {
 
             let (config, filtered_repos) = if needs_config {
                 let config = Config::load_config(&config_path)?;
-                let filtered_repos = if include_tags.is_empty() && exclude_tags.is_empty() {
+                let filtered_repos = if include_tags.is_empty()
+                    && exclude_tags.is_empty()
+                    && globals.repos_from.is_none()
+                {
                     config.repositories.clone()
                 } else {
-                    config.filter_repositories(&include_tags, &exclude_tags, None)
+                    config.filter_repositories(
+                        &include_tags,
+                        &exclude_tags,
+                        globals.repos_from.as_deref(),
+                    )
                 };
                 (config, filtered_repos)
             } else {
@@ -320,7 +510,7 @@ async fn main() -> Result<()> {
 
             plugins::try_external_plugin(plugin_name, &context)?;
         }
-        Some(command) => execute_builtin_command(command).await?,
+        Some(command) => execute_builtin_command(command, &globals).await?,
         None => {
             // No command provided, print help
             anyhow::bail!("No command provided. Use --help for usage information.");
@@ -330,196 +520,58 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-async fn execute_builtin_command(command: Commands) -> Result<()> {
-    // Execute the appropriate command
+async fn execute_builtin_command(command: Commands, globals: &GlobalOptions) -> Result<()> {
     match command {
         Commands::External(_) => {
             // These cases are handled in main(), this should not be reached
             unreachable!("External commands should be handled in main()")
         }
-        Commands::Clone {
-            repos,
-            config,
-            tag,
-            exclude_tag,
-            parallel,
-        } => {
-            let config = Config::load_config(&config)?;
-
-            // Validate clone command arguments using centralized validators
-            validators::validate_tag_filters(&tag)?;
-            validators::validate_tag_filters(&exclude_tag)?;
-            validators::validate_repository_names(&repos)?;
-
-            let context = CommandContext {
-                config,
-                tag,
-                exclude_tag,
-                parallel,
-                repos: if repos.is_empty() { None } else { Some(repos) },
-            };
-            CloneCommand.execute(&context).await?;
-        }
-        Commands::Run {
-            command,
-            recipe,
-            repos,
-            config,
-            tag,
-            exclude_tag,
-            parallel,
-            no_save,
-            output_dir,
-        } => {
-            let config = Config::load_config(&config)?;
-
-            // Validate run command arguments using centralized validators
-            validators::validate_run_args(&command, &recipe)?;
-            validators::validate_tag_filters(&tag)?;
-            validators::validate_tag_filters(&exclude_tag)?;
-            validators::validate_repository_names(&repos)?;
-            validators::validate_output_directory(&output_dir)?;
-
-            let context = CommandContext {
-                config,
-                tag,
-                exclude_tag,
-                parallel,
-                repos: if repos.is_empty() { None } else { Some(repos) },
-            };
-
-            if let Some(cmd) = command {
-                RunCommand::new_command(cmd, no_save, output_dir.map(PathBuf::from))
-                    .execute(&context)
-                    .await?;
-            } else if let Some(recipe_name) = recipe {
-                RunCommand::new_recipe(recipe_name, no_save, output_dir.map(PathBuf::from))
-                    .execute(&context)
-                    .await?;
-            }
-        }
-        Commands::Pr {
-            repos,
-            title,
-            body,
-            branch,
-            base,
-            message,
-            draft,
-            token,
-            create_only,
-            config,
-            tag,
-            exclude_tag,
-            parallel,
-        } => {
-            let config = Config::load_config(&config)?;
-
-            // Validate PR command arguments using centralized validators
-            validators::validate_pr_args(&token)?;
-            validators::validate_tag_filters(&tag)?;
-            validators::validate_tag_filters(&exclude_tag)?;
-            validators::validate_repository_names(&repos)?;
-            validators::validate_branch_name(&branch)?;
-            validators::validate_branch_name(&base)?;
-            validators::validate_commit_message(&message)?;
-
-            let context = CommandContext {
-                config,
-                tag,
-                exclude_tag,
-                parallel,
-                repos: if repos.is_empty() { None } else { Some(repos) },
-            };
-
-            let token = token.or_else(|| env::var("GITHUB_TOKEN").ok())
-                .ok_or_else(|| anyhow::anyhow!("GitHub token not provided. Use --token flag or set GITHUB_TOKEN environment variable."))?;
-
-            PrCommand {
-                title,
-                body,
-                branch_name: branch,
-                base_branch: base,
-                commit_msg: message,
-                draft,
-                token,
-                create_only,
-            }
-            .execute(&context)
-            .await?;
-        }
-        Commands::Rm {
-            repos,
-            config,
-            tag,
-            exclude_tag,
-            parallel,
-        } => {
-            let config = Config::load_config(&config)?;
-
-            // Validate remove command arguments using centralized validators
-            validators::validate_tag_filters(&tag)?;
-            validators::validate_tag_filters(&exclude_tag)?;
-            validators::validate_repository_names(&repos)?;
-
-            let context = CommandContext {
-                config,
-                tag,
-                exclude_tag,
-                parallel,
-                repos: if repos.is_empty() { None } else { Some(repos) },
-            };
-            RemoveCommand.execute(&context).await?;
-        }
-        Commands::Ls {
-            repos,
-            config,
-            tag,
-            exclude_tag,
-            json,
-        } => {
-            let config = Config::load_config(&config)?;
-
-            // Validate list command arguments using centralized validators
-            validators::validate_tag_filters(&tag)?;
-            validators::validate_tag_filters(&exclude_tag)?;
-            validators::validate_repository_names(&repos)?;
-
-            let context = CommandContext {
-                config,
-                tag,
-                exclude_tag,
-                parallel: false, // List command doesn't need parallel execution
-                repos: if repos.is_empty() { None } else { Some(repos) },
-            };
-            ListCommand { json }.execute(&context).await?;
-        }
-        Commands::Init {
-            output,
-            overwrite,
-            supplement,
-        } => {
-            // Init command doesn't need config since it creates one
-            let context = CommandContext {
-                config: Config::new(),
-                tag: Vec::new(),
-                exclude_tag: Vec::new(),
-                parallel: false,
-                repos: None,
-            };
-            InitCommand {
-                output,
-                overwrite,
-                supplement,
-            }
-            .execute(&context)
-            .await?;
-        }
+        Commands::Clone(args) => args.run(globals).await,
+        Commands::Run(args) => args.run(globals).await,
+        Commands::Runs { action } => action.run().await,
+        Commands::Triage(args) => args.run().await,
+        Commands::Diff(args) => args.run(globals).await,
+        Commands::Pr(args) => args.run(globals).await,
+        Commands::Merge(args) => args.run(globals).await,
+        Commands::Reaper(args) => args.run(globals).await,
+        Commands::Archive(args) => args.run(globals).await,
+        Commands::Stats(args) => args.run(globals).await,
+        Commands::Inventory(args) => args.run(globals).await,
+        Commands::Cp(args) => args.run(globals).await,
+        Commands::Apply(args) => args.run(globals).await,
+        Commands::Rollback(args) => args.run(globals).await,
+        Commands::Shell(args) => args.run(globals).await,
+        Commands::Open(args) => args.run(globals).await,
+        Commands::Release(args) => args.run(globals).await,
+        Commands::Ci(args) => args.run(globals).await,
+        Commands::Protect(args) => args.run(globals).await,
+        Commands::Audit(args) => args.run(globals).await,
+        Commands::Issues { action } => action.run(globals).await,
+        #[cfg(feature = "tui")]
+        Commands::Ui(args) => args.run(globals).await,
+        Commands::Rm(args) => args.run(globals).await,
+        Commands::Fetch(args) => args.run(globals).await,
+        Commands::Mirror(args) => args.run(globals).await,
+        Commands::Push(args) => args.run(globals).await,
+        Commands::Remote { action } => action.run(globals).await,
+        Commands::Watch(args) => args.run(globals).await,
+        Commands::Checkout(args) => args.run(globals).await,
+        Commands::Rebase(args) => args.run(globals).await,
+        Commands::Ls(args) => args.run(globals).await,
+        Commands::Select(args) => args.run(globals).await,
+        Commands::Init(args) => args.run().await,
+        Commands::New(args) => args.run().await,
+        Commands::Config { action } => action.run(globals).await,
+        Commands::Tags { action } => action.run(globals).await,
+        Commands::Files { action } => action.run(globals).await,
+        Commands::Stash { action } => action.run(globals).await,
+        Commands::Submodule { action } => action.run(globals).await,
+        Commands::Bookmark { action } => action.run(globals).await,
+        Commands::Auth { action } => action.run().await,
+        Commands::Plugin { action } => action.run().await,
         Commands::Completions { .. } => {
             // Handled in main(), this should not be reached
             unreachable!("Completions command should be handled in main()")
         }
     }
-
-    Ok(())
 }
@@ -1,12 +1,28 @@
 use anyhow::Result;
 use std::env;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use crate::config::{Config, Repository};
+use crate::constants::plugins::PLUGIN_DIR;
+
+pub mod manager;
+#[cfg(feature = "wasm-plugins")]
+pub mod wasm;
 
 /// Prefix for external plugin executables
-const PLUGIN_PREFIX: &str = "repos-";
+pub(crate) const PLUGIN_PREFIX: &str = "repos-";
+
+/// Directory `repos plugin install` installs to, searched for plugins before PATH
+pub fn plugin_dir() -> Option<PathBuf> {
+    env::var_os("HOME").map(|home| PathBuf::from(home).join(PLUGIN_DIR))
+}
+
+/// Path of an executable plugin installed in the plugin directory
+fn installed_plugin(binary_name: &str) -> Option<PathBuf> {
+    let path = plugin_dir()?.join(format!("{}{}", binary_name, env::consts::EXE_SUFFIX));
+    is_executable(&path).then_some(path)
+}
 
 /// Context passed to plugins with pre-processed configuration and repositories
 #[derive(Debug, Clone)]
@@ -60,18 +76,38 @@ impl PluginContext {
 
 /// Try to execute an external plugin with injected context
 pub fn try_external_plugin(plugin_name: &str, context: &PluginContext) -> Result<()> {
+    #[cfg(feature = "wasm-plugins")]
+    if let Some(path) = wasm::find_plugin(plugin_name) {
+        return wasm::run_plugin(&path, context);
+    }
+
     let binary_name = format!("{}{}", PLUGIN_PREFIX, plugin_name);
 
     // Serialize filtered repositories to a temporary file
     let temp_file = tempfile::NamedTempFile::new()
         .map_err(|e| anyhow::anyhow!("Failed to create temp file for plugin context: {}", e))?;
 
-    serde_json::to_writer(&temp_file, &context.repositories)
+    // Plugins cannot see the config location, so hand them fully resolved paths
+    let repositories: Vec<_> = context
+        .repositories
+        .iter()
+        .map(|repo| {
+            let mut repo = repo.clone();
+            repo.path = Some(repo.get_target_dir());
+            // Top-level provider settings are not serialized with the repository
+            repo.provider = Some(repo.provider());
+            repo.provider_url = repo.provider_url();
+            repo
+        })
+        .collect();
+
+    serde_json::to_writer(&temp_file, &repositories)
         .map_err(|e| anyhow::anyhow!("Failed to serialize repositories: {}", e))?;
 
     let repos_file_path = temp_file.path().to_string_lossy().to_string();
 
-    let mut cmd = Command::new(&binary_name);
+    let program = installed_plugin(&binary_name).unwrap_or_else(|| PathBuf::from(&binary_name));
+    let mut cmd = Command::new(&program);
     cmd.args(&context.args)
         .env("REPOS_PLUGIN_PROTOCOL", "1")
         .env("REPOS_FILTERED_REPOS_FILE", &repos_file_path)
@@ -85,6 +121,11 @@ pub fn try_external_plugin(plugin_name: &str, context: &PluginContext) -> Result
             context.repositories.len().to_string(),
         );
 
+    // Plugins read GITHUB_TOKEN, so the profile's token has to be handed over
+    if let Some(token) = context.config.resolve_profile_token() {
+        cmd.env("GITHUB_TOKEN", token);
+    }
+
     // Set config file path if available
     if let Some(config_path) = &context.config_path {
         cmd.env("REPOS_CONFIG_FILE", config_path);
@@ -108,28 +149,36 @@ pub fn try_external_plugin(plugin_name: &str, context: &PluginContext) -> Result
     Ok(())
 }
 
-/// List all available external plugins by scanning PATH
+/// List all available external plugins by scanning the plugin directory and PATH
 pub fn list_external_plugins() -> Vec<String> {
     let mut plugins = Vec::new();
 
-    if let Ok(path_env) = env::var("PATH") {
-        for path_dir in env::split_paths(&path_env) {
-            if let Ok(entries) = std::fs::read_dir(&path_dir) {
-                for entry in entries.flatten() {
-                    if let Some(file_name) = entry.file_name().to_str()
-                        && file_name.starts_with(PLUGIN_PREFIX)
-                        && is_executable(&entry.path())
-                        && let Some(plugin_name) = file_name.strip_prefix(PLUGIN_PREFIX)
-                        && !plugin_name.is_empty()
-                        && !plugins.contains(&plugin_name.to_string())
-                    {
-                        plugins.push(plugin_name.to_string());
-                    }
+    let path_dirs = env::var("PATH")
+        .map(|path_env| env::split_paths(&path_env).collect())
+        .unwrap_or_else(|_| Vec::new());
+    for path_dir in plugin_dir().into_iter().chain(path_dirs) {
+        if let Ok(entries) = std::fs::read_dir(&path_dir) {
+            for entry in entries.flatten() {
+                if let Some(file_name) = entry.file_name().to_str()
+                    && file_name.starts_with(PLUGIN_PREFIX)
+                    && is_executable(&entry.path())
+                    && let Some(plugin_name) = file_name.strip_prefix(PLUGIN_PREFIX)
+                    && !plugin_name.is_empty()
+                    && !plugins.contains(&plugin_name.to_string())
+                {
+                    plugins.push(plugin_name.to_string());
                 }
             }
         }
     }
 
+    #[cfg(feature = "wasm-plugins")]
+    for plugin_name in wasm::list_plugins() {
+        if !plugins.contains(&plugin_name) {
+            plugins.push(plugin_name);
+        }
+    }
+
     plugins.sort();
     plugins
 }
@@ -0,0 +1,764 @@
+//! Installing plugins from GitHub releases
+//!
+//! `repos plugin install owner/repo` downloads the release asset built for the
+//! current platform, verifies it against the checksums published with the
+//! release and places the `repos-<name>` executable in the plugin directory,
+//! which is searched before PATH. Installed plugins are recorded in
+//! `installed.json` in the same directory so they can be updated and removed.
+
+use super::{PLUGIN_PREFIX, plugin_dir};
+use crate::logging;
+use anyhow::{Context, Result};
+use colored::*;
+use log::Level;
+use repos_github::{GitHubClient, Release, ReleaseAsset};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// File recording installed plugins inside the plugin directory
+const MANIFEST_FILE: &str = "installed.json";
+
+/// Alternative spellings of each OS in release asset names
+const OS_ALIASES: &[(&str, &[&str])] = &[
+    ("linux", &["linux"]),
+    ("macos", &["darwin", "macos", "apple", "osx"]),
+    ("windows", &["windows", "win64", "win32"]),
+];
+
+/// Alternative spellings of each architecture in release asset names
+const ARCH_ALIASES: &[(&str, &[&str])] = &[
+    ("x86_64", &["x86_64", "amd64", "x64"]),
+    ("aarch64", &["aarch64", "arm64"]),
+];
+
+/// GitHub repository publishing a plugin, optionally pinned to a release tag
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginSource {
+    pub owner: String,
+    pub repo: String,
+    pub tag: Option<String>,
+}
+
+impl PluginSource {
+    /// Plugin name derived from the repository, e.g. `health` for `repos-health`
+    pub fn default_name(&self) -> String {
+        self.repo
+            .strip_prefix(PLUGIN_PREFIX)
+            .filter(|name| !name.is_empty())
+            .unwrap_or(&self.repo)
+            .to_string()
+    }
+
+    fn slug(&self) -> String {
+        format!("{}/{}", self.owner, self.repo)
+    }
+}
+
+impl FromStr for PluginSource {
+    type Err = anyhow::Error;
+
+    /// Parse `owner/repo`, or a GitHub URL, with an optional `@tag`
+    fn from_str(s: &str) -> Result<Self> {
+        let (location, tag) = match s.rsplit_once('@') {
+            // `git@github.com:...` URLs contain an `@` that is not a tag
+            Some((location, tag)) if !location.is_empty() && location != "git" => {
+                (location, Some(tag.to_string()))
+            }
+            _ => (s, None),
+        };
+
+        let (owner, repo) = match location.split('/').collect::<Vec<_>>().as_slice() {
+            [owner, repo] if !location.contains(':') => (owner.to_string(), repo.to_string()),
+            _ => repos_github::parse_github_url(location)?,
+        };
+        if owner.is_empty() || repo.is_empty() {
+            anyhow::bail!("Invalid plugin source '{}'; expected owner/repo[@tag]", s);
+        }
+
+        Ok(Self { owner, repo, tag })
+    }
+}
+
+/// A plugin installed by `repos plugin install`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InstalledPlugin {
+    /// GitHub repository the plugin was installed from, as `owner/repo`
+    pub source: String,
+    /// Release tag of the installed version
+    pub version: String,
+    /// Release asset the executable was taken from
+    pub asset: String,
+    /// SHA-256 of the downloaded asset
+    pub sha256: String,
+    pub installed_at: String,
+    /// Installed from an explicit tag; `update` leaves it alone
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
+}
+
+/// Contents of `installed.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Manifest {
+    #[serde(default)]
+    plugins: BTreeMap<String, InstalledPlugin>,
+}
+
+/// Installs, updates and removes plugins in a plugin directory
+pub struct PluginManager {
+    dir: PathBuf,
+    client: GitHubClient,
+    /// Install assets even when the release publishes no checksum for them
+    allow_unverified: bool,
+}
+
+impl PluginManager {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            client: GitHubClient::new(None),
+            allow_unverified: false,
+        }
+    }
+
+    /// Manager for the default plugin directory, `~/.repos/plugins`
+    pub fn open_default() -> Result<Self> {
+        let dir = plugin_dir().ok_or_else(|| {
+            anyhow::anyhow!("HOME is not set; cannot locate the plugin directory")
+        })?;
+        Ok(Self::new(dir))
+    }
+
+    /// Use a specific GitHub token for API requests
+    pub fn with_token(mut self, token: Option<String>) -> Self {
+        self.client = GitHubClient::new(token);
+        self
+    }
+
+    /// Install assets without a published checksum instead of refusing them
+    pub fn with_allow_unverified(mut self, allow_unverified: bool) -> Self {
+        self.allow_unverified = allow_unverified;
+        self
+    }
+
+    /// Plugins recorded as installed, by name
+    pub fn installed(&self) -> Result<BTreeMap<String, InstalledPlugin>> {
+        Ok(self.load_manifest()?.plugins)
+    }
+
+    /// Install a plugin from the latest release of `source`, or its pinned tag
+    pub async fn install(&self, source: &PluginSource, name: Option<&str>) -> Result<()> {
+        let name = name
+            .map(str::to_string)
+            .unwrap_or_else(|| source.default_name());
+        validate_name(&name)?;
+        let release = match &source.tag {
+            Some(tag) => {
+                self.client
+                    .get_release_by_tag(&source.owner, &source.repo, tag)
+                    .await?
+            }
+            None => {
+                self.client
+                    .get_latest_release(&source.owner, &source.repo)
+                    .await?
+            }
+        };
+
+        let record = self.install_release(source, &name, &release).await?;
+        logging::status(
+            Level::Info,
+            &name,
+            format!("Installed {} from {}", record.version, record.source).green(),
+        );
+        Ok(())
+    }
+
+    /// Move installed plugins (all, or the named one) to their latest release
+    pub async fn update(&self, name: Option<&str>) -> Result<()> {
+        let installed = self.installed()?;
+        let selected: Vec<_> = match name {
+            Some(name) => {
+                validate_name(name)?;
+                let plugin = installed
+                    .get(name)
+                    .ok_or_else(|| anyhow::anyhow!("Plugin '{}' is not installed", name))?;
+                vec![(name.to_string(), plugin.clone())]
+            }
+            None => installed.into_iter().collect(),
+        };
+        if selected.is_empty() {
+            logging::message(Level::Info, "No plugins installed".yellow());
+            return Ok(());
+        }
+
+        let mut errors = Vec::new();
+        for (name, plugin) in selected {
+            if plugin.pinned {
+                logging::status(
+                    Level::Info,
+                    &name,
+                    format!(
+                        "Pinned to {}; reinstall without @tag to follow new releases",
+                        plugin.version
+                    )
+                    .yellow(),
+                );
+                continue;
+            }
+
+            let result = async {
+                validate_name(&name)?;
+                let source: PluginSource = plugin.source.parse()?;
+                let release = self
+                    .client
+                    .get_latest_release(&source.owner, &source.repo)
+                    .await?;
+                if release.tag_name == plugin.version {
+                    return Ok(format!("Already up to date ({})", plugin.version));
+                }
+                let record = self.install_release(&source, &name, &release).await?;
+                Ok::<_, anyhow::Error>(format!(
+                    "Updated from {} to {}",
+                    plugin.version, record.version
+                ))
+            }
+            .await;
+
+            match result {
+                Ok(message) => logging::status(Level::Info, &name, message.green()),
+                Err(e) => {
+                    logging::status(Level::Error, &name, format!("Error: {e}").red());
+                    errors.push((name, e));
+                }
+            }
+        }
+
+        if let Some((name, e)) = errors.first() {
+            anyhow::bail!(
+                "Failed to update {} plugin(s). First error ({}): {}",
+                errors.len(),
+                name,
+                e
+            );
+        }
+        Ok(())
+    }
+
+    /// Delete an installed plugin and forget about it
+    pub fn remove(&self, name: &str) -> Result<()> {
+        validate_name(name)?;
+        let mut manifest = self.load_manifest()?;
+        if manifest.plugins.remove(name).is_none() {
+            anyhow::bail!("Plugin '{}' is not installed", name);
+        }
+
+        let path = self.binary_path(name);
+        if path.exists() {
+            std::fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove {}", path.display()))?;
+        }
+        self.save_manifest(&manifest)?;
+        logging::status(Level::Info, name, "Removed".green());
+        Ok(())
+    }
+
+    /// Print installed plugins with their versions and sources
+    pub fn print_list(&self) -> Result<()> {
+        let installed = self.installed()?;
+        if installed.is_empty() {
+            println!("No plugins installed in {}.", self.dir.display());
+            println!("Install one with 'repos plugin install <owner>/<repo>'.");
+            return Ok(());
+        }
+
+        let name_width = installed
+            .keys()
+            .map(String::len)
+            .max()
+            .unwrap_or(0)
+            .max("NAME".len());
+        let version_width = installed
+            .values()
+            .map(|p| p.version.len())
+            .max()
+            .unwrap_or(0)
+            .max("VERSION".len());
+        println!(
+            "{:<name_width$}  {:<version_width$}  {}",
+            "NAME".bold(),
+            "VERSION".bold(),
+            "SOURCE".bold()
+        );
+        for (name, plugin) in &installed {
+            let pinned = if plugin.pinned { " (pinned)" } else { "" };
+            println!(
+                "{:<name_width$}  {:<version_width$}  {}{}",
+                name.cyan(),
+                plugin.version,
+                plugin.source,
+                pinned
+            );
+        }
+        Ok(())
+    }
+
+    /// Download, verify and install the asset of a release for this platform
+    async fn install_release(
+        &self,
+        source: &PluginSource,
+        name: &str,
+        release: &Release,
+    ) -> Result<InstalledPlugin> {
+        let asset = select_asset(
+            &release.assets,
+            std::env::consts::OS,
+            std::env::consts::ARCH,
+        )
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Release {} of {} has no asset for {}-{}",
+                release.tag_name,
+                source.slug(),
+                std::env::consts::OS,
+                std::env::consts::ARCH
+            )
+        })?;
+        logging::status(
+            Level::Info,
+            name,
+            format!("Downloading {} ({})", asset.name, release.tag_name),
+        );
+        let bytes = self.client.download_asset(asset).await?;
+        let sha256 = sha256_hex(&bytes);
+
+        match self.published_checksum(release, asset).await? {
+            Some(expected) if expected.eq_ignore_ascii_case(&sha256) => {}
+            Some(expected) => anyhow::bail!(
+                "Checksum mismatch for {}: expected {}, got {}",
+                asset.name,
+                expected,
+                sha256
+            ),
+            None if self.allow_unverified => logging::status(
+                Level::Warn,
+                name,
+                format!(
+                    "No checksum published for {}; installing unverified",
+                    asset.name
+                )
+                .yellow(),
+            ),
+            None => anyhow::bail!(
+                "Release {} of {} publishes no checksum for {}; pass --allow-unverified to install it anyway",
+                release.tag_name,
+                source.slug(),
+                asset.name
+            ),
+        }
+
+        let binary = extract_binary(&bytes, &asset.name, &binary_file_name(name))?;
+        self.write_binary(name, &binary)?;
+
+        let record = InstalledPlugin {
+            source: source.slug(),
+            version: release.tag_name.clone(),
+            asset: asset.name.clone(),
+            sha256,
+            installed_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            pinned: source.tag.is_some(),
+        };
+        let mut manifest = self.load_manifest()?;
+        manifest.plugins.insert(name.to_string(), record.clone());
+        self.save_manifest(&manifest)?;
+        Ok(record)
+    }
+
+    /// Checksum the release publishes for an asset, from `<asset>.sha256` or a checksums list
+    async fn published_checksum(
+        &self,
+        release: &Release,
+        asset: &ReleaseAsset,
+    ) -> Result<Option<String>> {
+        for candidate in release
+            .assets
+            .iter()
+            .filter(|candidate| is_checksum_file(&candidate.name))
+        {
+            let dedicated = candidate.name.starts_with(&asset.name);
+            let lowercase = candidate.name.to_lowercase();
+            if !dedicated && !lowercase.contains("checksums") && !lowercase.contains("sha256sums") {
+                continue;
+            }
+            let content = self.client.download_asset(candidate).await?;
+            let content = String::from_utf8_lossy(&content);
+            if let Some(checksum) = find_checksum(&content, &asset.name, dedicated) {
+                return Ok(Some(checksum));
+            }
+        }
+        Ok(None)
+    }
+
+    fn binary_path(&self, name: &str) -> PathBuf {
+        self.dir.join(binary_file_name(name))
+    }
+
+    /// Replace the plugin executable atomically, so a running copy is never half-written
+    fn write_binary(&self, name: &str, binary: &[u8]) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create {}", self.dir.display()))?;
+        let mut temp_file = tempfile::NamedTempFile::new_in(&self.dir)?;
+        std::io::Write::write_all(&mut temp_file, binary)?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(temp_file.path(), std::fs::Permissions::from_mode(0o755))?;
+        }
+
+        let path = self.binary_path(name);
+        temp_file
+            .persist(&path)
+            .with_context(|| format!("Failed to install {}", path.display()))?;
+        Ok(())
+    }
+
+    fn load_manifest(&self) -> Result<Manifest> {
+        let path = self.dir.join(MANIFEST_FILE);
+        if !path.exists() {
+            return Ok(Manifest::default());
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    fn save_manifest(&self, manifest: &Manifest) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        let path = self.dir.join(MANIFEST_FILE);
+        std::fs::write(&path, serde_json::to_string_pretty(manifest)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// Check that a plugin name is safe to use in a file name: letters, digits, `_` and `-`
+///
+/// Names end up in the path of the plugin executable, so anything else could
+/// write or delete files outside the plugin directory.
+fn validate_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !valid {
+        anyhow::bail!(
+            "Invalid plugin name '{}': use only letters, digits, '_' and '-'",
+            name
+        );
+    }
+    Ok(())
+}
+
+/// File name of a plugin executable, e.g. `repos-health` or `repos-health.exe`
+fn binary_file_name(name: &str) -> String {
+    format!("{}{}{}", PLUGIN_PREFIX, name, std::env::consts::EXE_SUFFIX)
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Whether a release asset holds checksums or signatures rather than a build
+fn is_checksum_file(name: &str) -> bool {
+    let name = name.to_lowercase();
+    name.ends_with(".sha256")
+        || name.ends_with(".sha256sum")
+        || name.contains("checksums")
+        || name.contains("sha256sums")
+}
+
+fn is_signature_file(name: &str) -> bool {
+    let name = name.to_lowercase();
+    [".sig", ".asc", ".pem", ".sbom", ".json"]
+        .iter()
+        .any(|ext| name.ends_with(ext))
+}
+
+/// Pick the asset built for an OS and architecture as named by `std::env::consts`
+pub fn select_asset<'a>(
+    assets: &'a [ReleaseAsset],
+    os: &str,
+    arch: &str,
+) -> Option<&'a ReleaseAsset> {
+    let aliases = |table: &[(&str, &'static [&'static str])], key: &str| {
+        table
+            .iter()
+            .find(|(name, _)| *name == key)
+            .map(|(_, aliases)| aliases.to_vec())
+            .unwrap_or_default()
+    };
+    let mut os_aliases = aliases(OS_ALIASES, os);
+    os_aliases.push(os);
+    let mut arch_aliases = aliases(ARCH_ALIASES, arch);
+    arch_aliases.push(arch);
+    if os == "macos" {
+        arch_aliases.push("universal");
+    }
+
+    assets.iter().find(|asset| {
+        let name = asset.name.to_lowercase();
+        !is_checksum_file(&name)
+            && !is_signature_file(&name)
+            && os_aliases.iter().any(|alias| name.contains(alias))
+            && arch_aliases.iter().any(|alias| name.contains(alias))
+    })
+}
+
+/// Find the checksum for `asset_name` in a checksum file
+///
+/// Checksum lists have `<sha256>  <file>` lines as written by `sha256sum`. A
+/// file dedicated to one asset (`<asset>.sha256`) may contain just the hash.
+fn find_checksum(content: &str, asset_name: &str, dedicated: bool) -> Option<String> {
+    content.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        let checksum = parts.next()?;
+        let is_sha256 = checksum.len() == 64 && checksum.chars().all(|c| c.is_ascii_hexdigit());
+        let file = parts.next().map(|file| file.trim_start_matches('*'));
+        let matches = match file {
+            Some(file) => file == asset_name || file.ends_with(&format!("/{}", asset_name)),
+            None => dedicated,
+        };
+        (is_sha256 && matches).then(|| checksum.to_lowercase())
+    })
+}
+
+/// Extract the plugin executable from a downloaded asset
+///
+/// `.tar.gz`/`.tgz` and `.zip` archives are searched for `binary_name`; any
+/// other asset is taken to be the executable itself.
+fn extract_binary(bytes: &[u8], asset_name: &str, binary_name: &str) -> Result<Vec<u8>> {
+    let lowercase = asset_name.to_lowercase();
+    let is_binary = |path: &Path| path.file_name().is_some_and(|name| name == binary_name);
+
+    if lowercase.ends_with(".tar.gz") || lowercase.ends_with(".tgz") {
+        let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(bytes));
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            if entry.header().entry_type().is_file() && is_binary(&entry.path()?) {
+                let mut binary = Vec::new();
+                entry.read_to_end(&mut binary)?;
+                return Ok(binary);
+            }
+        }
+    } else if lowercase.ends_with(".zip") {
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes))?;
+        for index in 0..archive.len() {
+            let mut file = archive.by_index(index)?;
+            if file.is_file() && is_binary(Path::new(file.name())) {
+                let mut binary = Vec::new();
+                file.read_to_end(&mut binary)?;
+                return Ok(binary);
+            }
+        }
+    } else {
+        return Ok(bytes.to_vec());
+    }
+
+    anyhow::bail!("{} does not contain {}", asset_name, binary_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn asset(name: &str) -> ReleaseAsset {
+        ReleaseAsset {
+            name: name.to_string(),
+            browser_download_url: format!("https://example.com/{}", name),
+            size: 0,
+        }
+    }
+
+    #[test]
+    fn test_parse_plugin_source() {
+        let source: PluginSource = "codcod/repos-health".parse().unwrap();
+        assert_eq!(source.owner, "codcod");
+        assert_eq!(source.repo, "repos-health");
+        assert_eq!(source.tag, None);
+        assert_eq!(source.default_name(), "health");
+
+        let source: PluginSource = "https://github.com/acme/lint-plugin@v1.2.0"
+            .parse()
+            .unwrap();
+        assert_eq!(source.repo, "lint-plugin");
+        assert_eq!(source.tag.as_deref(), Some("v1.2.0"));
+        assert_eq!(source.default_name(), "lint-plugin");
+
+        let source: PluginSource = "git@github.com:acme/repos-lint.git".parse().unwrap();
+        assert_eq!(source.slug(), "acme/repos-lint");
+        assert_eq!(source.tag, None);
+
+        assert!("just-a-name".parse::<PluginSource>().is_err());
+    }
+
+    #[test]
+    fn test_select_asset() {
+        let assets = vec![
+            asset("repos-health-x86_64-unknown-linux-gnu.tar.gz"),
+            asset("repos-health-x86_64-unknown-linux-gnu.tar.gz.sha256"),
+            asset("repos-health-aarch64-apple-darwin.tar.gz"),
+            asset("repos-health-x86_64-pc-windows-msvc.zip"),
+            asset("checksums.txt"),
+        ];
+
+        let pick = |os, arch| select_asset(&assets, os, arch).map(|a| a.name.as_str());
+        assert_eq!(
+            pick("linux", "x86_64"),
+            Some("repos-health-x86_64-unknown-linux-gnu.tar.gz")
+        );
+        assert_eq!(
+            pick("macos", "aarch64"),
+            Some("repos-health-aarch64-apple-darwin.tar.gz")
+        );
+        assert_eq!(
+            pick("windows", "x86_64"),
+            Some("repos-health-x86_64-pc-windows-msvc.zip")
+        );
+        assert_eq!(pick("linux", "aarch64"), None);
+
+        let assets = vec![asset("tool_Linux_amd64"), asset("tool_Darwin_universal")];
+        assert_eq!(
+            select_asset(&assets, "linux", "x86_64").map(|a| a.name.as_str()),
+            Some("tool_Linux_amd64")
+        );
+        assert_eq!(
+            select_asset(&assets, "macos", "aarch64").map(|a| a.name.as_str()),
+            Some("tool_Darwin_universal")
+        );
+    }
+
+    #[test]
+    fn test_find_checksum() {
+        let hash = "a".repeat(64);
+        let other = "b".repeat(64);
+        let list = format!("{}  tool.zip\n{} *tool.tar.gz\n", other, hash);
+        assert_eq!(
+            find_checksum(&list, "tool.tar.gz", false),
+            Some(hash.clone())
+        );
+        assert_eq!(find_checksum(&list, "missing.tar.gz", false), None);
+
+        // A file dedicated to one asset may hold just the hash
+        assert_eq!(
+            find_checksum(&hash, "tool.tar.gz", true),
+            Some(hash.clone())
+        );
+        assert_eq!(find_checksum(&hash, "tool.tar.gz", false), None);
+        assert_eq!(
+            find_checksum("not-a-hash tool.tar.gz", "tool.tar.gz", true),
+            None
+        );
+    }
+
+    #[test]
+    fn test_extract_binary() {
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+            Vec::new(),
+            flate2::Compression::default(),
+        ));
+        for (path, content) in [("dist/README.md", "docs"), ("dist/repos-health", "binary")] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o755);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, path, content.as_bytes())
+                .unwrap();
+        }
+        let archive = builder.into_inner().unwrap().finish().unwrap();
+
+        let binary = extract_binary(&archive, "health.tar.gz", "repos-health").unwrap();
+        assert_eq!(binary, b"binary");
+        assert!(extract_binary(&archive, "health.tar.gz", "repos-other").is_err());
+
+        // Anything that is not an archive is the executable itself
+        let binary = extract_binary(b"raw", "repos-health-linux-x86_64", "repos-health").unwrap();
+        assert_eq!(binary, b"raw");
+    }
+
+    #[test]
+    fn test_remove_installed_plugin() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = PluginManager::new(temp_dir.path().to_path_buf());
+        manager.write_binary("health", b"binary").unwrap();
+        let mut manifest = Manifest::default();
+        manifest.plugins.insert(
+            "health".to_string(),
+            InstalledPlugin {
+                source: "codcod/repos-health".to_string(),
+                version: "v1.0.0".to_string(),
+                asset: "repos-health-linux.tar.gz".to_string(),
+                sha256: "a".repeat(64),
+                installed_at: "2024-01-01 00:00:00".to_string(),
+                pinned: false,
+            },
+        );
+        manager.save_manifest(&manifest).unwrap();
+
+        assert!(manager.binary_path("health").exists());
+        assert!(manager.installed().unwrap().contains_key("health"));
+
+        manager.remove("health").unwrap();
+        assert!(!manager.binary_path("health").exists());
+        assert!(manager.installed().unwrap().is_empty());
+        assert!(manager.remove("health").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reject_names_outside_plugin_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().join("plugins");
+        let manager = PluginManager::new(dir.clone());
+        let outside = temp_dir.path().join(format!("{}escape", PLUGIN_PREFIX));
+        std::fs::write(&outside, b"keep").unwrap();
+
+        let mut manifest = Manifest::default();
+        manifest.plugins.insert(
+            "../escape".to_string(),
+            InstalledPlugin {
+                source: "acme/repos-escape".to_string(),
+                version: "v1.0.0".to_string(),
+                asset: "repos-escape-linux.tar.gz".to_string(),
+                sha256: "a".repeat(64),
+                installed_at: "2024-01-01 00:00:00".to_string(),
+                pinned: false,
+            },
+        );
+        manager.save_manifest(&manifest).unwrap();
+
+        let error = manager.remove("../escape").unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("Invalid plugin name '../escape'")
+        );
+        assert!(manager.update(Some("../escape")).await.is_err());
+        let source: PluginSource = "acme/repos-escape".parse().unwrap();
+        assert!(manager.install(&source, Some("../../bin/x")).await.is_err());
+        assert_eq!(std::fs::read(&outside).unwrap(), b"keep");
+
+        assert!(validate_name("health").is_ok());
+        assert!(validate_name("lint_v2-beta").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("a/b").is_err());
+        assert!(validate_name("x.exe").is_err());
+    }
+}
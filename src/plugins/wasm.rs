@@ -0,0 +1,282 @@
+//! WebAssembly plugins loaded from `~/.repos/plugins`
+//!
+//! A WASM plugin is a single `<name>.wasm` module, run with `repos <name>` like
+//! an external plugin. Modules must export `memory` and a `run() -> i32` entry
+//! point whose result is the plugin's exit code, and may import these host
+//! functions from the `repos` namespace:
+//!
+//! - `args(ptr, cap) -> len`: plugin arguments as a JSON array of strings
+//! - `repositories(ptr, cap) -> len`: filtered repositories as JSON, with
+//!   resolved paths
+//! - `log(ptr, len)`: print a UTF-8 message
+//! - `run_command(repo_ptr, repo_len, cmd_ptr, cmd_len) -> exit_code`: run a
+//!   shell command in one of the filtered repositories; returns -1 when the
+//!   repository is unknown or the command cannot be started
+//!
+//! `args` and `repositories` write at most `cap` bytes at `ptr` and return the
+//! full length, so a plugin can call again with a larger buffer.
+
+use super::{PluginContext, plugin_dir};
+use crate::config::Repository;
+use anyhow::{Context, Result};
+use colored::*;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use wasmi::{Caller, Engine, Extern, Linker, Module, Store};
+
+/// File extension of WASM plugins
+const WASM_EXTENSION: &str = "wasm";
+
+struct HostState {
+    plugin_name: String,
+    args_json: Vec<u8>,
+    repositories_json: Vec<u8>,
+    repositories: Vec<Repository>,
+}
+
+/// Path of the WASM plugin with the given name, if installed
+pub fn find_plugin(name: &str) -> Option<PathBuf> {
+    let path = plugin_dir()?.join(format!("{}.{}", name, WASM_EXTENSION));
+    path.is_file().then_some(path)
+}
+
+/// Names of all installed WASM plugins
+pub fn list_plugins() -> Vec<String> {
+    let Some(dir) = plugin_dir() else {
+        return Vec::new();
+    };
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut plugins: Vec<String> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == WASM_EXTENSION))
+        .filter_map(|path| path.file_stem().map(|s| s.to_string_lossy().to_string()))
+        .collect();
+    plugins.sort();
+    plugins
+}
+
+/// Load and run a WASM plugin, failing when it returns a non-zero exit code
+pub fn run_plugin(path: &Path, context: &PluginContext) -> Result<()> {
+    let plugin_name = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let bytes = std::fs::read(path)
+        .with_context(|| format!("Failed to read WASM plugin {}", path.display()))?;
+
+    let repositories: Vec<Repository> = context
+        .repositories
+        .iter()
+        .map(|repo| {
+            let mut repo = repo.clone();
+            repo.path = Some(repo.get_target_dir());
+            repo
+        })
+        .collect();
+
+    let engine = Engine::default();
+    let module = Module::new(&engine, &bytes)
+        .map_err(|e| anyhow::anyhow!("Invalid WASM plugin '{}': {}", plugin_name, e))?;
+    let mut store = Store::new(
+        &engine,
+        HostState {
+            plugin_name: plugin_name.clone(),
+            args_json: serde_json::to_vec(&context.args)?,
+            repositories_json: serde_json::to_vec(&repositories)?,
+            repositories,
+        },
+    );
+
+    let mut linker = <Linker<HostState>>::new(&engine);
+    link_host_api(&mut linker)?;
+
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .and_then(|pre| pre.start(&mut store))
+        .map_err(|e| {
+            anyhow::anyhow!("Failed to instantiate WASM plugin '{}': {}", plugin_name, e)
+        })?;
+    let run = instance
+        .get_typed_func::<(), i32>(&store, "run")
+        .map_err(|e| anyhow::anyhow!("WASM plugin '{}' has no run() export: {}", plugin_name, e))?;
+
+    let exit_code = run
+        .call(&mut store, ())
+        .map_err(|e| anyhow::anyhow!("WASM plugin '{}' failed: {}", plugin_name, e))?;
+    if exit_code != 0 {
+        anyhow::bail!("Plugin '{}' exited with status: {}", plugin_name, exit_code);
+    }
+
+    Ok(())
+}
+
+fn link_host_api(linker: &mut Linker<HostState>) -> Result<()> {
+    linker
+        .func_wrap(
+            "repos",
+            "args",
+            |mut caller: Caller<'_, HostState>, ptr: i32, cap: i32| -> Result<i32, wasmi::Error> {
+                let data = caller.data().args_json.clone();
+                write_buffer(&mut caller, ptr, cap, &data)
+            },
+        )
+        .map_err(|e| anyhow::anyhow!(e.to_string()))?
+        .func_wrap(
+            "repos",
+            "repositories",
+            |mut caller: Caller<'_, HostState>, ptr: i32, cap: i32| -> Result<i32, wasmi::Error> {
+                let data = caller.data().repositories_json.clone();
+                write_buffer(&mut caller, ptr, cap, &data)
+            },
+        )
+        .map_err(|e| anyhow::anyhow!(e.to_string()))?
+        .func_wrap(
+            "repos",
+            "log",
+            |caller: Caller<'_, HostState>, ptr: i32, len: i32| -> Result<(), wasmi::Error> {
+                let message = read_string(&caller, ptr, len)?;
+                println!("{} | {}", caller.data().plugin_name.cyan().bold(), message);
+                Ok(())
+            },
+        )
+        .map_err(|e| anyhow::anyhow!(e.to_string()))?
+        .func_wrap(
+            "repos",
+            "run_command",
+            |caller: Caller<'_, HostState>,
+             repo_ptr: i32,
+             repo_len: i32,
+             cmd_ptr: i32,
+             cmd_len: i32|
+             -> Result<i32, wasmi::Error> {
+                let name = read_string(&caller, repo_ptr, repo_len)?;
+                let command = read_string(&caller, cmd_ptr, cmd_len)?;
+                Ok(run_in_repository(
+                    &caller.data().repositories,
+                    &name,
+                    &command,
+                ))
+            },
+        )
+        .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+    Ok(())
+}
+
+/// Run a command in a filtered repository and return its exit code, or -1
+fn run_in_repository(repositories: &[Repository], name: &str, command: &str) -> i32 {
+    let Some(repo) = repositories.iter().find(|repo| repo.name == name) else {
+        return -1;
+    };
+
+    Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .current_dir(repo.get_target_dir())
+        .status()
+        .ok()
+        .and_then(|status| status.code())
+        .unwrap_or(-1)
+}
+
+fn write_buffer(
+    caller: &mut Caller<'_, HostState>,
+    ptr: i32,
+    cap: i32,
+    data: &[u8],
+) -> Result<i32, wasmi::Error> {
+    if data.len() <= cap as usize {
+        let memory = guest_memory(caller)?;
+        memory
+            .write(caller, ptr as usize, data)
+            .map_err(|e| wasmi::Error::new(e.to_string()))?;
+    }
+    Ok(data.len() as i32)
+}
+
+fn read_string(caller: &Caller<'_, HostState>, ptr: i32, len: i32) -> Result<String, wasmi::Error> {
+    let memory = guest_memory(caller)?;
+    let mut buffer = vec![0; len.max(0) as usize];
+    memory
+        .read(caller, ptr as usize, &mut buffer)
+        .map_err(|e| wasmi::Error::new(e.to_string()))?;
+    String::from_utf8(buffer).map_err(|_| wasmi::Error::new("string is not valid UTF-8"))
+}
+
+fn guest_memory(caller: &Caller<'_, HostState>) -> Result<wasmi::Memory, wasmi::Error> {
+    caller
+        .get_export("memory")
+        .and_then(Extern::into_memory)
+        .ok_or_else(|| wasmi::Error::new("plugin does not export memory"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use tempfile::TempDir;
+
+    /// Logs its arguments, then runs `test -f marker` in the repository named
+    /// by the first argument and returns that command's exit code
+    const TEST_PLUGIN: &str = r#"
+        (module
+          (import "repos" "args" (func $args (param i32 i32) (result i32)))
+          (import "repos" "log" (func $log (param i32 i32)))
+          (import "repos" "run_command" (func $run_command (param i32 i32 i32 i32) (result i32)))
+          (memory (export "memory") 1)
+          (data (i32.const 0) "test -f marker")
+          (func (export "run") (result i32)
+            (local $len i32)
+            ;; args JSON is written at offset 100, e.g. ["api"]
+            (local.set $len (call $args (i32.const 100) (i32.const 200)))
+            (call $log (i32.const 100) (local.get $len))
+            ;; skip the leading [" and trailing "] to get the repository name
+            (call $run_command
+              (i32.const 102) (i32.sub (local.get $len) (i32.const 4))
+              (i32.const 0) (i32.const 14))))
+    "#;
+
+    fn context(repo_dir: &Path, args: &[&str]) -> PluginContext {
+        let mut repo = Repository::new("api".to_string(), "git@github.com:org/api.git".to_string());
+        repo.path = Some(repo_dir.to_string_lossy().to_string());
+        PluginContext::new(
+            Config::new(),
+            vec![repo],
+            args.iter().map(|a| a.to_string()).collect(),
+            false,
+        )
+    }
+
+    fn write_plugin(dir: &Path) -> PathBuf {
+        let path = dir.join("check.wasm");
+        std::fs::write(&path, wat::parse_str(TEST_PLUGIN).unwrap()).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_run_plugin() {
+        let temp_dir = TempDir::new().unwrap();
+        let plugin = write_plugin(temp_dir.path());
+
+        // The marker file is missing, so the command and the plugin fail
+        assert!(run_plugin(&plugin, &context(temp_dir.path(), &["api"])).is_err());
+
+        std::fs::write(temp_dir.path().join("marker"), "").unwrap();
+        assert!(run_plugin(&plugin, &context(temp_dir.path(), &["api"])).is_ok());
+
+        // Repositories outside the filtered set cannot be targeted
+        assert!(run_plugin(&plugin, &context(temp_dir.path(), &["web"])).is_err());
+    }
+
+    #[test]
+    fn test_invalid_module() {
+        let temp_dir = TempDir::new().unwrap();
+        let plugin = temp_dir.path().join("broken.wasm");
+        std::fs::write(&plugin, b"not wasm").unwrap();
+
+        assert!(run_plugin(&plugin, &context(temp_dir.path(), &[])).is_err());
+    }
+}
@@ -0,0 +1,529 @@
+//! Local state store for information remembered between runs
+//!
+//! State lives in `.repos/state.json` next to the config file the command was
+//! given, under the current directory when there is none, or in the directory
+//! named by `REPOS_STATE_DIR`. Updates go through [`StateStore::update`],
+//! which re-reads the file under a process-wide lock so concurrent tasks do not
+//! overwrite each other's changes.
+
+use crate::constants::state::{
+    MAX_CHECKPOINTS, STATE_DIR, STATE_DIR_ENV, STATE_FILE, TRASH_DIR, WORKTREES_DIR,
+};
+use crate::git::WorkingState;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+static STATE_LOCK: Mutex<()> = Mutex::new(());
+
+static DEFAULT_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Keep the default state store next to the config file at `config_path` for the rest of the process
+pub fn anchor_to_config(config_path: &Path) {
+    let config_dir = config_path.parent().unwrap_or(Path::new(""));
+    let _ = DEFAULT_DIR.set(config_dir.join(STATE_DIR));
+}
+
+/// Everything remembered about the fleet
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct State {
+    #[serde(default)]
+    pub repositories: BTreeMap<String, RepositoryState>,
+    /// Pull requests opened by `repos pr`, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pull_requests: Vec<PullRequestRecord>,
+    /// Named checkpoints saved by `repos bookmark save`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub bookmarks: BTreeMap<String, Bookmark>,
+    /// Working copies saved before mutating commands, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub checkpoints: Vec<Checkpoint>,
+    /// Pull request rollouts opened in batches, by title
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub rollouts: BTreeMap<String, Rollout>,
+}
+
+/// Information remembered about a single repository
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RepositoryState {
+    /// Default branch as last detected locally or via the GitHub API
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_branch: Option<String>,
+    /// Branches found to be protected, which `repos pr` never pushes to
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub protected_branches: Vec<String>,
+    /// Whether the repository was archived when its provider was last asked
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub archived: bool,
+    /// When the provider was last asked whether the repository is archived
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archived_checked_at: Option<DateTime<Utc>>,
+    /// Marked as failing for a known reason, so `repos run` leaves it out
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub known_failing: bool,
+}
+
+impl RepositoryState {
+    /// Whether the provider was asked about archiving less than `ttl` ago
+    pub fn archived_checked_within(&self, ttl: Duration) -> bool {
+        self.archived_checked_at.is_some_and(|checked_at| {
+            Utc::now()
+                .signed_duration_since(checked_at)
+                .to_std()
+                .is_ok_and(|age| age < ttl)
+        })
+    }
+}
+
+/// A pull request opened by `repos pr`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PullRequestRecord {
+    /// Repository name from the config
+    pub repository: String,
+    pub owner: String,
+    pub repo: String,
+    pub number: u64,
+    pub url: String,
+    pub branch: String,
+    pub base: String,
+    pub created_at: String,
+}
+
+/// Branch and commit of each repository at the time a bookmark was saved
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub created_at: String,
+    pub repositories: BTreeMap<String, BookmarkEntry>,
+}
+
+/// Where a single repository was when a bookmark was saved
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BookmarkEntry {
+    /// Checked-out branch; `None` when HEAD was detached
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    pub commit: String,
+}
+
+/// Working copies of the repositories an operation was about to change
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Identifier passed to `repos rollback`
+    pub id: String,
+    /// The command that took the checkpoint, e.g. `run cargo fmt`
+    pub operation: String,
+    pub created_at: String,
+    pub repositories: BTreeMap<String, WorkingState>,
+}
+
+/// Progress of a `repos pr --batch-size` rollout
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Rollout {
+    pub started_at: String,
+    /// When the most recent batch was opened
+    pub batch_started_at: String,
+    /// Repositories already handled, whether or not they needed a pull request
+    pub done: Vec<String>,
+}
+
+impl Rollout {
+    /// Pull requests opened by this rollout
+    pub fn pull_requests<'a>(
+        &'a self,
+        records: &'a [PullRequestRecord],
+    ) -> impl Iterator<Item = &'a PullRequestRecord> {
+        // Timestamps are zero-padded, so they compare as strings
+        records.iter().filter(|record| {
+            record.created_at >= self.started_at && self.done.contains(&record.repository)
+        })
+    }
+}
+
+/// Handle to the state file
+#[derive(Debug, Clone)]
+pub struct StateStore {
+    path: PathBuf,
+}
+
+impl StateStore {
+    /// Open the state store at a specific directory
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        Self {
+            path: dir.as_ref().join(STATE_FILE),
+        }
+    }
+
+    /// Open the default state store
+    ///
+    /// This is `REPOS_STATE_DIR` when set, otherwise `.repos` next to the
+    /// config file given to [`anchor_to_config`], or under the current directory.
+    pub fn open_default() -> Self {
+        if let Ok(dir) = std::env::var(STATE_DIR_ENV) {
+            return Self::new(dir);
+        }
+        match DEFAULT_DIR.get() {
+            Some(dir) => Self::new(dir),
+            None => Self::new(STATE_DIR),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Directory where `repos rm --trash` moves removed repositories
+    pub fn trash_dir(&self) -> PathBuf {
+        self.path
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join(TRASH_DIR)
+    }
+
+    /// Directory where `--use-worktree` keeps one worktree per repository
+    pub fn worktrees_dir(&self) -> PathBuf {
+        self.path
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join(WORKTREES_DIR)
+    }
+
+    /// Read the current state; a missing file yields empty state
+    pub fn load(&self) -> Result<State> {
+        if !self.path.exists() {
+            return Ok(State::default());
+        }
+        let content = std::fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read state file {}", self.path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse state file {}", self.path.display()))
+    }
+
+    /// Read state for a single repository
+    pub fn repository(&self, name: &str) -> Result<RepositoryState> {
+        Ok(self
+            .load()?
+            .repositories
+            .get(name)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    /// Apply a change to the state and write it back
+    pub fn update<T>(&self, change: impl FnOnce(&mut State) -> T) -> Result<T> {
+        let _guard = STATE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+        let mut state = self.load()?;
+        let result = change(&mut state);
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        // Write to a temporary file first so a crash never leaves a truncated state file
+        let temp_path = self.path.with_extension("json.tmp");
+        std::fs::write(&temp_path, serde_json::to_string_pretty(&state)?)?;
+        std::fs::rename(&temp_path, &self.path)
+            .with_context(|| format!("Failed to write state file {}", self.path.display()))?;
+
+        Ok(result)
+    }
+
+    /// Apply a change to a single repository's state and write it back
+    pub fn update_repository<T>(
+        &self,
+        name: &str,
+        change: impl FnOnce(&mut RepositoryState) -> T,
+    ) -> Result<T> {
+        self.update(|state| change(state.repositories.entry(name.to_string()).or_default()))
+    }
+
+    /// Remember whether a repository is archived
+    pub fn record_archived(&self, name: &str, archived: bool) -> Result<()> {
+        self.update_repository(name, |state| {
+            state.archived = archived;
+            state.archived_checked_at = Some(Utc::now());
+        })
+    }
+
+    /// Mark a repository as known failing, or clear the mark
+    pub fn record_known_failing(&self, name: &str, known_failing: bool) -> Result<()> {
+        self.update_repository(name, |state| state.known_failing = known_failing)
+    }
+
+    /// Remember a newly opened pull request
+    pub fn record_pull_request(&self, record: PullRequestRecord) -> Result<()> {
+        self.update(|state| {
+            state.pull_requests.retain(|r| {
+                !(r.owner == record.owner && r.repo == record.repo && r.number == record.number)
+            });
+            state.pull_requests.push(record);
+        })
+    }
+
+    /// Forget pull requests matching the predicate, e.g. once they are merged
+    pub fn remove_pull_requests(&self, remove: impl Fn(&PullRequestRecord) -> bool) -> Result<()> {
+        self.update(|state| state.pull_requests.retain(|r| !remove(r)))
+    }
+
+    /// Look up a bookmark by name
+    pub fn bookmark(&self, name: &str) -> Result<Option<Bookmark>> {
+        Ok(self.load()?.bookmarks.remove(name))
+    }
+
+    /// Save a bookmark, replacing any bookmark with the same name
+    pub fn save_bookmark(&self, name: &str, bookmark: Bookmark) -> Result<()> {
+        self.update(|state| {
+            state.bookmarks.insert(name.to_string(), bookmark);
+        })
+    }
+
+    /// Look up a checkpoint by id
+    pub fn checkpoint(&self, id: &str) -> Result<Option<Checkpoint>> {
+        Ok(self.load()?.checkpoints.into_iter().find(|c| c.id == id))
+    }
+
+    /// Add a checkpoint to the journal, returning the old ones it pushed out
+    pub fn record_checkpoint(&self, checkpoint: Checkpoint) -> Result<Vec<Checkpoint>> {
+        self.update(|state| {
+            state.checkpoints.push(checkpoint);
+            let excess = state.checkpoints.len().saturating_sub(MAX_CHECKPOINTS);
+            state.checkpoints.drain(..excess).collect()
+        })
+    }
+
+    /// Look up a rollout by name
+    pub fn rollout(&self, name: &str) -> Result<Option<Rollout>> {
+        Ok(self.load()?.rollouts.remove(name))
+    }
+
+    /// Note that a rollout opened a new batch, starting the rollout if needed
+    pub fn start_rollout_batch(&self, name: &str) -> Result<()> {
+        let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        self.update(|state| {
+            let rollout = state
+                .rollouts
+                .entry(name.to_string())
+                .or_insert_with(|| Rollout {
+                    started_at: now.clone(),
+                    ..Default::default()
+                });
+            rollout.batch_started_at = now;
+        })
+    }
+
+    /// Mark a repository as handled by a rollout
+    pub fn record_rollout_progress(&self, name: &str, repository: &str) -> Result<()> {
+        self.update(|state| {
+            let rollout = state.rollouts.entry(name.to_string()).or_default();
+            if !rollout.done.iter().any(|done| done == repository) {
+                rollout.done.push(repository.to_string());
+            }
+        })
+    }
+
+    /// Forget a finished rollout, returning whether it existed
+    pub fn remove_rollout(&self, name: &str) -> Result<bool> {
+        self.update(|state| state.rollouts.remove(name).is_some())
+    }
+
+    /// Delete a bookmark, returning whether it existed
+    pub fn remove_bookmark(&self, name: &str) -> Result<bool> {
+        self.update(|state| state.bookmarks.remove(name).is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_missing_state_is_empty() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = StateStore::new(temp_dir.path().join(".repos"));
+
+        assert!(store.load().unwrap().repositories.is_empty());
+        assert!(store.repository("api").unwrap().default_branch.is_none());
+    }
+
+    #[test]
+    fn test_update_repository_persists() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = StateStore::new(temp_dir.path().join(".repos"));
+
+        store
+            .update_repository("api", |repo| {
+                repo.default_branch = Some("develop".to_string())
+            })
+            .unwrap();
+
+        let reopened = StateStore::new(temp_dir.path().join(".repos"));
+        assert_eq!(
+            reopened
+                .repository("api")
+                .unwrap()
+                .default_branch
+                .as_deref(),
+            Some("develop")
+        );
+        assert!(store.path().exists());
+    }
+
+    #[test]
+    fn test_archived_check_expires() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = StateStore::new(temp_dir.path());
+        let ttl = Duration::from_secs(3600);
+        assert!(!RepositoryState::default().archived_checked_within(ttl));
+
+        store.record_archived("api", false).unwrap();
+        assert!(
+            store
+                .repository("api")
+                .unwrap()
+                .archived_checked_within(ttl)
+        );
+
+        store
+            .update_repository("api", |state| {
+                state.archived_checked_at = Some(Utc::now() - chrono::Duration::hours(2))
+            })
+            .unwrap();
+        assert!(
+            !store
+                .repository("api")
+                .unwrap()
+                .archived_checked_within(ttl)
+        );
+    }
+
+    #[test]
+    fn test_corrupt_state_is_an_error() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = StateStore::new(temp_dir.path());
+        std::fs::write(store.path(), "{not json").unwrap();
+
+        assert!(store.load().is_err());
+    }
+
+    #[test]
+    fn test_pull_request_records() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = StateStore::new(temp_dir.path());
+        let record = |number: u64| PullRequestRecord {
+            repository: "api".to_string(),
+            owner: "org".to_string(),
+            repo: "api".to_string(),
+            number,
+            url: format!("https://github.com/org/api/pull/{}", number),
+            branch: "repos/abc123".to_string(),
+            base: "main".to_string(),
+            created_at: "2024-01-01 00:00:00".to_string(),
+        };
+
+        store.record_pull_request(record(1)).unwrap();
+        store.record_pull_request(record(2)).unwrap();
+        // Recording the same PR again replaces it
+        store.record_pull_request(record(1)).unwrap();
+        let numbers: Vec<u64> = store
+            .load()
+            .unwrap()
+            .pull_requests
+            .iter()
+            .map(|r| r.number)
+            .collect();
+        assert_eq!(numbers, vec![2, 1]);
+
+        store.remove_pull_requests(|r| r.number == 2).unwrap();
+        assert_eq!(store.load().unwrap().pull_requests, vec![record(1)]);
+    }
+
+    #[test]
+    fn test_bookmarks() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = StateStore::new(temp_dir.path());
+        let mut bookmark = Bookmark {
+            created_at: "2024-01-01 00:00:00".to_string(),
+            ..Default::default()
+        };
+        bookmark.repositories.insert(
+            "api".to_string(),
+            BookmarkEntry {
+                branch: Some("feature/login".to_string()),
+                commit: "abc123".to_string(),
+            },
+        );
+
+        assert!(store.bookmark("login").unwrap().is_none());
+        store.save_bookmark("login", bookmark.clone()).unwrap();
+        assert_eq!(store.bookmark("login").unwrap(), Some(bookmark));
+
+        assert!(store.remove_bookmark("login").unwrap());
+        assert!(!store.remove_bookmark("login").unwrap());
+        assert!(store.load().unwrap().bookmarks.is_empty());
+    }
+
+    #[test]
+    fn test_checkpoints_are_capped() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = StateStore::new(temp_dir.path());
+        let checkpoint = |id: usize| Checkpoint {
+            id: format!("op-{}", id),
+            operation: "run cargo fmt".to_string(),
+            ..Default::default()
+        };
+
+        for id in 0..MAX_CHECKPOINTS {
+            assert!(store.record_checkpoint(checkpoint(id)).unwrap().is_empty());
+        }
+        let dropped = store
+            .record_checkpoint(checkpoint(MAX_CHECKPOINTS))
+            .unwrap();
+        assert_eq!(dropped, vec![checkpoint(0)]);
+        assert!(store.checkpoint("op-0").unwrap().is_none());
+        assert_eq!(
+            store.checkpoint("op-1").unwrap().map(|c| c.operation),
+            Some("run cargo fmt".to_string())
+        );
+    }
+
+    #[test]
+    fn test_rollouts() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = StateStore::new(temp_dir.path());
+
+        assert!(store.rollout("Bump deps").unwrap().is_none());
+        store.start_rollout_batch("Bump deps").unwrap();
+        store.record_rollout_progress("Bump deps", "api").unwrap();
+        store.record_rollout_progress("Bump deps", "web").unwrap();
+        store.record_rollout_progress("Bump deps", "api").unwrap();
+
+        let rollout = store.rollout("Bump deps").unwrap().unwrap();
+        assert_eq!(rollout.done, vec!["api", "web"]);
+        assert_eq!(rollout.started_at, rollout.batch_started_at);
+
+        let record = |repository: &str, created_at: &str| PullRequestRecord {
+            repository: repository.to_string(),
+            owner: "org".to_string(),
+            repo: repository.to_string(),
+            number: 1,
+            url: format!("https://github.com/org/{}/pull/1", repository),
+            branch: "repos/bump-deps".to_string(),
+            base: "main".to_string(),
+            created_at: created_at.to_string(),
+        };
+        let records = vec![
+            record("api", "2000-01-01 00:00:00"),
+            record("api", "2999-01-01 00:00:00"),
+            record("db", "2999-01-01 00:00:00"),
+        ];
+        let opened: Vec<_> = rollout.pull_requests(&records).collect();
+        assert_eq!(opened, vec![&records[1]]);
+
+        assert!(store.remove_rollout("Bump deps").unwrap());
+        assert!(!store.remove_rollout("Bump deps").unwrap());
+    }
+}
@@ -0,0 +1,370 @@
+//! State of the dashboard and what each key does to it
+//!
+//! Nothing here touches the terminal or runs git, so the behaviour of the
+//! dashboard can be tested without either.
+
+use crate::config::Repository;
+use crate::git::WorkingStatus;
+use crate::utils::RunRecord;
+use chrono::NaiveDateTime;
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Rows moved by Page Up and Page Down
+const PAGE: usize = 10;
+
+/// What is known about the working copy of a repository
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RepoState {
+    Loading,
+    NotCloned,
+    Status(WorkingStatus),
+    Error(String),
+}
+
+/// The result a repository had in the newest saved run that included it
+#[derive(Debug, Clone, PartialEq)]
+pub struct LastRun {
+    pub run_id: String,
+    /// Command or recipe that was run
+    pub label: String,
+    pub started: Option<NaiveDateTime>,
+    pub exit_code: Option<i32>,
+    pub success: bool,
+    pub log_dir: Option<PathBuf>,
+}
+
+impl LastRun {
+    /// The newest result of every repository in `runs`, which come newest first
+    pub fn from_runs(runs: &[RunRecord]) -> HashMap<String, LastRun> {
+        let mut last_runs = HashMap::new();
+        for run in runs {
+            for result in &run.summary.results {
+                last_runs
+                    .entry(result.repository.clone())
+                    .or_insert_with(|| LastRun {
+                        run_id: run.id.clone(),
+                        label: run.label.clone(),
+                        started: run.started,
+                        exit_code: result.exit_code,
+                        success: result.is_success(),
+                        log_dir: result.log_dir.clone(),
+                    });
+            }
+        }
+        last_runs
+    }
+}
+
+/// A repository of the fleet as shown on the dashboard
+#[derive(Debug, Clone)]
+pub struct Row {
+    pub repo: Repository,
+    pub state: RepoState,
+    pub last_run: Option<LastRun>,
+}
+
+/// What keys currently do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Normal,
+    /// Typing a filter
+    Filter,
+    /// Choosing the recipe to run, by index into the recipes
+    PickRecipe(usize),
+}
+
+/// Something the event loop has to do in response to a key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    None,
+    Quit,
+    /// Reload the status of every repository and the saved runs
+    Refresh,
+    /// Fast-forward a repository, by index into the rows
+    Pull(usize),
+    RunRecipe(usize, String),
+    OpenPullRequest(usize),
+}
+
+/// The dashboard: all rows, the filter narrowing them and the selection
+#[derive(Debug, Clone)]
+pub struct App {
+    pub rows: Vec<Row>,
+    pub recipes: Vec<String>,
+    pub filter: String,
+    pub mode: Mode,
+    /// Index into [`App::visible`]
+    pub selected: usize,
+    /// Feedback on the last action, shown in the status line
+    pub message: Option<String>,
+}
+
+impl App {
+    pub fn new(repositories: Vec<Repository>, recipes: Vec<String>) -> Self {
+        Self {
+            rows: repositories
+                .into_iter()
+                .map(|repo| Row {
+                    repo,
+                    state: RepoState::Loading,
+                    last_run: None,
+                })
+                .collect(),
+            recipes,
+            filter: String::new(),
+            mode: Mode::Normal,
+            selected: 0,
+            message: None,
+        }
+    }
+
+    /// Indices of the rows matching the filter
+    ///
+    /// Every word of the filter has to occur in the repository's name or in
+    /// one of its tags, ignoring case.
+    pub fn visible(&self) -> Vec<usize> {
+        let words: Vec<String> = self
+            .filter
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .collect();
+        self.rows
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| {
+                words.iter().all(|word| {
+                    row.repo.name.to_lowercase().contains(word)
+                        || row
+                            .repo
+                            .tags
+                            .iter()
+                            .any(|tag| tag.to_lowercase().contains(word))
+                })
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Index into the rows of the selected repository
+    pub fn selected_row(&self) -> Option<usize> {
+        self.visible().get(self.selected).copied()
+    }
+
+    /// Replace the last run of every row
+    pub fn set_last_runs(&mut self, runs: &[RunRecord]) {
+        let mut last_runs = LastRun::from_runs(runs);
+        for row in &mut self.rows {
+            row.last_run = last_runs.remove(&row.repo.name);
+        }
+    }
+
+    fn select(&mut self, selected: usize) {
+        let count = self.visible().len();
+        self.selected = selected.min(count.saturating_sub(1));
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Action {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return Action::Quit;
+        }
+        match self.mode {
+            Mode::Normal => self.normal_key(key.code),
+            Mode::Filter => {
+                match key.code {
+                    KeyCode::Esc => {
+                        self.filter.clear();
+                        self.mode = Mode::Normal;
+                    }
+                    KeyCode::Enter => self.mode = Mode::Normal,
+                    KeyCode::Backspace => {
+                        self.filter.pop();
+                    }
+                    KeyCode::Char(c) => self.filter.push(c),
+                    _ => {}
+                }
+                self.select(self.selected);
+                Action::None
+            }
+            Mode::PickRecipe(index) => match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => {
+                    self.mode = Mode::Normal;
+                    Action::None
+                }
+                KeyCode::Up | KeyCode::Char('k') => {
+                    self.mode = Mode::PickRecipe(index.saturating_sub(1));
+                    Action::None
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    let last = self.recipes.len().saturating_sub(1);
+                    self.mode = Mode::PickRecipe((index + 1).min(last));
+                    Action::None
+                }
+                KeyCode::Enter => {
+                    self.mode = Mode::Normal;
+                    match (self.selected_row(), self.recipes.get(index)) {
+                        (Some(row), Some(recipe)) => Action::RunRecipe(row, recipe.clone()),
+                        _ => Action::None,
+                    }
+                }
+                _ => Action::None,
+            },
+        }
+    }
+
+    fn normal_key(&mut self, code: KeyCode) -> Action {
+        let count = self.visible().len();
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return Action::Quit,
+            KeyCode::Down | KeyCode::Char('j') => self.select(self.selected + 1),
+            KeyCode::Up | KeyCode::Char('k') => self.select(self.selected.saturating_sub(1)),
+            KeyCode::PageDown => self.select(self.selected + PAGE),
+            KeyCode::PageUp => self.select(self.selected.saturating_sub(PAGE)),
+            KeyCode::Home | KeyCode::Char('g') => self.select(0),
+            KeyCode::End | KeyCode::Char('G') => self.select(count),
+            KeyCode::Char('/') => self.mode = Mode::Filter,
+            KeyCode::Char('R') | KeyCode::F(5) => return Action::Refresh,
+            KeyCode::Char('p') => {
+                if let Some(row) = self.selected_row() {
+                    return Action::Pull(row);
+                }
+            }
+            KeyCode::Char('o') => {
+                if let Some(row) = self.selected_row() {
+                    return Action::OpenPullRequest(row);
+                }
+            }
+            KeyCode::Char('r') => match self.recipes.len() {
+                _ if self.selected_row().is_none() => {}
+                0 => self.message = Some("No recipes defined in the config".to_string()),
+                1 => {
+                    if let Some(row) = self.selected_row() {
+                        return Action::RunRecipe(row, self.recipes[0].clone());
+                    }
+                }
+                _ => self.mode = Mode::PickRecipe(0),
+            },
+            _ => {}
+        }
+        Action::None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{RepoRunResult, RunSummary};
+
+    fn app(recipes: &[&str]) -> App {
+        let repos = [
+            ("api", &["backend", "rust"][..]),
+            ("web", &["frontend", "node"][..]),
+            ("worker", &["backend", "node"][..]),
+        ]
+        .into_iter()
+        .map(|(name, tags)| {
+            let mut repo =
+                Repository::new(name.to_string(), format!("git@github.com:org/{}.git", name));
+            repo.tags = tags.iter().map(|tag| tag.to_string()).collect();
+            repo
+        })
+        .collect();
+        App::new(repos, recipes.iter().map(|r| r.to_string()).collect())
+    }
+
+    fn press(app: &mut App, keys: &str) -> Action {
+        keys.chars()
+            .map(|c| app.handle_key(KeyEvent::from(KeyCode::Char(c))))
+            .last()
+            .unwrap_or(Action::None)
+    }
+
+    #[test]
+    fn test_filter_and_selection() {
+        let mut app = app(&[]);
+        assert_eq!(app.visible(), vec![0, 1, 2]);
+
+        press(&mut app, "G");
+        assert_eq!(app.selected_row(), Some(2));
+        press(&mut app, "jjk");
+        assert_eq!(app.selected_row(), Some(1));
+
+        // Typing narrows the list and keeps the selection on it
+        press(&mut app, "/node back");
+        assert_eq!(app.mode, Mode::Filter);
+        assert_eq!(app.visible(), vec![2]);
+        assert_eq!(app.selected_row(), Some(2));
+
+        app.handle_key(KeyEvent::from(KeyCode::Enter));
+        assert_eq!(app.mode, Mode::Normal);
+        assert_eq!(press(&mut app, "p"), Action::Pull(2));
+
+        press(&mut app, "/");
+        app.handle_key(KeyEvent::from(KeyCode::Esc));
+        assert_eq!(app.filter, "");
+        assert_eq!(app.visible().len(), 3);
+
+        press(&mut app, "/nothing");
+        assert_eq!(app.selected_row(), None);
+        app.handle_key(KeyEvent::from(KeyCode::Enter));
+        assert_eq!(press(&mut app, "p"), Action::None);
+        assert_eq!(press(&mut app, "q"), Action::Quit);
+    }
+
+    #[test]
+    fn test_run_recipe_keys() {
+        let mut none = app(&[]);
+        assert_eq!(press(&mut none, "r"), Action::None);
+        assert!(none.message.is_some());
+
+        let mut one = app(&["test"]);
+        assert_eq!(
+            press(&mut one, "jr"),
+            Action::RunRecipe(1, "test".to_string())
+        );
+
+        let mut several = app(&["build", "test", "lint"]);
+        press(&mut several, "r");
+        assert_eq!(several.mode, Mode::PickRecipe(0));
+        press(&mut several, "jjj");
+        assert_eq!(several.mode, Mode::PickRecipe(2));
+        assert_eq!(
+            several.handle_key(KeyEvent::from(KeyCode::Enter)),
+            Action::RunRecipe(0, "lint".to_string())
+        );
+        assert_eq!(several.mode, Mode::Normal);
+        assert_eq!(press(&mut several, "o"), Action::OpenPullRequest(0));
+    }
+
+    #[test]
+    fn test_last_runs_prefer_newest() {
+        let result = |repository: &str, exit_code: i32| RepoRunResult {
+            repository: repository.to_string(),
+            exit_code: Some(exit_code),
+            exit_code_description: None,
+            duration_secs: 1.0,
+            log_dir: None,
+            error: None,
+        };
+        let run = |id: &str, results: Vec<RepoRunResult>| RunRecord {
+            id: id.to_string(),
+            dir: PathBuf::from(id),
+            started: None,
+            label: format!("label {}", id),
+            summary: RunSummary::new(results),
+        };
+        let runs = vec![
+            run("new", vec![result("api", 1)]),
+            run("old", vec![result("api", 0), result("web", 0)]),
+        ];
+
+        let mut app = app(&[]);
+        app.set_last_runs(&runs);
+        let api = app.rows[0].last_run.as_ref().unwrap();
+        assert_eq!(api.run_id, "new");
+        assert!(!api.success);
+        assert!(app.rows[1].last_run.as_ref().unwrap().success);
+        assert_eq!(app.rows[2].last_run, None);
+    }
+}
@@ -0,0 +1,229 @@
+//! Full-screen dashboard of the fleet behind `repos ui`
+//!
+//! - [`app`]: Rows, filter, selection and key bindings, without any I/O
+//! - [`view`]: Drawing the state with ratatui
+//!
+//! The status of each working copy is read on background threads, so the
+//! dashboard comes up at once and fills in as results arrive. Pulls run in
+//! the background as well; recipes and pull requests run as `repos run` and
+//! `repos pr` with the dashboard suspended, so their output and prompts look
+//! the same as on the command line.
+
+pub mod app;
+pub mod view;
+
+pub use app::{Action, App, LastRun, Mode, RepoState, Row};
+
+use crate::config::Repository;
+use crate::git;
+use crate::utils::list_runs;
+use anyhow::{Context, Result};
+use ratatui::DefaultTerminal;
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command as ProcessCommand;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Threads reading the status of working copies
+const STATUS_THREADS: usize = 8;
+
+/// How long to wait for a key before looking for background results
+const TICK: Duration = Duration::from_millis(200);
+
+/// A result from a background thread
+enum Update {
+    State(usize, RepoState),
+    Message(String),
+}
+
+/// Where the dashboard finds and sends its work
+#[derive(Debug, Clone)]
+pub struct Dashboard {
+    /// Config files, passed on to `repos run` and `repos pr`
+    pub config_paths: Vec<String>,
+    /// Directory `repos run` saves runs to
+    pub output_dir: PathBuf,
+}
+
+fn read_state(repo: &Repository) -> RepoState {
+    let target_dir = repo.get_target_dir();
+    if !Path::new(&target_dir).exists() {
+        return RepoState::NotCloned;
+    }
+    match git::working_status(&target_dir) {
+        Ok(status) => RepoState::Status(status),
+        Err(e) => RepoState::Error(e.to_string()),
+    }
+}
+
+/// Read the state of the given rows on background threads
+fn load_states(rows: Vec<(usize, Repository)>, updates: &Sender<Update>) {
+    let queue = Arc::new(Mutex::new(rows));
+    for _ in 0..STATUS_THREADS {
+        let queue = Arc::clone(&queue);
+        let updates = updates.clone();
+        std::thread::spawn(move || {
+            loop {
+                let next = queue.lock().ok().and_then(|mut queue| queue.pop());
+                let Some((index, repo)) = next else {
+                    break;
+                };
+                if updates
+                    .send(Update::State(index, read_state(&repo)))
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        });
+    }
+}
+
+impl Dashboard {
+    /// Show the dashboard until the user quits
+    pub fn run(&self, mut app: App) -> Result<()> {
+        let mut terminal = ratatui::init();
+        let result = self.event_loop(&mut terminal, &mut app);
+        ratatui::restore();
+        result
+    }
+
+    fn refresh(&self, app: &mut App, updates: &Sender<Update>) {
+        for row in &mut app.rows {
+            row.state = RepoState::Loading;
+        }
+        let rows = app
+            .rows
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(index, row)| (index, row.repo.clone()))
+            .collect();
+        load_states(rows, updates);
+        self.reload_runs(app);
+    }
+
+    fn reload_runs(&self, app: &mut App) {
+        match list_runs(&self.output_dir) {
+            Ok(runs) => app.set_last_runs(&runs),
+            Err(e) => app.message = Some(format!("Failed to read saved runs: {}", e)),
+        }
+    }
+
+    fn event_loop(&self, terminal: &mut DefaultTerminal, app: &mut App) -> Result<()> {
+        let (sender, updates): (Sender<Update>, Receiver<Update>) = mpsc::channel();
+        self.refresh(app, &sender);
+
+        loop {
+            while let Ok(update) = updates.try_recv() {
+                match update {
+                    Update::State(index, state) => app.rows[index].state = state,
+                    Update::Message(message) => app.message = Some(message),
+                }
+            }
+            terminal.draw(|frame| view::draw(frame, app))?;
+
+            if !event::poll(TICK)? {
+                continue;
+            }
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            app.message = None;
+
+            match app.handle_key(key) {
+                Action::None => {}
+                Action::Quit => return Ok(()),
+                Action::Refresh => self.refresh(app, &sender),
+                Action::Pull(index) => {
+                    let repo = app.rows[index].repo.clone();
+                    app.rows[index].state = RepoState::Loading;
+                    app.message = Some(format!("Pulling {}…", repo.name));
+                    let updates = sender.clone();
+                    std::thread::spawn(move || {
+                        let message = match git::pull(&repo.get_target_dir()) {
+                            Ok(summary) => format!("{}: {}", repo.name, summary),
+                            Err(e) => format!("{}: {}", repo.name, e),
+                        };
+                        let _ = updates.send(Update::Message(message));
+                        let _ = updates.send(Update::State(index, read_state(&repo)));
+                    });
+                }
+                Action::RunRecipe(index, recipe) => {
+                    let name = app.rows[index].repo.name.clone();
+                    let mut args = vec!["run".to_string(), "--recipe".to_string(), recipe];
+                    args.extend(["--output-dir".to_string(), self.output_dir_arg()]);
+                    args.push(name);
+                    *terminal = self.suspended(|| self.run_repos(&args))?;
+                    self.after_action(app, index, &sender);
+                }
+                Action::OpenPullRequest(index) => {
+                    let name = app.rows[index].repo.name.clone();
+                    *terminal = self.suspended(|| {
+                        let title: String = dialoguer::Input::new()
+                            .with_prompt(format!("Pull request title for {}", name))
+                            .default(crate::constants::git::DEFAULT_COMMIT_MSG.to_string())
+                            .interact_text()?;
+                        self.run_repos(&[
+                            "pr".to_string(),
+                            name.clone(),
+                            "--title".to_string(),
+                            title,
+                        ])
+                    })?;
+                    self.after_action(app, index, &sender);
+                }
+            }
+        }
+    }
+
+    fn output_dir_arg(&self) -> String {
+        self.output_dir.to_string_lossy().to_string()
+    }
+
+    /// Reread a repository and the saved runs after an action changed them
+    fn after_action(&self, app: &mut App, index: usize, updates: &Sender<Update>) {
+        app.rows[index].state = RepoState::Loading;
+        load_states(vec![(index, app.rows[index].repo.clone())], updates);
+        self.reload_runs(app);
+    }
+
+    /// Leave the dashboard for `action`, then wait for Enter and come back
+    fn suspended(&self, action: impl FnOnce() -> Result<()>) -> Result<DefaultTerminal> {
+        ratatui::restore();
+        if let Err(e) = action() {
+            eprintln!("Error: {e}");
+        }
+        print!("\nPress Enter to return to the dashboard");
+        std::io::stdout().flush()?;
+        let mut line = String::new();
+        std::io::stdin().read_line(&mut line)?;
+        Ok(ratatui::init())
+    }
+
+    /// Run this `repos` binary with the dashboard's config files
+    fn run_repos(&self, args: &[String]) -> Result<()> {
+        let exe = std::env::current_exe().context("Failed to find the repos executable")?;
+        let mut command = ProcessCommand::new(exe);
+        command.args(&args[..1]);
+        for path in &self.config_paths {
+            command.args(["--config", path]);
+        }
+        command.args(&args[1..]);
+        let status = command.status().context("Failed to start repos")?;
+        if !status.success() {
+            anyhow::bail!(
+                "repos {} failed with exit code {}",
+                args[0],
+                status.code().unwrap_or(-1)
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,196 @@
+//! Drawing the dashboard
+
+use super::app::{App, Mode, RepoState, Row};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{
+    Block, Borders, Cell, Clear, List, ListItem, ListState, Paragraph, Row as TableRow, Table,
+    TableState,
+};
+
+const KEYS: &str = "↑↓ select  / filter  p pull  r run recipe  o open PR  R refresh  q quit";
+
+pub fn draw(frame: &mut Frame, app: &App) {
+    let [title, table, details, status] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Min(3),
+        Constraint::Length(7),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    draw_title(frame, app, title);
+    draw_table(frame, app, table);
+    draw_details(frame, app, details);
+    draw_status(frame, app, status);
+    if let Mode::PickRecipe(selected) = app.mode {
+        draw_recipe_picker(frame, app, selected);
+    }
+}
+
+fn draw_title(frame: &mut Frame, app: &App, area: Rect) {
+    let visible = app.visible().len();
+    let mut spans = vec![
+        Span::styled(" repos ", Style::new().bold().reversed()),
+        Span::raw(format!(" {} of {} repositories", visible, app.rows.len())),
+    ];
+    if app.mode == Mode::Filter || !app.filter.is_empty() {
+        spans.push(Span::raw("  filter: "));
+        spans.push(Span::styled(app.filter.clone(), Style::new().yellow()));
+        if app.mode == Mode::Filter {
+            spans.push(Span::styled("▏", Style::new().yellow()));
+        }
+    }
+    frame.render_widget(Line::from(spans), area);
+}
+
+fn state_cells(row: &Row) -> (Cell<'static>, Cell<'static>) {
+    match &row.state {
+        RepoState::Loading => (Cell::from(""), Cell::from("…").dark_gray()),
+        RepoState::NotCloned => (Cell::from(""), Cell::from("not cloned").dark_gray()),
+        RepoState::Error(e) => (Cell::from(""), Cell::from(e.clone()).red()),
+        RepoState::Status(status) => {
+            let branch = status
+                .branch
+                .clone()
+                .unwrap_or_else(|| "(detached)".to_string());
+            let color = if status.conflicted > 0 {
+                Color::Red
+            } else if !status.is_clean() {
+                Color::Yellow
+            } else {
+                Color::Green
+            };
+            (
+                Cell::from(branch).cyan(),
+                Cell::from(status.describe()).fg(color),
+            )
+        }
+    }
+}
+
+fn last_run_cell(row: &Row) -> Cell<'static> {
+    match &row.last_run {
+        None => Cell::from("-").dark_gray(),
+        Some(run) if run.success => Cell::from(format!("✓ {}", run.label)).green(),
+        Some(run) => Cell::from(format!(
+            "✗ {} (exit {})",
+            run.label,
+            run.exit_code
+                .map(|code| code.to_string())
+                .unwrap_or_else(|| "?".to_string())
+        ))
+        .red(),
+    }
+}
+
+fn draw_table(frame: &mut Frame, app: &App, area: Rect) {
+    let rows: Vec<TableRow> = app
+        .visible()
+        .into_iter()
+        .map(|index| {
+            let row = &app.rows[index];
+            let (branch, status) = state_cells(row);
+            TableRow::new(vec![
+                Cell::from(row.repo.name.clone()).bold(),
+                branch,
+                status,
+                last_run_cell(row),
+            ])
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Percentage(25),
+            Constraint::Percentage(20),
+            Constraint::Percentage(30),
+            Constraint::Percentage(25),
+        ],
+    )
+    .header(
+        TableRow::new(vec!["Repository", "Branch", "Status", "Last run"])
+            .style(Style::new().add_modifier(Modifier::UNDERLINED)),
+    )
+    .block(Block::new().borders(Borders::TOP | Borders::BOTTOM))
+    .row_highlight_style(Style::new().reversed())
+    .highlight_symbol("▶ ");
+
+    let mut state = TableState::default().with_selected(app.selected_row().map(|_| app.selected));
+    frame.render_stateful_widget(table, area, &mut state);
+}
+
+fn draw_details(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(row) = app.selected_row().map(|index| &app.rows[index]) else {
+        frame.render_widget(Paragraph::new("No repositories match the filter"), area);
+        return;
+    };
+
+    let field = |name: &str, value: String| {
+        Line::from(vec![
+            Span::styled(format!("{:<9}", name), Style::new().dark_gray()),
+            Span::raw(value),
+        ])
+    };
+    let mut lines = vec![
+        field("URL", row.repo.url.clone()),
+        field("Path", row.repo.get_target_dir()),
+        field("Tags", row.repo.tags.join(", ")),
+    ];
+    if let Some(run) = &row.last_run {
+        let started = run
+            .started
+            .map(|started| started.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default();
+        lines.push(field("Last run", format!("{} {}", run.run_id, started)));
+        if let Some(log_dir) = &run.log_dir {
+            lines.push(field("Logs", log_dir.display().to_string()));
+        }
+    }
+    let title = format!(" {} ", row.repo.name);
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::new().borders(Borders::BOTTOM).title(title)),
+        area,
+    );
+}
+
+fn draw_status(frame: &mut Frame, app: &App, area: Rect) {
+    let line = match (&app.mode, &app.message) {
+        (Mode::Filter, _) => Line::from("Type to filter by name or tag  Enter keep  Esc clear"),
+        (Mode::PickRecipe(_), _) => Line::from("↑↓ select  Enter run  Esc cancel"),
+        (Mode::Normal, Some(message)) => Line::from(message.clone()).yellow(),
+        (Mode::Normal, None) => Line::from(KEYS).dark_gray(),
+    };
+    frame.render_widget(line, area);
+}
+
+fn draw_recipe_picker(frame: &mut Frame, app: &App, selected: usize) {
+    let height = (app.recipes.len() as u16 + 2).min(frame.area().height);
+    let [_, area, _] = Layout::vertical([
+        Constraint::Fill(1),
+        Constraint::Length(height),
+        Constraint::Fill(1),
+    ])
+    .areas(frame.area());
+    let [_, area, _] = Layout::horizontal([
+        Constraint::Fill(1),
+        Constraint::Length(40),
+        Constraint::Fill(1),
+    ])
+    .areas(area);
+
+    let items: Vec<ListItem> = app
+        .recipes
+        .iter()
+        .map(|recipe| ListItem::new(recipe.clone()))
+        .collect();
+    let list = List::new(items)
+        .block(Block::bordered().title(" Run recipe "))
+        .highlight_style(Style::new().reversed());
+    let mut state = ListState::default().with_selected(Some(selected));
+    frame.render_widget(Clear, area);
+    frame.render_stateful_widget(list, area, &mut state);
+}
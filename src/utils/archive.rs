@@ -0,0 +1,234 @@
+//! Compressed archives of repository directories
+
+use anyhow::{Context, Result};
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use std::fs::File;
+use std::path::Path;
+use std::str::FromStr;
+use walkdir::WalkDir;
+
+/// Supported archive formats
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    #[default]
+    TarGz,
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// File extension without the leading dot
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ArchiveFormat::TarGz => "tar.gz",
+            ArchiveFormat::Zip => "zip",
+        }
+    }
+}
+
+impl FromStr for ArchiveFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "tar.gz" | "tgz" => Ok(ArchiveFormat::TarGz),
+            "zip" => Ok(ArchiveFormat::Zip),
+            _ => anyhow::bail!("Unknown archive format '{}' (expected tar.gz or zip)", s),
+        }
+    }
+}
+
+/// Archive `source` into `destination`, storing entries under `root_name/`
+///
+/// Entries whose path relative to `source` matches one of `exclude` are skipped,
+/// together with everything below them. Symlinks are stored as links. Returns
+/// the number of files written.
+pub fn create_archive(
+    source: &Path,
+    destination: &Path,
+    format: ArchiveFormat,
+    exclude: &[glob::Pattern],
+    root_name: &str,
+) -> Result<usize> {
+    let file = File::create(destination)
+        .with_context(|| format!("Failed to create {}", destination.display()))?;
+
+    let entries = WalkDir::new(source)
+        .min_depth(1)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| {
+            let relative = entry.path().strip_prefix(source).unwrap_or(entry.path());
+            !exclude.iter().any(|pattern| pattern.matches_path(relative))
+        });
+
+    let mut files = 0;
+    match format {
+        ArchiveFormat::TarGz => {
+            let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+            builder.follow_symlinks(false);
+            for entry in entries {
+                let entry = entry?;
+                let name = Path::new(root_name).join(entry.path().strip_prefix(source)?);
+                builder.append_path_with_name(entry.path(), &name)?;
+                if !entry.file_type().is_dir() {
+                    files += 1;
+                }
+            }
+            builder.into_inner()?.finish()?;
+        }
+        ArchiveFormat::Zip => {
+            let mut writer = zip::ZipWriter::new(file);
+            for entry in entries {
+                let entry = entry?;
+                let relative = entry.path().strip_prefix(source)?;
+                // Zip entry names always use forward slashes
+                let name = format!(
+                    "{}/{}",
+                    root_name,
+                    relative.to_string_lossy().replace('\\', "/")
+                );
+                let options = zip::write::SimpleFileOptions::default()
+                    .compression_method(zip::CompressionMethod::Deflated)
+                    .unix_permissions(unix_mode(entry.path()));
+
+                if entry.file_type().is_dir() {
+                    writer.add_directory(name, options)?;
+                } else if entry.file_type().is_symlink() {
+                    let target = std::fs::read_link(entry.path())?;
+                    writer.add_symlink(name, target.to_string_lossy(), options)?;
+                    files += 1;
+                } else {
+                    writer.start_file(name, options)?;
+                    std::io::copy(&mut File::open(entry.path())?, &mut writer)?;
+                    files += 1;
+                }
+            }
+            writer.finish()?;
+        }
+    }
+
+    Ok(files)
+}
+
+#[cfg(unix)]
+fn unix_mode(path: &Path) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::symlink_metadata(path)
+        .map(|metadata| metadata.permissions().mode() & 0o777)
+        .unwrap_or(0o644)
+}
+
+#[cfg(not(unix))]
+fn unix_mode(path: &Path) -> u32 {
+    if path.is_dir() { 0o755 } else { 0o644 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use tempfile::TempDir;
+
+    fn sample_repository(dir: &Path) {
+        std::fs::create_dir_all(dir.join(".git/objects")).unwrap();
+        std::fs::write(dir.join(".git/HEAD"), "ref: refs/heads/main\n").unwrap();
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(dir.join("src/main.rs"), "fn main() {}\n").unwrap();
+        std::fs::create_dir_all(dir.join("target/debug")).unwrap();
+        std::fs::write(dir.join("target/debug/app"), "binary").unwrap();
+        std::fs::write(dir.join("README.md"), "# api\n").unwrap();
+    }
+
+    fn patterns(globs: &[&str]) -> Vec<glob::Pattern> {
+        globs
+            .iter()
+            .map(|g| glob::Pattern::new(g).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_archive_format_parsing() {
+        assert_eq!(
+            "tar.gz".parse::<ArchiveFormat>().unwrap(),
+            ArchiveFormat::TarGz
+        );
+        assert_eq!(
+            "tgz".parse::<ArchiveFormat>().unwrap(),
+            ArchiveFormat::TarGz
+        );
+        assert_eq!("zip".parse::<ArchiveFormat>().unwrap(), ArchiveFormat::Zip);
+        assert!("rar".parse::<ArchiveFormat>().is_err());
+        assert_eq!(ArchiveFormat::Zip.extension(), "zip");
+    }
+
+    #[test]
+    fn test_tar_gz_archive_with_excludes() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("api");
+        sample_repository(&source);
+        let destination = temp_dir.path().join("api.tar.gz");
+
+        let files = create_archive(
+            &source,
+            &destination,
+            ArchiveFormat::TarGz,
+            &patterns(&[".git", "target"]),
+            "api",
+        )
+        .unwrap();
+        assert_eq!(files, 2);
+
+        let mut archive = tar::Archive::new(GzDecoder::new(File::open(&destination).unwrap()));
+        let mut names: Vec<String> = archive
+            .entries()
+            .unwrap()
+            .map(|entry| entry.unwrap().path().unwrap().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        assert_eq!(names, ["api/README.md", "api/src", "api/src/main.rs"]);
+    }
+
+    #[test]
+    fn test_zip_archive() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("api");
+        sample_repository(&source);
+        let destination = temp_dir.path().join("api.zip");
+
+        let files = create_archive(
+            &source,
+            &destination,
+            ArchiveFormat::Zip,
+            &patterns(&["target/*"]),
+            "api",
+        )
+        .unwrap();
+        // .git is kept unless excluded; target/ stays as an empty directory
+        assert_eq!(files, 3);
+
+        let mut archive = zip::ZipArchive::new(File::open(&destination).unwrap()).unwrap();
+        let mut names: Vec<String> = archive.file_names().map(str::to_string).collect();
+        names.sort();
+        assert_eq!(
+            names,
+            [
+                "api/.git/",
+                "api/.git/HEAD",
+                "api/.git/objects/",
+                "api/README.md",
+                "api/src/",
+                "api/src/main.rs",
+                "api/target/",
+            ]
+        );
+
+        let mut content = String::new();
+        std::io::Read::read_to_string(
+            &mut archive.by_name("api/src/main.rs").unwrap(),
+            &mut content,
+        )
+        .unwrap();
+        assert_eq!(content, "fn main() {}\n");
+    }
+}
@@ -0,0 +1,168 @@
+//! Artifact collection utilities for gathering build outputs after runs
+
+use anyhow::{Context, Result};
+use std::path::{Component, Path, PathBuf};
+
+/// Name of the per-repository subdirectory that holds collected artifacts
+pub const ARTIFACTS_DIR: &str = "artifacts";
+
+/// Whether a pattern stays inside the repository: relative and without `..` components
+pub fn is_contained_pattern(pattern: &str) -> bool {
+    Path::new(pattern)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+}
+
+/// Copy files matching the given glob patterns from a repository into a destination directory
+///
+/// Patterns are resolved relative to `repo_dir` and the relative layout of each
+/// matched file is preserved under `dest_dir`. A trailing `**` collects every
+/// file below that directory (`dist/**`). Returns the relative paths of the
+/// collected files. Patterns that are absolute or climb out of `repo_dir` with
+/// `..` are rejected, so nothing is written outside `dest_dir`.
+pub fn collect_artifacts(
+    repo_dir: &Path,
+    patterns: &[String],
+    dest_dir: &Path,
+) -> Result<Vec<PathBuf>> {
+    let mut collected = Vec::new();
+
+    for pattern in patterns {
+        if !is_contained_pattern(pattern) {
+            anyhow::bail!(
+                "Artifact pattern '{}' must be a relative path inside the repository",
+                pattern
+            );
+        }
+
+        // `dir/**` only matches directories in glob syntax; extend it to their files
+        let expanded = if pattern == "**" || pattern.ends_with("/**") {
+            format!("{}/*", pattern)
+        } else {
+            pattern.clone()
+        };
+        let full_pattern = repo_dir.join(expanded);
+        let entries = glob::glob(&full_pattern.to_string_lossy())
+            .with_context(|| format!("Invalid artifact pattern '{}'", pattern))?;
+
+        for entry in entries.filter_map(|e| e.ok()) {
+            if !entry.is_file() {
+                continue;
+            }
+
+            let Ok(relative) = entry.strip_prefix(repo_dir).map(Path::to_path_buf) else {
+                anyhow::bail!("Artifact '{}' is outside the repository", entry.display());
+            };
+            if collected.contains(&relative) {
+                continue;
+            }
+
+            let target = dest_dir.join(&relative);
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::copy(&entry, &target)
+                .with_context(|| format!("Failed to copy artifact '{}'", relative.display()))?;
+
+            collected.push(relative);
+        }
+    }
+
+    collected.sort();
+    Ok(collected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_collect_artifacts_preserves_layout() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_dir = temp_dir.path().join("repo");
+        fs::create_dir_all(repo_dir.join("dist/js")).unwrap();
+        fs::write(repo_dir.join("dist/index.html"), "<html/>").unwrap();
+        fs::write(repo_dir.join("dist/js/app.js"), "app").unwrap();
+        fs::write(repo_dir.join("coverage.xml"), "<coverage/>").unwrap();
+        fs::write(repo_dir.join("README.md"), "readme").unwrap();
+
+        let dest = temp_dir.path().join("out");
+        let collected = collect_artifacts(
+            &repo_dir,
+            &["dist/**".to_string(), "coverage.xml".to_string()],
+            &dest,
+        )
+        .unwrap();
+
+        assert_eq!(
+            collected,
+            vec![
+                PathBuf::from("coverage.xml"),
+                PathBuf::from("dist/index.html"),
+                PathBuf::from("dist/js/app.js"),
+            ]
+        );
+        assert!(dest.join("dist/js/app.js").exists());
+        assert!(dest.join("coverage.xml").exists());
+        assert!(!dest.join("README.md").exists());
+    }
+
+    #[test]
+    fn test_collect_artifacts_no_matches() {
+        let temp_dir = TempDir::new().unwrap();
+        let dest = temp_dir.path().join("out");
+
+        let collected =
+            collect_artifacts(temp_dir.path(), &["missing/**".to_string()], &dest).unwrap();
+
+        assert!(collected.is_empty());
+        assert!(!dest.exists());
+    }
+
+    #[test]
+    fn test_collect_artifacts_deduplicates_overlapping_patterns() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_dir = temp_dir.path().join("repo");
+        fs::create_dir_all(&repo_dir).unwrap();
+        fs::write(repo_dir.join("report.xml"), "report").unwrap();
+
+        let dest = temp_dir.path().join("out");
+        let collected = collect_artifacts(
+            &repo_dir,
+            &["*.xml".to_string(), "report.xml".to_string()],
+            &dest,
+        )
+        .unwrap();
+
+        assert_eq!(collected, vec![PathBuf::from("report.xml")]);
+    }
+
+    #[test]
+    fn test_collect_artifacts_rejects_patterns_outside_repository() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_dir = temp_dir.path().join("repo");
+        fs::create_dir_all(&repo_dir).unwrap();
+        let source = repo_dir.join("report.xml");
+        fs::write(&source, "report").unwrap();
+
+        let dest = temp_dir.path().join("out");
+        let absolute = source.to_string_lossy().to_string();
+        assert!(collect_artifacts(&repo_dir, &[absolute], &dest).is_err());
+        assert_eq!(fs::read_to_string(&source).unwrap(), "report");
+
+        let escaping = "../repo/report.xml".to_string();
+        assert!(collect_artifacts(&repo_dir, &[escaping], &dest).is_err());
+        assert!(!dest.exists());
+    }
+
+    #[test]
+    fn test_is_contained_pattern() {
+        assert!(is_contained_pattern("dist/**"));
+        assert!(is_contained_pattern("./coverage.xml"));
+        assert!(!is_contained_pattern("/etc/passwd"));
+        assert!(!is_contained_pattern("../sibling/*.log"));
+        assert!(!is_contained_pattern("dist/../../out"));
+    }
+}
@@ -0,0 +1,289 @@
+//! Outcomes of an operation across many repositories
+//!
+//! `run`, `clone` and `pr` collect the result of every repository into a
+//! [`BatchResult`], whether the repositories were processed one after another
+//! or in parallel, and decide with a [`FailurePolicy`] whether the batch as a
+//! whole failed.
+
+use super::manifest::ManifestEntry;
+use super::notify::BatchReport;
+use crate::logging;
+use anyhow::Result;
+use colored::*;
+use log::Level;
+use std::time::Duration;
+
+/// How failing repositories affect a batch
+///
+/// Without limits any failure fails the batch. Sequential runs stop as soon as
+/// the batch failed and parallel ones run every repository, unless
+/// `keep_going` or `fail_fast` say otherwise. Repositories that already
+/// started always finish.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FailurePolicy {
+    /// Start no further repositories once the batch failed, also with --parallel
+    pub fail_fast: bool,
+    /// Run every repository, also without --parallel
+    pub keep_going: bool,
+    /// Number of failed repositories tolerated
+    pub max_failures: Option<usize>,
+    /// Percentage of failed repositories tolerated
+    pub failure_threshold: Option<f64>,
+}
+
+impl FailurePolicy {
+    /// The failures tolerated by `--max-failures` and `--failure-threshold`
+    pub fn from_limits(max_failures: Option<usize>, threshold: Option<&str>) -> Result<Self> {
+        Ok(Self {
+            max_failures,
+            failure_threshold: threshold.map(Self::parse_threshold).transpose()?,
+            ..Default::default()
+        })
+    }
+
+    /// Parse a threshold such as `20%` or `20` into a percentage
+    pub fn parse_threshold(value: &str) -> Result<f64> {
+        let number = value.trim().trim_end_matches('%').trim();
+        match number.parse::<f64>() {
+            Ok(percent) if (0.0..=100.0).contains(&percent) => Ok(percent),
+            _ => anyhow::bail!(
+                "Invalid failure threshold '{}' (expected a percentage such as 20%)",
+                value
+            ),
+        }
+    }
+
+    /// Whether `failed` out of `total` repositories fail the batch
+    pub fn exceeded(&self, failed: usize, total: usize) -> bool {
+        if failed == 0 {
+            return false;
+        }
+        if self.max_failures.is_none() && self.failure_threshold.is_none() {
+            return true;
+        }
+        self.max_failures.is_some_and(|max| failed > max)
+            || self
+                .failure_threshold
+                .is_some_and(|percent| failed as f64 * 100.0 > percent * total as f64)
+    }
+
+    /// Whether a batch stops once it failed
+    pub(crate) fn stops_early(&self, parallel: bool) -> bool {
+        self.fail_fast || (!parallel && !self.keep_going)
+    }
+
+    /// The limits that were exceeded, for the final error
+    fn describe(&self) -> String {
+        let mut limits = Vec::new();
+        if let Some(max) = self.max_failures {
+            limits.push(format!("at most {} tolerated", max));
+        }
+        if let Some(percent) = self.failure_threshold {
+            limits.push(format!("at most {}% tolerated", percent));
+        }
+        if limits.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", limits.join(", "))
+        }
+    }
+}
+
+/// Outcome of an operation in one repository
+#[derive(Debug)]
+pub struct RepoOutcome {
+    pub repository: String,
+    pub result: Result<()>,
+    pub duration: Option<Duration>,
+}
+
+/// Outcomes of an operation in every repository it was started for
+#[derive(Debug)]
+pub struct BatchResult {
+    /// Name of the operation, e.g. `clone`
+    pub operation: String,
+    /// Number of repositories the operation was started for
+    pub total: usize,
+    pub outcomes: Vec<RepoOutcome>,
+}
+
+impl BatchResult {
+    pub fn new(operation: impl Into<String>, total: usize) -> Self {
+        Self {
+            operation: operation.into(),
+            total,
+            outcomes: Vec::new(),
+        }
+    }
+
+    /// Record the outcome in one repository
+    pub fn push(&mut self, repository: &str, result: Result<()>, duration: Option<Duration>) {
+        self.outcomes.push(RepoOutcome {
+            repository: repository.to_string(),
+            result,
+            duration,
+        });
+    }
+
+    pub fn succeeded(&self) -> usize {
+        self.outcomes.len() - self.failed()
+    }
+
+    pub fn failed(&self) -> usize {
+        self.failures().count()
+    }
+
+    /// Repositories that failed, with their errors
+    pub fn failures(&self) -> impl Iterator<Item = (&str, &anyhow::Error)> {
+        self.outcomes.iter().filter_map(|outcome| {
+            outcome
+                .result
+                .as_ref()
+                .err()
+                .map(|e| (outcome.repository.as_str(), e))
+        })
+    }
+
+    /// The summary notifications are sent with
+    pub fn report(&self) -> BatchReport {
+        let failures = self
+            .failures()
+            .map(|(repository, _)| repository.to_string())
+            .collect();
+        BatchReport::new(self.operation.clone(), self.succeeded(), failures)
+    }
+
+    /// One manifest entry per repository
+    pub fn manifest_entries(&self) -> Vec<ManifestEntry> {
+        self.outcomes
+            .iter()
+            .map(|outcome| {
+                let entry = match &outcome.result {
+                    Ok(()) => ManifestEntry::succeeded(&outcome.repository),
+                    Err(e) => ManifestEntry::failed(&outcome.repository, e),
+                };
+                match outcome.duration {
+                    Some(duration) => entry.with_duration(duration),
+                    None => entry,
+                }
+            })
+            .collect()
+    }
+
+    /// Print every failed repository with its error
+    pub fn print_errors(&self) {
+        if self.failed() == 0 {
+            return;
+        }
+        logging::message(
+            Level::Error,
+            format!("{} of {} repositories failed:", self.failed(), self.total).red(),
+        );
+        let width = self
+            .failures()
+            .map(|(repository, _)| repository.len())
+            .max()
+            .unwrap_or(0);
+        for (repository, e) in self.failures() {
+            logging::message(
+                Level::Error,
+                format!("  {:<width$}  {:#}", repository.cyan(), e),
+            );
+        }
+    }
+
+    /// Fail when `policy` does not tolerate the failed repositories
+    ///
+    /// A single repository fails with its own error; otherwise the error
+    /// counts the failures, which [`print_errors`](Self::print_errors) lists.
+    pub fn into_result(self, policy: &FailurePolicy) -> Result<()> {
+        let failed = self.failed();
+        if !policy.exceeded(failed, self.total) {
+            if failed > 0 {
+                logging::message(
+                    Level::Warn,
+                    format!(
+                        "{} of {} repositories failed, within the tolerated limit",
+                        failed, self.total
+                    )
+                    .yellow(),
+                );
+            }
+            return Ok(());
+        }
+        if self.total == 1
+            && let Some(Err(e)) = self
+                .outcomes
+                .into_iter()
+                .map(|outcome| outcome.result)
+                .next()
+        {
+            return Err(e);
+        }
+        anyhow::bail!(
+            "{} of {} repositories failed{}",
+            failed,
+            self.total,
+            policy.describe()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failure_policy_limits() {
+        let strict = FailurePolicy::default();
+        assert!(!strict.exceeded(0, 10));
+        assert!(strict.exceeded(1, 10));
+        assert!(strict.stops_early(false));
+        assert!(!strict.stops_early(true));
+
+        let tolerant = FailurePolicy {
+            max_failures: Some(2),
+            failure_threshold: Some(FailurePolicy::parse_threshold("20%").unwrap()),
+            ..Default::default()
+        };
+        assert!(!tolerant.exceeded(2, 10));
+        assert!(tolerant.exceeded(3, 10));
+        // Either limit fails the batch
+        assert!(tolerant.exceeded(2, 5));
+
+        assert_eq!(FailurePolicy::parse_threshold("12.5").unwrap(), 12.5);
+        assert!(FailurePolicy::parse_threshold("150%").is_err());
+        assert!(FailurePolicy::parse_threshold("many").is_err());
+    }
+
+    #[test]
+    fn test_batch_result_follows_policy() {
+        let mut batch = BatchResult::new("clone", 3);
+        batch.push("api", Ok(()), Some(Duration::from_secs(1)));
+        batch.push("web", Err(anyhow::anyhow!("Authentication failed")), None);
+        batch.push("db", Ok(()), None);
+
+        assert_eq!((batch.succeeded(), batch.failed()), (2, 1));
+        let report = batch.report();
+        assert_eq!(report.failures, vec!["web".to_string()]);
+        let entries = batch.manifest_entries();
+        assert_eq!(entries[1].error.as_deref(), Some("Authentication failed"));
+
+        let error = batch.into_result(&FailurePolicy::default()).unwrap_err();
+        assert_eq!(error.to_string(), "1 of 3 repositories failed");
+
+        let mut batch = BatchResult::new("clone", 3);
+        batch.push("web", Err(anyhow::anyhow!("Authentication failed")), None);
+        let tolerant = FailurePolicy {
+            max_failures: Some(1),
+            ..Default::default()
+        };
+        assert!(batch.into_result(&tolerant).is_ok());
+
+        // A single repository fails with its own error
+        let mut batch = BatchResult::new("clone", 1);
+        batch.push("web", Err(anyhow::anyhow!("Authentication failed")), None);
+        let error = batch.into_result(&FailurePolicy::default()).unwrap_err();
+        assert_eq!(error.to_string(), "Authentication failed");
+    }
+}
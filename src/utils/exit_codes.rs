@@ -1,5 +1,8 @@
 //! Exit code utilities and mappings
 
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
 /// Get a human-readable description for an exit code
 pub fn get_exit_code_description(exit_code: i32) -> &'static str {
     match exit_code {
@@ -15,6 +18,38 @@ pub fn get_exit_code_description(exit_code: i32) -> &'static str {
     }
 }
 
+/// Exit code descriptions from the `exit_codes` config section
+///
+/// Codes that are not configured fall back to [`get_exit_code_description`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ExitCodeDescriptions(BTreeMap<i32, String>);
+
+impl ExitCodeDescriptions {
+    pub fn new(descriptions: BTreeMap<i32, String>) -> Self {
+        Self(descriptions)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Add descriptions from `other` for codes that are not described yet
+    pub fn merge(&mut self, other: ExitCodeDescriptions) {
+        for (code, description) in other.0 {
+            self.0.entry(code).or_insert(description);
+        }
+    }
+
+    /// Description of an exit code, preferring the configured one
+    pub fn describe(&self, exit_code: i32) -> &str {
+        self.0
+            .get(&exit_code)
+            .map(String::as_str)
+            .unwrap_or_else(|| get_exit_code_description(exit_code))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -40,4 +75,15 @@ mod tests {
         assert_eq!(get_exit_code_description(42), "error");
         assert_eq!(get_exit_code_description(-1), "error");
     }
+
+    #[test]
+    fn test_exit_code_descriptions() {
+        let descriptions: ExitCodeDescriptions =
+            serde_yaml::from_str("42: database migration pending\n1: lint failed\n").unwrap();
+        assert_eq!(descriptions.describe(42), "database migration pending");
+        // Configured descriptions take precedence over the built-in ones
+        assert_eq!(descriptions.describe(1), "lint failed");
+        assert_eq!(descriptions.describe(127), "command not found");
+        assert_eq!(ExitCodeDescriptions::default().describe(42), "error");
+    }
 }
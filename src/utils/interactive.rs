@@ -0,0 +1,145 @@
+//! Interactive repository selection and prompts for terminal sessions
+
+use crate::config::Repository;
+use anyhow::Result;
+use dialoguer::theme::ColorfulTheme;
+use dialoguer::{Confirm, FuzzySelect, Input, MultiSelect, Select};
+use std::io::IsTerminal;
+
+/// Maximum number of rows shown by the pickers before scrolling
+const MAX_VISIBLE_ITEMS: usize = 20;
+
+/// Let the user pick any number of repositories from a multi-select list
+///
+/// Returns an empty list when the selection is cancelled.
+pub fn select_repositories(repos: &[Repository]) -> Result<Vec<Repository>> {
+    select_repositories_with_prompt(repos, "Select repositories")
+}
+
+/// Like [`select_repositories`], with a custom prompt
+pub fn select_repositories_with_prompt(
+    repos: &[Repository],
+    prompt: &str,
+) -> Result<Vec<Repository>> {
+    ensure_terminal()?;
+    if repos.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let labels: Vec<String> = repos.iter().map(repository_label).collect();
+    let selection = MultiSelect::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!("{} (space to toggle, enter to confirm)", prompt))
+        .items(&labels)
+        .max_length(MAX_VISIBLE_ITEMS)
+        .interact_opt()?
+        .unwrap_or_default();
+
+    Ok(selection.into_iter().map(|i| repos[i].clone()).collect())
+}
+
+/// Let the user pick a single repository with fuzzy search
+///
+/// Returns `None` when the selection is cancelled.
+pub fn select_repository(repos: &[Repository]) -> Result<Option<Repository>> {
+    ensure_terminal()?;
+    if repos.is_empty() {
+        return Ok(None);
+    }
+
+    let labels: Vec<String> = repos.iter().map(repository_label).collect();
+    let selection = FuzzySelect::with_theme(&ColorfulTheme::default())
+        .with_prompt("Select a repository")
+        .items(&labels)
+        .max_length(MAX_VISIBLE_ITEMS)
+        .interact_opt()?;
+
+    Ok(selection.map(|i| repos[i].clone()))
+}
+
+/// Ask a yes/no question, defaulting to no
+pub fn confirm(prompt: &str) -> Result<bool> {
+    ensure_terminal()?;
+    Ok(Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .default(false)
+        .interact_opt()?
+        .unwrap_or(false))
+}
+
+/// Pick one of `items`, starting at `default`
+///
+/// Returns `None` when the prompt is cancelled.
+pub fn choose(prompt: &str, items: &[&str], default: usize) -> Result<Option<usize>> {
+    ensure_terminal()?;
+    Ok(Select::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .items(items)
+        .default(default)
+        .interact_opt()?)
+}
+
+/// Ask for a line of text, which may be left empty, prefilled with `default`
+pub fn input(prompt: &str, default: &str) -> Result<String> {
+    ensure_terminal()?;
+    Ok(Input::<String>::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .with_initial_text(default)
+        .allow_empty(true)
+        .interact_text()?
+        .trim()
+        .to_string())
+}
+
+/// Whether prompts can be shown
+pub fn is_terminal() -> bool {
+    std::io::stdin().is_terminal() && std::io::stderr().is_terminal()
+}
+
+fn ensure_terminal() -> Result<()> {
+    if !is_terminal() {
+        anyhow::bail!("Interactive selection requires a terminal");
+    }
+    Ok(())
+}
+
+/// Label shown for a repository in the pickers: name, tags, and local path
+fn repository_label(repo: &Repository) -> String {
+    let mut label = repo.name.clone();
+    if !repo.tags.is_empty() {
+        label.push_str(&format!(" [{}]", repo.tags.join(", ")));
+    }
+    label.push_str(&format!("  {}", repo.get_target_dir()));
+    label
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repository_label() {
+        let mut repo = Repository::new(
+            "api".to_string(),
+            "https://github.com/org/api.git".to_string(),
+        );
+        repo.tags = vec!["backend".to_string(), "rust".to_string()];
+        repo.path = Some("/work/api".to_string());
+
+        assert_eq!(repository_label(&repo), "api [backend, rust]  /work/api");
+    }
+
+    #[test]
+    fn test_selection_requires_terminal() {
+        // Only meaningful when tests are not attached to a terminal
+        if std::io::stdin().is_terminal() {
+            return;
+        }
+        let repo = Repository::new(
+            "api".to_string(),
+            "https://github.com/org/api.git".to_string(),
+        );
+
+        assert!(select_repositories(std::slice::from_ref(&repo)).is_err());
+        assert!(select_repository(&[repo]).is_err());
+    }
+}
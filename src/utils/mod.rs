@@ -1,19 +1,40 @@
 //! Utility modules for common functionality
 
+pub mod archive;
+pub mod artifacts;
+pub mod batch;
 pub mod exit_codes;
 pub mod filesystem;
 pub mod filters;
+pub mod interactive;
+pub mod manifest;
+pub mod notify;
+pub mod output_sink;
+pub mod redact;
 pub mod repository_discovery;
+pub mod repository_list;
+pub mod run_history;
+pub mod run_summary;
 pub mod sanitizers;
+pub mod upload;
 pub mod validators;
 
 // Re-export commonly used functions
-pub use exit_codes::get_exit_code_description;
+pub use artifacts::collect_artifacts;
+pub use batch::{BatchResult, FailurePolicy};
+pub use exit_codes::{ExitCodeDescriptions, get_exit_code_description};
 pub use filesystem::ensure_directory_exists;
 pub use filters::{filter_by_names, filter_by_tag, filter_repositories};
+pub use manifest::{Manifest, ManifestEntry};
+pub use notify::{BatchReport, NotifyConfig};
+pub use redact::Redactor;
 pub use repository_discovery::{
-    create_repository_from_path, detect_tags_from_path, find_git_repositories, get_remote_url,
+    config_path_for, create_repository_from_path, detect_frameworks, detect_languages,
+    detect_tags_from_path, find_git_repositories, get_remote_url,
 };
+pub use repository_list::{describe_source, read_repository_list};
+pub use run_history::{RunRecord, find_run, list_runs};
+pub use run_summary::{RepoRunResult, RunSummary};
 pub use sanitizers::{sanitize_for_filename, sanitize_script_name};
 pub use validators::{
     ValidationError, validate_config, validate_recipe, validate_repositories, validate_repository,
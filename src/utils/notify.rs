@@ -0,0 +1,245 @@
+//! Notifications posted when clone, run, and pull request batches finish
+//!
+//! Slack receives a one-line summary through an incoming webhook; generic
+//! webhooks receive the same summary along with the full report as JSON.
+
+use super::upload::expand_env;
+use crate::events;
+use crate::logging;
+use anyhow::{Context, Result};
+use colored::*;
+use log::Level;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Outcome of a batch that triggers a notification
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotifyEvent {
+    /// At least one repository failed
+    Failure,
+    /// The batch finished, whether or not anything failed
+    Completion,
+}
+
+/// Notification settings, configured via `notify:`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NotifyConfig {
+    /// Slack incoming webhook URL; `${VAR}` is replaced from the environment
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slack_webhook: Option<String>,
+    /// Endpoints receiving the batch report as a JSON `POST`; `${VAR}` in URLs is replaced too
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub webhooks: Vec<Webhook>,
+    /// Events that trigger a notification
+    #[serde(default = "default_events")]
+    pub on: Vec<NotifyEvent>,
+}
+
+fn default_events() -> Vec<NotifyEvent> {
+    vec![NotifyEvent::Completion]
+}
+
+/// A generic webhook endpoint
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Webhook {
+    pub url: String,
+    /// Extra request headers; `${VAR}` in values is replaced from the environment
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+}
+
+impl NotifyConfig {
+    /// Whether a batch with this outcome should be reported
+    pub fn should_notify(&self, report: &BatchReport) -> bool {
+        self.on.iter().any(|event| match event {
+            NotifyEvent::Completion => true,
+            NotifyEvent::Failure => report.failed > 0,
+        })
+    }
+
+    /// Post the report to every configured endpoint
+    pub async fn send(&self, report: &BatchReport) -> Result<()> {
+        let client = reqwest::Client::new();
+        let text = report.message();
+
+        if let Some(url) = &self.slack_webhook {
+            post(
+                &client,
+                &expand_env(url),
+                &BTreeMap::new(),
+                &serde_json::json!({ "text": text }),
+            )
+            .await
+            .context("Failed to notify Slack")?;
+        }
+
+        let mut payload = serde_json::to_value(report)?;
+        payload["text"] = serde_json::Value::String(text);
+        for webhook in &self.webhooks {
+            post(
+                &client,
+                &expand_env(&webhook.url),
+                &webhook.headers,
+                &payload,
+            )
+            .await
+            .with_context(|| format!("Failed to notify {}", webhook.url))?;
+        }
+
+        Ok(())
+    }
+}
+
+async fn post(
+    client: &reqwest::Client,
+    url: &str,
+    headers: &BTreeMap<String, String>,
+    payload: &serde_json::Value,
+) -> Result<()> {
+    let mut request = client.post(url).json(payload);
+    for (name, value) in headers {
+        request = request.header(name, expand_env(value));
+    }
+
+    let response = request.send().await?;
+    if !response.status().is_success() {
+        anyhow::bail!("HTTP {}", response.status());
+    }
+    Ok(())
+}
+
+/// Summary of a finished batch of repository operations
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchReport {
+    /// What was done, e.g. `clone` or `run cargo test`
+    pub operation: String,
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    /// Names of the repositories that failed
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub failures: Vec<String>,
+    /// Run output directory or the location it was uploaded to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+}
+
+impl BatchReport {
+    pub fn new(operation: impl Into<String>, succeeded: usize, failures: Vec<String>) -> Self {
+        Self {
+            operation: operation.into(),
+            total: succeeded + failures.len(),
+            succeeded,
+            failed: failures.len(),
+            failures,
+            output: None,
+        }
+    }
+
+    pub fn with_output(mut self, output: impl Into<String>) -> Self {
+        self.output = Some(output.into());
+        self
+    }
+
+    /// One-line human readable summary
+    pub fn message(&self) -> String {
+        let mut message = format!(
+            "repos {}: {} of {} repositories succeeded",
+            self.operation, self.succeeded, self.total
+        );
+        if self.failed > 0 {
+            message.push_str(&format!(
+                ", {} failed ({})",
+                self.failed,
+                self.failures.join(", ")
+            ));
+        }
+        if let Some(output) = &self.output {
+            message.push_str(&format!(". Output: {}", output));
+        }
+        message
+    }
+}
+
+/// Send a notification if one is configured for this outcome
+///
+/// The report is also emitted as a `run_summary` event. Delivery problems are reported as warnings and never fail the command.
+pub async fn notify(config: Option<&NotifyConfig>, report: &BatchReport) {
+    events::summary(report);
+    let Some(config) = config else {
+        return;
+    };
+    if !config.should_notify(report) {
+        return;
+    }
+    if let Err(e) = config.send(report).await {
+        logging::message(
+            Level::Warn,
+            format!("Warning: Failed to send notification: {:#}", e).yellow(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_notify_config() {
+        let config: NotifyConfig = serde_yaml::from_str(
+            "slack_webhook: https://hooks.slack.com/services/${SLACK_HOOK}\nwebhooks:\n  - url: https://ci.example.com/hooks/repos\n    headers:\n      Authorization: Bearer ${TOKEN}\non: [failure]\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.slack_webhook.as_deref(),
+            Some("https://hooks.slack.com/services/${SLACK_HOOK}")
+        );
+        assert_eq!(config.webhooks[0].url, "https://ci.example.com/hooks/repos");
+        assert_eq!(
+            config.webhooks[0].headers["Authorization"],
+            "Bearer ${TOKEN}"
+        );
+        assert_eq!(config.on, vec![NotifyEvent::Failure]);
+
+        let config: NotifyConfig =
+            serde_yaml::from_str("slack_webhook: https://hooks.slack.com/x\n").unwrap();
+        assert_eq!(config.on, vec![NotifyEvent::Completion]);
+    }
+
+    #[test]
+    fn test_should_notify() {
+        let passed = BatchReport::new("clone", 3, vec![]);
+        let failed = BatchReport::new("clone", 2, vec!["api".to_string()]);
+
+        let on_failure: NotifyConfig = serde_yaml::from_str("on: [failure]\n").unwrap();
+        assert!(!on_failure.should_notify(&passed));
+        assert!(on_failure.should_notify(&failed));
+
+        let on_completion: NotifyConfig = serde_yaml::from_str("on: [completion]\n").unwrap();
+        assert!(on_completion.should_notify(&passed));
+        assert!(on_completion.should_notify(&failed));
+    }
+
+    #[test]
+    fn test_report_message() {
+        let report = BatchReport::new("clone", 3, vec![]);
+        assert_eq!(
+            report.message(),
+            "repos clone: 3 of 3 repositories succeeded"
+        );
+
+        let report = BatchReport::new(
+            "run cargo test",
+            1,
+            vec!["api".to_string(), "web".to_string()],
+        )
+        .with_output("output/runs/20240101-120000_cargo_test");
+        assert_eq!(report.total, 3);
+        assert_eq!(
+            report.message(),
+            "repos run cargo test: 1 of 3 repositories succeeded, 2 failed (api, web). Output: output/runs/20240101-120000_cargo_test"
+        );
+    }
+}
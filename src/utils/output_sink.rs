@@ -0,0 +1,451 @@
+//! Destinations for the output of each repository in a saved run
+//!
+//! Every saved run writes `metadata.json`, `stdout.log` and `stderr.log` per
+//! repository into its run directory, which `repos runs` and `--upload` read.
+//! Sinks configured via `log_sinks:` receive the same records as each
+//! repository finishes, so CI jobs can ship fleet logs while the run is still
+//! going. S3 uploads shell out to the `aws` CLI and syslog to `logger`, so
+//! their usual configuration applies.
+
+use crate::utils::upload::expand_env;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+
+/// Output and metadata of one repository in a run
+#[derive(Debug, Clone, Copy)]
+pub struct LogRecord<'a> {
+    pub repository: &'a str,
+    /// Command or recipe, exit code and timestamp, already redacted
+    pub metadata: &'a serde_json::Value,
+    pub stdout: &'a str,
+    pub stderr: &'a str,
+}
+
+impl LogRecord<'_> {
+    fn exit_code(&self) -> i64 {
+        self.metadata["exit_code"].as_i64().unwrap_or(-1)
+    }
+
+    /// One line describing how the repository finished
+    fn headline(&self) -> String {
+        let mut line = format!("{}: exit code {}", self.repository, self.exit_code());
+        if let Some(description) = self.metadata["exit_code_description"].as_str() {
+            line.push_str(&format!(" ({})", description));
+        }
+        line
+    }
+}
+
+/// Somewhere the output of each repository is written to
+#[async_trait]
+pub trait OutputSink: Debug + Send + Sync {
+    async fn write(&self, record: &LogRecord<'_>) -> Result<()>;
+}
+
+/// `<dir>/<repository>/{metadata.json,stdout.log,stderr.log}`, the layout of run directories
+#[derive(Debug, Clone)]
+pub struct DirectorySink {
+    dir: PathBuf,
+}
+
+impl DirectorySink {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+#[async_trait]
+impl OutputSink for DirectorySink {
+    async fn write(&self, record: &LogRecord<'_>) -> Result<()> {
+        let repo_dir = self.dir.join(record.repository);
+        std::fs::create_dir_all(&repo_dir)?;
+        std::fs::write(
+            repo_dir.join("metadata.json"),
+            serde_json::to_string_pretty(record.metadata)?,
+        )?;
+        // Written even when empty, to show the stream was captured
+        std::fs::write(repo_dir.join("stdout.log"), record.stdout)?;
+        std::fs::write(repo_dir.join("stderr.log"), record.stderr)?;
+        Ok(())
+    }
+}
+
+/// All repositories appended to one file, each under a header line
+#[derive(Debug)]
+pub struct FileSink {
+    path: PathBuf,
+    /// Keeps records of repositories running in parallel apart
+    lock: Mutex<()>,
+}
+
+impl FileSink {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    fn render(record: &LogRecord<'_>) -> String {
+        let mut text = format!("=== {} ===\n", record.headline());
+        text.push_str(record.stdout);
+        if !record.stderr.is_empty() {
+            text.push_str("--- stderr ---\n");
+            text.push_str(record.stderr);
+        }
+        if !text.ends_with('\n') {
+            text.push('\n');
+        }
+        text
+    }
+}
+
+#[async_trait]
+impl OutputSink for FileSink {
+    async fn write(&self, record: &LogRecord<'_>) -> Result<()> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Failed to open {}", self.path.display()))?;
+        file.write_all(Self::render(record).as_bytes())?;
+        Ok(())
+    }
+}
+
+/// Objects under `s3://<bucket>/<prefix>/<run>/<repository>/` in the run directory layout
+#[derive(Debug, Clone)]
+pub struct S3Sink {
+    /// `s3://` URL of the run
+    destination: String,
+    /// Endpoint of S3-compatible storage such as MinIO
+    endpoint: Option<String>,
+}
+
+impl S3Sink {
+    fn upload(&self, key: &str, content: &[u8]) -> Result<()> {
+        let destination = format!("{}/{}", self.destination, key);
+        let mut command = Command::new("aws");
+        command.args(["s3", "cp", "-", &destination]);
+        if let Some(endpoint) = &self.endpoint {
+            command.args(["--endpoint-url", endpoint]);
+        }
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("Failed to execute aws (is it installed?)")?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(content)?;
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "Failed to upload to {}: {}",
+                destination,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl OutputSink for S3Sink {
+    async fn write(&self, record: &LogRecord<'_>) -> Result<()> {
+        let repository = record.repository;
+        self.upload(
+            &format!("{}/metadata.json", repository),
+            serde_json::to_string_pretty(record.metadata)?.as_bytes(),
+        )?;
+        self.upload(
+            &format!("{}/stdout.log", repository),
+            record.stdout.as_bytes(),
+        )?;
+        self.upload(
+            &format!("{}/stderr.log", repository),
+            record.stderr.as_bytes(),
+        )
+    }
+}
+
+/// One JSON document per repository `POST`ed to a URL
+#[derive(Debug, Clone)]
+pub struct HttpSink {
+    url: String,
+    run: String,
+    headers: BTreeMap<String, String>,
+    client: reqwest::Client,
+}
+
+#[async_trait]
+impl OutputSink for HttpSink {
+    async fn write(&self, record: &LogRecord<'_>) -> Result<()> {
+        let body = serde_json::json!({
+            "run": self.run,
+            "repository": record.repository,
+            "metadata": record.metadata,
+            "stdout": record.stdout,
+            "stderr": record.stderr,
+        });
+        let mut request = self.client.post(&self.url).json(&body);
+        for (name, value) in &self.headers {
+            request = request.header(name, expand_env(value));
+        }
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("Failed to post logs to {}", self.url))?;
+        if !response.status().is_success() {
+            anyhow::bail!(
+                "Failed to post logs to {}: HTTP {}",
+                self.url,
+                response.status()
+            );
+        }
+        Ok(())
+    }
+}
+
+/// The local syslog through `logger`, one message per output line
+#[derive(Debug, Clone)]
+pub struct SyslogSink {
+    tag: String,
+}
+
+impl SyslogSink {
+    fn render(record: &LogRecord<'_>) -> String {
+        let mut lines = vec![record.headline()];
+        lines.extend(
+            record
+                .stdout
+                .lines()
+                .map(|line| format!("{}: {}", record.repository, line)),
+        );
+        lines.extend(
+            record
+                .stderr
+                .lines()
+                .map(|line| format!("{} [stderr]: {}", record.repository, line)),
+        );
+        lines.join("\n") + "\n"
+    }
+}
+
+#[async_trait]
+impl OutputSink for SyslogSink {
+    async fn write(&self, record: &LogRecord<'_>) -> Result<()> {
+        let priority = if record.exit_code() == 0 {
+            "user.info"
+        } else {
+            "user.err"
+        };
+        let mut child = Command::new("logger")
+            .args(["-t", &self.tag, "-p", priority])
+            .stdin(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("Failed to execute logger (is it installed?)")?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(Self::render(record).as_bytes())?;
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "Failed to write to syslog: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(())
+    }
+}
+
+/// A sink configured via `log_sinks:`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum LogSinkConfig {
+    /// One file for the whole run; `run.log` in the run directory by default
+    File {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        path: Option<PathBuf>,
+    },
+    /// S3 or S3-compatible bucket, uploaded with `aws s3 cp`
+    S3 {
+        bucket: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        prefix: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        endpoint: Option<String>,
+    },
+    /// Any HTTP endpoint accepting `POST` requests with a JSON body
+    Http {
+        url: String,
+        /// Extra request headers; `${VAR}` in values is replaced from the environment
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        headers: BTreeMap<String, String>,
+    },
+    /// The local syslog, tagged `repos` unless configured otherwise
+    Syslog {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        tag: Option<String>,
+    },
+}
+
+impl LogSinkConfig {
+    /// The sink for a run saved to `run_dir`
+    pub fn build(&self, run_dir: &Path) -> Arc<dyn OutputSink> {
+        let run = run_dir
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        match self {
+            LogSinkConfig::File { path } => Arc::new(FileSink::new(
+                path.clone().unwrap_or_else(|| run_dir.join("run.log")),
+            )),
+            LogSinkConfig::S3 {
+                bucket,
+                prefix,
+                endpoint,
+            } => {
+                let prefix = prefix
+                    .as_deref()
+                    .map(|p| p.trim_matches('/'))
+                    .filter(|p| !p.is_empty());
+                let destination = match prefix {
+                    Some(prefix) => format!("s3://{}/{}/{}", bucket, prefix, run),
+                    None => format!("s3://{}/{}", bucket, run),
+                };
+                Arc::new(S3Sink {
+                    destination,
+                    endpoint: endpoint.clone(),
+                })
+            }
+            LogSinkConfig::Http { url, headers } => Arc::new(HttpSink {
+                url: url.clone(),
+                run,
+                headers: headers.clone(),
+                client: reqwest::Client::new(),
+            }),
+            LogSinkConfig::Syslog { tag } => Arc::new(SyslogSink {
+                tag: tag.clone().unwrap_or_else(|| "repos".to_string()),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn metadata(exit_code: i32) -> serde_json::Value {
+        serde_json::json!({
+            "command": "make test",
+            "exit_code": exit_code,
+            "exit_code_description": if exit_code == 0 { "success" } else { "general error" },
+            "repository": "api",
+        })
+    }
+
+    #[tokio::test]
+    async fn test_directory_and_file_sinks() {
+        let temp_dir = TempDir::new().unwrap();
+        let success = metadata(0);
+        let failure = metadata(1);
+
+        let directory = DirectorySink::new(temp_dir.path());
+        directory
+            .write(&LogRecord {
+                repository: "api",
+                metadata: &success,
+                stdout: "ok\n",
+                stderr: "",
+            })
+            .await
+            .unwrap();
+        let repo_dir = temp_dir.path().join("api");
+        assert_eq!(
+            std::fs::read_to_string(repo_dir.join("stdout.log")).unwrap(),
+            "ok\n"
+        );
+        assert!(repo_dir.join("stderr.log").exists());
+        assert!(repo_dir.join("metadata.json").exists());
+
+        let file = LogSinkConfig::File { path: None }.build(temp_dir.path());
+        file.write(&LogRecord {
+            repository: "api",
+            metadata: &success,
+            stdout: "ok\n",
+            stderr: "",
+        })
+        .await
+        .unwrap();
+        file.write(&LogRecord {
+            repository: "web",
+            metadata: &failure,
+            stdout: "building",
+            stderr: "boom\n",
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(temp_dir.path().join("run.log")).unwrap(),
+            "=== api: exit code 0 (success) ===\nok\n\
+             === web: exit code 1 (general error) ===\nbuilding--- stderr ---\nboom\n"
+        );
+    }
+
+    #[test]
+    fn test_syslog_lines() {
+        let failure = metadata(2);
+        let text = SyslogSink::render(&LogRecord {
+            repository: "api",
+            metadata: &failure,
+            stdout: "one\ntwo\n",
+            stderr: "bad\n",
+        });
+        assert_eq!(
+            text,
+            "api: exit code 2 (general error)\napi: one\napi: two\napi [stderr]: bad\n"
+        );
+    }
+
+    #[test]
+    fn test_deserialize_sinks() {
+        let sinks: Vec<LogSinkConfig> = serde_yaml::from_str(
+            "- type: file\n  path: fleet.log\n\
+             - type: s3\n  bucket: ci-logs\n  prefix: /repos/\n  endpoint: https://minio.example.com\n\
+             - type: http\n  url: https://logs.example.com/ingest\n\
+             - type: syslog\n",
+        )
+        .unwrap();
+        assert_eq!(sinks.len(), 4);
+        assert_eq!(
+            sinks[0],
+            LogSinkConfig::File {
+                path: Some(PathBuf::from("fleet.log"))
+            }
+        );
+        assert_eq!(sinks[3], LogSinkConfig::Syslog { tag: None });
+
+        let s3 = format!(
+            "{:?}",
+            sinks[1].build(Path::new("output/runs/20240501-100000_make"))
+        );
+        assert!(s3.contains("s3://ci-logs/repos/20240501-100000_make"));
+        assert!(s3.contains("minio.example.com"));
+    }
+}
@@ -1,7 +1,8 @@
 //! Repository discovery utilities for detecting and analyzing Git repositories
 
-use crate::config::Repository;
+use crate::config::{PathStrategy, Repository};
 use anyhow::Result;
+use std::collections::BTreeMap;
 use std::path::Path;
 use walkdir::WalkDir;
 
@@ -29,54 +30,160 @@ pub fn find_git_repositories(start_path: &str) -> Result<Vec<Repository>> {
     Ok(repositories)
 }
 
-/// Get remote URL from a Git repository
+/// Compute the `path:` to record for a discovered repository
+///
+/// The path is made relative to `base_dir` (the directory of the config file)
+/// when possible, and omitted entirely when the path strategy already resolves
+/// the repository to that location.
+pub fn config_path_for(
+    repo_dir: &Path,
+    base_dir: &Path,
+    name: &str,
+    url: &str,
+    strategy: &PathStrategy,
+) -> Option<String> {
+    let relative = repo_dir.strip_prefix(base_dir).unwrap_or(repo_dir);
+    if relative == Path::new(&strategy.relative_dir(name, url)) {
+        None
+    } else {
+        Some(relative.to_string_lossy().to_string())
+    }
+}
+
+/// Get the URL of the primary remote of a Git repository (see [`primary_remote`])
 pub fn get_remote_url(repo_path: &Path) -> Result<Option<String>> {
+    let remotes = get_remotes(repo_path)?;
+    Ok(primary_remote(&remotes, None).map(|(_, url)| url.clone()))
+}
+
+/// All remotes of a Git repository by name
+pub fn get_remotes(repo_path: &Path) -> Result<BTreeMap<String, String>> {
     use std::process::Command;
 
     let output = Command::new("git")
-        .arg("remote")
-        .arg("get-url")
-        .arg("origin")
+        .args(["config", "--get-regexp", r"^remote\..*\.url$"])
         .current_dir(repo_path)
         .output();
 
-    if let Ok(output) = output
-        && output.status.success()
-    {
-        let url = String::from_utf8_lossy(&output.stdout).trim().to_string();
-        return Ok(Some(url));
-    }
-
-    Ok(None)
+    // git exits with 1 when nothing matches, i.e. there are no remotes
+    let Ok(output) = output else {
+        return Ok(BTreeMap::new());
+    };
+    Ok(parse_remotes(&String::from_utf8_lossy(&output.stdout)))
 }
 
-/// Detect tags from repository path based on files and directory names
-pub fn detect_tags_from_path(path: &Path) -> Vec<String> {
-    let mut tags = Vec::new();
+/// Parse `remote.<name>.url <url>` lines from `git config --get-regexp`
+fn parse_remotes(output: &str) -> BTreeMap<String, String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (key, url) = line.split_once(' ')?;
+            let name = key.strip_prefix("remote.")?.strip_suffix(".url")?;
+            Some((name.to_string(), url.trim().to_string()))
+        })
+        .collect()
+}
 
-    // Check for common patterns in directory names or files
-    let path_str = path.to_string_lossy().to_lowercase();
+/// The remote a repository's `url` is taken from
+///
+/// This is `preferred` when the repository has it, then `origin`, then the
+/// first remote by name.
+pub fn primary_remote<'a>(
+    remotes: &'a BTreeMap<String, String>,
+    preferred: Option<&str>,
+) -> Option<(&'a String, &'a String)> {
+    preferred
+        .and_then(|name| remotes.get_key_value(name))
+        .or_else(|| remotes.get_key_value("origin"))
+        .or_else(|| remotes.iter().next())
+}
 
-    // Language detection based on files
+/// Frameworks recognized by the package names their manifests mention
+const FRAMEWORKS: &[(&str, &[&str], &str)] = &[
+    ("package.json", &["\"react\""], "react"),
+    ("package.json", &["\"next\""], "nextjs"),
+    ("package.json", &["\"vue\""], "vue"),
+    ("package.json", &["\"@angular/core\""], "angular"),
+    ("package.json", &["\"svelte\""], "svelte"),
+    ("package.json", &["\"express\""], "express"),
+    ("package.json", &["\"@nestjs/core\""], "nestjs"),
+    ("Cargo.toml", &["axum"], "axum"),
+    ("Cargo.toml", &["actix-web"], "actix-web"),
+    ("Cargo.toml", &["rocket"], "rocket"),
+    ("requirements.txt", &["django"], "django"),
+    ("requirements.txt", &["flask"], "flask"),
+    ("requirements.txt", &["fastapi"], "fastapi"),
+    ("pyproject.toml", &["django"], "django"),
+    ("pyproject.toml", &["flask"], "flask"),
+    ("pyproject.toml", &["fastapi"], "fastapi"),
+    ("pom.xml", &["spring-boot"], "spring-boot"),
+    (
+        "build.gradle",
+        &["spring-boot", "org.springframework.boot"],
+        "spring-boot",
+    ),
+    (
+        "build.gradle.kts",
+        &["spring-boot", "org.springframework.boot"],
+        "spring-boot",
+    ),
+    ("go.mod", &["github.com/gin-gonic/gin"], "gin"),
+    ("go.mod", &["github.com/labstack/echo"], "echo"),
+    ("go.mod", &["github.com/gofiber/fiber"], "fiber"),
+];
+
+/// Detect the languages of a repository from the manifest files at its root
+pub fn detect_languages(path: &Path) -> Vec<String> {
+    let mut languages = Vec::new();
     if path.join("go.mod").exists() || path.join("main.go").exists() {
-        tags.push("go".to_string());
+        languages.push("go".to_string());
     }
     if path.join("package.json").exists() {
-        tags.push("javascript".to_string());
-        tags.push("node".to_string());
+        languages.push("javascript".to_string());
+        languages.push("node".to_string());
     }
     if path.join("requirements.txt").exists()
         || path.join("setup.py").exists()
         || path.join("pyproject.toml").exists()
     {
-        tags.push("python".to_string());
+        languages.push("python".to_string());
     }
     if path.join("pom.xml").exists() || path.join("build.gradle").exists() {
-        tags.push("java".to_string());
+        languages.push("java".to_string());
     }
     if path.join("Cargo.toml").exists() {
-        tags.push("rust".to_string());
+        languages.push("rust".to_string());
     }
+    languages
+}
+
+/// Detect the frameworks a repository uses from the dependencies in its manifests
+pub fn detect_frameworks(path: &Path) -> Vec<String> {
+    let mut manifests: BTreeMap<&str, Option<String>> = BTreeMap::new();
+    let mut frameworks: Vec<String> = Vec::new();
+    for (manifest, needles, framework) in FRAMEWORKS {
+        let content = manifests
+            .entry(manifest)
+            .or_insert_with(|| std::fs::read_to_string(path.join(manifest)).ok());
+        let Some(content) = content else {
+            continue;
+        };
+        if needles.iter().any(|needle| content.contains(needle))
+            && !frameworks.iter().any(|f| f == framework)
+        {
+            frameworks.push(framework.to_string());
+        }
+    }
+    frameworks
+}
+
+/// Detect tags from repository path based on files and directory names
+pub fn detect_tags_from_path(path: &Path) -> Vec<String> {
+    // Check for common patterns in directory names or files
+    let path_str = path.to_string_lossy().to_lowercase();
+
+    // Language detection based on files
+    let mut tags = detect_languages(path);
 
     // Type detection based on directory names
     if path_str.contains("frontend") || path_str.contains("ui") || path_str.contains("web") {
@@ -114,6 +221,17 @@ pub fn create_repository_from_path(path: &Path) -> Result<Option<Repository>> {
                 path: Some(path.to_string_lossy().to_string()),
                 branch: None,
                 config_dir: None, // Will be set when config is loaded
+                path_strategy: Default::default(),
+                default_branch: None,
+                sparse: Vec::new(),
+                provider: None,
+                provider_url: None,
+                remotes: BTreeMap::new(),
+                provider_defaults: Default::default(),
+                git: Default::default(),
+                depends_on: Vec::new(),
+                post_clone: None,
+                archived: false,
             };
 
             return Ok(Some(repository));
@@ -126,6 +244,82 @@ pub fn create_repository_from_path(path: &Path) -> Result<Option<Repository>> {
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    #[test]
+    fn test_primary_remote() {
+        let remotes = parse_remotes(
+            "remote.fork.url git@github.com:me/api.git\nremote.upstream.url git@github.com:org/api.git\n",
+        );
+        assert_eq!(remotes.len(), 2);
+        assert_eq!(
+            primary_remote(&remotes, None).map(|(name, _)| name.as_str()),
+            Some("fork")
+        );
+        assert_eq!(
+            primary_remote(&remotes, Some("upstream")).map(|(_, url)| url.as_str()),
+            Some("git@github.com:org/api.git")
+        );
+
+        let mut remotes = remotes;
+        remotes.insert(
+            "origin".to_string(),
+            "git@github.com:org/api.git".to_string(),
+        );
+        assert_eq!(
+            primary_remote(&remotes, Some("missing")).map(|(name, _)| name.as_str()),
+            Some("origin")
+        );
+    }
+
+    #[test]
+    fn test_config_path_for() {
+        let base = Path::new("/work");
+        let url = "git@github.com:org/api.git";
+
+        // Matches the strategy, so no explicit path is needed
+        assert_eq!(
+            config_path_for(
+                Path::new("/work/api"),
+                base,
+                "api",
+                url,
+                &PathStrategy::Flat
+            ),
+            None
+        );
+        assert_eq!(
+            config_path_for(
+                Path::new("/work/github.com/org/api"),
+                base,
+                "api",
+                url,
+                &PathStrategy::HostOwnerName
+            ),
+            None
+        );
+        // Elsewhere under the base: relative path
+        assert_eq!(
+            config_path_for(
+                Path::new("/work/services/api"),
+                base,
+                "api",
+                url,
+                &PathStrategy::Flat
+            ),
+            Some("services/api".to_string())
+        );
+        // Outside the base: absolute path
+        assert_eq!(
+            config_path_for(
+                Path::new("/other/api"),
+                base,
+                "api",
+                url,
+                &PathStrategy::Flat
+            ),
+            Some("/other/api".to_string())
+        );
+    }
     use std::fs;
     use std::process::Command;
     use tempfile::TempDir;
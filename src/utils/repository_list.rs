@@ -0,0 +1,98 @@
+//! Repository names read from a file, for `--repos-from`
+//!
+//! The file holds one repository name per line; blank lines and lines starting
+//! with `#` are ignored. The JSON printed by `repos select --json` or
+//! `repos ls --json` is accepted as well. A path of `-` reads the list from
+//! standard input.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::io::Read;
+use std::path::Path;
+
+/// Path that reads the list from standard input
+pub const STDIN_PATH: &str = "-";
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Entry {
+    Name(String),
+    Repository { name: String },
+}
+
+/// Parse a list of repository names
+pub fn parse_repository_list(content: &str) -> Result<Vec<String>> {
+    if content.trim_start().starts_with('[') {
+        let entries: Vec<Entry> = serde_json::from_str(content)
+            .context("Expected a JSON array of names or of objects with a `name`")?;
+        return Ok(entries
+            .into_iter()
+            .map(|entry| match entry {
+                Entry::Name(name) | Entry::Repository { name } => name,
+            })
+            .collect());
+    }
+
+    Ok(content
+        .split(['\n', '\0'])
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect())
+}
+
+/// Read a list of repository names from a file, or from standard input for `-`
+pub fn read_repository_list(path: &Path) -> Result<Vec<String>> {
+    let content = if path == Path::new(STDIN_PATH) {
+        let mut content = String::new();
+        std::io::stdin()
+            .read_to_string(&mut content)
+            .context("Failed to read repository list from standard input")?;
+        content
+    } else {
+        std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read repository list {}", path.display()))?
+    };
+    parse_repository_list(&content)
+        .with_context(|| format!("Failed to parse repository list {}", describe_source(path)))
+}
+
+/// How a repository list path is shown in messages
+pub fn describe_source(path: &Path) -> String {
+    if path == Path::new(STDIN_PATH) {
+        "standard input".to_string()
+    } else {
+        path.display().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lines() {
+        let names = parse_repository_list("api\n\n# services\n  web  \nwork/cli\n").unwrap();
+        assert_eq!(names, vec!["api", "web", "work/cli"]);
+        assert_eq!(
+            parse_repository_list("api\0web\0").unwrap(),
+            vec!["api", "web"]
+        );
+    }
+
+    #[test]
+    fn test_parse_json() {
+        let names = parse_repository_list(
+            r#"[{"name": "api", "url": "git@github.com:org/api.git"}, "web"]"#,
+        )
+        .unwrap();
+        assert_eq!(names, vec!["api", "web"]);
+        assert!(parse_repository_list("[1, 2]").is_err());
+    }
+
+    #[test]
+    fn test_describe_source() {
+        assert_eq!(describe_source(Path::new("-")), "standard input");
+        assert_eq!(describe_source(Path::new("picked.txt")), "picked.txt");
+    }
+}
@@ -0,0 +1,260 @@
+//! Past runs saved under `<output dir>/runs`
+//!
+//! Every saved `repos run` leaves a `<timestamp>_<command>` directory holding a
+//! `summary.json` and one directory of logs per repository. Runs from before
+//! summaries were written, or interrupted runs, are pieced together from the
+//! per-repository `metadata.json` files instead.
+
+use super::run_summary::{RepoRunResult, RunSummary, SUMMARY_FILE};
+use anyhow::{Context, Result};
+use chrono::NaiveDateTime;
+use std::path::{Path, PathBuf};
+
+/// Directory below the output directory holding one directory per run
+pub const RUNS_DIR: &str = "runs";
+
+/// Format of the timestamp a run directory name starts with
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+/// Id that always refers to the newest run
+pub const LATEST: &str = "latest";
+
+/// A saved run
+#[derive(Debug, Clone)]
+pub struct RunRecord {
+    /// Name of the run directory, used to refer to the run
+    pub id: String,
+    pub dir: PathBuf,
+    pub started: Option<NaiveDateTime>,
+    /// Command or recipe that was run
+    pub label: String,
+    pub summary: RunSummary,
+}
+
+impl RunRecord {
+    /// Load a run from its directory
+    pub fn load(dir: &Path) -> Result<Self> {
+        let id = dir
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let summary_path = dir.join(SUMMARY_FILE);
+        let summary = if summary_path.exists() {
+            let content = std::fs::read_to_string(&summary_path)
+                .with_context(|| format!("Failed to read {}", summary_path.display()))?;
+            serde_json::from_str(&content)
+                .with_context(|| format!("Failed to parse {}", summary_path.display()))?
+        } else {
+            summary_from_metadata(dir)?
+        };
+
+        let (timestamp, suffix) = id.split_once('_').unwrap_or((&id, ""));
+        let started = NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT).ok();
+        let label = summary
+            .results
+            .iter()
+            .find_map(|result| read_metadata(&dir.join(&result.repository)))
+            .and_then(|metadata| {
+                metadata["command"]
+                    .as_str()
+                    .or(metadata["recipe"].as_str())
+                    .map(str::to_string)
+            })
+            .unwrap_or_else(|| suffix.to_string());
+
+        Ok(Self {
+            id,
+            dir: dir.to_path_buf(),
+            started,
+            label,
+            summary,
+        })
+    }
+
+    /// Directory holding the logs of one repository
+    pub fn repository_dir(&self, repository: &str) -> PathBuf {
+        self.dir.join(repository)
+    }
+}
+
+fn read_metadata(repo_dir: &Path) -> Option<serde_json::Value> {
+    let content = std::fs::read_to_string(repo_dir.join("metadata.json")).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Rebuild a summary from the `metadata.json` of each repository directory
+fn summary_from_metadata(dir: &Path) -> Result<RunSummary> {
+    let mut results = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let Some(metadata) = read_metadata(&path) else {
+            continue;
+        };
+        let repository = metadata["repository"]
+            .as_str()
+            .map(str::to_string)
+            .unwrap_or_else(|| entry_name(&path));
+        results.push(RepoRunResult {
+            repository,
+            exit_code: metadata["exit_code"].as_i64().map(|code| code as i32),
+            exit_code_description: metadata["exit_code_description"]
+                .as_str()
+                .map(str::to_string),
+            duration_secs: 0.0,
+            log_dir: Some(path),
+            error: None,
+        });
+    }
+    results.sort_by(|a, b| a.repository.cmp(&b.repository));
+    Ok(RunSummary::new(results))
+}
+
+fn entry_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// All saved runs in `output_dir`, newest first
+pub fn list_runs(output_dir: &Path) -> Result<Vec<RunRecord>> {
+    let runs_dir = output_dir.join(RUNS_DIR);
+    if !runs_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut dirs = Vec::new();
+    for entry in std::fs::read_dir(&runs_dir)
+        .with_context(|| format!("Failed to read {}", runs_dir.display()))?
+    {
+        let path = entry?.path();
+        if path.is_dir() {
+            dirs.push(path);
+        }
+    }
+    // Directory names start with the timestamp, so they sort by age
+    dirs.sort_by_key(|dir| std::cmp::Reverse(entry_name(dir)));
+
+    dirs.iter().map(|dir| RunRecord::load(dir)).collect()
+}
+
+/// Find a run by its id, a unique prefix of it (e.g. just the timestamp), or `latest`
+pub fn find_run(output_dir: &Path, id: &str) -> Result<RunRecord> {
+    let runs_dir = output_dir.join(RUNS_DIR);
+    let mut names: Vec<String> = match std::fs::read_dir(&runs_dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .map(|path| entry_name(&path))
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    names.sort();
+
+    let name = if id == LATEST {
+        names.last().cloned()
+    } else if names.iter().any(|name| name == id) {
+        Some(id.to_string())
+    } else {
+        let matches: Vec<&String> = names.iter().filter(|name| name.starts_with(id)).collect();
+        if matches.len() > 1 {
+            anyhow::bail!(
+                "Run id '{}' is ambiguous; it matches {}",
+                id,
+                matches
+                    .iter()
+                    .map(|name| name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+        matches.first().map(|name| name.to_string())
+    };
+
+    let Some(name) = name else {
+        anyhow::bail!("No run '{}' in {}", id, runs_dir.display());
+    };
+    RunRecord::load(&runs_dir.join(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::ExitCodeDescriptions;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn write_run(output_dir: &Path, id: &str, codes: &[(&str, i32)]) -> PathBuf {
+        let dir = output_dir.join(RUNS_DIR).join(id);
+        std::fs::create_dir_all(&dir).unwrap();
+        for (repo, code) in codes {
+            std::fs::create_dir_all(dir.join(repo)).unwrap();
+            std::fs::write(
+                dir.join(repo).join("metadata.json"),
+                serde_json::json!({
+                    "command": "make test",
+                    "exit_code": code,
+                    "exit_code_description": if *code == 0 { "success" } else { "general error" },
+                    "repository": repo,
+                })
+                .to_string(),
+            )
+            .unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_list_runs_newest_first() {
+        let temp_dir = TempDir::new().unwrap();
+        let older = write_run(
+            temp_dir.path(),
+            "20240101-090000_make_test",
+            &[("api", 0), ("web", 1)],
+        );
+        RunSummary::new(vec![RepoRunResult::new(
+            "api",
+            &Ok(0),
+            Duration::from_secs(1),
+            None,
+            &ExitCodeDescriptions::default(),
+        )])
+        .write_json(&write_run(
+            temp_dir.path(),
+            "20240102-090000_make_test",
+            &[("api", 0)],
+        ))
+        .unwrap();
+
+        let runs = list_runs(temp_dir.path()).unwrap();
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].id, "20240102-090000_make_test");
+        assert_eq!(runs[0].summary.total, 1);
+        assert_eq!(runs[0].label, "make test");
+
+        // Rebuilt from metadata.json when there is no summary
+        assert_eq!(runs[1].dir, older);
+        assert_eq!(runs[1].summary.succeeded, 1);
+        assert_eq!(runs[1].summary.failed, 1);
+        assert_eq!(runs[1].started.unwrap().to_string(), "2024-01-01 09:00:00");
+    }
+
+    #[test]
+    fn test_find_run() {
+        let temp_dir = TempDir::new().unwrap();
+        write_run(temp_dir.path(), "20240101-090000_make_test", &[]);
+        write_run(temp_dir.path(), "20240101-100000_make_lint", &[]);
+
+        assert_eq!(
+            find_run(temp_dir.path(), LATEST).unwrap().id,
+            "20240101-100000_make_lint"
+        );
+        assert_eq!(
+            find_run(temp_dir.path(), "20240101-09").unwrap().id,
+            "20240101-090000_make_test"
+        );
+        assert!(find_run(temp_dir.path(), "20240101").is_err());
+        assert!(find_run(temp_dir.path(), "2023").is_err());
+    }
+}
@@ -0,0 +1,269 @@
+//! Aggregated results of a multi-repository run
+
+use super::ExitCodeDescriptions;
+use anyhow::Result;
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Name of the summary file written at the top of a run directory
+pub const SUMMARY_FILE: &str = "summary.json";
+
+/// Outcome of running a command or recipe in one repository
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepoRunResult {
+    pub repository: String,
+    /// Exit code of the command, if it ran to completion
+    pub exit_code: Option<i32>,
+    /// Description of the exit code, from the `exit_codes` config or the built-in table
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exit_code_description: Option<String>,
+    pub duration_secs: f64,
+    /// Directory holding this repository's logs, if output was saved
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_dir: Option<PathBuf>,
+    /// Error that prevented the command from completing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl RepoRunResult {
+    pub fn new(
+        repository: &str,
+        result: &Result<i32>,
+        duration: Duration,
+        log_dir: Option<PathBuf>,
+        exit_codes: &ExitCodeDescriptions,
+    ) -> Self {
+        let (exit_code, error) = match result {
+            Ok(code) => (Some(*code), None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+        Self {
+            repository: repository.to_string(),
+            exit_code,
+            exit_code_description: exit_code.map(|code| exit_codes.describe(code).to_string()),
+            duration_secs: duration.as_secs_f64(),
+            log_dir,
+            error,
+        }
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+}
+
+/// Results of a run across all selected repositories
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RunSummary {
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    /// Where the run directory was uploaded, if it was
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_location: Option<String>,
+    pub results: Vec<RepoRunResult>,
+}
+
+impl RunSummary {
+    pub fn new(results: Vec<RepoRunResult>) -> Self {
+        let succeeded = results.iter().filter(|r| r.is_success()).count();
+        Self {
+            total: results.len(),
+            succeeded,
+            failed: results.len() - succeeded,
+            remote_location: None,
+            results,
+        }
+    }
+
+    /// Print a table with one row per repository followed by totals
+    pub fn print_table(&self) {
+        let name_width = self
+            .results
+            .iter()
+            .map(|r| r.repository.len())
+            .max()
+            .unwrap_or(0)
+            .max("REPOSITORY".len());
+
+        println!();
+        println!(
+            "{:<name_width$}  {:>4}  {:>9}  {}",
+            "REPOSITORY".bold(),
+            "EXIT".bold(),
+            "DURATION".bold(),
+            "LOGS".bold(),
+        );
+        for result in &self.results {
+            let exit = match result.exit_code {
+                Some(code) => code.to_string(),
+                None => "-".to_string(),
+            };
+            let exit = if result.is_success() {
+                exit.green()
+            } else {
+                exit.red()
+            };
+            let mut logs = match (&result.log_dir, &result.error) {
+                (_, Some(error)) => error.red().to_string(),
+                (Some(dir), None) => dir.display().to_string(),
+                (None, None) => "-".to_string(),
+            };
+            if let (false, None, Some(description)) = (
+                result.is_success(),
+                &result.error,
+                &result.exit_code_description,
+            ) {
+                logs = format!("{} ({})", logs, description.red());
+            }
+            println!(
+                "{:<name_width$}  {:>4}  {:>8.1}s  {}",
+                result.repository.cyan(),
+                exit,
+                result.duration_secs,
+                logs,
+            );
+        }
+        println!();
+        println!(
+            "Completed with {} successful, {} failed",
+            self.succeeded.to_string().green(),
+            self.failed.to_string().red()
+        );
+        if let Some(location) = &self.remote_location {
+            println!("Run output uploaded to {}", location.cyan());
+        }
+    }
+
+    /// Render the summary as a Markdown table, e.g. for a CI step summary
+    pub fn to_markdown(&self, title: &str) -> String {
+        let mut markdown = format!(
+            "### repos run: `{}`\n\n{} succeeded, {} failed\n\n| Repository | Exit | Duration | Error |\n| --- | ---: | ---: | --- |\n",
+            title, self.succeeded, self.failed
+        );
+        for result in &self.results {
+            let status = if result.is_success() { "✅" } else { "❌" };
+            let exit = result
+                .exit_code
+                .map(|code| code.to_string())
+                .unwrap_or_else(|| "-".to_string());
+            let error = result
+                .error
+                .as_deref()
+                .or(if result.is_success() {
+                    None
+                } else {
+                    result.exit_code_description.as_deref()
+                })
+                .map(|error| error.replace('|', "\\|").replace('\n', " "))
+                .unwrap_or_default();
+            markdown.push_str(&format!(
+                "| {} {} | {} | {:.1}s | {} |\n",
+                status, result.repository, exit, result.duration_secs, error
+            ));
+        }
+        if let Some(location) = &self.remote_location {
+            markdown.push_str(&format!("\nRun output uploaded to {}\n", location));
+        }
+        markdown
+    }
+
+    /// Write the summary as JSON into the run directory
+    pub fn write_json(&self, run_dir: &Path) -> Result<PathBuf> {
+        let path = run_dir.join(SUMMARY_FILE);
+        std::fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_summary_counts() {
+        let codes = ExitCodeDescriptions::default();
+        let summary = RunSummary::new(vec![
+            RepoRunResult::new("a", &Ok(0), Duration::from_millis(10), None, &codes),
+            RepoRunResult::new("b", &Ok(2), Duration::from_millis(10), None, &codes),
+            RepoRunResult::new(
+                "c",
+                &Err(anyhow::anyhow!("missing")),
+                Duration::ZERO,
+                None,
+                &codes,
+            ),
+        ]);
+
+        assert_eq!(summary.total, 3);
+        assert_eq!(summary.succeeded, 1);
+        assert_eq!(summary.failed, 2);
+        assert_eq!(summary.results[2].error.as_deref(), Some("missing"));
+    }
+
+    #[test]
+    fn test_to_markdown() {
+        let codes = ExitCodeDescriptions::default();
+        let summary = RunSummary::new(vec![
+            RepoRunResult::new("a", &Ok(0), Duration::from_millis(1500), None, &codes),
+            RepoRunResult::new(
+                "b",
+                &Err(anyhow::anyhow!("x | y")),
+                Duration::ZERO,
+                None,
+                &codes,
+            ),
+        ]);
+
+        let markdown = summary.to_markdown("cargo test");
+        assert!(markdown.starts_with("### repos run: `cargo test`\n\n1 succeeded, 1 failed\n"));
+        assert!(markdown.contains("| ✅ a | 0 | 1.5s |  |\n"));
+        assert!(markdown.contains("| ❌ b | - | 0.0s | x \\| y |\n"));
+    }
+
+    #[test]
+    fn test_write_json() {
+        let temp_dir = TempDir::new().unwrap();
+        let summary = RunSummary::new(vec![RepoRunResult::new(
+            "a",
+            &Ok(0),
+            Duration::from_secs(1),
+            Some(temp_dir.path().join("a")),
+            &ExitCodeDescriptions::default(),
+        )]);
+
+        let path = summary.write_json(temp_dir.path()).unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+
+        assert_eq!(json["succeeded"], 1);
+        assert_eq!(json["results"][0]["repository"], "a");
+        assert_eq!(json["results"][0]["exit_code"], 0);
+        assert!(json["results"][0].get("error").is_none());
+    }
+
+    #[test]
+    fn test_exit_code_description() {
+        let codes = ExitCodeDescriptions::new(
+            [(42, "database migration pending".to_string())]
+                .into_iter()
+                .collect(),
+        );
+        let summary = RunSummary::new(vec![
+            RepoRunResult::new("a", &Ok(42), Duration::ZERO, None, &codes),
+            RepoRunResult::new("b", &Ok(1), Duration::ZERO, None, &codes),
+        ]);
+
+        assert_eq!(
+            summary.results[0].exit_code_description.as_deref(),
+            Some("database migration pending")
+        );
+        let markdown = summary.to_markdown("migrate");
+        assert!(markdown.contains("| ❌ a | 42 | 0.0s | database migration pending |\n"));
+        assert!(markdown.contains("| ❌ b | 1 | 0.0s | general error |\n"));
+    }
+}
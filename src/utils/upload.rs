@@ -0,0 +1,212 @@
+//! Upload of run output directories to remote storage
+//!
+//! S3 and GCS uploads shell out to the `aws` and `gcloud` CLIs so their usual
+//! credential chains apply; HTTP uploads `PUT` each file to a base URL.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Command;
+use walkdir::WalkDir;
+
+/// Remote destination for run outputs, configured via `artifacts_remote:`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ArtifactsRemote {
+    /// Amazon S3 bucket, uploaded with `aws s3 cp`
+    S3 {
+        bucket: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        prefix: Option<String>,
+    },
+    /// Google Cloud Storage bucket, uploaded with `gcloud storage cp`
+    Gcs {
+        bucket: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        prefix: Option<String>,
+    },
+    /// Any HTTP endpoint accepting `PUT` requests
+    Http {
+        url: String,
+        /// Extra request headers; `${VAR}` in values is replaced from the environment
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        headers: BTreeMap<String, String>,
+    },
+}
+
+impl ArtifactsRemote {
+    /// Location the given run directory is uploaded to
+    pub fn destination(&self, run_name: &str) -> String {
+        match self {
+            ArtifactsRemote::S3 { bucket, prefix } => {
+                format!(
+                    "s3://{}/{}",
+                    bucket,
+                    join_prefix(prefix.as_deref(), run_name)
+                )
+            }
+            ArtifactsRemote::Gcs { bucket, prefix } => {
+                format!(
+                    "gs://{}/{}",
+                    bucket,
+                    join_prefix(prefix.as_deref(), run_name)
+                )
+            }
+            ArtifactsRemote::Http { url, .. } => {
+                format!("{}/{}", url.trim_end_matches('/'), run_name)
+            }
+        }
+    }
+}
+
+fn join_prefix(prefix: Option<&str>, run_name: &str) -> String {
+    match prefix
+        .map(|p| p.trim_matches('/'))
+        .filter(|p| !p.is_empty())
+    {
+        Some(prefix) => format!("{}/{}", prefix, run_name),
+        None => run_name.to_string(),
+    }
+}
+
+/// Upload a run directory and return the location it was uploaded to
+pub async fn upload_run_dir(remote: &ArtifactsRemote, run_dir: &Path) -> Result<String> {
+    let run_name = run_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| anyhow::anyhow!("Invalid run directory: {}", run_dir.display()))?;
+    let destination = remote.destination(&run_name);
+
+    match remote {
+        ArtifactsRemote::S3 { .. } => {
+            run_upload_cli("aws", &["s3", "cp", "--recursive"], run_dir, &destination)?
+        }
+        ArtifactsRemote::Gcs { .. } => run_upload_cli(
+            "gcloud",
+            &["storage", "cp", "--recursive"],
+            run_dir,
+            &format!("{}/", destination),
+        )?,
+        ArtifactsRemote::Http { headers, .. } => {
+            upload_http(run_dir, &destination, headers).await?
+        }
+    }
+
+    Ok(destination)
+}
+
+fn run_upload_cli(program: &str, args: &[&str], run_dir: &Path, destination: &str) -> Result<()> {
+    let output = Command::new(program)
+        .args(args)
+        .arg(run_dir)
+        .arg(destination)
+        .output()
+        .with_context(|| format!("Failed to execute {} (is it installed?)", program))?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to upload to {}: {}",
+            destination,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(())
+}
+
+async fn upload_http(
+    run_dir: &Path,
+    destination: &str,
+    headers: &BTreeMap<String, String>,
+) -> Result<()> {
+    let client = reqwest::Client::new();
+
+    for entry in WalkDir::new(run_dir).into_iter().filter_map(|e| e.ok()) {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let relative = entry.path().strip_prefix(run_dir)?;
+        let url = format!(
+            "{}/{}",
+            destination,
+            relative.to_string_lossy().replace('\\', "/")
+        );
+
+        let mut request = client.put(&url).body(std::fs::read(entry.path())?);
+        for (name, value) in headers {
+            request = request.header(name, expand_env(value));
+        }
+
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("Failed to upload {}", url))?;
+        if !response.status().is_success() {
+            anyhow::bail!("Failed to upload {}: HTTP {}", url, response.status());
+        }
+    }
+
+    Ok(())
+}
+
+/// Replace `${VAR}` references with environment variable values
+pub(crate) fn expand_env(value: &str) -> String {
+    let pattern = regex::Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*)\}").unwrap();
+    pattern
+        .replace_all(value, |caps: &regex::Captures| {
+            std::env::var(&caps[1]).unwrap_or_default()
+        })
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_destination() {
+        let s3 = ArtifactsRemote::S3 {
+            bucket: "ci-artifacts".to_string(),
+            prefix: Some("/repos/".to_string()),
+        };
+        assert_eq!(s3.destination("run1"), "s3://ci-artifacts/repos/run1");
+
+        let gcs = ArtifactsRemote::Gcs {
+            bucket: "ci-artifacts".to_string(),
+            prefix: None,
+        };
+        assert_eq!(gcs.destination("run1"), "gs://ci-artifacts/run1");
+
+        let http = ArtifactsRemote::Http {
+            url: "https://files.example.com/upload/".to_string(),
+            headers: BTreeMap::new(),
+        };
+        assert_eq!(
+            http.destination("run1"),
+            "https://files.example.com/upload/run1"
+        );
+    }
+
+    #[test]
+    fn test_deserialize_remote() {
+        let remote: ArtifactsRemote = serde_yaml::from_str(
+            "type: http\nurl: https://files.example.com\nheaders:\n  Authorization: Bearer ${TOKEN}\n",
+        )
+        .unwrap();
+
+        match remote {
+            ArtifactsRemote::Http { url, headers } => {
+                assert_eq!(url, "https://files.example.com");
+                assert_eq!(headers["Authorization"], "Bearer ${TOKEN}");
+            }
+            other => panic!("unexpected remote: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_expand_env() {
+        assert_eq!(expand_env("no variables"), "no variables");
+        assert_eq!(expand_env("Bearer ${REPOS_TEST_UNSET_VARIABLE}"), "Bearer ");
+    }
+}
@@ -4,6 +4,7 @@
 //! validation rules, promoting separation of concerns and improved testability.
 
 use crate::config::{Config, Recipe, Repository};
+use crate::utils::artifacts::is_contained_pattern;
 use anyhow::{Result, anyhow};
 use std::collections::HashSet;
 
@@ -26,6 +27,8 @@ pub enum ValidationError {
     EmptyRecipeName,
     /// Duplicate recipe names found
     DuplicateRecipeName(String),
+    /// Recipe artifact pattern is absolute or leaves the repository
+    InvalidArtifactPattern(String, String),
     /// Tag filter is empty or whitespace-only
     EmptyTagFilter(String),
     /// No repositories found with specified tag
@@ -59,6 +62,13 @@ impl std::fmt::Display for ValidationError {
             ValidationError::DuplicateRecipeName(name) => {
                 write!(f, "Duplicate recipe name: '{}'", name)
             }
+            ValidationError::InvalidArtifactPattern(name, pattern) => {
+                write!(
+                    f,
+                    "Recipe '{}' has artifact pattern '{}' outside the repository",
+                    name, pattern
+                )
+            }
             ValidationError::EmptyTagFilter(filter) => {
                 write!(f, "Tag filter cannot be empty: '{}'", filter)
             }
@@ -196,6 +206,8 @@ pub fn validate_recipe(recipe: &Recipe) -> Result<(), Vec<ValidationError>> {
         errors.push(ValidationError::RecipeWithNoSteps(recipe.name.clone()));
     }
 
+    errors.extend(artifact_pattern_errors(recipe));
+
     if errors.is_empty() {
         Ok(())
     } else {
@@ -203,6 +215,31 @@ pub fn validate_recipe(recipe: &Recipe) -> Result<(), Vec<ValidationError>> {
     }
 }
 
+/// Validates that the artifact patterns of every recipe stay inside the repository
+///
+/// Patterns must be relative and free of `..`, or collected files could land
+/// outside the run directory or overwrite their source.
+pub fn validate_artifact_patterns(recipes: &[Recipe]) -> Result<(), Vec<ValidationError>> {
+    let errors: Vec<ValidationError> = recipes.iter().flat_map(artifact_pattern_errors).collect();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+fn artifact_pattern_errors(recipe: &Recipe) -> Vec<ValidationError> {
+    recipe
+        .artifacts
+        .iter()
+        .filter(|pattern| !is_contained_pattern(pattern))
+        .map(|pattern| {
+            ValidationError::InvalidArtifactPattern(recipe.name.clone(), pattern.clone())
+        })
+        .collect()
+}
+
 /// Validates a tag filter string
 ///
 /// Ensures the tag filter is not empty or whitespace-only.
@@ -255,7 +292,10 @@ mod tests {
     fn create_valid_recipe(name: &str, steps: Vec<&str>) -> Recipe {
         Recipe {
             name: name.to_string(),
-            steps: steps.iter().map(|s| s.to_string()).collect(),
+            steps: steps.iter().map(|s| (*s).into()).collect(),
+            artifacts: vec![],
+            cache: false,
+            env: Default::default(),
         }
     }
 
@@ -264,6 +304,23 @@ mod tests {
         let config = Config {
             repositories: vec![],
             recipes: vec![],
+            artifacts_remote: None,
+            log_sinks: Vec::new(),
+            path_strategy: Default::default(),
+            root: None,
+            files: Vec::new(),
+            branch_protection: Vec::new(),
+            repository_settings: Vec::new(),
+            post_clone: Vec::new(),
+            pr: None,
+            provider: None,
+            provider_url: None,
+            git: Default::default(),
+            exit_codes: Default::default(),
+            notify: None,
+            profiles: Default::default(),
+            secrets: None,
+            profile_token: None,
         };
 
         // Empty repositories should be allowed (config can be initialized empty)
@@ -278,6 +335,23 @@ mod tests {
                 "git@github.com:owner/repo1.git",
             )],
             recipes: vec![create_valid_recipe("recipe1", vec!["echo hello"])],
+            artifacts_remote: None,
+            log_sinks: Vec::new(),
+            path_strategy: Default::default(),
+            root: None,
+            files: Vec::new(),
+            branch_protection: Vec::new(),
+            repository_settings: Vec::new(),
+            post_clone: Vec::new(),
+            pr: None,
+            provider: None,
+            provider_url: None,
+            git: Default::default(),
+            exit_codes: Default::default(),
+            notify: None,
+            profiles: Default::default(),
+            secrets: None,
+            profile_token: None,
         };
 
         assert!(validate_config(&config).is_ok());
@@ -380,7 +454,10 @@ mod tests {
     fn test_validate_recipe_empty_name() {
         let recipe = Recipe {
             name: "".to_string(),
-            steps: vec!["echo hello".to_string()],
+            steps: vec!["echo hello".into()],
+            artifacts: vec![],
+            cache: false,
+            env: Default::default(),
         };
 
         let result = validate_recipe(&recipe);
@@ -398,6 +475,9 @@ mod tests {
         let recipe = Recipe {
             name: "recipe1".to_string(),
             steps: vec![],
+            artifacts: vec![],
+            cache: false,
+            env: Default::default(),
         };
 
         let result = validate_recipe(&recipe);
@@ -410,6 +490,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_validate_artifact_patterns() {
+        let mut recipe = create_valid_recipe("build", vec!["make"]);
+        recipe.artifacts = vec!["dist/**".to_string(), "./coverage.xml".to_string()];
+        assert!(validate_artifact_patterns(std::slice::from_ref(&recipe)).is_ok());
+
+        recipe.artifacts = vec!["/tmp/report.xml".to_string(), "../out/*".to_string()];
+        let errors = validate_artifact_patterns(&[recipe.clone()]).unwrap_err();
+        assert_eq!(
+            errors,
+            vec![
+                ValidationError::InvalidArtifactPattern(
+                    "build".to_string(),
+                    "/tmp/report.xml".to_string()
+                ),
+                ValidationError::InvalidArtifactPattern(
+                    "build".to_string(),
+                    "../out/*".to_string()
+                ),
+            ]
+        );
+        assert_eq!(validate_recipe(&recipe).unwrap_err().len(), 2);
+    }
+
     #[test]
     fn test_validate_tag_filter_valid() {
         assert!(validate_tag_filter("frontend").is_ok());
@@ -1,8 +1,9 @@
 //! CLI argument parsing integration tests
 
 use std::env;
+use std::io::Write;
 use std::path::PathBuf;
-use std::process::Command;
+use std::process::{Command, Stdio};
 use tempfile::TempDir;
 
 /// Helper struct for creating temporary test workspaces
@@ -168,6 +169,30 @@ fn test_pr_command_missing_required_args() {
     );
 }
 
+#[test]
+fn test_pr_comment_requires_branch_or_label() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "pr", "comment", "--body", "Please merge"])
+        .output()
+        .expect("Failed to execute cargo run");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("--branch"));
+}
+
+#[test]
+fn test_reaper_requires_a_policy() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "reaper", "--apply"])
+        .output()
+        .expect("Failed to execute cargo run");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("--stale-prs-days"));
+}
+
 #[test]
 fn test_remove_command_with_invalid_config() {
     let output = Command::new("cargo")
@@ -201,3 +226,519 @@ repositories:
     assert_eq!(output.status, 0);
     assert!(output.stdout.contains("No repositories") || output.stdout.is_empty());
 }
+
+#[test]
+fn test_run_with_github_ci_output() {
+    let ws = Workspace::new();
+    let repos_dir = ws.root.path().join("repos");
+    std::fs::create_dir_all(repos_dir.join("api")).unwrap();
+    std::fs::create_dir_all(repos_dir.join("web")).unwrap();
+    ws.write_config(&format!(
+        r#"
+repositories:
+  - name: api
+    url: https://github.com/test/api
+    tags: [test]
+    path: {0}/api
+  - name: web
+    url: https://github.com/test/web
+    tags: [test]
+    path: {0}/web
+"#,
+        repos_dir.display()
+    ));
+    let step_summary = ws.root.path().join("step-summary.md");
+    let output_dir = ws.root.path().join("output");
+
+    let output = Command::new("cargo")
+        .args(["run", "--quiet", "--"])
+        .args([
+            "run",
+            "--config",
+            ws.config_str(),
+            "--no-save",
+            "--output-dir",
+            output_dir.to_str().unwrap(),
+            "--ci",
+            "github",
+        ])
+        .arg("echo hello")
+        .env("GITHUB_STEP_SUMMARY", &step_summary)
+        .env("RUNNER_TEMP", ws.root.path())
+        .output()
+        .expect("Failed to execute cargo run");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("::add-matcher::"));
+    assert!(stdout.contains("::group::api"));
+    assert!(stdout.contains("::group::web"));
+    assert!(stdout.contains("::endgroup::"));
+    assert!(ws.root.path().join("repos-problem-matcher.json").is_file());
+
+    let summary = std::fs::read_to_string(&step_summary).unwrap();
+    assert!(summary.contains("### repos run: `echo hello`"));
+    assert!(summary.contains("2 succeeded, 0 failed"));
+}
+
+#[test]
+fn test_bookmark_save_and_switch() {
+    let ws = Workspace::new();
+    let repo_dir = ws.root.path().join("api");
+    std::fs::create_dir_all(&repo_dir).unwrap();
+    let git = |args: &[&str]| {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(&repo_dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {:?} failed", args);
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    };
+    git(&["init", "-b", "main"]);
+    git(&["config", "user.name", "Test User"]);
+    git(&["config", "user.email", "test@example.com"]);
+    git(&["commit", "--allow-empty", "-m", "Initial commit"]);
+    git(&["checkout", "-b", "feature/login"]);
+
+    ws.write_config(&format!(
+        r#"
+repositories:
+  - name: api
+    url: https://github.com/test/api
+    tags: [test]
+    path: {}
+"#,
+        repo_dir.display()
+    ));
+    let state_dir = ws.root.path().join(".repos");
+    let run = |args: &[&str]| {
+        Command::new("cargo")
+            .args(["run", "--quiet", "--", "bookmark"])
+            .args(args)
+            .env("REPOS_STATE_DIR", &state_dir)
+            .output()
+            .expect("Failed to execute cargo run")
+    };
+
+    assert!(
+        run(&["save", "login", "--config", ws.config_str()])
+            .status
+            .success()
+    );
+    // Saving again under the same name needs --force
+    assert!(
+        !run(&["save", "login", "--config", ws.config_str()])
+            .status
+            .success()
+    );
+
+    git(&["checkout", "main"]);
+    assert!(
+        run(&["switch", "login", "--config", ws.config_str()])
+            .status
+            .success()
+    );
+    assert_eq!(git(&["branch", "--show-current"]), "feature/login");
+
+    let list = run(&["list"]);
+    assert!(String::from_utf8_lossy(&list.stdout).contains("feature/login"));
+
+    assert!(run(&["delete", "login"]).status.success());
+    assert!(
+        !run(&["switch", "login", "--config", ws.config_str()])
+            .status
+            .success()
+    );
+}
+
+#[test]
+fn test_state_lives_next_to_config() {
+    let ws = Workspace::new();
+    let repo_dir = ws.root.path().join("api");
+    std::fs::create_dir_all(&repo_dir).unwrap();
+    for args in [
+        &["init", "-b", "main"][..],
+        &["config", "user.name", "Test User"],
+        &["config", "user.email", "test@example.com"],
+        &["commit", "--allow-empty", "-m", "Initial commit"],
+    ] {
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(&repo_dir)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+    ws.write_config(&format!(
+        "repositories:\n  - name: api\n    url: https://github.com/test/api\n    tags: []\n    path: {}\n",
+        repo_dir.display()
+    ));
+
+    // Run from the project root, away from the config file
+    let output = Command::new("cargo")
+        .args(["run", "--quiet", "--", "bookmark", "save", "anchored"])
+        .args(["--config", ws.config_str()])
+        .env_remove("REPOS_STATE_DIR")
+        .output()
+        .expect("Failed to execute cargo run");
+    assert!(output.status.success());
+
+    let state = std::fs::read_to_string(ws.root.path().join(".repos/state.json")).unwrap();
+    assert!(state.contains("anchored"));
+}
+
+#[test]
+fn test_pr_batch_size_continues_rollout() {
+    let ws = Workspace::new();
+    let mut repositories = String::from("repositories:\n");
+    for name in ["api", "db", "web"] {
+        let repo_dir = ws.root.path().join(name);
+        std::fs::create_dir_all(&repo_dir).unwrap();
+        let status = Command::new("git")
+            .args(["init", "--quiet"])
+            .current_dir(&repo_dir)
+            .status()
+            .unwrap();
+        assert!(status.success());
+        repositories.push_str(&format!(
+            "  - name: {}\n    url: https://github.com/test/{}\n    tags: []\n    path: {}\n",
+            name,
+            name,
+            repo_dir.display()
+        ));
+    }
+    ws.write_config(&repositories);
+    let state_dir = ws.root.path().join(".repos");
+    let run = || {
+        let output = Command::new("cargo")
+            .args(["run", "--quiet", "--", "pr", "--title", "Bump deps"])
+            .args(["--batch-size", "2", "--token", "test_token"])
+            .args(["--config", ws.config_str()])
+            .arg("--output-dir")
+            .arg(ws.root.path().join("output"))
+            .env("REPOS_STATE_DIR", &state_dir)
+            .output()
+            .expect("Failed to execute cargo run");
+        assert!(output.status.success());
+        String::from_utf8_lossy(&output.stdout).to_string()
+    };
+    let done = || {
+        let state: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(state_dir.join("state.json")).unwrap())
+                .unwrap();
+        state["rollouts"]["Bump deps"]["done"].clone()
+    };
+
+    // Repositories without changes need no pull request, so batches never wait
+    assert!(run().contains("1 repositories left in this rollout"));
+    assert_eq!(done(), serde_json::json!(["api", "db"]));
+    // The last batch finishes the rollout
+    assert!(run().contains("Rollout 'Bump deps' is complete"));
+    assert!(done().is_null());
+}
+
+#[test]
+fn test_files_check_reports_drift() {
+    let ws = Workspace::new();
+    let api_dir = ws.root.path().join("api");
+    let web_dir = ws.root.path().join("web");
+    std::fs::create_dir_all(&api_dir).unwrap();
+    std::fs::create_dir_all(&web_dir).unwrap();
+    std::fs::write(
+        api_dir.join("SECURITY.md"),
+        "## Reporting a Vulnerability\n",
+    )
+    .unwrap();
+    std::fs::write(web_dir.join("SECURITY.md"), "TODO\n").unwrap();
+
+    ws.write_config(&format!(
+        r#"
+repositories:
+  - name: api
+    url: https://github.com/test/api
+    tags: [test]
+    path: {}
+  - name: web
+    url: https://github.com/test/web
+    tags: [test]
+    path: {}
+files:
+  - path: SECURITY.md
+    contains: [Reporting a Vulnerability]
+"#,
+        api_dir.display(),
+        web_dir.display()
+    ));
+
+    let output = Command::new("cargo")
+        .args(["run", "--quiet", "--", "files", "check", "--json"])
+        .args(["--config", ws.config_str()])
+        .output()
+        .expect("Failed to execute cargo run");
+
+    assert!(!output.status.success());
+    let reports: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(reports[0]["repository"], "api");
+    assert_eq!(reports[0]["drift"].as_array().unwrap().len(), 0);
+    assert_eq!(reports[1]["repository"], "web");
+    assert_eq!(reports[1]["drift"][0]["kind"], "missing_content");
+
+    // The drifting repository can be left out
+    let output = Command::new("cargo")
+        .args(["run", "--quiet", "--", "files", "check", "api"])
+        .args(["--config", ws.config_str()])
+        .output()
+        .expect("Failed to execute cargo run");
+    assert!(output.status.success());
+}
+
+#[test]
+fn test_ls_across_multiple_configs() {
+    let ws = Workspace::new();
+    let config = |name: &str| {
+        let path = ws.root.path().join(format!("{}.yaml", name));
+        std::fs::write(
+            &path,
+            format!(
+                "repositories:\n  - name: api\n    url: https://github.com/{}/api\n    tags: []\n",
+                name
+            ),
+        )
+        .unwrap();
+        path.to_string_lossy().to_string()
+    };
+    let work = config("work");
+    let oss = config("oss");
+
+    let output = run_cli(&["ls", "--json", "--config", &work, "--config", &oss]);
+    assert_eq!(output.status, 0, "{}", output.stderr);
+    assert!(output.stdout.contains("\"work/api\""));
+    assert!(output.stdout.contains("\"oss/api\""));
+
+    // A workspace file does the same, with explicit namespaces
+    ws.write_config(&format!(
+        "workspace:\n  - config: {}\n    name: job\n  - config: oss.yaml\n",
+        work
+    ));
+    let output = run_cli(&["ls", "--json", "--tag", "job", "--config", ws.config_str()]);
+    assert_eq!(output.status, 0, "{}", output.stderr);
+    assert!(output.stdout.contains("\"job/api\""));
+    assert!(!output.stdout.contains("\"oss/api\""));
+}
+
+#[test]
+fn test_select_output_feeds_repos_from() {
+    let ws = Workspace::new();
+    ws.write_config(
+        "repositories:\n  - name: api-users\n    url: https://github.com/org/api-users\n    tags: [backend]\n  - name: api-orders\n    url: https://github.com/org/api-orders\n    tags: [backend]\n  - name: web\n    url: https://github.com/org/web\n    tags: [frontend]\n",
+    );
+
+    let output = run_cli(&["select", "api-*", "--config", ws.config_str()]);
+    assert_eq!(output.status, 0, "{}", output.stderr);
+    assert_eq!(output.stdout, "api-users\napi-orders\n");
+
+    let list = ws.root.path().join("selected.txt");
+    std::fs::write(&list, "web\napi-orders\n").unwrap();
+    let output = run_cli(&[
+        "ls",
+        "--json",
+        "--repos-from",
+        list.to_str().unwrap(),
+        "--config",
+        ws.config_str(),
+    ]);
+    assert_eq!(output.status, 0, "{}", output.stderr);
+    assert!(output.stdout.contains("\"web\""));
+    assert!(output.stdout.contains("\"api-orders\""));
+    assert!(!output.stdout.contains("\"api-users\""));
+
+    // `-` reads the list from standard input
+    let mut child = Command::new("cargo")
+        .args(["run", "--quiet", "--", "ls", "--repos-from", "-"])
+        .args(["--config", ws.config_str()])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to execute cargo run");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"api-users\n")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("api-users"));
+    assert!(!stdout.contains("api-orders"));
+}
+
+#[test]
+fn test_release_dry_run_previews_next_version() {
+    let ws = Workspace::new();
+    let root = ws.root.path();
+    let repo_dir = root.join("billing");
+    let git = |dir: &std::path::Path, args: &[&str]| {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {:?} failed", args);
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    };
+    git(root, &["init", "-q", "--bare", "origin.git"]);
+    git(root, &["clone", "-q", "origin.git", "billing"]);
+    git(&repo_dir, &["config", "user.name", "Test User"]);
+    git(&repo_dir, &["config", "user.email", "test@example.com"]);
+    git(
+        &repo_dir,
+        &["commit", "--allow-empty", "-m", "Initial commit"],
+    );
+    git(&repo_dir, &["tag", "-a", "v1.2.0", "-m", "Release v1.2.0"]);
+    git(
+        &repo_dir,
+        &["commit", "--allow-empty", "-m", "Add invoices"],
+    );
+
+    ws.write_config(&format!(
+        r#"
+repositories:
+  - name: billing
+    url: https://github.com/test/billing
+    tags: [backend]
+    path: {}
+"#,
+        repo_dir.display()
+    ));
+
+    let output = run_cli(&[
+        "release",
+        "--bump",
+        "minor",
+        "--dry-run",
+        "--config",
+        ws.config_str(),
+    ]);
+    assert_eq!(output.status, 0, "{}", output.stderr);
+    assert!(
+        output.stdout.contains("v1.2.0 → v1.3.0"),
+        "{}",
+        output.stdout
+    );
+    assert!(
+        output.stdout.contains("- Add invoices"),
+        "{}",
+        output.stdout
+    );
+    assert_eq!(git(&repo_dir, &["tag", "--list"]), "v1.2.0");
+
+    let output = run_cli(&["release", "--config", ws.config_str()]);
+    assert_ne!(output.status, 0);
+    assert!(output.stderr.contains("--version"), "{}", output.stderr);
+}
+
+#[test]
+fn test_config_defaults_seed_flags() {
+    let ws = Workspace::new();
+    ws.write_config(
+        r#"
+defaults:
+  tag: [backend]
+  ls:
+    urls: true
+repositories:
+  - name: api
+    url: https://github.com/test/api
+    tags: [backend]
+  - name: web
+    url: https://github.com/test/web
+    tags: [frontend]
+"#,
+    );
+
+    let output = run_cli(&["ls", "--config", ws.config_str()]);
+    assert_eq!(output.status, 0, "{}", output.stderr);
+    assert_eq!(output.stdout.trim(), "https://github.com/test/api");
+
+    // Flags on the command line win over the defaults
+    let output = run_cli(&["ls", "--config", ws.config_str(), "--tag", "frontend"]);
+    assert_eq!(output.status, 0, "{}", output.stderr);
+    assert_eq!(output.stdout.trim(), "https://github.com/test/web");
+
+    ws.write_config("defaults:\n  ls:\n    ulrs: true\nrepositories: []\n");
+    let output = run_cli(&["ls", "--config", ws.config_str()]);
+    assert_ne!(output.status, 0);
+    assert!(
+        output
+            .stderr
+            .contains("Unknown option 'ulrs' for 'repos ls'")
+    );
+}
+
+#[test]
+fn test_alias_expands_to_command_with_appended_args() {
+    let ws = Workspace::new();
+    ws.write_config(
+        r#"
+aliases:
+  backend-urls: ls --urls -t backend
+  all-urls: backend-urls
+  loop: loop
+repositories:
+  - name: api
+    url: https://github.com/test/api
+    tags: [backend]
+  - name: db
+    url: https://github.com/test/db
+    tags: [backend, storage]
+  - name: web
+    url: https://github.com/test/web
+    tags: [frontend]
+"#,
+    );
+
+    let output = run_cli(&["backend-urls", "--config", ws.config_str(), "-e", "storage"]);
+    assert_eq!(output.status, 0, "{}", output.stderr);
+    assert_eq!(output.stdout.trim(), "https://github.com/test/api");
+
+    // Aliases can name other aliases
+    let output = run_cli(&["all-urls", "--config", ws.config_str()]);
+    assert_eq!(output.status, 0, "{}", output.stderr);
+    assert_eq!(output.stdout.lines().count(), 2);
+
+    let output = run_cli(&["loop", "--config", ws.config_str()]);
+    assert_ne!(output.status, 0);
+    assert!(output.stderr.contains("Alias 'loop' expands to itself"));
+}
+
+#[test]
+fn test_alias_applies_global_options() {
+    let ws = Workspace::new();
+    let list = ws.root.path().join("picked.txt");
+    std::fs::write(&list, "web\n").unwrap();
+    ws.write_config(&format!(
+        r#"
+aliases:
+  picked-urls: ls --urls --repos-from {}
+repositories:
+  - name: api
+    url: https://github.com/test/api
+    tags: [backend]
+  - name: web
+    url: https://github.com/test/web
+    tags: [frontend]
+"#,
+        list.display()
+    ));
+
+    let output = run_cli(&["picked-urls", "--config", ws.config_str()]);
+    assert_eq!(output.status, 0, "{}", output.stderr);
+    assert_eq!(output.stdout.trim(), "https://github.com/test/web");
+
+    // Global options before the alias apply too
+    let output = run_cli(&["--quiet", "picked-urls", "--config", ws.config_str()]);
+    assert_eq!(output.status, 0, "{}", output.stderr);
+    assert_eq!(output.stdout.trim(), "https://github.com/test/web");
+}
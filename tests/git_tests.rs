@@ -3,8 +3,10 @@
 use repos::{
     config::Repository,
     git::{
-        Logger, add_all_changes, clone_repository, commit_changes, create_and_checkout_branch,
-        get_default_branch, has_changes, push_branch, remove_repository,
+        CloneOutcome, CloneProgress, LocalWork, Logger, RebaseOutcome, RemoteAccess, abort_rebase,
+        add_all_changes, clone_repository, commit_changes, create_and_checkout_branch, fetch,
+        get_default_branch, has_changes, local_work, patch_id, push_branch, rebase,
+        remove_repository, trash_repository, workspace_diff, workspace_files,
     },
 };
 use std::fs;
@@ -54,6 +56,17 @@ fn create_test_repository(name: &str, url: &str, path: Option<String>) -> Reposi
         path,
         branch: None,
         config_dir: None,
+        path_strategy: Default::default(),
+        default_branch: None,
+        sparse: Vec::new(),
+        provider: None,
+        provider_url: None,
+        provider_defaults: Default::default(),
+        git: Default::default(),
+        depends_on: Vec::new(),
+        post_clone: None,
+        archived: false,
+        remotes: Default::default(),
     }
 }
 
@@ -90,6 +103,17 @@ fn test_clone_repository_directory_exists() {
         path: Some(temp_dir.path().to_string_lossy().to_string()),
         branch: None,
         config_dir: None,
+        path_strategy: Default::default(),
+        default_branch: None,
+        sparse: Vec::new(),
+        provider: None,
+        provider_url: None,
+        provider_defaults: Default::default(),
+        git: Default::default(),
+        depends_on: Vec::new(),
+        post_clone: None,
+        archived: false,
+        remotes: Default::default(),
     };
 
     // Should succeed but skip cloning because the directory exists.
@@ -109,6 +133,17 @@ fn test_clone_repository_network_failure() {
         path: Some(temp_dir.path().to_string_lossy().to_string()),
         branch: None,
         config_dir: None,
+        path_strategy: Default::default(),
+        default_branch: None,
+        sparse: Vec::new(),
+        provider: None,
+        provider_url: None,
+        provider_defaults: Default::default(),
+        git: Default::default(),
+        depends_on: Vec::new(),
+        post_clone: None,
+        archived: false,
+        remotes: Default::default(),
     };
 
     // Ensure the target directory doesn't exist by checking and removing if it does
@@ -126,6 +161,150 @@ fn test_clone_repository_network_failure() {
     );
 }
 
+#[test]
+fn test_clone_repository_sparse() {
+    let temp_dir = TempDir::new().unwrap();
+    let source = temp_dir.path().join("source");
+    fs::create_dir_all(source.join("services/api")).unwrap();
+    fs::create_dir_all(source.join("services/web")).unwrap();
+    fs::write(source.join("services/api/main.rs"), "fn main() {}").unwrap();
+    fs::write(source.join("services/web/index.html"), "<html></html>").unwrap();
+    create_git_repo(&source, None).unwrap();
+
+    let target = temp_dir.path().join("monorepo");
+    let mut repo = create_test_repository(
+        "monorepo",
+        &format!("file://{}", source.display()),
+        Some(target.to_string_lossy().to_string()),
+    );
+    repo.sparse = vec!["services/api".to_string()];
+
+    clone_repository(&repo).unwrap();
+
+    assert!(target.join("README.md").exists());
+    assert!(target.join("services/api/main.rs").exists());
+    assert!(!target.join("services/web").exists());
+}
+
+#[test]
+fn test_clone_repository_adds_remotes() {
+    let temp_dir = TempDir::new().unwrap();
+    let source = temp_dir.path().join("source");
+    fs::create_dir_all(&source).unwrap();
+    create_git_repo(&source, None).unwrap();
+
+    let target = temp_dir.path().join("fork");
+    let mut repo = create_test_repository(
+        "fork",
+        &format!("file://{}", source.display()),
+        Some(target.to_string_lossy().to_string()),
+    );
+    repo.remotes = [
+        (
+            "origin".to_string(),
+            "https://example.com/me/fork.git".to_string(),
+        ),
+        (
+            "mirror".to_string(),
+            "https://example.com/mirror/fork.git".to_string(),
+        ),
+    ]
+    .into();
+
+    clone_repository(&repo).unwrap();
+
+    assert_eq!(
+        git(&target, &["remote", "get-url", "mirror"]),
+        "https://example.com/mirror/fork.git"
+    );
+    // A fork's own origin is declared in remotes, so the url becomes upstream
+    assert_eq!(
+        git(&target, &["remote", "get-url", "origin"]),
+        "https://example.com/me/fork.git"
+    );
+    assert_eq!(
+        git(&target, &["remote", "get-url", "upstream"]),
+        format!("file://{}", source.display())
+    );
+}
+
+#[test]
+fn test_clone_repository_resumes_interrupted_clone() {
+    let temp_dir = TempDir::new().unwrap();
+    let source = temp_dir.path().join("source");
+    fs::create_dir_all(&source).unwrap();
+    create_git_repo(&source, None).unwrap();
+    let url = format!("file://{}", source.display());
+
+    // A clone killed during the download leaves a .git directory without commits
+    let target = temp_dir.path().join("api");
+    fs::create_dir_all(&target).unwrap();
+    Command::new("git")
+        .args(["init", "-q"])
+        .current_dir(&target)
+        .output()
+        .unwrap();
+    Command::new("git")
+        .args(["remote", "add", "origin", &url])
+        .current_dir(&target)
+        .output()
+        .unwrap();
+    assert!(repos::git::is_interrupted_clone(&target.to_string_lossy()));
+
+    let repo = create_test_repository("api", &url, Some(target.to_string_lossy().to_string()));
+    assert_eq!(clone_repository(&repo).unwrap(), CloneOutcome::Resumed);
+    assert!(target.join("README.md").exists());
+    assert!(!repos::git::is_interrupted_clone(&target.to_string_lossy()));
+    assert_eq!(
+        clone_repository(&repo).unwrap(),
+        CloneOutcome::AlreadyCloned
+    );
+}
+
+#[test]
+fn test_clone_progress_parse() {
+    assert_eq!(
+        CloneProgress::parse("Receiving objects:  45% (450/1000), 1.20 MiB | 2.00 MiB/s"),
+        Some(CloneProgress {
+            phase: "Receiving objects".to_string(),
+            percent: 45,
+            detail: "(450/1000), 1.20 MiB | 2.00 MiB/s".to_string(),
+        })
+    );
+    let remote = CloneProgress::parse("remote: Compressing objects: 100% (12/12), done.").unwrap();
+    assert_eq!(remote.phase, "Compressing objects");
+    assert_eq!(remote.percent, 100);
+    assert_eq!(remote.detail, "(12/12)");
+    assert_eq!(CloneProgress::parse("Cloning into 'api'..."), None);
+}
+
+#[test]
+fn test_clone_repository_rewrites_url() {
+    let temp_dir = TempDir::new().unwrap();
+    let source = temp_dir.path().join("org").join("api");
+    fs::create_dir_all(&source).unwrap();
+    create_git_repo(&source, None).unwrap();
+
+    let target = temp_dir.path().join("api");
+    let mut repo = create_test_repository(
+        "api",
+        "https://git.example.com/org/api",
+        Some(target.to_string_lossy().to_string()),
+    );
+    repo.provider_defaults.git.rewrite = [(
+        "https://git.example.com/".to_string(),
+        format!("file://{}/", temp_dir.path().display()),
+    )]
+    .into();
+
+    clone_repository(&repo).unwrap();
+
+    assert_eq!(
+        git(&target, &["remote", "get-url", "origin"]),
+        format!("file://{}", source.display())
+    );
+}
+
 #[test]
 fn test_remove_repository() {
     let temp_dir = TempDir::new().unwrap();
@@ -140,6 +319,17 @@ fn test_remove_repository() {
         path: Some(temp_dir.path().to_string_lossy().to_string()),
         branch: None,
         config_dir: None,
+        path_strategy: Default::default(),
+        default_branch: None,
+        sparse: Vec::new(),
+        provider: None,
+        provider_url: None,
+        provider_defaults: Default::default(),
+        git: Default::default(),
+        depends_on: Vec::new(),
+        post_clone: None,
+        archived: false,
+        remotes: Default::default(),
     };
 
     // Test successful removal
@@ -399,13 +589,21 @@ fn test_commit_changes_invalid_repo() {
 fn test_push_branch() {
     // Test with invalid repo
     let temp_dir_invalid = TempDir::new().unwrap();
-    let result_invalid = push_branch(temp_dir_invalid.path().to_str().unwrap(), "main");
+    let result_invalid = push_branch(
+        temp_dir_invalid.path().to_str().unwrap(),
+        "main",
+        &RemoteAccess::default(),
+    );
     assert!(result_invalid.is_err());
 
     // Test with no remote
     let temp_dir_no_remote = TempDir::new().unwrap();
     create_git_repo(temp_dir_no_remote.path(), None).unwrap();
-    let result_no_remote = push_branch(temp_dir_no_remote.path().to_str().unwrap(), "main");
+    let result_no_remote = push_branch(
+        temp_dir_no_remote.path().to_str().unwrap(),
+        "main",
+        &RemoteAccess::default(),
+    );
     assert!(result_no_remote.is_err());
 
     // Test with a (non-functional) remote
@@ -415,7 +613,11 @@ fn test_push_branch() {
         Some("https://github.com/user/test.git"),
     )
     .unwrap();
-    let result_with_remote = push_branch(temp_dir_with_remote.path().to_str().unwrap(), "main");
+    let result_with_remote = push_branch(
+        temp_dir_with_remote.path().to_str().unwrap(),
+        "main",
+        &RemoteAccess::default(),
+    );
     assert!(result_with_remote.is_err()); // Expected to fail as the remote isn't real/accessible
     assert!(
         result_with_remote
@@ -424,3 +626,193 @@ fn test_push_branch() {
             .contains("Failed to push")
     );
 }
+
+// =================================
+// ===== Rebase Tests
+// =================================
+
+fn git(path: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(path)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "git {:?} failed", args);
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+fn commit_file(path: &Path, file: &str, content: &str, message: &str) {
+    fs::write(path.join(file), content).unwrap();
+    git(path, &["add", "."]);
+    git(path, &["commit", "-m", message]);
+}
+
+/// An origin repository and a clone of it checked out on a `feature` branch
+fn create_feature_clone(temp_dir: &TempDir) -> (std::path::PathBuf, std::path::PathBuf, String) {
+    let origin = temp_dir.path().join("origin");
+    fs::create_dir_all(&origin).unwrap();
+    create_git_repo(&origin, None).unwrap();
+    let base = git(&origin, &["branch", "--show-current"]);
+
+    let clone = temp_dir.path().join("clone");
+    git(
+        temp_dir.path(),
+        &["clone", origin.to_str().unwrap(), clone.to_str().unwrap()],
+    );
+    git(&clone, &["config", "user.name", "Test User"]);
+    git(&clone, &["config", "user.email", "test@example.com"]);
+    git(&clone, &["checkout", "-b", "feature"]);
+
+    (origin, clone, base)
+}
+
+#[test]
+fn test_rebase_onto_updated_base() {
+    let temp_dir = TempDir::new().unwrap();
+    let (origin, clone, base) = create_feature_clone(&temp_dir);
+    commit_file(&clone, "feature.txt", "feature", "Add feature");
+    commit_file(&origin, "upstream.txt", "upstream", "Add upstream change");
+
+    let clone_path = clone.to_str().unwrap();
+    let upstream = format!("origin/{}", base);
+    fetch(clone_path).unwrap();
+    assert_eq!(
+        rebase(clone_path, &upstream).unwrap(),
+        RebaseOutcome::Rebased
+    );
+    assert!(clone.join("upstream.txt").exists());
+    assert!(clone.join("feature.txt").exists());
+
+    assert_eq!(
+        rebase(clone_path, &upstream).unwrap(),
+        RebaseOutcome::UpToDate
+    );
+    assert!(rebase(clone_path, "origin/does-not-exist").is_err());
+}
+
+#[test]
+fn test_rebase_reports_conflicts() {
+    let temp_dir = TempDir::new().unwrap();
+    let (origin, clone, base) = create_feature_clone(&temp_dir);
+    commit_file(&clone, "README.md", "# Feature", "Edit README on feature");
+    commit_file(&origin, "README.md", "# Upstream", "Edit README upstream");
+
+    let clone_path = clone.to_str().unwrap();
+    let before = git(&clone, &["rev-parse", "HEAD"]);
+    fetch(clone_path).unwrap();
+    let outcome = rebase(clone_path, &format!("origin/{}", base)).unwrap();
+    assert_eq!(
+        outcome,
+        RebaseOutcome::Conflicts(vec!["README.md".to_string()])
+    );
+
+    abort_rebase(clone_path).unwrap();
+    assert_eq!(git(&clone, &["rev-parse", "HEAD"]), before);
+    assert_eq!(git(&clone, &["branch", "--show-current"]), "feature");
+    assert!(!has_changes(clone_path).unwrap());
+}
+
+#[test]
+fn test_local_work() {
+    let temp_dir = TempDir::new().unwrap();
+    let (_origin, clone, _base) = create_feature_clone(&temp_dir);
+    let clone_path = clone.to_str().unwrap();
+
+    // A new branch without commits of its own has nothing to lose
+    assert!(local_work(clone_path).unwrap().is_empty());
+
+    commit_file(&clone, "feature.txt", "feature", "Add feature");
+    fs::write(clone.join("notes.txt"), "draft").unwrap();
+    fs::write(clone.join("README.md"), "# Stashed").unwrap();
+    git(&clone, &["stash"]);
+
+    let work = local_work(clone_path).unwrap();
+    assert_eq!(
+        work,
+        LocalWork {
+            uncommitted: true,
+            stashes: 1,
+            unpushed_branches: vec!["feature".to_string()],
+        }
+    );
+    assert_eq!(
+        work.describe(),
+        "uncommitted changes, 1 stash, unpushed branches: feature"
+    );
+
+    // Plain directories are not inspected
+    assert!(
+        local_work(temp_dir.path().to_str().unwrap())
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_trash_repository() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo_path = temp_dir.path().join("to-trash");
+    fs::create_dir_all(&repo_path).unwrap();
+    fs::write(repo_path.join("file.txt"), "content").unwrap();
+    let repo = create_test_repository(
+        "to-trash",
+        "https://github.com/user/to-trash.git",
+        Some(repo_path.to_string_lossy().to_string()),
+    );
+
+    let trash_dir = temp_dir.path().join("trash");
+    let moved = trash_repository(&repo, &trash_dir).unwrap();
+    assert!(!repo_path.exists());
+    assert!(moved.starts_with(&trash_dir));
+    assert_eq!(
+        fs::read_to_string(moved.join("file.txt")).unwrap(),
+        "content"
+    );
+
+    assert!(trash_repository(&repo, &trash_dir).is_err());
+}
+
+// =================================
+// ===== Diff Tests
+// =================================
+
+#[test]
+fn test_workspace_diff_includes_untracked_files() {
+    let temp_dir = TempDir::new().unwrap();
+    create_git_repo(temp_dir.path(), None).unwrap();
+    let repo_path = temp_dir.path().to_str().unwrap();
+
+    fs::write(temp_dir.path().join("README.md"), "# Updated").unwrap();
+    fs::write(temp_dir.path().join("NEW.md"), "new file").unwrap();
+
+    assert_eq!(
+        workspace_files(repo_path).unwrap(),
+        vec!["NEW.md".to_string(), "README.md".to_string()]
+    );
+    let diff = workspace_diff(repo_path, &[]).unwrap();
+    assert!(diff.contains("+new file"));
+
+    // The repository's own index is left alone
+    let status = Command::new("git")
+        .args(["status", "--porcelain"])
+        .current_dir(repo_path)
+        .output()
+        .unwrap();
+    assert!(String::from_utf8_lossy(&status.stdout).contains("?? NEW.md"));
+
+    // The same change committed elsewhere has the same patch ID
+    add_all_changes(repo_path).unwrap();
+    commit_changes(repo_path, "Same change").unwrap();
+    let committed = Command::new("git")
+        .args(["diff", "HEAD~1", "HEAD"])
+        .current_dir(repo_path)
+        .output()
+        .unwrap();
+    let local_id = patch_id(repo_path, &diff).unwrap();
+    assert!(local_id.is_some());
+    assert_eq!(
+        patch_id(repo_path, &String::from_utf8_lossy(&committed.stdout)).unwrap(),
+        local_id
+    );
+    assert_eq!(patch_id(repo_path, "").unwrap(), None);
+}
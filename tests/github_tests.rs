@@ -83,6 +83,17 @@ async fn test_create_pr_from_workspace_with_changes_success_flow() {
         tags: Vec::new(),
         branch: None,
         config_dir: None,
+        path_strategy: Default::default(),
+        default_branch: None,
+        sparse: Vec::new(),
+        provider: None,
+        provider_url: None,
+        provider_defaults: Default::default(),
+        git: Default::default(),
+        depends_on: Vec::new(),
+        post_clone: None,
+        archived: false,
+        remotes: Default::default(),
     };
 
     let options = PrOptions::new(
@@ -139,6 +150,17 @@ async fn test_create_pr_workspace_no_changes_early_return() {
         tags: Vec::new(),
         branch: None,
         config_dir: None,
+        path_strategy: Default::default(),
+        default_branch: None,
+        sparse: Vec::new(),
+        provider: None,
+        provider_url: None,
+        provider_defaults: Default::default(),
+        git: Default::default(),
+        depends_on: Vec::new(),
+        post_clone: None,
+        archived: false,
+        remotes: Default::default(),
     };
 
     let options = PrOptions::new(
@@ -185,6 +207,17 @@ async fn test_create_pr_workspace_commit_message_fallback() {
         tags: Vec::new(),
         branch: None,
         config_dir: None,
+        path_strategy: Default::default(),
+        default_branch: None,
+        sparse: Vec::new(),
+        provider: None,
+        provider_url: None,
+        provider_defaults: Default::default(),
+        git: Default::default(),
+        depends_on: Vec::new(),
+        post_clone: None,
+        archived: false,
+        remotes: Default::default(),
     };
 
     // Options without commit_msg to test fallback to title
@@ -254,6 +287,17 @@ async fn test_create_pr_workspace_branch_name_generation() {
         tags: Vec::new(),
         branch: None,
         config_dir: None,
+        path_strategy: Default::default(),
+        default_branch: None,
+        sparse: Vec::new(),
+        provider: None,
+        provider_url: None,
+        provider_defaults: Default::default(),
+        git: Default::default(),
+        depends_on: Vec::new(),
+        post_clone: None,
+        archived: false,
+        remotes: Default::default(),
     };
 
     // Options without branch_name to test auto-generation
@@ -294,6 +338,17 @@ async fn test_create_pr_workspace_git_operations_error_paths() {
         tags: Vec::new(),
         branch: None,
         config_dir: None,
+        path_strategy: Default::default(),
+        default_branch: None,
+        sparse: Vec::new(),
+        provider: None,
+        provider_url: None,
+        provider_defaults: Default::default(),
+        git: Default::default(),
+        depends_on: Vec::new(),
+        post_clone: None,
+        archived: false,
+        remotes: Default::default(),
     };
 
     let options = PrOptions::new(
@@ -341,6 +396,17 @@ async fn test_create_pr_workspace_custom_branch_and_commit() {
         tags: Vec::new(),
         branch: None,
         config_dir: None,
+        path_strategy: Default::default(),
+        default_branch: None,
+        sparse: Vec::new(),
+        provider: None,
+        provider_url: None,
+        provider_defaults: Default::default(),
+        git: Default::default(),
+        depends_on: Vec::new(),
+        post_clone: None,
+        archived: false,
+        remotes: Default::default(),
     };
 
     // Options with custom branch name and commit message
@@ -415,6 +481,17 @@ async fn test_github_integration_auth_client_api() {
         tags: Vec::new(),
         branch: None,
         config_dir: None,
+        path_strategy: Default::default(),
+        default_branch: None,
+        sparse: Vec::new(),
+        provider: None,
+        provider_url: None,
+        provider_defaults: Default::default(),
+        git: Default::default(),
+        depends_on: Vec::new(),
+        post_clone: None,
+        archived: false,
+        remotes: Default::default(),
     };
 
     let options = PrOptions::new(
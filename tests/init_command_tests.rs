@@ -41,6 +41,9 @@ async fn test_init_command_basic_creation() {
         output: output_path.to_string_lossy().to_string(),
         overwrite: false,
         supplement: false,
+        remote: None,
+        interactive: false,
+        import: None,
     };
 
     let context = CommandContext {
@@ -84,6 +87,9 @@ async fn test_init_command_overwrite_existing_file() {
         output: output_path.to_string_lossy().to_string(),
         overwrite: true, // Should overwrite
         supplement: false,
+        remote: None,
+        interactive: false,
+        import: None,
     };
 
     let context = CommandContext {
@@ -122,6 +128,9 @@ async fn test_init_command_no_overwrite_existing_file() {
         output: output_path.to_string_lossy().to_string(),
         overwrite: false, // Should not overwrite
         supplement: false,
+        remote: None,
+        interactive: false,
+        import: None,
     };
 
     let context = CommandContext {
@@ -166,6 +175,9 @@ async fn test_init_command_with_git_repository() {
         output: output_path.to_string_lossy().to_string(),
         overwrite: false,
         supplement: false,
+        remote: None,
+        interactive: false,
+        import: None,
     };
 
     let context = CommandContext {
@@ -202,6 +214,23 @@ async fn test_init_command_supplement_with_duplicate_repository() {
             "git@github.com:owner/test-repo.git".to_string(),
         )],
         recipes: vec![],
+        artifacts_remote: None,
+        log_sinks: Vec::new(),
+        path_strategy: Default::default(),
+        root: None,
+        files: Vec::new(),
+        branch_protection: Vec::new(),
+        repository_settings: Vec::new(),
+        post_clone: Vec::new(),
+        pr: None,
+        provider: None,
+        provider_url: None,
+        git: Default::default(),
+        exit_codes: Default::default(),
+        notify: None,
+        profiles: Default::default(),
+        secrets: None,
+        profile_token: None,
     };
     existing_config
         .save(&output_path.to_string_lossy())
@@ -217,6 +246,9 @@ async fn test_init_command_supplement_with_duplicate_repository() {
         output: output_path.to_string_lossy().to_string(),
         overwrite: false,
         supplement: true, // Should supplement but skip duplicates
+        remote: None,
+        interactive: false,
+        import: None,
     };
 
     let context = CommandContext {
@@ -252,6 +284,23 @@ async fn test_init_command_supplement_with_new_repository() {
             "git@github.com:owner/existing-repo.git".to_string(),
         )],
         recipes: vec![],
+        artifacts_remote: None,
+        log_sinks: Vec::new(),
+        path_strategy: Default::default(),
+        root: None,
+        files: Vec::new(),
+        branch_protection: Vec::new(),
+        repository_settings: Vec::new(),
+        post_clone: Vec::new(),
+        pr: None,
+        provider: None,
+        provider_url: None,
+        git: Default::default(),
+        exit_codes: Default::default(),
+        notify: None,
+        profiles: Default::default(),
+        secrets: None,
+        profile_token: None,
     };
     existing_config
         .save(&output_path.to_string_lossy())
@@ -267,6 +316,9 @@ async fn test_init_command_supplement_with_new_repository() {
         output: output_path.to_string_lossy().to_string(),
         overwrite: false,
         supplement: true, // Should supplement with new repo
+        remote: None,
+        interactive: false,
+        import: None,
     };
 
     let context = CommandContext {
@@ -314,6 +366,9 @@ async fn test_init_command_git_directory_edge_cases() {
         output: output_path.to_string_lossy().to_string(),
         overwrite: false,
         supplement: false,
+        remote: None,
+        interactive: false,
+        import: None,
     };
 
     let context = CommandContext {
@@ -346,6 +401,9 @@ async fn test_init_command_empty_directory() {
         output: output_path.to_string_lossy().to_string(),
         overwrite: false,
         supplement: false,
+        remote: None,
+        interactive: false,
+        import: None,
     };
 
     let context = CommandContext {
@@ -392,6 +450,9 @@ async fn test_init_command_multiple_git_repositories() {
         output: output_path.to_string_lossy().to_string(),
         overwrite: false,
         supplement: false,
+        remote: None,
+        interactive: false,
+        import: None,
     };
 
     let context = CommandContext {
@@ -447,6 +508,9 @@ async fn test_init_command_integration_flow() {
         output: output_path.to_string_lossy().to_string(),
         overwrite: false,
         supplement: false,
+        remote: None,
+        interactive: false,
+        import: None,
     };
 
     let context = CommandContext {
@@ -503,6 +567,9 @@ async fn test_init_command_discovers_repos_two_levels_deep() {
         output: output_path.to_string_lossy().to_string(),
         overwrite: false,
         supplement: false,
+        remote: None,
+        interactive: false,
+        import: None,
     };
 
     let context = CommandContext {
@@ -596,6 +663,9 @@ async fn test_init_command_depth_boundary() {
         output: output_path.to_string_lossy().to_string(),
         overwrite: false,
         supplement: false,
+        remote: None,
+        interactive: false,
+        import: None,
     };
 
     let context = CommandContext {
@@ -632,3 +702,49 @@ async fn test_init_command_depth_boundary() {
     assert!(repo_names.contains(&"repo3"));
     assert!(!repo_names.contains(&"repo4")); // Should not be discovered
 }
+
+#[tokio::test]
+async fn test_init_command_imports_repo_manifest() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_path = temp_dir.path().join("repos.yaml");
+    let manifest = temp_dir.path().join("default.xml");
+    fs::write(
+        &manifest,
+        r#"<manifest>
+  <remote name="github" fetch="git@github.com:org" />
+  <default remote="github" revision="main" />
+  <project name="api" groups="backend" />
+  <project name="web" path="frontend/web" />
+</manifest>"#,
+    )
+    .unwrap();
+
+    let command = InitCommand {
+        output: output_path.to_string_lossy().to_string(),
+        overwrite: false,
+        supplement: false,
+        remote: None,
+        interactive: false,
+        import: Some(manifest),
+    };
+
+    let context = CommandContext {
+        config: Config::new(),
+        tag: vec![],
+        exclude_tag: vec![],
+        repos: None,
+        parallel: false,
+    };
+
+    command.execute(&context).await.unwrap();
+
+    let config = Config::load(&output_path.to_string_lossy()).unwrap();
+    assert_eq!(config.repositories.len(), 2);
+    let api = config.get_repository("api").unwrap();
+    assert_eq!(api.url, "git@github.com:org/api");
+    assert_eq!(api.branch.as_deref(), Some("main"));
+    assert_eq!(api.tags, vec!["backend"]);
+    assert_eq!(api.path, None);
+    let web = config.get_repository("web").unwrap();
+    assert_eq!(web.path.as_deref(), Some("frontend/web"));
+}
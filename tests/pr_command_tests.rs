@@ -4,6 +4,9 @@
 use repos::commands::pr::PrCommand;
 use repos::commands::{Command, CommandContext};
 use repos::config::{Config, Repository};
+use repos::credentials::Credentials;
+use repos::github::placeholders::Placeholders;
+use tempfile::TempDir;
 
 /// Helper function to create a test config with repositories
 fn create_test_config() -> Config {
@@ -31,6 +34,23 @@ fn create_test_config() -> Config {
     Config {
         repositories: vec![repo1, repo2, repo3],
         recipes: vec![],
+        artifacts_remote: None,
+        log_sinks: Vec::new(),
+        path_strategy: Default::default(),
+        root: None,
+        files: Vec::new(),
+        branch_protection: Vec::new(),
+        repository_settings: Vec::new(),
+        post_clone: Vec::new(),
+        pr: None,
+        provider: None,
+        provider_url: None,
+        git: Default::default(),
+        exit_codes: Default::default(),
+        notify: None,
+        profiles: Default::default(),
+        secrets: None,
+        profile_token: None,
     }
 }
 
@@ -56,6 +76,7 @@ async fn test_pr_command_basic_execution() {
     let config = create_test_config();
     let context = create_test_context(config, vec![], vec![], None, false);
 
+    let output_dir = TempDir::new().unwrap();
     let pr_command = PrCommand {
         title: "Test PR".to_string(),
         body: "Test body".to_string(),
@@ -65,6 +86,21 @@ async fn test_pr_command_basic_execution() {
         draft: false,
         token: "fake-token".to_string(),
         create_only: true, // Avoid actual GitHub API calls
+        template: None,
+        auto_merge: None,
+        review_team: None,
+        balance: false,
+        all_or_nothing: false,
+        worktrees: None,
+        duplicates: None,
+        existing: None,
+        placeholders: Placeholders::default(),
+        change_summary: false,
+        batch_size: None,
+        batch_delay: None,
+        output_dir: output_dir.path().to_path_buf(),
+        failure_policy: Default::default(),
+        credentials: Credentials::default(),
     };
 
     // Should not panic and complete execution
@@ -78,6 +114,7 @@ async fn test_pr_command_with_tag_filter() {
     let config = create_test_config();
     let context = create_test_context(config, vec!["backend".to_string()], vec![], None, false);
 
+    let output_dir = TempDir::new().unwrap();
     let pr_command = PrCommand {
         title: "Backend PR".to_string(),
         body: "Backend changes".to_string(),
@@ -87,6 +124,21 @@ async fn test_pr_command_with_tag_filter() {
         draft: false,
         token: "fake-token".to_string(),
         create_only: true,
+        template: None,
+        auto_merge: None,
+        review_team: None,
+        balance: false,
+        all_or_nothing: false,
+        worktrees: None,
+        duplicates: None,
+        existing: None,
+        placeholders: Placeholders::default(),
+        change_summary: false,
+        batch_size: None,
+        batch_delay: None,
+        output_dir: output_dir.path().to_path_buf(),
+        failure_policy: Default::default(),
+        credentials: Credentials::default(),
     };
 
     let result = pr_command.execute(&context).await;
@@ -104,6 +156,7 @@ async fn test_pr_command_with_specific_repos() {
         false,
     );
 
+    let output_dir = TempDir::new().unwrap();
     let pr_command = PrCommand {
         title: "Specific repos PR".to_string(),
         body: "Changes for specific repos".to_string(),
@@ -113,6 +166,21 @@ async fn test_pr_command_with_specific_repos() {
         draft: false,
         token: "fake-token".to_string(),
         create_only: true,
+        template: None,
+        auto_merge: None,
+        review_team: None,
+        balance: false,
+        all_or_nothing: false,
+        worktrees: None,
+        duplicates: None,
+        existing: None,
+        placeholders: Placeholders::default(),
+        change_summary: false,
+        batch_size: None,
+        batch_delay: None,
+        output_dir: output_dir.path().to_path_buf(),
+        failure_policy: Default::default(),
+        credentials: Credentials::default(),
     };
 
     let result = pr_command.execute(&context).await;
@@ -130,6 +198,7 @@ async fn test_pr_command_with_tag_and_repos_filter() {
         false,
     );
 
+    let output_dir = TempDir::new().unwrap();
     let pr_command = PrCommand {
         title: "Filtered PR".to_string(),
         body: "Changes for filtered repos".to_string(),
@@ -139,6 +208,21 @@ async fn test_pr_command_with_tag_and_repos_filter() {
         draft: false,
         token: "fake-token".to_string(),
         create_only: true,
+        template: None,
+        auto_merge: None,
+        review_team: None,
+        balance: false,
+        all_or_nothing: false,
+        worktrees: None,
+        duplicates: None,
+        existing: None,
+        placeholders: Placeholders::default(),
+        change_summary: false,
+        batch_size: None,
+        batch_delay: None,
+        output_dir: output_dir.path().to_path_buf(),
+        failure_policy: Default::default(),
+        credentials: Credentials::default(),
     };
 
     let result = pr_command.execute(&context).await;
@@ -150,6 +234,7 @@ async fn test_pr_command_no_matching_repositories() {
     let config = create_test_config();
     let context = create_test_context(config, vec!["nonexistent".to_string()], vec![], None, false);
 
+    let output_dir = TempDir::new().unwrap();
     let pr_command = PrCommand {
         title: "No repos PR".to_string(),
         body: "Should find no repos".to_string(),
@@ -159,6 +244,21 @@ async fn test_pr_command_no_matching_repositories() {
         draft: false,
         token: "fake-token".to_string(),
         create_only: true,
+        template: None,
+        auto_merge: None,
+        review_team: None,
+        balance: false,
+        all_or_nothing: false,
+        worktrees: None,
+        duplicates: None,
+        existing: None,
+        placeholders: Placeholders::default(),
+        change_summary: false,
+        batch_size: None,
+        batch_delay: None,
+        output_dir: output_dir.path().to_path_buf(),
+        failure_policy: Default::default(),
+        credentials: Credentials::default(),
     };
 
     // Should succeed (print message about no repos found)
@@ -171,9 +271,27 @@ async fn test_pr_command_empty_repositories() {
     let config = Config {
         repositories: vec![],
         recipes: vec![],
+        artifacts_remote: None,
+        log_sinks: Vec::new(),
+        path_strategy: Default::default(),
+        root: None,
+        files: Vec::new(),
+        branch_protection: Vec::new(),
+        repository_settings: Vec::new(),
+        post_clone: Vec::new(),
+        pr: None,
+        provider: None,
+        provider_url: None,
+        git: Default::default(),
+        exit_codes: Default::default(),
+        notify: None,
+        profiles: Default::default(),
+        secrets: None,
+        profile_token: None,
     };
     let context = create_test_context(config, vec![], vec![], None, false);
 
+    let output_dir = TempDir::new().unwrap();
     let pr_command = PrCommand {
         title: "Empty config PR".to_string(),
         body: "No repositories in config".to_string(),
@@ -183,6 +301,21 @@ async fn test_pr_command_empty_repositories() {
         draft: false,
         token: "fake-token".to_string(),
         create_only: true,
+        template: None,
+        auto_merge: None,
+        review_team: None,
+        balance: false,
+        all_or_nothing: false,
+        worktrees: None,
+        duplicates: None,
+        existing: None,
+        placeholders: Placeholders::default(),
+        change_summary: false,
+        batch_size: None,
+        batch_delay: None,
+        output_dir: output_dir.path().to_path_buf(),
+        failure_policy: Default::default(),
+        credentials: Credentials::default(),
     };
 
     // Should succeed (print message about no repos found)
@@ -195,6 +328,7 @@ async fn test_pr_command_parallel_execution() {
     let config = create_test_config();
     let context = create_test_context(config, vec![], vec![], None, true); // parallel = true
 
+    let output_dir = TempDir::new().unwrap();
     let pr_command = PrCommand {
         title: "Parallel PR".to_string(),
         body: "Parallel execution test".to_string(),
@@ -204,6 +338,21 @@ async fn test_pr_command_parallel_execution() {
         draft: false,
         token: "fake-token".to_string(),
         create_only: true,
+        template: None,
+        auto_merge: None,
+        review_team: None,
+        balance: false,
+        all_or_nothing: false,
+        worktrees: None,
+        duplicates: None,
+        existing: None,
+        placeholders: Placeholders::default(),
+        change_summary: false,
+        batch_size: None,
+        batch_delay: None,
+        output_dir: output_dir.path().to_path_buf(),
+        failure_policy: Default::default(),
+        credentials: Credentials::default(),
     };
 
     let result = pr_command.execute(&context).await;
@@ -215,6 +364,7 @@ async fn test_pr_command_with_custom_branch_name() {
     let config = create_test_config();
     let context = create_test_context(config, vec![], vec![], None, false);
 
+    let output_dir = TempDir::new().unwrap();
     let pr_command = PrCommand {
         title: "Custom branch PR".to_string(),
         body: "PR with custom branch".to_string(),
@@ -224,6 +374,21 @@ async fn test_pr_command_with_custom_branch_name() {
         draft: false,
         token: "fake-token".to_string(),
         create_only: true,
+        template: None,
+        auto_merge: None,
+        review_team: None,
+        balance: false,
+        all_or_nothing: false,
+        worktrees: None,
+        duplicates: None,
+        existing: None,
+        placeholders: Placeholders::default(),
+        change_summary: false,
+        batch_size: None,
+        batch_delay: None,
+        output_dir: output_dir.path().to_path_buf(),
+        failure_policy: Default::default(),
+        credentials: Credentials::default(),
     };
 
     let result = pr_command.execute(&context).await;
@@ -235,6 +400,7 @@ async fn test_pr_command_with_custom_base_branch() {
     let config = create_test_config();
     let context = create_test_context(config, vec![], vec![], None, false);
 
+    let output_dir = TempDir::new().unwrap();
     let pr_command = PrCommand {
         title: "Custom base PR".to_string(),
         body: "PR with custom base branch".to_string(),
@@ -244,6 +410,21 @@ async fn test_pr_command_with_custom_base_branch() {
         draft: false,
         token: "fake-token".to_string(),
         create_only: true,
+        template: None,
+        auto_merge: None,
+        review_team: None,
+        balance: false,
+        all_or_nothing: false,
+        worktrees: None,
+        duplicates: None,
+        existing: None,
+        placeholders: Placeholders::default(),
+        change_summary: false,
+        batch_size: None,
+        batch_delay: None,
+        output_dir: output_dir.path().to_path_buf(),
+        failure_policy: Default::default(),
+        credentials: Credentials::default(),
     };
 
     let result = pr_command.execute(&context).await;
@@ -255,6 +436,7 @@ async fn test_pr_command_with_custom_commit_message() {
     let config = create_test_config();
     let context = create_test_context(config, vec![], vec![], None, false);
 
+    let output_dir = TempDir::new().unwrap();
     let pr_command = PrCommand {
         title: "Custom commit PR".to_string(),
         body: "PR with custom commit message".to_string(),
@@ -264,6 +446,21 @@ async fn test_pr_command_with_custom_commit_message() {
         draft: false,
         token: "fake-token".to_string(),
         create_only: true,
+        template: None,
+        auto_merge: None,
+        review_team: None,
+        balance: false,
+        all_or_nothing: false,
+        worktrees: None,
+        duplicates: None,
+        existing: None,
+        placeholders: Placeholders::default(),
+        change_summary: false,
+        batch_size: None,
+        batch_delay: None,
+        output_dir: output_dir.path().to_path_buf(),
+        failure_policy: Default::default(),
+        credentials: Credentials::default(),
     };
 
     let result = pr_command.execute(&context).await;
@@ -275,6 +472,7 @@ async fn test_pr_command_draft_mode() {
     let config = create_test_config();
     let context = create_test_context(config, vec![], vec![], None, false);
 
+    let output_dir = TempDir::new().unwrap();
     let pr_command = PrCommand {
         title: "Draft PR".to_string(),
         body: "Draft pull request".to_string(),
@@ -284,6 +482,21 @@ async fn test_pr_command_draft_mode() {
         draft: true,
         token: "fake-token".to_string(),
         create_only: true,
+        template: None,
+        auto_merge: None,
+        review_team: None,
+        balance: false,
+        all_or_nothing: false,
+        worktrees: None,
+        duplicates: None,
+        existing: None,
+        placeholders: Placeholders::default(),
+        change_summary: false,
+        batch_size: None,
+        batch_delay: None,
+        output_dir: output_dir.path().to_path_buf(),
+        failure_policy: Default::default(),
+        credentials: Credentials::default(),
     };
 
     let result = pr_command.execute(&context).await;
@@ -295,6 +508,7 @@ async fn test_pr_command_create_only_mode() {
     let config = create_test_config();
     let context = create_test_context(config, vec![], vec![], None, false);
 
+    let output_dir = TempDir::new().unwrap();
     let pr_command = PrCommand {
         title: "Create only PR".to_string(),
         body: "Create only mode test".to_string(),
@@ -304,6 +518,21 @@ async fn test_pr_command_create_only_mode() {
         draft: false,
         token: "fake-token".to_string(),
         create_only: true,
+        template: None,
+        auto_merge: None,
+        review_team: None,
+        balance: false,
+        all_or_nothing: false,
+        worktrees: None,
+        duplicates: None,
+        existing: None,
+        placeholders: Placeholders::default(),
+        change_summary: false,
+        batch_size: None,
+        batch_delay: None,
+        output_dir: output_dir.path().to_path_buf(),
+        failure_policy: Default::default(),
+        credentials: Credentials::default(),
     };
 
     let result = pr_command.execute(&context).await;
@@ -315,6 +544,7 @@ async fn test_pr_command_without_create_only() {
     let config = create_test_config();
     let context = create_test_context(config, vec![], vec![], None, false);
 
+    let output_dir = TempDir::new().unwrap();
     let pr_command = PrCommand {
         title: "Full PR".to_string(),
         body: "Full PR creation test".to_string(),
@@ -324,6 +554,21 @@ async fn test_pr_command_without_create_only() {
         draft: false,
         token: "fake-token".to_string(),
         create_only: false, // This will try to push and create actual PR
+        template: None,
+        auto_merge: None,
+        review_team: None,
+        balance: false,
+        all_or_nothing: false,
+        worktrees: None,
+        duplicates: None,
+        existing: None,
+        placeholders: Placeholders::default(),
+        change_summary: false,
+        batch_size: None,
+        batch_delay: None,
+        output_dir: output_dir.path().to_path_buf(),
+        failure_policy: Default::default(),
+        credentials: Credentials::default(),
     };
 
     // This should fail since we're using a fake token
@@ -336,6 +581,7 @@ async fn test_pr_command_empty_token() {
     let config = create_test_config();
     let context = create_test_context(config, vec![], vec![], None, false);
 
+    let output_dir = TempDir::new().unwrap();
     let pr_command = PrCommand {
         title: "Empty token PR".to_string(),
         body: "PR with empty token".to_string(),
@@ -345,6 +591,21 @@ async fn test_pr_command_empty_token() {
         draft: false,
         token: "".to_string(), // Empty token
         create_only: true,
+        template: None,
+        auto_merge: None,
+        review_team: None,
+        balance: false,
+        all_or_nothing: false,
+        worktrees: None,
+        duplicates: None,
+        existing: None,
+        placeholders: Placeholders::default(),
+        change_summary: false,
+        batch_size: None,
+        batch_delay: None,
+        output_dir: output_dir.path().to_path_buf(),
+        failure_policy: Default::default(),
+        credentials: Credentials::default(),
     };
 
     let result = pr_command.execute(&context).await;
@@ -356,6 +617,7 @@ async fn test_pr_command_special_characters_in_title() {
     let config = create_test_config();
     let context = create_test_context(config, vec![], vec![], None, false);
 
+    let output_dir = TempDir::new().unwrap();
     let pr_command = PrCommand {
         title: "PR with special chars: 你好 🚀 @#$%".to_string(),
         body: "Body with\nmultiple\nlines".to_string(),
@@ -365,6 +627,21 @@ async fn test_pr_command_special_characters_in_title() {
         draft: false,
         token: "fake-token".to_string(),
         create_only: true,
+        template: None,
+        auto_merge: None,
+        review_team: None,
+        balance: false,
+        all_or_nothing: false,
+        worktrees: None,
+        duplicates: None,
+        existing: None,
+        placeholders: Placeholders::default(),
+        change_summary: false,
+        batch_size: None,
+        batch_delay: None,
+        output_dir: output_dir.path().to_path_buf(),
+        failure_policy: Default::default(),
+        credentials: Credentials::default(),
     };
 
     let result = pr_command.execute(&context).await;
@@ -377,6 +654,7 @@ async fn test_pr_command_very_long_title() {
     let context = create_test_context(config, vec![], vec![], None, false);
 
     let long_title = "A".repeat(1000);
+    let output_dir = TempDir::new().unwrap();
     let pr_command = PrCommand {
         title: long_title,
         body: "PR with very long title".to_string(),
@@ -386,6 +664,21 @@ async fn test_pr_command_very_long_title() {
         draft: false,
         token: "fake-token".to_string(),
         create_only: true,
+        template: None,
+        auto_merge: None,
+        review_team: None,
+        balance: false,
+        all_or_nothing: false,
+        worktrees: None,
+        duplicates: None,
+        existing: None,
+        placeholders: Placeholders::default(),
+        change_summary: false,
+        batch_size: None,
+        batch_delay: None,
+        output_dir: output_dir.path().to_path_buf(),
+        failure_policy: Default::default(),
+        credentials: Credentials::default(),
     };
 
     let result = pr_command.execute(&context).await;
@@ -398,6 +691,7 @@ async fn test_pr_command_very_long_body() {
     let context = create_test_context(config, vec![], vec![], None, false);
 
     let long_body = "B".repeat(10000);
+    let output_dir = TempDir::new().unwrap();
     let pr_command = PrCommand {
         title: "PR with long body".to_string(),
         body: long_body,
@@ -407,6 +701,21 @@ async fn test_pr_command_very_long_body() {
         draft: false,
         token: "fake-token".to_string(),
         create_only: true,
+        template: None,
+        auto_merge: None,
+        review_team: None,
+        balance: false,
+        all_or_nothing: false,
+        worktrees: None,
+        duplicates: None,
+        existing: None,
+        placeholders: Placeholders::default(),
+        change_summary: false,
+        batch_size: None,
+        batch_delay: None,
+        output_dir: output_dir.path().to_path_buf(),
+        failure_policy: Default::default(),
+        credentials: Credentials::default(),
     };
 
     let result = pr_command.execute(&context).await;
@@ -424,6 +733,7 @@ async fn test_pr_command_all_options_combined() {
         true, // parallel
     );
 
+    let output_dir = TempDir::new().unwrap();
     let pr_command = PrCommand {
         title: "Full options PR".to_string(),
         body: "PR with all options set".to_string(),
@@ -433,6 +743,21 @@ async fn test_pr_command_all_options_combined() {
         draft: true,
         token: "fake-token".to_string(),
         create_only: true,
+        template: None,
+        auto_merge: None,
+        review_team: None,
+        balance: false,
+        all_or_nothing: false,
+        worktrees: None,
+        duplicates: None,
+        existing: None,
+        placeholders: Placeholders::default(),
+        change_summary: false,
+        batch_size: None,
+        batch_delay: None,
+        output_dir: output_dir.path().to_path_buf(),
+        failure_policy: Default::default(),
+        credentials: Credentials::default(),
     };
 
     let result = pr_command.execute(&context).await;
@@ -450,6 +775,7 @@ async fn test_pr_command_invalid_repository_names() {
         false,
     );
 
+    let output_dir = TempDir::new().unwrap();
     let pr_command = PrCommand {
         title: "Invalid repos PR".to_string(),
         body: "PR for nonexistent repos".to_string(),
@@ -459,6 +785,21 @@ async fn test_pr_command_invalid_repository_names() {
         draft: false,
         token: "fake-token".to_string(),
         create_only: true,
+        template: None,
+        auto_merge: None,
+        review_team: None,
+        balance: false,
+        all_or_nothing: false,
+        worktrees: None,
+        duplicates: None,
+        existing: None,
+        placeholders: Placeholders::default(),
+        change_summary: false,
+        batch_size: None,
+        batch_delay: None,
+        output_dir: output_dir.path().to_path_buf(),
+        failure_policy: Default::default(),
+        credentials: Credentials::default(),
     };
 
     // Should succeed (print message about no repos found)
@@ -481,6 +822,7 @@ async fn test_pr_command_mixed_valid_invalid_repos() {
         false,
     );
 
+    let output_dir = TempDir::new().unwrap();
     let pr_command = PrCommand {
         title: "Mixed repos PR".to_string(),
         body: "PR for mix of valid and invalid repos".to_string(),
@@ -490,6 +832,21 @@ async fn test_pr_command_mixed_valid_invalid_repos() {
         draft: false,
         token: "fake-token".to_string(),
         create_only: true,
+        template: None,
+        auto_merge: None,
+        review_team: None,
+        balance: false,
+        all_or_nothing: false,
+        worktrees: None,
+        duplicates: None,
+        existing: None,
+        placeholders: Placeholders::default(),
+        change_summary: false,
+        batch_size: None,
+        batch_delay: None,
+        output_dir: output_dir.path().to_path_buf(),
+        failure_policy: Default::default(),
+        credentials: Credentials::default(),
     };
 
     let result = pr_command.execute(&context).await;
@@ -501,6 +858,7 @@ async fn test_pr_command_case_sensitive_tag_filter() {
     let config = create_test_config();
     let context = create_test_context(config, vec!["BACKEND".to_string()], vec![], None, false);
 
+    let output_dir = TempDir::new().unwrap();
     let pr_command = PrCommand {
         title: "Case sensitive PR".to_string(),
         body: "Testing case sensitivity".to_string(),
@@ -510,6 +868,21 @@ async fn test_pr_command_case_sensitive_tag_filter() {
         draft: false,
         token: "fake-token".to_string(),
         create_only: true,
+        template: None,
+        auto_merge: None,
+        review_team: None,
+        balance: false,
+        all_or_nothing: false,
+        worktrees: None,
+        duplicates: None,
+        existing: None,
+        placeholders: Placeholders::default(),
+        change_summary: false,
+        batch_size: None,
+        batch_delay: None,
+        output_dir: output_dir.path().to_path_buf(),
+        failure_policy: Default::default(),
+        credentials: Credentials::default(),
     };
 
     // Should find no repos because tags are case sensitive
@@ -528,6 +901,7 @@ async fn test_pr_command_case_sensitive_repo_names() {
         false,
     );
 
+    let output_dir = TempDir::new().unwrap();
     let pr_command = PrCommand {
         title: "Case sensitive repos PR".to_string(),
         body: "Testing repo name case sensitivity".to_string(),
@@ -537,6 +911,21 @@ async fn test_pr_command_case_sensitive_repo_names() {
         draft: false,
         token: "fake-token".to_string(),
         create_only: true,
+        template: None,
+        auto_merge: None,
+        review_team: None,
+        balance: false,
+        all_or_nothing: false,
+        worktrees: None,
+        duplicates: None,
+        existing: None,
+        placeholders: Placeholders::default(),
+        change_summary: false,
+        batch_size: None,
+        batch_delay: None,
+        output_dir: output_dir.path().to_path_buf(),
+        failure_policy: Default::default(),
+        credentials: Credentials::default(),
     };
 
     // Should find no repos because repo names are case sensitive
@@ -555,6 +944,7 @@ async fn test_pr_command_with_exclude_tag() {
         false,
     );
 
+    let output_dir = TempDir::new().unwrap();
     let pr_command = PrCommand {
         title: "Backend only PR".to_string(),
         body: "Excludes frontend repos".to_string(),
@@ -564,6 +954,21 @@ async fn test_pr_command_with_exclude_tag() {
         draft: false,
         token: "fake-token".to_string(),
         create_only: true,
+        template: None,
+        auto_merge: None,
+        review_team: None,
+        balance: false,
+        all_or_nothing: false,
+        worktrees: None,
+        duplicates: None,
+        existing: None,
+        placeholders: Placeholders::default(),
+        change_summary: false,
+        batch_size: None,
+        batch_delay: None,
+        output_dir: output_dir.path().to_path_buf(),
+        failure_policy: Default::default(),
+        credentials: Credentials::default(),
     };
 
     // Should only work with backend repos (repo2, repo3)
@@ -582,6 +987,7 @@ async fn test_pr_command_with_multiple_exclude_tags() {
         false,
     );
 
+    let output_dir = TempDir::new().unwrap();
     let pr_command = PrCommand {
         title: "Rust only PR".to_string(),
         body: "Excludes frontend and database repos".to_string(),
@@ -591,6 +997,21 @@ async fn test_pr_command_with_multiple_exclude_tags() {
         draft: false,
         token: "fake-token".to_string(),
         create_only: true,
+        template: None,
+        auto_merge: None,
+        review_team: None,
+        balance: false,
+        all_or_nothing: false,
+        worktrees: None,
+        duplicates: None,
+        existing: None,
+        placeholders: Placeholders::default(),
+        change_summary: false,
+        batch_size: None,
+        batch_delay: None,
+        output_dir: output_dir.path().to_path_buf(),
+        failure_policy: Default::default(),
+        credentials: Credentials::default(),
     };
 
     // Should only work with repo2 (rust backend, no database tag)
@@ -609,6 +1030,7 @@ async fn test_pr_command_with_inclusion_and_exclusion() {
         false,
     );
 
+    let output_dir = TempDir::new().unwrap();
     let pr_command = PrCommand {
         title: "Backend no DB PR".to_string(),
         body: "Backend repos but not database ones".to_string(),
@@ -618,6 +1040,21 @@ async fn test_pr_command_with_inclusion_and_exclusion() {
         draft: false,
         token: "fake-token".to_string(),
         create_only: true,
+        template: None,
+        auto_merge: None,
+        review_team: None,
+        balance: false,
+        all_or_nothing: false,
+        worktrees: None,
+        duplicates: None,
+        existing: None,
+        placeholders: Placeholders::default(),
+        change_summary: false,
+        batch_size: None,
+        batch_delay: None,
+        output_dir: output_dir.path().to_path_buf(),
+        failure_policy: Default::default(),
+        credentials: Credentials::default(),
     };
 
     // Should only work with repo2 (backend but not database)
@@ -636,6 +1073,7 @@ async fn test_pr_command_exclude_all_repos() {
         false,
     );
 
+    let output_dir = TempDir::new().unwrap();
     let pr_command = PrCommand {
         title: "No repos PR".to_string(),
         body: "Should exclude all repos".to_string(),
@@ -645,6 +1083,21 @@ async fn test_pr_command_exclude_all_repos() {
         draft: false,
         token: "fake-token".to_string(),
         create_only: true,
+        template: None,
+        auto_merge: None,
+        review_team: None,
+        balance: false,
+        all_or_nothing: false,
+        worktrees: None,
+        duplicates: None,
+        existing: None,
+        placeholders: Placeholders::default(),
+        change_summary: false,
+        batch_size: None,
+        batch_delay: None,
+        output_dir: output_dir.path().to_path_buf(),
+        failure_policy: Default::default(),
+        credentials: Credentials::default(),
     };
 
     // Should find no repos
@@ -663,6 +1116,7 @@ async fn test_pr_command_multiple_inclusion_tags() {
         false,
     );
 
+    let output_dir = TempDir::new().unwrap();
     let pr_command = PrCommand {
         title: "Multi-tag PR".to_string(),
         body: "Includes repos with frontend OR rust tags".to_string(),
@@ -672,9 +1126,108 @@ async fn test_pr_command_multiple_inclusion_tags() {
         draft: false,
         token: "fake-token".to_string(),
         create_only: true,
+        template: None,
+        auto_merge: None,
+        review_team: None,
+        balance: false,
+        all_or_nothing: false,
+        worktrees: None,
+        duplicates: None,
+        existing: None,
+        placeholders: Placeholders::default(),
+        change_summary: false,
+        batch_size: None,
+        batch_delay: None,
+        output_dir: output_dir.path().to_path_buf(),
+        failure_policy: Default::default(),
+        credentials: Credentials::default(),
     };
 
     // Should work with repo1 (frontend) and repo2 (rust)
     let result = pr_command.execute(&context).await;
     assert!(result.is_ok() || result.is_err());
 }
+
+#[tokio::test]
+async fn test_pr_command_from_worktree() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo_dir = temp_dir.path().join("repo1");
+    std::fs::create_dir_all(&repo_dir).unwrap();
+    let git = |args: &[&str]| {
+        let output = std::process::Command::new("git")
+            .args(args)
+            .current_dir(&repo_dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {:?} failed", args);
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    };
+    git(&["init"]);
+    git(&["config", "user.name", "Test User"]);
+    git(&["config", "user.email", "test@example.com"]);
+    std::fs::write(repo_dir.join("README.md"), "# repo1").unwrap();
+    git(&["add", "."]);
+    git(&["commit", "-m", "Initial commit"]);
+    let original_branch = git(&["rev-parse", "--abbrev-ref", "HEAD"]);
+
+    let mut repo = Repository::new(
+        "repo1".to_string(),
+        "git@github.com:owner/repo1.git".to_string(),
+    );
+    repo.path = Some(repo_dir.to_string_lossy().to_string());
+
+    // Changes left in the worktree by an earlier `repos run --use-worktree`
+    let worktrees_dir = temp_dir.path().join("worktrees");
+    let worktree = repos::git::open_worktree(&repo, &worktrees_dir).unwrap();
+    std::fs::write(
+        std::path::Path::new(&worktree.get_target_dir()).join("CHANGED.txt"),
+        "change",
+    )
+    .unwrap();
+    // Unrelated uncommitted work in the developer's checkout
+    std::fs::write(repo_dir.join("README.md"), "work in progress").unwrap();
+
+    let mut config = create_test_config();
+    config.repositories = vec![repo];
+    let context = create_test_context(config, vec![], vec![], None, false);
+
+    let output_dir = TempDir::new().unwrap();
+    let pr_command = PrCommand {
+        title: "Worktree PR".to_string(),
+        body: "Changes from a worktree".to_string(),
+        branch_name: Some("automated-change".to_string()),
+        base_branch: Some("main".to_string()),
+        commit_msg: Some("Add CHANGED.txt".to_string()),
+        draft: false,
+        token: "fake-token".to_string(),
+        create_only: true,
+        template: None,
+        auto_merge: None,
+        review_team: None,
+        balance: false,
+        all_or_nothing: false,
+        worktrees: Some(worktrees_dir.clone()),
+        duplicates: None,
+        existing: None,
+        placeholders: Placeholders::default(),
+        change_summary: false,
+        batch_size: None,
+        batch_delay: None,
+        output_dir: output_dir.path().to_path_buf(),
+        failure_policy: Default::default(),
+        credentials: Credentials::default(),
+    };
+    pr_command.execute(&context).await.unwrap();
+
+    // The commit is on the new branch, and the worktree is gone
+    assert_eq!(
+        git(&["show", "--name-only", "--format=", "automated-change"]),
+        "CHANGED.txt"
+    );
+    assert!(!worktrees_dir.join("repo1").exists());
+    assert_eq!(git(&["rev-parse", "--abbrev-ref", "HEAD"]), original_branch);
+    assert_eq!(
+        std::fs::read_to_string(repo_dir.join("README.md")).unwrap(),
+        "work in progress"
+    );
+}
@@ -1,9 +1,9 @@
 use repos::{
     commands::{
         Command, CommandContext,
-        run::{RunCommand, RunType},
+        run::{FailurePolicy, RunCommand, RunType},
     },
-    config::{Config, Recipe, Repository},
+    config::{Config, Recipe, RecipeStep, Repository},
 };
 use std::fs;
 use std::path::PathBuf;
@@ -67,17 +67,48 @@ fn setup_recipe_test(
         path: Some(repo_dir.to_string_lossy().to_string()),
         branch: None,
         config_dir: None,
+        path_strategy: Default::default(),
+        default_branch: None,
+        sparse: Vec::new(),
+        provider: None,
+        provider_url: None,
+        provider_defaults: Default::default(),
+        git: Default::default(),
+        depends_on: Vec::new(),
+        post_clone: None,
+        archived: false,
+        remotes: Default::default(),
     };
 
     let recipe = Recipe {
         name: recipe_name.to_string(),
-        steps: steps.into_iter().map(|s| s.to_string()).collect(),
+        steps: steps.into_iter().map(Into::into).collect(),
+        artifacts: vec![],
+        cache: false,
+        env: Default::default(),
     };
 
     let context = CommandContext {
         config: Config {
             repositories: vec![repo.clone()],
             recipes: vec![recipe.clone()],
+            artifacts_remote: None,
+            log_sinks: Vec::new(),
+            path_strategy: Default::default(),
+            root: None,
+            files: Vec::new(),
+            branch_protection: Vec::new(),
+            repository_settings: Vec::new(),
+            post_clone: Vec::new(),
+            pr: None,
+            provider: None,
+            provider_url: None,
+            git: Default::default(),
+            exit_codes: Default::default(),
+            notify: None,
+            profiles: Default::default(),
+            secrets: None,
+            profile_token: None,
         },
         tag: vec![],
         exclude_tag: vec![],
@@ -102,12 +133,40 @@ fn setup_basic_test(repo_name: &str) -> (TempDir, Repository, CommandContext) {
         path: Some(repo_dir.to_string_lossy().to_string()),
         branch: None,
         config_dir: None,
+        path_strategy: Default::default(),
+        default_branch: None,
+        sparse: Vec::new(),
+        provider: None,
+        provider_url: None,
+        provider_defaults: Default::default(),
+        git: Default::default(),
+        depends_on: Vec::new(),
+        post_clone: None,
+        archived: false,
+        remotes: Default::default(),
     };
 
     let context = CommandContext {
         config: Config {
             repositories: vec![repo.clone()],
             recipes: vec![],
+            artifacts_remote: None,
+            log_sinks: Vec::new(),
+            path_strategy: Default::default(),
+            root: None,
+            files: Vec::new(),
+            branch_protection: Vec::new(),
+            repository_settings: Vec::new(),
+            post_clone: Vec::new(),
+            pr: None,
+            provider: None,
+            provider_url: None,
+            git: Default::default(),
+            exit_codes: Default::default(),
+            notify: None,
+            profiles: Default::default(),
+            secrets: None,
+            profile_token: None,
         },
         tag: vec![],
         exclude_tag: vec![],
@@ -135,6 +194,17 @@ fn setup_parallel_test(
         path: Some(repo1_dir.to_string_lossy().to_string()),
         branch: None,
         config_dir: None,
+        path_strategy: Default::default(),
+        default_branch: None,
+        sparse: Vec::new(),
+        provider: None,
+        provider_url: None,
+        provider_defaults: Default::default(),
+        git: Default::default(),
+        depends_on: Vec::new(),
+        post_clone: None,
+        archived: false,
+        remotes: Default::default(),
     };
 
     let repo2_dir = temp_dir.path().join(repo2_name);
@@ -147,6 +217,17 @@ fn setup_parallel_test(
         path: Some(repo2_dir.to_string_lossy().to_string()),
         branch: None,
         config_dir: None,
+        path_strategy: Default::default(),
+        default_branch: None,
+        sparse: Vec::new(),
+        provider: None,
+        provider_url: None,
+        provider_defaults: Default::default(),
+        git: Default::default(),
+        depends_on: Vec::new(),
+        post_clone: None,
+        archived: false,
+        remotes: Default::default(),
     };
 
     let repos = vec![repo1, repo2];
@@ -154,6 +235,23 @@ fn setup_parallel_test(
         config: Config {
             repositories: repos.clone(),
             recipes: vec![],
+            artifacts_remote: None,
+            log_sinks: Vec::new(),
+            path_strategy: Default::default(),
+            root: None,
+            files: Vec::new(),
+            branch_protection: Vec::new(),
+            repository_settings: Vec::new(),
+            post_clone: Vec::new(),
+            pr: None,
+            provider: None,
+            provider_url: None,
+            git: Default::default(),
+            exit_codes: Default::default(),
+            notify: None,
+            profiles: Default::default(),
+            secrets: None,
+            profile_token: None,
         },
         tag: vec![],
         exclude_tag: vec![],
@@ -181,6 +279,17 @@ fn create_tagged_repo_setup(
         path: Some(repo_dir.to_string_lossy().to_string()),
         branch: None,
         config_dir: None,
+        path_strategy: Default::default(),
+        default_branch: None,
+        sparse: Vec::new(),
+        provider: None,
+        provider_url: None,
+        provider_defaults: Default::default(),
+        git: Default::default(),
+        depends_on: Vec::new(),
+        post_clone: None,
+        archived: false,
+        remotes: Default::default(),
     };
 
     (repo_dir, repo)
@@ -223,6 +332,23 @@ impl CommandContextBuilder {
             config: Config {
                 repositories: self.repositories,
                 recipes: self.recipes,
+                artifacts_remote: None,
+                log_sinks: Vec::new(),
+                path_strategy: Default::default(),
+                root: None,
+                files: Vec::new(),
+                branch_protection: Vec::new(),
+                repository_settings: Vec::new(),
+                post_clone: Vec::new(),
+                pr: None,
+                provider: None,
+                provider_url: None,
+                git: Default::default(),
+                exit_codes: Default::default(),
+                notify: None,
+                profiles: Default::default(),
+                secrets: None,
+                profile_token: None,
             },
             tag: self.tag,
             exclude_tag: self.exclude_tag,
@@ -239,11 +365,7 @@ impl CommandContextBuilder {
 /// Test basic RunCommand creation with command
 #[tokio::test]
 async fn test_run_command_creation() {
-    let command = RunCommand {
-        run_type: RunType::Command("echo hello".to_string()),
-        no_save: true,
-        output_dir: None,
-    };
+    let command = RunCommand::new_command("echo hello".to_string(), true, None);
 
     // Test that the run_type contains the right command
     match &command.run_type {
@@ -257,11 +379,7 @@ async fn test_run_command_creation() {
 /// Test recipe variant creation
 #[tokio::test]
 async fn test_run_command_recipe_creation() {
-    let command = RunCommand {
-        run_type: RunType::Recipe("test-recipe".to_string()),
-        no_save: false,
-        output_dir: None,
-    };
+    let command = RunCommand::new_recipe("test-recipe".to_string(), false, None);
 
     match &command.run_type {
         RunType::Recipe(recipe) => assert_eq!(recipe, "test-recipe"),
@@ -273,11 +391,7 @@ async fn test_run_command_recipe_creation() {
 #[tokio::test]
 async fn test_run_command_with_custom_output_dir() {
     let output_dir = PathBuf::from("/tmp/custom");
-    let command = RunCommand {
-        run_type: RunType::Command("ls".to_string()),
-        no_save: false,
-        output_dir: Some(output_dir.clone()),
-    };
+    let command = RunCommand::new_command("ls".to_string(), false, Some(output_dir.clone()));
 
     match &command.run_type {
         RunType::Command(cmd) => assert_eq!(cmd, "ls"),
@@ -289,16 +403,29 @@ async fn test_run_command_with_custom_output_dir() {
 
 #[tokio::test]
 async fn test_run_command_empty_repositories() {
-    let command = RunCommand {
-        run_type: RunType::Command("echo test".to_string()),
-        no_save: true,
-        output_dir: None,
-    };
+    let command = RunCommand::new_command("echo test".to_string(), true, None);
 
     let context = CommandContext {
         config: Config {
             repositories: vec![],
             recipes: vec![],
+            artifacts_remote: None,
+            log_sinks: Vec::new(),
+            path_strategy: Default::default(),
+            root: None,
+            files: Vec::new(),
+            branch_protection: Vec::new(),
+            repository_settings: Vec::new(),
+            post_clone: Vec::new(),
+            pr: None,
+            provider: None,
+            provider_url: None,
+            git: Default::default(),
+            exit_codes: Default::default(),
+            notify: None,
+            profiles: Default::default(),
+            secrets: None,
+            profile_token: None,
         },
         tag: vec![],
         exclude_tag: vec![],
@@ -312,13 +439,13 @@ async fn test_run_command_empty_repositories() {
 
 #[tokio::test]
 async fn test_run_command_basic_execution() {
-    let (_temp_dir, _repo, context) = setup_basic_test("test-repo");
+    let (temp_dir, _repo, context) = setup_basic_test("test-repo");
 
-    let command = RunCommand {
-        run_type: RunType::Command("echo hello".to_string()),
-        no_save: true,
-        output_dir: None,
-    };
+    let command = RunCommand::new_command(
+        "echo hello".to_string(),
+        true,
+        Some(temp_dir.path().join("output")),
+    );
 
     let result = command.execute(&context).await;
     assert!(result.is_ok());
@@ -326,13 +453,13 @@ async fn test_run_command_basic_execution() {
 
 #[tokio::test]
 async fn test_run_command_parallel_execution() {
-    let (_temp_dir, _repos, context) = setup_parallel_test("test-repo1", "test-repo2");
+    let (temp_dir, _repos, context) = setup_parallel_test("test-repo1", "test-repo2");
 
-    let command = RunCommand {
-        run_type: RunType::Command("echo hello".to_string()),
-        no_save: true,
-        output_dir: None,
-    };
+    let command = RunCommand::new_command(
+        "echo hello".to_string(),
+        true,
+        Some(temp_dir.path().join("output")),
+    );
 
     let result = command.execute(&context).await;
     assert!(result.is_ok());
@@ -346,11 +473,11 @@ async fn test_run_command_with_tag_filter() {
     let (_frontend_dir, frontend_repo) =
         create_tagged_repo_setup(&temp_dir, "frontend-repo", vec!["frontend", "javascript"]);
 
-    let command = RunCommand {
-        run_type: RunType::Command("echo hello".to_string()),
-        no_save: true,
-        output_dir: None,
-    };
+    let command = RunCommand::new_command(
+        "echo hello".to_string(),
+        true,
+        Some(temp_dir.path().join("output")),
+    );
 
     let context = CommandContextBuilder::new()
         .with_repositories(vec![backend_repo, frontend_repo])
@@ -363,13 +490,13 @@ async fn test_run_command_with_tag_filter() {
 
 #[tokio::test]
 async fn test_run_command_error_handling() {
-    let (_temp_dir, _repo, context) = setup_basic_test("test-repo");
+    let (temp_dir, _repo, context) = setup_basic_test("test-repo");
 
-    let command = RunCommand {
-        run_type: RunType::Command("false".to_string()), // Command that will fail
-        no_save: true,
-        output_dir: None,
-    };
+    let command = RunCommand::new_command(
+        "false".to_string(), // Command that will fail
+        true,
+        Some(temp_dir.path().join("output")),
+    );
 
     let result = command.execute(&context).await;
     // The command should fail when all individual commands fail
@@ -378,16 +505,33 @@ async fn test_run_command_error_handling() {
 
 #[tokio::test]
 async fn test_run_command_with_special_characters() {
-    let command = RunCommand {
-        run_type: RunType::Command("echo \"test with spaces and symbols: @#$%\"".to_string()),
-        no_save: true,
-        output_dir: None,
-    };
+    let command = RunCommand::new_command(
+        "echo \"test with spaces and symbols: @#$%\"".to_string(),
+        true,
+        None,
+    );
 
     let context = CommandContext {
         config: Config {
             repositories: vec![],
             recipes: vec![],
+            artifacts_remote: None,
+            log_sinks: Vec::new(),
+            path_strategy: Default::default(),
+            root: None,
+            files: Vec::new(),
+            branch_protection: Vec::new(),
+            repository_settings: Vec::new(),
+            post_clone: Vec::new(),
+            pr: None,
+            provider: None,
+            provider_url: None,
+            git: Default::default(),
+            exit_codes: Default::default(),
+            notify: None,
+            profiles: Default::default(),
+            secrets: None,
+            profile_token: None,
         },
         tag: vec![],
         exclude_tag: vec![],
@@ -403,16 +547,33 @@ async fn test_run_command_with_special_characters() {
 
 #[tokio::test]
 async fn test_run_command_error_no_command_nor_recipe() {
-    let command = RunCommand {
-        run_type: RunType::Command("".to_string()), // Empty command
-        no_save: true,
-        output_dir: None,
-    };
+    let command = RunCommand::new_command(
+        "".to_string(), // Empty command
+        true,
+        None,
+    );
 
     let context = CommandContext {
         config: Config {
             repositories: vec![],
             recipes: vec![],
+            artifacts_remote: None,
+            log_sinks: Vec::new(),
+            path_strategy: Default::default(),
+            root: None,
+            files: Vec::new(),
+            branch_protection: Vec::new(),
+            repository_settings: Vec::new(),
+            post_clone: Vec::new(),
+            pr: None,
+            provider: None,
+            provider_url: None,
+            git: Default::default(),
+            exit_codes: Default::default(),
+            notify: None,
+            profiles: Default::default(),
+            secrets: None,
+            profile_token: None,
         },
         tag: vec![],
         exclude_tag: vec![],
@@ -432,11 +593,11 @@ async fn test_run_command_existing_output_dir() {
 
     let (_temp_dir, _repo, context) = setup_basic_test("test-repo");
 
-    let command = RunCommand {
-        run_type: RunType::Command("echo existing_out_dir".to_string()),
-        no_save: false,
-        output_dir: Some(output_dir.clone()),
-    };
+    let command = RunCommand::new_command(
+        "echo existing_out_dir".to_string(),
+        false,
+        Some(output_dir.clone()),
+    );
 
     let result = command.execute(&context).await;
     assert!(result.is_ok());
@@ -445,14 +606,14 @@ async fn test_run_command_existing_output_dir() {
 
 #[tokio::test]
 async fn test_run_recipe_without_shebang_implicit_shell() {
-    let (_temp_dir, _repo, _recipe, context) =
+    let (temp_dir, _repo, _recipe, context) =
         setup_recipe_test("test-repo", "no-shebang", vec!["echo IMPLICIT_SHELL_OK"]);
 
-    let command = RunCommand {
-        run_type: RunType::Recipe("no-shebang".to_string()),
-        no_save: true,
-        output_dir: None,
-    };
+    let command = RunCommand::new_recipe(
+        "no-shebang".to_string(),
+        true,
+        Some(temp_dir.path().join("output")),
+    );
 
     let result = command.execute(&context).await;
     assert!(result.is_ok());
@@ -460,15 +621,18 @@ async fn test_run_recipe_without_shebang_implicit_shell() {
 
 #[tokio::test]
 async fn test_run_recipe_parallel_failure_branch() {
-    let (_temp_dir, _repos, context) = setup_parallel_test("repo1", "repo2");
+    let (temp_dir, _repos, context) = setup_parallel_test("repo1", "repo2");
 
     // First step succeeds; second step uses a definitely missing command to force non-zero exit.
     let recipe = Recipe {
         name: "parallel-failure".to_string(),
         steps: vec![
-            "echo FIRST".to_string(),
-            "this-command-should-not-exist-12345".to_string(),
+            "echo FIRST".into(),
+            "this-command-should-not-exist-12345".into(),
         ],
+        artifacts: vec![],
+        cache: false,
+        env: Default::default(),
     };
 
     // Update context to include the recipe
@@ -476,6 +640,23 @@ async fn test_run_recipe_parallel_failure_branch() {
         config: Config {
             repositories: context.config.repositories,
             recipes: vec![recipe],
+            artifacts_remote: None,
+            log_sinks: Vec::new(),
+            path_strategy: Default::default(),
+            root: None,
+            files: Vec::new(),
+            branch_protection: Vec::new(),
+            repository_settings: Vec::new(),
+            post_clone: Vec::new(),
+            pr: None,
+            provider: None,
+            provider_url: None,
+            git: Default::default(),
+            exit_codes: Default::default(),
+            notify: None,
+            profiles: Default::default(),
+            secrets: None,
+            profile_token: None,
         },
         tag: context.tag,
         exclude_tag: context.exclude_tag,
@@ -483,28 +664,74 @@ async fn test_run_recipe_parallel_failure_branch() {
         repos: context.repos,
     };
 
-    let command = RunCommand {
-        run_type: RunType::Recipe("parallel-failure".to_string()),
-        no_save: true,
-        output_dir: None,
-    };
+    let command = RunCommand::new_recipe(
+        "parallel-failure".to_string(),
+        true,
+        Some(temp_dir.path().join("output")),
+    );
 
     let result = command.execute(&context).await;
-    assert!(
-        result.is_ok(),
-        "Run returns Ok but individual failures should be logged internally"
+    let error = result.expect_err("Failing repositories should fail a parallel run");
+    assert_eq!(error.to_string(), "2 of 2 repositories failed");
+
+    // Tolerating every failure lets the run succeed
+    let command = command.with_failure_policy(FailurePolicy {
+        failure_threshold: Some(100.0),
+        ..Default::default()
+    });
+    assert!(command.execute(&context).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_run_keep_going_runs_every_repository_sequentially() {
+    let (_temp_dir, _repos, mut context) = setup_parallel_test("repo1", "repo2");
+    context.parallel = false;
+    let output_dir = TempDir::new().unwrap();
+
+    let command = RunCommand::new_for_test(
+        "echo ran > ran.txt; exit 1".to_string(),
+        output_dir.path().to_string_lossy().to_string(),
     );
+    let error = command.execute(&context).await.unwrap_err();
+    assert_eq!(error.to_string(), "1 of 2 repositories failed");
+    let ran = |name: &str| {
+        context
+            .config
+            .repositories
+            .iter()
+            .find(|repo| repo.name == name)
+            .map(|repo| {
+                PathBuf::from(repo.get_target_dir())
+                    .join("ran.txt")
+                    .exists()
+            })
+            .unwrap()
+    };
+    assert!(ran("repo1"));
+    assert!(!ran("repo2"), "Sequential runs stop at the first failure");
+
+    let command = command.with_failure_policy(FailurePolicy {
+        keep_going: true,
+        max_failures: Some(1),
+        ..Default::default()
+    });
+    let error = command.execute(&context).await.unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "2 of 2 repositories failed (at most 1 tolerated)"
+    );
+    assert!(ran("repo2"));
 }
 
 #[tokio::test]
 async fn test_run_command_skip_save_branch() {
-    let (_temp_dir, _repo, context) = setup_basic_test("test-repo");
+    let (temp_dir, _repo, context) = setup_basic_test("test-repo");
 
-    let command = RunCommand {
-        run_type: RunType::Command("echo SKIP_SAVE_MODE".to_string()),
-        no_save: true, // Skip save mode
-        output_dir: None,
-    };
+    let command = RunCommand::new_command(
+        "echo SKIP_SAVE_MODE".to_string(),
+        true, // Skip save mode
+        Some(temp_dir.path().join("output")),
+    );
 
     let result = command.execute(&context).await;
     assert!(result.is_ok());
@@ -516,11 +743,11 @@ async fn test_run_long_command_name_sanitization() {
     let (_temp_dir, _repo, context) = setup_basic_test("test-repo");
 
     let long_cmd = "echo THIS_IS_A_REALLY_LONG_COMMAND_NAME_WITH_SPECIAL_CHARS_%_#_@_!_____END";
-    let command = RunCommand {
-        run_type: RunType::Command(long_cmd.to_string()),
-        no_save: false,
-        output_dir: Some(temp_dir.path().join("long_cmd_output")),
-    };
+    let command = RunCommand::new_command(
+        long_cmd.to_string(),
+        false,
+        Some(temp_dir.path().join("long_cmd_output")),
+    );
 
     let result = command.execute(&context).await;
     assert!(result.is_ok());
@@ -530,17 +757,17 @@ async fn test_run_long_command_name_sanitization() {
 
 #[tokio::test]
 async fn test_run_recipe_script_creation_error_handling() {
-    let (_temp_dir, _repo, _recipe, context) = setup_recipe_test(
+    let (temp_dir, _repo, _recipe, context) = setup_recipe_test(
         "test-repo",
         "script-creation",
         vec!["echo 'Testing script creation'", "echo 'Second step'"],
     );
 
-    let command = RunCommand {
-        run_type: RunType::Recipe("script-creation".to_string()),
-        no_save: true,
-        output_dir: None,
-    };
+    let command = RunCommand::new_recipe(
+        "script-creation".to_string(),
+        true,
+        Some(temp_dir.path().join("output")),
+    );
 
     let result = command.execute(&context).await;
     assert!(result.is_ok());
@@ -548,17 +775,17 @@ async fn test_run_recipe_script_creation_error_handling() {
 
 #[tokio::test]
 async fn test_run_recipe_with_readonly_directory() {
-    let (_temp_dir, _repo, _recipe, context) = setup_recipe_test(
+    let (temp_dir, _repo, _recipe, context) = setup_recipe_test(
         "test-repo",
         "readonly-test",
         vec!["echo 'Testing readonly scenario'"],
     );
 
-    let command = RunCommand {
-        run_type: RunType::Recipe("readonly-test".to_string()),
-        no_save: true,
-        output_dir: None,
-    };
+    let command = RunCommand::new_recipe(
+        "readonly-test".to_string(),
+        true,
+        Some(temp_dir.path().join("output")),
+    );
 
     let result = command.execute(&context).await;
     assert!(result.is_ok());
@@ -608,14 +835,14 @@ async fn test_run_command_new_for_test() {
 #[tokio::test]
 async fn test_run_command_recipe_execution() {
     let recipe_steps = vec!["echo 'Hello from recipe'", "pwd"];
-    let (_temp_dir, _repo, _recipe, context) =
+    let (temp_dir, _repo, _recipe, context) =
         setup_recipe_test("test-repo", "test-recipe", recipe_steps);
 
-    let command = RunCommand {
-        run_type: RunType::Recipe("test-recipe".to_string()),
-        no_save: true,
-        output_dir: None,
-    };
+    let command = RunCommand::new_recipe(
+        "test-recipe".to_string(),
+        true,
+        Some(temp_dir.path().join("output")),
+    );
 
     let result = command.execute(&context).await;
     assert!(result.is_ok());
@@ -623,16 +850,29 @@ async fn test_run_command_recipe_execution() {
 
 #[tokio::test]
 async fn test_run_command_recipe_not_found() {
-    let command = RunCommand {
-        run_type: RunType::Recipe("nonexistent-recipe".to_string()),
-        no_save: true,
-        output_dir: None,
-    };
+    let command = RunCommand::new_recipe("nonexistent-recipe".to_string(), true, None);
 
     let context = CommandContext {
         config: Config {
             repositories: vec![],
             recipes: vec![],
+            artifacts_remote: None,
+            log_sinks: Vec::new(),
+            path_strategy: Default::default(),
+            root: None,
+            files: Vec::new(),
+            branch_protection: Vec::new(),
+            repository_settings: Vec::new(),
+            post_clone: Vec::new(),
+            pr: None,
+            provider: None,
+            provider_url: None,
+            git: Default::default(),
+            exit_codes: Default::default(),
+            notify: None,
+            profiles: Default::default(),
+            secrets: None,
+            profile_token: None,
         },
         tag: vec![],
         exclude_tag: vec![],
@@ -652,21 +892,24 @@ async fn test_run_command_recipe_not_found() {
 
 #[tokio::test]
 async fn test_run_command_recipe_parallel_execution() {
-    let (_temp_dir, _repos, mut context) = setup_parallel_test("test-repo1", "test-repo2");
+    let (temp_dir, _repos, mut context) = setup_parallel_test("test-repo1", "test-repo2");
 
     // Add the recipe for parallel execution
     let recipe = Recipe {
         name: "parallel-recipe".to_string(),
-        steps: vec!["echo 'Parallel recipe execution'".to_string()],
+        steps: vec!["echo 'Parallel recipe execution'".into()],
+        artifacts: vec![],
+        cache: false,
+        env: Default::default(),
     };
     context.config.recipes.push(recipe);
     context.parallel = true;
 
-    let command = RunCommand {
-        run_type: RunType::Recipe("parallel-recipe".to_string()),
-        no_save: true,
-        output_dir: None,
-    };
+    let command = RunCommand::new_recipe(
+        "parallel-recipe".to_string(),
+        true,
+        Some(temp_dir.path().join("output")),
+    );
 
     let result = command.execute(&context).await;
     assert!(result.is_ok());
@@ -676,7 +919,7 @@ async fn test_run_command_recipe_parallel_execution() {
 
 #[tokio::test]
 async fn test_run_command_with_exclude_tag() {
-    let (_temp_dir, repos, mut context) = setup_parallel_test("backend-repo", "frontend-repo");
+    let (temp_dir, repos, mut context) = setup_parallel_test("backend-repo", "frontend-repo");
 
     // Customize the repository tags for this test
     let mut backend_repo = repos[0].clone();
@@ -687,11 +930,11 @@ async fn test_run_command_with_exclude_tag() {
     context.config.repositories = vec![backend_repo, frontend_repo];
     context.exclude_tag = vec!["frontend".to_string()]; // Exclude frontend repos
 
-    let command = RunCommand {
-        run_type: RunType::Command("echo exclude_test".to_string()),
-        no_save: true,
-        output_dir: None,
-    };
+    let command = RunCommand::new_command(
+        "echo exclude_test".to_string(),
+        true,
+        Some(temp_dir.path().join("output")),
+    );
 
     let result = command.execute(&context).await;
     assert!(result.is_ok());
@@ -701,7 +944,7 @@ async fn test_run_command_with_exclude_tag() {
 
 #[tokio::test]
 async fn test_run_command_with_specific_repos() {
-    let (_temp_dir, repos, mut context) = setup_parallel_test("backend-repo", "frontend-repo");
+    let (temp_dir, repos, mut context) = setup_parallel_test("backend-repo", "frontend-repo");
 
     // Customize the repository tags for this test
     let mut backend_repo = repos[0].clone();
@@ -712,11 +955,11 @@ async fn test_run_command_with_specific_repos() {
     context.config.repositories = vec![backend_repo, frontend_repo];
     context.repos = Some(vec!["backend-repo".to_string()]); // Only run on backend-repo
 
-    let command = RunCommand {
-        run_type: RunType::Command("echo specific_repo_test".to_string()),
-        no_save: true,
-        output_dir: None,
-    };
+    let command = RunCommand::new_command(
+        "echo specific_repo_test".to_string(),
+        true,
+        Some(temp_dir.path().join("output")),
+    );
 
     let result = command.execute(&context).await;
     assert!(result.is_ok());
@@ -731,11 +974,11 @@ async fn test_run_command_with_output_directory_creation() {
 
     let (_temp_dir, _repo, context) = setup_basic_test("test-repo");
 
-    let command = RunCommand {
-        run_type: RunType::Command("echo 'Testing output directory'".to_string()),
-        no_save: false, // Enable saving to test directory creation
-        output_dir: Some(output_dir.clone()),
-    };
+    let command = RunCommand::new_command(
+        "echo 'Testing output directory'".to_string(),
+        false, // Enable saving to test directory creation
+        Some(output_dir.clone()),
+    );
 
     let result = command.execute(&context).await;
     assert!(result.is_ok());
@@ -768,6 +1011,17 @@ async fn test_run_command_mixed_success_failure_sequential() {
         path: Some(repo_dir1.to_string_lossy().to_string()),
         branch: None,
         config_dir: None,
+        path_strategy: Default::default(),
+        default_branch: None,
+        sparse: Vec::new(),
+        provider: None,
+        provider_url: None,
+        provider_defaults: Default::default(),
+        git: Default::default(),
+        depends_on: Vec::new(),
+        post_clone: None,
+        archived: false,
+        remotes: Default::default(),
     };
 
     let bad_repo = Repository {
@@ -777,18 +1031,46 @@ async fn test_run_command_mixed_success_failure_sequential() {
         path: Some(bad_repo_path.to_string_lossy().to_string()),
         branch: None,
         config_dir: None,
-    };
-
-    let command = RunCommand {
-        run_type: RunType::Command("echo hello".to_string()),
-        no_save: true,
-        output_dir: None,
-    };
+        path_strategy: Default::default(),
+        default_branch: None,
+        sparse: Vec::new(),
+        provider: None,
+        provider_url: None,
+        provider_defaults: Default::default(),
+        git: Default::default(),
+        depends_on: Vec::new(),
+        post_clone: None,
+        archived: false,
+        remotes: Default::default(),
+    };
+
+    let command = RunCommand::new_command(
+        "echo hello".to_string(),
+        true,
+        Some(temp_dir.path().join("output")),
+    );
 
     let context = CommandContext {
         config: Config {
             repositories: vec![good_repo, bad_repo],
             recipes: vec![],
+            artifacts_remote: None,
+            log_sinks: Vec::new(),
+            path_strategy: Default::default(),
+            root: None,
+            files: Vec::new(),
+            branch_protection: Vec::new(),
+            repository_settings: Vec::new(),
+            post_clone: Vec::new(),
+            pr: None,
+            provider: None,
+            provider_url: None,
+            git: Default::default(),
+            exit_codes: Default::default(),
+            notify: None,
+            profiles: Default::default(),
+            secrets: None,
+            profile_token: None,
         },
         tag: vec![],
         exclude_tag: vec![],
@@ -805,16 +1087,29 @@ async fn test_run_command_mixed_success_failure_sequential() {
 
 #[tokio::test]
 async fn test_run_command_empty_command_string() {
-    let command = RunCommand {
-        run_type: RunType::Command("".to_string()),
-        no_save: true,
-        output_dir: None,
-    };
+    let command = RunCommand::new_command("".to_string(), true, None);
 
     let context = CommandContext {
         config: Config {
             repositories: vec![],
             recipes: vec![],
+            artifacts_remote: None,
+            log_sinks: Vec::new(),
+            path_strategy: Default::default(),
+            root: None,
+            files: Vec::new(),
+            branch_protection: Vec::new(),
+            repository_settings: Vec::new(),
+            post_clone: Vec::new(),
+            pr: None,
+            provider: None,
+            provider_url: None,
+            git: Default::default(),
+            exit_codes: Default::default(),
+            notify: None,
+            profiles: Default::default(),
+            secrets: None,
+            profile_token: None,
         },
         tag: vec![],
         exclude_tag: vec![],
@@ -835,11 +1130,11 @@ async fn test_run_command_with_save_enabled() {
 
     let (_temp_dir, _repo, context) = setup_basic_test("test-repo");
 
-    let command = RunCommand {
-        run_type: RunType::Command("echo 'save test'".to_string()),
-        no_save: false, // Enable saving
-        output_dir: Some(output_dir.clone()),
-    };
+    let command = RunCommand::new_command(
+        "echo 'save test'".to_string(),
+        false, // Enable saving
+        Some(output_dir.clone()),
+    );
 
     let result = command.execute(&context).await;
     assert!(result.is_ok());
@@ -853,11 +1148,11 @@ async fn test_run_command_with_save_enabled() {
 async fn test_run_command_with_save_default_output_dir() {
     let (_temp_dir, _repo, context) = setup_basic_test("test-repo");
 
-    let command = RunCommand {
-        run_type: RunType::Command("echo 'default output test'".to_string()),
-        no_save: false,   // Enable saving
-        output_dir: None, // Use default "output" directory
-    };
+    let command = RunCommand::new_command(
+        "echo 'default output test'".to_string(),
+        false, // Enable saving
+        None,  // Use default "output" directory
+    );
 
     let result = command.execute(&context).await;
     assert!(result.is_ok());
@@ -871,11 +1166,11 @@ async fn test_run_command_parallel_with_save() {
     let (_temp_dir, _repos, mut context) = setup_parallel_test("test-repo1", "test-repo2");
     context.parallel = true; // Enable parallel execution
 
-    let command = RunCommand {
-        run_type: RunType::Command("echo 'parallel save test'".to_string()),
-        no_save: false, // Enable saving
-        output_dir: Some(output_dir.clone()),
-    };
+    let command = RunCommand::new_command(
+        "echo 'parallel save test'".to_string(),
+        false, // Enable saving
+        Some(output_dir.clone()),
+    );
 
     let result = command.execute(&context).await;
     assert!(result.is_ok());
@@ -887,14 +1182,14 @@ async fn test_run_command_parallel_with_save() {
 
 #[tokio::test]
 async fn test_run_command_parallel_with_no_save() {
-    let (_temp_dir, _repos, mut context) = setup_parallel_test("test-repo1", "test-repo2");
+    let (temp_dir, _repos, mut context) = setup_parallel_test("test-repo1", "test-repo2");
     context.parallel = true; // Enable parallel execution
 
-    let command = RunCommand {
-        run_type: RunType::Command("echo 'parallel no save test'".to_string()),
-        no_save: true, // Disable saving
-        output_dir: None,
-    };
+    let command = RunCommand::new_command(
+        "echo 'parallel no save test'".to_string(),
+        true, // Disable saving
+        Some(temp_dir.path().join("output")),
+    );
 
     let result = command.execute(&context).await;
     assert!(result.is_ok());
@@ -911,11 +1206,11 @@ async fn test_run_command_recipe_with_save_enabled() {
     let (_temp_dir, _repo, _recipe, context) =
         setup_recipe_test("test-repo", "save-recipe", recipe_steps);
 
-    let command = RunCommand {
-        run_type: RunType::Recipe("save-recipe".to_string()),
-        no_save: false, // Enable saving
-        output_dir: Some(output_dir.clone()),
-    };
+    let command = RunCommand::new_recipe(
+        "save-recipe".to_string(),
+        false, // Enable saving
+        Some(output_dir.clone()),
+    );
 
     let result = command.execute(&context).await;
     assert!(result.is_ok());
@@ -935,16 +1230,19 @@ async fn test_run_command_recipe_parallel_with_save() {
     // Add recipe for parallel execution
     let recipe = Recipe {
         name: "parallel-save-recipe".to_string(),
-        steps: vec!["echo 'Parallel recipe with save'".to_string()],
+        steps: vec!["echo 'Parallel recipe with save'".into()],
+        artifacts: vec![],
+        cache: false,
+        env: Default::default(),
     };
     context.config.recipes.push(recipe);
     context.parallel = true; // Enable parallel execution
 
-    let command = RunCommand {
-        run_type: RunType::Recipe("parallel-save-recipe".to_string()),
-        no_save: false, // Enable saving
-        output_dir: Some(output_dir.clone()),
-    };
+    let command = RunCommand::new_recipe(
+        "parallel-save-recipe".to_string(),
+        false, // Enable saving
+        Some(output_dir.clone()),
+    );
 
     let result = command.execute(&context).await;
     assert!(result.is_ok());
@@ -956,21 +1254,24 @@ async fn test_run_command_recipe_parallel_with_save() {
 
 #[tokio::test]
 async fn test_run_command_recipe_parallel_with_no_save() {
-    let (_temp_dir, _repos, mut context) = setup_parallel_test("test-repo1", "test-repo2");
+    let (temp_dir, _repos, mut context) = setup_parallel_test("test-repo1", "test-repo2");
 
     // Add recipe for parallel execution
     let recipe = Recipe {
         name: "parallel-no-save-recipe".to_string(),
-        steps: vec!["echo 'Parallel recipe without save'".to_string()],
+        steps: vec!["echo 'Parallel recipe without save'".into()],
+        artifacts: vec![],
+        cache: false,
+        env: Default::default(),
     };
     context.config.recipes.push(recipe);
     context.parallel = true; // Enable parallel execution
 
-    let command = RunCommand {
-        run_type: RunType::Recipe("parallel-no-save-recipe".to_string()),
-        no_save: true, // Disable saving
-        output_dir: None,
-    };
+    let command = RunCommand::new_recipe(
+        "parallel-no-save-recipe".to_string(),
+        true, // Disable saving
+        Some(temp_dir.path().join("output")),
+    );
 
     let result = command.execute(&context).await;
     assert!(result.is_ok());
@@ -979,14 +1280,14 @@ async fn test_run_command_recipe_parallel_with_no_save() {
 #[tokio::test]
 async fn test_run_command_recipe_sequential_with_no_save() {
     let recipe_steps = vec!["echo 'Sequential recipe without save'"];
-    let (_temp_dir, _repo, _recipe, context) =
+    let (temp_dir, _repo, _recipe, context) =
         setup_recipe_test("test-repo", "sequential-no-save-recipe", recipe_steps);
 
-    let command = RunCommand {
-        run_type: RunType::Recipe("sequential-no-save-recipe".to_string()),
-        no_save: true, // Disable saving
-        output_dir: None,
-    };
+    let command = RunCommand::new_recipe(
+        "sequential-no-save-recipe".to_string(),
+        true, // Disable saving
+        Some(temp_dir.path().join("output")),
+    );
 
     let result = command.execute(&context).await;
     assert!(result.is_ok());
@@ -997,14 +1298,14 @@ async fn test_run_command_recipe_sequential_with_no_save() {
 #[tokio::test]
 async fn test_script_materialization_with_shebang() {
     let recipe_steps = vec!["#!/bin/bash", "echo 'Script with shebang'"];
-    let (_temp_dir, _repo, _recipe, context) =
+    let (temp_dir, _repo, _recipe, context) =
         setup_recipe_test("test-repo", "shebang-recipe", recipe_steps);
 
-    let command = RunCommand {
-        run_type: RunType::Recipe("shebang-recipe".to_string()),
-        no_save: true,
-        output_dir: None,
-    };
+    let command = RunCommand::new_recipe(
+        "shebang-recipe".to_string(),
+        true,
+        Some(temp_dir.path().join("output")),
+    );
 
     let result = command.execute(&context).await;
     assert!(result.is_ok());
@@ -1013,14 +1314,14 @@ async fn test_script_materialization_with_shebang() {
 #[tokio::test]
 async fn test_script_materialization_without_shebang() {
     let recipe_steps = vec!["echo 'Script without shebang'", "pwd"];
-    let (_temp_dir, _repo, _recipe, context) =
+    let (temp_dir, _repo, _recipe, context) =
         setup_recipe_test("test-repo", "no-shebang-recipe", recipe_steps);
 
-    let command = RunCommand {
-        run_type: RunType::Recipe("no-shebang-recipe".to_string()),
-        no_save: true,
-        output_dir: None,
-    };
+    let command = RunCommand::new_recipe(
+        "no-shebang-recipe".to_string(),
+        true,
+        Some(temp_dir.path().join("output")),
+    );
 
     let result = command.execute(&context).await;
     assert!(result.is_ok());
@@ -1032,11 +1333,11 @@ async fn test_sanitize_command_for_filename() {
     let (_temp_dir, _repo, context) = setup_basic_test("test-repo");
 
     // Command with special characters that need sanitization
-    let command = RunCommand {
-        run_type: RunType::Command("echo 'test with / \\ : * ? \" < > | characters'".to_string()),
-        no_save: false, // Enable saving to test sanitization
-        output_dir: Some(temp_dir.path().join("sanitize_test")),
-    };
+    let command = RunCommand::new_command(
+        "echo 'test with / \\ : * ? \" < > | characters'".to_string(),
+        false, // Enable saving to test sanitization
+        Some(temp_dir.path().join("sanitize_test")),
+    );
 
     let result = command.execute(&context).await;
     assert!(result.is_ok());
@@ -1045,17 +1346,17 @@ async fn test_sanitize_command_for_filename() {
 #[tokio::test]
 async fn test_sanitize_script_name() {
     let recipe_steps = vec!["echo 'Recipe with special name'"];
-    let (_temp_dir, _repo, mut _recipe, context) = setup_recipe_test(
+    let (temp_dir, _repo, mut _recipe, context) = setup_recipe_test(
         "test-repo",
         "Recipe-With.Special@Characters#And$Symbols%",
         recipe_steps,
     );
 
-    let command = RunCommand {
-        run_type: RunType::Recipe("Recipe-With.Special@Characters#And$Symbols%".to_string()),
-        no_save: true,
-        output_dir: None,
-    };
+    let command = RunCommand::new_recipe(
+        "Recipe-With.Special@Characters#And$Symbols%".to_string(),
+        true,
+        Some(temp_dir.path().join("output")),
+    );
 
     let result = command.execute(&context).await;
     assert!(result.is_ok());
@@ -1069,12 +1370,12 @@ async fn test_long_command_name_truncation() {
     let (_temp_dir, _repo, context) = setup_basic_test("test-repo");
 
     // Very long command that should be truncated for directory name
-    let long_command = "a".repeat(100);
-    let command = RunCommand {
-        run_type: RunType::Command(long_command),
-        no_save: false, // Enable saving to test truncation
-        output_dir: Some(temp_dir.path().join("long_command_test")),
-    };
+    let long_command = format!("echo {}", "a".repeat(100));
+    let command = RunCommand::new_command(
+        long_command,
+        false, // Enable saving to test truncation
+        Some(temp_dir.path().join("long_command_test")),
+    );
 
     let result = command.execute(&context).await;
     assert!(result.is_ok());
@@ -1085,19 +1386,18 @@ async fn test_long_command_name_truncation() {
 #[tokio::test]
 async fn test_recipe_sequential_execution_with_script_error() {
     let recipe_steps = vec!["nonexistent_command_that_will_fail_12345"]; // Non-existent command
-    let (_temp_dir, _repo, _recipe, context) =
+    let (temp_dir, _repo, _recipe, context) =
         setup_recipe_test("test-repo", "script-error-recipe", recipe_steps);
 
-    let command = RunCommand {
-        run_type: RunType::Recipe("script-error-recipe".to_string()),
-        no_save: true,
-        output_dir: None,
-    };
+    let command = RunCommand::new_recipe(
+        "script-error-recipe".to_string(),
+        true,
+        Some(temp_dir.path().join("output")),
+    );
 
     let result = command.execute(&context).await;
-    // The recipe should succeed even if commands within it fail, based on current implementation
-    // This tests the behavior where script execution completes but commands inside may fail
-    assert!(result.is_ok());
+    // The script runs to completion, but its exit code fails the run
+    assert!(result.is_err());
 }
 
 // ===== Complex Path and Script Tests =====
@@ -1105,17 +1405,17 @@ async fn test_recipe_sequential_execution_with_script_error() {
 #[tokio::test]
 async fn test_recipe_script_path_resolution() {
     let recipe_steps = vec!["pwd", "echo 'Path resolution test'"];
-    let (_temp_dir, _repo, _recipe, context) = setup_recipe_test(
+    let (temp_dir, _repo, _recipe, context) = setup_recipe_test(
         "test-repo-with-complex-name",
         "path-resolution-recipe",
         recipe_steps,
     );
 
-    let command = RunCommand {
-        run_type: RunType::Recipe("path-resolution-recipe".to_string()),
-        no_save: true,
-        output_dir: None,
-    };
+    let command = RunCommand::new_recipe(
+        "path-resolution-recipe".to_string(),
+        true,
+        Some(temp_dir.path().join("output")),
+    );
 
     let result = command.execute(&context).await;
     assert!(result.is_ok());
@@ -1125,14 +1425,14 @@ async fn test_recipe_script_path_resolution() {
 
 #[tokio::test]
 async fn test_recipe_with_empty_steps() {
-    let (_temp_dir, _repo, _recipe, context) =
+    let (temp_dir, _repo, _recipe, context) =
         setup_recipe_test("test-repo", "empty-recipe", vec![]);
 
-    let command = RunCommand {
-        run_type: RunType::Recipe("empty-recipe".to_string()),
-        no_save: true,
-        output_dir: None,
-    };
+    let command = RunCommand::new_recipe(
+        "empty-recipe".to_string(),
+        true,
+        Some(temp_dir.path().join("output")),
+    );
 
     let result = command.execute(&context).await;
     assert!(result.is_ok());
@@ -1149,14 +1449,14 @@ async fn test_script_creation_with_various_contents() {
         "  echo 'Found README.md'",
         "fi",
     ];
-    let (_temp_dir, _repo, _recipe, context) =
+    let (temp_dir, _repo, _recipe, context) =
         setup_recipe_test("test-repo", "complex-script", recipe_steps);
 
-    let command = RunCommand {
-        run_type: RunType::Recipe("complex-script".to_string()),
-        no_save: true,
-        output_dir: None,
-    };
+    let command = RunCommand::new_recipe(
+        "complex-script".to_string(),
+        true,
+        Some(temp_dir.path().join("output")),
+    );
 
     let result = command.execute(&context).await;
     assert!(result.is_ok());
@@ -1172,11 +1472,11 @@ async fn test_recipe_sequential_execution_with_default_output() {
         vec!["echo 'Testing default output directory'"],
     );
 
-    let command = RunCommand {
-        run_type: RunType::Recipe("default-output-recipe".to_string()),
-        no_save: false,   // Enable saving with default output directory
-        output_dir: None, // Use default
-    };
+    let command = RunCommand::new_recipe(
+        "default-output-recipe".to_string(),
+        false, // Enable saving with default output directory
+        None,  // Use default
+    );
 
     let result = command.execute(&context).await;
     assert!(result.is_ok());
@@ -1192,14 +1492,14 @@ async fn test_multi_step_recipe_sequential() {
         "echo 'Step 3: Ending recipe'",
         "ls -la",
     ];
-    let (_temp_dir, _repo, _recipe, context) =
+    let (temp_dir, _repo, _recipe, context) =
         setup_recipe_test("test-repo", "multi-step-recipe", recipe_steps);
 
-    let command = RunCommand {
-        run_type: RunType::Recipe("multi-step-recipe".to_string()),
-        no_save: true,
-        output_dir: None,
-    };
+    let command = RunCommand::new_recipe(
+        "multi-step-recipe".to_string(),
+        true,
+        Some(temp_dir.path().join("output")),
+    );
 
     let result = command.execute(&context).await;
     assert!(result.is_ok());
@@ -1209,20 +1509,23 @@ async fn test_multi_step_recipe_sequential() {
 
 #[tokio::test]
 async fn test_recipe_multi_repo_complex_names() {
-    let (_temp_dir, _repos, mut context) =
+    let (temp_dir, _repos, mut context) =
         setup_parallel_test("repo-with-dashes-1", "repo_with_underscores_2");
 
     let recipe = Recipe {
         name: "Complex-Recipe_Name.With@Special#Characters".to_string(),
-        steps: vec!["echo 'Complex recipe with multiple repos'".to_string()],
+        steps: vec!["echo 'Complex recipe with multiple repos'".into()],
+        artifacts: vec![],
+        cache: false,
+        env: Default::default(),
     };
     context.config.recipes.push(recipe);
 
-    let command = RunCommand {
-        run_type: RunType::Recipe("Complex-Recipe_Name.With@Special#Characters".to_string()),
-        no_save: true,
-        output_dir: None,
-    };
+    let command = RunCommand::new_recipe(
+        "Complex-Recipe_Name.With@Special#Characters".to_string(),
+        true,
+        Some(temp_dir.path().join("output")),
+    );
 
     let result = command.execute(&context).await;
     assert!(result.is_ok());
@@ -1238,11 +1541,11 @@ async fn test_run_command_creates_logs_with_content() {
     let (_temp_dir, _repo, context) = setup_basic_test("test-repo");
 
     let test_output = "Hello from command test";
-    let command = RunCommand {
-        run_type: RunType::Command(format!("echo '{}'", test_output)),
-        no_save: false, // Enable saving to create log files
-        output_dir: Some(output_dir.clone()),
-    };
+    let command = RunCommand::new_command(
+        format!("echo '{}'", test_output),
+        false, // Enable saving to create log files
+        Some(output_dir.clone()),
+    );
 
     let result = command.execute(&context).await;
     assert!(result.is_ok());
@@ -1335,11 +1638,11 @@ async fn test_run_recipe_creates_logs_with_content() {
     let (_temp_dir, _repo, _recipe, context) =
         setup_recipe_test("test-repo", "log-test-recipe", recipe_steps);
 
-    let command = RunCommand {
-        run_type: RunType::Recipe("log-test-recipe".to_string()),
-        no_save: false, // Enable saving to create log files
-        output_dir: Some(output_dir.clone()),
-    };
+    let command = RunCommand::new_recipe(
+        "log-test-recipe".to_string(),
+        false, // Enable saving to create log files
+        Some(output_dir.clone()),
+    );
 
     let result = command.execute(&context).await;
     assert!(result.is_ok());
@@ -1425,3 +1728,334 @@ async fn test_run_recipe_creates_logs_with_content() {
         metadata_content
     );
 }
+
+#[tokio::test]
+async fn test_run_recipe_collects_artifacts() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_dir = temp_dir.path().join("artifact_test_output");
+
+    let recipe_steps = vec!["mkdir -p dist", "echo built > dist/app.txt"];
+    let (_temp_dir, _repo, _recipe, mut context) =
+        setup_recipe_test("test-repo", "build", recipe_steps);
+    context.config.recipes[0].artifacts = vec!["dist/**".to_string()];
+
+    let command = RunCommand::new_recipe("build".to_string(), false, Some(output_dir.clone()))
+        .with_artifacts(vec!["README.md".to_string()]);
+
+    let result = command.execute(&context).await;
+    assert!(result.is_ok());
+
+    let run_dir = fs::read_dir(output_dir.join("runs"))
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .path();
+    let artifacts_dir = run_dir.join("test-repo").join("artifacts");
+
+    // Recipe-declared and command-line artifacts are both collected
+    assert_eq!(
+        fs::read_to_string(artifacts_dir.join("dist/app.txt")).unwrap(),
+        "built\n"
+    );
+    assert!(artifacts_dir.join("README.md").exists());
+}
+
+#[tokio::test]
+async fn test_run_recipe_skips_steps_whose_conditions_fail() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_dir = temp_dir.path().join("conditional_output");
+
+    let (_temp_dir, _repo, _recipe, mut context) =
+        setup_recipe_test("test-repo", "install", vec![]);
+    context.config.recipes[0].steps = vec![
+        "echo always".into(),
+        RecipeStep {
+            run: "echo tagged".to_string(),
+            condition: Some("tag == 'test'".parse().unwrap()),
+        },
+        RecipeStep {
+            run: "echo node".to_string(),
+            condition: Some("file_exists('package.json')".parse().unwrap()),
+        },
+    ];
+
+    let command = RunCommand::new_recipe("install".to_string(), false, Some(output_dir.clone()));
+    command.execute(&context).await.unwrap();
+
+    let run_dir = fs::read_dir(output_dir.join("runs"))
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .path();
+    let repo_log_dir = run_dir.join("test-repo");
+    assert_eq!(
+        fs::read_to_string(repo_log_dir.join("stdout.log")).unwrap(),
+        "always\ntagged\n"
+    );
+
+    let metadata: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(repo_log_dir.join("metadata.json")).unwrap())
+            .unwrap();
+    assert_eq!(
+        metadata["recipe_steps"],
+        serde_json::json!(["echo always", "echo tagged"])
+    );
+    assert_eq!(
+        metadata["skipped_steps"],
+        serde_json::json!([{"run": "echo node", "if": "file_exists('package.json')"}])
+    );
+}
+
+#[tokio::test]
+async fn test_run_command_reuses_cached_result() {
+    let (temp_dir, _repo, context) = setup_basic_test("test-repo");
+    let output_dir = temp_dir.path().join("cache_test_output");
+    let counter = temp_dir.path().join("counter.txt");
+    let command_line = format!("echo run >> {}", counter.display());
+
+    let run = |no_cache: bool| {
+        RunCommand::new_command(command_line.clone(), false, Some(output_dir.clone()))
+            .with_cache(true, no_cache)
+    };
+
+    run(false).execute(&context).await.unwrap();
+    run(false).execute(&context).await.unwrap();
+
+    // The second run is served from the cache
+    assert_eq!(fs::read_to_string(&counter).unwrap().lines().count(), 1);
+    let cache_entries = fs::read_dir(output_dir.join("cache")).unwrap().count();
+    assert_eq!(cache_entries, 1);
+
+    // --no-cache forces execution
+    run(true).execute(&context).await.unwrap();
+    assert_eq!(fs::read_to_string(&counter).unwrap().lines().count(), 2);
+}
+
+#[tokio::test]
+async fn test_run_writes_summary_json() {
+    let (temp_dir, _repos, context) = setup_parallel_test("repo-ok", "repo-fail");
+    let output_dir = temp_dir.path().join("summary_test_output");
+
+    let command = RunCommand::new_command(
+        "test \"$(basename $PWD)\" = repo-ok".to_string(),
+        false,
+        Some(output_dir.clone()),
+    );
+
+    let error = command.execute(&context).await.unwrap_err();
+    assert_eq!(error.to_string(), "1 of 2 repositories failed");
+
+    let run_dir = fs::read_dir(output_dir.join("runs"))
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .path();
+    let summary: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(run_dir.join("summary.json")).unwrap()).unwrap();
+
+    assert_eq!(summary["total"], 2);
+    assert_eq!(summary["succeeded"], 1);
+    assert_eq!(summary["failed"], 1);
+
+    let results = summary["results"].as_array().unwrap();
+    let failed = results
+        .iter()
+        .find(|r| r["repository"] == "repo-fail")
+        .unwrap();
+    assert_eq!(failed["exit_code"], 1);
+    assert!(failed["log_dir"].as_str().unwrap().ends_with("repo-fail"));
+
+    // The manifest records the invocation next to the summary
+    let manifest: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(run_dir.join("manifest.json")).unwrap()).unwrap();
+    assert_eq!(manifest["operation"], "run");
+    assert_eq!(manifest["config_hash"].as_str().unwrap().len(), 64);
+    assert_eq!(manifest["filters"]["tags"], serde_json::json!([]));
+    assert_eq!(
+        (manifest["total"].as_u64(), manifest["failed"].as_u64()),
+        (Some(2), Some(1))
+    );
+    assert!(manifest["started_at"].as_str().unwrap() <= manifest["finished_at"].as_str().unwrap());
+}
+
+#[tokio::test]
+async fn test_run_against_refs_in_worktrees() {
+    let (temp_dir, repo, context) = setup_basic_test("test-repo");
+    let repo_dir = PathBuf::from(repo.path.clone().unwrap());
+    let output_dir = temp_dir.path().join("ref_test_output");
+
+    let git = |args: &[&str]| {
+        let output = ProcessCommand::new("git")
+            .args(args)
+            .current_dir(&repo_dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {:?} failed", args);
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    };
+    let original_branch = git(&["rev-parse", "--abbrev-ref", "HEAD"]);
+    git(&["checkout", "-b", "release/1.0"]);
+    fs::write(repo_dir.join("VERSION"), "1.0").unwrap();
+    git(&["add", "VERSION"]);
+    git(&["commit", "-m", "Release 1.0"]);
+    git(&["tag", "v1.0"]);
+    git(&["checkout", &original_branch]);
+    // Uncommitted work in the developer's checkout must survive the run
+    fs::write(repo_dir.join("README.md"), "work in progress").unwrap();
+
+    let command =
+        RunCommand::new_command("cat VERSION".to_string(), false, Some(output_dir.clone()))
+            .with_refs(vec!["release/1.0".to_string(), "v1.0".to_string()]);
+    command.execute(&context).await.unwrap();
+
+    let run_dir = fs::read_dir(output_dir.join("runs"))
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .path();
+    for (name, git_ref) in [
+        ("test-repo@release_1.0", "release/1.0"),
+        ("test-repo@v1.0", "v1.0"),
+    ] {
+        let stdout = fs::read_to_string(run_dir.join(name).join("stdout.log")).unwrap();
+        assert_eq!(stdout.trim(), "1.0");
+        // Recorded so `repos triage` retries against the same ref
+        let metadata: serde_json::Value = serde_json::from_str(
+            &fs::read_to_string(run_dir.join(name).join("metadata.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(metadata["ref"], git_ref);
+        assert_eq!(metadata["parallel"], false);
+    }
+
+    assert_eq!(git(&["rev-parse", "--abbrev-ref", "HEAD"]), original_branch);
+    assert_eq!(
+        fs::read_to_string(repo_dir.join("README.md")).unwrap(),
+        "work in progress"
+    );
+    assert!(!repo_dir.join("VERSION").exists());
+    assert_eq!(git(&["worktree", "list"]).lines().count(), 1);
+
+    // Unknown refs fail without leaving anything behind
+    let command = RunCommand::new_command("true".to_string(), true, Some(output_dir.clone()))
+        .with_refs(vec!["no-such-branch".to_string()]);
+    assert!(command.execute(&context).await.is_err());
+    assert_eq!(git(&["worktree", "list"]).lines().count(), 1);
+}
+
+#[tokio::test]
+async fn test_run_in_persistent_worktree() {
+    let (temp_dir, repo, context) = setup_basic_test("test-repo");
+    let repo_dir = PathBuf::from(repo.path.clone().unwrap());
+    let worktrees_dir = temp_dir.path().join("worktrees");
+
+    let output_dir = Some(temp_dir.path().join("output"));
+
+    let command = RunCommand::new_command(
+        "echo change > CHANGED.txt".to_string(),
+        true,
+        output_dir.clone(),
+    )
+    .with_worktrees(worktrees_dir.clone());
+    command.execute(&context).await.unwrap();
+
+    let worktree = worktrees_dir.join("test-repo");
+    assert!(worktree.join("CHANGED.txt").exists());
+    assert!(!repo_dir.join("CHANGED.txt").exists());
+
+    // The worktree, and the change in it, is reused by later runs
+    let command = RunCommand::new_command("test -f CHANGED.txt".to_string(), true, output_dir)
+        .with_worktrees(worktrees_dir);
+    command.execute(&context).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_run_ordered_runs_dependencies_first() {
+    let (_core_dir, core, mut context) = setup_basic_test("core");
+    let (_api_dir, mut api, _) = setup_basic_test("api");
+    let (_web_dir, mut web, _) = setup_basic_test("web");
+    let (_app_dir, mut app, _) = setup_basic_test("app");
+    api.depends_on = vec!["core".to_string()];
+    web.depends_on = vec!["core".to_string()];
+    app.depends_on = vec!["api".to_string(), "web".to_string()];
+    context.config.repositories = vec![app, web, api, core];
+    context.parallel = true;
+
+    let log_dir = TempDir::new().unwrap();
+    let log = log_dir.path().join("order.log");
+    let command = RunCommand::new_command(
+        format!("basename \"$PWD\" >> '{}'", log.display()),
+        true,
+        Some(log_dir.path().join("output")),
+    )
+    .with_ordered(true);
+    command.execute(&context).await.unwrap();
+
+    let order = fs::read_to_string(&log).unwrap();
+    let position = |name: &str| order.lines().position(|line| line == name).unwrap();
+    assert_eq!(position("core"), 0);
+    assert!(position("api") < position("app"));
+    assert!(position("web") < position("app"));
+
+    // Repositories depending on a failed one are skipped
+    fs::remove_file(&log).unwrap();
+    let command = RunCommand::new_command(
+        format!(
+            "basename \"$PWD\" >> '{}' && test \"$(basename \"$PWD\")\" != api",
+            log.display()
+        ),
+        true,
+        Some(log_dir.path().join("output")),
+    )
+    .with_ordered(true);
+    let error = command.execute(&context).await.unwrap_err();
+    assert_eq!(error.to_string(), "2 of 4 repositories failed");
+    let order = fs::read_to_string(&log).unwrap();
+    assert!(order.lines().any(|line| line == "web"));
+    assert!(!order.lines().any(|line| line == "app"));
+
+    // Cycles are reported before anything runs
+    context.config.repositories[3].depends_on = vec!["app".to_string()];
+    let error = command.execute(&context).await.unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .starts_with("Dependency cycle between repositories:")
+    );
+}
+
+#[tokio::test]
+async fn test_run_command_writes_log_sinks() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_dir = temp_dir.path().join("output");
+    let fleet_log = temp_dir.path().join("fleet.log");
+
+    let (_repo_dir, _repo, context) = setup_basic_test("test-repo");
+
+    let sinks: Vec<repos::utils::output_sink::LogSinkConfig> =
+        serde_yaml::from_str(&format!("- type: file\n  path: {}\n", fleet_log.display())).unwrap();
+    let command = RunCommand::new_command(
+        "echo 'to the sink'".to_string(),
+        false,
+        Some(output_dir.clone()),
+    )
+    .with_log_sinks(sinks);
+    command.execute(&context).await.unwrap();
+
+    let log = fs::read_to_string(&fleet_log).unwrap();
+    assert!(log.starts_with("=== test-repo: exit code 0 (success) ===\n"));
+    assert!(log.contains("to the sink\n"));
+
+    // The run directory is written as before
+    let run_dir = fs::read_dir(output_dir.join("runs"))
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .path();
+    assert!(run_dir.join("test-repo").join("stdout.log").exists());
+}
@@ -119,6 +119,17 @@ pub fn create_test_repo(name: &str, temp_dir: &TempDir) -> Repository {
         path: Some(repo_dir.to_string_lossy().to_string()),
         branch: None,
         config_dir: None,
+        path_strategy: Default::default(),
+        default_branch: None,
+        sparse: Vec::new(),
+        provider: None,
+        provider_url: None,
+        provider_defaults: Default::default(),
+        git: Default::default(),
+        depends_on: Vec::new(),
+        post_clone: None,
+        archived: false,
+        remotes: Default::default(),
     }
 }
 
@@ -126,7 +137,10 @@ pub fn create_test_repo(name: &str, temp_dir: &TempDir) -> Repository {
 pub fn create_test_recipe(name: &str, steps: Vec<&str>) -> Recipe {
     Recipe {
         name: name.to_string(),
-        steps: steps.into_iter().map(|s| s.to_string()).collect(),
+        steps: steps.into_iter().map(Into::into).collect(),
+        artifacts: vec![],
+        cache: false,
+        env: Default::default(),
     }
 }
 
@@ -136,6 +150,23 @@ pub fn create_test_context(repositories: Vec<Repository>, recipes: Vec<Recipe>)
         config: Config {
             repositories,
             recipes,
+            artifacts_remote: None,
+            log_sinks: Vec::new(),
+            path_strategy: Default::default(),
+            root: None,
+            files: Vec::new(),
+            branch_protection: Vec::new(),
+            repository_settings: Vec::new(),
+            post_clone: Vec::new(),
+            pr: None,
+            provider: None,
+            provider_url: None,
+            git: Default::default(),
+            exit_codes: Default::default(),
+            notify: None,
+            profiles: Default::default(),
+            secrets: None,
+            profile_token: None,
         },
         tag: vec![],
         exclude_tag: vec![],